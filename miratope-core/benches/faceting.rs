@@ -0,0 +1,183 @@
+//! Benchmarks each stage of the faceting pipeline through its public API.
+//!
+//! The stages after the symmetry group are timed from the durations in the
+//! [`FacetingStats`] of each run, so that only the stage itself is measured.
+//! Set the `MIRATOPE_BENCH_STATS` environment variable to print the stats of
+//! every run to stderr, so that they can be compared across changes.
+//!
+//! The crate has no constructors for the 24-cell or the icosahedral polytopes,
+//! so these are read from the fixtures of the integration tests.
+
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use miratope_core::{
+    conc::{
+        faceting::{EdgeLengthFilter, FacetingOptions, FacetingStats, GroupEnum},
+        Concrete,
+    },
+    file::FromFile,
+};
+
+/// The environment variable that makes every run print its stats.
+const STATS_VAR: &str = "MIRATOPE_BENCH_STATS";
+
+/// Returns the 24-cell.
+fn icositetrachoron() -> Concrete {
+    Concrete::from_off(include_str!("../tests/fixtures/ico.off")).unwrap()
+}
+
+/// Returns the 600-cell.
+fn hexacosichoron() -> Concrete {
+    Concrete::from_off(include_str!("../tests/fixtures/ex.off")).unwrap()
+}
+
+/// Returns the icosahedron.
+fn icosahedron() -> Concrete {
+    Concrete::from_off(include_str!("../tests/fixtures/ike.off")).unwrap()
+}
+
+/// Prints the stats of a run if [`STATS_VAR`] is set.
+fn dump_stats(name: &str, stats: &FacetingStats) {
+    if std::env::var_os(STATS_VAR).is_some() {
+        eprintln!("{}: {:?}", name, stats);
+    }
+}
+
+/// Returns the shortest distance between two vertices of a polytope, which is
+/// the edge length of every polytope benchmarked here.
+fn edge_length(poly: &Concrete) -> f64 {
+    let vertices = &poly.vertices;
+    vertices[1..]
+        .iter()
+        .map(|v| (v - &vertices[0]).norm())
+        .fold(f64::INFINITY, f64::min)
+}
+
+/// Returns the options for the small runs, which only use the edges of the
+/// polytope, and for the medium runs, which use every edge length.
+fn sized_options(poly: &Concrete) -> [(&'static str, FacetingOptions); 2] {
+    let length = edge_length(poly);
+    let small = FacetingOptions {
+        edge_length: EdgeLengthFilter::between(length * (1.0 - 1e-6), length * (1.0 + 1e-6)),
+        ..Default::default()
+    };
+
+    [("small", small), ("medium", Default::default())]
+}
+
+/// Runs the stages up to the faceting tables some number of times, and
+/// returns the total time spent in one of them.
+fn time_tables<F: Fn(&FacetingStats) -> Duration>(
+    name: &str,
+    poly: &Concrete,
+    options: &FacetingOptions,
+    iters: u64,
+    stage: F,
+) -> Duration {
+    let mut total = Duration::default();
+
+    for _ in 0..iters {
+        let mut poly = poly.clone();
+        let vertices = poly.vertices.to_vec();
+        let (_, stats) = poly
+            .faceting_tables_with_stats(vertices, GroupEnum::Chiral(false), options)
+            .unwrap();
+
+        dump_stats(name, &stats);
+        total += stage(&stats);
+    }
+
+    total
+}
+
+/// Benchmarks a stage up to the faceting tables on a polytope, for each size.
+fn bench_tables_stage<F: Fn(&FacetingStats) -> Duration + Copy>(
+    c: &mut Criterion,
+    group_name: &str,
+    poly: &Concrete,
+    stage: F,
+) {
+    let mut group = c.benchmark_group(group_name);
+    group.sample_size(10);
+
+    for (size, options) in &sized_options(poly) {
+        group.bench_with_input(BenchmarkId::from_parameter(size), options, |b, options| {
+            b.iter_custom(|iters| time_tables(group_name, poly, options, iters, stage))
+        });
+    }
+
+    group.finish();
+}
+
+/// Benchmarks the computation of the symmetry group.
+fn symmetry_group(c: &mut Criterion) {
+    let mut group = c.benchmark_group("symmetry_group");
+    group.sample_size(10);
+
+    for (size, poly) in &[("small", icositetrachoron()), ("medium", hexacosichoron())] {
+        group.bench_with_input(BenchmarkId::from_parameter(size), poly, |b, poly| {
+            b.iter_batched(
+                || poly.clone(),
+                |mut poly| poly.get_symmetry_group(),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+/// Benchmarks the enumeration of the hyperplanes of the 24-cell.
+fn hyperplanes(c: &mut Criterion) {
+    bench_tables_stage(c, "hyperplanes", &icositetrachoron(), |stats| {
+        stats.hyperplane_time
+    });
+}
+
+/// Benchmarks the faceting of the planes through the vertices of the
+/// icosahedron.
+fn facets(c: &mut Criterion) {
+    bench_tables_stage(c, "facets", &icosahedron(), |stats| stats.facet_time);
+}
+
+/// Benchmarks the computation of the ridge orbits of the icosahedron.
+fn ridges(c: &mut Criterion) {
+    bench_tables_stage(c, "ridges", &icosahedron(), |stats| stats.ridge_time);
+}
+
+/// Benchmarks the combination of the facets of the icosahedron into
+/// facetings, without building them.
+fn combine(c: &mut Criterion) {
+    let poly = icosahedron();
+    let mut group = c.benchmark_group("combine");
+    group.sample_size(10);
+
+    for (size, mut options) in sized_options(&poly) {
+        options.save = false;
+
+        group.bench_with_input(BenchmarkId::from_parameter(size), &options, |b, options| {
+            b.iter_custom(|iters| {
+                let mut total = Duration::default();
+
+                for _ in 0..iters {
+                    let mut poly = poly.clone();
+                    let vertices = poly.vertices.to_vec();
+                    let (_, stats) = poly
+                        .try_faceting_with_stats(vertices, GroupEnum::Chiral(false), options)
+                        .unwrap();
+
+                    dump_stats("combine", &stats);
+                    total += stats.combine_time;
+                }
+
+                total
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, symmetry_group, hyperplanes, facets, ridges, combine);
+criterion_main!(benches);
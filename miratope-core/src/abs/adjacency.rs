@@ -0,0 +1,251 @@
+//! Declares the [`AdjacencyGraph`] type, which stores the facets of a polytope
+//! together with the ridges they share.
+
+use std::{collections::VecDeque, fmt::Write};
+
+use super::{Abstract, Ranked};
+
+use vec_like::*;
+
+/// The facet adjacency graph of a polytope, whose nodes are its facets, and
+/// where two facets are adjacent when they share a ridge. The nodes are
+/// indexed like the facets.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AdjacencyGraph {
+    /// The nodes adjacent to each node, sorted.
+    neighbors: Vec<Vec<usize>>,
+}
+
+impl AdjacencyGraph {
+    /// Builds a graph from the nodes adjacent to each node. Repeated nodes
+    /// and loops are removed.
+    pub fn new(mut neighbors: Vec<Vec<usize>>) -> Self {
+        for (node, list) in neighbors.iter_mut().enumerate() {
+            list.sort_unstable();
+            list.dedup();
+            list.retain(|&other| other != node);
+        }
+
+        Self { neighbors }
+    }
+
+    /// Returns the number of nodes.
+    pub fn len(&self) -> usize {
+        self.neighbors.len()
+    }
+
+    /// Returns whether the graph has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.neighbors.is_empty()
+    }
+
+    /// Returns the number of edges.
+    pub fn edge_count(&self) -> usize {
+        self.neighbors.iter().map(Vec::len).sum::<usize>() / 2
+    }
+
+    /// Returns the nodes adjacent to a node, sorted.
+    pub fn neighbors(&self, node: usize) -> &[usize] {
+        &self.neighbors[node]
+    }
+
+    /// Returns the number of nodes adjacent to a node.
+    pub fn degree(&self, node: usize) -> usize {
+        self.neighbors[node].len()
+    }
+
+    /// Returns the degree of every node.
+    pub fn degrees(&self) -> Vec<usize> {
+        self.neighbors.iter().map(Vec::len).collect()
+    }
+
+    /// Returns the degree of every node if they're all the same, which for
+    /// the facetings of a polytope is a quick check for isotopy.
+    pub fn regular_degree(&self) -> Option<usize> {
+        let (first, rest) = self.neighbors.split_first()?;
+        if rest.iter().all(|list| list.len() == first.len()) {
+            Some(first.len())
+        } else {
+            None
+        }
+    }
+
+    /// Returns the distance from a node to every other, or `None` for the
+    /// nodes that can't be reached from it. The search stops once the nodes
+    /// at distance `limit` are found, so that the further nodes are left as
+    /// `None` too.
+    pub fn distances_from(&self, node: usize, limit: usize) -> Vec<Option<usize>> {
+        let mut distances = vec![None; self.len()];
+        distances[node] = Some(0);
+        let mut queue = VecDeque::from(vec![node]);
+
+        while let Some(node) = queue.pop_front() {
+            let distance = distances[node].unwrap();
+            if distance == limit {
+                break;
+            }
+
+            for &other in &self.neighbors[node] {
+                if distances[other].is_none() {
+                    distances[other] = Some(distance + 1);
+                    queue.push_back(other);
+                }
+            }
+        }
+
+        distances
+    }
+
+    /// Returns the connected components of the graph, each with its nodes
+    /// sorted. A polytope whose facet adjacency graph has more than one is a
+    /// compound.
+    pub fn components(&self) -> Vec<Vec<usize>> {
+        let mut component_of = vec![None; self.len()];
+        let mut components = Vec::new();
+
+        for start in 0..self.len() {
+            if component_of[start].is_some() {
+                continue;
+            }
+
+            let idx = components.len();
+            component_of[start] = Some(idx);
+            let mut component = vec![start];
+            let mut queue = VecDeque::from(vec![start]);
+
+            while let Some(node) = queue.pop_front() {
+                for &other in &self.neighbors[node] {
+                    if component_of[other].is_none() {
+                        component_of[other] = Some(idx);
+                        component.push(other);
+                        queue.push_back(other);
+                    }
+                }
+            }
+
+            component.sort_unstable();
+            components.push(component);
+        }
+
+        components
+    }
+
+    /// Returns whether the graph is connected. The empty graph isn't.
+    pub fn is_connected(&self) -> bool {
+        !self.is_empty() && self.distances_from(0, usize::MAX).iter().all(Option::is_some)
+    }
+
+    /// Returns the greatest distance between two nodes, or `None` if the graph
+    /// is empty or disconnected.
+    pub fn diameter(&self) -> Option<usize> {
+        self.diameter_up_to(usize::MAX)
+    }
+
+    /// Returns the greatest distance between two nodes if it's at most some
+    /// limit, or `None` if it's greater, or if the graph is empty or
+    /// disconnected. Each search stops as soon as it passes the limit, so a
+    /// small limit makes this much quicker on large graphs.
+    pub fn diameter_up_to(&self, limit: usize) -> Option<usize> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let search_limit = limit.saturating_add(1);
+        let mut diameter = 0;
+        for node in 0..self.len() {
+            let distances = self.distances_from(node, search_limit);
+            for distance in distances {
+                diameter = diameter.max(distance?);
+            }
+            if diameter > limit {
+                return None;
+            }
+        }
+
+        Some(diameter)
+    }
+
+    /// Writes the graph in the DOT format read by Graphviz, under some name.
+    pub fn to_dot(&self, name: &str) -> String {
+        let mut dot = format!("graph \"{}\" {{\n", name.replace('"', "\\\""));
+        for node in 0..self.len() {
+            writeln!(dot, "    {};", node).unwrap();
+        }
+        for (node, list) in self.neighbors.iter().enumerate() {
+            for &other in list.iter().filter(|&&other| other > node) {
+                writeln!(dot, "    {} -- {};", node, other).unwrap();
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+impl Abstract {
+    /// Returns the facet adjacency graph of the polytope, where two facets
+    /// are adjacent when they share a ridge. Polytopes of rank less than 1
+    /// have no ridges, so none of their facets are adjacent.
+    pub fn facet_adjacency(&self) -> AdjacencyGraph {
+        let mut neighbors = vec![Vec::new(); self.facet_count()];
+
+        if self.rank() >= 2 {
+            for ridge in &self.ranks()[self.rank() - 2] {
+                for &facet in &ridge.sups {
+                    neighbors[facet].extend(ridge.sups.iter().copied());
+                }
+            }
+        }
+
+        AdjacencyGraph::new(neighbors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Polytope;
+
+    /// The facets of a cube form the graph of an octahedron.
+    #[test]
+    fn cube() {
+        let graph = Abstract::hypercube(4).facet_adjacency();
+        assert_eq!(graph.len(), 6);
+        assert_eq!(graph.edge_count(), 12);
+        assert_eq!(graph.regular_degree(), Some(4));
+        assert_eq!(graph.components().len(), 1);
+        assert_eq!(graph.diameter(), Some(2));
+        assert_eq!(graph.diameter_up_to(1), None);
+        assert_eq!(graph.diameter_up_to(2), Some(2));
+    }
+
+    /// The facets of a polygon form a cycle.
+    #[test]
+    fn polygon() {
+        let graph = Abstract::polygon(7).facet_adjacency();
+        assert_eq!(graph.regular_degree(), Some(2));
+        assert_eq!(graph.diameter(), Some(3));
+
+        let distances = graph.distances_from(0, 2);
+        assert_eq!(distances.iter().filter(|distance| distance.is_some()).count(), 5);
+    }
+
+    /// The components of a compound are found, and it has no diameter.
+    #[test]
+    fn compound() {
+        let compound = Abstract::compound(vec![Abstract::simplex(4), Abstract::simplex(4)].into_iter());
+        let graph = compound.facet_adjacency();
+        assert_eq!(graph.components(), vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7]]);
+        assert!(!graph.is_connected());
+        assert_eq!(graph.diameter(), None);
+    }
+
+    /// Every node and edge is written out once.
+    #[test]
+    fn dot() {
+        let dot = Abstract::polygon(3).facet_adjacency().to_dot("triangle");
+        assert_eq!(
+            dot,
+            "graph \"triangle\" {\n    0;\n    1;\n    2;\n    0 -- 1;\n    0 -- 2;\n    1 -- 2;\n}\n"
+        );
+    }
+}
@@ -0,0 +1,338 @@
+//! Computes the homology of the boundary complex of an abstract polytope,
+//! whose cells are its proper elements other than the minimal one.
+//!
+//! The boundary maps are written down from the incidences between elements of
+//! consecutive ranks, and their ranks are found by Gaussian elimination. The
+//! Betti numbers then follow from these and the number of elements of each
+//! rank.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Display,
+};
+
+use super::{Abstract, Ranked};
+
+use vec_like::*;
+
+/// The prime that the boundary maps are reduced modulo when computing with
+/// [`Coefficients::Rational`]. It's small enough for products of residues to
+/// fit in a `u64`.
+const PRIME: u64 = (1 << 31) - 1;
+
+/// The coefficients of the chains that the homology is computed with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Coefficients {
+    /// The integers modulo 2. These need no orientations, so they work for
+    /// every polytope.
+    Z2,
+
+    /// The rationals. The Betti numbers count the free part of each integral
+    /// homology group, so that torsion is ignored. The chains need
+    /// orientations, and the boundary maps are reduced modulo a large prime,
+    /// which only changes the result if the integral homology has torsion of
+    /// that order.
+    Rational,
+}
+
+/// The error returned when computing the homology with
+/// [`Coefficients::Rational`] of a polytope where the boundary of some
+/// element isn't orientable, so that the elements don't form a cell complex.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NonOrientable {
+    /// The rank of the element, given as for [`Ranked::el_count`].
+    pub rank: usize,
+
+    /// The index of the element.
+    pub idx: usize,
+}
+
+impl Display for NonOrientable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "the boundary of element {} of rank {} isn't orientable", self.idx, self.rank)
+    }
+}
+
+impl std::error::Error for NonOrientable {}
+
+/// Returns the rank of a matrix over the integers modulo 2, whose rows are
+/// packed into bits.
+fn gf2_rank(rows: Vec<Vec<u64>>) -> usize {
+    // Reduced rows, each with the index of its lowest bit, which none of the
+    // later rows have set.
+    let mut basis: Vec<(usize, Vec<u64>)> = Vec::new();
+
+    for mut row in rows {
+        for (pivot, reduced) in &basis {
+            if row[pivot / 64] >> (pivot % 64) & 1 == 1 {
+                for (word, &other) in row.iter_mut().zip(reduced) {
+                    *word ^= other;
+                }
+            }
+        }
+
+        if let Some(word) = row.iter().position(|&word| word != 0) {
+            let pivot = word * 64 + row[word].trailing_zeros() as usize;
+            basis.push((pivot, row));
+        }
+    }
+
+    basis.len()
+}
+
+/// Returns the inverse of a nonzero residue modulo [`PRIME`].
+fn inverse(mut base: u64) -> u64 {
+    let mut exp = PRIME - 2;
+    let mut inverse = 1;
+
+    while exp > 0 {
+        if exp & 1 == 1 {
+            inverse = inverse * base % PRIME;
+        }
+        base = base * base % PRIME;
+        exp >>= 1;
+    }
+
+    inverse
+}
+
+/// Returns the rank of a matrix over the integers modulo [`PRIME`].
+fn modular_rank(rows: Vec<Vec<u64>>) -> usize {
+    // Reduced rows, each with the index of its first nonzero entry, which is
+    // 1 and which all of the later rows have as 0.
+    let mut basis: Vec<(usize, Vec<u64>)> = Vec::new();
+
+    for mut row in rows {
+        for (pivot, reduced) in &basis {
+            let factor = row[*pivot];
+            if factor != 0 {
+                for (entry, &other) in row.iter_mut().zip(reduced) {
+                    *entry = (*entry + (PRIME - factor) * other) % PRIME;
+                }
+            }
+        }
+
+        if let Some(pivot) = row.iter().position(|&entry| entry != 0) {
+            let factor = inverse(row[pivot]);
+            for entry in &mut row {
+                *entry = *entry * factor % PRIME;
+            }
+            basis.push((pivot, row));
+        }
+    }
+
+    basis.len()
+}
+
+impl Abstract {
+    /// Orients the boundary of every element of a given rank, which must be
+    /// at least 2, given the orientations of the elements of the rank below.
+    /// Returns the sign of each subelement of each element, in the same order
+    /// as the subelements, or an error if the boundary of some element isn't
+    /// orientable.
+    fn orient(&self, rank: usize, below: &[Vec<i8>]) -> Result<Vec<Vec<i8>>, NonOrientable> {
+        let mut signs = Vec::new();
+
+        for (idx, el) in self.ranks()[rank].iter().enumerate() {
+            // The signs of the edges are arbitrary, as long as they're opposite.
+            if rank == 2 {
+                signs.push(el.subs.iter().enumerate().map(|(i, _)| if i == 0 { 1 } else { -1 }).collect());
+                continue;
+            }
+
+            let position: HashMap<_, _> = el.subs.iter().enumerate().map(|(i, &sub)| (sub, i)).collect();
+            let mut el_signs = vec![0; el.subs.len()];
+
+            // Every ridge of the element is in two of its facets, which must
+            // induce opposite orientations on it.
+            for start in 0..el_signs.len() {
+                if el_signs[start] != 0 {
+                    continue;
+                }
+
+                el_signs[start] = 1;
+                let mut queue = VecDeque::from(vec![start]);
+
+                while let Some(i) = queue.pop_front() {
+                    let facet = el.subs[i];
+                    for (&ridge, &ridge_sign) in self[(rank - 1, facet)].subs.iter().zip(&below[facet]) {
+                        for &other in &self[(rank - 2, ridge)].sups {
+                            let j = match position.get(&other) {
+                                Some(&j) if other != facet => j,
+                                _ => continue,
+                            };
+
+                            let k = self[(rank - 1, other)].subs.iter().position(|&sub| sub == ridge).unwrap();
+                            let other_sign = below[other][k];
+                            let sign = -el_signs[i] * ridge_sign * other_sign;
+                            if el_signs[j] == 0 {
+                                el_signs[j] = sign;
+                                queue.push_back(j);
+                            } else if el_signs[j] != sign {
+                                return Err(NonOrientable { rank, idx });
+                            }
+                        }
+                    }
+                }
+            }
+
+            signs.push(el_signs);
+        }
+
+        Ok(signs)
+    }
+
+    /// Returns the Betti numbers of the boundary complex of the polytope,
+    /// whose cells of each dimension are its elements of one rank more. The
+    /// `i`-th entry is the rank of its `i`-th homology group, so a polyhedron
+    /// that's a sphere gives `[1, 0, 1]`. Elements of rank less than 1 and
+    /// the maximal element aren't cells.
+    ///
+    /// With [`Coefficients::Rational`], an error is returned if the boundary
+    /// of some element isn't orientable, in which case the elements don't
+    /// form a cell complex. This happens for instance in polytopes with
+    /// hemicube facets. With [`Coefficients::Z2`], this never fails.
+    pub fn betti_numbers(&self, coefficients: Coefficients) -> Result<Vec<usize>, NonOrientable> {
+        let top = self.rank();
+        if top < 2 {
+            return Ok(Vec::new());
+        }
+
+        // The rank of the boundary map from the elements of each rank, which
+        // is zero for the vertices and the maximal element.
+        let mut boundary_ranks = vec![0; 2];
+        let mut signs = Vec::new();
+
+        for rank in 2..top {
+            let cols = self.el_count(rank - 1);
+
+            boundary_ranks.push(match coefficients {
+                Coefficients::Z2 => gf2_rank(
+                    self.ranks()[rank]
+                        .iter()
+                        .map(|el| {
+                            let mut row = vec![0; cols / 64 + 1];
+                            for &sub in &el.subs {
+                                row[sub / 64] |= 1 << (sub % 64);
+                            }
+                            row
+                        })
+                        .collect(),
+                ),
+
+                Coefficients::Rational => {
+                    signs = self.orient(rank, &signs)?;
+                    modular_rank(
+                        self.ranks()[rank]
+                            .iter()
+                            .zip(&signs)
+                            .map(|(el, el_signs)| {
+                                let mut row = vec![0; cols];
+                                for (&sub, &sign) in el.subs.iter().zip(el_signs) {
+                                    row[sub] = if sign > 0 { 1 } else { PRIME - 1 };
+                                }
+                                row
+                            })
+                            .collect(),
+                    )
+                }
+            });
+        }
+        boundary_ranks.push(0);
+
+        Ok((1..top)
+            .map(|rank| self.el_count(rank) - boundary_ranks[rank] - boundary_ranks[rank + 1])
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Polytope;
+
+    /// Returns the Betti numbers with both kinds of coefficients.
+    fn betti_numbers(poly: &Abstract) -> (Vec<usize>, Vec<usize>) {
+        (
+            poly.betti_numbers(Coefficients::Z2).unwrap(),
+            poly.betti_numbers(Coefficients::Rational).unwrap(),
+        )
+    }
+
+    /// The boundaries of polytopes are spheres.
+    #[test]
+    fn spheres() {
+        assert_eq!(betti_numbers(&Abstract::polygon(5)), (vec![1, 1], vec![1, 1]));
+        assert_eq!(betti_numbers(&Abstract::hypercube(4)), (vec![1, 0, 1], vec![1, 0, 1]));
+        assert_eq!(betti_numbers(&Abstract::simplex(5)), (vec![1, 0, 0, 1], vec![1, 0, 0, 1]));
+        assert_eq!(betti_numbers(&Abstract::orthoplex(5)), (vec![1, 0, 0, 1], vec![1, 0, 0, 1]));
+    }
+
+    /// Small polytopes have no cells to speak of, or just two points.
+    #[test]
+    fn small() {
+        assert_eq!(Abstract::point().betti_numbers(Coefficients::Z2), Ok(Vec::new()));
+        assert_eq!(Abstract::dyad().betti_numbers(Coefficients::Rational), Ok(vec![2]));
+    }
+
+    /// Every component of a compound adds to the count of components.
+    #[test]
+    fn compound() {
+        let compound = Abstract::compound(vec![Abstract::simplex(4), Abstract::hypercube(4)].into_iter());
+        assert_eq!(betti_numbers(&compound), (vec![2, 0, 2], vec![2, 0, 2]));
+    }
+
+    /// Returns the hemicube, the quotient of the cube by its central inversion.
+    fn hemicube() -> Abstract {
+        let mut cube = Abstract::cube();
+        cube.element_sort();
+        cube.central_quotient().unwrap()
+    }
+
+    /// The hemicube is a projective plane, whose homology has torsion.
+    #[test]
+    fn projective_plane() {
+        let hemicube = hemicube();
+        assert_eq!(betti_numbers(&hemicube), (vec![1, 1, 1], vec![1, 0, 0]));
+    }
+
+    /// The square tiling {4, 4}₍₃,₀₎ is a torus.
+    #[test]
+    fn torus() {
+        let vertex = |i: usize, j: usize| (i % 3) * 3 + j % 3;
+        let horizontal = |i: usize, j: usize| vertex(i, j);
+        let vertical = |i: usize, j: usize| 9 + vertex(i, j);
+
+        let mut edges = vec![Vec::new(); 18];
+        let mut faces = Vec::new();
+        for i in 0..3 {
+            for j in 0..3 {
+                edges[horizontal(i, j)] = vec![vertex(i, j), vertex(i, j + 1)];
+                edges[vertical(i, j)] = vec![vertex(i, j), vertex(i + 1, j)];
+                faces.push(vec![horizontal(i, j), horizontal(i + 1, j), vertical(i, j), vertical(i, j + 1)]);
+            }
+        }
+
+        let torus = Abstract::from_subelements(vec![
+            vec![vec![]],
+            vec![vec![0]; 9],
+            edges,
+            faces,
+            vec![(0..9).collect()],
+        ])
+        .unwrap();
+        assert_eq!(betti_numbers(&torus), (vec![1, 2, 1], vec![1, 2, 1]));
+    }
+
+    /// Polytopes with non-orientable facets have no rational homology, but
+    /// still have homology modulo 2.
+    #[test]
+    fn non_orientable_facets() {
+        let pyramid = hemicube().pyramid();
+        assert_eq!(
+            pyramid.betti_numbers(Coefficients::Rational),
+            Err(NonOrientable { rank: 4, idx: 0 })
+        );
+        assert!(pyramid.betti_numbers(Coefficients::Z2).is_ok());
+    }
+}
@@ -0,0 +1,93 @@
+//! Limits on the number of elements of the polytopes that get built.
+//!
+//! Repeated products and facetings of big polytopes can easily have more
+//! elements than fit in memory. Operations that take [`ElementLimits`] predict
+//! their element counts before building anything, and return a [`TooLarge`]
+//! error instead of running out of memory.
+
+use std::fmt::Display;
+
+/// The default maximum number of elements of any rank, about 67 million. Each
+/// element takes up at least a few dozen bytes, so a polytope with this many
+/// elements of some rank already takes up gigabytes.
+pub const DEFAULT_MAX_ELEMENTS: usize = 1 << 26;
+
+/// Limits on the element counts of the polytopes built by an operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ElementLimits {
+    /// The maximum number of elements of any rank.
+    pub max_per_rank: usize,
+}
+
+impl Default for ElementLimits {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_ELEMENTS)
+    }
+}
+
+impl ElementLimits {
+    /// No limits, other than the element counts fitting in a `usize`.
+    pub const UNLIMITED: Self = Self {
+        max_per_rank: usize::MAX,
+    };
+
+    /// Initializes new limits with a maximum number of elements of any rank.
+    pub fn new(max_per_rank: usize) -> Self {
+        Self { max_per_rank }
+    }
+
+    /// Returns an error if a predicted number of elements of some rank
+    /// exceeds the limits. Predictions are saturated at `usize::MAX`, which is
+    /// never allowed.
+    pub fn check_rank(&self, rank: usize, predicted: usize) -> Result<(), TooLarge> {
+        if predicted > self.max_per_rank || predicted == usize::MAX {
+            Err(TooLarge {
+                rank,
+                predicted,
+                limit: self.max_per_rank,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns an error for the lowest rank whose predicted number of elements
+    /// exceeds the limits.
+    pub fn check(&self, counts: &[usize]) -> Result<(), TooLarge> {
+        for (rank, &predicted) in counts.iter().enumerate() {
+            self.check_rank(rank, predicted)?;
+        }
+        Ok(())
+    }
+}
+
+/// The error returned when a polytope would have more elements of some rank
+/// than the [`ElementLimits`] allow.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TooLarge {
+    /// The rank with too many elements.
+    pub rank: usize,
+
+    /// The predicted number of elements of that rank, or `usize::MAX` if it
+    /// doesn't even fit in a `usize`.
+    pub predicted: usize,
+
+    /// The maximum number of elements of any rank allowed.
+    pub limit: usize,
+}
+
+impl Display for TooLarge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.predicted == usize::MAX {
+            write!(f, "the number of elements of rank {} overflows", self.rank)
+        } else {
+            write!(
+                f,
+                "the polytope would have {} elements of rank {}, which exceeds the limit of {}",
+                self.predicted, self.rank, self.limit
+            )
+        }
+    }
+}
+
+impl std::error::Error for TooLarge {}
@@ -0,0 +1,50 @@
+//! Macros for writing small polytopes by hand, mostly in tests.
+
+/// Builds an [`Abstract`](crate::abs::Abstract) polytope from the subelements
+/// of each of its elements, rank by rank, starting from the minimal element.
+/// For instance, a triangle is written as
+///
+/// ```
+/// # use miratope_core::{abs::Ranked, abstract_poly};
+/// let triangle = abstract_poly![
+///     [[]],
+///     [[0], [0], [0]],
+///     [[0, 1], [1, 2], [0, 2]],
+///     [[0, 1, 2]],
+/// ];
+/// assert_eq!(triangle.edge_count(), 3);
+/// ```
+///
+/// # Panics
+/// Panics if the polytope isn't valid, with the error returned by
+/// [`Abstract::from_subelements`](crate::abs::Abstract::from_subelements),
+/// which says which element is at fault.
+#[macro_export]
+macro_rules! abstract_poly {
+    ($([$([$($sub:expr),* $(,)?]),* $(,)?]),* $(,)?) => {
+        match $crate::abs::Abstract::from_subelements(
+            ::std::vec![$(::std::vec![$(::std::vec![$($sub),*]),*]),*]
+        ) {
+            ::std::result::Result::Ok(poly) => poly,
+            ::std::result::Result::Err(err) => ::std::panic!("invalid polytope: {}", err),
+        }
+    };
+}
+
+/// Builds [`Ranks`](crate::abs::Ranks) from the subelements of each of their
+/// elements, in the same format as [`abstract_poly!`]. Unlike it, this doesn't
+/// fill in the superelements or check anything, so it can write down the
+/// partial structures used while faceting, whose ranks may be empty.
+#[macro_export]
+macro_rules! ranks {
+    ($([$([$($sub:expr),* $(,)?]),* $(,)?]),* $(,)?) => {
+        $crate::abs::Ranks::from(::std::vec![$(
+            $crate::abs::ElementList::from(::std::vec![$(
+                $crate::abs::Element::new(
+                    $crate::abs::Subelements::from(::std::vec![$($sub),*]),
+                    $crate::abs::Superelements::from(::std::vec::Vec::new()),
+                )
+            ),*])
+        ),*])
+    };
+}
@@ -1,13 +1,21 @@
 //! Declares the [`Abstract`] polytope type and all associated data structures.
 
+pub mod adjacency;
 pub mod antiprism;
 pub mod flag;
+pub mod homology;
+mod macros;
+pub mod limits;
+pub mod partial;
 pub mod product;
+pub mod quotient;
+pub mod rank;
 pub mod ranked;
+pub mod untangle;
 pub mod valid;
 
 use std::{
-    collections::{BTreeSet, HashMap},
+    collections::{BTreeSet, HashMap, HashSet, VecDeque},
     convert::Infallible,
     ops::{Index, IndexMut},
     slice, vec, iter,
@@ -15,14 +23,32 @@ use std::{
 
 use self::flag::{Flag, FlagSet};
 use super::Polytope;
+use crate::conc::element_types::EL_NAMES;
 
 use vec_like::VecLike;
 
 use partitions::{PartitionVec, partition_vec};
 
+pub use rank::Rank;
 pub use ranked::*;
 pub use valid::*;
 
+/// The most ranks of proper elements whose numbers are written in the summary
+/// of a polytope, see [`Abstract::fmt_counts`].
+const SHOWN_RANKS: usize = 6;
+
+/// Writes a number of elements of some rank, such as `12 edges`.
+fn write_el_count(f: &mut std::fmt::Formatter<'_>, rank: usize, count: usize) -> std::fmt::Result {
+    let name = match EL_NAMES.get(rank) {
+        Some(_) if rank == 1 && count == 1 => "vertex".to_string(),
+        Some(name) if count == 1 => name.trim_end_matches('s').to_lowercase(),
+        Some(name) => name.to_lowercase(),
+        None if count == 1 => format!("{}-element", rank),
+        None => format!("{}-elements", rank),
+    };
+    write!(f, "{} {}", count, name)
+}
+
 /// Contains some metadata about how a polytope has been built up, which can
 /// then be used by methods on polytopes to avoid expensive recomputations.
 ///
@@ -35,11 +61,18 @@ pub struct Metadata {
     /// by index. This is a necessary condition for the methods that iterate
     /// over flags.
     sorted: bool,
+
+    /// Whether the polytope is orientable, if this has been found by
+    /// [`Polytope::orientable_mut`].
+    orientable: Option<bool>,
 }
 
 impl Default for Metadata {
     fn default() -> Self {
-        Self { sorted: false }
+        Self {
+            sorted: false,
+            orientable: None,
+        }
     }
 }
 
@@ -142,6 +175,7 @@ impl Index<usize> for Abstract {
 
 impl IndexMut<usize> for Abstract {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.meta.orientable = None;
         &mut self.ranks[index]
     }
 }
@@ -174,12 +208,56 @@ impl Abstract {
     /// abstract polytope. These are outlined in the documentation for the
     /// [`Abstract`] type.
     pub unsafe fn from_ranks(ranks: Ranks) -> Self {
+        ranks.debug_assert_consistent();
         Self {
             ranks,
             meta: Default::default(),
         }
     }
 
+    /// Builds a polytope from the subelements of each of its elements, rank by
+    /// rank, starting from the minimal element. The superelements are filled
+    /// in from these. See also the [`abstract_poly!`](crate::abstract_poly)
+    /// macro.
+    ///
+    /// Returns an error if an index is out of range, or if the polytope isn't
+    /// bounded, ranked or dyadic, as checked by [`Ranks::is_valid`]. The
+    /// errors point at the element at fault by its rank and index. The indices
+    /// of the polytope are sorted, so that its flags can be iterated over.
+    pub fn from_subelements(lists: Vec<Vec<Vec<usize>>>) -> AbstractResult<Self> {
+        if lists.is_empty() {
+            return Err(AbstractError::Bounded {
+                min_count: 0,
+                max_count: 0,
+            });
+        }
+
+        let mut builder = AbstractBuilder::new();
+        builder.reserve(lists.len());
+        let mut prev_count = 0;
+        for (r, list) in lists.iter().enumerate() {
+            builder.push_with_capacity(list.len());
+            for (idx, subs) in list.iter().enumerate() {
+                if let Some(&index) = subs.iter().find(|&&sub| sub >= prev_count) {
+                    return Err(AbstractError::Index {
+                        el: (r, idx),
+                        incidence_type: IncidenceType::Subelement,
+                        index,
+                    });
+                }
+                builder.push_subs(subs.clone().into());
+            }
+            prev_count = list.len();
+        }
+
+        builder.ranks().is_valid()?;
+
+        // Safety: we just checked that the polytope is valid.
+        let mut poly = unsafe { builder.build() };
+        poly.element_sort();
+        Ok(poly)
+    }
+
     /// Returns a mutable reference to the [`Ranks`] of the polytope. As a side
     /// effect, this will reset the polytope's metadata.
     ///
@@ -197,6 +275,60 @@ impl Abstract {
         self.meta.sorted
     }
 
+    /// Returns whether the polytope is orientable, if this is already known
+    /// from a call to [`Polytope::orientable_mut`]. The result is kept until
+    /// the polytope changes.
+    pub fn known_orientability(&self) -> Option<bool> {
+        self.meta.orientable
+    }
+
+    /// Returns the Euler characteristic of the polytope: the alternating sum
+    /// of the numbers of its proper elements, starting with the vertices.
+    /// It's 2 for polyhedra that are spheres, and 0 for polygons and
+    /// polychora.
+    pub fn euler_characteristic(&self) -> isize {
+        (1..self.rank().max(1))
+            .map(|r| if r % 2 == 1 { self.el_count(r) as isize } else { -(self.el_count(r) as isize) })
+            .sum()
+    }
+
+    /// Writes the numbers of proper elements of each rank, whether the
+    /// polytope is orientable if it's known, and its Euler characteristic,
+    /// as in `: 8 vertices, 12 edges, 6 faces; χ=2`. Unless the alternate
+    /// format is asked for, only the first and last few ranks are listed for
+    /// polytopes of high rank.
+    pub(crate) fn fmt_counts(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let proper = self.rank().saturating_sub(1);
+        if proper == 0 {
+            return Ok(());
+        }
+
+        let shown: Vec<_> = if proper <= SHOWN_RANKS || f.alternate() {
+            (1..=proper).map(Some).collect()
+        } else {
+            (1..=SHOWN_RANKS - 2)
+                .map(Some)
+                .chain(iter::once(None))
+                .chain((proper - 1..=proper).map(Some))
+                .collect()
+        };
+
+        for (i, rank) in shown.into_iter().enumerate() {
+            write!(f, "{}", if i == 0 { ": " } else { ", " })?;
+            match rank {
+                Some(rank) => write_el_count(f, rank, self.el_count(rank))?,
+                None => write!(f, "…")?,
+            }
+        }
+
+        match self.known_orientability() {
+            Some(true) => write!(f, "; orientable")?,
+            Some(false) => write!(f, "; non-orientable")?,
+            None => {}
+        }
+        write!(f, "; χ={}", self.euler_characteristic())
+    }
+
     /// Sets the metadata of the polytope that stores whether the indices of the
     /// polytope's subelements and superelements are sorted to a given value.
     ///
@@ -207,6 +339,25 @@ impl Abstract {
         self.meta.sorted = sorted;
     }
 
+    /// Rebuilds the superelements of every element from the subelements, in
+    /// parallel over the ranks. See [`Ranks::regenerate_superelements`].
+    pub fn regenerate_superelements(&mut self) {
+        let sorted = self.sorted();
+
+        // Safety: the subelements are left untouched, and the superelements
+        // are made to match them.
+        unsafe {
+            self.ranks_mut().regenerate_superelements();
+            self.set_sorted(sorted);
+        }
+    }
+
+    /// Returns every mismatch between the subelements and superelements of the
+    /// polytope. See [`Ranks::check_sub_sup_consistency`].
+    pub fn check_sub_sup_consistency(&self) -> Result<(), InconsistencyReport> {
+        self.ranks.check_sub_sup_consistency()
+    }
+
     /// Returns an iterator over the [`ElementLists`](ElementList) of each rank.
     pub fn iter(&self) -> slice::Iter<'_, ElementList> {
         self.ranks.iter()
@@ -239,6 +390,21 @@ impl Abstract {
         self
     }
 
+    /// Returns the correspondence between the elements of this polytope and
+    /// those of its dual, as built by [`Self::dual_mut`]. Element `idx` of rank
+    /// `r` becomes element `idx` of rank `n - r`.
+    pub fn dual_correspondence(&self) -> DualCorrespondence {
+        (0..=self.rank())
+            .map(|r| (0..self.el_count(r)).collect())
+            .collect()
+    }
+
+    /// Takes the dual of an abstract polytope, and also returns the
+    /// correspondence between the elements of both.
+    pub fn dual_with_correspondence(&self) -> (Self, DualCorrespondence) {
+        (self.dual(), self.dual_correspondence())
+    }
+
     /// Builds an [antiprism](https://polytope.miraheze.org/wiki/Antiprism)
     /// based on a given polytope. Also returns the indices of the vertices that
     /// form the base and the dual base, in that order.
@@ -254,13 +420,13 @@ impl Abstract {
 
     /// Gets the indices of the vertices of an element in the polytope, if it
     /// exists.
-    pub fn element_vertices(&self, rank: usize, idx: usize) -> Option<Vec<usize>> {
+    pub fn element_vertices(&self, rank: impl Into<Rank>, idx: usize) -> Option<Vec<usize>> {
         Some(ElementHash::new(self, rank, idx)?.to_vertices())
     }
 
     /// Gets both elements with a given rank and index as a polytope and the
     /// indices of its vertices on the original polytope, if it exists.
-    pub fn element_and_vertices(&self, rank: usize, idx: usize) -> Option<(Vec<usize>, Self)> {
+    pub fn element_and_vertices(&self, rank: impl Into<Rank>, idx: usize) -> Option<(Vec<usize>, Self)> {
         let element_hash = ElementHash::new(self, rank, idx)?;
         Some((element_hash.to_vertices(), element_hash.to_polytope(self)))
     }
@@ -461,14 +627,95 @@ impl Abstract {
         (unsafe { builder.build() }, verts_subflags)
     }
     
-    /// Returns whether a polytope is compound
-    ///
-    /// # Panics
-    /// You must call [`Polytope::element_sort`] before calling this method.
+    /// Returns whether a polytope is compound, that is, whether its flags
+    /// split into more than one component. If the polytope isn't sorted, a
+    /// sorted copy of it is checked instead.
     pub fn is_compound(&self) -> bool {
+        if !self.sorted() {
+            let mut abs = self.clone();
+            abs.element_sort();
+            return abs.is_compound();
+        }
+
         let flag_set = FlagSet::new_all(self);
         flag_set.len() != self.flags().count()
     }
+
+    /// Returns whether a polytope is fissary, that is, whether some section
+    /// of rank at least 2 that isn't an element, like a vertex figure or the
+    /// vertex figure of a face, is a compound. Unlike
+    /// [`ConcretePolytope::is_fissary`](crate::conc::ConcretePolytope::is_fissary),
+    /// the compound elements aren't skipped.
+    ///
+    /// A section splits into a compound exactly when some section within it
+    /// has a disconnected proper part, so we check these using the
+    /// superelements and subelements directly, without building any sections.
+    pub fn is_fissary(&self) -> bool {
+        (1..self.rank().saturating_sub(2))
+            .any(|lo| (0..self.el_count(lo)).any(|idx| self.has_disconnected_section_over(lo, idx)))
+    }
+
+    /// Returns whether some section of rank at least 2 whose lowest element is
+    /// the given one has a disconnected proper part.
+    fn has_disconnected_section_over(&self, lo: usize, idx: usize) -> bool {
+        // The elements above the given one.
+        let mut above = HashSet::new();
+        let mut queue = VecDeque::from(vec![(lo, idx)]);
+        while let Some((r, i)) = queue.pop_front() {
+            for &sup in &self[(r, i)].sups {
+                if above.insert((r + 1, sup)) {
+                    queue.push_back((r + 1, sup));
+                }
+            }
+        }
+
+        above.iter().filter(|&&(hi, _)| hi >= lo + 3).any(|&(hi, hi_idx)| {
+            // The elements strictly between the two.
+            let mut between = HashSet::new();
+            let mut queue = VecDeque::from(vec![(hi, hi_idx)]);
+            while let Some((r, i)) = queue.pop_front() {
+                for &sub in &self[(r, i)].subs {
+                    let el = (r - 1, sub);
+                    if above.contains(&el) && between.insert(el) {
+                        queue.push_back(el);
+                    }
+                }
+            }
+
+            // Whether these are connected through their incidences.
+            let start = match between.iter().next() {
+                Some(&start) => start,
+                None => return false,
+            };
+            let mut reached = HashSet::new();
+            reached.insert(start);
+            let mut queue = VecDeque::from(vec![start]);
+            while let Some((r, i)) = queue.pop_front() {
+                let el = &self[(r, i)];
+                let subs = el.subs.iter().map(|&sub| (r - 1, sub));
+                let sups = el.sups.iter().map(|&sup| (r + 1, sup));
+                for other in subs.chain(sups) {
+                    if between.contains(&other) && reached.insert(other) {
+                        queue.push_back(other);
+                    }
+                }
+            }
+
+            reached.len() != between.len()
+        })
+    }
+}
+
+/// Writes a one-line summary of the polytope, such as `rank-4 polytope: 8
+/// vertices, 12 edges, 6 faces; χ=2`. Nothing expensive is computed, so the
+/// orientability is only written if it's known, see
+/// [`Abstract::known_orientability`]. The alternate format lists the elements
+/// of every rank.
+impl std::fmt::Display for Abstract {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "rank-{} polytope", self.rank())?;
+        self.fmt_counts(f)
+    }
 }
 
 impl Polytope for Abstract {
@@ -486,6 +733,20 @@ impl Polytope for Abstract {
         self
     }
 
+    /// Determines whether a given polytope is
+    /// [orientable](https://polytope.miraheze.org/wiki/Orientability), and
+    /// keeps the result, see [`Self::known_orientability`].
+    fn orientable_mut(&mut self) -> bool {
+        if let Some(orientable) = self.meta.orientable {
+            return orientable;
+        }
+
+        self.element_sort();
+        let orientable = self.orientable();
+        self.meta.orientable = Some(orientable);
+        orientable
+    }
+
     /// Returns an instance of the
     /// [nullitope](https://polytope.miraheze.org/wiki/Nullitope), the unique
     /// polytope of rank &minus;1.
@@ -555,11 +816,10 @@ impl Polytope for Abstract {
 
     /// Builds an [orthoplex](https://polytope.miraheze.org/wiki/Orthoplex) with
     /// a given rank.
-    fn orthoplex(rank: usize) -> Self {
-        if rank == 0 {
-            Self::nullitope()
-        } else {
-            Self::multitegum(iter::repeat(&Self::dyad()).take(rank - 1))
+    fn orthoplex(rank: impl Into<Rank>) -> Self {
+        match rank.into().facet() {
+            None => Self::nullitope(),
+            Some(facet) => Self::multitegum(iter::repeat(&Self::dyad()).take(facet.get())),
         }
     }
 
@@ -662,17 +922,9 @@ impl Polytope for Abstract {
         ranks.pop();
 
         // Pushes the new faces and a new maximal element.
-        for el in &mut ranks[2] {
-            el.sups.clear();
-        }
-
         let face_count = faces.len();
         let mut new_faces = ElementList::with_capacity(face_count);
-        for (idx, face) in faces.into_iter().enumerate() {
-            for &sub in &face {
-                ranks[(2, sub)].sups.push(idx);
-            }
-
+        for face in faces {
             new_faces.push(Element {
                 sups: vec![0].into(),
                 subs: face,
@@ -681,6 +933,8 @@ impl Polytope for Abstract {
 
         ranks.push(new_faces);
         ranks.push(ElementList::max(face_count));
+        ranks.regenerate_superelements();
+        ranks.debug_assert_consistent();
 
         // Checks for dyadicity, since that sometimes fails.
         ranks.ranks().is_dyadic().is_ok()
@@ -837,16 +1091,16 @@ impl Polytope for Abstract {
     }
     
     /// Gets the element with a given rank and index as a polytope, if it exists.
-    fn element(&self, rank: usize, idx: usize) -> Option<Self> {
+    fn element(&self, rank: impl Into<Rank>, idx: usize) -> Option<Self> {
         Some(ElementHash::new(self, rank, idx)?.to_polytope(self))
     }
 
     /// Gets the element figure with a given rank and index as a polytope.
-    fn element_fig(&self, rank: usize, idx: usize) -> Result<Option<Self>, Self::DualError> {
-        if rank <= self.rank() {
+    fn element_fig(&self, rank: impl Into<Rank>, idx: usize) -> Result<Option<Self>, Self::DualError> {
+        if let Some(co_rank) = Rank::from(self.rank()).checked_diff(rank.into()) {
             // todo: this is quite inefficient for a small element figure since
             // we take the dual of the entire thing.
-            if let Some(mut element_fig) = self.try_dual()?.element(self.rank() - rank, idx) {
+            if let Some(mut element_fig) = self.try_dual()?.element(co_rank, idx) {
                 element_fig.try_dual_mut()?;
                 return Ok(Some(element_fig));
             }
@@ -906,6 +1160,7 @@ impl Polytope for Abstract {
             top_vertex.subs = (0..bottom_facet_count).collect();
         }
 
+        product.ranks.debug_assert_consistent();
         product
     }
 
@@ -914,6 +1169,7 @@ impl Polytope for Abstract {
     fn ditope_mut(&mut self) {
         if self.rank() != 0 {
             let rank = self.rank();
+            self.meta.orientable = None;
             let ranks = &mut self.ranks;
 
             for v in &mut ranks[rank - 1] {
@@ -925,6 +1181,7 @@ impl Polytope for Abstract {
             ranks[rank].push(max);
 
             ranks.push(ElementList::max(2));
+            ranks.debug_assert_consistent();
         }
     }
 
@@ -932,6 +1189,7 @@ impl Polytope for Abstract {
     /// given polytope in place. Does nothing in case of the nullitope.
     fn hosotope_mut(&mut self) {
         if self.rank() != 0 {
+            self.meta.orientable = None;
             let ranks = &mut self.ranks;
 
             for v in &mut ranks[1] {
@@ -943,76 +1201,20 @@ impl Polytope for Abstract {
             ranks[0].push(min);
 
             ranks.insert(0, ElementList::min(2));
+            ranks.debug_assert_consistent();
         }
     }
 
     /// Splits compound faces into their components.
     fn untangle_faces(&mut self) {
-        if self.rank() < 4 {
-            return
-        }
-        let mut new_faces = ElementList::new();
-        let self_3_len = self[3].len();
-
-        for f_i in 0..self_3_len {
-            let current_len = new_faces.len();
-            let mut map = HashMap::new();
-            let mut partition = PartitionVec::new();
-            let edge_idxs = &self[3][f_i].subs.clone();
-            
-            for edge_idx in edge_idxs {
-                let edge = &self[2][*edge_idx];
-
-                if edge.subs.len() != 2 { // This shouldn't happen, but apparently it does sometimes when doing cross-sections
-                    return
-                }
-                for i in 0..=1 {
-                    if map.get(&edge.subs[i]).is_none() {
-                        map.insert(edge.subs[i], map.len());
-                        partition.push(edge.subs[i]);
-                    }
-                }
-                partition.union(
-                    *map.get(&edge.subs[0]).unwrap(),
-                    *map.get(&edge.subs[1]).unwrap()
-                );
-            }
-
-            let mut set_of_vertex = HashMap::new();
-            for (i, set) in partition.all_sets().enumerate() {
-                for (_, v) in set {
-                    set_of_vertex.insert(v, i);
-                }
-                if i > 0 {
-                    for sup in &self[3][f_i].sups.clone() {
-                        self[4][*sup].subs.push(new_faces.len() + self_3_len);
-                    }
-                    new_faces.push(Element::new(Subelements::new(), self[3][f_i].sups.clone()));
-                }
-            }
-
-            let mut new_face = self[3][f_i].clone();
-            new_face.subs.clear();
-
-            for edge_idx in edge_idxs {
-                let set_idx = set_of_vertex.get(&self[2][*edge_idx].subs[0]).unwrap();
-                if set_idx > &0 {
-                    let idx = current_len + set_idx - 1;
-                    new_faces[idx].subs.push(*edge_idx);
-                    for sup_i in 0..self[2][*edge_idx].sups.len() {
-                        if &self[2][*edge_idx].sups[sup_i] == &f_i {
-                            self[2][*edge_idx].sups[sup_i] = idx + self_3_len;
-                        }
-                    }
-                }
-                else {
-                    new_face.subs.push(*edge_idx);
-                }
-            }
+        self.untangle_elements(3);
+    }
 
-            self[3][f_i] = new_face;
-        }
-        self[3].append(&mut new_faces);
+    /// Splits the elements of a given rank into their components. Since
+    /// there's no geometry to tell them apart, all subelements through a
+    /// ridge are joined.
+    fn untangle_elements(&mut self, rank: impl Into<Rank>) {
+        self.untangle_elements_with(rank, |_, _, _, _| None);
     }
 }
 
@@ -1140,4 +1342,298 @@ mod tests {
         test(&Abstract::polygon(6).into_dual(), [1, 6, 6, 1]);
         test(&Abstract::cube().into_dual(), [1, 6, 12, 8, 1]);
     }
+
+    #[test]
+    /// Checks that tags go back to where they started after taking the dual
+    /// twice, and that they end up on elements with the right incidences.
+    fn dual_tags() {
+        let cube = Abstract::cube();
+        let tags = ElementTags::from_fn(&cube, |r, idx| (r, idx));
+
+        let (dual, correspondence) = cube.dual_with_correspondence();
+        let dual_tags = tags.clone().dualize(&correspondence);
+        for r in 0..=dual.rank() {
+            for idx in 0..dual.el_count(r) {
+                let (old_r, old_idx) = dual_tags[(r, idx)];
+                assert_eq!(old_r, cube.rank() - r);
+                assert_eq!(
+                    dual[(r, idx)].sups.len(),
+                    cube[(old_r, old_idx)].subs.len()
+                );
+            }
+        }
+
+        let (_, correspondence) = dual.dual_with_correspondence();
+        let tags_again = dual_tags.dualize(&correspondence);
+        for r in 0..=cube.rank() {
+            assert_eq!(tags_again[r], tags[r]);
+        }
+    }
+
+    /// Checks that regenerating the superelements of a hypercube gives back
+    /// the superelements it started with.
+    #[test]
+    fn regenerate_superelements() {
+        let mut tesseract = Abstract::cube().prism();
+        tesseract.element_sort();
+        let expected = tesseract.clone();
+
+        unsafe {
+            tesseract.ranks_mut().for_each_element_mut(|el| el.sups.clear());
+        }
+        assert!(tesseract.check_sub_sup_consistency().is_err());
+
+        tesseract.regenerate_superelements();
+        assert_eq!(tesseract.check_sub_sup_consistency(), Ok(()));
+        for r in 0..=tesseract.rank() {
+            for idx in 0..tesseract.el_count(r) {
+                assert_eq!(tesseract[(r, idx)].sups, expected[(r, idx)].sups);
+            }
+        }
+    }
+
+    /// Checks that the consistency check reports every mismatch.
+    #[test]
+    fn check_sub_sup_consistency() {
+        let mut cube = Abstract::cube();
+        assert_eq!(cube.check_sub_sup_consistency(), Ok(()));
+
+        let vertex = cube[(2, 0)].subs[0];
+        let bad_sup = cube.el_count(3);
+        unsafe {
+            let ranks = cube.ranks_mut();
+            ranks[(2, 0)].subs.swap_remove(0);
+            ranks[(2, 1)].sups.push(bad_sup);
+        }
+
+        let report = cube.check_sub_sup_consistency().unwrap_err();
+        assert!(report.0.contains(&Inconsistency {
+            el: (1, vertex),
+            incidence_type: IncidenceType::Superelement,
+            index: 0,
+            exists: true,
+        }));
+        assert!(report.0.contains(&Inconsistency {
+            el: (2, 1),
+            incidence_type: IncidenceType::Superelement,
+            index: bad_sup,
+            exists: false,
+        }));
+        assert_eq!(report.0.len(), 2);
+    }
+
+    /// Checks a triangle written with the macro.
+    #[test]
+    fn macro_triangle() {
+        let triangle = crate::abstract_poly![
+            [[]],
+            [[0], [0], [0]],
+            [[0, 1], [1, 2], [0, 2]],
+            [[0, 1, 2]],
+        ];
+        test(&triangle, [1, 3, 3, 1]);
+    }
+
+    /// Checks a tetrahedron written with the macro.
+    #[test]
+    fn macro_tetrahedron() {
+        let tetrahedron = crate::abstract_poly![
+            [[]],
+            [[0], [0], [0], [0]],
+            [[0, 1], [0, 2], [0, 3], [1, 2], [1, 3], [2, 3]],
+            [[0, 1, 3], [0, 2, 4], [1, 2, 5], [3, 4, 5]],
+            [[0, 1, 2, 3]],
+        ];
+        test(&tetrahedron, [1, 4, 6, 4, 1]);
+    }
+
+    /// Checks that the superelements are filled in from the subelements.
+    #[test]
+    fn macro_superelements() {
+        let square = crate::abstract_poly![
+            [[]],
+            [[0], [0], [0], [0]],
+            [[0, 1], [1, 2], [2, 3], [0, 3]],
+            [[0, 1, 2, 3]],
+        ];
+        assert_eq!(square.check_sub_sup_consistency(), Ok(()));
+        assert_eq!(square[(1, 0)].sups, vec![0, 3].into());
+        assert_eq!(square[(0, 0)].sups, vec![0, 1, 2, 3].into());
+    }
+
+    /// Two triangles sharing no vertices form a compound.
+    #[test]
+    fn macro_compound() {
+        let compound = crate::abstract_poly![
+            [[]],
+            [[0], [0], [0], [0], [0], [0]],
+            [[0, 1], [1, 2], [0, 2], [3, 4], [4, 5], [3, 5]],
+            [[0, 1, 2, 3, 4, 5]],
+        ];
+        assert!(compound.is_compound());
+        assert!(!Abstract::polygon(4).is_compound());
+    }
+
+    /// Compounds aren't fissary just for that.
+    #[test]
+    fn not_fissary() {
+        let compound = Abstract::compound(vec![Abstract::simplex(4), Abstract::simplex(4)].into_iter());
+        assert!(compound.is_compound());
+        assert!(!compound.is_fissary());
+
+        assert!(!Abstract::hypercube(5).is_fissary());
+    }
+
+    /// A prism's lateral squares, with both bases coned off to the same
+    /// vertex, whose vertex figure is then two triangles.
+    #[test]
+    fn fissary() {
+        let pinched = crate::abstract_poly![
+            [[]],
+            [[0], [0], [0], [0], [0], [0], [0]],
+            [
+                [0, 1], [1, 2], [2, 0], [3, 4], [4, 5], [5, 3], [0, 3], [1, 4],
+                [2, 5], [6, 0], [6, 1], [6, 2], [6, 3], [6, 4], [6, 5],
+            ],
+            [
+                [0, 3, 6, 7], [1, 4, 7, 8], [2, 5, 6, 8], [0, 9, 10], [1, 10, 11],
+                [2, 9, 11], [3, 12, 13], [4, 13, 14], [5, 12, 14],
+            ],
+            [[0, 1, 2, 3, 4, 5, 6, 7, 8]],
+        ];
+        assert!(pinched.ranks().is_valid().is_ok());
+        assert!(!pinched.is_compound());
+        assert!(pinched.is_fissary());
+        assert!(pinched.pyramid().is_fissary());
+    }
+
+    /// An index out of range is reported along with the element it's in.
+    #[test]
+    fn from_subelements_index() {
+        let result = Abstract::from_subelements(vec![
+            vec![vec![]],
+            vec![vec![0], vec![0]],
+            vec![vec![0, 2]],
+        ]);
+        assert!(matches!(
+            result,
+            Err(AbstractError::Index {
+                el: (2, 0),
+                incidence_type: IncidenceType::Subelement,
+                index: 2,
+            })
+        ));
+
+        // The minimal element can't have subelements.
+        let result = Abstract::from_subelements(vec![vec![vec![0]]]);
+        assert!(matches!(result, Err(AbstractError::Index { el: (0, 0), .. })));
+    }
+
+    /// A polytope with two maximal elements, or none at all, isn't bounded.
+    #[test]
+    fn from_subelements_bounded() {
+        let result = Abstract::from_subelements(vec![
+            vec![vec![]],
+            vec![vec![0], vec![0]],
+            vec![vec![0, 1], vec![0, 1]],
+        ]);
+        assert!(matches!(
+            result,
+            Err(AbstractError::Bounded {
+                min_count: 1,
+                max_count: 2,
+            })
+        ));
+
+        assert!(matches!(
+            Abstract::from_subelements(Vec::new()),
+            Err(AbstractError::Bounded { .. })
+        ));
+    }
+
+    /// A face whose edges form a path instead of a cycle breaks the diamond
+    /// property.
+    #[test]
+    fn from_subelements_dyadic() {
+        let result = Abstract::from_subelements(vec![
+            vec![vec![]],
+            vec![vec![0], vec![0], vec![0]],
+            vec![vec![0, 1], vec![1, 2], vec![0, 2]],
+            vec![vec![0, 1], vec![0, 1, 2]],
+            vec![vec![0, 1]],
+        ]);
+        assert!(matches!(result, Err(AbstractError::Dyadic { more: false, .. })));
+    }
+
+    /// An edge without vertices isn't ranked.
+    #[test]
+    fn from_subelements_ranked() {
+        let result = Abstract::from_subelements(vec![
+            vec![vec![]],
+            vec![vec![0], vec![0]],
+            vec![vec![0, 1], vec![]],
+            vec![vec![0, 1]],
+        ]);
+        assert!(matches!(
+            result,
+            Err(AbstractError::Ranked {
+                el: (2, 1),
+                incidence_type: IncidenceType::Subelement,
+            })
+        ));
+    }
+
+    /// The macro panics on invalid polytopes, saying where the error is.
+    #[test]
+    #[should_panic(expected = "invalid polytope: Polytope has an invalid index: (1, 1)")]
+    fn macro_invalid() {
+        crate::abstract_poly![[[]], [[0], [1]], [[0, 1]]];
+    }
+
+    /// The raw ranks are written down as they are, without superelements.
+    #[test]
+    fn raw_ranks() {
+        let ranks = crate::ranks![[], [[0]], [[1], [0, 1]]];
+        assert_eq!(ranks.len(), 3);
+        assert_eq!(ranks[0].len(), 0);
+        assert_eq!(ranks[(2, 1)].subs, vec![0, 1].into());
+        assert!(ranks[(1, 0)].sups.is_empty());
+    }
+
+    /// The summary of a polytope only mentions its orientability once it's
+    /// known, and forgets it when the polytope changes.
+    #[test]
+    fn display() {
+        let mut cube = Abstract::cube();
+        assert_eq!(cube.to_string(), "rank-4 polytope: 8 vertices, 12 edges, 6 faces; χ=2");
+        assert!(cube.orientable_mut());
+        assert_eq!(cube.known_orientability(), Some(true));
+        assert_eq!(cube.to_string(), "rank-4 polytope: 8 vertices, 12 edges, 6 faces; orientable; χ=2");
+
+        cube.ditope_mut();
+        assert_eq!(cube.known_orientability(), None);
+
+        let mut hemicube = Abstract::cube();
+        hemicube.element_sort();
+        let mut hemicube = hemicube.central_quotient().unwrap();
+        assert!(!hemicube.orientable_mut());
+        assert_eq!(hemicube.to_string(), "rank-4 polytope: 4 vertices, 6 edges, 3 faces; non-orientable; χ=1");
+        assert_eq!(Abstract::nullitope().to_string(), "rank-0 polytope");
+    }
+
+    /// Only the first and last few ranks of a polytope of high rank are
+    /// listed, unless the alternate format is asked for.
+    #[test]
+    fn display_high_rank() {
+        let simplex = Abstract::simplex(9);
+        assert_eq!(
+            simplex.to_string(),
+            "rank-9 polytope: 9 vertices, 36 edges, 84 faces, 126 cells, …, 36 exa, 9 zetta; χ=0"
+        );
+        assert_eq!(
+            format!("{:#}", simplex),
+            "rank-9 polytope: 9 vertices, 36 edges, 84 faces, 126 cells, 126 tera, 84 peta, 36 exa, 9 zetta; χ=0"
+        );
+        assert_eq!(Abstract::simplex(6).to_string(), "rank-6 polytope: 6 vertices, 15 edges, 20 faces, 15 cells, 6 tera; χ=2");
+    }
 }
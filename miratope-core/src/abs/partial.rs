@@ -0,0 +1,384 @@
+//! Declares the [`PartialAbstract`] type, which stores structures that are
+//! meant to become abstract polytopes but may not be yet, such as the
+//! intermediate states of a blend or an incomplete faceting.
+
+use std::collections::HashMap;
+
+use super::{Abstract, IncidenceType, Ranked, Ranks, Section, Subelements};
+
+use vec_like::*;
+
+/// A way in which a [`PartialAbstract`] fails to be an abstract polytope.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Violation {
+    /// The structure doesn't have a single minimal and maximal element.
+    Bounded {
+        /// The number of minimal elements.
+        min_count: usize,
+
+        /// The number of maximal elements.
+        max_count: usize,
+    },
+
+    /// Some element that's not minimal or not maximal lacks a subelement or
+    /// superelement, respectively.
+    Ranked {
+        /// The coordinates of the element at fault.
+        el: (usize, usize),
+
+        /// Whether the missing incidences are at subelements or superelements.
+        incidence_type: IncidenceType,
+    },
+
+    /// Some section of height 1 doesn't have exactly 2 elements in between. A
+    /// ridge in 4 facets has 4, and a ridge on an unclosed boundary has 1.
+    Dyadic {
+        /// The section at fault.
+        section: Section,
+
+        /// The number of elements in the section.
+        count: usize,
+    },
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Violation::Bounded {
+                min_count,
+                max_count,
+            } => write!(
+                f,
+                "found {} minimal elements and {} maximal elements",
+                min_count, max_count
+            ),
+
+            Violation::Ranked { el, incidence_type } => {
+                write!(f, "{:?} has no {}s", el, incidence_type)
+            }
+
+            Violation::Dyadic { section, count } => {
+                write!(f, "there are {} elements in the {}", count, section)
+            }
+        }
+    }
+}
+
+/// Every way in which a [`PartialAbstract`] fails to be an abstract polytope.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Violations(pub Vec<Violation>);
+
+impl std::fmt::Display for Violations {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Structure has {} violations:", self.0.len())?;
+
+        for violation in &self.0 {
+            write!(f, "\n  {}", violation)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for Violations {}
+
+impl Violations {
+    /// Finds every violation in some ranks, whose superelements match their
+    /// subelements.
+    fn of(ranks: &Ranks) -> Self {
+        let mut violations = Vec::new();
+        let rank = ranks.rank();
+
+        let min_count = ranks.min_count();
+        let max_count = ranks.max_count();
+        if min_count != 1 || max_count != 1 {
+            violations.push(Violation::Bounded {
+                min_count,
+                max_count,
+            });
+        }
+
+        for (r, elements) in ranks.iter().enumerate() {
+            for (idx, el) in elements.iter().enumerate() {
+                if r != 0 && el.subs.is_empty() {
+                    violations.push(Violation::Ranked {
+                        el: (r, idx),
+                        incidence_type: IncidenceType::Subelement,
+                    });
+                }
+
+                if r != rank && el.sups.is_empty() {
+                    violations.push(Violation::Ranked {
+                        el: (r, idx),
+                        incidence_type: IncidenceType::Superelement,
+                    });
+                }
+            }
+        }
+
+        // Unlike Ranks::is_dyadic, this also checks the sections below the
+        // maximal element, which is where the facets fail to close up.
+        for r in 2..=rank {
+            for (idx, el) in ranks[r].iter().enumerate() {
+                let mut counts = HashMap::new();
+                for &sub in &el.subs {
+                    for &sub_sub in &ranks[(r - 1, sub)].subs {
+                        *counts.entry(sub_sub).or_insert(0) += 1;
+                    }
+                }
+
+                let mut counts: Vec<_> = counts.into_iter().filter(|&(_, count)| count != 2).collect();
+                counts.sort_unstable();
+                for (sub_sub, count) in counts {
+                    violations.push(Violation::Dyadic {
+                        section: Section::new(r - 2, sub_sub, r, idx),
+                        count,
+                    });
+                }
+            }
+        }
+
+        Self(violations)
+    }
+}
+
+/// A structure that's meant to become an abstract polytope, but may not be
+/// bounded, ranked, or dyadic yet. The violations are kept track of as the
+/// structure is edited, and once there are none left, it can be turned into
+/// an [`Abstract`] via [`Self::try_finalize`].
+///
+/// Only the subelements are given when building one of these. The
+/// superelements are always regenerated from them, so they're consistent.
+#[derive(Clone, Debug)]
+pub struct PartialAbstract {
+    /// The ranks of the structure.
+    ranks: Ranks,
+
+    /// Every way in which the structure fails to be an abstract polytope.
+    violations: Violations,
+}
+
+impl From<Abstract> for PartialAbstract {
+    fn from(abs: Abstract) -> Self {
+        Self::new(abs.into())
+    }
+}
+
+impl PartialAbstract {
+    /// Builds a structure from the subelements of some ranks. Their
+    /// superelements are regenerated.
+    ///
+    /// # Panics
+    /// Panics if some element has a subelement that doesn't exist.
+    pub fn new(mut ranks: Ranks) -> Self {
+        ranks.regenerate_superelements();
+        let violations = Violations::of(&ranks);
+        Self { ranks, violations }
+    }
+
+    /// Returns the ranks of the structure.
+    pub fn ranks(&self) -> &Ranks {
+        &self.ranks
+    }
+
+    /// Returns the rank of the structure.
+    pub fn rank(&self) -> usize {
+        self.ranks.rank()
+    }
+
+    /// Returns every way in which the structure fails to be an abstract
+    /// polytope.
+    pub fn violations(&self) -> &[Violation] {
+        &self.violations.0
+    }
+
+    /// Returns whether the structure is an abstract polytope, save for
+    /// connectivity, which isn't checked.
+    pub fn is_valid(&self) -> bool {
+        self.violations.0.is_empty()
+    }
+
+    /// Returns the number of facets of the structure.
+    pub fn facet_count(&self) -> usize {
+        self.ranks[self.rank() - 1].len()
+    }
+
+    /// Regenerates the superelements and the violations after an edit.
+    fn update(&mut self) {
+        self.ranks.regenerate_superelements();
+        self.violations = Violations::of(&self.ranks);
+    }
+
+    /// Adds a facet with the given ridges, and makes it a subelement of the
+    /// maximal element. Returns the index of the new facet.
+    ///
+    /// # Panics
+    /// Panics if the structure has no maximal element, or if some ridge
+    /// doesn't exist.
+    pub fn add_facet(&mut self, ridges: Subelements) -> usize {
+        let rank = self.rank();
+        let ridge_count = self.ranks[rank - 2].len();
+        assert!(
+            ridges.iter().all(|&ridge| ridge < ridge_count),
+            "Ridge index out of bounds!"
+        );
+
+        let idx = self.facet_count();
+        self.ranks[rank - 1].push(ridges.into());
+        self.ranks[(rank, 0)].subs.push(idx);
+        self.update();
+        idx
+    }
+
+    /// Removes some facets, along with the elements of rank 2 and up that are
+    /// then left without superelements. The vertices are never removed, so
+    /// that they keep their indices. The remaining elements of each rank keep
+    /// their order.
+    pub fn remove_facets(&mut self, facets: &[usize]) {
+        let rank = self.rank();
+        let mut removed: Vec<_> = (0..self.facet_count()).map(|idx| facets.contains(&idx)).collect();
+
+        for r in (2..rank).rev() {
+            // Maps the kept elements to their new indices.
+            let mut new_idx = Vec::with_capacity(removed.len());
+            let mut count = 0;
+            for &removed in &removed {
+                if removed {
+                    new_idx.push(None);
+                } else {
+                    new_idx.push(Some(count));
+                    count += 1;
+                }
+            }
+
+            let elements = std::mem::take(&mut self.ranks[r]);
+            self.ranks[r] = elements
+                .into_iter()
+                .zip(&removed)
+                .filter(|(_, &removed)| !removed)
+                .map(|(el, _)| el)
+                .collect();
+
+            for el in self.ranks[r + 1].iter_mut() {
+                el.subs = el.subs.iter().filter_map(|&sub| new_idx[sub]).collect();
+            }
+
+            // The elements below that are only in removed elements.
+            let mut used = vec![false; self.ranks[r - 1].len()];
+            for el in &self.ranks[r] {
+                for &sub in &el.subs {
+                    used[sub] = true;
+                }
+            }
+            removed = used.into_iter().map(|used| !used).collect();
+        }
+
+        self.update();
+    }
+
+    /// Removes a single facet. See [`Self::remove_facets`].
+    pub fn remove_facet(&mut self, facet: usize) {
+        self.remove_facets(&[facet]);
+    }
+
+    /// Runs a step of blending: removes every pair of facets with the same
+    /// ridges, as happens when two polytopes are blended along coinciding
+    /// facets. Returns the number of facets removed.
+    pub fn blend_step(&mut self) -> usize {
+        let rank = self.rank();
+        let mut by_ridges: HashMap<_, Vec<_>> = HashMap::new();
+        for (idx, facet) in self.ranks[rank - 1].iter().enumerate() {
+            let mut ridges = facet.subs.clone();
+            ridges.sort_unstable();
+            by_ridges.entry(ridges).or_default().push(idx);
+        }
+
+        let mut removed = Vec::new();
+        for facets in by_ridges.values() {
+            removed.extend_from_slice(&facets[..facets.len() / 2 * 2]);
+        }
+
+        if !removed.is_empty() {
+            self.remove_facets(&removed);
+        }
+        removed.len()
+    }
+
+    /// Turns the structure into an abstract polytope, if it has no
+    /// violations left. Otherwise, returns them.
+    pub fn try_finalize(self) -> Result<Abstract, Violations> {
+        if self.is_valid() {
+            // Safety: the structure is bounded, ranked, dyadic, and its
+            // superelements match its subelements.
+            Ok(unsafe { Abstract::from_ranks(self.ranks) })
+        } else {
+            Err(self.violations)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Polytope;
+
+    /// Returns the sections of a structure with a given number of elements in
+    /// between.
+    fn sections_with(partial: &PartialAbstract, count: usize) -> usize {
+        partial
+            .violations()
+            .iter()
+            .filter(|v| matches!(v, Violation::Dyadic { count: c, .. } if *c == count))
+            .count()
+    }
+
+    /// Removes a face of a cube and puts it back.
+    #[test]
+    fn remove_and_add_facet() {
+        let mut cube = PartialAbstract::from(Abstract::cube());
+        assert!(cube.is_valid());
+
+        let face = cube.ranks()[(3, 0)].subs.clone();
+        cube.remove_facet(0);
+        assert_eq!(cube.facet_count(), 5);
+        assert_eq!(sections_with(&cube, 1), 4);
+        assert_eq!(cube.violations().len(), 4);
+
+        cube.add_facet(face);
+        assert!(cube.is_valid());
+        assert_eq!(cube.try_finalize().unwrap().el_count(3), 6);
+    }
+
+    /// Adds a second copy of a face of a cube, and blends both away.
+    #[test]
+    fn blend_step() {
+        let mut cube = PartialAbstract::from(Abstract::cube());
+        let face = cube.ranks()[(3, 0)].subs.clone();
+        cube.add_facet(face);
+        assert_eq!(sections_with(&cube, 3), 4);
+
+        assert_eq!(cube.blend_step(), 2);
+        assert_eq!(cube.facet_count(), 5);
+        assert_eq!(sections_with(&cube, 1), 4);
+        assert!(cube.clone().try_finalize().is_err());
+        assert_eq!(cube.blend_step(), 0);
+    }
+
+    /// Removes every face of a cube but one, which also removes the edges
+    /// that are left without faces.
+    #[test]
+    fn remove_facets() {
+        let mut cube = PartialAbstract::from(Abstract::cube());
+        cube.remove_facets(&[1, 2, 3, 4, 5]);
+        assert_eq!(cube.ranks()[2].len(), 4);
+        assert_eq!(cube.ranks()[1].len(), 8);
+
+        // Four of the vertices are now in no edge.
+        let ranked = cube
+            .violations()
+            .iter()
+            .filter(|v| matches!(v, Violation::Ranked { .. }))
+            .count();
+        assert_eq!(ranked, 4);
+    }
+}
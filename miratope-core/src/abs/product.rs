@@ -1,6 +1,77 @@
 //! Contains the code for the polytope products.
 
 use super::*;
+use super::limits::{ElementLimits, TooLarge};
+
+/// The four products on polytopes, which differ in whether the minimal and
+/// maximal elements of the factors are taken into account.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProductType {
+    /// The [pyramid product](https://polytope.miraheze.org/wiki/Pyramid_product).
+    Pyramid,
+
+    /// The [prism product](https://polytope.miraheze.org/wiki/Prism_product).
+    Prism,
+
+    /// The [tegum product](https://polytope.miraheze.org/wiki/Tegum_product).
+    Tegum,
+
+    /// The [comb product](https://polytope.miraheze.org/wiki/Comb_product).
+    Comb,
+}
+
+/// Returns the number of elements of each rank of a [`product`] with the
+/// given flags, without building it. The counts saturate at `usize::MAX`.
+fn product_counts<const MIN: bool, const MAX: bool>(p: &Abstract, q: &Abstract) -> Vec<usize> {
+    let p_rank = p.rank();
+    let q_rank = q.rank();
+    if (MIN || MAX) && (p_rank == 0 || q_rank == 0) {
+        return vec![1];
+    }
+
+    let min_u = MIN as usize;
+    let max_u = MAX as usize;
+    let p_hi = p_rank - max_u;
+    let q_hi = q_rank - max_u;
+    let rank = p_rank + q_rank - min_u - max_u;
+    let mut counts = Vec::with_capacity(rank + 1);
+
+    if MIN {
+        counts.push(1);
+        counts.push(p.vertex_count().saturating_mul(q.vertex_count()));
+    }
+
+    // Mirrors the way the elements are added in the product.
+    for prod_rank in (2 * min_u)..=rank.saturating_sub(max_u) {
+        let lo = min_u.max((prod_rank + min_u).saturating_sub(q_hi));
+        let hi = p_hi.min(prod_rank);
+        let mut count = 0usize;
+
+        for p_el_rank in lo..=hi {
+            let q_el_rank = prod_rank + min_u - p_el_rank;
+            count = count.saturating_add(p.el_count(p_el_rank).saturating_mul(q.el_count(q_el_rank)));
+        }
+        counts.push(count);
+    }
+
+    if MAX {
+        counts.push(1);
+    }
+    counts
+}
+
+/// Returns the number of elements of each rank of the product of two
+/// polytopes, without building it. The counts saturate at `usize::MAX`.
+pub fn predicted_counts(p: &Abstract, q: &Abstract, product: ProductType) -> Vec<usize> {
+    // The pyramid and tegum products swap their factors, but that doesn't
+    // change the element counts.
+    match product {
+        ProductType::Pyramid => product_counts::<false, false>(p, q),
+        ProductType::Prism => product_counts::<true, false>(p, q),
+        ProductType::Tegum => product_counts::<false, true>(p, q),
+        ProductType::Comb => product_counts::<true, true>(p, q),
+    }
+}
 
 /// When we compute any polytope product, we add the elements of any given rank
 /// in lexicographic order of the ranks of the elements they come from. This
@@ -126,6 +197,13 @@ fn product<const MIN: bool, const MAX: bool>(p: &Abstract, q: &Abstract) -> Abst
         return Abstract::nullitope();
     }
 
+    // The indices of the elements are computed with unchecked arithmetic, so
+    // we make sure that none of them can overflow.
+    assert!(
+        product_counts::<MIN, MAX>(p, q).iter().all(|&count| count != usize::MAX),
+        "the product has too many elements to index"
+    );
+
     // 0 or 1 depending on whether the minimum/maximum elements are in the
     // polytope.
     let min_u = MIN as usize;
@@ -249,6 +327,40 @@ pub(super) fn duocomb(p: &Abstract, q: &Abstract) -> Abstract {
     product::<true, true>(p, q)
 }
 
+impl Abstract {
+    /// Builds the product of two polytopes, or returns an error without
+    /// building anything if it would have more elements of some rank than the
+    /// limits allow.
+    pub fn try_product(&self, other: &Self, product: ProductType, limits: &ElementLimits) -> Result<Self, TooLarge> {
+        limits.check(&predicted_counts(self, other, product))?;
+
+        Ok(match product {
+            ProductType::Pyramid => duopyramid(self, other),
+            ProductType::Prism => duoprism(self, other),
+            ProductType::Tegum => duotegum(self, other),
+            ProductType::Comb => duocomb(self, other),
+        })
+    }
+
+    /// Takes the product of an iterator over polytopes like
+    /// [`Polytope::multiprism`] and the like, but checks the limits before
+    /// building each of the intermediate products.
+    pub fn try_multiproduct<'a, I: IntoIterator<Item = &'a Self>>(
+        factors: I,
+        product: ProductType,
+        limits: &ElementLimits,
+    ) -> Result<Self, TooLarge> {
+        crate::try_multiproduct(
+            factors,
+            |p, q| p.try_product(q, product, limits),
+            || match product {
+                ProductType::Pyramid | ProductType::Comb => Self::nullitope(),
+                ProductType::Prism | ProductType::Tegum => Self::point(),
+            },
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -321,4 +433,53 @@ mod tests {
     fn duocomb() {
         test_duoproduct(Abstract::duocomb, |m, n| [1, m * n, 2 * m * n, m * n, 1])
     }
+
+    /// The predicted element counts are those of the products built.
+    #[test]
+    fn predicted_counts() {
+        let polytopes = [Abstract::dyad(), Abstract::polygon(5), Abstract::cube()];
+        let products = [
+            ProductType::Pyramid,
+            ProductType::Prism,
+            ProductType::Tegum,
+            ProductType::Comb,
+        ];
+
+        for p in &polytopes {
+            for q in &polytopes {
+                for &product in &products {
+                    let built = p.try_product(q, product, &ElementLimits::UNLIMITED).unwrap();
+                    let counts: Vec<_> = built.el_count_iter().collect();
+                    assert_eq!(super::predicted_counts(p, q, product), counts, "{:?}", product);
+                }
+            }
+        }
+    }
+
+    /// A hexeract can be built with a limit of exactly its 240 squares, but
+    /// not with one less.
+    #[test]
+    fn limits() {
+        let dyads = vec![Abstract::dyad(); 6];
+
+        let hexeract = Abstract::try_multiproduct(&dyads, ProductType::Prism, &ElementLimits::new(240)).unwrap();
+        test(&hexeract, [1, 64, 192, 240, 160, 60, 12, 1]);
+
+        assert_eq!(
+            Abstract::try_multiproduct(&dyads, ProductType::Prism, &ElementLimits::new(239)).unwrap_err(),
+            TooLarge {
+                rank: 3,
+                predicted: 240,
+                limit: 239,
+            }
+        );
+    }
+
+    /// Counts that don't fit in a `usize` are never allowed.
+    #[test]
+    fn overflow() {
+        let limits = ElementLimits::UNLIMITED;
+        assert!(limits.check(&[1, usize::MAX, 1]).is_err());
+        assert!(limits.check(&[1, usize::MAX - 1, 1]).is_ok());
+    }
 }
@@ -0,0 +1,334 @@
+//! Finds the central quotients of abstract polytopes: the polytopes that they
+//! double cover, such as the hemicube under the cube.
+//!
+//! An automorphism of a polytope is determined by where it sends a single flag
+//! of each of its flag-connected components, so we search through these
+//! images, keeping only the involutions without fixed proper elements.
+
+use std::collections::{HashMap, VecDeque};
+
+use super::{flag::Flag, partial::PartialAbstract, Abstract, ElementList, ElementMap, Ranked, Ranks, Subelements};
+use crate::Polytope;
+
+use vec_like::*;
+
+/// The flags of a polytope, together with the flag changes between them.
+struct FlagGraph {
+    /// The flags of the polytope.
+    flags: Vec<Flag>,
+
+    /// The index of every flag change of every flag. The `i`-th entry of each
+    /// list is the index of the `i + 1`-flag change.
+    changes: Vec<Vec<usize>>,
+
+    /// The flags in each flag-connected component.
+    components: Vec<Vec<usize>>,
+
+    /// The component of each flag.
+    component_of: Vec<usize>,
+}
+
+impl FlagGraph {
+    /// Builds the flag graph of a sorted polytope.
+    fn new(poly: &Abstract) -> Self {
+        let rank = poly.rank();
+        let flags: Vec<_> = poly.flags().collect();
+        let index: HashMap<_, _> = flags.iter().enumerate().map(|(idx, flag)| (flag, idx)).collect();
+
+        let changes: Vec<Vec<_>> = flags
+            .iter()
+            .map(|flag| (1..rank).map(|r| index[&flag.change(poly, r)]).collect())
+            .collect();
+
+        let mut components = Vec::new();
+        let mut component_of = vec![usize::MAX; flags.len()];
+        for start in 0..flags.len() {
+            if component_of[start] != usize::MAX {
+                continue;
+            }
+
+            let component_idx = components.len();
+            component_of[start] = component_idx;
+            let mut component = vec![start];
+            let mut queue = VecDeque::from(vec![start]);
+
+            while let Some(flag) = queue.pop_front() {
+                for &next in &changes[flag] {
+                    if component_of[next] == usize::MAX {
+                        component_of[next] = component_idx;
+                        component.push(next);
+                        queue.push_back(next);
+                    }
+                }
+            }
+
+            components.push(component);
+        }
+
+        Self {
+            flags,
+            changes,
+            components,
+            component_of,
+        }
+    }
+
+    /// Returns the isomorphism from a component onto another that sends its
+    /// first flag to a given one, as pairs of flags and their images, if it
+    /// exists.
+    fn isomorphism(&self, component: usize, image: usize) -> Option<Vec<(usize, usize)>> {
+        let flags = &self.components[component];
+        if flags.len() != self.components[self.component_of[image]].len() {
+            return None;
+        }
+
+        let mut map = HashMap::new();
+        let mut used = HashMap::new();
+        map.insert(flags[0], image);
+        used.insert(image, flags[0]);
+        let mut queue = VecDeque::from(vec![(flags[0], image)]);
+
+        while let Some((flag, image)) = queue.pop_front() {
+            for (&next, &next_image) in self.changes[flag].iter().zip(&self.changes[image]) {
+                match (map.get(&next), used.get(&next_image)) {
+                    (Some(&old_image), _) => {
+                        if old_image != next_image {
+                            return None;
+                        }
+                    }
+                    (None, Some(_)) => return None,
+                    (None, None) => {
+                        map.insert(next, next_image);
+                        used.insert(next_image, next);
+                        queue.push_back((next, next_image));
+                    }
+                }
+            }
+        }
+
+        Some(map.into_iter().collect())
+    }
+}
+
+/// An element map in the making, where `None` stands for an element whose
+/// image hasn't been found yet.
+type PartialMap = ElementMap<Option<usize>>;
+
+/// Sends the elements of each flag to the elements of its image. Returns
+/// `false` if this contradicts the images found so far.
+fn extend_map(map: &mut PartialMap, graph: &FlagGraph, pairs: &[(usize, usize)]) -> bool {
+    for &(flag, image) in pairs {
+        for (r, (&el, &el_image)) in graph.flags[flag].iter().zip(graph.flags[image].iter()).enumerate() {
+            match map[(r, el)] {
+                Some(old_image) if old_image != el_image => return false,
+                _ => map[(r, el)] = Some(el_image),
+            }
+        }
+    }
+
+    true
+}
+
+impl Abstract {
+    /// Returns the quotient of the polytope by some involution without fixed
+    /// proper elements, if the result is a polytope. This finds the figures
+    /// that double cover another, like the cube does the hemicube, or a
+    /// compound of two copies of a polytope does the polytope itself. See
+    /// [`Self::central_quotient_and_involution`].
+    ///
+    /// # Panics
+    /// You must call [`Polytope::element_sort`] before calling this method.
+    pub fn central_quotient(&self) -> Option<Self> {
+        self.central_quotient_and_involution().map(|(quotient, _)| quotient)
+    }
+
+    /// Returns the quotient of the polytope by some involution without fixed
+    /// proper elements, if the result is a polytope, along with the
+    /// involution. This sends every element to its image, and the elements of
+    /// the quotient are listed in the order of the first element of each pair.
+    ///
+    /// The involutions are searched in a fixed order, and the first one whose
+    /// quotient is dyadic is returned.
+    ///
+    /// # Panics
+    /// You must call [`Polytope::element_sort`] before calling this method.
+    pub fn central_quotient_and_involution(&self) -> Option<(Self, ElementMap<usize>)> {
+        if self.rank() < 2 {
+            return None;
+        }
+
+        let graph = FlagGraph::new(self);
+        let map = ElementMap::from_fn(self, |_, _| None);
+        let mut assigned = vec![false; graph.components.len()];
+        self.search_involutions(&graph, map, &mut assigned)
+    }
+
+    /// Searches for an involution that extends a partial one, in which the
+    /// assigned components already have their images.
+    fn search_involutions(
+        &self,
+        graph: &FlagGraph,
+        map: PartialMap,
+        assigned: &mut [bool],
+    ) -> Option<(Self, ElementMap<usize>)> {
+        let component = match assigned.iter().position(|&assigned| !assigned) {
+            Some(component) => component,
+            None => {
+                let involution: ElementMap<usize> = map
+                    .into_iter()
+                    .map(|list| list.into_iter().map(Option::unwrap).collect())
+                    .collect();
+                return self.quotient_by(&involution).map(|quotient| (quotient, involution));
+            }
+        };
+
+        for image in 0..graph.flags.len() {
+            let image_component = graph.component_of[image];
+            if assigned[image_component] {
+                continue;
+            }
+
+            let pairs = match graph.isomorphism(component, image) {
+                Some(pairs) => pairs,
+                None => continue,
+            };
+
+            let mut new_map = map.clone();
+            if !extend_map(&mut new_map, graph, &pairs) {
+                continue;
+            }
+
+            // The image component must be sent back.
+            if image_component != component {
+                let inverse: Vec<_> = pairs.iter().map(|&(flag, image)| (image, flag)).collect();
+                if !extend_map(&mut new_map, graph, &inverse) {
+                    continue;
+                }
+            }
+
+            if !self.is_partial_involution(&new_map) {
+                continue;
+            }
+
+            assigned[component] = true;
+            assigned[image_component] = true;
+            let found = self.search_involutions(graph, new_map, assigned);
+            assigned[component] = false;
+            assigned[image_component] = false;
+
+            if found.is_some() {
+                return found;
+            }
+        }
+
+        None
+    }
+
+    /// Returns whether every proper element whose image is known is sent to
+    /// another element, which is sent back to it.
+    fn is_partial_involution(&self, map: &PartialMap) -> bool {
+        let rank = self.rank();
+
+        (1..rank).all(|r| {
+            map[r].iter().enumerate().all(|(idx, &image)| match image {
+                Some(image) => image != idx && !matches!(map[(r, image)], Some(back) if back != idx),
+                None => true,
+            })
+        })
+    }
+
+    /// Identifies every element with its image under an involution, and
+    /// returns the result if it's a polytope.
+    fn quotient_by(&self, involution: &ElementMap<usize>) -> Option<Self> {
+        let rank = self.rank();
+
+        // The index of the orbit of each element.
+        let new_idx: Vec<Vec<_>> = involution
+            .iter()
+            .map(|list| {
+                let mut new_idx = vec![0; list.len()];
+                let mut count = 0;
+                for (idx, &image) in list.iter().enumerate() {
+                    if idx <= image {
+                        new_idx[idx] = count;
+                        new_idx[image] = count;
+                        count += 1;
+                    }
+                }
+                new_idx
+            })
+            .collect();
+
+        let mut ranks = Ranks::with_rank_capacity(rank);
+        for r in 0..=rank {
+            let mut elements = ElementList::new();
+
+            for (idx, &image) in involution[r].iter().enumerate() {
+                if idx > image {
+                    continue;
+                }
+
+                let mut subs: Vec<_> = self[(r, idx)].subs.iter().map(|&sub| new_idx[r - 1][sub]).collect();
+                subs.sort_unstable();
+                let len = subs.len();
+                subs.dedup();
+
+                // Two subelements of some proper element were identified.
+                if r != rank && subs.len() != len {
+                    return None;
+                }
+
+                elements.push(Subelements::from(subs).into());
+            }
+
+            ranks.push(elements);
+        }
+
+        PartialAbstract::new(ranks).try_finalize().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a polytope with its elements sorted.
+    fn sorted(mut poly: Abstract) -> Abstract {
+        poly.element_sort();
+        poly
+    }
+
+    /// The cube double covers the hemicube, via the central inversion.
+    #[test]
+    fn cube() {
+        let (mut hemicube, involution) = sorted(Abstract::cube()).central_quotient_and_involution().unwrap();
+        assert_eq!(hemicube.el_count_iter().collect::<Vec<_>>(), vec![1, 4, 6, 3, 1]);
+        assert!(!hemicube.orientable_mut());
+
+        for r in 1..4 {
+            for (idx, &image) in involution[r].iter().enumerate() {
+                assert_ne!(idx, image);
+                assert_eq!(involution[(r, image)], idx);
+            }
+        }
+    }
+
+    /// Every involution of the tetrahedron fixes some element.
+    #[test]
+    fn tetrahedron() {
+        assert!(sorted(Abstract::tetrahedron()).central_quotient().is_none());
+    }
+
+    /// A compound of two tetrahedra double covers a tetrahedron, via the
+    /// involution that swaps them.
+    #[test]
+    fn compound() {
+        let compound = sorted(Abstract::compound(vec![Abstract::tetrahedron(); 2].into_iter()));
+        let (mut tetrahedron, involution) = compound.central_quotient_and_involution().unwrap();
+        assert_eq!(tetrahedron.el_count_iter().collect::<Vec<_>>(), vec![1, 4, 6, 4, 1]);
+
+        tetrahedron.element_sort();
+        assert!(!tetrahedron.is_compound());
+        assert!(involution[1].iter().enumerate().all(|(idx, &image)| (idx < 4) != (image < 4)));
+    }
+}
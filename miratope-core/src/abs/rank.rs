@@ -0,0 +1,215 @@
+//! A type for the ranks of elements, to tell them apart from dimensions.
+//!
+//! Ranks are counted from the minimal element: the nullitope has rank 0, a
+//! point rank 1, a dyad rank 2, and so on, so that a polyhedron has rank 4.
+//! This is one more than the dimension of the polytope, and mixing both up is
+//! an easy way to get an off-by-one error. Going down from a rank is checked,
+//! so that asking for the facets of the nullitope fails loudly instead of
+//! wrapping around.
+
+use std::{
+    fmt::Display,
+    ops::{Add, Sub},
+};
+
+/// The rank of an element or a polytope. See the [module docs](self) for the
+/// conventions used.
+///
+/// Every function taking a rank accepts anything that converts into one, so
+/// plain `usize`s can still be passed in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Rank(usize);
+
+impl Rank {
+    /// The rank of the minimal element, the nullitope.
+    pub const MIN: Self = Self(0);
+
+    /// The rank of the vertices.
+    pub const VERTEX: Self = Self(1);
+
+    /// The rank of the edges.
+    pub const EDGE: Self = Self(2);
+
+    /// The rank of the faces.
+    pub const FACE: Self = Self(3);
+
+    /// Initializes a rank from its value.
+    pub const fn new(rank: usize) -> Self {
+        Self(rank)
+    }
+
+    /// Returns the rank of a polytope with a given dimension, which is one
+    /// more than it.
+    pub const fn of_dim(dim: usize) -> Self {
+        Self(dim + 1)
+    }
+
+    /// Returns the value of the rank.
+    pub const fn get(self) -> usize {
+        self.0
+    }
+
+    /// Returns the dimension of a polytope with this rank, or `None` for the
+    /// nullitope, whose dimension is -1.
+    pub fn dim(self) -> Option<usize> {
+        self.0.checked_sub(1)
+    }
+
+    /// Returns the rank of the facets of a polytope with this rank, or `None`
+    /// for the nullitope.
+    pub fn facet(self) -> Option<Self> {
+        self.checked_sub(1)
+    }
+
+    /// Returns the rank of the ridges of a polytope with this rank, or `None`
+    /// if it has none.
+    pub fn ridge(self) -> Option<Self> {
+        self.checked_sub(2)
+    }
+
+    /// Returns the rank a given amount below this one, or `None` if it would
+    /// be negative.
+    pub fn checked_sub(self, n: usize) -> Option<Self> {
+        self.0.checked_sub(n).map(Self)
+    }
+
+    /// Returns how many ranks below this one another rank is, or `None` if
+    /// it's above.
+    pub fn checked_diff(self, other: Self) -> Option<usize> {
+        self.0.checked_sub(other.0)
+    }
+}
+
+impl From<usize> for Rank {
+    fn from(rank: usize) -> Self {
+        Self(rank)
+    }
+}
+
+impl From<Rank> for usize {
+    fn from(rank: Rank) -> Self {
+        rank.0
+    }
+}
+
+impl PartialEq<usize> for Rank {
+    fn eq(&self, other: &usize) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialOrd<usize> for Rank {
+    fn partial_cmp(&self, other: &usize) -> Option<std::cmp::Ordering> {
+        self.0.partial_cmp(other)
+    }
+}
+
+impl Add<usize> for Rank {
+    type Output = Self;
+
+    fn add(self, n: usize) -> Self {
+        Self(self.0 + n)
+    }
+}
+
+impl Sub<usize> for Rank {
+    type Output = Self;
+
+    /// Returns the rank a given amount below this one.
+    ///
+    /// # Panics
+    /// Panics if the result would be negative, even in release builds.
+    fn sub(self, n: usize) -> Self {
+        self.checked_sub(n)
+            .unwrap_or_else(|| panic!("rank {} has nothing {} ranks below it", self, n))
+    }
+}
+
+impl Sub for Rank {
+    type Output = usize;
+
+    /// Returns how many ranks below this one another rank is.
+    ///
+    /// # Panics
+    /// Panics if the other rank is above this one, even in release builds.
+    fn sub(self, other: Self) -> usize {
+        self.checked_diff(other)
+            .unwrap_or_else(|| panic!("rank {} is above rank {}", other, self))
+    }
+}
+
+impl Display for Rank {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A polyhedron has rank 4, with faces of rank 3 and edges of rank 2.
+    #[test]
+    fn polyhedron() {
+        let rank = Rank::of_dim(3);
+        assert_eq!(rank, 4);
+        assert_eq!(rank.dim(), Some(3));
+        assert_eq!(rank.facet(), Some(Rank::FACE));
+        assert_eq!(rank.ridge(), Some(Rank::EDGE));
+        assert_eq!(rank - Rank::VERTEX, 3);
+    }
+
+    /// The lowest ranks have no dimension, facets or ridges to speak of.
+    #[test]
+    fn low_ranks() {
+        assert_eq!(Rank::MIN.dim(), None);
+        assert_eq!(Rank::MIN.facet(), None);
+        assert_eq!(Rank::MIN.ridge(), None);
+
+        assert_eq!(Rank::VERTEX.dim(), Some(0));
+        assert_eq!(Rank::VERTEX.facet(), Some(Rank::MIN));
+        assert_eq!(Rank::VERTEX.ridge(), None);
+
+        assert_eq!(Rank::EDGE.ridge(), Some(Rank::MIN));
+        assert_eq!(Rank::VERTEX.checked_diff(Rank::EDGE), None);
+    }
+
+    /// Going below the nullitope panics instead of wrapping around.
+    #[test]
+    #[should_panic(expected = "rank 1 has nothing 2 ranks below it")]
+    fn underflow() {
+        let _ = Rank::VERTEX - 2;
+    }
+
+    /// So does taking the difference with a higher rank.
+    #[test]
+    #[should_panic(expected = "rank 3 is above rank 2")]
+    fn negative_difference() {
+        let _ = Rank::EDGE - Rank::FACE;
+    }
+
+    /// The constructors and element getters handle the lowest ranks, and
+    /// ranks past the top.
+    #[test]
+    fn polytopes() {
+        use crate::{
+            abs::{Abstract, Ranked},
+            Polytope,
+        };
+
+        for rank in 0..=4 {
+            let rank = Rank::new(rank);
+            assert_eq!(Abstract::simplex(rank).rank(), rank.get());
+            assert_eq!(Abstract::hypercube(rank).rank(), rank.get());
+            assert_eq!(Abstract::orthoplex(rank).rank(), rank.get());
+        }
+
+        let cube = Abstract::cube();
+        assert_eq!(cube.el_count(Rank::EDGE), 12);
+        assert_eq!(cube.el_count(Rank::of_dim(5)), 0);
+        assert!(cube.element(Rank::of_dim(4), 0).is_none());
+        assert_eq!(cube.element(Rank::FACE, 0).unwrap().el_count(Rank::VERTEX), 4);
+        assert!(cube.element_fig(Rank::of_dim(4), 0).unwrap().is_none());
+        assert_eq!(cube.element_fig(Rank::VERTEX, 0).unwrap().unwrap().el_count(Rank::VERTEX), 3);
+    }
+}
@@ -8,9 +8,9 @@ use std::{
     slice, vec,
 };
 
-use super::Abstract;
+use super::{Abstract, Rank};
 
-use rayon::iter::{IntoParallelRefMutIterator, ParallelIterator};
+use rayon::iter::{IntoParallelIterator, IntoParallelRefMutIterator, ParallelIterator};
 use vec_like::*;
 
 /// Represents a map from ranks and indices into elements of a given type.
@@ -37,6 +37,54 @@ impl<T> IndexMut<(usize, usize)> for ElementMap<T> {
     }
 }
 
+impl<T> ElementMap<T> {
+    /// Builds an element map for a polytope, by evaluating a function on the
+    /// rank and index of each of its elements.
+    pub fn from_fn<R: Ranked, F: FnMut(usize, usize) -> T>(poly: &R, mut f: F) -> Self {
+        (0..=poly.rank())
+            .map(|r| (0..poly.el_count(r)).map(|idx| f(r, idx)).collect())
+            .collect()
+    }
+
+    /// Moves the value attached to every element of a polytope to the
+    /// corresponding element of its dual, as given by a [`DualCorrespondence`].
+    ///
+    /// # Panics
+    /// Panics if the correspondence doesn't have the same shape as the map, or
+    /// if it isn't a bijection on every rank.
+    pub fn dualize(self, correspondence: &DualCorrespondence) -> Self {
+        assert_eq!(self.len(), correspondence.len(), "rank mismatch");
+        let rank = self.len();
+        let mut dual: Vec<Vec<Option<T>>> = Vec::with_capacity(rank);
+        for r in (0..rank).rev() {
+            dual.push(iter::repeat_with(|| None).take(self[r].len()).collect());
+        }
+
+        for (r, list) in self.into_iter().enumerate() {
+            for (idx, value) in list.into_iter().enumerate() {
+                let slot = &mut dual[rank - 1 - r][correspondence[r][idx]];
+                assert!(slot.is_none(), "correspondence isn't a bijection");
+                *slot = Some(value);
+            }
+        }
+
+        dual.into_iter()
+            .map(|list| list.into_iter().map(Option::unwrap).collect())
+            .collect()
+    }
+}
+
+/// Custom data attached to the elements of a polytope, such as orbit tags,
+/// colors, or provenance. See [`ElementMap::dualize`] to carry it over to the
+/// dual.
+pub type ElementTags<T> = ElementMap<T>;
+
+/// The correspondence between the elements of a polytope and those of its
+/// dual. For every rank `r` and index `idx` of an element in a polytope of rank
+/// `n`, `correspondence[r][idx]` is the index of the corresponding element of
+/// rank `n - r` in the dual.
+pub type DualCorrespondence = Vec<Vec<usize>>;
+
 /// Represents a list of subelements in a polytope. Each element is represented
 /// as its index in the [`ElementList`] of the previous rank. This is used as
 /// one of the fields in an [`Element`].
@@ -142,7 +190,7 @@ impl Element {
 pub struct ElementList(pub Vec<Element>);
 impl_veclike!(ElementList, Item = Element);
 
-impl<'a> rayon::iter::IntoParallelIterator for &'a mut ElementList {
+impl<'a> IntoParallelIterator for &'a mut ElementList {
     type Iter = rayon::slice::IterMut<'a, Element>;
     type Item = &'a mut Element;
 
@@ -340,8 +388,8 @@ pub trait Ranked:
 
     /// Returns the number of elements of a given rank. Returns 0 if the rank is
     /// out of bounds.
-    fn el_count(&self, rank: usize) -> usize {
-        self.ranks().get(rank).map(ElementList::len).unwrap_or(0)
+    fn el_count(&self, rank: impl Into<Rank>) -> usize {
+        self.ranks().get(rank.into().get()).map(ElementList::len).unwrap_or(0)
     }
 
     /// Returns an iterator over the element counts of the structure.
@@ -351,13 +399,13 @@ pub trait Ranked:
 
     /// Returns a reference to an element of the polytope. To actually get the
     /// entire polytope it defines, use [`Polytope::element`](crate::Polytope::element).
-    fn get_element(&self, rank: usize, idx: usize) -> Option<&Element> {
-        self.ranks().get(rank)?.get(idx)
+    fn get_element(&self, rank: impl Into<Rank>, idx: usize) -> Option<&Element> {
+        self.ranks().get(rank.into().get())?.get(idx)
     }
 
     /// Gets a reference to the element list of a given rank.
-    fn get_element_list(&self, rank: usize) -> Option<&ElementList> {
-        self.ranks().get(rank)
+    fn get_element_list(&self, rank: impl Into<Rank>) -> Option<&ElementList> {
+        self.ranks().get(rank.into().get())
     }
 
     /// Returns a reference to the minimal element of the polytope.
@@ -433,8 +481,8 @@ impl Ranked for Ranks {
 impl Ranks {
     /// Initializes a new set of ranks capable of storing elements up to a given
     /// rank.
-    pub fn with_rank_capacity(rank: usize) -> Self {
-        Self::with_capacity(rank + 1)
+    pub fn with_rank_capacity(rank: impl Into<Rank>) -> Self {
+        Self::with_capacity(rank.into().get() + 1)
     }
 
     /// Returns a mutable reference to the minimal element of the polytope.
@@ -455,8 +503,8 @@ impl Ranks {
     }
 
     /// Returns a mutable reference to an element of the polytope.
-    pub fn get_element_mut(&mut self, rank: usize, idx: usize) -> Option<&mut Element> {
-        self.get_mut(rank)?.get_mut(idx)
+    pub fn get_element_mut(&mut self, rank: impl Into<Rank>, idx: usize) -> Option<&mut Element> {
+        self.get_mut(rank.into().get())?.get_mut(idx)
     }
 
     /// Returns a mutable iterator over the elements.
@@ -482,6 +530,35 @@ impl Ranks {
     pub fn element_sort(&mut self) {
         self.for_each_element_mut(Element::sort)
     }
+
+    /// Rebuilds the superelements of every element from the subelements of
+    /// the rank above it, in parallel over the ranks. The superelements of
+    /// each element end up sorted by index.
+    pub fn regenerate_superelements(&mut self) {
+        let ranks = &*self;
+        let sups: Vec<Vec<Superelements>> = (0..ranks.len())
+            .into_par_iter()
+            .map(|r| {
+                let mut sups = vec![Superelements::new(); ranks[r].len()];
+
+                if let Some(elements) = ranks.get(r + 1) {
+                    for (idx, el) in elements.iter().enumerate() {
+                        for &sub in &el.subs {
+                            sups[sub].push(idx);
+                        }
+                    }
+                }
+
+                sups
+            })
+            .collect();
+
+        for (elements, sups) in self.iter_mut().zip(sups) {
+            for (el, sups) in elements.iter_mut().zip(sups) {
+                el.sups = sups;
+            }
+        }
+    }
 }
 
 /// This struct allows us to build a polytope rank by rank by specifying the
@@ -524,7 +601,7 @@ impl AbstractBuilder {
 
     /// Initializes a new empty abstract builder with a capacity to store
     /// elements up and until a given rank.
-    pub fn with_rank_capacity(rank: usize) -> Self {
+    pub fn with_rank_capacity(rank: impl Into<Rank>) -> Self {
         Self(Ranks::with_rank_capacity(rank))
     }
 
@@ -646,7 +723,8 @@ impl ElementHash {
     /// Returns a map from elements on a polytope to elements on a new polytope
     /// representing a particular element (as a polytope). If the element
     /// doesn't exist, we return `None`.
-    pub fn new(poly: &Abstract, rank: usize, idx: usize) -> Option<Self> {
+    pub fn new(poly: &Abstract, rank: impl Into<Rank>, idx: usize) -> Option<Self> {
+        let rank = rank.into().get();
         poly.get_element(rank, idx)?;
 
         // A vector of HashMaps. The k-th entry is a map from k-elements of the
@@ -733,22 +811,18 @@ impl ElementHash {
                     }
                 }
 
-                // Gets the superelements. (do I really need to do this?)
-                let mut sups = Superelements::new();
-                if let Some(next_hash) = self.get(r + 1) {
-                    for sup in &el.sups {
-                        if let Some(&new_sup) = next_hash.get(sup) {
-                            sups.push(new_sup);
-                        }
-                    }
-                }
-
-                elements[new_idx] = Element { subs, sups };
+                elements[new_idx] = Element {
+                    subs,
+                    sups: Superelements::new(),
+                };
             }
 
             abs.push(elements);
         }
 
+        // The superelements are just the subelements read backwards.
+        abs.regenerate_superelements();
+
         // Safety: TODO document
         unsafe { Abstract::from_ranks(abs) }
     }
@@ -0,0 +1,211 @@
+//! Splits the elements of a polytope into their connected components, the
+//! sets of subelements that are connected through their ridges.
+//!
+//! Polytopes built by faceting or by taking cross-sections often have
+//! elements that are really compounds, or whose subelements meet in a way
+//! that makes them self-intersect. Splitting these apart keeps later
+//! operations from mixing them up.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use super::{Abstract, Element, Rank, Ranked};
+
+use vec_like::*;
+
+/// The subelements of an element joined to each subelement.
+type Adjacency = HashMap<usize, Vec<usize>>;
+
+/// Joins two subelements of an element.
+fn join(adjacency: &mut Adjacency, a: usize, b: usize) {
+    adjacency.entry(a).or_default().push(b);
+    adjacency.entry(b).or_default().push(a);
+}
+
+/// Splits a list of subelements into the components of a graph, each listed
+/// in depth-first order from its first subelement in the list.
+fn traverse(subs: &[usize], adjacency: &Adjacency) -> Vec<Vec<usize>> {
+    let mut visited = HashSet::new();
+    let mut components = Vec::new();
+
+    for &start in subs {
+        if visited.contains(&start) {
+            continue;
+        }
+
+        let mut component = Vec::new();
+        let mut stack = vec![start];
+
+        while let Some(sub) = stack.pop() {
+            if !visited.insert(sub) {
+                continue;
+            }
+
+            component.push(sub);
+            if let Some(next) = adjacency.get(&sub) {
+                stack.extend(next.iter().rev().filter(|next| !visited.contains(next)));
+            }
+        }
+
+        components.push(component);
+    }
+
+    components
+}
+
+impl Abstract {
+    /// Splits the elements of a given rank into their components, and lists
+    /// the subelements of each in the order they're traversed. Two
+    /// subelements are connected when they're the only ones in the element
+    /// through some ridge.
+    ///
+    /// When more subelements go through a ridge, `order` is called with the
+    /// index of the element, that of the ridge, and these subelements. It may return them sorted
+    /// cyclically around the ridge, in which case they're joined in
+    /// consecutive pairs, choosing whichever of the two possible pairings
+    /// joins the fewest subelements that aren't otherwise connected. If it
+    /// returns `None` instead, all of them are joined.
+    ///
+    /// Components other than the first become new elements at the end of the
+    /// list. Does nothing for ranks below 3 or above the rank of the facets.
+    pub fn untangle_elements_with<F>(&mut self, rank: impl Into<Rank>, mut order: F)
+    where
+        F: FnMut(&Self, usize, usize, &[usize]) -> Option<Vec<usize>>,
+    {
+        let rank = rank.into();
+        if rank < Rank::FACE || rank >= self.rank() {
+            return;
+        }
+        let rank = rank.get();
+
+        for idx in 0..self[rank].len() {
+            let mut components = self.element_components(rank, idx, &mut order);
+
+            if components.len() == 1 {
+                self[(rank, idx)].subs = components.pop().unwrap().into();
+            } else {
+                self.split_element(rank, idx, components);
+            }
+        }
+
+        self.meta.sorted = false;
+        self.ranks.debug_assert_consistent();
+    }
+
+    /// Returns the components of an element, as described in
+    /// [`Self::untangle_elements_with`].
+    fn element_components<F>(&self, rank: usize, idx: usize, order: &mut F) -> Vec<Vec<usize>>
+    where
+        F: FnMut(&Self, usize, usize, &[usize]) -> Option<Vec<usize>>,
+    {
+        let subs: Vec<_> = self[(rank, idx)].subs.iter().copied().collect();
+
+        // The subelements through each ridge.
+        let mut through: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for &sub in &subs {
+            for &ridge in &self[(rank - 1, sub)].subs {
+                through.entry(ridge).or_default().push(sub);
+            }
+        }
+
+        let mut adjacency = Adjacency::new();
+        let mut ambiguous = Vec::new();
+        for (&ridge, list) in &through {
+            match list.len() {
+                0 | 1 => {}
+                2 => join(&mut adjacency, list[0], list[1]),
+                _ => ambiguous.push(ridge),
+            }
+        }
+
+        if ambiguous.is_empty() {
+            return traverse(&subs, &adjacency);
+        }
+
+        // The components found without the ambiguous ridges.
+        let mut component_of = HashMap::new();
+        for (component_idx, component) in traverse(&subs, &adjacency).into_iter().enumerate() {
+            for sub in component {
+                component_of.insert(sub, component_idx);
+            }
+        }
+
+        for ridge in ambiguous {
+            let list = &through[&ridge];
+
+            match order(self, idx, ridge, list) {
+                Some(cyclic) if cyclic.len() % 2 == 0 => {
+                    let len = cyclic.len();
+                    let pair = |offset: usize, i: usize| (cyclic[(2 * i + offset) % len], cyclic[(2 * i + 1 + offset) % len]);
+                    let cost = |offset| {
+                        (0..len / 2)
+                            .filter(|&i| {
+                                let (a, b) = pair(offset, i);
+                                component_of[&a] != component_of[&b]
+                            })
+                            .count()
+                    };
+
+                    let offset = if cost(1) < cost(0) { 1 } else { 0 };
+                    for i in 0..len / 2 {
+                        let (a, b) = pair(offset, i);
+                        join(&mut adjacency, a, b);
+                    }
+                }
+
+                _ => {
+                    for pair in list.windows(2) {
+                        join(&mut adjacency, pair[0], pair[1]);
+                    }
+                }
+            }
+        }
+
+        traverse(&subs, &adjacency)
+    }
+
+    /// Gives the first component to an element, and makes new elements out
+    /// of the rest.
+    fn split_element(&mut self, rank: usize, idx: usize, components: Vec<Vec<usize>>) {
+        let sups = self[(rank, idx)].sups.clone();
+        let mut components = components.into_iter();
+        self[(rank, idx)].subs = components.next().unwrap().into();
+
+        for component in components {
+            let new_idx = self[rank].len();
+
+            for &sub in &component {
+                for sup in self[(rank - 1, sub)].sups.iter_mut() {
+                    if *sup == idx {
+                        *sup = new_idx;
+                    }
+                }
+            }
+
+            for &sup in &sups {
+                self[(rank + 1, sup)].subs.push(new_idx);
+            }
+
+            self[rank].push(Element::new(component.into(), sups.clone()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Polytope;
+
+    /// The pyramid on a compound of two triangles has a compound face, which
+    /// is split into the two triangles.
+    #[test]
+    fn compound_face() {
+        let mut pyramid = Abstract::compound(vec![Abstract::polygon(3); 2].into_iter()).pyramid();
+        let compound_idx = (0..pyramid[3].len()).find(|&idx| pyramid[(3, idx)].subs.len() == 6).unwrap();
+        pyramid.untangle_elements(3);
+
+        assert_eq!(pyramid.el_count_iter().collect::<Vec<_>>(), vec![1, 7, 12, 8, 1]);
+        assert_eq!(pyramid[(3, compound_idx)].subs.len(), 3);
+        assert_eq!(pyramid[3].last().unwrap().subs.len(), 3);
+        assert!(pyramid[3].iter().all(|face| face.subs.len() == 3));
+    }
+}
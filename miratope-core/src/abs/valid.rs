@@ -13,7 +13,7 @@ use super::{Ranked, Ranks, Section};
 /// Represents the way in which two elements with one rank of difference are
 /// incident to one another. Used as a field in some [`AbstractError`] variants.
 
-#[derive(Clone, Copy, Debug, Display)]
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq)]
 pub enum IncidenceType {
     /// This element is a subelement of another.
     #[strum(serialize = "subelement")]
@@ -152,6 +152,64 @@ impl std::error::Error for AbstractError {}
 /// The return value for [`Ranks::is_valid`].
 pub type AbstractResult<T> = Result<T, AbstractError>;
 
+/// A single link between two elements that's recorded on only one side, or
+/// that points to a non-existent element.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Inconsistency {
+    /// The coordinates of the element at fault.
+    pub el: (usize, usize),
+
+    /// Whether the link is to a subelement or a superelement.
+    pub incidence_type: IncidenceType,
+
+    /// The index of the linked element.
+    pub index: usize,
+
+    /// Whether the linked element exists. If it does, it's missing the link
+    /// back.
+    pub exists: bool,
+}
+
+impl std::fmt::Display for Inconsistency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?} has a {} with index {}, but {}",
+            self.el,
+            self.incidence_type,
+            self.index,
+            if self.exists {
+                "not viceversa"
+            } else {
+                "it doesn't exist"
+            }
+        )
+    }
+}
+
+/// Every mismatch between the subelements and superelements of a polytope, as
+/// returned by [`Ranks::check_sub_sup_consistency`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InconsistencyReport(pub Vec<Inconsistency>);
+
+impl std::fmt::Display for InconsistencyReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Polytope has {} inconsistent incidences:",
+            self.0.len()
+        )?;
+
+        for inconsistency in &self.0 {
+            write!(f, "\n  {}", inconsistency)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for InconsistencyReport {}
+
 impl Ranks {
     /// Checks whether the ranks form a valid polytope, i.e. whether the poset
     /// is bounded, dyadic, and all of its indices refer to valid elements.
@@ -259,6 +317,59 @@ impl Ranks {
         Ok(())
     }
 
+    /// Checks whether every subelement lists its superelements and viceversa,
+    /// and whether they all refer to valid elements. Unlike
+    /// [`Self::check_incidences`], this doesn't stop at the first error, but
+    /// returns every mismatch it finds.
+    pub fn check_sub_sup_consistency(&self) -> Result<(), InconsistencyReport> {
+        let mut inconsistencies = Vec::new();
+
+        for (r, elements) in self.iter().enumerate() {
+            for (idx, el) in elements.iter().enumerate() {
+                for &sub in &el.subs {
+                    let sub_el = r.checked_sub(1).and_then(|r| self.get_element(r, sub));
+                    if !matches!(sub_el, Some(sub_el) if sub_el.sups.contains(&idx)) {
+                        inconsistencies.push(Inconsistency {
+                            el: (r, idx),
+                            incidence_type: IncidenceType::Subelement,
+                            index: sub,
+                            exists: sub_el.is_some(),
+                        });
+                    }
+                }
+
+                for &sup in &el.sups {
+                    let sup_el = self.get_element(r + 1, sup);
+                    if !matches!(sup_el, Some(sup_el) if sup_el.subs.contains(&idx)) {
+                        inconsistencies.push(Inconsistency {
+                            el: (r, idx),
+                            incidence_type: IncidenceType::Superelement,
+                            index: sup,
+                            exists: sup_el.is_some(),
+                        });
+                    }
+                }
+            }
+        }
+
+        if inconsistencies.is_empty() {
+            Ok(())
+        } else {
+            Err(InconsistencyReport(inconsistencies))
+        }
+    }
+
+    /// Panics with every mismatch between subelements and superelements, in
+    /// debug builds only. Called at the end of the operations that modify the
+    /// structure of a polytope.
+    pub(crate) fn debug_assert_consistent(&self) {
+        if cfg!(debug_assertions) {
+            if let Err(report) = self.check_sub_sup_consistency() {
+                panic!("{}", report);
+            }
+        }
+    }
+
     /// Determines whether the polytope satisfies the diamond property. A valid
     /// non-fissary polytope should always return `true`.
     pub fn is_dyadic(&self) -> AbstractResult<()> {
@@ -0,0 +1,63 @@
+//! The command line interface to Miratope's faceting algorithm. See
+//! [`miratope_core::cli`] for the usage.
+//!
+//! Messages logged by the faceting are written to the standard error, up to
+//! the level in the `MIRATOPE_LOG` environment variable, or up to warnings if
+//! it's unset. The progress of the faceting is drawn on the last line of the
+//! standard error.
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use log::{LevelFilter, Log, Metadata, Record};
+use miratope_core::{cli, conc::faceting::ProgressSink};
+
+/// Writes every message logged to the standard error.
+struct StderrLogger;
+
+impl Log for StderrLogger {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        eprintln!("\n{}: {}", record.level().as_str().to_lowercase(), record.args());
+    }
+
+    fn flush(&self) {}
+}
+
+fn main() {
+    let level = std::env::var("MIRATOPE_LOG")
+        .ok()
+        .and_then(|level| level.parse().ok())
+        .unwrap_or(LevelFilter::Warn);
+    log::set_logger(&StderrLogger).expect("couldn't set the logger");
+    log::set_max_level(level);
+
+    let cancel = Arc::new(AtomicBool::new(false));
+
+    // The first Ctrl-C stops the enumeration and keeps what was found so far,
+    // the second one exits immediately.
+    let handler_cancel = Arc::clone(&cancel);
+    ctrlc::set_handler(move || {
+        if handler_cancel.swap(true, Ordering::Relaxed) {
+            std::process::exit(130);
+        }
+        eprintln!("\nCancelling, press Ctrl-C again to exit immediately...");
+    })
+    .expect("couldn't set the Ctrl-C handler");
+
+    // Each report overwrites the last one. Logged messages start on a new
+    // line, so they're never drawn over.
+    let progress = ProgressSink::new(|progress| eprint!("\r{}\x1b[K", cli::progress_bar(progress)));
+    let result = cli::run(std::env::args().skip(1), cancel, Some(progress));
+    eprint!("\r\x1b[K");
+
+    if let Err(err) = result {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    }
+}
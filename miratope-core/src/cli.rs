@@ -0,0 +1,625 @@
+//! A command line interface to the faceting algorithm, for running it on
+//! machines without a display.
+//!
+//! The usage is
+//!
+//! ```text
+//! miratope-cli facet <input.off> [--group full|chiral|file.grp] [--edge-length L]
+//!                    [--noble N] [--max-tuples N] [--out DIR] [--format off|json]
+//!                    [--precision N] [--exact] [--db FILE]
+//! ```
+//!
+//! A group file consists of whitespace-separated numbers, where every block of
+//! d² numbers is read as a d × d matrix in row-major order, d being the
+//! dimension of the input polytope. Everything after a `#` in a line is
+//! ignored. The matrices must form a group, or the command fails. The
+//! vertices of the input are copied by the group before faceting, just like
+//! when the group is taken from another polytope in the UI.
+//!
+//! The coordinates are written with `--precision` digits after the decimal
+//! point, or in full by default. With `--exact`, the closed forms of the
+//! coordinates are written next to them: as comments in OFF files, and as an
+//! `exact` list parallel to the vertices in JSON files.
+//!
+//! With `--db`, the vertices faceted, the run and every faceting are also
+//! added to a SQLite survey database, see
+//! [`SurveyDb`](crate::conc::faceting::survey::SurveyDb). This needs the
+//! `sqlite` feature.
+//!
+//! While faceting, the progress is drawn as a bar on the standard error, see
+//! [`progress_bar`].
+
+use std::{
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{atomic::AtomicBool, Arc},
+};
+
+use crate::{
+    abs::Ranked,
+    conc::{
+        faceting::{
+            EdgeLengthFilter, FacetingError, FacetingOptions, FacetingProgress, FacetingResult, FacetingStats,
+            GroupEnum, ProgressSink,
+        },
+        symmetry::{VertexMap, Vertices},
+        Concrete, ConcretePolytope,
+    },
+    file::{
+        exact::{recognize_constant, DEFAULT_TOLERANCE},
+        format_coordinate,
+        off::{OffOptions, OffSaveError},
+        FromFile, IoError,
+    },
+    geometry::Matrix,
+    group::Group,
+};
+
+#[cfg(feature = "sqlite")]
+use crate::conc::faceting::survey::{SurveyDb, SurveyError};
+
+use serde_json::{json, Value};
+use vec_like::VecLike;
+
+/// The usage string of the command line interface.
+pub const USAGE: &str = "usage: miratope-cli facet <input.off> [--group full|chiral|file.grp] \
+[--edge-length L] [--noble N] [--max-tuples N] [--out DIR] [--format off|json] [--precision N] [--exact] [--db FILE]";
+
+/// Any error encountered while running the command line interface.
+#[derive(Debug)]
+pub enum CliError {
+    /// The arguments couldn't be parsed.
+    Usage(String),
+
+    /// The input polytope couldn't be loaded.
+    Load(String),
+
+    /// The group file couldn't be parsed.
+    Group(String),
+
+    /// The symmetry group of the input couldn't be computed.
+    Symmetry,
+
+    /// The faceting was aborted.
+    Faceting(FacetingError),
+
+    /// A faceting couldn't be written as an OFF file.
+    Save(OffSaveError),
+
+    /// Some generic I/O error occured.
+    IoError(IoError),
+
+    /// The facetings couldn't be added to the survey database.
+    #[cfg(feature = "sqlite")]
+    Survey(SurveyError),
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Usage(msg) => write!(f, "{}\n{}", msg, USAGE),
+            Self::Load(msg) => write!(f, "couldn't load polytope: {}", msg),
+            Self::Group(msg) => write!(f, "invalid group file: {}", msg),
+            Self::Symmetry => write!(f, "couldn't compute the symmetry group of the input"),
+            Self::Faceting(err) => write!(f, "faceting aborted: {}", err),
+            Self::Save(err) => write!(f, "couldn't save OFF file: {}", err),
+            Self::IoError(err) => write!(f, "IO error: {}", err),
+            #[cfg(feature = "sqlite")]
+            Self::Survey(err) => write!(f, "couldn't store facetings: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+/// [`IoError`] is a type of [`CliError`].
+impl From<IoError> for CliError {
+    fn from(err: IoError) -> Self {
+        Self::IoError(err)
+    }
+}
+
+/// [`OffSaveError`] is a type of [`CliError`].
+impl From<OffSaveError> for CliError {
+    fn from(err: OffSaveError) -> Self {
+        Self::Save(err)
+    }
+}
+
+/// [`FacetingError`] is a type of [`CliError`].
+impl From<FacetingError> for CliError {
+    fn from(err: FacetingError) -> Self {
+        Self::Faceting(err)
+    }
+}
+
+/// [`SurveyError`] is a type of [`CliError`].
+#[cfg(feature = "sqlite")]
+impl From<SurveyError> for CliError {
+    fn from(err: SurveyError) -> Self {
+        Self::Survey(err)
+    }
+}
+
+/// The result of running a command.
+pub type CliResult<T> = Result<T, CliError>;
+
+/// The symmetry group to facet under.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GroupArg {
+    /// The full symmetry group of the input.
+    Full,
+
+    /// The rotation group of the input.
+    Chiral,
+
+    /// A group read from a file.
+    File(PathBuf),
+}
+
+impl FromStr for GroupArg {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> CliResult<Self> {
+        Ok(match s {
+            "full" => Self::Full,
+            "chiral" => Self::Chiral,
+            path => Self::File(PathBuf::from(path)),
+        })
+    }
+}
+
+/// The format the facetings are written in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OutputFormat {
+    /// One OFF file per faceting.
+    Off,
+
+    /// One JSON file per faceting, containing the vertices and the subelements
+    /// of every element.
+    Json,
+}
+
+impl OutputFormat {
+    /// The file extension for this format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Json => "json",
+        }
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> CliResult<Self> {
+        match s {
+            "off" => Ok(Self::Off),
+            "json" => Ok(Self::Json),
+            _ => Err(CliError::Usage(format!("unknown format \"{}\"", s))),
+        }
+    }
+}
+
+/// The arguments of the `facet` command.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FacetArgs {
+    /// The path to the polytope to facet.
+    pub input: PathBuf,
+
+    /// The symmetry group to facet under.
+    pub group: GroupArg,
+
+    /// The edge length of the facetings, if fixed.
+    pub edge_length: Option<f64>,
+
+    /// The exact number of facet orbits.
+    pub noble: Option<usize>,
+
+    /// The maximum estimated number of vertex tuples to check before giving
+    /// up.
+    pub max_tuples: Option<u64>,
+
+    /// The directory the facetings are written to.
+    pub out: PathBuf,
+
+    /// The format the facetings are written in.
+    pub format: OutputFormat,
+
+    /// The number of digits written after the decimal point of each
+    /// coordinate, if limited.
+    pub precision: Option<usize>,
+
+    /// Whether the closed forms of the coordinates are written next to them.
+    pub exact: bool,
+
+    /// The survey database the facetings are also added to, if any.
+    pub db: Option<PathBuf>,
+}
+
+/// Parses the value of a flag.
+fn flag_value<T: FromStr, I: Iterator<Item = String>>(flag: &str, args: &mut I) -> CliResult<T> {
+    let value = args
+        .next()
+        .ok_or_else(|| CliError::Usage(format!("missing value for {}", flag)))?;
+
+    value
+        .parse()
+        .map_err(|_| CliError::Usage(format!("invalid value \"{}\" for {}", value, flag)))
+}
+
+impl FacetArgs {
+    /// Parses the arguments that follow the `facet` command.
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> CliResult<Self> {
+        let mut args = args.into_iter();
+        let mut input = None;
+        let mut group = GroupArg::Full;
+        let mut edge_length = None;
+        let mut noble = None;
+        let mut max_tuples = None;
+        let mut out = PathBuf::from(".");
+        let mut format = OutputFormat::Off;
+        let mut precision = None;
+        let mut exact = false;
+        let mut db = None;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--group" => group = flag_value("--group", &mut args)?,
+                "--edge-length" => edge_length = Some(flag_value("--edge-length", &mut args)?),
+                "--noble" => noble = Some(flag_value("--noble", &mut args)?),
+                "--max-tuples" => max_tuples = Some(flag_value("--max-tuples", &mut args)?),
+                "--out" => out = flag_value("--out", &mut args)?,
+                "--format" => format = flag_value("--format", &mut args)?,
+                "--precision" => precision = Some(flag_value("--precision", &mut args)?),
+                "--exact" => exact = true,
+                "--db" => db = Some(flag_value("--db", &mut args)?),
+                flag if flag.starts_with("--") => {
+                    return Err(CliError::Usage(format!("unknown flag {}", flag)))
+                }
+                _ => {
+                    if input.is_some() {
+                        return Err(CliError::Usage(format!("unexpected argument \"{}\"", arg)));
+                    }
+                    input = Some(PathBuf::from(arg));
+                }
+            }
+        }
+
+        Ok(Self {
+            input: input.ok_or_else(|| CliError::Usage("missing input file".to_string()))?,
+            group,
+            edge_length,
+            noble,
+            max_tuples,
+            out,
+            format,
+            precision,
+            exact,
+            db,
+        })
+    }
+
+    /// Returns the options the facetings are written with. These apply to
+    /// every output format.
+    pub fn off_options(&self) -> OffOptions {
+        OffOptions {
+            precision: self.precision,
+            exact: self.exact,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the faceting options corresponding to these arguments.
+    pub fn options(&self, cancel: Arc<AtomicBool>) -> FacetingOptions {
+        FacetingOptions {
+            edge_length: self.edge_length.map_or(EdgeLengthFilter::Any, EdgeLengthFilter::Single),
+            noble: self.noble,
+            abort_if_estimated_tuples_exceed: self.max_tuples,
+            cancel: Some(cancel),
+            ..Default::default()
+        }
+    }
+}
+
+/// Reads a group of `dim × dim` matrices from the contents of a group file.
+pub fn parse_group(src: &str, dim: usize) -> CliResult<Vec<Matrix<f64>>> {
+    let mut entries = Vec::new();
+    for line in src.lines() {
+        let line = line.split('#').next().unwrap_or_default();
+        for token in line.split_whitespace() {
+            entries.push(
+                token
+                    .parse::<f64>()
+                    .map_err(|_| CliError::Group(format!("invalid number \"{}\"", token)))?,
+            );
+        }
+    }
+
+    let size = dim * dim;
+    if entries.is_empty() || size == 0 || entries.len() % size != 0 {
+        return Err(CliError::Group(format!(
+            "expected a positive multiple of {} entries, found {}",
+            size,
+            entries.len()
+        )));
+    }
+
+    Ok(entries
+        .chunks(size)
+        .map(|chunk| Matrix::from_row_slice(dim, dim, chunk))
+        .collect())
+}
+
+/// The number of characters in a progress bar.
+const BAR_WIDTH: usize = 20;
+
+/// Returns a line describing the progress of a faceting, with a bar for the
+/// stages whose length is known.
+pub fn progress_bar(progress: FacetingProgress) -> String {
+    let bar = |label: &str, done: usize, total: usize| {
+        let filled = if total == 0 { BAR_WIDTH } else { (done * BAR_WIDTH / total).min(BAR_WIDTH) };
+        format!(
+            "{} [{}{}] {}/{}",
+            label,
+            "#".repeat(filled),
+            " ".repeat(BAR_WIDTH - filled),
+            done,
+            total
+        )
+    };
+
+    match progress {
+        FacetingProgress::EdgeOrbits { found } => format!("Enumerating edge orbits: {} found", found),
+        FacetingProgress::Subspaces { rank, found } => {
+            format!("Enumerating rank {} subspaces: {} orbits found", rank, found)
+        }
+        FacetingProgress::Hyperplanes { found } => format!("Enumerating hyperplanes: {} orbits found", found),
+        FacetingProgress::HyperplaneFacets { done, total } => bar("Faceting hyperplanes", done, total),
+        FacetingProgress::Subfacetings { found } => format!("Faceting a hyperplane: {} facetings found", found),
+        FacetingProgress::Ridges { done, total } => bar("Sorting ridges", done, total),
+        FacetingProgress::Combining { found } => format!("Combining facets: {} facetings found", found),
+        FacetingProgress::Building { done, total } => bar("Building facetings", done, total),
+    }
+}
+
+/// Writes the facetings to a directory, one file per faceting, and returns the
+/// paths of the written files. Unnamed facetings are named after their index.
+/// The precision and the closed forms of the coordinates are taken from the
+/// options in every format.
+pub fn export(
+    facetings: &[FacetingResult],
+    dir: &Path,
+    format: OutputFormat,
+    options: OffOptions,
+) -> CliResult<Vec<PathBuf>> {
+    fs::create_dir_all(dir)?;
+    let mut paths = Vec::new();
+
+    for (idx, faceting) in facetings.iter().enumerate() {
+        let poly = &faceting.polytope;
+        let name = match &faceting.name {
+            Some(name) => name.clone(),
+            None => format!("faceting {}", idx),
+        };
+        let path = dir.join(format!("{}.{}", name, format.extension()));
+
+        match format {
+            OutputFormat::Off => poly.to_path(&path, options)?,
+            OutputFormat::Json => fs::write(&path, to_json(poly, &name, options).to_string())?,
+        }
+
+        paths.push(path);
+    }
+
+    Ok(paths)
+}
+
+/// Returns a JSON representation of a polytope, with its metadata, its
+/// vertices and the subelements of each of its elements from the edges up.
+///
+/// The coordinates are rounded to the precision in the options. If closed
+/// forms are asked for, they're recognized from the unrounded coordinates and
+/// listed under `exact`, with `null` for the coordinates that aren't
+/// recognized.
+pub fn to_json(poly: &Concrete, name: &str, options: OffOptions) -> Value {
+    let vertices: Vec<Vec<f64>> = poly
+        .vertices
+        .iter()
+        .map(|v| {
+            v.iter()
+                .map(|&c| match options.precision {
+                    Some(_) => format_coordinate(c, options.precision).parse().unwrap(),
+                    None => c,
+                })
+                .collect()
+        })
+        .collect();
+    let elements: Vec<Vec<Vec<usize>>> = (2..poly.rank())
+        .map(|r| poly[r].iter().map(|el| el.subs.iter().copied().collect()).collect())
+        .collect();
+
+    let mut json = json!({
+        "name": name,
+        "meta": poly.meta,
+        "rank": poly.rank(),
+        "vertices": vertices,
+        "elements": elements,
+    });
+
+    if options.exact {
+        let exact: Vec<Vec<_>> = poly
+            .vertices
+            .iter()
+            .map(|v| v.iter().map(|&c| recognize_constant(c, DEFAULT_TOLERANCE)).collect())
+            .collect();
+        json["exact"] = json!(exact);
+    }
+
+    json
+}
+
+/// Runs the `facet` command, writing the facetings to the output directory.
+/// Setting `cancel` stops the enumeration early, and the facetings found so far
+/// are still written. The progress is reported to `progress`, if given.
+/// Returns the paths of the files written along with the facetings in them.
+pub fn facet(
+    args: &FacetArgs,
+    cancel: Arc<AtomicBool>,
+    progress: Option<ProgressSink>,
+) -> CliResult<(Vec<PathBuf>, Vec<FacetingResult>, FacetingStats)> {
+    #[cfg(not(feature = "sqlite"))]
+    if args.db.is_some() {
+        return Err(CliError::Usage("--db needs the sqlite feature".to_string()));
+    }
+
+    let mut poly =
+        Concrete::from_path(&args.input).map_err(|err| CliError::Load(err.to_string()))?;
+    let options = FacetingOptions {
+        progress,
+        ..args.options(cancel)
+    };
+
+    let (vertices, vertex_map) = match &args.group {
+        GroupArg::Full => (
+            poly.vertices.to_vec(),
+            VertexMap::from(poly.get_symmetry_group().ok_or(CliError::Symmetry)?.1),
+        ),
+        GroupArg::Chiral => (
+            poly.vertices.to_vec(),
+            VertexMap::from(poly.get_rotation_group().ok_or(CliError::Symmetry)?.1),
+        ),
+        GroupArg::File(path) => {
+            let dim = poly.dim().ok_or(CliError::Symmetry)?;
+            let matrices = parse_group(&fs::read_to_string(path)?, dim)?;
+
+            // Safety: the matrices aren't trusted to form a group, so they're
+            // checked below before the group is used for anything else.
+            let group = unsafe { Group::new(dim, matrices.into_iter()) };
+            let (vertices, vertex_map) = Vertices(poly.vertices.to_vec())
+                .try_copy_by_symmetry(group)
+                .ok_or_else(|| CliError::Group("the matrices aren't closed under composition".to_string()))?;
+            let vertex_map = VertexMap::new_checked(vertex_map, vertices.0.len(), true)
+                .map_err(|err| CliError::Group(err.to_string()))?;
+            (vertices.0, vertex_map)
+        }
+    };
+
+    let (facetings, stats) =
+        poly.try_faceting_with_stats(vertices.clone(), GroupEnum::VertexMap(vertex_map), &options)?;
+    let paths = export(&facetings, &args.out, args.format, args.off_options())?;
+
+    #[cfg(feature = "sqlite")]
+    if let Some(db) = &args.db {
+        SurveyDb::open(db)?.insert_survey(&vertices, &poly.meta, &options, &stats, &facetings)?;
+    }
+    Ok((paths, facetings, stats))
+}
+
+/// Runs the command line interface on the arguments, not including the
+/// program name, reporting the progress of the faceting to `progress`.
+pub fn run<I: IntoIterator<Item = String>>(
+    args: I,
+    cancel: Arc<AtomicBool>,
+    progress: Option<ProgressSink>,
+) -> CliResult<()> {
+    let mut args = args.into_iter();
+
+    match args.next().as_deref() {
+        Some("facet") => {
+            let args = FacetArgs::parse(args)?;
+            let (paths, facetings, stats) = facet(&args, cancel, progress)?;
+            for (path, faceting) in paths.iter().zip(&facetings) {
+                println!("{}: {}", path.display(), faceting);
+            }
+            println!("{}", stats);
+            println!("Wrote {} files to {}", paths.len(), args.out.display());
+            Ok(())
+        }
+        Some(cmd) => Err(CliError::Usage(format!("unknown command \"{}\"", cmd))),
+        None => Err(CliError::Usage("missing command".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn parse_defaults() {
+        let parsed = FacetArgs::parse(args("oct.off")).unwrap();
+        assert_eq!(parsed.input, PathBuf::from("oct.off"));
+        assert_eq!(parsed.group, GroupArg::Full);
+        assert_eq!(parsed.edge_length, None);
+        assert_eq!(parsed.noble, None);
+        assert_eq!(parsed.out, PathBuf::from("."));
+        assert_eq!(parsed.format, OutputFormat::Off);
+        assert_eq!(parsed.precision, None);
+        assert!(!parsed.exact);
+        assert_eq!(parsed.db, None);
+    }
+
+    #[test]
+    fn parse_flags() {
+        let parsed = FacetArgs::parse(args(
+            "oct.off --group chiral --edge-length 1.5 --noble 1 --max-tuples 100 --out out --format json --precision 6 --exact --db survey.db",
+        ))
+        .unwrap();
+        assert_eq!(parsed.group, GroupArg::Chiral);
+        assert_eq!(parsed.edge_length, Some(1.5));
+        assert_eq!(parsed.noble, Some(1));
+        assert_eq!(parsed.max_tuples, Some(100));
+        assert_eq!(parsed.out, PathBuf::from("out"));
+        assert_eq!(parsed.format, OutputFormat::Json);
+        assert_eq!(parsed.precision, Some(6));
+        assert!(parsed.exact);
+        assert_eq!(parsed.db, Some(PathBuf::from("survey.db")));
+
+        let options = parsed.options(Default::default());
+        assert_eq!(options.edge_length, EdgeLengthFilter::Single(1.5));
+        assert_eq!(options.abort_if_estimated_tuples_exceed, Some(100));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(FacetArgs::parse(args("")).is_err());
+        assert!(FacetArgs::parse(args("a.off b.off")).is_err());
+        assert!(FacetArgs::parse(args("a.off --noble")).is_err());
+        assert!(FacetArgs::parse(args("a.off --noble x")).is_err());
+        assert!(FacetArgs::parse(args("a.off --format ggb")).is_err());
+        assert!(FacetArgs::parse(args("a.off --precision -1")).is_err());
+        assert!(FacetArgs::parse(args("a.off --frobnicate")).is_err());
+    }
+
+    #[test]
+    fn group_file() {
+        let group = parse_group("# identity\n1 0\n0 1\n\n-1 0 0 -1", 2).unwrap();
+        assert_eq!(group.len(), 2);
+        assert_eq!(group[1], -Matrix::identity(2, 2));
+        assert!(parse_group("1 0 0", 2).is_err());
+        assert!(parse_group("", 2).is_err());
+    }
+
+    #[test]
+    fn progress() {
+        assert_eq!(
+            progress_bar(FacetingProgress::HyperplaneFacets { done: 3, total: 4 }),
+            "Faceting hyperplanes [###############     ] 3/4"
+        );
+        assert_eq!(
+            progress_bar(FacetingProgress::Building { done: 0, total: 0 }),
+            "Building facetings [####################] 0/0"
+        );
+        assert_eq!(
+            progress_bar(FacetingProgress::Combining { found: 7 }),
+            "Combining facets: 7 facetings found"
+        );
+    }
+}
@@ -0,0 +1,427 @@
+//! Clips polytopes by half-spaces, and slices them by a fundamental domain of
+//! a symmetry group, so that only a single copy of their structure is left.
+//!
+//! Clipping works element by element, like a cross-section does. Every element
+//! inside the half-space is kept, every element outside of it is removed, and
+//! every element crossing its wall is cut down to its part inside, which gets a
+//! new subelement on the wall: the section of the element by the wall. This is
+//! exact for polytopes whose elements are convex. Otherwise, the sections of
+//! polygons are split into edges like in a cross-section, but the sections of
+//! higher elements are taken as a single element each.
+
+use std::collections::HashMap;
+
+use super::{Concrete, ConcretePolytope};
+use crate::{
+    abs::{AbstractBuilder, Ranked, SubelementList, Subelements},
+    float::Float,
+    geometry::{Hyperplane, Matrix, Point, PointOrd},
+    group::Group,
+    Polytope,
+};
+
+use vec_like::*;
+
+/// Where an element of a clipped polytope came from.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ElementOrigin {
+    /// The rank of the original element.
+    pub rank: usize,
+
+    /// The index of the original element.
+    pub idx: usize,
+
+    /// The indices of the walls that cut the original element down to this
+    /// one, in the order they were clipped by. Each of them lowers the rank by
+    /// one, so this is empty for the elements that are the original element or
+    /// its part inside the region.
+    pub walls: Vec<usize>,
+}
+
+impl ElementOrigin {
+    /// Returns the origin of the section of an element with this origin by a
+    /// wall.
+    fn on_wall(&self, wall: usize) -> Self {
+        let mut walls = self.walls.clone();
+        walls.push(wall);
+
+        Self {
+            rank: self.rank,
+            idx: self.idx,
+            walls,
+        }
+    }
+}
+
+/// A polytope clipped by some half-spaces, together with the origin of each of
+/// its elements in the polytope before clipping.
+#[derive(Clone, Debug)]
+pub struct ClippedPolytope {
+    /// The clipped polytope.
+    pub polytope: Concrete,
+
+    /// The origin of each element of the clipped polytope, by rank.
+    pub origins: Vec<Vec<ElementOrigin>>,
+}
+
+impl ClippedPolytope {
+    /// Wraps a polytope that hasn't been clipped yet, whose elements are their
+    /// own origins.
+    pub fn new(polytope: Concrete) -> Self {
+        let origins = (0..=polytope.rank())
+            .map(|rank| {
+                (0..polytope.el_count(rank))
+                    .map(|idx| ElementOrigin {
+                        rank,
+                        idx,
+                        walls: Vec::new(),
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self { polytope, origins }
+    }
+
+    /// Returns the clipped polytope with nothing left, keeping the origin of
+    /// the minimal element.
+    fn nullitope(&self) -> Self {
+        Self {
+            polytope: Concrete::nullitope(),
+            origins: vec![vec![self.origins[0][0].clone()]],
+        }
+    }
+
+    /// Clips the polytope by the half-space on the positive side of a
+    /// hyperplane. The new elements on the hyperplane are
+    /// recorded as lying on the wall with the given index.
+    ///
+    /// Vertices within [`Float::EPS`] of the hyperplane count as lying on it.
+    /// These are kept only if some element they're in crosses into the
+    /// half-space.
+    pub fn clip(&self, wall: &Hyperplane<f64>, wall_idx: usize) -> Self {
+        let poly = &self.polytope;
+        let rank = poly.rank();
+        let distances: Vec<_> = poly.vertices.iter().map(|v| wall.distance(v)).collect();
+
+        if rank < 2 {
+            return if distances.iter().all(|&d| d > f64::EPS) {
+                self.clone()
+            } else {
+                self.nullitope()
+            };
+        }
+
+        let mut vertices = Vec::new();
+        let mut ranks = vec![SubelementList::min(), SubelementList::new()];
+        let mut origins = vec![vec![self.origins[0][0].clone()], Vec::new()];
+
+        // The index of the part of each element inside the half-space, if
+        // any, and whether that's the whole element.
+        let mut parts = vec![Vec::new(), Vec::new()];
+        let mut whole = vec![Vec::new(), Vec::new()];
+
+        for (idx, &distance) in distances.iter().enumerate() {
+            if distance > f64::EPS {
+                parts[1].push(Some(vertices.len()));
+                vertices.push(poly.vertices[idx].clone());
+                ranks[1].push(vec![0].into());
+                origins[1].push(self.origins[1][idx].clone());
+            } else {
+                parts[1].push(None);
+            }
+            whole[1].push(distance > f64::EPS);
+        }
+
+        // The sections of the elements of the previous rank by the wall. The
+        // vertices of the original polytope on the wall stand in for the
+        // sections of the edges through them.
+        let mut sections: Vec<Vec<usize>> = Vec::new();
+        let mut wall_vertices = HashMap::new();
+
+        ranks.push(SubelementList::new());
+        origins.push(Vec::new());
+        let mut edge_parts = Vec::new();
+        let mut edge_whole = Vec::new();
+
+        for (idx, edge) in poly[2].iter().enumerate() {
+            let (v0, v1) = (edge.subs[0], edge.subs[1]);
+            let (inside, outside) = match (parts[1][v0], parts[1][v1]) {
+                (Some(p0), Some(p1)) => {
+                    edge_parts.push(Some(ranks[2].len()));
+                    edge_whole.push(true);
+                    sections.push(Vec::new());
+                    ranks[2].push(vec![p0, p1].into());
+                    origins[2].push(self.origins[2][idx].clone());
+                    continue;
+                }
+                (Some(_), None) => (v0, v1),
+                (None, Some(_)) => (v1, v0),
+                (None, None) => {
+                    edge_parts.push(None);
+                    edge_whole.push(false);
+                    sections.push(Vec::new());
+                    continue;
+                }
+            };
+
+            let section = if distances[outside] > -f64::EPS {
+                *wall_vertices.entry(outside).or_insert_with(|| {
+                    vertices.push(poly.vertices[outside].clone());
+                    ranks[1].push(vec![0].into());
+                    origins[1].push(self.origins[1][outside].clone());
+                    vertices.len() - 1
+                })
+            } else {
+                let (d0, d1) = (distances[inside], distances[outside]);
+                let (p0, p1) = (&poly.vertices[inside], &poly.vertices[outside]);
+                vertices.push(p0 + (p1 - p0) * (d0 / (d0 - d1)));
+                ranks[1].push(vec![0].into());
+                origins[1].push(self.origins[2][idx].on_wall(wall_idx));
+                vertices.len() - 1
+            };
+
+            edge_parts.push(Some(ranks[2].len()));
+            edge_whole.push(false);
+            sections.push(vec![section]);
+            ranks[2].push(vec![parts[1][inside].unwrap(), section].into());
+            origins[2].push(self.origins[2][idx].clone());
+        }
+
+        parts.push(edge_parts);
+        whole.push(edge_whole);
+
+        for r in 3..=rank {
+            let mut new_parts = Vec::new();
+            let mut new_whole = Vec::new();
+            let mut new_sections = Vec::new();
+            ranks.push(SubelementList::new());
+            origins.push(Vec::new());
+
+            // The sections already found, so that elements sharing one share
+            // the same element.
+            let mut section_idx = HashMap::new();
+
+            for (idx, el) in poly[r].iter().enumerate() {
+                if el.subs.iter().all(|&sub| parts[r - 1][sub].is_none()) {
+                    new_parts.push(None);
+                    new_whole.push(false);
+                    new_sections.push(Vec::new());
+                    continue;
+                }
+
+                let is_whole = el.subs.iter().all(|&sub| whole[r - 1][sub]);
+                let mut subs: Subelements = el.subs.iter().filter_map(|&sub| parts[r - 1][sub]).collect();
+                let mut el_sections = Vec::new();
+
+                if !is_whole {
+                    let mut pieces: Vec<_> = el.subs.iter().flat_map(|&sub| sections[sub].iter().copied()).collect();
+                    pieces.sort_unstable();
+                    pieces.dedup();
+
+                    // The section of a polygon is split into edges between
+                    // consecutive points along the wall.
+                    let section_subs = if r == 3 {
+                        pieces.sort_unstable_by_key(|&v| PointOrd::new(vertices[v].clone()));
+                        pieces.chunks_exact(2).map(|pair| pair.to_vec()).collect()
+                    } else if pieces.len() >= r - 1 {
+                        vec![pieces]
+                    } else {
+                        Vec::new()
+                    };
+
+                    for mut section in section_subs {
+                        section.sort_unstable();
+                        let section = *section_idx.entry(section.clone()).or_insert_with(|| {
+                            ranks[r - 1].push(section.into_iter().collect());
+                            origins[r - 1].push(self.origins[r][idx].on_wall(wall_idx));
+                            ranks[r - 1].len() - 1
+                        });
+                        el_sections.push(section);
+                    }
+                }
+
+                subs.extend(el_sections.iter().copied());
+                new_parts.push(Some(ranks[r].len()));
+                new_whole.push(is_whole);
+                new_sections.push(el_sections);
+                ranks[r].push(subs);
+                origins[r].push(self.origins[r][idx].clone());
+            }
+
+            parts.push(new_parts);
+            whole.push(new_whole);
+            sections = new_sections;
+        }
+
+        if parts[rank][0].is_none() {
+            return self.nullitope();
+        }
+
+        let mut builder = AbstractBuilder::new();
+        for subelements in ranks {
+            builder.push(subelements);
+        }
+
+        // Safety: every element is built from the parts of the subelements
+        // of an element and its sections, which are consistent for convex
+        // elements.
+        let polytope = Concrete::new(vertices, unsafe { builder.build() }).with_meta(poly.meta.clone());
+        Self { polytope, origins }
+    }
+}
+
+/// Returns a point in no special position, which no symmetry other than the
+/// identity should fix. Its coordinates are the cube roots of the first
+/// primes, which satisfy no linear relation over the quadratic fields that the
+/// coordinates of roots of reflections usually lie in.
+fn generic_point(dim: usize) -> Point<f64> {
+    let primes = (2..).filter(|&n: &u32| (2..n).all(|d| n % d != 0));
+    Point::from_iterator(dim, primes.take(dim).map(|p| f64::from(p).cbrt()))
+}
+
+/// Returns the walls of a fundamental domain of a group of isometries fixing
+/// the origin, whose positive sides intersect in the domain.
+///
+/// If the group is generated by reflections, these are the mirrors of its
+/// simple roots, which bound its dominant Weyl chamber. Otherwise, this is the
+/// Dirichlet domain of a generic point: the points closer to it than to any of
+/// its images, bounded by the hyperplanes halfway between them.
+pub fn fundamental_domain_walls<I>(group: &Group<I>, dim: usize) -> Vec<Hyperplane<f64>>
+where
+    I: Iterator<Item = Matrix<f64>> + Clone,
+{
+    let point = generic_point(dim);
+    let simple_roots = group.simple_roots();
+
+    // The Weyl chamber is a fundamental domain exactly when a generic point
+    // has a single image in it.
+    if !simple_roots.is_empty() {
+        let images_inside = group
+            .clone()
+            .filter(|el| {
+                let image = el * &point;
+                simple_roots.iter().all(|root| image.dot(root) > f64::EPS)
+            })
+            .count();
+
+        if images_inside == 1 {
+            return simple_roots.into_iter().map(|root| Hyperplane::new(root, 0.0)).collect();
+        }
+    }
+
+    group
+        .clone()
+        .filter_map(|el| {
+            let offset = &point - el * &point;
+            (offset.norm() > f64::EPS).then(|| Hyperplane::new(offset.normalize(), 0.0))
+        })
+        .collect()
+}
+
+impl Concrete {
+    /// Clips the polytope by the intersection of the half-spaces on the
+    /// positive sides of some hyperplanes, by [`ClippedPolytope::clip`]. The
+    /// walls are indexed in the order given.
+    pub fn clip(&self, walls: &[Hyperplane<f64>]) -> ClippedPolytope {
+        let mut clipped = walls
+            .iter()
+            .enumerate()
+            .fold(ClippedPolytope::new(self.clone()), |clipped, (idx, wall)| clipped.clip(wall, idx));
+        clipped.polytope.meta = self.meta.derive("clipping");
+        clipped
+    }
+
+    /// Clips the polytope by a fundamental domain of a group of its
+    /// symmetries, given by [`fundamental_domain_walls`], so that only one
+    /// copy of it under the group is left. The group should fix the origin,
+    /// as the symmetry groups of polytopes centered there do.
+    ///
+    /// This is mostly useful to show the structure of a polytope with many
+    /// elements, such as a faceting, whose elements cover each other.
+    pub fn fundamental_slice<I>(&self, group: &Group<I>) -> ClippedPolytope
+    where
+        I: Iterator<Item = Matrix<f64>> + Clone,
+    {
+        let dim = self.dim().unwrap_or(0);
+        let mut slice = self.clip(&fundamental_domain_walls(group, dim));
+        slice.polytope.meta = self.meta.derive("fundamental slice");
+        slice
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{file::FromFile, geometry::Vector};
+
+    use approx::assert_abs_diff_eq;
+
+    /// Every element's origin has as many walls as the rank it lost.
+    fn assert_origins(clipped: &ClippedPolytope) {
+        for (rank, origins) in clipped.origins.iter().enumerate() {
+            assert_eq!(origins.len(), clipped.polytope.el_count(rank));
+            for origin in origins {
+                assert_eq!(origin.rank, rank + origin.walls.len());
+            }
+        }
+    }
+
+    /// Cutting a cube in half through its center gives a cuboid, with a new
+    /// face on the wall.
+    #[test]
+    fn half_cube() {
+        let cube = Concrete::hypercube(4);
+        let wall = Hyperplane::new(Vector::from_vec(vec![1.0, 0.0, 0.0]), 0.0);
+        let mut half = cube.clip(&[wall]);
+
+        assert_origins(&half);
+        assert_eq!(
+            (0..=4).map(|r| half.polytope.el_count(r)).collect::<Vec<_>>(),
+            vec![1, 8, 12, 6, 1]
+        );
+        assert_eq!(half.origins[3].iter().filter(|origin| !origin.walls.is_empty()).count(), 1);
+        assert_abs_diff_eq!(half.polytope.volume_mut().unwrap(), 0.5, epsilon = 1e-9);
+    }
+
+    /// Cutting a cube through a face keeps the face, and clipping it away
+    /// leaves nothing.
+    #[test]
+    fn through_face() {
+        let cube = Concrete::hypercube(4);
+        let wall = Hyperplane::new(Vector::from_vec(vec![1.0, 0.0, 0.0]), -0.5);
+        let mut same = cube.clip(&[wall]);
+        assert_eq!(same.polytope.el_count(3), 6);
+        assert_abs_diff_eq!(same.polytope.volume_mut().unwrap(), 1.0, epsilon = 1e-9);
+
+        let wall = Hyperplane::new(Vector::from_vec(vec![-1.0, 0.0, 0.0]), 0.5);
+        assert_eq!(cube.clip(&[wall]).polytope.rank(), 0);
+    }
+
+    /// A chamber of the symmetry group of the 24-cell, which is generated by
+    /// reflections, holds 1/1152 of its volume.
+    #[test]
+    fn icositetrachoron_chamber() {
+        let mut ico = Concrete::from_off(include_str!("../../tests/fixtures/ico.off")).unwrap();
+        let volume = ico.volume_mut().unwrap();
+        let group = ico.get_symmetry_group().unwrap().0;
+        assert_eq!(fundamental_domain_walls(&group, 4).len(), 4);
+
+        let mut slice = ico.fundamental_slice(&group);
+        assert_origins(&slice);
+        assert_eq!(slice.polytope.rank(), 5);
+        assert_abs_diff_eq!(slice.polytope.volume_mut().unwrap(), volume / 1152.0, epsilon = 1e-9);
+    }
+
+    /// The rotation group of the cube has no reflections, so the cube is
+    /// clipped by a Dirichlet domain instead, which holds 1/24 of it.
+    #[test]
+    fn cube_rotations() {
+        let mut cube = Concrete::hypercube(4);
+        let group = cube.get_symmetry_group().unwrap().0.rotations().cache();
+
+        let mut slice = cube.fundamental_slice(&group);
+        assert_origins(&slice);
+        assert_abs_diff_eq!(slice.polytope.volume_mut().unwrap(), 1.0 / 24.0, epsilon = 1e-9);
+    }
+}
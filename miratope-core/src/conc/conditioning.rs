@@ -0,0 +1,402 @@
+//! Looks for the numerical hazards in a vertex arrangement that make faceting
+//! give wrong results.
+//!
+//! Faceting compares distances and checks whether vertices lie on hyperplanes
+//! up to a tolerance. Values that differ by less than the tolerance are taken
+//! to be equal, while values that differ by barely more are taken to be
+//! distinct. The first kind of hazard merges things that should be separate.
+//! The second splits things that should be together as soon as the
+//! coordinates lose a bit of precision. Either way, faceting gives nonsense.
+
+use std::fmt::Display;
+
+use super::Concrete;
+use crate::geometry::{Point, Subspace};
+
+/// How many times the tolerance two vertices must be apart so that they're
+/// not reported as too close.
+const CLOSE_FACTOR: f64 = 10.0;
+
+/// How many times the tolerance a separation must be for the arrangement to
+/// be rated as [`Conditioning::Good`].
+const MARGIN_FACTOR: f64 = 100.0;
+
+/// Differences this small, relative to the size of the arrangement, are taken
+/// to be rounding errors of values that are actually equal.
+const NOISE: f64 = 1e-12;
+
+/// The most hyperplanes through vertices that are checked. If there are more
+/// of them, this many are sampled instead.
+const PLANE_SAMPLES: usize = 256;
+
+/// How well-behaved an arrangement is numerically.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Conditioning {
+    /// Every separation is well above the tolerance.
+    Good,
+
+    /// Some separation is above the tolerance, but not by much. The results
+    /// should be right, but a slightly less precise input might change them.
+    Marginal,
+
+    /// Some values are merged by the tolerance even though they're distinct,
+    /// or some vertices are almost on top of each other. Expect wrong results.
+    Unreliable,
+}
+
+impl Display for Conditioning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Good => "good",
+            Self::Marginal => "marginal",
+            Self::Unreliable => "expect wrong results",
+        })
+    }
+}
+
+/// The smallest gap between two distinct distances between vertices.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DistanceGap {
+    /// The difference between both distances.
+    pub gap: f64,
+
+    /// The pairs of vertices at either distance.
+    pub pairs: [(usize, usize); 2],
+}
+
+/// The smallest distance from a vertex to a hyperplane through other vertices
+/// that it isn't on.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlaneDistance {
+    /// The distance from the vertex to the hyperplane.
+    pub distance: f64,
+
+    /// The vertices spanning the hyperplane.
+    pub plane: Vec<usize>,
+
+    /// The vertex off the hyperplane.
+    pub vertex: usize,
+}
+
+/// The numerical hazards of a vertex arrangement, as returned by
+/// [`Concrete::conditioning_report`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConditioningReport {
+    /// The rating of the arrangement, from its worst hazard.
+    pub rating: Conditioning,
+
+    /// The tolerance the hazards were measured against.
+    pub tolerance: f64,
+
+    /// The smallest gap between distinct distances between vertices, if there
+    /// are at least two of them.
+    pub distance_gap: Option<DistanceGap>,
+
+    /// The smallest distance from a vertex to a hyperplane through other
+    /// vertices that it isn't on, over the hyperplanes checked.
+    pub plane_distance: Option<PlaneDistance>,
+
+    /// The number of hyperplanes through vertices that were checked.
+    pub planes_checked: usize,
+
+    /// The pairs of vertices closer than 10 times the tolerance.
+    pub close_pairs: Vec<(usize, usize)>,
+}
+
+impl ConditioningReport {
+    /// Returns the vertices involved in the hazards that lowered the rating,
+    /// in increasing order.
+    pub fn offending_vertices(&self) -> Vec<usize> {
+        let margin = MARGIN_FACTOR * self.tolerance;
+        let mut vertices = Vec::new();
+
+        for &(i, j) in &self.close_pairs {
+            vertices.extend([i, j]);
+        }
+        if let Some(gap) = &self.distance_gap {
+            if gap.gap < margin {
+                for &(i, j) in &gap.pairs {
+                    vertices.extend([i, j]);
+                }
+            }
+        }
+        if let Some(plane) = &self.plane_distance {
+            if plane.distance < margin {
+                vertices.extend(plane.plane.iter().copied());
+                vertices.push(plane.vertex);
+            }
+        }
+
+        vertices.sort_unstable();
+        vertices.dedup();
+        vertices
+    }
+}
+
+impl Display for ConditioningReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "conditioning: {} (tolerance {:e})", self.rating, self.tolerance)?;
+
+        if let Some(gap) = &self.distance_gap {
+            let [(a, b), (c, d)] = gap.pairs;
+            write!(
+                f,
+                "\n  smallest gap between distances: {:e} (vertices {}-{} and {}-{})",
+                gap.gap, a, b, c, d
+            )?;
+        }
+        if let Some(plane) = &self.plane_distance {
+            write!(
+                f,
+                "\n  smallest distance to a hyperplane: {:e} (vertex {} off {:?}, {} hyperplanes checked)",
+                plane.distance, plane.vertex, plane.plane, self.planes_checked
+            )?;
+        }
+        if !self.close_pairs.is_empty() {
+            write!(f, "\n  {} pairs of vertices too close: {:?}", self.close_pairs.len(), self.close_pairs)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Rates a separation against the tolerance.
+fn rate(separation: f64, tolerance: f64) -> Conditioning {
+    if separation <= tolerance {
+        Conditioning::Unreliable
+    } else if separation < MARGIN_FACTOR * tolerance {
+        Conditioning::Marginal
+    } else {
+        Conditioning::Good
+    }
+}
+
+/// Returns the number of ways to choose `k` things out of `n`, or `None` if
+/// it's more than some limit.
+fn binomial_up_to(n: usize, k: usize, limit: usize) -> Option<usize> {
+    let mut count = 1usize;
+    for i in 0..k.min(n) {
+        count = count.checked_mul(n - i)? / (i + 1);
+        if count > limit {
+            return None;
+        }
+    }
+
+    if k > n {
+        Some(0)
+    } else {
+        Some(count)
+    }
+}
+
+/// Advances a strictly increasing list of indices below `n` to the next one
+/// in lexicographic order. Returns `false` if it was the last one.
+fn next_combination(indices: &mut [usize], n: usize) -> bool {
+    let k = indices.len();
+    for i in (0..k).rev() {
+        if indices[i] < n - k + i {
+            indices[i] += 1;
+            for j in i + 1..k {
+                indices[j] = indices[j - 1] + 1;
+            }
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Returns the lists of vertices that span the hyperplanes to be checked.
+/// These are all of them if there are few enough, and a fixed sample of them
+/// otherwise, so that the report is the same on every run.
+fn plane_samples(vertex_count: usize, dim: usize) -> Vec<Vec<usize>> {
+    if dim == 0 || vertex_count < dim {
+        return Vec::new();
+    }
+
+    if binomial_up_to(vertex_count, dim, PLANE_SAMPLES).is_some() {
+        let mut samples = Vec::new();
+        let mut indices: Vec<usize> = (0..dim).collect();
+        loop {
+            samples.push(indices.clone());
+            if !next_combination(&mut indices, vertex_count) {
+                return samples;
+            }
+        }
+    }
+
+    // A linear congruential generator, so that the samples are the same on
+    // every run.
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = || {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        ((state >> 33) % vertex_count as u64) as usize
+    };
+
+    (0..PLANE_SAMPLES)
+        .map(|_| {
+            let mut indices = Vec::with_capacity(dim);
+            while indices.len() < dim {
+                let idx = next();
+                if !indices.contains(&idx) {
+                    indices.push(idx);
+                }
+            }
+            indices.sort_unstable();
+            indices
+        })
+        .collect()
+}
+
+/// Looks for the numerical hazards in some vertices. See
+/// [`Concrete::conditioning_report`].
+pub fn conditioning_report(vertices: &[Point<f64>], tolerance: f64) -> ConditioningReport {
+    let mut distances = Vec::new();
+    for i in 0..vertices.len() {
+        for j in i + 1..vertices.len() {
+            distances.push(((&vertices[i] - &vertices[j]).norm(), (i, j)));
+        }
+    }
+    distances.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap());
+
+    let diameter = distances.last().map_or(0.0, |&(d, _)| d);
+    let noise = NOISE * diameter.max(1.0);
+    let mut rating = Conditioning::Good;
+
+    // Pairs of vertices that are almost on top of each other.
+    let close_pairs: Vec<_> = distances
+        .iter()
+        .take_while(|&&(d, _)| d < CLOSE_FACTOR * tolerance)
+        .map(|&(_, pair)| pair)
+        .collect();
+    if !close_pairs.is_empty() {
+        rating = Conditioning::Unreliable;
+    }
+
+    // Distances that are almost, but not quite, equal.
+    let mut distance_gap: Option<DistanceGap> = None;
+    for pair in distances.windows(2) {
+        let gap = pair[1].0 - pair[0].0;
+        if gap > noise && !matches!(distance_gap, Some(min) if min.gap <= gap) {
+            distance_gap = Some(DistanceGap {
+                gap,
+                pairs: [pair[0].1, pair[1].1],
+            });
+        }
+    }
+    if let Some(gap) = &distance_gap {
+        rating = rating.max(rate(gap.gap, tolerance));
+    }
+
+    // Vertices that are almost, but not quite, on a hyperplane.
+    let dim = vertices.first().map_or(0, |v| v.len());
+    let mut plane_distance: Option<PlaneDistance> = None;
+    let mut planes_checked = 0;
+    for plane in plane_samples(vertices.len(), dim) {
+        let mut subspace = Subspace::new(vertices[plane[0]].clone());
+        for &idx in &plane[1..] {
+            subspace.add(&vertices[idx]);
+        }
+        if !subspace.is_hyperplane() {
+            continue;
+        }
+
+        planes_checked += 1;
+        for (vertex, v) in vertices.iter().enumerate() {
+            let distance = subspace.distance(v);
+            if distance > noise && !matches!(&plane_distance, Some(min) if min.distance <= distance) {
+                plane_distance = Some(PlaneDistance {
+                    distance,
+                    plane: plane.clone(),
+                    vertex,
+                });
+            }
+        }
+    }
+    if let Some(plane) = &plane_distance {
+        rating = rating.max(rate(plane.distance, tolerance));
+    }
+
+    ConditioningReport {
+        rating,
+        tolerance,
+        distance_gap,
+        plane_distance,
+        planes_checked,
+        close_pairs,
+    }
+}
+
+impl Concrete {
+    /// Scans the vertices for the numerical hazards that make faceting give
+    /// wrong results, and rates them. These are distances that are almost but
+    /// not quite equal, vertices that are almost but not quite on a hyperplane
+    /// through others, and vertices that are almost on top of each other.
+    ///
+    /// Every hyperplane through vertices is checked when there are at most
+    /// 256 of them, and a fixed sample of 256 of them otherwise.
+    pub fn conditioning_report(&self, tolerance: f64) -> ConditioningReport {
+        conditioning_report(&self.vertices, tolerance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{float::Float, Polytope};
+
+    /// A cube with one of its vertices moved out by some amount.
+    fn perturbed_cube(amount: f64) -> Concrete {
+        let mut cube = Concrete::hypercube(4);
+        cube.vertices[0] *= 1.0 + amount;
+        cube
+    }
+
+    /// A cube has nothing to worry about.
+    #[test]
+    fn cube() {
+        let report = Concrete::hypercube(4).conditioning_report(f64::EPS);
+        assert_eq!(report.rating, Conditioning::Good);
+        assert_eq!(report.planes_checked, 56);
+        assert!(report.close_pairs.is_empty());
+        assert!(report.offending_vertices().is_empty());
+    }
+
+    /// A vertex barely off the planes of a cube is found.
+    #[test]
+    fn nearly_coplanar() {
+        let report = perturbed_cube(1e-6).conditioning_report(f64::EPS);
+        assert_eq!(report.rating, Conditioning::Marginal);
+        assert!(report.offending_vertices().contains(&0));
+
+        let report = perturbed_cube(5e-8).conditioning_report(f64::EPS);
+        assert_eq!(report.rating, Conditioning::Unreliable);
+        assert!(report.offending_vertices().contains(&0));
+    }
+
+    /// Vertices almost on top of each other are reported.
+    #[test]
+    fn close_pair() {
+        let mut cube = Concrete::hypercube(4);
+        let mut vertex = cube.vertices[3].clone();
+        vertex[0] += 3.0 * f64::EPS;
+        cube.vertices.push(vertex);
+
+        let report = cube.conditioning_report(f64::EPS);
+        assert_eq!(report.rating, Conditioning::Unreliable);
+        assert_eq!(report.close_pairs, vec![(3, 8)]);
+        assert!(report.offending_vertices().contains(&8));
+    }
+
+    /// Large arrangements are sampled, the same way every time.
+    #[test]
+    fn sampled() {
+        let poly = Concrete::hypercube(6);
+        let report = poly.conditioning_report(f64::EPS);
+        assert_eq!(report.rating, Conditioning::Good);
+        assert!(report.planes_checked <= PLANE_SAMPLES);
+        assert_eq!(report, poly.conditioning_report(f64::EPS));
+    }
+}
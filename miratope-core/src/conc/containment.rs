@@ -0,0 +1,478 @@
+//! Tests whether points are inside a polytope, and samples points inside it.
+//!
+//! Every flag of a polytope spans a simplex through a vertex of each of its
+//! elements, and the simplices of the flags through a facet, with the signs of
+//! their orientations, make up the facet. A point is located by casting a ray
+//! from it and adding up the signs of the simplices it goes through, which
+//! gives the winding number of the polytope around it. This is the same
+//! decomposition the volume is computed from, but used in a different way, so
+//! that one can be checked against the other.
+
+use super::{Concrete, ConcretePolytope};
+use crate::{
+    abs::{
+        flag::{FlagChanges, FlagEvent, OrientedFlagIter},
+        Ranked,
+    },
+    float::Float,
+    geometry::{Matrix, Point},
+    Polytope,
+};
+
+use std::collections::HashSet;
+
+/// The most rays cast from a point before giving up on avoiding the
+/// boundaries of the simplices, and taking the point to be on the polytope.
+const MAX_CASTS: usize = 32;
+
+/// How many points are tried per point asked for when sampling the interior,
+/// before giving up.
+const MAX_ATTEMPTS_PER_SAMPLE: usize = 1000;
+
+/// The multiplier of the linear congruential generator the rays and samples
+/// are drawn from.
+const LCG_MULTIPLIER: u64 = 6_364_136_223_846_793_005;
+
+/// The increment of the linear congruential generator.
+const LCG_INCREMENT: u64 = 1_442_695_040_888_963_407;
+
+/// The seed for the directions of the rays, so that every point is always
+/// located in the same way.
+const RAY_SEED: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// A deterministic stream of numbers in `[0, 1)`.
+struct Lcg(u64);
+
+impl Lcg {
+    /// Returns the next number.
+    fn next(&mut self) -> f64 {
+        self.0 = self.0.wrapping_mul(LCG_MULTIPLIER).wrapping_add(LCG_INCREMENT);
+        (self.0 >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns a unit vector in a given dimension.
+    fn direction(&mut self, dim: usize) -> Point<f64> {
+        loop {
+            let v: Point<f64> = (0..dim).map(|_| 2.0 * self.next() - 1.0).collect::<Vec<_>>().into();
+            let norm = v.norm();
+            if norm > 0.1 {
+                return v / norm;
+            }
+        }
+    }
+}
+
+/// Which points a polytope with overlapping parts is taken to fill.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FillRule {
+    /// The points the polytope winds around at least once, in either
+    /// direction. This fills the silhouette of a star polytope.
+    NonZero,
+
+    /// The points the polytope winds around an odd number of times. This
+    /// leaves out the regions of even density, like the core of a pentagram.
+    EvenOdd,
+}
+
+/// The simplex spanned by a flag.
+struct FlagSimplex {
+    /// The vertex of the flag's vertex.
+    base: Point<f64>,
+
+    /// The differences between the vertices of the other elements of the flag
+    /// and the base, as columns.
+    edges: Matrix<f64>,
+
+    /// The sign of the flag, relative to the orientation of its component.
+    /// This is garbage if the polytope isn't orientable.
+    sign: i64,
+}
+
+/// What a ray cast from a point went through.
+enum Cast {
+    /// The ray only went through the interiors of simplices, with these
+    /// signed and unsigned counts.
+    Crossings { winding: i64, count: usize },
+
+    /// The point is on one of the simplices.
+    Boundary,
+
+    /// The ray went through a lower-dimensional element of a simplex, or along
+    /// one, and must be cast again.
+    Degenerate,
+}
+
+/// Locates points with respect to a polytope. Building one decomposes the
+/// polytope into the simplices of its flags, so that many points can be
+/// located without doing it again.
+pub struct PointLocator {
+    /// The simplices of the flags that aren't degenerate.
+    simplices: Vec<FlagSimplex>,
+
+    /// The dimension of the polytope, which must also be that of the space
+    /// it's in for it to have an interior.
+    dim: usize,
+
+    /// Whether the polytope is orientable, so that its winding numbers are
+    /// defined.
+    orientable: bool,
+
+    /// The tolerance for distances, scaled to the size of the polytope.
+    tolerance: f64,
+
+    /// The tolerance for the determinants whose columns are a direction and
+    /// the edges of a simplex.
+    det_tolerance: f64,
+}
+
+impl PointLocator {
+    /// Decomposes a polytope into the simplices of its flags. Polytopes that
+    /// don't span the space they're in have no interior, and no point is
+    /// located inside of them.
+    pub fn new(poly: &Concrete) -> Self {
+        let rank = poly.rank();
+        let dim = poly.dim_or();
+        let mut locator = Self {
+            simplices: Vec::new(),
+            dim,
+            orientable: true,
+            tolerance: f64::EPS,
+            det_tolerance: f64::EPS,
+        };
+
+        if rank < 2 || dim + 1 != rank || poly.vertices.is_empty() {
+            return locator;
+        }
+
+        let mut sorted;
+        let poly = if poly.abs.sorted() {
+            poly
+        } else {
+            sorted = poly.clone();
+            sorted.element_sort();
+            &sorted
+        };
+
+        let scale = poly
+            .vertices
+            .iter()
+            .map(|v| (v - &poly.vertices[0]).norm())
+            .fold(1.0, f64::max);
+        locator.tolerance = f64::EPS * scale;
+        locator.det_tolerance = f64::EPS * scale.powi(dim as i32 - 1);
+
+        let vertex_map = poly.abs.vertex_map();
+        let mut all_flags = HashSet::new();
+
+        for flag in poly.flags() {
+            if all_flags.contains(&flag) {
+                continue;
+            }
+
+            // The simplices of this component, and its volume with the
+            // orientation of its first flag.
+            let mut component = Vec::new();
+            let mut volume = 0.0;
+
+            for flag_event in OrientedFlagIter::with_flags(&poly.abs, FlagChanges::all(rank), flag.into()) {
+                let oriented_flag = if let FlagEvent::Flag(oriented_flag) = flag_event {
+                    oriented_flag
+                } else {
+                    locator.orientable = false;
+                    continue;
+                };
+
+                all_flags.insert(oriented_flag.flag.clone());
+                let sign = oriented_flag.orientation.sign();
+                let vertices: Vec<_> = oriented_flag
+                    .into_iter()
+                    .enumerate()
+                    .skip(1)
+                    .take(rank - 1)
+                    .map(|(rank, idx)| vertex_map[(rank, idx)])
+                    .collect();
+
+                volume += sign
+                    * Matrix::from_iterator(
+                        dim,
+                        dim,
+                        vertices.iter().flat_map(|&v| poly.vertices[v].iter().copied()),
+                    )
+                    .determinant();
+
+                let base = poly.vertices[vertices[0]].clone();
+                let edges = Matrix::from_iterator(
+                    dim,
+                    dim - 1,
+                    vertices[1..].iter().flat_map(|&v| (&poly.vertices[v] - &base).iter().copied().collect::<Vec<_>>()),
+                );
+
+                // Skips the simplices without any content, which a ray can
+                // only go through along their boundaries.
+                let content = (edges.transpose() * &edges).determinant();
+                if dim == 1 || content.fabs() > (locator.tolerance * scale).powi(dim as i32 - 1) {
+                    component.push(FlagSimplex {
+                        base,
+                        edges,
+                        sign: sign as i64,
+                    });
+                }
+            }
+
+            // Winding numbers are taken to be positive in each component.
+            if volume < 0.0 {
+                for simplex in &mut component {
+                    simplex.sign = -simplex.sign;
+                }
+            }
+            locator.simplices.append(&mut component);
+        }
+
+        locator
+    }
+
+    /// Casts a ray from a point in a given direction.
+    fn cast(&self, p: &Point<f64>, direction: &Point<f64>) -> Cast {
+        let mut winding = 0;
+        let mut count = 0;
+        let eps = f64::EPS;
+
+        for simplex in &self.simplices {
+            // Solves for the point on the ray that's on the hyperplane of the
+            // simplex, in terms of the distance along the ray and the
+            // coordinates along the edges of the simplex.
+            let mut matrix = Matrix::zeros(self.dim, self.dim);
+            matrix.set_column(0, &(-direction));
+            matrix.columns_mut(1, self.dim - 1).copy_from(&simplex.edges);
+
+            let det = matrix.determinant();
+            let solution = if det.fabs() > self.det_tolerance {
+                matrix.lu().solve(&(p - &simplex.base))
+            } else {
+                None
+            };
+            let solution = match solution {
+                Some(solution) => solution,
+                None => return Cast::Degenerate,
+            };
+
+            let t = solution[0];
+            let coordinates = solution.rows(1, self.dim - 1);
+            let base_coordinate = 1.0 - coordinates.sum();
+            let min_coordinate = coordinates.iter().copied().fold(base_coordinate, f64::min);
+
+            if min_coordinate < -eps || t < -self.tolerance {
+                continue;
+            }
+            if t <= self.tolerance {
+                return Cast::Boundary;
+            }
+            if min_coordinate <= eps {
+                return Cast::Degenerate;
+            }
+
+            count += 1;
+            winding += if det > 0.0 { -simplex.sign } else { simplex.sign };
+        }
+
+        Cast::Crossings { winding, count }
+    }
+
+    /// Casts rays from a point until one avoids the boundaries of the
+    /// simplices. Returns `None` if the point is on the polytope.
+    fn locate(&self, p: &Point<f64>) -> Option<(i64, usize)> {
+        if p.len() != self.dim {
+            return Some((0, 0));
+        }
+
+        let mut rays = Lcg(RAY_SEED);
+        for _ in 0..MAX_CASTS {
+            match self.cast(p, &rays.direction(self.dim)) {
+                Cast::Crossings { winding, count } => return Some((winding, count)),
+                Cast::Boundary => return None,
+                Cast::Degenerate => {}
+            }
+        }
+
+        None
+    }
+
+    /// Returns the number of times the polytope winds around a point, counting
+    /// each of its components as positively oriented. Returns `None` if the
+    /// polytope isn't orientable, or if the point is on the polytope.
+    pub fn winding_number(&self, p: &Point<f64>) -> Option<i64> {
+        if !self.orientable {
+            return None;
+        }
+        self.locate(p).map(|(winding, _)| winding)
+    }
+
+    /// Returns whether a point is inside the polytope under a given fill rule.
+    /// The points on the polytope itself are taken to be inside. The winding
+    /// numbers of a non-orientable polytope are only defined up to parity, so
+    /// [`FillRule::NonZero`] is the same as [`FillRule::EvenOdd`] for them.
+    pub fn contains(&self, p: &Point<f64>, rule: FillRule) -> bool {
+        if self.simplices.is_empty() {
+            return false;
+        }
+
+        match self.locate(p) {
+            None => true,
+            Some((winding, count)) => match rule {
+                FillRule::NonZero if self.orientable => winding != 0,
+                _ => count % 2 == 1,
+            },
+        }
+    }
+}
+
+impl Concrete {
+    /// Returns whether a point is inside the polytope under a given fill rule.
+    /// See [`PointLocator::contains`]. To locate many points, build a
+    /// [`PointLocator`] once instead.
+    pub fn contains_point(&self, p: &Point<f64>, rule: FillRule) -> bool {
+        PointLocator::new(self).contains(p, rule)
+    }
+
+    /// Samples points inside the polytope under [`FillRule::NonZero`], by
+    /// rejection sampling in the bounding box of its vertices. The same seed
+    /// always gives the same points. Fewer than `n` points are returned if
+    /// too many of the points tried are outside, as happens when the polytope
+    /// has no interior.
+    pub fn sample_interior(&self, n: usize, seed: u64) -> Vec<Point<f64>> {
+        let locator = PointLocator::new(self);
+        let mut samples = Vec::with_capacity(n);
+        if locator.simplices.is_empty() {
+            return samples;
+        }
+
+        let dim = locator.dim;
+        let mut min = self.vertices[0].clone();
+        let mut max = self.vertices[0].clone();
+        for v in &self.vertices {
+            min = min.inf(v);
+            max = max.sup(v);
+        }
+
+        let mut rng = Lcg(seed);
+        for _ in 0..n.saturating_mul(MAX_ATTEMPTS_PER_SAMPLE) {
+            let p: Point<f64> = (0..dim)
+                .map(|i| min[i] + rng.next() * (max[i] - min[i]))
+                .collect::<Vec<_>>()
+                .into();
+
+            if locator.contains(&p, FillRule::NonZero) {
+                samples.push(p);
+                if samples.len() == n {
+                    break;
+                }
+            }
+        }
+
+        samples
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a point from its coordinates.
+    fn point(coordinates: &[f64]) -> Point<f64> {
+        coordinates.to_vec().into()
+    }
+
+    /// The cube contains the points with all coordinates below a half in
+    /// absolute value, including those on its boundary.
+    #[test]
+    fn cube() {
+        let cube = Concrete::hypercube(4);
+        let locator = PointLocator::new(&cube);
+
+        for rule in [FillRule::NonZero, FillRule::EvenOdd] {
+            assert!(locator.contains(&point(&[0.0, 0.0, 0.0]), rule));
+            assert!(locator.contains(&point(&[0.4, -0.3, 0.49]), rule));
+            assert!(!locator.contains(&point(&[0.6, 0.0, 0.0]), rule));
+            assert!(!locator.contains(&point(&[0.3, -0.7, 0.2]), rule));
+            assert!(locator.contains(&point(&[0.5, 0.5, 0.5]), rule));
+            assert!(locator.contains(&point(&[0.5, 0.0, 0.1]), rule));
+        }
+
+        assert_eq!(locator.winding_number(&point(&[0.1, 0.2, 0.3])), Some(1));
+        assert_eq!(locator.winding_number(&point(&[2.0, 0.2, 0.3])), Some(0));
+    }
+
+    /// The core of a pentagram is wound around twice, so only one of the fill
+    /// rules fills it, while both fill its points.
+    #[test]
+    fn pentagram() {
+        let pentagram = Concrete::star_polygon(5, 2);
+        let locator = PointLocator::new(&pentagram);
+        let center = point(&[0.01, 0.02]);
+        let tip = pentagram.vertices[0].clone() * 0.8;
+        let outside = pentagram.vertices[0].clone() * 1.1;
+
+        assert_eq!(locator.winding_number(&center), Some(2));
+        assert!(locator.contains(&center, FillRule::NonZero));
+        assert!(!locator.contains(&center, FillRule::EvenOdd));
+
+        assert_eq!(locator.winding_number(&tip), Some(1));
+        assert!(locator.contains(&tip, FillRule::NonZero));
+        assert!(locator.contains(&tip, FillRule::EvenOdd));
+
+        assert_eq!(locator.winding_number(&outside), Some(0));
+        assert!(!locator.contains(&outside, FillRule::NonZero));
+    }
+
+    /// The points sampled are inside, and the same seed gives the same
+    /// points.
+    #[test]
+    fn sample_interior() {
+        let cube = Concrete::hypercube(4);
+        let samples = cube.sample_interior(100, 7);
+
+        assert_eq!(samples.len(), 100);
+        assert!(samples.iter().all(|p| p.iter().all(|x| x.abs() <= 0.5)));
+        assert_eq!(samples, cube.sample_interior(100, 7));
+        assert!(Concrete::nullitope().sample_interior(10, 7).is_empty());
+    }
+
+    /// Averaging the winding numbers over points in the bounding box gives
+    /// back the volume, weighted by density.
+    #[test]
+    fn monte_carlo_volume() {
+        use crate::file::FromFile;
+
+        let polytopes = [
+            Concrete::star_polygon(5, 2),
+            Concrete::from_off(include_str!("../../tests/fixtures/ike.off")).unwrap(),
+        ];
+
+        for mut poly in polytopes {
+            let locator = PointLocator::new(&poly);
+            let dim = poly.dim_or();
+            let mut min = poly.vertices[0].clone();
+            let mut max = poly.vertices[0].clone();
+            for v in &poly.vertices {
+                min = min.inf(v);
+                max = max.sup(v);
+            }
+            let box_volume: f64 = (0..dim).map(|i| max[i] - min[i]).product();
+
+            let samples = 20000;
+            let mut rng = Lcg(1);
+            let mut winding = 0;
+            for _ in 0..samples {
+                let p: Point<f64> = (0..dim)
+                    .map(|i| min[i] + rng.next() * (max[i] - min[i]))
+                    .collect::<Vec<_>>()
+                    .into();
+                winding += locator.winding_number(&p).unwrap_or(0);
+            }
+
+            let estimate = winding as f64 / samples as f64 * box_volume;
+            let volume = poly.volume_mut().unwrap();
+            assert!((estimate - volume).abs() < 0.03 * volume, "{} vs {}", estimate, volume);
+        }
+    }
+}
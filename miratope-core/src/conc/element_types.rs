@@ -5,8 +5,7 @@ use std::{collections::BTreeMap, cmp::Ordering};
 use crate::{
     abs::{ElementMap, Ranked},
     conc::Concrete,
-    float::Float,
-    geometry::{Point, Subspace},
+    geometry::{GeomContext, Point, Subspace},
 };
 
 use ordered_float::OrderedFloat;
@@ -54,13 +53,13 @@ pub const EL_SUFFIXES: [&str; 25] = [
 ];
 
 impl Subspace<f64> {
-    fn distance_heuristic(&self, list: &mut Vec<f64>) -> f64 {
+    fn distance_heuristic(&self, list: &mut Vec<f64>, tolerance: f64) -> f64 {
         let dim = self.offset.len();
         let mut dist = self.distance(&Point::zeros(dim));
 
         match list.binary_search_by(|x| {
             let diff = x-&dist;
-            if diff.abs() < f64::EPS {Ordering::Equal}
+            if diff.abs() < tolerance {Ordering::Equal}
             else if diff > 0. {Ordering::Greater}
             else {Ordering::Less}
         }) {
@@ -90,6 +89,13 @@ impl Concrete {
     /// - iterate over ranks backwards, use superelements instead of subelements
     /// - get number of types in total, if it's the same as previous loop, stop
     pub fn element_types_common(&self) -> (Vec<Vec<ElementType>>, ElementMap<usize>) {
+        self.element_types_common_in(&GeomContext::default())
+    }
+
+    /// Finds the element types like [`Self::element_types_common`], comparing
+    /// the distances of the elements from the origin with the tolerance of a
+    /// geometry context.
+    pub fn element_types_common_in(&self, context: &GeomContext) -> (Vec<Vec<ElementType>>, ElementMap<usize>) {
         let rank = self.rank();
 
         // A nullitope has no proper elements.
@@ -112,7 +118,7 @@ impl Concrete {
 
         let mut type_count = rank-1;
 
-        let subspaces = self.element_map_affine_hulls();
+        let subspaces = self.element_map_affine_hulls_in(context);
         let mut distances = Vec::new();
 
         // To limit the number of passes, we can turn this into a `for` loop.
@@ -134,7 +140,7 @@ impl Concrete {
                     let type_data = TypeData {
                         prev_index: type_of_element[r][i],
                         type_counts: sub_type_counts,
-                        heuristics: OrderedFloat(subspaces[r-1][i].distance_heuristic(&mut distances)),
+                        heuristics: OrderedFloat(subspaces[r-1][i].distance_heuristic(&mut distances, context.tolerance)),
                     };
 
                     match dict.get(&type_data) {
@@ -177,7 +183,7 @@ impl Concrete {
                     let type_data = TypeData {
                         prev_index: type_of_element[r][i],
                         type_counts: sup_type_counts,
-                        heuristics: OrderedFloat(subspaces[r-1][i].distance_heuristic(&mut distances)),
+                        heuristics: OrderedFloat(subspaces[r-1][i].distance_heuristic(&mut distances, context.tolerance)),
                     };
 
                     match dict.get(&type_data) {
@@ -225,6 +231,7 @@ impl Concrete {
     }
 
     /// Prints all element types of a polytope into the console.
+    #[allow(clippy::print_stdout)]
     pub fn print_element_types(&self) {
         for (r, types) in self.element_types().into_iter().enumerate().skip(1) {
             if r == self.rank() {
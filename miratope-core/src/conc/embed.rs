@@ -0,0 +1,307 @@
+//! Recovers the points of a polytope from the inner products or distances
+//! between them, by classical multidimensional scaling.
+//!
+//! The Gram matrix of a list of points is the matrix of their inner products,
+//! which is symmetric and positive semidefinite. Conversely, any such matrix
+//! factors through its eigendecomposition as the Gram matrix of some points,
+//! which are unique up to an orthogonal transformation, and which span as
+//! many dimensions as the matrix has positive eigenvalues.
+
+use super::Concrete;
+use crate::{
+    abs::AbstractBuilder,
+    geometry::{Matrix, Point},
+};
+
+/// The relative size below which the eigenvalues of a Gram matrix are taken to
+/// be zero, and below which its asymmetries are ignored.
+pub const EMBED_TOLERANCE: f64 = 1e-8;
+
+/// Represents an error found while embedding a Gram matrix.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EmbedError {
+    /// The matrix has no entries, so there are no points.
+    Empty,
+
+    /// The matrix isn't square.
+    NotSquare {
+        /// The number of rows.
+        rows: usize,
+
+        /// The number of columns.
+        columns: usize,
+    },
+
+    /// The matrix isn't symmetric.
+    NotSymmetric {
+        /// The row of the entry that differs the most from its transpose.
+        row: usize,
+
+        /// The column of that entry.
+        column: usize,
+
+        /// The difference between the entry and its transpose.
+        difference: f64,
+    },
+
+    /// The matrix has a negative eigenvalue, so it isn't the Gram matrix of
+    /// any points.
+    NotPositiveSemidefinite {
+        /// The most negative eigenvalue.
+        eigenvalue: f64,
+    },
+}
+
+impl std::fmt::Display for EmbedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "the matrix is empty"),
+            Self::NotSquare { rows, columns } => {
+                write!(f, "the matrix has {} rows but {} columns", rows, columns)
+            }
+            Self::NotSymmetric {
+                row,
+                column,
+                difference,
+            } => write!(
+                f,
+                "entry ({}, {}) differs from its transpose by {}",
+                row, column, difference
+            ),
+            Self::NotPositiveSemidefinite { eigenvalue } => {
+                write!(f, "the matrix has a negative eigenvalue {}", eigenvalue)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EmbedError {}
+
+/// The points recovered from a Gram matrix.
+#[derive(Clone, Debug)]
+pub struct Embedding {
+    /// The points, in the order of the rows of the matrix.
+    pub points: Vec<Point<f64>>,
+
+    /// The dimension of the space the points span, which is the number of
+    /// eigenvalues that weren't taken to be zero.
+    pub dim: usize,
+
+    /// The Frobenius norm of the difference between the Gram matrix and that
+    /// of the points, which comes from the eigenvalues taken to be zero.
+    pub residual: f64,
+}
+
+impl Embedding {
+    /// Finds points whose Gram matrix is the given one, in as few dimensions
+    /// as possible. Their coordinates are along the eigenvectors of the
+    /// matrix, sorted by decreasing eigenvalue.
+    ///
+    /// Eigenvalues and asymmetries up to [`EMBED_TOLERANCE`] times the
+    /// largest eigenvalue are ignored, so that the matrix only needs to be
+    /// approximately positive semidefinite.
+    pub fn from_gram_matrix(gram: &Matrix<f64>) -> Result<Self, EmbedError> {
+        let (rows, columns) = gram.shape();
+        if rows != columns {
+            return Err(EmbedError::NotSquare { rows, columns });
+        }
+        if rows == 0 {
+            return Err(EmbedError::Empty);
+        }
+
+        let scale = gram.amax().max(f64::MIN_POSITIVE);
+        let mut asymmetry = (0, 0, 0.0_f64);
+        for row in 0..rows {
+            for column in 0..row {
+                let difference = gram[(row, column)] - gram[(column, row)];
+                if difference.abs() > asymmetry.2.abs() {
+                    asymmetry = (row, column, difference);
+                }
+            }
+        }
+        if asymmetry.2.abs() > EMBED_TOLERANCE * scale {
+            let (row, column, difference) = asymmetry;
+            return Err(EmbedError::NotSymmetric {
+                row,
+                column,
+                difference,
+            });
+        }
+
+        let eigen = ((gram + gram.transpose()) / 2.0).symmetric_eigen();
+        let mut order: Vec<_> = (0..rows).collect();
+        order.sort_unstable_by(|&i, &j| eigen.eigenvalues[j].partial_cmp(&eigen.eigenvalues[i]).unwrap());
+
+        let threshold = EMBED_TOLERANCE * eigen.eigenvalues[order[0]].max(f64::MIN_POSITIVE);
+        let smallest = eigen.eigenvalues[order[rows - 1]];
+        if smallest < -threshold {
+            return Err(EmbedError::NotPositiveSemidefinite { eigenvalue: smallest });
+        }
+
+        let axes: Vec<_> = order
+            .iter()
+            .copied()
+            .take_while(|&i| eigen.eigenvalues[i] > threshold)
+            .collect();
+        let points: Vec<_> = (0..rows)
+            .map(|row| {
+                Point::from_iterator(
+                    axes.len(),
+                    axes.iter()
+                        .map(|&i| eigen.eigenvectors[(row, i)] * eigen.eigenvalues[i].sqrt()),
+                )
+            })
+            .collect();
+
+        let mut residual = 0.0;
+        for row in 0..rows {
+            for column in 0..rows {
+                residual += (gram[(row, column)] - points[row].dot(&points[column])).powi(2);
+            }
+        }
+
+        Ok(Self {
+            dim: axes.len(),
+            points,
+            residual: residual.sqrt(),
+        })
+    }
+}
+
+/// Returns the Gram matrix of the points centered at their centroid, given
+/// the distances between them. This is the matrix that classical
+/// multidimensional scaling embeds.
+pub fn centered_gram_matrix(distances: &Matrix<f64>) -> Matrix<f64> {
+    let (rows, columns) = distances.shape();
+    let squared = distances.map(|x| x * x);
+    let row_means: Vec<_> = (0..rows).map(|row| squared.row(row).mean()).collect();
+    let column_means: Vec<_> = (0..columns).map(|column| squared.column(column).mean()).collect();
+    let mean = squared.mean();
+
+    Matrix::from_fn(rows, columns, |row, column| {
+        -(squared[(row, column)] - row_means[row] - column_means[column] + mean) / 2.0
+    })
+}
+
+impl Concrete {
+    /// Builds a polytope on the points whose Gram matrix is the given one,
+    /// which are found by [`Embedding::from_gram_matrix`]. The dimension they
+    /// span and the residual of the embedding are recorded in the metadata,
+    /// under the `dimension` and `residual` properties.
+    ///
+    /// The faces of the hull of the points aren't computed. Instead, the
+    /// points are joined by a single element of each rank above the vertices,
+    /// up to the rank of a polytope that spans their dimension, or up to rank
+    /// 2 if they're all the same point. This isn't a valid polytope, but it's
+    /// enough to facet the points with a given
+    /// [`GroupEnum::VertexMap`](super::faceting::GroupEnum::VertexMap).
+    pub fn from_gram_matrix(gram: &Matrix<f64>) -> Result<Self, EmbedError> {
+        let embedding = Embedding::from_gram_matrix(gram)?;
+
+        let mut builder = AbstractBuilder::new();
+        builder.push_min();
+        builder.push_vertices(embedding.points.len());
+        for _ in 0..embedding.dim.max(1) {
+            builder.push_max();
+        }
+
+        // Safety: the incidences are consistent, though the polytope isn't
+        // valid, as documented.
+        let mut poly = Self::new(embedding.points, unsafe { builder.build() });
+        poly.meta.source = Some("Gram matrix".to_string());
+        poly.meta.properties.insert("dimension".to_string(), embedding.dim.to_string());
+        poly.meta
+            .properties
+            .insert("residual".to_string(), embedding.residual.to_string());
+        Ok(poly)
+    }
+
+    /// Builds a polytope on points with the given distances between them,
+    /// centered at the origin, like [`Self::from_gram_matrix`].
+    pub fn from_distance_matrix(distances: &Matrix<f64>) -> Result<Self, EmbedError> {
+        Self::from_gram_matrix(&centered_gram_matrix(distances))
+    }
+
+    /// Returns the Gram matrix of the vertices of the polytope.
+    pub fn gram_matrix(&self) -> Matrix<f64> {
+        let n = self.vertices.len();
+        Matrix::from_fn(n, n, |i, j| self.vertices[i].dot(&self.vertices[j]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{abs::Ranked, conc::ConcretePolytope, Polytope};
+
+    use approx::assert_abs_diff_eq;
+
+    /// The distances between the points of an embedding match the original
+    /// ones.
+    fn assert_same_distances(points: &[Point<f64>], other: &[Point<f64>]) {
+        for (p, q) in points.iter().zip(other) {
+            for (r, s) in points.iter().zip(other) {
+                assert_abs_diff_eq!((p - r).norm(), (q - s).norm(), epsilon = 1e-9);
+            }
+        }
+    }
+
+    /// The vertices of a cube embed in three dimensions.
+    #[test]
+    fn cube() {
+        let cube = Concrete::hypercube(4);
+        let poly = Concrete::from_gram_matrix(&cube.gram_matrix()).unwrap();
+
+        assert_eq!(poly.dim(), Some(3));
+        assert_eq!(poly.rank(), 4);
+        assert_same_distances(&poly.vertices, &cube.vertices);
+    }
+
+    /// The vertices of a square in a plane of 3D space embed in two
+    /// dimensions.
+    #[test]
+    fn rank_deficient() {
+        let square: Vec<_> = [[1.0, 0.0], [0.0, 1.0], [-1.0, 0.0], [0.0, -1.0]]
+            .iter()
+            .map(|&[x, y]| Point::from_vec(vec![x, y, x + y]))
+            .collect();
+        let gram = Matrix::from_fn(4, 4, |i, j| square[i].dot(&square[j]));
+        let embedding = Embedding::from_gram_matrix(&gram).unwrap();
+
+        assert_eq!(embedding.dim, 2);
+        assert!(embedding.residual < 1e-9);
+        assert_same_distances(&embedding.points, &square);
+        assert_eq!(Concrete::from_gram_matrix(&gram).unwrap().rank(), 3);
+    }
+
+    /// Distance matrices are embedded around the centroid.
+    #[test]
+    fn distances() {
+        let triangle = Concrete::polygon(3);
+        let n = triangle.vertices.len();
+        let distances = Matrix::from_fn(n, n, |i, j| (&triangle.vertices[i] - &triangle.vertices[j]).norm());
+        let poly = Concrete::from_distance_matrix(&distances).unwrap();
+
+        assert_eq!(poly.dim(), Some(2));
+        assert_same_distances(&poly.vertices, &triangle.vertices);
+        assert_abs_diff_eq!(poly.vertices.iter().sum::<Point<f64>>().norm(), 0.0, epsilon = 1e-9);
+    }
+
+    /// Matrices that aren't Gram matrices are refused.
+    #[test]
+    fn errors() {
+        assert_eq!(Embedding::from_gram_matrix(&Matrix::zeros(0, 0)).unwrap_err(), EmbedError::Empty);
+        assert_eq!(
+            Embedding::from_gram_matrix(&Matrix::zeros(2, 3)).unwrap_err(),
+            EmbedError::NotSquare { rows: 2, columns: 3 }
+        );
+        assert!(matches!(
+            Embedding::from_gram_matrix(&Matrix::from_row_slice(2, 2, &[1.0, 0.5, 0.0, 1.0])),
+            Err(EmbedError::NotSymmetric { row: 1, column: 0, .. })
+        ));
+        assert!(matches!(
+            Embedding::from_gram_matrix(&Matrix::from_row_slice(2, 2, &[1.0, 2.0, 2.0, 1.0])),
+            Err(EmbedError::NotPositiveSemidefinite { eigenvalue }) if (eigenvalue + 1.0).abs() < 1e-9
+        ));
+    }
+}
@@ -1,2186 +1,7907 @@
 //! The faceting algorithm.
 
-use std::{collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque}, vec, iter::FromIterator, io::Write, time::Instant, path::PathBuf};
+pub mod cache;
+pub mod chain;
+pub mod crosscheck;
+mod memo;
+pub mod merge;
+#[cfg(feature = "sqlite")]
+pub mod survey;
+
+use std::{cmp::Ordering as CmpOrdering, mem::size_of, collections::{hash_map::DefaultHasher, BinaryHeap, BTreeMap, BTreeSet, HashMap, HashSet, VecDeque}, hash::{Hash, Hasher}, vec, iter::FromIterator, io::BufReader, fs::File, time::{Duration, Instant}, path::{Path, PathBuf}, fmt::Display, sync::{Arc, atomic::{AtomicBool, Ordering}, mpsc::{self, RecvTimeoutError}}, thread};
 
 use crate::{
-    abs::{Abstract, Element, ElementList, Ranked, Ranks, Subelements, Superelements, AbstractBuilder},
-    conc::{Concrete, ConcretePolytope},
+    abs::{limits::{ElementLimits, TooLarge}, Abstract, Element, ElementList, Rank, Ranked, Ranks, Subelements, Superelements, AbstractBuilder, partial::PartialAbstract},
+    conc::{conditioning::{conditioning_report, Conditioning}, meta::PolytopeMeta, symmetry::{check_rotation_orbits, element_orbits_under, induced_stabilizer, normalize_vertex_map, restrict_vertex_map, rotation_subgroup, validate_vertex_map, VertexList, VertexMap, VertexMapError, VertexOrbit, VertexPermutation}, uniform::UniformId, Concrete, ConcretePolytope, PlanarityError},
+    cover::TwofoldCover,
     float::Float,
-    group::{Group}, geometry::{Matrix, PointOrd, Subspace, Point}, Polytope
+    group::{name::GroupName, Group}, geometry::{cmp_coordinates, GeomContext, Matrix, PointOrd, Subspace, Point}, Polytope
 };
 
+use log::{debug, info, trace, warn};
 use ordered_float::OrderedFloat;
 
 use vec_like::*;
 
+use self::cache::{CacheResult, Checkpoint};
+use self::memo::{Shape, SubdimMemo, SubdimOutput};
+
 /// Input for the faceting function
 pub enum GroupEnum {
     /// Group of matrices
     ConcGroup(Group<vec::IntoIter<Matrix<f64>>>),
     /// Group of vertex mappings
-    VertexMap(Vec<Vec<usize>>),
+    VertexMap(VertexMap),
     /// True: take chiral group
     /// False: take full group
     Chiral(bool),
+    /// Rotation subgroup of a group of matrices, such as the full symmetry
+    /// group from [`Concrete::get_symmetry_group`]. This gives the same
+    /// facetings as `Chiral(true)` when the group is the full symmetry group,
+    /// without computing it again.
+    ChiralOf(Group<vec::IntoIter<Matrix<f64>>>),
 }
 
-const CL: &str = "\r                                                                                                                   \r";
+/// Which facetings to output when the symmetry group is missing the
+/// orientation-reversing symmetries of the vertices, such as when searching
+/// under the rotation group. In this case the hyperplane orbits come in pairs
+/// of mirror images.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChiralSelection {
+    /// Output every faceting.
+    Any,
+
+    /// Only output facetings whose hyperplane orbits are unions of mirror
+    /// pairs. These are the only ones that can be reflexible. Compounds of a
+    /// faceting and its mirror image are kept even if compounds are
+    /// excluded, as the components themselves aren't output.
+    PairedOnly,
+
+    /// Only output facetings whose hyperplane orbits aren't unions of mirror
+    /// pairs. These are always chiral.
+    UnpairedOnly,
+}
 
-const DELAY: u128 = 200;
+impl Default for ChiralSelection {
+    fn default() -> Self {
+        Self::Any
+    }
+}
 
-impl Ranks {
-    /// Sorts some stuff in a way that's useful for the faceting algorithm.
-    pub fn element_sort_strong(&mut self) {
-        for el in 0..self[2].len() {
-            self[2][el].subs.sort_unstable();
-        }
+/// The order in which the hyperplane orbits are tried when combining facets.
+///
+/// The combinations are explored one hyperplane orbit at a time, each one
+/// starting from a facet of that orbit and only adding facets from the orbits
+/// that haven't been tried yet, so every combination is found exactly once
+/// whatever the order. The order only changes how much work this takes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OrbitOrder {
+    /// Try the orbits in the order they were found.
+    Index,
+
+    /// Try the orbits with the fewest possible facets first.
+    FewestFacetsFirst,
+
+    /// Start in the order they were found, and every `interval` orbits,
+    /// reorder the orbits not yet tried so that the ones whose facets were
+    /// most often rejected as exotic come first.
+    Adaptive {
+        /// The number of orbits to try between reorderings.
+        interval: usize,
+    },
+}
 
-        for rank in 2..self.len()-1 {
-            let mut all_subs = Vec::new();
-            for el in &self[rank] {
-                all_subs.push(el.subs.clone());
-            }
-            let mut sorted = all_subs.clone();
-            sorted.sort_unstable();
+impl Default for OrbitOrder {
+    fn default() -> Self {
+        Self::Index
+    }
+}
 
-            let mut perm = Vec::new();
-            for i in &all_subs {
-                perm.push(sorted.iter().position(|x| x == i).unwrap());
-            }
+/// The stage of the faceting after which to stop, see
+/// [`FacetingOptions::stop_after`]. Each stage gives a different
+/// [`PartialFacetingOutput`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stage {
+    /// Stop once the hyperplane orbits are found.
+    Hyperplanes,
 
-            for i in 0..self[rank].len() {
-                self[rank][i].subs = sorted[i].clone();
-                self[rank][i].subs.sort_unstable();
-            }
+    /// Stop once the possible facets of every hyperplane orbit are found, and
+    /// return the tables used to combine them.
+    FacetTypes,
 
-            let mut new_list = ElementList::new();
-            for i in 0..self[rank+1].len() {
-                let mut new = Element::new(Subelements::new(), Superelements::new());
-                for sub in &self[rank+1][i].subs {
-                    new.subs.push(perm[*sub]);
-                }
-                new.sort();
-                new_list.push(new);
-            }
-            self[rank+1] = new_list;
-        }
+    /// Stop once the facets are combined, and count the facetings found
+    /// without building them.
+    Count,
+
+    /// Combine the facets and build the facetings.
+    Full,
+}
+
+impl Default for Stage {
+    fn default() -> Self {
+        Self::Full
     }
+}
 
-    /// Sorts some stuff in a way that's useful for the faceting algorithm.
-    pub fn element_sort_strong_with_local(&mut self, local: &Ranks) {
-        for el in 0..self[2].len() {
-            self[2][el].subs.sort_unstable();
-        }
+/// What to do with the possible facets whose vertices don't span their
+/// hyperplane, see [`FacetingOptions::degenerate_facets`]. Such facets have no
+/// volume, even though they're fine as abstract polytopes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DegenerateFacets {
+    /// Keep them without looking for them.
+    Keep,
 
-        for rank in 2..self.len()-1 {
-            let mut all_subs = Vec::new();
-            for el in &self[rank] {
-                all_subs.push(el.subs.clone());
-            }
-            let mut sorted = all_subs.clone();
-            sorted.sort_unstable();
+    /// Keep them, and record them in the tables, see
+    /// [`FacetingTables::is_degenerate`].
+    Flag,
 
-            let mut perm = Vec::new();
-            for i in &all_subs {
-                perm.push(sorted.iter().position(|x| x == i).unwrap());
-            }
+    /// Leave them out, along with the compounds of them.
+    Exclude,
+}
 
-            for i in 0..self[rank].len() {
-                self[rank][i].subs = sorted[i].clone();
-                self[rank][i].subs.sort_unstable();
-            }
+impl Default for DegenerateFacets {
+    fn default() -> Self {
+        Self::Keep
+    }
+}
 
-            let mut map_to_local = HashMap::new();
+/// How often the facets of a hyperplane orbit were added to a combination, and
+/// how many of those times the combination turned out exotic.
+#[derive(Clone, Copy, Debug, Default)]
+struct PruningCount {
+    /// The number of times a facet of the orbit was added.
+    added: u64,
 
-            for i in 0..self[rank+1].len() {
-                for j in 0..self[rank+1][i].subs.len() {
-                    map_to_local.insert(self[rank+1][i].subs[j], local[rank+1][i].subs[j]);
-                }
-            }
+    /// The number of times this gave an exotic combination.
+    exotic: u64,
+}
 
-            let mut new_list = ElementList::new();
-            for i in 0..self[rank+1].len() {
-                let mut new = Element::new(Subelements::new(), Superelements::new());
-                for sub in &self[rank+1][i].subs {
-                    new.subs.push(perm[*map_to_local.get(sub).unwrap()]);
-                }
-                new.sort();
-                new_list.push(new);
-            }
-            self[rank+1] = new_list;
+impl PruningCount {
+    /// The fraction of additions that were rejected right away.
+    fn rate(&self) -> f64 {
+        if self.added == 0 {
+            0.0
+        } else {
+            self.exotic as f64 / self.added as f64
         }
     }
+}
 
-    /*
-    /// Combines two `Ranks`. Only meant to be used in the faceting algorithm.
-    fn append(&mut self, other: &Ranks) {
-        let counts: Vec<usize> = self.iter().map(|x| x.len()).collect();
+/// Returns the order in which to try the hyperplane orbits as the first facet
+/// of the combinations, given how many possible facets each one has.
+fn initial_orbit_order(possible_facets: &[usize], order: OrbitOrder) -> Vec<usize> {
+    let mut orbits: Vec<usize> = (0..possible_facets.len()).collect();
+    if order == OrbitOrder::FewestFacetsFirst {
+        orbits.sort_by_key(|&hp| possible_facets[hp]);
+    }
+    orbits
+}
 
-        for r in 1..=2 {
-            for el in &other[r] {
-                self[r].push(el.clone());
-            }
-        }
+/// The allowed edge lengths of the facetings.
+#[derive(Clone, Debug, PartialEq)]
+pub enum EdgeLengthFilter {
+    /// Any edge length is allowed.
+    Any,
 
-        for r in 3..self.rank() {
-            for el in &other[r] {
-                let mut new_el = el.clone();
-                for sub in &mut new_el.subs {
-                    *sub += counts[r-1];
-                }
-                for sup in &mut new_el.sups {
-                    *sup += counts[r+1];
-                }
-                self[r].push(new_el.clone());
-            }
-        }
+    /// Only a single edge length is allowed.
+    Single(f64),
+
+    /// Only the edge lengths in a list are allowed.
+    Set(Vec<f64>),
+
+    /// The edge lengths between two bounds are allowed. A missing bound
+    /// doesn't restrict the lengths.
+    Range {
+        /// The minimum allowed edge length.
+        min: Option<f64>,
+
+        /// The maximum allowed edge length.
+        max: Option<f64>,
+    },
+}
+
+impl Default for EdgeLengthFilter {
+    fn default() -> Self {
+        Self::Any
     }
-    */
 }
 
-/// Modified binary search that finds the first element whose first element is greater than `min`.
-fn binary(vec: &Vec<(usize,usize)>, min: usize) -> usize{
-    let mut lo  = -1;
-    let mut hi  = vec.len() as isize;
-    let mut c = (lo+hi)/2;
+impl EdgeLengthFilter {
+    /// Allows the edge lengths among some given ones that are between two
+    /// optional bounds, or any length between the bounds if none are given.
+    pub fn new(min: Option<f64>, max: Option<f64>, lengths: Vec<f64>) -> Self {
+        if lengths.is_empty() {
+            return match (min, max) {
+                (None, None) => Self::Any,
+                (Some(min), Some(max)) if min == max => Self::Single(min),
+                (min, max) => Self::Range { min, max },
+            };
+        }
 
-    while hi - lo > 1 {
-        if vec[c as usize].0 > min {
-            hi = c;
-        } else {
-            lo = c;
+        let mut lengths = lengths;
+        lengths.retain(|&length| min.map_or(true, |min| length >= min) && max.map_or(true, |max| length <= max));
+        match lengths.as_slice() {
+            &[length] => Self::Single(length),
+            _ => Self::Set(lengths),
         }
-        c = (lo+hi)/2;
     }
 
-    hi as usize
-}
-
-/// For each faceting, checks if it is a compound of other facetings, and labels it if so.
-fn label_irc(vec: &Vec<Vec<(usize,usize)>>) -> HashMap<usize, (usize,usize)> {
-    let mut out = HashMap::<usize, (usize,usize)>::new(); // Map of the index of the compound to the indices of the components.
+    /// Only allows a single edge length.
+    pub fn exact(length: f64) -> Self {
+        Self::Single(length)
+    }
 
-    'a: for a in 0..vec.len() { // `a` is the index of the base set
-        for b in 0..vec.len() { // `b` is the index of a potential subset of `a`
-            if vec[b].len() >= vec[a].len() { // A strict subset must be smaller than the base.
-                continue
-            }
-            if vec[b][0] > vec[a][0] { // One of the subsets must contain the first facet.
-                break
-            }
-            let mut i = 0;
-            for f in &vec[a] { // Searches through `b` to see if all elements are in `a`.
-                if &vec[b][i] > f {
-                    continue
-                }
-                if &vec[b][i] < f {
-                    break
-                }
-                i += 1;
+    /// Allows the edge lengths between two values.
+    pub fn between(min: f64, max: f64) -> Self {
+        Self::new(Some(min), Some(max), Vec::new())
+    }
 
-                if i >= vec[b].len() { // We've found a subset.
-                    let mut complement = Vec::new();
+    /// Only allows some edge lengths. Allows any edge length if there are
+    /// none.
+    pub fn set(lengths: Vec<f64>) -> Self {
+        Self::new(None, None, lengths)
+    }
 
-                    let mut j = 0;
-                    for g in &vec[a] {
-                        if j >= vec[b].len() {
-                            complement.push(*g);
-                            continue
-                        }
-                        if &vec[b][j] > g {
-                            complement.push(*g);
-                            continue
-                        }
-                        j += 1;
-                    }
+    /// Only allows the lengths of the edges of a polytope, so that the
+    /// facetings only have edges like the ones it already has.
+    pub fn edges_of(poly: &Concrete) -> Self {
+        Self::set(poly.edge_lengths())
+    }
 
-                    for c in b+1..vec.len() { // Look for its complement.
-                        if vec[c] == complement {
-                            out.insert(a,(b,c));
-                            break
-                        }
-                    }
-                    continue 'a;
-                }
-            }
+    /// Returns the minimum allowed edge length, if there's one.
+    pub fn min(&self) -> Option<f64> {
+        match self {
+            Self::Any => None,
+            Self::Single(length) => Some(*length),
+            Self::Set(lengths) => lengths.iter().copied().reduce(f64::min),
+            Self::Range { min, .. } => *min,
         }
     }
-    out
-}
-
-/// For each faceting, checks if it is a compound of other facetings, and removes it if so.
-fn filter_irc(vec: &Vec<Vec<(usize,usize)>>) -> Vec<usize> {
-    let mut out = Vec::new(); // The indices of the facetings that aren't compounds.
 
-    'a: for a in 0..vec.len() { // `a` is the index of the base set
-        for b in 0..vec.len() { // `b` is the index of a potential subset of `a`
-            if a == b {
-                continue
-            }
-            if vec[b].len() > vec[a].len() { // A strict subset must be smaller than the base.
-                continue
-            }
-            if vec[b][0] > vec[a][0] { // One of the subsets must contain the first facet.
-                break
-            }
-            let mut i = 0;
-            for f in &vec[a] { // Searches through `b` to see if all elements are in `a`.
-                if &vec[b][i] > f {
-                    continue
-                }
-                if &vec[b][i] < f {
-                    break
-                }
-                i += 1;
-                if i >= vec[b].len() { // We've found a subset.
-                    continue 'a;
-                }
-            }
+    /// Returns the maximum allowed edge length, if there's one.
+    pub fn max(&self) -> Option<f64> {
+        match self {
+            Self::Any => None,
+            Self::Single(length) => Some(*length),
+            Self::Set(lengths) => lengths.iter().copied().reduce(f64::max),
+            Self::Range { max, .. } => *max,
         }
-        out.push(a)
     }
-    out
-}
 
-fn faceting_subdim(
-    rank: usize,
-    plane: Subspace<f64>,
-    points: Vec<PointOrd<f64>>,
-    vertex_map: Vec<Vec<usize>>,
-    min_edge_length: Option<f64>,
-    max_edge_length: Option<f64>,
-    max_per_hyperplane: Option<usize>,
-    uniform: bool,
-    noble_package: Option<(&Vec<Vec<usize>>, &Vec<usize>, usize)>,
-    print_faceting_count: bool
-) ->
-    (Vec<(Ranks, Vec<(usize, usize)>)>, // Vec of facetings, along with the facet types of each of them
-    Vec<usize>, // Counts of each hyperplane orbit
-    Vec<Vec<Ranks>>, // Possible facets, these will be the possible ridges one dimension up
-    HashMap<usize, (usize,usize)> // Map of compound facetings to their components.
-) {
-    let total_vert_count = points.len();
-
-        let mut now = Instant::now();
-    if rank == 2 {
-        // The only faceting of a dyad is itself.
-        // We distinguish between snub and non-snub edges.
+    /// Returns the allowed edge lengths if only finitely many are allowed, or
+    /// nothing otherwise.
+    pub fn lengths(&self) -> &[f64] {
+        match self {
+            Self::Single(length) => std::slice::from_ref(length),
+            Self::Set(lengths) => lengths,
+            Self::Any | Self::Range { .. } => &[],
+        }
+    }
 
-        let mut snub = true;
+    /// Returns whether an edge length is allowed, up to floating point error.
+    pub fn contains(&self, length: f64) -> bool {
+        self.contains_within(length, f64::EPS)
+    }
 
-        for row in &vertex_map {
-            if row[0] == 1 {
-                snub = false;
-                break
+    /// Returns whether an edge length is allowed, up to some tolerance.
+    pub fn contains_within(&self, length: f64, tolerance: f64) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Single(allowed) => (length - allowed).abs() < tolerance,
+            Self::Set(lengths) => lengths.iter().any(|allowed| (length - allowed).abs() < tolerance),
+            Self::Range { min, max } => {
+                min.map_or(true, |min| length > min - tolerance) && max.map_or(true, |max| length < max + tolerance)
             }
         }
+    }
 
-        if snub {
-            return (
-                vec![(Abstract::dyad().ranks().clone(), vec![(0,0), (1,0)])],
-                vec![1,1],
-                vec![
-                    vec![vec![
-                        vec![].into(),
-                        vec![
-                            Element::new(vec![0].into(), vec![].into())
-                            ].into(),
-                        vec![
-                            Element::new(vec![0].into(), vec![].into())
-                            ].into(),
-                    ].into()],
-                    vec![vec![
-                        vec![].into(),
-                        vec![
-                            Element::new(vec![0].into(), vec![].into())
-                            ].into(),
-                        vec![
-                            Element::new(vec![1].into(), vec![].into())
-                            ].into(),
-                    ].into()]
-                    ],
-                    HashMap::new()
-            )
+    /// Returns the only edge length allowed, if there's just one.
+    pub fn single_length(&self) -> Option<f64> {
+        match self {
+            Self::Single(length) => Some(*length),
+            _ => None,
         }
-        else {
-            return (
-                vec![(Abstract::dyad().ranks().clone(), vec![(0,0)])],
-                vec![2],
-                vec![
-                    vec![vec![
-                        vec![].into(),
-                        vec![
-                            Element::new(vec![0].into(), vec![].into())
-                            ].into(),
-                        vec![
-                            Element::new(vec![0].into(), vec![].into())
-                            ].into(),
-                    ].into()]
-                    ],
-                    HashMap::new()
-            )
-        }
-    }
-    let mut flat_points = Vec::new();
-    for p in &points {
-        flat_points.push(PointOrd::new(plane.flatten(&p.0)));
     }
-    
-    let mut vertex_orbits = Vec::new(); // Vec of orbits which are vecs of vertices.
-    let mut orbit_of_vertex = vec![0; total_vert_count]; // For each vertex stores its orbit index.
-    let mut checked_vertices = vec![false; total_vert_count]; // Stores whether we've already checked the vertex.
 
-    let mut orbit_idx = 0;
-    for v in 0..total_vert_count {
-        if !checked_vertices[v] {
-            // We found a new orbit of vertices.
-            let mut new_orbit = Vec::new();
-            for row in &vertex_map {
-                // Find all vertices in the same orbit.
-                let c = row[v];
-                if !checked_vertices[c] {
-                    new_orbit.push(c);
-                    checked_vertices[c] = true;
-                    orbit_of_vertex[c] = orbit_idx;
-                }
+    /// Returns how far an edge length is from being allowed, which is zero
+    /// if it's allowed.
+    pub fn distance_to(&self, length: f64) -> f64 {
+        match self {
+            Self::Any => 0.0,
+            Self::Single(allowed) => (length - allowed).abs(),
+            Self::Set(lengths) => lengths
+                .iter()
+                .map(|allowed| (length - allowed).abs())
+                .reduce(f64::min)
+                .unwrap_or(f64::INFINITY),
+            Self::Range { min, max } => {
+                let below = min.map_or(0.0, |min| min - length);
+                let above = max.map_or(0.0, |max| length - max);
+                below.max(above).max(0.0)
             }
-            vertex_orbits.push(new_orbit);
-            orbit_idx += 1;
         }
     }
 
-    let mut pair_orbits = Vec::new();
-    let mut checked = vec![vec![false; total_vert_count]; total_vert_count];
-    
-    for orbit in vertex_orbits {
-        let rep = orbit[0]; // We only need one representative per orbit.
-        for vertex in rep+1..total_vert_count {
-            if !checked[rep][vertex] {
-                let edge_length = (&points[vertex].0-&points[rep].0).norm();
-                if let Some(min) = min_edge_length {
-                    if edge_length < min - f64::EPS {
-                        continue
-                    }
-                }
-                if let Some(max) = max_edge_length {
-                    if edge_length > max + f64::EPS {
-                        continue
-                    }
-                }
-                let mut new_orbit = Vec::new();
-                for row in &vertex_map {
-                    let (a1, a2) = (row[rep], row[vertex]);
-                    let c1 = a1.min(a2);
-                    let c2 = a1.max(a2);
-                    if !checked[c1][c2] {
-                        new_orbit.push(vec![c1, c2]);
-                        checked[c1][c2] = true;
-                    }
-                }
-                pair_orbits.push(new_orbit);
-            }
+    /// Returns the distances between the vertices that are closest to being
+    /// allowed, up to a given number of them, from the closest one.
+    pub fn closest_lengths(&self, vertices: &[Point<f64>], tolerance: f64, count: usize) -> Vec<f64> {
+        let mut lengths = length_spectrum(vertices, tolerance);
+        lengths.sort_by(|&a, &b| self.distance_to(a).partial_cmp(&self.distance_to(b)).unwrap());
+        lengths.truncate(count);
+        lengths
+    }
+}
+
+/// Returns the distinct distances between pairs of vertices, sorted, where
+/// distances closer than a tolerance count as the same.
+pub fn length_spectrum(vertices: &[Point<f64>], tolerance: f64) -> Vec<f64> {
+    let mut lengths = Vec::new();
+    for (idx, v) in vertices.iter().enumerate() {
+        for w in &vertices[idx + 1..] {
+            lengths.push((v - w).norm());
         }
     }
 
-    // Enumerate hyperplanes
-    let mut hyperplane_orbits = Vec::new();
-    let mut checked = HashSet::<Vec<usize>>::new();
-    let mut hyperplanes_vertices = Vec::new();
+    lengths.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    lengths.dedup_by(|a, b| *a - *b < tolerance);
+    lengths
+}
 
-    let mut noble_map = HashMap::<Vec<usize>, usize>::new();
-    let mut noble_counts = Vec::<usize>::new();
-    let mut noble_muls = Vec::<usize>::new();
+/// Which facet hyperplanes to keep by their depth, their distance from the
+/// origin. The bounds are inclusive, up to floating point error.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DepthFilter {
+    /// Keeps the hyperplanes at least this far from the origin.
+    Min(f64),
+
+    /// Keeps the hyperplanes at most this far from the origin.
+    Max(f64),
+
+    /// Keeps the hyperplanes whose distance from the origin is between two
+    /// values.
+    Between(f64, f64),
+
+    /// Keeps the hyperplanes at most this many times the radius of the
+    /// insphere from the origin, see [`ConcretePolytope::insphere_radius`].
+    /// A ratio of 1 keeps the hyperplanes at least as deep as the closest
+    /// facets.
+    RelativeToInsphere(f64),
+}
 
-    for pair_orbit in pair_orbits {
-        let rep = &pair_orbit[0];
+impl DepthFilter {
+    /// Returns the least and the greatest depth allowed, given the radius of
+    /// the insphere of the polytope being faceted.
+    fn bounds(self, insphere_radius: impl FnOnce() -> Option<f64>) -> Result<(f64, f64), FacetingError> {
+        Ok(match self {
+            Self::Min(min) => (min, f64::INFINITY),
+            Self::Max(max) => (f64::NEG_INFINITY, max),
+            Self::Between(min, max) => (min, max),
+            Self::RelativeToInsphere(ratio) => (
+                f64::NEG_INFINITY,
+                ratio * insphere_radius().ok_or(FacetingError::NoInsphere)?,
+            ),
+        })
+    }
+}
 
-        if rep[1]+rank-2 > points.len() {
-            continue;
+/// Which facetings to output by their number of facet orbits, see
+/// [`FacetingOptions::facet_orbits`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FacetOrbitCount {
+    /// Keeps the facetings with exactly this many facet orbits.
+    Exact(usize),
+
+    /// Keeps the facetings with at most this many facet orbits.
+    AtMost(usize),
+
+    /// Keeps the facetings whose number of facet orbits is between two
+    /// values, inclusive.
+    Between(usize, usize),
+}
+
+impl FacetOrbitCount {
+    /// Returns the least and the greatest number of facet orbits allowed.
+    pub fn bounds(self) -> (usize, usize) {
+        match self {
+            Self::Exact(count) => (count, count),
+            Self::AtMost(max) => (0, max),
+            Self::Between(min, max) => (min, max),
         }
-        let mut new_vertices: Vec<usize> = (rep[1]+1..rep[1]+rank-2).collect();
-        let mut update = 0;
-        if rank > 3 {
-            update = rank-4;
-        }
-        'b: loop {
-            'c: loop {
-                // WLOG checks if the vertices are all the right distance away from the first vertex.
-                for (v_i, v) in new_vertices.iter().enumerate() {
-                    let edge_length = (&points[*v].0-&points[rep[0]].0).norm();
-                    if let Some(min) = min_edge_length {
-                        if edge_length < min - f64::EPS {
-                            update = v_i;
-                            break 'c;
-                        }
-                    }
-                    if let Some(max) = max_edge_length {
-                        if edge_length > max + f64::EPS {
-                            update = v_i;
-                            break 'c;
-                        }
-                    }
-                }
-                // We start with a pair and add enough vertices to define a hyperplane.
-                let mut tuple = rep.clone();
-                tuple.append(&mut new_vertices.clone());
+    }
 
-                let mut first_points = Vec::new();
-                for v in tuple {
-                    first_points.push(&flat_points[v].0);
-                }
+    /// Returns whether a number of facet orbits is allowed.
+    pub fn contains(self, count: usize) -> bool {
+        let (min, max) = self.bounds();
+        min <= count && count <= max
+    }
+}
 
-                let hyperplane = Subspace::from_points(first_points.clone().into_iter());
-                if hyperplane.is_hyperplane() {
+/// What to do with a candidate found while combining facets, as decided by a
+/// [`CandidateCallback`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CandidateDecision {
+    /// Keep the candidate, and keep extending it.
+    Accept,
 
-                    let mut hyperplane_vertices = Vec::new();
-                    for (idx, v) in flat_points.iter().enumerate() {
-                        if hyperplane.distance(&v.0) < f64::EPS {
-                            hyperplane_vertices.push(idx);
-                        }
-                    }
-                    hyperplane_vertices.sort_unstable();
+    /// Don't output the candidate, but keep extending it.
+    Reject,
 
-                    // Check if the hyperplane has been found already.
-                    if !checked.contains(&hyperplane_vertices) {
-                        // If it's new, we add all the ones in its orbit.
-                        let mut new_orbit = Vec::new();
-                        let mut new_orbit_vertices = Vec::new();
-                        for row in &vertex_map {
-                            let mut new_hp_v = Vec::new();
-                            for idx in &hyperplane_vertices {
-                                new_hp_v.push(row[*idx]);
-                            }
-                            let new_hp_points = new_hp_v.iter().map(|x| &flat_points[*x].0);
-                            let new_hp = Subspace::from_points(new_hp_points);
+    /// Don't output the candidate nor anything extending it.
+    RejectSubtree,
+}
 
-                            let mut sorted = new_hp_v.clone();
-                            sorted.sort_unstable();
+/// A combination of facet orbits found while combining, as seen by a
+/// [`CandidateCallback`]. Only combinations that don't cover any ridge more
+/// times than [`FacetingOptions::ridge_multiplicity`] get this far.
+#[derive(Clone, Copy, Debug)]
+pub struct FacetingCandidate<'a> {
+    /// The facet orbits chosen, as pairs of a hyperplane orbit and one of its
+    /// possible facets, in the order they were added.
+    pub facets: &'a [(usize, usize)],
 
-                            if !checked.contains(&sorted) {
-                                checked.insert(sorted);
-                                new_orbit.push(new_hp);
-                                new_orbit_vertices.push(new_hp_v);
-                            }
-                        }
+    /// How many times the chosen facets cover each ridge of each ridge orbit.
+    pub ridge_coverage: &'a [usize],
 
-                        if let Some((full_vertex_map, global_v, count)) = noble_package {
-                            let mut set = HashSet::new();
+    /// Whether every ridge is covered as many times as the ridge multiplicity
+    /// or not at all, that is, whether the candidate is a faceting.
+    pub complete: bool,
 
-                            let mut global_hp_v = Vec::new();
-                            for idx in &hyperplane_vertices {
-                                global_hp_v.push(global_v[*idx]);
-                            }
-                            global_hp_v.sort_unstable();
-                            
-                            match noble_map.get(&global_hp_v) {
-                                Some(idx) => {
-                                    let mul = count * new_orbit.len() / noble_counts[*idx];
-                                    noble_muls[*idx] += mul;
-                                },
-                                None => {
-                                    for row in full_vertex_map {
-                                        let mut new_hp_v = Vec::new();
-                                        for idx in &hyperplane_vertices {
-                                            new_hp_v.push(row[global_v[*idx]]);
-                                        }
-                                        
-                                        let mut sorted = new_hp_v.clone();
-                                        sorted.sort_unstable();
-        
-                                        set.insert(sorted.clone());
-                                        noble_map.insert(sorted, noble_counts.len());
-                                    }
-        
-                                    let mul = count * new_orbit.len() / set.len();
-                                    noble_counts.push(set.len());
-                                    noble_muls.push(mul);
-                                },
-                            }
-                        }
+    /// The tables the facets come from.
+    tables: &'a FacetingTables,
+}
 
-                        hyperplane_orbits.push(new_orbit);
-                        hyperplanes_vertices.push(new_orbit_vertices);
-                    }
-                }
-                break;
-            }
-            if rank <= 3 {
-                break;
-            }
-            loop { // Increment new_vertices.
-                if new_vertices[update] == total_vert_count + update - rank + 3 {
-                    if update < 1 {
-                        break 'b;
-                    }
-                    else {
-                        update -= 1;
-                    }
-                } else {
-                    new_vertices[update] += 1;
-                    for i in update+1..rank-3 {
-                        new_vertices[i] = new_vertices[i-1]+1;
-                    }
-                    update = rank-4;
-                    break;
-                }
-            }
-        }
+impl<'a> FacetingCandidate<'a> {
+    /// Returns a hyperplane of the orbit of each chosen facet, in the same
+    /// order as the facets.
+    pub fn hyperplanes(&self) -> Vec<&'a Subspace<f64>> {
+        let tables = self.tables;
+        self.facets.iter().map(|&(hp, _)| tables.hyperplane(hp)).collect()
     }
-    // Filter the invalid hyperplanes if noble faceting.
-    if let Some((_, global_v, _)) = noble_package {
-        let mut new_hyperplane_orbits = Vec::new();
-        let mut new_hyperplanes_vertices = Vec::new();
+}
 
-        for (idx, orbit) in hyperplanes_vertices.iter().enumerate() {
-            let mut global_hp_v: Vec<usize> = orbit[0].clone().iter().map(|x| global_v[*x]).collect();
-            global_hp_v.sort_unstable();
-            if noble_muls[*noble_map.get(&global_hp_v).unwrap()] >= 2 {
-                new_hyperplane_orbits.push(hyperplane_orbits[idx].clone());
-                new_hyperplanes_vertices.push(orbit.clone());
-            }
-        }
+/// A function deciding which candidates to keep while combining facets, for
+/// acceptance criteria that no other option covers.
+///
+/// It's called on every candidate after checking that it covers no ridge
+/// more than twice, and before building anything. Returning
+/// [`CandidateDecision::RejectSubtree`] on an incomplete candidate prunes the
+/// search. The function may be called from several threads at once, so it
+/// must be `Send + Sync`, and any state it keeps must be behind a lock or an
+/// atomic.
+#[derive(Clone)]
+pub struct CandidateCallback(Arc<dyn Fn(&FacetingCandidate<'_>) -> CandidateDecision + Send + Sync>);
+
+impl CandidateCallback {
+    /// Wraps a function into a callback.
+    pub fn new<F: Fn(&FacetingCandidate<'_>) -> CandidateDecision + Send + Sync + 'static>(callback: F) -> Self {
+        Self(Arc::new(callback))
+    }
+
+    /// Decides what to do with a candidate.
+    pub fn decide(&self, candidate: &FacetingCandidate<'_>) -> CandidateDecision {
+        (self.0)(candidate)
+    }
+}
 
-        hyperplane_orbits = new_hyperplane_orbits;
-        hyperplanes_vertices = new_hyperplanes_vertices;
+impl std::fmt::Debug for CandidateCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("CandidateCallback")
     }
+}
 
-    // Facet the hyperplanes
-    let mut possible_facets = Vec::new();
-    let mut possible_facets_global: Vec<Vec<(Ranks, Vec<(usize,usize)>)>> = Vec::new(); // copy of above but with semi-global vertex indices
-    let mut compound_facets: Vec<HashMap<usize, (usize,usize)>> = Vec::new();
-    let mut ridges: Vec<Vec<Vec<Ranks>>> = Vec::new();
-    let mut ff_counts = Vec::new();
+/// How far along a faceting is, as reported to a [`ProgressSink`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FacetingProgress {
+    /// Enumerating the orbits of pairs of vertices at an allowed edge length.
+    EdgeOrbits {
+        /// The number of edge orbits found so far.
+        found: usize,
+    },
+    /// Enumerating the orbits of the subspaces of some rank spanned by the
+    /// vertices, on the way to the hyperplanes.
+    Subspaces {
+        /// The rank of the subspaces.
+        rank: usize,
+        /// The number of subspace orbits found so far.
+        found: usize,
+    },
+    /// Enumerating the hyperplane orbits.
+    Hyperplanes {
+        /// The number of hyperplane orbits found so far.
+        found: usize,
+    },
+    /// Faceting the hyperplanes, orbit by orbit.
+    HyperplaneFacets {
+        /// The number of hyperplane orbits faceted so far.
+        done: usize,
+        /// The number of hyperplane orbits.
+        total: usize,
+    },
+    /// Faceting a single hyperplane.
+    Subfacetings {
+        /// The number of facetings of the hyperplane found so far.
+        found: usize,
+    },
+    /// Sorting the ridges of the possible facets into orbits.
+    Ridges {
+        /// The number of hyperplane orbits whose ridges were sorted so far.
+        done: usize,
+        /// The number of hyperplane orbits.
+        total: usize,
+    },
+    /// Combining the possible facets into facetings.
+    Combining {
+        /// The number of facetings found so far.
+        found: usize,
+    },
+    /// Building the facetings found.
+    Building {
+        /// The number of facetings built so far.
+        done: usize,
+        /// The number of facetings to build.
+        total: usize,
+    },
+}
 
-    for (i, orbit) in hyperplane_orbits.iter().enumerate() {
-        let (hp, hp_v) = (orbit[0].clone(), hyperplanes_vertices[i][0].clone());
-        let mut stabilizer = Vec::new();
-        for row in &vertex_map {
-            let mut slice = Vec::new();
-            for v in &hp_v {
-                slice.push(row[*v]);
-            }
-            let mut slice_sorted = slice.clone();
-            slice_sorted.sort_unstable();
+/// A function the progress of a faceting is reported to, see
+/// [`FacetingOptions::progress`]. Reports are throttled, so that a sink isn't
+/// called more than a few times a second from the inner loops. The function
+/// may be called from several threads at once, so it must be `Send + Sync`.
+#[derive(Clone)]
+pub struct ProgressSink(Arc<dyn Fn(FacetingProgress) + Send + Sync>);
+
+impl ProgressSink {
+    /// Wraps a function into a progress sink.
+    pub fn new<F: Fn(FacetingProgress) + Send + Sync + 'static>(sink: F) -> Self {
+        Self(Arc::new(sink))
+    }
+
+    /// Reports some progress.
+    pub fn report(&self, progress: FacetingProgress) {
+        (self.0)(progress)
+    }
+}
 
-            if slice_sorted == hp_v {
-                stabilizer.push(slice.clone());
+impl std::fmt::Debug for ProgressSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ProgressSink")
+    }
+}
+
+/// Reports progress to an optional sink at most once every [`DELAY`]
+/// milliseconds.
+struct Throttle<'a> {
+    sink: Option<&'a ProgressSink>,
+    now: Instant,
+}
+
+impl<'a> Throttle<'a> {
+    fn new(sink: Option<&'a ProgressSink>) -> Self {
+        Self { sink, now: Instant::now() }
+    }
+
+    /// Reports the progress built by `progress` if there's a sink and enough
+    /// time went by since the last report.
+    fn report<F: FnOnce() -> FacetingProgress>(&mut self, progress: F) {
+        if let Some(sink) = self.sink {
+            if self.now.elapsed().as_millis() > DELAY {
+                sink.report(progress());
+                self.now = Instant::now();
             }
         }
+    }
 
-        // Converts global vertex indices to local ones.
-        let mut map_back = BTreeMap::new();
-        for (idx, el) in stabilizer[0].iter().enumerate() {
-            map_back.insert(*el, idx);
+    /// Reports the progress built by `progress` if there's a sink, however
+    /// long ago the last report was.
+    fn finish<F: FnOnce() -> FacetingProgress>(&mut self, progress: F) {
+        if let Some(sink) = self.sink {
+            sink.report(progress());
+            self.now = Instant::now();
         }
-        
-        let mut new_stabilizer = stabilizer.clone();
+    }
+}
 
-        for a in 0..stabilizer.len() {
-            for b in 0..stabilizer[a].len() {
-                new_stabilizer[a][b] = *map_back.get(&stabilizer[a][b]).unwrap();
-            }
+/// Which possible facets can be used while combining facets, see
+/// [`FacetingOptions::facet_filter`]. Facets are given as pairs of a
+/// hyperplane orbit and an index among its possible facets, like in
+/// [`FacetingResult::facets`].
+///
+/// The filter is applied to the possible facets as they're combined, so a
+/// compound facet is allowed or not as a whole, whatever its components are.
+/// The facetings found are still output with the compound facets split into
+/// their components.
+#[derive(Clone)]
+pub enum FacetFilter {
+    /// Only these facets can be used.
+    Allow(HashSet<(usize, usize)>),
+
+    /// Every facet but these can be used.
+    Deny(HashSet<(usize, usize)>),
+
+    /// The facets for which a function returns `true` can be used. It's given
+    /// the hyperplane orbit, the index of the facet, and the facet with the
+    /// vertices indexed like those being faceted. Like a
+    /// [`CandidateCallback`], the function must be `Send + Sync`.
+    Predicate(Arc<FacetPredicate>),
+}
+
+/// A function deciding which facets a [`FacetFilter`] allows.
+type FacetPredicate = dyn Fn(usize, usize, &Ranks) -> bool + Send + Sync;
+
+impl FacetFilter {
+    /// Wraps a function into a filter.
+    pub fn predicate<F: Fn(usize, usize, &Ranks) -> bool + Send + Sync + 'static>(predicate: F) -> Self {
+        Self::Predicate(Arc::new(predicate))
+    }
+
+    /// Returns whether the filter allows a facet.
+    pub fn allows(&self, hyperplane_orbit: usize, facet: usize, ranks: &Ranks) -> bool {
+        match self {
+            Self::Allow(facets) => facets.contains(&(hyperplane_orbit, facet)),
+            Self::Deny(facets) => !facets.contains(&(hyperplane_orbit, facet)),
+            Self::Predicate(predicate) => predicate(hyperplane_orbit, facet, ranks),
         }
+    }
+}
 
-        let mut points = Vec::new();
-        for v in &hp_v {
-            points.push(flat_points[*v].clone());
+impl std::fmt::Debug for FacetFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Allow(facets) => f.debug_tuple("Allow").field(facets).finish(),
+            Self::Deny(facets) => f.debug_tuple("Deny").field(facets).finish(),
+            Self::Predicate(_) => f.write_str("Predicate"),
         }
+    }
+}
 
-        let (possible_facets_row, ff_counts_row, ridges_row, compound_facets_row) =
-            faceting_subdim(rank-1, hp, points, new_stabilizer.clone(), min_edge_length, max_edge_length, max_per_hyperplane, uniform, None, false);
+/// A score to maximize over the facetings, so that only the best few are
+/// output.
+///
+/// Every faceting is built and scored as soon as it's found, and only the
+/// facet orbits of the best ones so far are kept. Ties are broken by the
+/// facet orbits, so that the facetings coming first in the usual output order
+/// are kept. A `NaN` score counts as the lowest possible. Like a
+/// [`CandidateCallback`], the functions must be `Send + Sync`.
+#[derive(Clone)]
+pub struct FacetingObjective {
+    /// The function scoring each faceting.
+    score: Arc<ScoreFn>,
+
+    /// How many facetings to keep.
+    k: usize,
+
+    /// An upper bound for the scores of the facetings extending a candidate.
+    bound: Option<Arc<BoundFn>>,
+}
 
-        let mut possible_facets_global_row = Vec::new();
-        for f in &possible_facets_row {
-            let mut new_f = f.clone();
-            let mut new_edges = ElementList::new();
-            for v in f.0[2].clone() {
-                // Converts indices back to semi-global
-                let mut new_edge = Element::new(vec![].into(), vec![].into());
-                for s in v.subs {
-                    new_edge.subs.push(hp_v[s]);
-                }
-                new_edges.push(new_edge);
-            }
-            new_f.0[2] = new_edges;
+/// A function scoring facetings for a [`FacetingObjective`].
+type ScoreFn = dyn Fn(&FacetingResult) -> f64 + Send + Sync;
 
-            possible_facets_global_row.push(new_f);
+/// A function bounding the scores of the facetings extending a candidate, for
+/// a [`FacetingObjective`].
+type BoundFn = dyn Fn(&FacetingCandidate<'_>) -> f64 + Send + Sync;
+
+impl FacetingObjective {
+    /// Keeps the `k` facetings with the highest scores.
+    pub fn new<F: Fn(&FacetingResult) -> f64 + Send + Sync + 'static>(k: usize, score: F) -> Self {
+        Self {
+            score: Arc::new(score),
+            k,
+            bound: None,
         }
-        possible_facets.push(possible_facets_row);
-        possible_facets_global.push(possible_facets_global_row);
-        compound_facets.push(compound_facets_row);
-        ridges.push(ridges_row);
-        ff_counts.push(ff_counts_row);
     }
 
-    let mut ridge_idx_orbits = Vec::new();
-    let mut ridge_orbits = HashMap::new();
-    let mut ridge_counts = Vec::new(); // Counts the number of ridges in each orbit
-    let mut orbit_idx = 0;
+    /// Sets a function giving an upper bound for the score of every faceting
+    /// extending a candidate, including the candidate itself. Once `k`
+    /// facetings have been found, candidates whose bound is strictly below
+    /// all of their scores are pruned. A bound that's too low makes the
+    /// search miss facetings.
+    pub fn with_bound<F: Fn(&FacetingCandidate<'_>) -> f64 + Send + Sync + 'static>(mut self, bound: F) -> Self {
+        self.bound = Some(Arc::new(bound));
+        self
+    }
 
-    let mut hp_i = 0; // idk why i have to do this, thanks rust
-    for ridges_row in ridges {
-        let mut r_i_o_row = Vec::new();
+    /// Returns how many facetings are kept.
+    pub fn k(&self) -> usize {
+        self.k
+    }
 
-        for ridges_row_row in ridges_row {
-            let mut r_i_o_row_row = Vec::new();
+    /// Scores a faceting. `NaN` is turned into negative infinity.
+    pub fn score(&self, result: &FacetingResult) -> f64 {
+        let score = (self.score)(result);
+        if score.is_nan() {
+            f64::NEG_INFINITY
+        } else {
+            score
+        }
+    }
+}
 
-            for mut ridge in ridges_row_row {
-                // goes through all the ridges
+impl std::fmt::Debug for FacetingObjective {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FacetingObjective")
+            .field("k", &self.k)
+            .field("bound", &self.bound.is_some())
+            .finish()
+    }
+}
 
-                // globalize
-                let mut new_list = ElementList::new();
-                for i in 0..ridge[2].len() {
-                    let mut new = Element::new(Subelements::new(), Superelements::new());
-                    for sub in &ridge[2][i].subs {
-                        new.subs.push(hyperplanes_vertices[hp_i][0][*sub])
-                    }
-                    new_list.push(new);
-                }
-                ridge[2] = new_list;
+/// Where and how often to write checkpoints while combining facets, see
+/// [`FacetingOptions::checkpoint`]. A checkpoint is written once either
+/// interval has passed since the last one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CheckpointOptions {
+    /// The file the checkpoints are written to. Each one replaces the last.
+    pub path: PathBuf,
 
-                ridge.element_sort_strong();
+    /// How many candidates to check between two checkpoints.
+    pub every_candidates: Option<u64>,
 
-                match ridge_orbits.get(&ridge) {
-                    Some(idx) => {
-                        // writes the orbit index at the ridge index
-                        r_i_o_row_row.push(*idx);
-                    }
-                    None => {
-                        // adds all ridges with the same orbit to the map
-                        let mut count = 0;
-                        for row in &vertex_map {
-                            let mut new_ridge = ridge.clone();
+    /// How long to wait between two checkpoints.
+    pub every: Option<Duration>,
+}
 
-                            let mut new_list = ElementList::new();
-                            for i in 0..new_ridge[2].len() {
-                                let mut new = Element::new(Subelements::new(), Superelements::new());
-                                for sub in &ridge[2][i].subs {
-                                    new.subs.push(row[*sub])
-                                }
-                                new_list.push(new);
-                            }
-                            new_ridge[2] = new_list;
+impl CheckpointOptions {
+    /// Writes checkpoints to a file only when the search is cancelled.
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self {
+            path: path.into(),
+            every_candidates: None,
+            every: None,
+        }
+    }
+}
 
-                            new_ridge.element_sort_strong();
+/// Some of the vertices to facet, see [`FacetingOptions::vertex_subset`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VertexSubset {
+    /// The vertices with these indices. They must form a union of orbits of
+    /// the symmetry group for the faceting to use all of it, as only the
+    /// symmetries that send them to themselves are kept.
+    Vertices(Vec<usize>),
+
+    /// The vertex orbits with these indices, numbered by their smallest
+    /// vertex like in [`VertexOrbit::orbits`] under the symmetry group.
+    Orbits(Vec<usize>),
+}
 
-                            if ridge_orbits.get(&new_ridge).is_none() {
-                                ridge_orbits.insert(new_ridge, orbit_idx);
-                                count += 1;
-                            }
-                        }
-                        r_i_o_row_row.push(orbit_idx);
-                        ridge_counts.push(count);
-                        orbit_idx += 1;
-                    }
+impl VertexSubset {
+    /// Returns the sorted indices of the vertices picked out of the vertices
+    /// faceted, given the vertex map of the symmetry group.
+    pub fn resolve(&self, vertex_map: &[VertexPermutation], vertex_count: usize) -> Result<Vec<usize>, FacetingError> {
+        let mut subset = match self {
+            Self::Vertices(vertices) => {
+                if let Some(&vertex) = vertices.iter().find(|&&v| v >= vertex_count) {
+                    return Err(FacetingError::SubsetVertexOutOfRange { vertex, vertex_count });
+                }
+                vertices.clone()
+            }
+            Self::Orbits(orbits) => {
+                let vertex_orbits = VertexOrbit::orbits(vertex_count, vertex_map);
+                let mut subset = Vec::new();
+                for &orbit in orbits {
+                    let vertex_orbit = vertex_orbits.get(orbit).ok_or(FacetingError::SubsetOrbitOutOfRange {
+                        orbit,
+                        orbit_count: vertex_orbits.len(),
+                    })?;
+                    subset.extend(vertex_orbit.iter());
                 }
+                subset
             }
-            r_i_o_row.push(r_i_o_row_row);
+        };
+
+        subset.sort_unstable();
+        subset.dedup();
+        if subset.is_empty() {
+            return Err(FacetingError::EmptyVertexSubset);
         }
-        ridge_idx_orbits.push(r_i_o_row);
-        hp_i += 1;
+        Ok(subset)
     }
+}
 
-    let mut f_counts = Vec::new();
-    for orbit in hyperplane_orbits {
-        f_counts.push(orbit.len());
-    }
+/// The settings for [`Concrete::faceting`].
+#[derive(Clone, Debug)]
+pub struct FacetingOptions {
+    /// Whether to run the faceting once for each possible edge length.
+    pub any_single_edge_length: bool,
+
+    /// The geometry context of the workflow the faceting is part of, used to
+    /// compute the symmetries of the polytope. Its tolerance is how close two
+    /// lengths must be to count as equal: it's used to compare the edge
+    /// lengths and the depths of the hyperplanes, and to decide which
+    /// vertices are on a hyperplane. Coordinates built through long chains of
+    /// operations may need a looser tolerance than the default, so that their
+    /// hyperplanes aren't split into several ones.
+    pub geometry: GeomContext,
+
+    /// The tolerances to try in turn if the hyperplane orbits found with
+    /// [`Self::tolerance`] aren't consistent, see
+    /// [`HyperplaneInconsistency`]. The first one that gives consistent
+    /// orbits is used for the rest of the faceting, and recorded in
+    /// [`FacetingStats::retry_tolerance`]. If none does, the orbits found
+    /// with [`Self::tolerance`] are kept.
+    pub tolerance_retry: Option<Vec<f64>>,
+
+    /// Whether to look for hyperplane orbits that look like a single orbit
+    /// split apart by symmetries barely missing the tolerance, and warn about
+    /// them. See [`merge`] for how they're found.
+    pub detect_split_orbits: bool,
+
+    /// Whether to merge the hyperplane orbits found by
+    /// [`Self::detect_split_orbits`], which this implies, by building the
+    /// tables under the symmetries found with the looser tolerance.
+    pub auto_merge: bool,
+
+    /// The allowed edge lengths. See [`EdgeLengthFilter::edges_of`] to allow
+    /// the lengths of the edges of the polytope being faceted.
+    pub edge_length: EdgeLengthFilter,
+
+    /// The minimum allowed distance from a facet hyperplane to the origin.
+    pub min_inradius: Option<f64>,
+
+    /// The maximum allowed distance from a facet hyperplane to the origin.
+    pub max_inradius: Option<f64>,
+
+    /// Which hyperplane orbits to keep by their depth. Unlike
+    /// [`Self::min_inradius`] and [`Self::max_inradius`], this is applied to
+    /// the orbits once they're found, and the orbits it removes are counted
+    /// in [`FacetingStats::depth_filtered`]. The tables built with it store
+    /// it with [`DepthFilter::RelativeToInsphere`] replaced by the bounds it
+    /// gave.
+    pub depth_filter: Option<DepthFilter>,
+
+    /// Whether to skip hyperplanes through the origin.
+    pub exclude_hemis: bool,
+
+    /// Whether to only consider hyperplanes orthogonal to a vertex.
+    pub only_below_vertex: bool,
+
+    /// If set, only these vertices are faceted, under the symmetries that
+    /// send them to themselves. The facetings only have these vertices, in
+    /// the order they had among the vertices passed in, and the vertex
+    /// indices in [`Self::hyperplanes`] and [`Self::reflection`] refer to
+    /// them in that order.
+    pub vertex_subset: Option<VertexSubset>,
+
+    /// The hyperplanes to facet into, each given by the indices of some
+    /// vertices that span it, instead of searching through every hyperplane
+    /// through the vertices. Each one is expanded to its orbit under the
+    /// symmetries, and the ones in the same orbit are only used once. The
+    /// edge lengths aren't checked on them, but the inradius and hemi filters
+    /// still apply. This takes precedence over [`Self::only_below_vertex`].
+    pub hyperplanes: Option<Vec<Vec<usize>>>,
+
+    /// The exact number of facet orbits, as a shorthand for
+    /// [`FacetOrbitCount::Exact`] in [`Self::facet_orbits`]. `Some(1)` gives
+    /// the noble facetings. It's ignored when [`Self::facet_orbits`] is set.
+    pub noble: Option<usize>,
+
+    /// The numbers of facet orbits of the facetings to output. Its greatest
+    /// number caps the facet orbits searched through, and the facetings with
+    /// fewer facet orbits than its least number are still searched through,
+    /// but not output. It takes precedence over [`Self::noble`], see
+    /// [`Self::facet_orbit_count`].
+    pub facet_orbits: Option<FacetOrbitCount>,
+
+    /// The maximum number of facetings of each hyperplane.
+    pub max_per_hyperplane: Option<usize>,
+
+    /// Whether to only output facetings with a single vertex orbit.
+    pub uniform: bool,
+
+    /// Whether to output compounds of other facetings.
+    pub include_compounds: bool,
+
+    /// Whether to leave out the facetings that are compounds once they're
+    /// built, see [`ConcretePolytope::is_compound`]. Unlike
+    /// [`Self::include_compounds`], this also catches the compounds whose
+    /// components aren't facetings themselves, like the stella octangula
+    /// as a faceting of the cube. It's only applied to the facetings that are
+    /// built, so not with [`Stage::Count`].
+    pub exclude_compounds: bool,
+
+    /// Whether to leave out the facetings that are fissary once they're
+    /// built, see [`Abstract::is_fissary`]. Like [`Self::exclude_compounds`],
+    /// it's only applied to the facetings that are built.
+    pub exclude_fissaries: bool,
+
+    /// Whether to keep only the first of the facetings that are congruent
+    /// under the full symmetry group of the polytope, when faceting under a
+    /// smaller group, which finds several copies of them. The facetings are
+    /// compared by their facets before they're built, so this also applies
+    /// with [`Stage::Count`]. It's only possible when the vertices faceted are
+    /// those of the polytope.
+    pub dedup_congruent: bool,
+
+    /// Whether to mark compounds and fissary facetings in their names.
+    pub mark_fissary: bool,
+
+    /// Whether to include the facet orbits in the names of the facetings.
+    pub label_facets: bool,
+
+    /// Whether to build and output the facetings.
+    pub save: bool,
+
+    /// Whether to output the facets of the facetings.
+    pub save_facets: bool,
+
+    /// Whether to write the outputs to [`Self::file_path`] instead of
+    /// returning them.
+    pub save_to_file: bool,
+
+    /// The directory the outputs are written to if [`Self::save_to_file`] is
+    /// set.
+    pub file_path: String,
+
+    /// A flag that can be set from another thread to stop the enumeration
+    /// early. It's checked while enumerating the hyperplanes, between
+    /// hyperplane orbits and while faceting each of them, and while combining
+    /// the facets.
+    ///
+    /// The facetings found up to that point are still built and returned.
+    /// They're valid polytopes, as every ridge of each of them is in exactly
+    /// two facets: stopping early only means that some facetings are missing.
+    /// If the flag is set before the facets are combined, nothing is found.
+    pub cancel: Option<Arc<AtomicBool>>,
+
+    /// If set, the enumeration is cancelled like with [`Self::cancel`] once
+    /// it's been running for this long, and [`FacetingStats::timed_out`] is
+    /// set. Where it stopped is given by [`FacetingStats::stop_point`]. This
+    /// applies to [`Concrete::try_faceting_staged`], which the other ways of
+    /// faceting a polytope go through, and to [`Concrete::faceting_resume`].
+    pub time_limit: Option<Duration>,
+
+    /// If set, the state of the search is written to a file every so often
+    /// while combining facets, and when it's cancelled, so that it can be
+    /// picked up again with [`Concrete::faceting_resume`] after a crash. Only
+    /// the search for every faceting writes checkpoints, and not
+    /// [`FacetingIter`] or the completion of a partial faceting. When
+    /// faceting for several edge lengths, each search overwrites the
+    /// checkpoint of the last.
+    pub checkpoint: Option<CheckpointOptions>,
+
+    /// Which facetings to output depending on how their hyperplane orbits
+    /// pair up under a reflection.
+    pub chiral_selection: ChiralSelection,
+
+    /// An orientation-reversing permutation of the vertices, used to pair up
+    /// the hyperplane orbits for [`Self::chiral_selection`]. If not set, it's
+    /// taken from the full symmetry group of the polytope.
+    pub reflection: Option<VertexPermutation>,
+
+    /// Stops before enumerating the hyperplanes if the estimated number of
+    /// vertex tuples to check, given by [`OrbitStructure`], exceeds this.
+    pub abort_if_estimated_tuples_exceed: Option<u64>,
+
+    /// Edge length filters for the facets of specific hyperplane orbits,
+    /// keyed by the orbit index logged when faceting the hyperplanes. These
+    /// replace [`Self::edge_length`] for those orbits, but not when
+    /// enumerating the hyperplanes.
+    pub per_orbit_edge_length: HashMap<usize, EdgeLengthFilter>,
+
+    /// The order in which the hyperplane orbits are tried when combining
+    /// facets.
+    pub orbit_order: OrbitOrder,
+
+    /// A function deciding which candidates to keep while combining facets.
+    /// When combining ridge orbit by ridge orbit, it's only called on the
+    /// facetings found, so rejecting a subtree is the same as rejecting.
+    pub candidate_callback: Option<CandidateCallback>,
+
+    /// Where to report how far along the faceting is. The stages and the
+    /// results are logged through the `log` facade either way.
+    pub progress: Option<ProgressSink>,
+
+    /// Which possible facets can be used while combining facets. The others
+    /// are never added to a candidate, which cuts down the search a lot when
+    /// only a few kinds of facets are wanted.
+    pub facet_filter: Option<FacetFilter>,
+
+    /// Whether to go on when the symmetry group computed for the polytope is
+    /// trivial, even though the distances between its vertices suggest it
+    /// has more symmetries. This usually means that the coordinates are too
+    /// imprecise, and the search would take forever.
+    pub allow_trivial_group: bool,
+
+    /// Whether to recognize the symmetry group computed for the polytope by
+    /// name, see [`Group::identify_with_vertex_map`], and return it in
+    /// [`FacetingStats::group_name`]. This goes through all of the elements of
+    /// the group, so it's left off by default.
+    pub identify_group: bool,
+
+    /// Whether to go on with no facetings when no two vertices are at an
+    /// allowed edge length, instead of returning
+    /// [`FacetingError::NoAllowedEdges`]. This usually means that the edge
+    /// lengths asked for are wrong.
+    pub allow_vacuous_edge_lengths: bool,
+
+    /// Also stops when the order of the symmetry group computed is less than
+    /// this fraction of the bound given by [`Concrete::distance_spectrum`].
+    pub min_group_fraction: Option<f64>,
+
+    /// Whether to keep the possible facets of each hyperplane orbit as
+    /// standalone polytopes in the tables, see
+    /// [`FacetingTables::subfacetings`]. These can take up a lot of memory.
+    pub collect_subfacetings: bool,
+
+    /// If set, only the best facetings by this objective are output, best
+    /// first. When combining ridge orbit by ridge orbit, its bound isn't
+    /// used.
+    pub objective: Option<FacetingObjective>,
+
+    /// How many facets each ridge must be in. Anything other than 2 gives
+    /// complexes that aren't polytopes, such as the branched ones you get with
+    /// 3 or more, which can only be found through
+    /// [`FacetingTables::complexes`].
+    pub ridge_multiplicity: usize,
+
+    /// The stage after which to stop. Only
+    /// [`Concrete::try_faceting_staged`] returns what the earlier stages
+    /// found.
+    pub stop_after: Stage,
+
+    /// Whether to check the vertices for numerical hazards with
+    /// [`Concrete::conditioning_report`] before faceting, and warn about them.
+    pub check_conditioning: bool,
+
+    /// Only output the facetings that are their own polars, with respect to
+    /// the sphere about the origin with this squared radius. The poles of the
+    /// facet hyperplanes must then be exactly the vertices used. See
+    /// [`FacetingTables::polar_pairing`].
+    pub require_self_polar: Option<f64>,
+
+    /// Limits on the element counts of the facetings. Every facet of a
+    /// faceting lies on one of the hyperplanes, so the faceting stops with
+    /// [`FacetingError::TooLarge`] when there are more hyperplanes than the
+    /// limit on the facets allows.
+    pub element_limits: ElementLimits,
+
+    /// What to do with the possible facets whose vertices span less than
+    /// their hyperplane. They're counted in
+    /// [`FacetingStats::degenerate_facet_count`] unless they're kept without
+    /// looking for them.
+    pub degenerate_facets: DegenerateFacets,
+
+    /// How far a vertex of a possible facet must be from the subspace spanned
+    /// by the others to count towards its dimension, when looking for
+    /// degenerate facets.
+    pub degeneracy_tolerance: f64,
+
+    /// Whether to reuse the facetings of a hyperplane for the later ones whose
+    /// vertices are congruent to its own, with conjugate stabilizers, instead
+    /// of faceting them again. Such hyperplanes needn't be related by a
+    /// symmetry of the polytope. This only changes the order in which the
+    /// possible facets of these hyperplanes are listed, but that changes the
+    /// facet indices that facetings are given by, so it's off by default.
+    pub memoize_subdim: bool,
+
+    /// The maximum number of facetings to find. Once that many are found, the
+    /// search stops as soon as it finds another one, and
+    /// [`FacetingStats::truncated`] is set. The facetings found are still
+    /// filtered as usual afterwards, so that fewer may be returned when
+    /// compounds are excluded. This is ignored when there's an
+    /// [`Self::objective`], as every faceting must then be scored.
+    pub max_results: Option<usize>,
+}
 
-    // Actually do the faceting
-    let mut ridge_muls = Vec::new();
-    let mut ones = vec![Vec::<(usize, usize)>::new(); ridge_counts.len()];
+impl Default for FacetingOptions {
+    fn default() -> Self {
+        Self {
+            any_single_edge_length: false,
+            geometry: GeomContext::default(),
+            tolerance_retry: None,
+            detect_split_orbits: false,
+            auto_merge: false,
+            edge_length: EdgeLengthFilter::Any,
+            min_inradius: None,
+            max_inradius: None,
+            depth_filter: None,
+            exclude_hemis: false,
+            only_below_vertex: false,
+            vertex_subset: None,
+            hyperplanes: None,
+            noble: None,
+            facet_orbits: None,
+            max_per_hyperplane: None,
+            uniform: false,
+            include_compounds: false,
+            exclude_compounds: false,
+            exclude_fissaries: false,
+            dedup_congruent: false,
+            mark_fissary: false,
+            label_facets: false,
+            save: true,
+            save_facets: false,
+            save_to_file: false,
+            file_path: String::new(),
+            cancel: None,
+            time_limit: None,
+            checkpoint: None,
+            chiral_selection: ChiralSelection::Any,
+            reflection: None,
+            abort_if_estimated_tuples_exceed: None,
+            per_orbit_edge_length: HashMap::new(),
+            orbit_order: OrbitOrder::Index,
+            candidate_callback: None,
+            progress: None,
+            facet_filter: None,
+            allow_trivial_group: false,
+            identify_group: false,
+            allow_vacuous_edge_lengths: false,
+            min_group_fraction: None,
+            collect_subfacetings: false,
+            objective: None,
+            ridge_multiplicity: 2,
+            stop_after: Stage::Full,
+            check_conditioning: false,
+            require_self_polar: None,
+            element_limits: ElementLimits::default(),
+            degenerate_facets: DegenerateFacets::Keep,
+            degeneracy_tolerance: f64::EPS,
+            memoize_subdim: false,
+            max_results: None,
+        }
+    }
+}
 
-    for (hp, list) in possible_facets.iter().enumerate() {
-        let mut ridge_muls_hp = Vec::new();
-        for (f, _) in list.iter().enumerate() {
-            let mut ridge_muls_facet = vec![0; ridge_counts.len()];
+impl FacetingOptions {
+    /// Returns the numbers of facet orbits of the facetings to output, given
+    /// by [`Self::facet_orbits`], or else by [`Self::noble`].
+    pub fn facet_orbit_count(&self) -> Option<FacetOrbitCount> {
+        self.facet_orbits.or_else(|| self.noble.map(FacetOrbitCount::Exact))
+    }
 
-            let f_count = f_counts[hp];
+    /// Returns the greatest number of facet orbits a faceting can have, see
+    /// [`Self::facet_orbit_count`].
+    pub fn max_facet_orbits(&self) -> Option<usize> {
+        self.facet_orbit_count().map(|count| count.bounds().1)
+    }
 
-            let ridge_idxs_local = &possible_facets[hp][f].1;
-            for ridge_idx in ridge_idxs_local {
-                let ridge_orbit = ridge_idx_orbits[hp][ridge_idx.0][ridge_idx.1];
-                let ridge_count = ff_counts[hp][ridge_idx.0];
-                let total_ridge_count = ridge_counts[ridge_orbit];
-                let mul = f_count * ridge_count / total_ridge_count;
+    /// Returns an error if the facetings can't be built as polytopes with
+    /// these options, or at all.
+    fn check_polytopes(&self) -> Result<(), FacetingError> {
+        self.check_ridge_multiplicity()?;
+        if self.ridge_multiplicity == 2 {
+            Ok(())
+        } else {
+            Err(FacetingError::NotPolytopes { ridge_multiplicity: self.ridge_multiplicity })
+        }
+    }
 
-                if mul == 1 {
-                    ones[ridge_orbit].push((hp, f));
-                }
+    /// Returns whether a faceting that's been built is left out by
+    /// [`Self::exclude_compounds`] or [`Self::exclude_fissaries`], counting
+    /// it in the statistics if so.
+    fn excludes(&self, polytope: &Concrete, stats: &mut FacetingStats) -> bool {
+        if self.exclude_compounds && polytope.is_compound() {
+            stats.excluded_compound_count += 1;
+            true
+        } else if self.exclude_fissaries && polytope.abs.is_fissary() {
+            stats.excluded_fissary_count += 1;
+            true
+        } else {
+            false
+        }
+    }
 
-                ridge_muls_facet[ridge_orbit] = mul;
-            }
+    /// Returns an error if no facets can have the ridge multiplicity.
+    fn check_ridge_multiplicity(&self) -> Result<(), FacetingError> {
+        if self.ridge_multiplicity == 0 {
+            Err(FacetingError::ZeroRidgeMultiplicity)
+        } else {
+            Ok(())
+        }
+    }
 
-            ridge_muls_hp.push(ridge_muls_facet);
+    /// Returns the default options with a geometry context.
+    pub fn with_geometry(geometry: GeomContext) -> Self {
+        Self {
+            geometry,
+            ..Default::default()
         }
-        ridge_muls.push(ridge_muls_hp);
     }
 
-    let mut output = Vec::new();
-    let mut output_facets = Vec::new();
+    /// Returns the default options with the default geometry context at
+    /// another tolerance.
+    #[deprecated(note = "use `FacetingOptions::with_geometry(GeomContext::with_tolerance(tolerance))` instead")]
+    pub fn with_tolerance(tolerance: f64) -> Self {
+        Self::with_geometry(GeomContext::with_tolerance(tolerance))
+    }
 
-    let mut facets_queue = VecDeque::<(
-        Vec<(usize, usize)>, // list of facets
-        usize, // min hyperplane
-        Vec<usize> // cached ridge muls
-    )>::new();
+    /// Returns how close two lengths must be to count as equal, the tolerance
+    /// of [`Self::geometry`].
+    pub fn tolerance(&self) -> f64 {
+        self.geometry.tolerance
+    }
 
-    for (hp, list) in possible_facets.iter().enumerate() {
-        for f in 0..list.len() {
-            facets_queue.push_back((
-                vec![(hp, f)],
-                hp,
-                vec![0; ridge_counts.len()]
-            ));
+    /// Returns whether the enumeration has been cancelled.
+    fn is_cancelled(&self) -> bool {
+        match &self.cancel {
+            Some(cancel) => cancel.load(Ordering::Relaxed),
+            None => false,
         }
     }
 
-    let mut skipped = 0;
-    'l: while let Some((facets, min_hp, cached_ridge_muls)) = facets_queue.pop_back() {
-        if uniform {
-            if now.elapsed().as_millis() > DELAY && print_faceting_count {
-                print!("{}", CL);
-                print!("{:.115}", format!("{} facets found, {} skipped, {:?}", output.len(), skipped, facets));
-                std::io::stdout().flush().unwrap();
-                now = Instant::now();
+    /// If there's a [`Self::time_limit`], starts a timer for it, and returns
+    /// a copy of the options with a cancellation flag that it sets once the
+    /// time runs out or [`Self::cancel`] is set.
+    fn with_timer(&self) -> Option<(Self, Timer)> {
+        let deadline = Instant::now() + self.time_limit?;
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (stop, stopped) = mpsc::channel::<()>();
+
+        let user = self.cancel.clone();
+        let timer_cancel = Arc::clone(&cancel);
+        thread::spawn(move || loop {
+            let now = Instant::now();
+            if now >= deadline || matches!(&user, Some(user) if user.load(Ordering::Relaxed)) {
+                timer_cancel.store(true, Ordering::Relaxed);
+                return;
             }
-        } else {
-            if now.elapsed().as_millis() > DELAY && print_faceting_count {
-                print!("{}", CL);
-                print!("{:.115}", format!("{} facets found, {:?}", output.len(), facets));
-                std::io::stdout().flush().unwrap();
-                now = Instant::now();
+
+            // The flag of the caller can only be forwarded by polling it.
+            let wait = match user {
+                Some(_) => (deadline - now).min(CANCEL_POLL),
+                None => deadline - now,
+            };
+            if let Err(RecvTimeoutError::Disconnected) = stopped.recv_timeout(wait) {
+                return;
             }
-        }
-        
-        let mut new_ridge_muls = cached_ridge_muls.clone();
+        });
 
-        let last_facet = facets.last().unwrap();
+        let options = Self {
+            cancel: Some(cancel),
+            ..self.clone()
+        };
+        Some((options, Timer { _stop: stop }))
+    }
+}
 
-        'a: loop {
-            let hp = last_facet.0;
-            let f = last_facet.1;
+/// How often the flag of [`FacetingOptions::cancel`] is checked while a timer
+/// for [`FacetingOptions::time_limit`] runs.
+const CANCEL_POLL: Duration = Duration::from_millis(10);
 
-            let ridge_idxs_local = &possible_facets[hp][f].1;
-            for ridge_idx in ridge_idxs_local {
-                let ridge_orbit = ridge_idx_orbits[hp][ridge_idx.0][ridge_idx.1];
-                let mul = ridge_muls[hp][f][ridge_orbit];
+/// A timer for [`FacetingOptions::time_limit`], see
+/// [`FacetingOptions::with_timer`]. Its thread stops when it's dropped.
+struct Timer {
+    /// Disconnects the channel the thread waits on when dropped.
+    _stop: mpsc::Sender<()>,
+}
 
-                new_ridge_muls[ridge_orbit] += mul;
-                if new_ridge_muls[ridge_orbit] > 2 {
-                    break 'a;
-                }
-            }
-            break;
+impl Timer {
+    /// Marks the enumeration as timed out if it was cancelled by the timer
+    /// rather than by the caller.
+    fn finish(self, options: &FacetingOptions, stats: &mut FacetingStats) {
+        if stats.cancelled && !options.is_cancelled() {
+            stats.timed_out = true;
         }
-        let mut valid = 0; // 0: valid, 1: exotic, 2: incomplete
-        for r in &new_ridge_muls {
-            if *r > 2 {
-                valid = 1;
-                break
-            }
-            if *r == 1 {
-                valid = 2;
-            }
+    }
+}
+
+/// An inconsistency in the hyperplane orbits found while faceting, which
+/// usually means that [`FacetingOptions::tolerance`] doesn't suit the
+/// precision of the vertices.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HyperplaneInconsistency {
+    /// The images of the vertices on a hyperplane under some symmetry don't
+    /// span a hyperplane.
+    NotSpanning {
+        /// The index of the hyperplane orbit.
+        hyperplane_orbit: usize,
+
+        /// The row of the vertex map of the symmetry.
+        row: usize,
+    },
+
+    /// The images of the vertices on a hyperplane under some symmetry span a
+    /// hyperplane with a different number of vertices on it.
+    Membership {
+        /// The index of the hyperplane orbit.
+        hyperplane_orbit: usize,
+
+        /// The row of the vertex map of the symmetry.
+        row: usize,
+
+        /// The number of vertices on the hyperplane.
+        expected: usize,
+
+        /// The number of vertices on its image.
+        found: usize,
+    },
+
+    /// The number of hyperplanes in an orbit times the order of the
+    /// stabilizer of one of them isn't the order of the group.
+    OrbitSize {
+        /// The index of the hyperplane orbit.
+        hyperplane_orbit: usize,
+
+        /// The number of hyperplanes in the orbit.
+        orbit_size: usize,
+
+        /// The number of symmetries fixing the vertices on a hyperplane.
+        stabilizer_order: usize,
+
+        /// The order of the symmetry group.
+        group_order: usize,
+    },
+}
+
+impl Display for HyperplaneInconsistency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::NotSpanning { hyperplane_orbit, row } => write!(
+                f,
+                "the image of hyperplane orbit {} under row {} of the vertex map doesn't span a hyperplane",
+                hyperplane_orbit, row
+            ),
+            Self::Membership { hyperplane_orbit, row, expected, found } => write!(
+                f,
+                "hyperplane orbit {} has {} vertices, but its image under row {} of the vertex map has {}",
+                hyperplane_orbit, expected, row, found
+            ),
+            Self::OrbitSize { hyperplane_orbit, orbit_size, stabilizer_order, group_order } => write!(
+                f,
+                "hyperplane orbit {} has {} hyperplanes with stabilizers of order {}, but the group has order {}",
+                hyperplane_orbit, orbit_size, stabilizer_order, group_order
+            ),
         }
-        match valid {
-            0 => {
-                // Split compound facets into their components.
-                let mut new_facets = Vec::new();
-
-                for (hp, idx) in &facets {
-                    let mut all_components = Vec::<usize>::new();
-                    let mut queue = VecDeque::new();
-                    queue.push_back(*idx);
-                    while let Some(next) = queue.pop_front() {
-                        if let Some(components) = compound_facets[*hp].get(&next) {
-                            queue.push_back(components.0);
-                            queue.push_back(components.1);
-                        } else {
-                            all_components.push(next);
-                        }
-                    }
-                    for component in all_components {
-                        new_facets.push((*hp, component));
-                    }
-                }
+    }
+}
 
-                // Output the faceted polytope. We will build it from the set of its facets.
+/// The number of images of each hyperplane checked for consistency.
+const CONSISTENCY_SAMPLE: usize = 32;
 
-                let mut facet_set = HashSet::new();
-                for facet_orbit in &new_facets {
-                    let facet = &possible_facets_global[facet_orbit.0][facet_orbit.1].0;
-                    let facet_local = &possible_facets[facet_orbit.0][facet_orbit.1].0;
-                    for row in &vertex_map {
-                        let mut new_facet = facet.clone();
-                            
-                        let mut new_list = ElementList::new();
-                        for i in 0..facet[2].len() {
-                            let mut new = Element::new(Subelements::new(), Superelements::new());
-                            for sub in &facet[2][i].subs {
-                                new.subs.push(row[*sub])
-                            }
-                            new_list.push(new);
-                        }
-                        new_facet[2] = new_list;
+/// Statistics collected over a run of [`Concrete::faceting_with_stats`].
+#[derive(Clone, Debug, Default)]
+pub struct FacetingStats {
+    /// The number of vertices.
+    pub vertex_count: usize,
 
-                        new_facet.element_sort_strong_with_local(facet_local);
-                        facet_set.insert(new_facet);
-                    }
-                }
+    /// The number of vertex orbits.
+    pub vertex_orbit_count: usize,
 
-                let mut facet_vec = Vec::from_iter(facet_set.clone());
-                let mut facet_vec2 = Vec::from_iter(facet_set);
+    /// The rating of the vertices by [`Concrete::conditioning_report`], if
+    /// they were checked.
+    pub conditioning: Option<Conditioning>,
 
-                let mut ranks = Ranks::new();
-                ranks.push(vec![Element::new(vec![].into(), vec![].into())].into()); // nullitope
-                ranks.push(vec![Element::new(vec![0].into(), vec![].into()); total_vert_count].into()); // vertices
-                
-                let mut ranks2 = Ranks::new();
-                ranks2.push(vec![Element::new(vec![].into(), vec![].into())].into()); // nullitope
-
-                let mut to_new_idx = HashMap::new();
-                let mut to_old_idx = Vec::new();
-                let mut idx = 0;
-                if uniform {
-                    for i in 0..facet_vec2.len() {
-                        let mut new_list = ElementList::new();
-                        for j in 0..facet_vec2[i][2].len() {
-                            let mut new = Element::new(Subelements::new(), Superelements::new());
-                            for sub in facet_vec2[i][2][j].subs.clone() {
-                                if to_new_idx.get(&sub).is_none() {
-                                    to_new_idx.insert(sub, idx);
-                                    to_old_idx.push(sub);
-                                    idx += 1;
-                                }
-                                new.subs.push(*to_new_idx.get(&sub).unwrap())
-                            }
-                            new_list.push(new);
-                        }
-                        facet_vec2[i][2] = new_list;
-                    }
-                    let mut new_rank = ElementList::new();
-                    for _i in 0..idx {
-                        new_rank.push(Element::new(vec![0].into(), vec![].into()));
-                    }
-                    ranks2.push(new_rank);
-                }
+    /// The order of the symmetry group used.
+    pub group_order: usize,
 
-                for r in 2..rank-1 { // edges and up
-                    let mut subs_to_idx = HashMap::new();
-                    let mut idx_to_subs = Vec::new();
-                    let mut idx = 0;
+    /// The name of the symmetry group computed for the polytope, when asked
+    /// for with [`FacetingOptions::identify_group`] and recognized. It's left
+    /// out when only some of the vertices are faceted, as they're only acted
+    /// on by a subgroup.
+    pub group_name: Option<GroupName>,
 
-                    for facet in &facet_vec {
-                        let els = &facet[r];
-                        for el in els {
-                            if subs_to_idx.get(&el.subs).is_none() {
-                                subs_to_idx.insert(el.subs.clone(), idx);
-                                idx_to_subs.push(el.subs.clone());
-                                idx += 1;
-                            }
-                        }
-                    }
-                    for i in 0..facet_vec.len() {
-                        let mut new_list = ElementList::new();
-                        for j in 0..facet_vec[i][r+1].len() {
-                            let mut new = Element::new(Subelements::new(), Superelements::new());
-                            for sub in &facet_vec[i][r+1][j].subs {
-                                let sub_subs = &facet_vec[i][r][*sub].subs;
-                                new.subs.push(*subs_to_idx.get(sub_subs).unwrap())
-                            }
-                            new_list.push(new);
-                        }
-                        facet_vec[i][r+1] = new_list;
-                    }
+    /// Whether the vertex map contains the central inversion, which halves the
+    /// work done when enumerating hyperplanes.
+    pub central_symmetry: bool,
 
-                    let mut new_rank = ElementList::new();
-                    for el in idx_to_subs {
-                        new_rank.push(Element::new(el, vec![].into()));
-                    }
-                    ranks.push(new_rank);
-                    
-                    if uniform {
-                        let mut subs_to_idx = HashMap::new();
-                        let mut idx_to_subs = Vec::new();
-                        let mut idx = 0;
-                        for facet in &facet_vec2 {
-                            let els = &facet[r];
-                            for el in els {
-                                if subs_to_idx.get(&el.subs).is_none() {
-                                    subs_to_idx.insert(el.subs.clone(), idx);
-                                    idx_to_subs.push(el.subs.clone());
-                                    idx += 1;
-                                }
-                            }
-                        }
-                        for i in 0..facet_vec2.len() {
-                            let mut new_list = ElementList::new();
-                            for j in 0..facet_vec2[i][r+1].len() {
-                                let mut new = Element::new(Subelements::new(), Superelements::new());
-                                for sub in &facet_vec2[i][r+1][j].subs {
-                                    let sub_subs = &facet_vec2[i][r][*sub].subs;
-                                    new.subs.push(*subs_to_idx.get(sub_subs).unwrap())
-                                }
-                                new_list.push(new);
-                            }
-                            facet_vec2[i][r+1] = new_list;
-                        }
+    /// The number of hyperplane orbits found.
+    pub hyperplane_orbit_count: usize,
 
-                        let mut new_rank = ElementList::new();
-                        for el in idx_to_subs {
-                            new_rank.push(Element::new(el, vec![].into()));
-                        }
-                        ranks2.push(new_rank);
-                    }
-                }
-                let mut new_rank = ElementList::new();
-                let mut set = HashSet::new();
+    /// The number of hyperplanes found.
+    pub hyperplane_count: usize,
 
-                for f_i in 0..facet_vec.len() {
-                    facet_vec[f_i][rank-1][0].subs.sort();
-                    let subs = facet_vec[f_i][rank-1][0].subs.clone();
-                    if !set.contains(&subs) {
-                        new_rank.push(Element::new(subs.clone(), Superelements::new()));
-                        set.insert(subs);
-                    }
-                }
-                let n_r_len = new_rank.len();
-                ranks.push(new_rank); // facets
+    /// The number of inconsistencies found in the hyperplane orbits that were
+    /// kept, see [`HyperplaneInconsistency`].
+    pub hyperplane_inconsistency_count: usize,
+
+    /// The first of these inconsistencies.
+    pub first_hyperplane_inconsistency: Option<HyperplaneInconsistency>,
 
-                ranks.push(vec![Element::new(Subelements::from_iter(0..n_r_len), Superelements::new())].into()); // body
-                
-                if uniform {
-                    let mut new_rank = ElementList::new();
-                    let mut set = HashSet::new();
+    /// The tolerance from [`FacetingOptions::tolerance_retry`] that gave
+    /// consistent hyperplane orbits, if the first one didn't.
+    pub retry_tolerance: Option<f64>,
 
-                    for f_i in 0..facet_vec2.len() {
-                        facet_vec2[f_i][rank-1][0].subs.sort();
-                        let subs = facet_vec2[f_i][rank-1][0].subs.clone();
-                        if !set.contains(&subs) {
-                            new_rank.push(Element::new(subs.clone(), Superelements::new()));
-                            set.insert(subs);
-                        }
-                    }
-                    let n_r_len = new_rank.len();
-                    ranks2.push(new_rank); // facets
+    /// The number of pairs of hyperplane orbits that look like a single orbit
+    /// split apart by the tolerance, see
+    /// [`FacetingOptions::detect_split_orbits`].
+    pub merge_candidate_count: usize,
 
-                    ranks2.push(vec![Element::new(Subelements::from_iter(0..n_r_len), Superelements::new())].into()); // body
-                }
+    /// The number of hyperplane orbits removed by merging them into others
+    /// with [`FacetingOptions::auto_merge`], which aren't counted in
+    /// [`Self::hyperplane_orbit_count`].
+    pub merged_orbit_count: usize,
 
-                if uniform {
-                    unsafe {
-                        let mut builder = AbstractBuilder::new();
-                        for rank in ranks2 {
-                            builder.push_empty();
-                            for el in rank {
-                                builder.push_subs(el.subs);
-                            }
-                        }
-            
-                        if builder.ranks().is_dyadic().is_ok() {
-                            let abs = builder.build();
-                            let mut new_vertices = Vec::new();
-                            for i in to_old_idx {
-                                new_vertices.push(flat_points[i].0.clone());
-                            }
+    /// The number of hyperplane orbits removed by
+    /// [`FacetingOptions::depth_filter`], which aren't counted in the others.
+    pub depth_filtered: usize,
 
-                            let mut poly = Concrete {
-                                vertices: new_vertices,
-                                abs: abs.clone(),
-                            };
-                            poly.recenter();
-                            
-                            let amount = poly.element_types()[1].len();
-                            
-                            if amount <= 1 {
-                                output.push((ranks, new_facets.clone()));
-                                output_facets.push(new_facets.clone());
-                            } else {
-                                poly.element_sort();
-                                let components = poly.defiss();
-                                let mut isogonal = true;
-                                for component in components {
-                                    if component.element_types()[1].len() > 1 {
-                                        isogonal = false;
-                                        break;
-                                    }
-                                }
-                                if isogonal {
-                                    output.push((ranks, new_facets.clone()));
-                                    output_facets.push(new_facets.clone());
-                                } else {
-                                    skipped += 1;
-                                }
-                            }
-                        } else {
-                            unreachable!();
-                        }
-                    }
-                } else {
-                    output.push((ranks, new_facets.clone()));
-                    output_facets.push(new_facets.clone());
-                }
+    /// The number of possible facets, summed over all hyperplane orbits.
+    pub possible_facet_count: usize,
 
-                if let Some(max) = max_per_hyperplane {
-                    if output.len() + skipped >= max {
-                        break 'l;
-                    }
-                }
+    /// The number of possible facets whose vertices span less than their
+    /// hyperplane, when looking for them with
+    /// [`FacetingOptions::degenerate_facets`]. The excluded ones aren't
+    /// counted in [`Self::possible_facet_count`].
+    pub degenerate_facet_count: usize,
 
-                if noble_package.is_none() {
-                    let mut used_hps = HashSet::new();
-                    for facet in facets.iter().skip(1) {
-                        used_hps.insert(facet.0);
-                    }
-                    for (hp, list) in possible_facets.iter().enumerate().skip(min_hp+1) {
-                        if !used_hps.contains(&hp) {
-                            for f in 0..list.len() {
-                                let mut new_facets = facets.clone();
-                                new_facets.push((hp, f));
-                                facets_queue.push_back((new_facets, hp, new_ridge_muls.clone()));
-                            }
-                        }
-                    }
-                }
-            }
-            1 => {}
-            2 => {
-                let mut used_hps = HashSet::new();
-                for facet in facets.iter().skip(1) {
-                    used_hps.insert(facet.0);
-                }
-                for (idx, mul) in new_ridge_muls.iter().enumerate() {
-                    if *mul == 1 {
-                        for facet in ones[idx]
-                            .iter()
-                            .skip(binary(&ones[idx], min_hp))
-                        {
-                            if !used_hps.contains(&facet.0) {
-                                let mut new_facets = facets.clone();
-                                new_facets.push(*facet);
-                                facets_queue.push_back((new_facets, min_hp, new_ridge_muls.clone()));
-                            }
-                        }
-                        break;
-                    }
-                }
-            }
-            _ => {}
-        }
-    }
+    /// The number of ridge orbits found.
+    pub ridge_orbit_count: usize,
 
-    output.sort_by(|a,b| a.1.cmp(&b.1));
-    output_facets.sort_unstable();
+    /// The number of facetings found.
+    pub faceting_count: usize,
 
-    let mut output_ridges = Vec::new();
-    for i in possible_facets_global {
-        let mut a = Vec::new();
-        for j in i {
-            a.push(j.0);
-        }
-        output_ridges.push(a);
-    }
-
-    return (output, f_counts, output_ridges, label_irc(&output_facets))
-}
-
-impl Concrete {
-    /// Enumerates the facetings of a polytope under a provided symmetry group or vertex map.
-    /// If the symmetry group is not provided, it uses the full symmetry of the polytope.
-    pub fn faceting(
-        &mut self,
-        vertices: Vec<Point<f64>>,
-        symmetry: GroupEnum,
-        any_single_edge_length: bool,
-        mut min_edge_length: Option<f64>,
-        mut max_edge_length: Option<f64>,
-        min_inradius: Option<f64>,
-        max_inradius: Option<f64>,
-        exclude_hemis: bool,
-        only_below_vertex: bool,
-        noble: Option<usize>,
-        max_per_hyperplane: Option<usize>,
-        uniform: bool,
-        include_compounds: bool,
-        mark_fissary: bool,
-        label_facets: bool,
-        save: bool,
-        save_facets: bool,
-        save_to_file: bool,
-        file_path: String
-    ) -> Vec<(Concrete, Option<String>)> {
-        let rank = self.rank();
-        let mut now = Instant::now();
-
-        if rank < 4 {
-            println!("\nFaceting polytopes of rank less than 3 is not supported!\n");
-            return Vec::new()
-        }
-
-        let mut vertices_ord = Vec::<PointOrd<f64>>::new();
-        for v in &vertices {
-            vertices_ord.push(PointOrd::new(v.clone()));
-        }
-
-        let vertex_map = match symmetry {
-            GroupEnum::ConcGroup(group) => {
-                println!("\nComputing vertex map...");
-                self.get_vertex_map(group)
-            },
-            GroupEnum::VertexMap(a) => a,
-            GroupEnum::Chiral(chiral) => {
-                if chiral {
-                    println!("\nComputing rotation symmetry group...");
-                    let g = self.get_rotation_group().unwrap();
-                    println!("Rotation symmetry order {}", g.0.count());
-                    g.1
-                }
-                else {
-                    println!("\nComputing symmetry group...");
-                    let g = self.get_symmetry_group().unwrap();
-                    println!("Symmetry order {}", g.0.count());
-                    g.1
-                }
-            },
-        };
+    /// The number of facetings left out by
+    /// [`FacetingOptions::exclude_compounds`], which aren't counted in
+    /// [`Self::faceting_count`].
+    pub excluded_compound_count: usize,
 
-        let mut output = Vec::new();
+    /// The number of facetings left out by
+    /// [`FacetingOptions::exclude_fissaries`], which aren't counted in
+    /// [`Self::faceting_count`].
+    pub excluded_fissary_count: usize,
 
-        println!("\nMatching vertices...");
+    /// The number of facetings left out by
+    /// [`FacetingOptions::dedup_congruent`] as congruent to one found before,
+    /// which aren't counted in [`Self::faceting_count`].
+    pub congruent_count: usize,
 
-        // Checking every r-tuple of vertices would take too long, so we put pairs into orbits first to reduce the number.
-        // I don't think we need to store the whole orbits at this point, but they might be useful if we want to improve the algorithm.
-        let mut vertex_orbits = Vec::new(); // Vec of orbits which are vecs of vertices.
-        let mut orbit_of_vertex = vec![0; vertices.len()]; // For each vertex stores its orbit index.
-        let mut checked_vertices = vec![false; vertices.len()]; // Stores whether we've already checked the vertex.
+    /// The number of facetings left out as made of the same facets as one
+    /// found before from other facet orbits, which aren't counted in
+    /// [`Self::faceting_count`].
+    pub duplicate_count: usize,
 
-        let mut orbit_idx = 0;
-        for v in 0..vertices.len() {
-            if !checked_vertices[v] {
-                // We found a new orbit of vertices.
-                let mut new_orbit = Vec::new();
-                for row in &vertex_map {
-                    // Find all vertices in the same orbit.
-                    let c = row[v];
-                    if !checked_vertices[c] {
-                        new_orbit.push(c);
-                        checked_vertices[c] = true;
-                        orbit_of_vertex[c] = orbit_idx;
-                    }
-                }
-                vertex_orbits.push(new_orbit);
-                orbit_idx += 1;
-            }
-        }
+    /// The number of combinations of facets checked while combining.
+    pub candidates_examined: u64,
 
-        println!("{} vertices in {} orbit{}", vertices.len(), orbit_idx, if orbit_idx == 1 {""} else {"s"});
+    /// The number of combinations of facets rejected by
+    /// [`FacetingOptions::candidate_callback`].
+    pub candidates_rejected: u64,
 
-        let mut possible_lengths_set = BTreeSet::<OrderedFloat<f64>>::new();
-        let mut possible_lengths = Vec::new();
+    /// The number of warnings logged, such as for a suspiciously small
+    /// symmetry group or inconsistent hyperplanes.
+    pub warning_count: usize,
 
-        if any_single_edge_length {
-            println!("\nComputing edge lengths...");
+    /// Whether the enumeration was cancelled before finishing.
+    pub cancelled: bool,
 
-            for orbit in &vertex_orbits {
-                let rep = orbit[0];
-                for i in rep+1..vertices.len() {
-                    possible_lengths_set.insert(OrderedFloat((vertices[rep].clone() - vertices[i].clone()).norm()));
-                }
-            }
-            let mut possible_lengths_ordf: Vec<&OrderedFloat<f64>> = possible_lengths_set.iter().collect();
-            possible_lengths_ordf.sort_unstable();
+    /// Whether the enumeration stopped after finding more facetings than
+    /// [`FacetingOptions::max_results`] allows. There are more facetings than
+    /// those returned exactly when this is set.
+    pub truncated: bool,
 
-            if possible_lengths_ordf.len() > 0 {
-                possible_lengths.push(possible_lengths_ordf[0].0);
-            }
-            for idx in 0..possible_lengths_ordf.len()-1 {
-                let len1 = possible_lengths_ordf[idx].0;
-                let len2 = possible_lengths_ordf[idx+1].0;
-                if len2-len1 > f64::EPS {
-                    possible_lengths.push(len2);
-                }
-            }
+    /// Whether the enumeration stopped because it ran past
+    /// [`FacetingOptions::time_limit`]. [`Self::cancelled`] is set along with
+    /// it.
+    pub timed_out: bool,
 
-            println!("Found {} edge lengths: {:?}", possible_lengths.len(), possible_lengths);
-        }
-        let mut edge_length_idx = 0;
-        
-        loop {
-            if any_single_edge_length {
-                let edge_length = possible_lengths[edge_length_idx];
-                min_edge_length = Some(edge_length);
-                max_edge_length = Some(edge_length);
-                println!("\nChecking edge length {} ({}/{})", edge_length, edge_length_idx+1, possible_lengths.len());
-            }
+    /// Where the enumeration was when it was cancelled or timed out.
+    pub stop_point: Option<StopPoint>,
 
-            println!("\nEnumerating hyperplanes...");
+    /// The edge length being faceted when the enumeration was cancelled or
+    /// timed out, if faceting for several of them.
+    pub stop_edge_length: Option<f64>,
 
-            let mut hyperplane_orbits = Vec::new();
+    /// The estimated number of vertex tuples checked when enumerating
+    /// hyperplanes, summed over all edge lengths.
+    pub estimated_tuples: u64,
 
-            if only_below_vertex {
-                for v_orbit in &vertex_orbits {
-                    let mut map = BTreeMap::<OrderedFloat<f64>, Vec<usize>>::new();
-                    let rep = v_orbit[0];
-                    let point = &vertices[rep];
+    /// The number of hyperplane orbits whose possible facets were carried over
+    /// from previous tables instead of being recomputed, when extending them
+    /// with [`FacetingTables::extend_with_vertices_with_stats`].
+    pub reused_hyperplane_orbit_count: usize,
 
-                    for (idx, vertex) in vertices.iter().enumerate() {
-                        let dot = OrderedFloat(vertex.dot(point));
-                        if let Some(list) = map.get_mut(&dot) {
-                            list.push(idx);
-                        } else {
-                            map.insert(dot, vec![idx]);
-                        }
-                    }
-                    
-                    let mut checked = HashSet::new();
+    /// The number of hyperplane orbits whose possible facets were relabeled
+    /// from those of an earlier orbit with congruent vertices, when
+    /// [`FacetingOptions::memoize_subdim`] is set.
+    pub memoized_hyperplane_orbit_count: usize,
 
-                    let mut dbg_count: u64 = 0;
+    /// The number of possible facets kept as standalone polytopes, when
+    /// [`FacetingOptions::collect_subfacetings`] is set.
+    pub subfaceting_count: usize,
 
-                    'd: for (_dot, l) in &map {
-                        let mut list = l.clone();
-                        list.sort_unstable();
+    /// The estimated number of bytes taken up by these polytopes.
+    pub subfaceting_bytes: usize,
 
-                        if now.elapsed().as_millis() > DELAY {
-                            print!("{}loop {}, verts {:?}", CL, dbg_count, list);
-                            std::io::stdout().flush().unwrap();
-                            now = Instant::now();
-                        }
-                        dbg_count += 1;
-
-                        // WLOG checks if the vertices are all the right distance away from the first vertex.
-                        for v in &list[1..] {
-                            let edge_length = (&vertices[*v]-&vertices[list[0]]).norm();
-                            if let Some(min) = min_edge_length {
-                                if edge_length < min - f64::EPS {
-                                    continue 'd;
-                                }
-                            }
-                            if let Some(max) = max_edge_length {
-                                if edge_length > max + f64::EPS {
-                                    continue 'd;
-                                }
-                            }
-                        }
+    /// The number of facetings built and scored for
+    /// [`FacetingOptions::objective`].
+    pub scored_count: usize,
 
-                        // We define a hyperplane from the list of vertices.
-                        let points = list.iter().map(|x| &vertices[*x]);
+    /// The number of candidates pruned by the bound of
+    /// [`FacetingOptions::objective`].
+    pub bound_pruned: u64,
 
-                        let hyperplane = Subspace::from_points(points);
+    /// Time spent enumerating hyperplanes.
+    pub hyperplane_time: Duration,
 
-                        if hyperplane.is_hyperplane() {
-                            let inradius = hyperplane.distance(&Point::zeros(self.dim().unwrap()));
-                            if let Some(min) = min_inradius {
-                                if inradius < min - f64::EPS {
-                                    continue
-                                }
-                            }
-                            if let Some(max) = max_inradius {
-                                if inradius > max + f64::EPS {
-                                    continue
-                                }
-                            }
-                            if exclude_hemis {
-                                if inradius.abs() < f64::EPS {
-                                    continue
-                                }
-                            }
+    /// Time spent faceting the hyperplanes.
+    pub facet_time: Duration,
 
-                            let mut hyperplane_vertices = Vec::new();
-                            for (idx, v) in vertices.iter().enumerate() {
-                                if hyperplane.distance(&v) < f64::EPS {
-                                    hyperplane_vertices.push(idx);
-                                }
-                            }
-                            hyperplane_vertices.sort_unstable();
+    /// Time spent computing the ridges.
+    pub ridge_time: Duration,
 
-                            // Check if the hyperplane has been found already.
-                            let mut is_new = true;
-                            let mut counting = HashSet::<Vec<usize>>::new();
-                            for row in &vertex_map {
-                                let mut new_hp_v = Vec::new();
-                                for idx in &hyperplane_vertices {
-                                    new_hp_v.push(row[*idx]);
-                                }
-                                new_hp_v.sort_unstable();
+    /// Time spent combining facets.
+    pub combine_time: Duration,
 
-                                if checked.contains(&new_hp_v) {
-                                    is_new = false;
-                                    break
-                                }
+    /// Time spent building the facetings.
+    pub build_time: Duration,
+}
 
-                                counting.insert(new_hp_v);
-                            }
-                            if is_new {
-                                checked.insert(hyperplane_vertices.clone());
-                                hyperplane_orbits.push((hyperplane, hyperplane_vertices, counting.len()));
-                            }
-                        }
-                    }
+impl Display for FacetingStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} vertices in {} orbits, group {}order {}{}",
+            self.vertex_count,
+            self.vertex_orbit_count,
+            match &self.group_name {
+                Some(name) => format!("{} of ", name),
+                None => String::new(),
+            },
+            self.group_order,
+            if self.central_symmetry {", centrally symmetric"} else {""}
+        )?;
+        if let Some(conditioning) = self.conditioning {
+            writeln!(f, "conditioning: {}", conditioning)?;
+        }
+        writeln!(f, "{} hyperplanes in {} orbits{} ({:.2?})",
+            self.hyperplane_count,
+            self.hyperplane_orbit_count,
+            if self.depth_filtered > 0 {
+                format!(", {} orbits removed by depth", self.depth_filtered)
+            } else {
+                String::new()
+            },
+            self.hyperplane_time
+        )?;
+        if let Some(tolerance) = self.retry_tolerance {
+            writeln!(f, "consistent hyperplanes at tolerance {:e}", tolerance)?;
+        }
+        if let Some(inconsistency) = self.first_hyperplane_inconsistency {
+            writeln!(f, "warning: {} inconsistencies in the hyperplanes, the first being that {}",
+                self.hyperplane_inconsistency_count,
+                inconsistency
+            )?;
+        }
+        if self.merge_candidate_count > 0 {
+            writeln!(f, "{} hyperplane orbits possibly split by the tolerance{}",
+                self.merge_candidate_count,
+                if self.merged_orbit_count > 0 {
+                    format!(", {} merged", self.merged_orbit_count)
+                } else {
+                    String::new()
                 }
+            )?;
+        }
+        writeln!(f, "{} possible facets{}{}{} ({:.2?})",
+            self.possible_facet_count,
+            if self.degenerate_facet_count > 0 {
+                format!(", {} degenerate", self.degenerate_facet_count)
+            } else {
+                String::new()
+            },
+            if self.reused_hyperplane_orbit_count > 0 {
+                format!(", {} hyperplane orbits reused", self.reused_hyperplane_orbit_count)
+            } else {
+                String::new()
+            },
+            if self.memoized_hyperplane_orbit_count > 0 {
+                format!(", {} congruent hyperplane orbits memoized", self.memoized_hyperplane_orbit_count)
+            } else {
+                String::new()
+            },
+            self.facet_time
+        )?;
+        writeln!(f, "{} ridge orbits ({:.2?})", self.ridge_orbit_count, self.ridge_time)?;
+        if self.subfaceting_count > 0 {
+            writeln!(f, "{} subfacetings collected (~{:.1} KiB)", self.subfaceting_count, self.subfaceting_bytes as f64 / 1024.0)?;
+        }
+        writeln!(f, "{} facetings{}{}{}{}{} from {} candidates ({:.2?} combining, {:.2?} building)",
+            self.faceting_count,
+            if self.congruent_count > 0 {
+                format!(" up to congruence ({} in all)", self.faceting_count + self.congruent_count)
+            } else {
+                String::new()
+            },
+            if self.timed_out {" (timed out)"} else if self.cancelled {" (cancelled)"} else if self.truncated {" (truncated)"} else {""},
+            if self.excluded_compound_count > 0 {
+                format!(", {} compounds excluded", self.excluded_compound_count)
+            } else {
+                String::new()
+            },
+            if self.excluded_fissary_count > 0 {
+                format!(", {} fissaries excluded", self.excluded_fissary_count)
+            } else {
+                String::new()
+            },
+            if self.duplicate_count > 0 {
+                format!(
+                    ", {} duplicate{} removed",
+                    self.duplicate_count,
+                    if self.duplicate_count == 1 { "" } else { "s" }
+                )
+            } else {
+                String::new()
+            },
+            self.candidates_examined,
+            self.combine_time,
+            self.build_time
+        )?;
+        if let Some(stop_point) = self.stop_point {
+            write!(f, "stopped while {}", stop_point)?;
+            if let Some(edge_length) = self.stop_edge_length {
+                write!(f, " for edge length {}", edge_length)?;
             }
-            else {
-
-                // Enumerate edges
-
-                let mut pair_orbits = Vec::new();
-                let mut checked = vec![vec![false; vertices.len()]; vertices.len()];
-                
-                for orbit in &vertex_orbits {
-                    let rep = orbit[0]; // We only need one representative per orbit.
-                    for vertex in rep+1..vertices.len() {
-                        if now.elapsed().as_millis() > DELAY {
-                            print!("{}{} edge orbits, verts [{}, {}]", CL, pair_orbits.len(), rep, vertex);
-                            std::io::stdout().flush().unwrap();
-                            now = Instant::now();
-                        }
-
-                        if !checked[rep][vertex] {
-                            let edge_length = (&vertices[vertex]-&vertices[rep]).norm();
-                            if let Some(min) = min_edge_length {
-                                if edge_length < min - f64::EPS {
-                                    continue;
-                                }
-                            }
-                            if let Some(max) = max_edge_length {
-                                if edge_length > max + f64::EPS {
-                                    continue;
-                                }
-                            }
-                            let mut new_orbit = Vec::new();
-                            for row in &vertex_map {
-                                let (a1, a2) = (row[rep], row[vertex]);
-                                let c1 = a1.min(a2);
-                                let c2 = a1.max(a2);
-                                
-                                if !checked[c1][c2] {
-                                    new_orbit.push(vec![c1, c2]);
-                                    checked[c1][c2] = true;
-                                }
-                            }
-                            pair_orbits.push(new_orbit);
-                        }
-                    }
-                }
-
-                println!("{}{} edge orbit{}", CL, pair_orbits.len(), if pair_orbits.len() == 1 {""} else {"s"});
-
-                // Enumerate subspaces between lines and hyperplanes
-
-                let mut tuple_orbits: Vec<Vec<usize>> = pair_orbits.iter().map(|orbit| orbit[0].clone()).collect();
-                for number in 3..rank-1 {
-                    let mut checked = HashSet::new();
-                    let mut new_tuple_orbits = Vec::new();
-
-                    for tuple in tuple_orbits {
-                        for new_vertex in tuple[tuple.len()-1]..vertices.len() {
-                            if now.elapsed().as_millis() > DELAY {
-                                print!("{}{} {}-plane orbits, verts {:?}", CL, new_tuple_orbits.len(), number-1, tuple);
-                                std::io::stdout().flush().unwrap();
-                                now = Instant::now();
-                            }
+            writeln!(f)?;
+        }
+        if self.warning_count > 0 {
+            writeln!(f, "{} warning{} logged", self.warning_count, if self.warning_count == 1 {""} else {"s"})?;
+        }
+        Ok(())
+    }
+}
 
-                            let mut wrong_edge = false;
+impl FacetingStats {
+    /// Returns whether every faceting was found, that is, whether the
+    /// enumeration wasn't cancelled, timed out or truncated.
+    pub fn is_exhaustive(&self) -> bool {
+        !self.cancelled && !self.timed_out && !self.truncated
+    }
 
-                            let edge_length = (&vertices[tuple[0]]-&vertices[new_vertex]).norm();
-                            if let Some(min) = min_edge_length {
-                                if edge_length < min - f64::EPS {
-                                    wrong_edge = true;
-                                }
-                            }
-                            if let Some(max) = max_edge_length {
-                                if edge_length > max + f64::EPS {
-                                    wrong_edge = true;
-                                }
-                            }
-                            if wrong_edge {
-                                continue;
-                            }
+    /// Marks the enumeration as cancelled at the given point, unless it was
+    /// already stopped somewhere.
+    fn stop_at(&mut self, stop_point: StopPoint) {
+        self.cancelled = true;
+        self.stop_point.get_or_insert(stop_point);
+    }
+}
 
-                            let mut new_tuple = tuple.clone();
-                            new_tuple.push(new_vertex);
+/// Where an enumeration was when it was cancelled or ran out of time, see
+/// [`FacetingStats::stop_point`].
+///
+/// Everything before this point was searched exhaustively. The search for
+/// facetings can be picked up from where it stopped with
+/// [`Concrete::faceting_resume`] if [`FacetingOptions::checkpoint`] was set.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StopPoint {
+    /// While enumerating the hyperplanes. No facets were found.
+    Hyperplanes,
+
+    /// While faceting the hyperplanes. Only the hyperplane orbits before the
+    /// given one were faceted exhaustively.
+    FacetTypes {
+        /// The index of the hyperplane orbit being faceted.
+        hyperplane_orbit: usize,
+    },
+
+    /// While combining the facets. The hyperplane orbits are tried one at a
+    /// time as the first facet of the combinations, so that those tried
+    /// before this one were searched exhaustively.
+    Combining {
+        /// The hyperplane orbit of the first facet of the combination being
+        /// checked, if any.
+        hyperplane_orbit: Option<usize>,
+    },
+}
 
-                            let mut already_seen = false;
-                            for row in &vertex_map {
-                                let mut moved: Vec<usize> = new_tuple.iter().map(|x| row[*x]).collect();
-                                moved.sort_unstable();
+impl Display for StopPoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Hyperplanes => write!(f, "enumerating hyperplanes"),
+            Self::FacetTypes { hyperplane_orbit } => {
+                write!(f, "faceting hyperplane orbit {}", hyperplane_orbit)
+            }
+            Self::Combining { hyperplane_orbit: Some(hyperplane_orbit) } => {
+                write!(f, "combining facets starting in hyperplane orbit {}", hyperplane_orbit)
+            }
+            Self::Combining { hyperplane_orbit: None } => write!(f, "combining facets"),
+        }
+    }
+}
 
-                                if checked.contains(&moved) {
-                                    already_seen = true;
-                                    break;
-                                }
-                            }
-                            if already_seen {
-                                continue;
-                            }
+/// The estimated number of vertex tuples above which we warn that the faceting
+/// is probably infeasible.
+const TUPLE_WARNING: u64 = 100_000_000;
 
-                            new_tuple.sort_unstable();
+/// The orbits of the vertices under a symmetry group, along with an estimate of
+/// how many vertex tuples need to be checked to find all hyperplanes.
+#[derive(Clone, Debug)]
+pub struct OrbitStructure {
+    /// The order of the symmetry group.
+    pub group_order: usize,
 
-                            let subspace = Subspace::from_points(new_tuple.iter().map(|x| &vertices[*x]));
-                            if subspace.rank() == number-1 {
-                                new_tuple_orbits.push(new_tuple.clone());
-                            }
+    /// The size of each vertex orbit.
+    pub orbit_sizes: Vec<usize>,
 
-                            checked.insert(new_tuple);
-                        }
-                    }
-                    println!("{}{} {}-plane orbit{}", CL, new_tuple_orbits.len(), number-1, if new_tuple_orbits.len() == 1 {""} else {"s"});
-                    tuple_orbits = new_tuple_orbits.iter().map(|x| x.clone()).collect();
-                }
+    /// The estimated number of orbits of vertex tuples spanning a hyperplane.
+    /// Only the vertices at an allowed edge length from the first vertex of a
+    /// tuple are considered, just as when enumerating the hyperplanes.
+    pub estimated_tuples: u64,
+}
 
-                // Enumerate hyperplanes
-                let mut checked = HashSet::new();
+impl OrbitStructure {
+    /// Computes the orbit structure of some vertices under a vertex map, with
+    /// the edge lengths restricted to the given range.
+    pub fn new(
+        vertices: &[Point<f64>],
+        vertex_map: &[VertexPermutation],
+        min_edge_length: Option<f64>,
+        max_edge_length: Option<f64>,
+    ) -> Self {
+        let filter = EdgeLengthFilter::new(min_edge_length, max_edge_length, Vec::new());
+        Self::with_filter(vertices, vertex_map, &filter)
+    }
 
-                for rep in tuple_orbits {
-                    let last_vert = rep[rep.len()-1];
+    /// Computes the orbit structure of some vertices under a vertex map, with
+    /// the edge lengths restricted by a filter.
+    pub fn with_filter(vertices: &[Point<f64>], vertex_map: &[VertexPermutation], filter: &EdgeLengthFilter) -> Self {
+        // Repeated rows don't make the group any bigger.
+        let group_order = vertex_map.iter().collect::<HashSet<_>>().len().max(1);
+        let dim = vertices.first().map_or(0, |v| v.len());
+        let mut orbit_sizes = Vec::new();
+        let mut estimate = 0.0;
+
+        for orbit in VertexOrbit::orbits(vertices.len(), vertex_map) {
+            let rep = orbit.representative();
+            let size = orbit.len();
+            orbit_sizes.push(size);
+
+            // The vertices that can share a facet with the representative.
+            let neighbors = vertices
+                .iter()
+                .enumerate()
+                .filter(|(idx, v)| *idx != rep && filter.contains((*v - &vertices[rep]).norm()))
+                .count();
+
+            // Every tuple through the representative is copied to the rest of
+            // the orbit, and the group identifies the copies.
+            estimate += binomial(neighbors, dim.saturating_sub(1)) * size as f64 / group_order as f64;
+        }
 
-                    for new_vertex in last_vert+1..vertices.len() {
-                        let mut tuple = rep.clone();
-                        tuple.push(new_vertex);
+        Self {
+            group_order,
+            orbit_sizes,
+            estimated_tuples: estimate.ceil() as u64,
+        }
+    }
 
-                        if now.elapsed().as_millis() > DELAY {
-                            print!("{}{} hyperplane orbits, verts {:?}", CL, hyperplane_orbits.len(), tuple);
-                            std::io::stdout().flush().unwrap();
-                            now = Instant::now();
-                        }
+    /// Returns whether the faceting is likely to finish in a reasonable time.
+    pub fn is_feasible(&self) -> bool {
+        self.estimated_tuples <= TUPLE_WARNING
+    }
 
-                        let edge_length = (&vertices[new_vertex]-&vertices[rep[0]]).norm();
-                        if let Some(min) = min_edge_length {
-                            if edge_length < min - f64::EPS {
-                                continue;
-                            }
-                        }
-                        if let Some(max) = max_edge_length {
-                            if edge_length > max + f64::EPS {
-                                continue;
-                            }
-                        }
+    /// Returns an error if the estimated number of tuples exceeds a limit.
+    pub fn check(&self, limit: Option<u64>) -> Result<(), FacetingError> {
+        match limit {
+            Some(limit) if self.estimated_tuples > limit => Err(FacetingError::TooManyTuples {
+                estimated: self.estimated_tuples,
+                limit,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
 
-                        let mut points = Vec::new();
-                        for v in tuple {
-                            points.push(vertices[v].clone());
-                        }
+impl Display for OrbitStructure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Orbits of the same size are grouped together, so that inputs with
+        // many orbits stay readable.
+        let mut sizes = BTreeMap::new();
+        for size in &self.orbit_sizes {
+            *sizes.entry(*size).or_insert(0) += 1;
+        }
 
-                        let hyperplane = Subspace::from_points(points.iter());
+        write!(f, "Vertex orbit sizes:")?;
+        for (size, count) in sizes {
+            if count == 1 {
+                write!(f, " {}", size)?;
+            } else {
+                write!(f, " {}x{}", size, count)?;
+            }
+        }
+        write!(f, "\nAbout {} vertex tuples to check", self.estimated_tuples)?;
 
-                        if hyperplane.is_hyperplane() {
-                            let inradius = hyperplane.distance(&Point::zeros(self.dim().unwrap()));
-                            if let Some(min) = min_inradius {
-                                if inradius < min - f64::EPS {
-                                    break
-                                }
-                            }
-                            if let Some(max) = max_inradius {
-                                if inradius > max + f64::EPS {
-                                    break
-                                }
-                            }
-                            if exclude_hemis {
-                                if inradius.abs() < f64::EPS {
-                                    break
-                                }
-                            }
+        if !self.is_feasible() {
+            write!(f, "\nWarning: this will probably take too long, consider restricting the edge lengths")?;
+        }
+        Ok(())
+    }
+}
 
-                            let mut hyperplane_vertices = Vec::new();
-                            for (idx, v) in vertices.iter().enumerate() {
-                                if hyperplane.distance(&v) < f64::EPS {
-                                    hyperplane_vertices.push(idx);
-                                }
-                            }
-                            hyperplane_vertices.sort_unstable();
+/// Returns the binomial coefficient `n` choose `k` as a float, so that it can
+/// be very large.
+fn binomial(n: usize, k: usize) -> f64 {
+    if k > n {
+        return 0.0;
+    }
 
-                            // Check if the hyperplane has been found already.
-                            let mut is_new = true;
-                            let mut counting = HashSet::<Vec<usize>>::new();
-                            for row in &vertex_map {
-                                let mut new_hp_v = Vec::new();
-                                for idx in &hyperplane_vertices {
-                                    new_hp_v.push(row[*idx]);
-                                }
-                                new_hp_v.sort_unstable();
+    (0..k).fold(1.0, |acc, i| acc * (n - i) as f64 / (i + 1) as f64)
+}
 
-                                if checked.contains(&new_hp_v) {
-                                    is_new = false;
-                                    break;
-                                }
+/// An error that stops the faceting algorithm before it starts.
+#[derive(Clone, Copy, Debug)]
+pub enum FacetingError {
+    /// The estimated number of vertex tuples exceeds the limit set by
+    /// [`FacetingOptions::abort_if_estimated_tuples_exceed`].
+    TooManyTuples {
+        /// The estimated number of tuples.
+        estimated: u64,
+
+        /// The maximum number of tuples allowed.
+        limit: u64,
+    },
+
+    /// A facet passed to [`FacetingTables::complete`] doesn't exist.
+    InvalidFacet {
+        /// The hyperplane orbit of the facet.
+        hyperplane_orbit: usize,
+
+        /// The index of the facet among the possible facets of the hyperplane
+        /// orbit.
+        facet: usize,
+    },
+
+    /// Two facets passed to [`FacetingTables::complete`] come from the same
+    /// hyperplane orbit.
+    RepeatedHyperplaneOrbit(usize),
+
+    /// The facets passed to [`FacetingTables::complete`] cover the ridges of
+    /// some orbit more times than the ridge multiplicity.
+    OverCoveredRidge {
+        /// The index of the ridge orbit.
+        ridge_orbit: usize,
+    },
+
+    /// The symmetry group computed for the polytope is much smaller than the
+    /// distances between its vertices suggest, which usually means that the
+    /// coordinates are too imprecise. See
+    /// [`FacetingOptions::allow_trivial_group`].
+    TrivialGroup {
+        /// The order of the symmetry group computed.
+        group_order: usize,
+
+        /// The upper bound on the order given by the distances between the
+        /// vertices.
+        automorphism_bound: u64,
+
+        /// The greatest difference between the distances of two vertices that
+        /// could be swapped by a symmetry to the others.
+        residual: f64,
+    },
+
+    /// No two vertices are at an allowed edge length, so there are no
+    /// facetings at all. See [`FacetingOptions::allow_vacuous_edge_lengths`].
+    NoAllowedEdges {
+        /// The only edge length allowed, if there's just one.
+        requested: Option<f64>,
+
+        /// The tolerance the edge lengths were compared with.
+        tolerance: f64,
+
+        /// The distances between vertices that are closest to being allowed,
+        /// from the closest one.
+        closest: [Option<f64>; 3],
+    },
+
+    /// The polytope has a rank below [`MIN_FACETING_RANK`].
+    UnsupportedRank(Rank),
+
+    /// Facetings were asked for as polytopes, but the ridge multiplicity isn't
+    /// 2, so they're complexes instead. See [`FacetingTables::complexes`].
+    NotPolytopes {
+        /// The ridge multiplicity asked for.
+        ridge_multiplicity: usize,
+    },
+
+    /// The ridge multiplicity is 0, which no set of facets has.
+    ZeroRidgeMultiplicity,
+
+    /// The hyperplanes were filtered by [`DepthFilter::RelativeToInsphere`],
+    /// but the polytope has no insphere radius.
+    NoInsphere,
+
+    /// The symmetry group of the polytope couldn't be computed.
+    NoSymmetryGroup,
+
+    /// The vertex map of the symmetry group isn't that of a group acting on
+    /// the vertices.
+    InvalidVertexMap(VertexMapError),
+
+    /// The facetings could have more elements than the limits set by
+    /// [`FacetingOptions::element_limits`] allow.
+    TooLarge(TooLarge),
+
+    /// A hyperplane given in [`FacetingOptions::hyperplanes`] goes through a
+    /// vertex that doesn't exist.
+    HyperplaneVertexOutOfRange {
+        /// The index of the hyperplane in the list.
+        hyperplane: usize,
+
+        /// The index of the vertex.
+        vertex: usize,
+
+        /// The number of vertices.
+        vertex_count: usize,
+    },
+
+    /// The vertices given for a hyperplane in [`FacetingOptions::hyperplanes`]
+    /// don't span a hyperplane, either because there's too few of them or
+    /// they're on a smaller subspace, or because they aren't coplanar.
+    InvalidHyperplane {
+        /// The index of the hyperplane in the list.
+        hyperplane: usize,
+
+        /// The rank of the subspace the vertices span, which is 0 if there
+        /// are none.
+        rank: usize,
+
+        /// The rank of a hyperplane.
+        expected: usize,
+    },
+
+    /// A vertex given in [`FacetingOptions::vertex_subset`] doesn't exist.
+    SubsetVertexOutOfRange {
+        /// The index of the vertex.
+        vertex: usize,
+
+        /// The number of vertices.
+        vertex_count: usize,
+    },
+
+    /// A vertex orbit given in [`FacetingOptions::vertex_subset`] doesn't
+    /// exist.
+    SubsetOrbitOutOfRange {
+        /// The index of the vertex orbit.
+        orbit: usize,
+
+        /// The number of vertex orbits.
+        orbit_count: usize,
+    },
+
+    /// No vertices were given in [`FacetingOptions::vertex_subset`].
+    EmptyVertexSubset,
+}
 
-                                counting.insert(new_hp_v);
-                            }
-                            if is_new {
-                                checked.insert(hyperplane_vertices.clone());
-                                hyperplane_orbits.push((hyperplane, hyperplane_vertices, counting.len()));
-                            }
-                        }
-                    }
+impl Display for FacetingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::TooManyTuples { estimated, limit } => write!(
+                f,
+                "about {} vertex tuples would have to be checked, which exceeds the limit of {}",
+                estimated, limit
+            ),
+            Self::InvalidFacet { hyperplane_orbit, facet } => write!(
+                f,
+                "hyperplane orbit {} has no possible facet {}",
+                hyperplane_orbit, facet
+            ),
+            Self::RepeatedHyperplaneOrbit(hyperplane_orbit) => write!(
+                f,
+                "more than one facet was given from hyperplane orbit {}",
+                hyperplane_orbit
+            ),
+            Self::OverCoveredRidge { ridge_orbit } => write!(
+                f,
+                "the ridges of orbit {} are covered too many times",
+                ridge_orbit
+            ),
+            Self::TrivialGroup { group_order, automorphism_bound, residual } => write!(
+                f,
+                "the symmetry group found has order {}, but the distances between the vertices allow up to {} \
+                symmetries if they're off by up to {:e}, so the coordinates are probably too imprecise",
+                group_order, automorphism_bound, residual
+            ),
+            Self::NoAllowedEdges { requested, tolerance, closest } => {
+                match requested {
+                    Some(length) => write!(f, "no two vertices are {} apart", length)?,
+                    None => write!(f, "no two vertices are at an allowed edge length")?,
                 }
-            }
+                write!(f, " within a tolerance of {:e}", tolerance)?;
 
-            let mut sum: u64 = 0;
-            let mut f_counts = Vec::new();
-            for orbit in &hyperplane_orbits {
-                let count = orbit.2;
-                f_counts.push(count);
-                sum += count as u64;
+                let closest: Vec<_> = closest.iter().flatten().map(ToString::to_string).collect();
+                if !closest.is_empty() {
+                    write!(f, "; the closest distances between them are {}", closest.join(", "))?;
+                }
+                Ok(())
             }
+            Self::UnsupportedRank(rank) => write!(
+                f,
+                "only polytopes of rank {} and up can be faceted, but this one has rank {}",
+                MIN_FACETING_RANK, rank
+            ),
+            Self::NotPolytopes { ridge_multiplicity } => write!(
+                f,
+                "with every ridge in {} facets, the facetings aren't polytopes",
+                ridge_multiplicity
+            ),
+            Self::ZeroRidgeMultiplicity => write!(f, "the ridge multiplicity must be positive"),
+            Self::NoInsphere => write!(
+                f,
+                "the depths can't be compared to the insphere, as some facet of the polytope isn't flat"
+            ),
+            Self::NoSymmetryGroup => write!(
+                f,
+                "the symmetry group couldn't be computed, as every facet of the polytope passes through the origin"
+            ),
+            Self::InvalidVertexMap(err) => write!(f, "invalid vertex map: {}", err),
+            Self::TooLarge(err) => write!(f, "{}", err),
+            Self::HyperplaneVertexOutOfRange { hyperplane, vertex, vertex_count } => write!(
+                f,
+                "hyperplane {} goes through vertex {}, but there's only {} vertices",
+                hyperplane, vertex, vertex_count
+            ),
+            Self::InvalidHyperplane { hyperplane, rank, expected } => write!(
+                f,
+                "the vertices given for hyperplane {} span a subspace of rank {}, but a hyperplane has rank {}",
+                hyperplane, rank, expected
+            ),
+            Self::SubsetVertexOutOfRange { vertex, vertex_count } => write!(
+                f,
+                "the vertex subset contains vertex {}, but there's only {} vertices",
+                vertex, vertex_count
+            ),
+            Self::SubsetOrbitOutOfRange { orbit, orbit_count } => write!(
+                f,
+                "the vertex subset contains vertex orbit {}, but there's only {} orbits",
+                orbit, orbit_count
+            ),
+            Self::EmptyVertexSubset => write!(f, "the vertex subset is empty"),
+        }
+    }
+}
 
-            println!("{}{} hyperplanes in {} orbit{}", CL, sum, hyperplane_orbits.len(), if hyperplane_orbits.len() == 1 {""} else {"s"});
+impl std::error::Error for FacetingError {}
 
-            println!("\nFaceting hyperplanes...");
+impl From<VertexMapError> for FacetingError {
+    fn from(err: VertexMapError) -> Self {
+        Self::InvalidVertexMap(err)
+    }
+}
 
-            // Facet the hyperplanes
-            let mut possible_facets = Vec::new();
-            let mut possible_facets_global: Vec<Vec<(Ranks, Vec<(usize,usize)>)>> = Vec::new(); // copy of above but with global vertex indices
-            let mut compound_facets: Vec<HashMap<usize, (usize,usize)>> = Vec::new();
-            let mut ridges: Vec<Vec<Vec<Ranks>>> = Vec::new();
-            let mut ff_counts = Vec::new();
+impl From<TooLarge> for FacetingError {
+    fn from(err: TooLarge) -> Self {
+        Self::TooLarge(err)
+    }
+}
 
-            for (idx, orbit) in hyperplane_orbits.iter().enumerate() {
-                let (hp, hp_v) = (orbit.0.clone(), orbit.1.clone());
-                let mut stabilizer = Vec::new();
-                for row in &vertex_map {
-                    let mut slice = Vec::new();
-                    for v in &hp_v {
-                        slice.push(row[*v]);
-                    }
-                    let mut slice_sorted = slice.clone();
-                    slice_sorted.sort_unstable();
+/// An error returned when a faceting can't be rebuilt from its facet orbits
+/// by [`FacetingTables::build_single`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReplayError {
+    /// No facets were given.
+    NoFacets,
+
+    /// A facet doesn't exist in these tables, so the facets must have been
+    /// recorded from other ones.
+    InvalidFacet {
+        /// The hyperplane orbit of the facet.
+        hyperplane_orbit: usize,
+
+        /// The index of the facet among the possible facets of the hyperplane
+        /// orbit.
+        facet: usize,
+    },
+
+    /// The facets exist, but the ridges of some orbit aren't each covered
+    /// twice or not at all by them. This means the tables differ from the ones
+    /// the facets were recorded from.
+    OpenRidges {
+        /// The index of the ridge orbit.
+        ridge_orbit: usize,
+
+        /// The number of times each ridge of the orbit is covered.
+        coverage: usize,
+    },
+
+    /// The facets cover every ridge twice, but don't make up a dyadic
+    /// polytope. This also means the tables differ from the original ones.
+    NotDyadic,
+}
 
-                    if slice_sorted == hp_v {
-                        stabilizer.push(slice.clone());
-                    }
-                }
+impl Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::NoFacets => write!(f, "no facets were given"),
+            Self::InvalidFacet { hyperplane_orbit, facet } => write!(
+                f,
+                "hyperplane orbit {} has no possible facet {} in these tables",
+                hyperplane_orbit, facet
+            ),
+            Self::OpenRidges { ridge_orbit, coverage } => write!(
+                f,
+                "the ridges of orbit {} are covered {} times instead of twice, so the tables differ from the original ones",
+                ridge_orbit, coverage
+            ),
+            Self::NotDyadic => write!(
+                f,
+                "the facets don't make up a dyadic polytope, so the tables differ from the original ones"
+            ),
+        }
+    }
+}
 
-                // Converts global vertex indices to local ones.
-                let mut map_back = BTreeMap::new();
-                for (idx, el) in stabilizer[0].iter().enumerate() {
-                    map_back.insert(*el, idx);
-                }
-                let mut new_stabilizer = stabilizer.clone();
-        
-                for a in 0..stabilizer.len() {
-                    for b in 0..stabilizer[a].len() {
-                        new_stabilizer[a][b] = *map_back.get(&stabilizer[a][b]).unwrap();
-                    }
-                }
-                
-                let mut points = Vec::new();
-                for v in &hp_v {
-                    points.push(vertices_ord[*v].clone());
-                }
+impl std::error::Error for ReplayError {}
 
-                let noble_package = if noble == Some(1) {
-                    Some((&vertex_map, &hp_v, orbit.2))
-                } else {
-                    None
-                };
+/// The lowest rank of the polytopes that can be faceted, that of the
+/// polyhedra.
+pub const MIN_FACETING_RANK: Rank = Rank::of_dim(3);
 
-                let (possible_facets_row, ff_counts_row, ridges_row, compound_facets_row) =
-                    faceting_subdim(rank-1, hp, points, new_stabilizer, min_edge_length, max_edge_length, max_per_hyperplane, uniform, noble_package, true);
-
-                let mut possible_facets_global_row = Vec::new();
-                for f in &possible_facets_row {
-                    let mut new_f = f.clone();
-                    let mut new_edges = ElementList::new();
-                    for v in f.0[2].clone() {
-                        // Converts indices back to global
-                        let mut new_edge = Element::new(vec![].into(), vec![].into());
-                        for s in v.subs {
-                            new_edge.subs.push(hp_v[s]);
-                        }
-                        new_edges.push(new_edge);
-                    }
-                    new_f.0[2] = new_edges;
+const DELAY: u128 = 200;
 
-                    possible_facets_global_row.push(new_f);
-                }
-                possible_facets.push(possible_facets_row.clone());
-                possible_facets_global.push(possible_facets_global_row);
-                compound_facets.push(compound_facets_row);
-                ridges.push(ridges_row);
-                ff_counts.push(ff_counts_row);
+/// How far a vertex of an output facet may be from the hull of the others
+/// before we consider the faceting broken.
+const PLANARITY_TOLERANCE: f64 = 1e-5;
 
-                println!("{}{}: {} facets, {} verts, {} copies", CL, idx, possible_facets_row.len(), hp_v.len(), orbit.2);
-                std::io::stdout().flush().unwrap();
-            }
+/// The relative tolerance used to recognize the uniform polytopes among the
+/// facetings.
+const UNIFORM_TOLERANCE: f64 = 1e-6;
 
-            println!("\nComputing ridges...");
+/// The relative tolerance used to compare the distances between vertices when
+/// checking the symmetry group.
+const SPECTRUM_TOLERANCE: f64 = 1e-3;
 
-            let mut ridge_idx_orbits = Vec::new();
-            let mut ridge_orbits = HashMap::new();
-            let mut ridge_counts = Vec::new(); // Counts the number of ridges in each orbit
-            let mut orbit_idx = 0;
+impl Ranks {
+    /// Sorts some stuff in a way that's useful for the faceting algorithm.
+    pub fn element_sort_strong(&mut self) {
+        for el in 0..self[2].len() {
+            self[2][el].subs.sort_unstable();
+        }
 
-            for (hp_i, ridges_row) in ridges.iter_mut().enumerate() {
-                let mut r_i_o_row = Vec::new();
+        for rank in 2..self.len()-1 {
+            let mut all_subs = Vec::new();
+            for el in &self[rank] {
+                all_subs.push(el.subs.clone());
+            }
+            let mut sorted = all_subs.clone();
+            sorted.sort_unstable();
 
-                for ridges_row_row in ridges_row {
-                    let mut r_i_o_row_row = Vec::new();
+            let mut perm = Vec::new();
+            for i in &all_subs {
+                perm.push(sorted.iter().position(|x| x == i).unwrap());
+            }
 
-                    for ridge in ridges_row_row {
-                        // goes through all the ridges
+            for i in 0..self[rank].len() {
+                self[rank][i].subs = sorted[i].clone();
+                self[rank][i].subs.sort_unstable();
+            }
 
-                        // globalize
-                        let mut new_list = ElementList::new();
-                        for i in 0..ridge[2].len() {
-                            let mut new = Element::new(Subelements::new(), Superelements::new());
-                            for sub in &ridge[2][i].subs {
-                                new.subs.push(hyperplane_orbits[hp_i].1[*sub])
-                            }
-                            new_list.push(new);
-                        }
-                        ridge[2] = new_list;
+            let mut new_list = ElementList::new();
+            for i in 0..self[rank+1].len() {
+                let mut new = Element::new(Subelements::new(), Superelements::new());
+                for sub in &self[rank+1][i].subs {
+                    new.subs.push(perm[*sub]);
+                }
+                new.sort();
+                new_list.push(new);
+            }
+            self[rank+1] = new_list;
+        }
+    }
+
+    /// Sorts some stuff in a way that's useful for the faceting algorithm.
+    pub fn element_sort_strong_with_local(&mut self, local: &Ranks) {
+        for el in 0..self[2].len() {
+            self[2][el].subs.sort_unstable();
+        }
 
-                        ridge.element_sort_strong();
+        for rank in 2..self.len()-1 {
+            let mut all_subs = Vec::new();
+            for el in &self[rank] {
+                all_subs.push(el.subs.clone());
+            }
+            let mut sorted = all_subs.clone();
+            sorted.sort_unstable();
 
-                        /*
-                        // look for possible disentanglement
-                        let mut disentangled = None;
+            let mut perm = Vec::new();
+            for i in &all_subs {
+                perm.push(sorted.iter().position(|x| x == i).unwrap());
+            }
 
-                        let mut ridge_vertices_idx = HashSet::new();
-                        
-                        for edge in &ridge[2] {
-                            for sub in &edge.subs {
-                                ridge_vertices_idx.insert(*sub);
-                            }
-                        }
+            for i in 0..self[rank].len() {
+                self[rank][i].subs = sorted[i].clone();
+                self[rank][i].subs.sort_unstable();
+            }
 
-                        let mut ridge_vertices = Vec::new();
+            let mut map_to_local = HashMap::new();
 
-                        for idx in &ridge_vertices_idx {
-                            ridge_vertices.push(vertices[*idx].clone());
-                        }
+            for i in 0..self[rank+1].len() {
+                for j in 0..self[rank+1][i].subs.len() {
+                    map_to_local.insert(self[rank+1][i].subs[j], local[rank+1][i].subs[j]);
+                }
+            }
+
+            let mut new_list = ElementList::new();
+            for i in 0..self[rank+1].len() {
+                let mut new = Element::new(Subelements::new(), Superelements::new());
+                for sub in &self[rank+1][i].subs {
+                    new.subs.push(perm[*map_to_local.get(sub).unwrap()]);
+                }
+                new.sort();
+                new_list.push(new);
+            }
+            self[rank+1] = new_list;
+        }
+    }
 
-                        let subspace = Subspace::from_points(ridge_vertices.iter());
-                        let mut all_vertices_idx = HashSet::new();
+    /*
+    /// Combines two `Ranks`. Only meant to be used in the faceting algorithm.
+    fn append(&mut self, other: &Ranks) {
+        let counts: Vec<usize> = self.iter().map(|x| x.len()).collect();
 
-                        for (i, vertex) in vertices.iter().enumerate() {
-                            if subspace.distance(&vertex) < f64::EPS {
-                                all_vertices_idx.insert(i);
-                            }
+        for r in 1..=2 {
+            for el in &other[r] {
+                self[r].push(el.clone());
+            }
+        }
+
+        for r in 3..self.rank() {
+            for el in &other[r] {
+                let mut new_el = el.clone();
+                for sub in &mut new_el.subs {
+                    *sub += counts[r-1];
+                }
+                for sup in &mut new_el.sups {
+                    *sup += counts[r+1];
+                }
+                self[r].push(new_el.clone());
+            }
+        }
+    }
+    */
+}
+
+/// Modified binary search that finds the first element whose first element is greater than `min`.
+fn binary(vec: &Vec<(usize,usize)>, min: usize) -> usize{
+    let mut lo  = -1;
+    let mut hi  = vec.len() as isize;
+    let mut c = (lo+hi)/2;
+
+    while hi - lo > 1 {
+        if vec[c as usize].0 > min {
+            hi = c;
+        } else {
+            lo = c;
+        }
+        c = (lo+hi)/2;
+    }
+
+    hi as usize
+}
+
+/// For each faceting, checks if it is a compound of other facetings, and labels it if so.
+fn label_irc(vec: &Vec<Vec<(usize,usize)>>) -> HashMap<usize, (usize,usize)> {
+    let mut out = HashMap::<usize, (usize,usize)>::new(); // Map of the index of the compound to the indices of the components.
+
+    'a: for a in 0..vec.len() { // `a` is the index of the base set
+        for b in 0..vec.len() { // `b` is the index of a potential subset of `a`
+            if vec[b].len() >= vec[a].len() { // A strict subset must be smaller than the base.
+                continue
+            }
+            if vec[b][0] > vec[a][0] { // One of the subsets must contain the first facet.
+                break
+            }
+            let mut i = 0;
+            for f in &vec[a] { // Searches through `b` to see if all elements are in `a`.
+                if &vec[b][i] > f {
+                    continue
+                }
+                if &vec[b][i] < f {
+                    break
+                }
+                i += 1;
+
+                if i >= vec[b].len() { // We've found a subset.
+                    let mut complement = Vec::new();
+
+                    let mut j = 0;
+                    for g in &vec[a] {
+                        if j >= vec[b].len() {
+                            complement.push(*g);
+                            continue
+                        }
+                        if &vec[b][j] > g {
+                            complement.push(*g);
+                            continue
                         }
+                        j += 1;
+                    }
 
-                        if all_vertices_idx.len() > ridge_vertices_idx.len() {
-                            'vmap: for row in vertex_map.iter().skip(1) {
-                                let mut different = false;
-                                for vertex in &ridge_vertices_idx {
-                                    if !all_vertices_idx.contains(&row[*vertex]) {
-                                        continue 'vmap;
-                                    }
-                                    if !ridge_vertices_idx.contains(&row[*vertex]) {
-                                        different = true;
-                                    }
-                                }
-                                if different {
-                                    // We found a coplanar copy of the ridge, thus a disentanglement.
-                                    let mut new_ridge = ridge.clone();
-        
-                                    let mut new_list = ElementList::new();
-                                    for i in 0..new_ridge[2].len() {
-                                        let mut new = Element::new(Subelements::new(), Superelements::new());
-                                        for sub in &ridge[2][i].subs {
-                                            new.subs.push(row[*sub])
-                                        }
-                                        new_list.push(new);
-                                    }
-                                    new_ridge[2] = new_list;
-        
-                                    disentangled = Some(new_ridge);
-                                    break;
-                                }
-                            }
-                            if let Some(copy) = &disentangled {
-                                let mut compound = ridge.clone();
-                                compound.append(copy);
-                            }
+                    for c in b+1..vec.len() { // Look for its complement.
+                        if vec[c] == complement {
+                            out.insert(a,(b,c));
+                            break
                         }
-                        */
+                    }
+                    continue 'a;
+                }
+            }
+        }
+    }
+    out
+}
 
-                        let mut found = false;
+/// For each faceting, checks if it is a compound of other facetings, and removes it if so.
+fn filter_irc(vec: &Vec<Vec<(usize,usize)>>) -> Vec<usize> {
+    let mut out = Vec::new(); // The indices of the facetings that aren't compounds.
 
-                        for row in &vertex_map {
-                            let mut new_ridge = ridge.clone();
-                        
-                            let mut new_list = ElementList::new();
-                            for i in 0..new_ridge[2].len() {
-                                let mut new = Element::new(Subelements::new(), Superelements::new());
-                                for sub in &ridge[2][i].subs {
-                                    new.subs.push(row[*sub])
-                                }
-                                new_list.push(new);
-                            }
-                            new_ridge[2] = new_list;
+    'a: for a in 0..vec.len() { // `a` is the index of the base set
+        for b in 0..vec.len() { // `b` is the index of a potential subset of `a`
+            if a == b {
+                continue
+            }
+            if vec[b].len() > vec[a].len() { // A strict subset must be smaller than the base.
+                continue
+            }
+            if vec[b][0] > vec[a][0] { // One of the subsets must contain the first facet.
+                break
+            }
+            let mut i = 0;
+            for f in &vec[a] { // Searches through `b` to see if all elements are in `a`.
+                if &vec[b][i] > f {
+                    continue
+                }
+                if &vec[b][i] < f {
+                    break
+                }
+                i += 1;
+                if i >= vec[b].len() { // We've found a subset.
+                    continue 'a;
+                }
+            }
+        }
+        out.push(a)
+    }
+    out
+}
 
-                            new_ridge.element_sort_strong();
-                            if let Some((idx, _)) = ridge_orbits.get(&new_ridge) {
-                                // writes the orbit index at the ridge index
-                                r_i_o_row_row.push(*idx);
-                                found = true;
-                                break
-                            }
-                        }
+/// Returns the row of the vertex map that sends every vertex to its antipode,
+/// if there is one.
+fn central_inversion(vertices: &[Point<f64>], vertex_map: &[VertexPermutation]) -> Option<VertexPermutation> {
+    vertex_map.iter().find(|row| {
+        row.iter()
+            .enumerate()
+            .all(|(v, w)| (&vertices[v] + &vertices[*w]).norm() < f64::EPS)
+    }).cloned()
+}
 
-                        if !found {
-                            // counts the ridges in the orbit
-                            let mut count = 0;
-                            let mut set = HashSet::new();
+/// Returns one out of every two rows of the vertex map that differ by the
+/// central inversion. Every element of the group is either one of these rows
+/// or its composition with the inversion.
+fn half_vertex_map(vertex_map: &[VertexPermutation], inversion: &VertexPermutation) -> Vec<VertexPermutation> {
+    let mut seen = HashSet::new();
+    let mut half = Vec::new();
+
+    for row in vertex_map {
+        if !seen.contains(row) {
+            seen.insert(inversion.compose(row));
+            seen.insert(row.clone());
+            half.push(row.clone());
+        }
+    }
 
-                            for row in &vertex_map {
-                                let mut new_ridge = ridge.clone();
-                            
-                                let mut new_list = ElementList::new();
-                                for i in 0..new_ridge[2].len() {
-                                    let mut new = Element::new(Subelements::new(), Superelements::new());
-                                    for sub in &ridge[2][i].subs {
-                                        new.subs.push(row[*sub])
-                                    }
-                                    new_list.push(new);
-                                }
-                                new_ridge[2] = new_list;
+    half
+}
+
+/// Pairs up the hyperplane orbits, given by the vertices of a representative,
+/// under a reflection of the vertices. Returns the index of the mirror image of
+/// every orbit, or `None` for all of them if there's no reflection.
+fn hyperplane_pairing(
+    hyperplane_orbits: &[&VertexList],
+    vertex_map: &[VertexPermutation],
+    reflection: Option<&VertexPermutation>,
+) -> Vec<Option<usize>> {
+    let reflection = match reflection {
+        Some(reflection) => reflection,
+        None => return vec![None; hyperplane_orbits.len()],
+    };
+
+    let mut orbit_of_hyperplane = HashMap::new();
+    for (idx, hp_v) in hyperplane_orbits.iter().enumerate() {
+        for row in vertex_map {
+            orbit_of_hyperplane.insert(row.apply(hp_v.as_slice()), idx);
+        }
+    }
+
+    hyperplane_orbits
+        .iter()
+        .map(|hp_v| orbit_of_hyperplane.get(&reflection.apply(hp_v.as_slice())).copied())
+        .collect()
+}
+
+/// Returns whether a set of hyperplane orbits is closed under the pairing
+/// given by [`hyperplane_pairing`].
+fn is_paired(pairing: &[Option<usize>], hps: &HashSet<usize>) -> bool {
+    hps.iter().all(|hp| match pairing[*hp] {
+        Some(pair) => hps.contains(&pair),
+        None => false,
+    })
+}
+
+/// Returns the index of the vertex orbit of the pole of each hyperplane, with
+/// respect to the sphere about the origin with some squared radius, or `None`
+/// if it isn't a vertex. The hyperplanes through the origin have no pole.
+fn polar_pairing(
+    hyperplanes: &[Subspace<f64>],
+    vertices: &[Point<f64>],
+    vertex_orbit_of: &[usize],
+    squared_radius: f64,
+) -> Vec<Option<usize>> {
+    hyperplanes
+        .iter()
+        .map(|hyperplane| {
+            // The closest point to the origin is at the depth of the hyperplane, in the direction of its pole.
+            let foot = hyperplane.project(&Point::zeros(hyperplane.dim()));
+            let depth_sq = foot.norm_squared();
+            if depth_sq.sqrt() <= f64::EPS {
+                return None;
+            }
+
+            let pole = foot * (squared_radius / depth_sq);
+            vertices
+                .iter()
+                .position(|v| (v - &pole).norm() <= f64::EPS)
+                .map(|v| vertex_orbit_of[v])
+        })
+        .collect()
+}
+
+/// The settings used when faceting the hyperplanes of a polytope, and their
+/// own hyperplanes in turn.
+#[derive(Clone, Copy, Debug)]
+struct SubdimSettings<'a> {
+    /// The allowed edge lengths.
+    edge_lengths: &'a EdgeLengthFilter,
+
+    /// How close two lengths must be to count as equal, from
+    /// [`FacetingOptions::tolerance`].
+    tolerance: f64,
+
+    /// The maximum number of facetings of each hyperplane.
+    max_per_hyperplane: Option<usize>,
+
+    /// Whether to only keep the facetings with a single vertex orbit, or
+    /// compounds of these.
+    uniform: bool,
+
+    /// The flag that cancels the enumeration, from
+    /// [`FacetingOptions::cancel`].
+    cancel: Option<&'a AtomicBool>,
+
+    /// Where to report progress, from [`FacetingOptions::progress`].
+    progress: Option<&'a ProgressSink>,
+}
+
+impl<'a> SubdimSettings<'a> {
+    /// Returns whether the enumeration has been cancelled.
+    fn is_cancelled(&self) -> bool {
+        match self.cancel {
+            Some(cancel) => cancel.load(Ordering::Relaxed),
+            None => false,
+        }
+    }
+}
+
+/// Where [`faceting_subdim`] is called from. This is all that changes between
+/// faceting a hyperplane of the polytope and faceting a hyperplane of a
+/// hyperplane, further down.
+#[derive(Clone, Copy, Debug)]
+enum SubdimMode<'a> {
+    /// Faceting a hyperplane of the polytope. Progress is reported, and when
+    /// looking for noble facetings, the hyperplanes are filtered using the
+    /// full vertex map, the global indices of the vertices on the hyperplane,
+    /// and the number of hyperplanes in its orbit.
+    Hyperplane(Option<(&'a [VertexPermutation], &'a VertexList, usize)>),
+
+    /// Faceting a hyperplane of a hyperplane.
+    Nested,
+}
+
+impl<'a> SubdimMode<'a> {
+    /// Returns what's needed to filter the hyperplanes for noble facetings.
+    fn noble_package(self) -> Option<(&'a [VertexPermutation], &'a VertexList, usize)> {
+        match self {
+            Self::Hyperplane(noble_package) => noble_package,
+            Self::Nested => None,
+        }
+    }
+
+    /// Returns whether to report progress.
+    fn reports_progress(self) -> bool {
+        matches!(self, Self::Hyperplane(_))
+    }
+}
+
+/// Enumerates the orbits of pairs of vertices whose distance is allowed by an
+/// edge length filter, up to some tolerance. Each orbit is given as a list of pairs, the first of
+/// which has the representative of a vertex orbit as its first vertex.
+///
+/// If the central inversion is given, the vertex map only needs to contain one
+/// of each pair of symmetries that differ by it.
+///
+/// The vertex orbits are gone through one representative at a time, and only
+/// the pairs starting at a representative are remembered, as those are the
+/// only ones the search asks about. This keeps the memory used in line with
+/// the number of pairs found rather than the square of the number of vertices.
+fn pair_orbits(
+    vertices: &[Point<f64>],
+    vertex_orbits: &[VertexOrbit],
+    vertex_map: &[VertexPermutation],
+    inversion: Option<&VertexPermutation>,
+    edge_lengths: &EdgeLengthFilter,
+    tolerance: f64,
+    progress: Option<&ProgressSink>,
+) -> Vec<Vec<Vec<usize>>> {
+    let mut throttle = Throttle::new(progress);
+    let mut pair_orbits = Vec::new();
+
+    // The pairs found so far that start at a representative yet to be gone through.
+    let mut is_rep = vec![false; vertices.len()];
+    for orbit in vertex_orbits {
+        is_rep[orbit.representative()] = true;
+    }
+    let mut pending = HashMap::<usize, Vec<usize>>::new();
+
+    // The pairs starting at the current representative that have been found.
+    let mut checked = vec![false; vertices.len()];
+
+    for orbit in vertex_orbits {
+        let rep = orbit.representative(); // We only need one representative per orbit.
+        is_rep[rep] = false;
+        checked.fill(false);
+        for c2 in pending.remove(&rep).unwrap_or_default() {
+            checked[c2] = true;
+        }
+
+        for vertex in rep+1..vertices.len() {
+            throttle.report(|| FacetingProgress::EdgeOrbits { found: pair_orbits.len() });
+
+            if !checked[vertex] {
+                if !edge_lengths.contains_within((&vertices[vertex]-&vertices[rep]).norm(), tolerance) {
+                    continue;
+                }
+                let mut new_orbit = Vec::new();
+                let mut found = HashSet::new();
+                for row in vertex_map {
+                    let (a1, a2) = (row[rep], row[vertex]);
+                    let mut images = vec![(a1, a2)];
+                    if let Some(inversion) = inversion {
+                        images.push((inversion[a1], inversion[a2]));
+                    }
+
+                    for (a1, a2) in images {
+                        let c1 = a1.min(a2);
+                        let c2 = a1.max(a2);
+
+                        if found.insert((c1, c2)) {
+                            new_orbit.push(vec![c1, c2]);
+                            if c1 == rep {
+                                checked[c2] = true;
+                            } else if is_rep[c1] {
+                                pending.entry(c1).or_default().push(c2);
+                            }
+                        }
+                    }
+                }
+                pair_orbits.push(new_orbit);
+            }
+        }
+    }
+
+    pair_orbits
+}
+
+/// Replaces the vertex indices of the edges of a polytope on a hyperplane,
+/// given by their positions in a list of vertices, by the indices in the list.
+fn globalize(ranks: &mut Ranks, hp_v: &[usize]) {
+    let mut new_edges = ElementList::new();
+    for edge in &ranks[2] {
+        let mut new_edge = Element::new(Subelements::new(), Superelements::new());
+        for sub in &edge.subs {
+            new_edge.subs.push(hp_v[*sub]);
+        }
+        new_edges.push(new_edge);
+    }
+    ranks[2] = new_edges;
+}
+
+/// Facets the vertices on a hyperplane of a polytope with a given rank under
+/// their stabilizer, and finds the ridges of the possible facets. `points` are
+/// all the vertices, given in the coordinates the hyperplane is in, and `hp_v`
+/// the indices of those on it.
+///
+/// If a memo is given, the facetings of an earlier hyperplane with congruent
+/// vertices and a conjugate stabilizer are reused, and those of this one are
+/// stored in it otherwise.
+#[allow(clippy::too_many_arguments)]
+fn facet_subspace(
+    rank: usize,
+    hyperplane: Subspace<f64>,
+    hp_v: &[usize],
+    points: &[PointOrd<f64>],
+    vertex_map: &[VertexPermutation],
+    settings: SubdimSettings<'_>,
+    mode: SubdimMode<'_>,
+    memo: Option<&mut SubdimMemo>,
+) -> HyperplaneFacets {
+    let stabilizer = induced_stabilizer(vertex_map, hp_v);
+    let hp_points: Vec<_> = hp_v.iter().map(|v| points[*v].clone()).collect();
+
+    let output = match memo {
+        Some(memo) => {
+            let shape = Shape::new(&hp_points, &stabilizer);
+            match memo.get(&shape, settings.edge_lengths, settings.tolerance) {
+                Some(output) => output,
+                None => {
+                    let output = faceting_subdim(rank-1, hyperplane, hp_points, stabilizer, settings, mode);
+                    memo.insert(shape, settings.edge_lengths, &output);
+                    output
+                }
+            }
+        }
+        None => faceting_subdim(rank-1, hyperplane, hp_points, stabilizer, settings, mode),
+    };
+
+    HyperplaneFacets::from_subdim(output, hp_v)
+}
+
+/// Logs the order of a symmetry group, along with its name if it's
+/// recognized by [`Group::identify_with_vertex_map`].
+fn log_group(
+    what: &str,
+    group: &Group<vec::IntoIter<Matrix<f64>>>,
+    vertex_map: &[Vec<usize>],
+    identify: bool,
+) -> Option<GroupName> {
+    let name = if identify {
+        Some(group.identify_with_vertex_map(vertex_map)).filter(GroupName::is_known)
+    } else {
+        None
+    };
+    match &name {
+        Some(name) => info!("{} group {}, of order {}", what, name, group.order()),
+        None => info!("{} order {}", what, group.order()),
+    }
+    name
+}
+
+/// Returns whether the vertices of a possible facet of a polytope with a given
+/// rank span less than its hyperplane. The facet is given with global vertex
+/// indices, and the vertices closer than the tolerance to the subspace spanned
+/// by the others don't count.
+fn is_degenerate(facet: &Ranks, vertices: &[Point<f64>], rank: usize, tolerance: f64) -> bool {
+    let mut facet_vertices: Vec<_> = facet[2].iter().flat_map(|edge| edge.subs.iter().copied()).collect();
+    facet_vertices.sort_unstable();
+    facet_vertices.dedup();
+
+    if facet_vertices.is_empty() {
+        return true;
+    }
+    let subspace = Subspace::from_points_within(facet_vertices.iter().map(|&v| &vertices[v]), tolerance);
+    subspace.rank() + 2 < rank
+}
+
+/// How the ridges of the possible facets of the hyperplane orbits of some
+/// vertices fit together.
+struct RidgeTables {
+    /// The ridge orbit of each ridge of each facet.
+    ridge_idx_orbits: Vec<Vec<Vec<usize>>>,
+
+    /// The number of ridges in each ridge orbit.
+    ridge_counts: Vec<usize>,
+
+    /// How many times the copies of each possible facet cover each ridge of
+    /// each ridge orbit.
+    ridge_muls: Vec<Vec<Vec<usize>>>,
+
+    /// The possible facets covering the ridges of each ridge orbit exactly
+    /// once.
+    ones: Vec<Vec<(usize, usize)>>,
+}
+
+impl RidgeTables {
+    /// Sorts the ridges of the possible facets of each hyperplane orbit into
+    /// orbits under the vertex map, and counts how many times each facet
+    /// covers them. `f_counts` are the numbers of hyperplanes in each orbit.
+    fn new(facets: &[HyperplaneFacets], f_counts: &[usize], vertex_map: &[VertexPermutation], progress: Option<&ProgressSink>) -> Self {
+        let mut throttle = Throttle::new(progress);
+        let mut ridge_idx_orbits = Vec::new();
+        let mut ridge_orbits = HashMap::new();
+        let mut ridge_counts = Vec::new(); // Counts the number of ridges in each orbit
+        let mut orbit_idx = 0;
+
+        for (hp_i, hp_facets) in facets.iter().enumerate() {
+            let mut r_i_o_row = Vec::new();
+
+            for ridges_row_row in &hp_facets.ridges {
+                let mut r_i_o_row_row = Vec::new();
+
+                for ridge in ridges_row_row {
+                    // goes through all the ridges
+                    let mut found = false;
+
+                    for row in vertex_map {
+                        let mut new_ridge = row.apply_to_ranks(ridge);
+
+                        new_ridge.element_sort_strong();
+                        if let Some(idx) = ridge_orbits.get(&new_ridge) {
+                            // writes the orbit index at the ridge index
+                            r_i_o_row_row.push(*idx);
+                            found = true;
+                            break
+                        }
+                    }
+
+                    if !found {
+                        // counts the ridges in the orbit
+                        let mut set = HashSet::new();
+
+                        for row in vertex_map {
+                            let mut new_ridge = row.apply_to_ranks(ridge);
+
+                            new_ridge.element_sort_strong();
+                            set.insert(new_ridge);
+                        }
+                        ridge_orbits.insert(ridge, orbit_idx);
+                        r_i_o_row_row.push(orbit_idx);
+                        ridge_counts.push(set.len());
+                        orbit_idx += 1;
+                        throttle.report(|| FacetingProgress::Ridges { done: hp_i, total: facets.len() });
+                    }
+                }
+                r_i_o_row.push(r_i_o_row_row);
+            }
+            ridge_idx_orbits.push(r_i_o_row);
+            throttle.finish(|| FacetingProgress::Ridges { done: hp_i + 1, total: facets.len() });
+        }
+
+        let mut ridge_muls = Vec::new();
+        let mut ones = vec![Vec::<(usize, usize)>::new(); ridge_counts.len()];
+
+        for (hp, hp_facets) in facets.iter().enumerate() {
+            let mut ridge_muls_hp = Vec::new();
+            for (f, facet) in hp_facets.possible.iter().enumerate() {
+                let mut ridge_muls_facet = vec![0; ridge_counts.len()];
+
+                let f_count = f_counts[hp];
+
+                for ridge_idx in &facet.1 {
+                    let ridge_orbit = ridge_idx_orbits[hp][ridge_idx.0][ridge_idx.1];
+                    let ridge_count = hp_facets.ff_counts[ridge_idx.0];
+                    let total_ridge_count = ridge_counts[ridge_orbit];
+                    let mul = f_count * ridge_count / total_ridge_count;
+
+                    if mul == 1 {
+                        ones[ridge_orbit].push((hp, f));
+                    }
+
+                    ridge_muls_facet[ridge_orbit] = mul;
+                }
+
+                ridge_muls_hp.push(ridge_muls_facet);
+            }
+            ridge_muls.push(ridge_muls_hp);
+        }
+
+        Self {
+            ridge_idx_orbits,
+            ridge_counts,
+            ridge_muls,
+            ones,
+        }
+    }
+}
+
+/// Adds how many times a possible facet covers each ridge orbit to the counts
+/// of a candidate, given the ridges of the facet, the ridge orbits of the
+/// ridges of its hyperplane orbit, and how many times the facet covers each.
+/// Stops at the first ridge orbit covered more than `max` times, as the
+/// candidate is exotic then.
+fn add_ridge_muls(candidate_muls: &mut [usize], ridges: &[(usize, usize)], ridge_orbits: &[Vec<usize>], facet_muls: &[usize], max: usize) {
+    for ridge in ridges {
+        let ridge_orbit = ridge_orbits[ridge.0][ridge.1];
+        candidate_muls[ridge_orbit] += facet_muls[ridge_orbit];
+        if candidate_muls[ridge_orbit] > max {
+            return;
+        }
+    }
+}
+
+/// Returns what [`faceting_subdim`] returns on the two points of a dyad,
+/// under some symmetries of them.
+fn dyad_faceting(vertex_map: &[VertexPermutation]) -> SubdimOutput {
+    // The only faceting of a dyad is itself.
+    // We distinguish between snub and non-snub edges.
+
+    let mut snub = true;
+
+    for row in vertex_map {
+        if row[0] == 1 {
+            snub = false;
+            break
+        }
+    }
+
+    if snub {
+        (
+            vec![(Abstract::dyad().ranks().clone(), vec![(0,0), (1,0)])],
+            vec![1,1],
+            vec![
+                vec![crate::ranks![[], [[0]], [[0]]]],
+                vec![crate::ranks![[], [[0]], [[1]]]],
+            ],
+            HashMap::new()
+        )
+    }
+    else {
+        (
+            vec![(Abstract::dyad().ranks().clone(), vec![(0,0)])],
+            vec![2],
+            vec![vec![crate::ranks![[], [[0]], [[0]]]]],
+            HashMap::new()
+        )
+    }
+}
+
+/// Facets some points on a subspace, under some symmetries of them. This is
+/// the same as faceting a polytope with these vertices, one dimension at a
+/// time: the hyperplanes of the subspace are faceted in the same way one rank
+/// lower, and their facetings combined. Returns the facetings
+/// found as the subelements of their elements, along with their facet orbits,
+/// the number of hyperplanes in each hyperplane orbit, the possible facets of
+/// each hyperplane orbit with indices among the points, which are the ridges
+/// one rank up, and the facetings that are compounds of others mapped to
+/// their components.
+///
+/// The search only differs from the one for the polytope itself, in
+/// [`FacetingTables::combine`], in that it stops at
+/// [`SubdimSettings::max_per_hyperplane`] facetings and can filter out those
+/// that aren't uniform.
+///
+/// The hyperplanes of each rank are faceted from an explicit stack of
+/// [`SubdimFrame`]s rather than by recursion, as the frames are big enough
+/// for high ranks to overflow the stack of the thread.
+fn faceting_subdim(
+    rank: usize,
+    plane: Subspace<f64>,
+    points: Vec<PointOrd<f64>>,
+    vertex_map: Vec<VertexPermutation>,
+    settings: SubdimSettings<'_>,
+    mode: SubdimMode<'_>,
+) -> SubdimOutput {
+    if rank == 2 {
+        return dyad_faceting(&vertex_map);
+    }
+
+    let mut stack = vec![SubdimFrame::new(rank, plane, points, vertex_map, settings, mode)];
+    loop {
+        let frame = stack.last_mut().unwrap();
+        let idx = frame.facets.len();
+
+        // Once every hyperplane orbit is faceted, the facetings are passed on
+        // to the hyperplane orbit of the frame below.
+        if idx == frame.hyperplane_orbits.len() {
+            let output = stack.pop().unwrap().combine(settings);
+            match stack.last_mut() {
+                Some(parent) => parent.push_facets(output),
+                None => return output,
+            }
+            continue;
+        }
+
+        // Facet the next hyperplane orbit under the stabilizer of one of its
+        // hyperplanes.
+        let hp_v = &frame.hyperplanes_vertices[idx][0];
+        let stabilizer = induced_stabilizer(&frame.vertex_map, hp_v);
+        if frame.rank == 3 {
+            let output = dyad_faceting(&stabilizer);
+            frame.push_facets(output);
+        } else {
+            let hp_points = hp_v.iter().map(|v| frame.flat_points[*v].clone()).collect();
+            let hyperplane = frame.hyperplane_orbits[idx][0].clone();
+            let child = SubdimFrame::new(frame.rank - 1, hyperplane, hp_points, stabilizer, settings, SubdimMode::Nested);
+            stack.push(child);
+        }
+    }
+}
+
+/// A subspace being faceted by [`faceting_subdim`], whose hyperplane orbits
+/// have been found, waiting for the facetings of these.
+struct SubdimFrame<'a> {
+    /// The rank of the polytopes that the points are faceted into.
+    rank: usize,
+
+    /// The points, in the coordinates of the subspace.
+    flat_points: Vec<PointOrd<f64>>,
+
+    /// The symmetries the points are faceted under.
+    vertex_map: Vec<VertexPermutation>,
+
+    /// Where the subspace is faceted from.
+    mode: SubdimMode<'a>,
+
+    /// The hyperplanes of each hyperplane orbit.
+    hyperplane_orbits: Vec<Vec<Subspace<f64>>>,
+
+    /// The indices of the points on each hyperplane of each hyperplane orbit.
+    hyperplanes_vertices: Vec<Vec<Vec<usize>>>,
+
+    /// The possible facets of the hyperplane orbits faceted so far.
+    facets: Vec<HyperplaneFacets>,
+}
+
+impl<'a> SubdimFrame<'a> {
+    /// Finds the hyperplane orbits of some points on a subspace, under some
+    /// symmetries of them. The rank must be at least 3.
+    fn new(
+        rank: usize,
+        plane: Subspace<f64>,
+        points: Vec<PointOrd<f64>>,
+        vertex_map: Vec<VertexPermutation>,
+        settings: SubdimSettings<'_>,
+        mode: SubdimMode<'a>,
+    ) -> Self {
+        let SubdimSettings {
+            edge_lengths,
+            tolerance,
+            ..
+        } = settings;
+        let noble_package = mode.noble_package();
+        let total_vert_count = points.len();
+
+        let mut flat_points = Vec::new();
+        for p in &points {
+            flat_points.push(PointOrd::new(plane.flatten(&p.0)));
+        }
+    
+        let vertex_orbits = VertexOrbit::orbits(total_vert_count, &vertex_map);
+        let plain_points: Vec<_> = points.iter().map(|p| p.0.clone()).collect();
+        let pair_orbits = pair_orbits(&plain_points, &vertex_orbits, &vertex_map, None, edge_lengths, tolerance, None);
+
+        // Enumerate hyperplanes
+        let mut hyperplane_orbits = Vec::new();
+        let mut checked = HashSet::<Vec<usize>>::new();
+        let mut hyperplanes_vertices = Vec::new();
+
+        let mut noble_map = HashMap::<Vec<usize>, usize>::new();
+        let mut noble_counts = Vec::<usize>::new();
+        let mut noble_muls = Vec::<usize>::new();
+
+        for pair_orbit in pair_orbits {
+            let rep = &pair_orbit[0];
+
+            if rep[1]+rank-2 > points.len() {
+                continue;
+            }
+            let mut new_vertices: Vec<usize> = (rep[1]+1..rep[1]+rank-2).collect();
+            let mut update = 0;
+            if rank > 3 {
+                update = rank-4;
+            }
+            'b: loop {
+                'c: loop {
+                    // WLOG checks if the vertices are all the right distance away from the first vertex.
+                    for (v_i, v) in new_vertices.iter().enumerate() {
+                        if !edge_lengths.contains_within((&points[*v].0-&points[rep[0]].0).norm(), tolerance) {
+                            update = v_i;
+                            break 'c;
+                        }
+                    }
+                    // We start with a pair and add enough vertices to define a hyperplane.
+                    let mut tuple = rep.clone();
+                    tuple.append(&mut new_vertices.clone());
+
+                    let mut first_points = Vec::new();
+                    for v in tuple {
+                        first_points.push(&flat_points[v].0);
+                    }
+
+                    let hyperplane = Subspace::from_points_within(first_points.clone().into_iter(), tolerance);
+                    if hyperplane.is_hyperplane() {
+
+                        let mut hyperplane_vertices = Vec::new();
+                        for (idx, v) in flat_points.iter().enumerate() {
+                            if hyperplane.distance(&v.0) < tolerance {
+                                hyperplane_vertices.push(idx);
+                            }
+                        }
+                        hyperplane_vertices.sort_unstable();
+
+                        // Check if the hyperplane has been found already.
+                        if !checked.contains(&hyperplane_vertices) {
+                            // If it's new, we add all the ones in its orbit.
+                            let mut new_orbit = Vec::new();
+                            let mut new_orbit_vertices = Vec::new();
+                            for row in &vertex_map {
+                                let mut new_hp_v = Vec::new();
+                                for idx in &hyperplane_vertices {
+                                    new_hp_v.push(row[*idx]);
+                                }
+                                let new_hp_points = new_hp_v.iter().map(|x| &flat_points[*x].0);
+                                let new_hp = Subspace::from_points_within(new_hp_points, tolerance);
+
+                                let mut sorted = new_hp_v.clone();
+                                sorted.sort_unstable();
+
+                                if !checked.contains(&sorted) {
+                                    checked.insert(sorted);
+                                    new_orbit.push(new_hp);
+                                    new_orbit_vertices.push(new_hp_v);
+                                }
+                            }
+
+                            if let Some((full_vertex_map, global_v, count)) = noble_package {
+                                let mut set = HashSet::new();
+
+                                let mut global_hp_v = Vec::new();
+                                for idx in &hyperplane_vertices {
+                                    global_hp_v.push(global_v[*idx]);
+                                }
+                                global_hp_v.sort_unstable();
+                            
+                                match noble_map.get(&global_hp_v) {
+                                    Some(idx) => {
+                                        let mul = count * new_orbit.len() / noble_counts[*idx];
+                                        noble_muls[*idx] += mul;
+                                    },
+                                    None => {
+                                        for row in full_vertex_map {
+                                            let mut new_hp_v = Vec::new();
+                                            for idx in &hyperplane_vertices {
+                                                new_hp_v.push(row[global_v[*idx]]);
+                                            }
+                                        
+                                            let mut sorted = new_hp_v.clone();
+                                            sorted.sort_unstable();
+        
+                                            set.insert(sorted.clone());
+                                            noble_map.insert(sorted, noble_counts.len());
+                                        }
+        
+                                        let mul = count * new_orbit.len() / set.len();
+                                        noble_counts.push(set.len());
+                                        noble_muls.push(mul);
+                                    },
+                                }
+                            }
+
+                            hyperplane_orbits.push(new_orbit);
+                            hyperplanes_vertices.push(new_orbit_vertices);
+                        }
+                    }
+                    break;
+                }
+                if rank <= 3 {
+                    break;
+                }
+                loop { // Increment new_vertices.
+                    if new_vertices[update] == total_vert_count + update - rank + 3 {
+                        if update < 1 {
+                            break 'b;
+                        }
+                        else {
+                            update -= 1;
+                        }
+                    } else {
+                        new_vertices[update] += 1;
+                        for i in update+1..rank-3 {
+                            new_vertices[i] = new_vertices[i-1]+1;
+                        }
+                        update = rank-4;
+                        break;
+                    }
+                }
+            }
+        }
+        // Filter the invalid hyperplanes if noble faceting.
+        if let Some((_, global_v, _)) = noble_package {
+            let mut new_hyperplane_orbits = Vec::new();
+            let mut new_hyperplanes_vertices = Vec::new();
+
+            for (idx, orbit) in hyperplanes_vertices.iter().enumerate() {
+                let mut global_hp_v: Vec<usize> = orbit[0].clone().iter().map(|x| global_v[*x]).collect();
+                global_hp_v.sort_unstable();
+                if noble_muls[*noble_map.get(&global_hp_v).unwrap()] >= 2 {
+                    new_hyperplane_orbits.push(hyperplane_orbits[idx].clone());
+                    new_hyperplanes_vertices.push(orbit.clone());
+                }
+            }
+
+            hyperplane_orbits = new_hyperplane_orbits;
+            hyperplanes_vertices = new_hyperplanes_vertices;
+        }
+
+
+        Self {
+            rank,
+            flat_points,
+            vertex_map,
+            mode,
+            hyperplane_orbits,
+            hyperplanes_vertices,
+            facets: Vec::new(),
+        }
+    }
+
+    /// Adds the facetings of the next hyperplane orbit.
+    fn push_facets(&mut self, output: SubdimOutput) {
+        let hp_v = &self.hyperplanes_vertices[self.facets.len()][0];
+        let hp_facets = HyperplaneFacets::from_subdim(output, hp_v);
+        self.facets.push(hp_facets);
+    }
+
+    /// Combines the possible facets of the hyperplane orbits into the
+    /// facetings of the points, once every hyperplane orbit is faceted.
+    fn combine(self, settings: SubdimSettings<'_>) -> SubdimOutput {
+        let Self {
+            rank,
+            flat_points,
+            vertex_map,
+            mode,
+            hyperplane_orbits,
+            facets,
+            ..
+        } = self;
+        let SubdimSettings {
+            max_per_hyperplane,
+            uniform,
+            ..
+        } = settings;
+        let noble_package = mode.noble_package();
+        let mut throttle = Throttle::new(if mode.reports_progress() { settings.progress } else { None });
+        let total_vert_count = flat_points.len();
+
+        let f_counts: Vec<usize> = hyperplane_orbits.iter().map(Vec::len).collect();
+        let RidgeTables {
+            ridge_idx_orbits,
+            ridge_counts,
+            ridge_muls,
+            ones,
+        } = RidgeTables::new(&facets, &f_counts, &vertex_map, None);
+
+        let mut possible_facets = Vec::new();
+        let mut possible_facets_global = Vec::new();
+        let mut compound_facets = Vec::new();
+        for hp_facets in facets {
+            possible_facets.push(hp_facets.possible);
+            possible_facets_global.push(hp_facets.global);
+            compound_facets.push(hp_facets.compound);
+        }
+
+        let mut output = Vec::new();
+        let mut output_facets = Vec::new();
+
+        let mut facets_queue = VecDeque::<(
+            Vec<(usize, usize)>, // list of facets
+            usize, // min hyperplane
+            Vec<usize> // cached ridge muls
+        )>::new();
+
+        for (hp, list) in possible_facets.iter().enumerate() {
+            for f in 0..list.len() {
+                facets_queue.push_back((
+                    vec![(hp, f)],
+                    hp,
+                    vec![0; ridge_counts.len()]
+                ));
+            }
+        }
+
+        let mut skipped = 0;
+        'l: while let Some((facets, min_hp, cached_ridge_muls)) = facets_queue.pop_back() {
+            // The possible facets found so far are still valid when stopping early.
+            if settings.is_cancelled() {
+                break;
+            }
+            throttle.report(|| FacetingProgress::Subfacetings { found: output.len() });
+        
+            let mut new_ridge_muls = cached_ridge_muls.clone();
+
+            let &(hp, f) = facets.last().unwrap();
+            add_ridge_muls(&mut new_ridge_muls, &possible_facets[hp][f].1, &ridge_idx_orbits[hp], &ridge_muls[hp][f], 2);
+
+            let mut valid = 0; // 0: valid, 1: exotic, 2: incomplete
+            for r in &new_ridge_muls {
+                if *r > 2 {
+                    valid = 1;
+                    break
+                }
+                if *r == 1 {
+                    valid = 2;
+                }
+            }
+            match valid {
+                0 => {
+                    // Split compound facets into their components.
+                    let mut new_facets = Vec::new();
+
+                    for (hp, idx) in &facets {
+                        let mut all_components = Vec::<usize>::new();
+                        let mut queue = VecDeque::new();
+                        queue.push_back(*idx);
+                        while let Some(next) = queue.pop_front() {
+                            if let Some(components) = compound_facets[*hp].get(&next) {
+                                queue.push_back(components.0);
+                                queue.push_back(components.1);
+                            } else {
+                                all_components.push(next);
+                            }
+                        }
+                        for component in all_components {
+                            new_facets.push((*hp, component));
+                        }
+                    }
+
+                    // Output the faceted polytope. We will build it from the set of its facets.
+
+                    let mut facet_set = HashSet::new();
+                    for facet_orbit in &new_facets {
+                        let facet = &possible_facets_global[facet_orbit.0][facet_orbit.1].0;
+                        let facet_local = &possible_facets[facet_orbit.0][facet_orbit.1].0;
+                        for row in &vertex_map {
+                            let mut new_facet = row.apply_to_ranks(facet);
+
+                            new_facet.element_sort_strong_with_local(facet_local);
+                            facet_set.insert(new_facet);
+                        }
+                    }
+
+                    let mut facet_vec = Vec::from_iter(facet_set.clone());
+                    let mut facet_vec2 = Vec::from_iter(facet_set);
+
+                    let mut ranks = Ranks::new();
+                    ranks.push(vec![Element::new(vec![].into(), vec![].into())].into()); // nullitope
+                    ranks.push(vec![Element::new(vec![0].into(), vec![].into()); total_vert_count].into()); // vertices
+                
+                    let mut ranks2 = Ranks::new();
+                    ranks2.push(vec![Element::new(vec![].into(), vec![].into())].into()); // nullitope
+
+                    let mut to_new_idx = HashMap::new();
+                    let mut to_old_idx = Vec::new();
+                    let mut idx = 0;
+                    if uniform {
+                        for i in 0..facet_vec2.len() {
+                            let mut new_list = ElementList::new();
+                            for j in 0..facet_vec2[i][2].len() {
+                                let mut new = Element::new(Subelements::new(), Superelements::new());
+                                for sub in facet_vec2[i][2][j].subs.clone() {
+                                    if to_new_idx.get(&sub).is_none() {
+                                        to_new_idx.insert(sub, idx);
+                                        to_old_idx.push(sub);
+                                        idx += 1;
+                                    }
+                                    new.subs.push(*to_new_idx.get(&sub).unwrap())
+                                }
+                                new_list.push(new);
+                            }
+                            facet_vec2[i][2] = new_list;
+                        }
+                        let mut new_rank = ElementList::new();
+                        for _i in 0..idx {
+                            new_rank.push(Element::new(vec![0].into(), vec![].into()));
+                        }
+                        ranks2.push(new_rank);
+                    }
+
+                    for r in 2..rank-1 { // edges and up
+                        let mut subs_to_idx = HashMap::new();
+                        let mut idx_to_subs = Vec::new();
+                        let mut idx = 0;
+
+                        for facet in &facet_vec {
+                            let els = &facet[r];
+                            for el in els {
+                                if subs_to_idx.get(&el.subs).is_none() {
+                                    subs_to_idx.insert(el.subs.clone(), idx);
+                                    idx_to_subs.push(el.subs.clone());
+                                    idx += 1;
+                                }
+                            }
+                        }
+                        for i in 0..facet_vec.len() {
+                            let mut new_list = ElementList::new();
+                            for j in 0..facet_vec[i][r+1].len() {
+                                let mut new = Element::new(Subelements::new(), Superelements::new());
+                                for sub in &facet_vec[i][r+1][j].subs {
+                                    let sub_subs = &facet_vec[i][r][*sub].subs;
+                                    new.subs.push(*subs_to_idx.get(sub_subs).unwrap())
+                                }
+                                new_list.push(new);
+                            }
+                            facet_vec[i][r+1] = new_list;
+                        }
+
+                        let mut new_rank = ElementList::new();
+                        for el in idx_to_subs {
+                            new_rank.push(Element::new(el, vec![].into()));
+                        }
+                        ranks.push(new_rank);
+                    
+                        if uniform {
+                            let mut subs_to_idx = HashMap::new();
+                            let mut idx_to_subs = Vec::new();
+                            let mut idx = 0;
+                            for facet in &facet_vec2 {
+                                let els = &facet[r];
+                                for el in els {
+                                    if subs_to_idx.get(&el.subs).is_none() {
+                                        subs_to_idx.insert(el.subs.clone(), idx);
+                                        idx_to_subs.push(el.subs.clone());
+                                        idx += 1;
+                                    }
+                                }
+                            }
+                            for i in 0..facet_vec2.len() {
+                                let mut new_list = ElementList::new();
+                                for j in 0..facet_vec2[i][r+1].len() {
+                                    let mut new = Element::new(Subelements::new(), Superelements::new());
+                                    for sub in &facet_vec2[i][r+1][j].subs {
+                                        let sub_subs = &facet_vec2[i][r][*sub].subs;
+                                        new.subs.push(*subs_to_idx.get(sub_subs).unwrap())
+                                    }
+                                    new_list.push(new);
+                                }
+                                facet_vec2[i][r+1] = new_list;
+                            }
+
+                            let mut new_rank = ElementList::new();
+                            for el in idx_to_subs {
+                                new_rank.push(Element::new(el, vec![].into()));
+                            }
+                            ranks2.push(new_rank);
+                        }
+                    }
+                    let mut new_rank = ElementList::new();
+                    let mut set = HashSet::new();
+
+                    for f_i in 0..facet_vec.len() {
+                        facet_vec[f_i][rank-1][0].subs.sort();
+                        let subs = facet_vec[f_i][rank-1][0].subs.clone();
+                        if !set.contains(&subs) {
+                            new_rank.push(Element::new(subs.clone(), Superelements::new()));
+                            set.insert(subs);
+                        }
+                    }
+                    let n_r_len = new_rank.len();
+                    ranks.push(new_rank); // facets
+
+                    ranks.push(vec![Element::new(Subelements::from_iter(0..n_r_len), Superelements::new())].into()); // body
+                
+                    if uniform {
+                        let mut new_rank = ElementList::new();
+                        let mut set = HashSet::new();
+
+                        for f_i in 0..facet_vec2.len() {
+                            facet_vec2[f_i][rank-1][0].subs.sort();
+                            let subs = facet_vec2[f_i][rank-1][0].subs.clone();
+                            if !set.contains(&subs) {
+                                new_rank.push(Element::new(subs.clone(), Superelements::new()));
+                                set.insert(subs);
+                            }
+                        }
+                        let n_r_len = new_rank.len();
+                        ranks2.push(new_rank); // facets
+
+                        ranks2.push(vec![Element::new(Subelements::from_iter(0..n_r_len), Superelements::new())].into()); // body
+                    }
+
+                    if uniform {
+                        unsafe {
+                            let mut builder = AbstractBuilder::new();
+                            for rank in ranks2 {
+                                builder.push_empty();
+                                for el in rank {
+                                    builder.push_subs(el.subs);
+                                }
+                            }
+            
+                            if builder.ranks().is_dyadic().is_ok() {
+                                let abs = builder.build();
+                                let mut new_vertices = Vec::new();
+                                for i in to_old_idx {
+                                    new_vertices.push(flat_points[i].0.clone());
+                                }
+
+                                let mut poly = Concrete {
+                                    vertices: new_vertices.into(),
+                                    abs: abs.clone(),
+                                    meta: PolytopeMeta::default(),
+                                    history: None,
+                                };
+                                poly.recenter();
+                            
+                                let amount = poly.element_types()[1].len();
+                            
+                                if amount <= 1 {
+                                    output.push((ranks, new_facets.clone()));
+                                    output_facets.push(new_facets.clone());
+                                } else {
+                                    poly.element_sort();
+                                    let components = poly.defiss();
+                                    let mut isogonal = true;
+                                    for component in components {
+                                        if component.element_types()[1].len() > 1 {
+                                            isogonal = false;
+                                            break;
+                                        }
+                                    }
+                                    if isogonal {
+                                        output.push((ranks, new_facets.clone()));
+                                        output_facets.push(new_facets.clone());
+                                    } else {
+                                        skipped += 1;
+                                    }
+                                }
+                            } else {
+                                unreachable!();
+                            }
+                        }
+                    } else {
+                        output.push((ranks, new_facets.clone()));
+                        output_facets.push(new_facets.clone());
+                    }
+
+                    if let Some(max) = max_per_hyperplane {
+                        if output.len() + skipped >= max {
+                            break 'l;
+                        }
+                    }
+
+                    if noble_package.is_none() {
+                        let mut used_hps = HashSet::new();
+                        for facet in facets.iter().skip(1) {
+                            used_hps.insert(facet.0);
+                        }
+                        for (hp, list) in possible_facets.iter().enumerate().skip(min_hp+1) {
+                            if !used_hps.contains(&hp) {
+                                for f in 0..list.len() {
+                                    let mut new_facets = facets.clone();
+                                    new_facets.push((hp, f));
+                                    facets_queue.push_back((new_facets, hp, new_ridge_muls.clone()));
+                                }
+                            }
+                        }
+                    }
+                }
+                1 => {}
+                2 => {
+                    let mut used_hps = HashSet::new();
+                    for facet in facets.iter().skip(1) {
+                        used_hps.insert(facet.0);
+                    }
+                    for (idx, mul) in new_ridge_muls.iter().enumerate() {
+                        if *mul == 1 {
+                            for facet in ones[idx]
+                                .iter()
+                                .skip(binary(&ones[idx], min_hp))
+                            {
+                                if !used_hps.contains(&facet.0) {
+                                    let mut new_facets = facets.clone();
+                                    new_facets.push(*facet);
+                                    facets_queue.push_back((new_facets, min_hp, new_ridge_muls.clone()));
+                                }
+                            }
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        output.sort_by(|a,b| a.1.cmp(&b.1));
+        output_facets.sort_unstable();
+
+        let mut output_ridges = Vec::new();
+        for i in possible_facets_global {
+            let mut a = Vec::new();
+            for j in i {
+                a.push(j.0);
+            }
+            output_ridges.push(a);
+        }
+
+        (output, f_counts, output_ridges, label_irc(&output_facets))
+    }
+}
+
+/// An orbit of hyperplanes through the vertices being faceted.
+#[derive(Clone, Debug)]
+pub struct HyperplaneOrbit {
+    /// A hyperplane of the orbit.
+    pub hyperplane: Subspace<f64>,
+
+    /// The vertices on the hyperplane.
+    pub vertices: VertexList,
+
+    /// The vertices on each hyperplane of the orbit, in increasing order.
+    pub hyperplanes: Vec<VertexList>,
+
+    /// The number of hyperplanes in the orbit.
+    pub size: usize,
+
+    /// The distance of the hyperplanes from the origin.
+    pub depth: f64,
+}
+
+impl HyperplaneOrbit {
+    /// Describes an orbit of hyperplanes through vertices in some dimension,
+    /// under the symmetries of the vertices.
+    fn new((hyperplane, vertices, size): OrbitOfHyperplanes, vertex_map: &[VertexPermutation], dim: usize) -> Self {
+        let hyperplanes: BTreeSet<_> = vertex_map.iter().map(|row| row.apply(vertices.as_slice())).collect();
+        Self {
+            depth: hyperplane_depth(&hyperplane, dim),
+            hyperplane,
+            vertices,
+            hyperplanes: hyperplanes.into_iter().collect(),
+            size,
+        }
+    }
+}
+
+/// Writes the size of the orbit, the number of vertices on each hyperplane
+/// and their depth, such as `6 hyperplanes through 4 vertices, depth
+/// 0.500000`. The alternate format also lists the vertices.
+impl Display for HyperplaneOrbit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} hyperplane{} through {} vertices, depth {:.6}",
+            self.size,
+            if self.size == 1 { "" } else { "s" },
+            self.vertices.len(),
+            self.depth
+        )?;
+        if f.alternate() {
+            write!(f, "\n  vertices {:?}", self.vertices.as_slice())?;
+        }
+        Ok(())
+    }
+}
+
+/// What the faceting found up to the stage it stopped after, as given by
+/// [`FacetingOptions::stop_after`]. Each variant holds one entry for each edge
+/// length checked, so there's more than one only with
+/// [`FacetingOptions::any_single_edge_length`].
+#[derive(Clone, Debug)]
+pub enum PartialFacetingOutput {
+    /// The hyperplane orbits, sorted as in the tables.
+    Hyperplanes(Vec<Vec<HyperplaneOrbit>>),
+
+    /// The tables with the possible facets of each hyperplane orbit.
+    FacetTypes(Vec<FacetingTables>),
+
+    /// The number of facetings found, along with their facet orbits.
+    Count(Vec<FacetingCount>),
+
+    /// The facetings, as output by [`Concrete::faceting`].
+    Full(Vec<FacetingResult>),
+}
+
+impl PartialFacetingOutput {
+    /// Returns the output of a stage when nothing was found.
+    fn empty(stage: Stage) -> Self {
+        match stage {
+            Stage::Hyperplanes => Self::Hyperplanes(Vec::new()),
+            Stage::FacetTypes => Self::FacetTypes(Vec::new()),
+            Stage::Count => Self::Count(Vec::new()),
+            Stage::Full => Self::Full(Vec::new()),
+        }
+    }
+}
+
+/// The facetings found by [`Concrete::count_facetings`], or with
+/// [`Stage::Count`], which are only counted and never built. These are the
+/// facetings [`Concrete::faceting`] would output, as long as they all turn out
+/// dyadic when built, which they should.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FacetingCount {
+    /// The number of facetings found.
+    pub total: usize,
+
+    /// The number of facetings with each number of facet orbits.
+    pub by_facet_orbits: BTreeMap<usize, usize>,
+
+    /// The facet orbits of each faceting, in the order
+    /// [`Concrete::faceting`] would build them in.
+    pub signatures: Vec<Vec<(usize, usize)>>,
+}
+
+impl FacetingCount {
+    /// Counts some facetings given by their facet orbits.
+    fn new(signatures: Vec<Vec<(usize, usize)>>) -> Self {
+        let mut by_facet_orbits = BTreeMap::new();
+        for facets in &signatures {
+            *by_facet_orbits.entry(facets.len()).or_insert(0) += 1;
+        }
+
+        Self {
+            total: signatures.len(),
+            by_facet_orbits,
+            signatures,
+        }
+    }
+
+    /// Adds the facetings counted for another edge length. Their facet orbits
+    /// refer to different tables.
+    fn append(&mut self, other: Self) {
+        self.total += other.total;
+        for (orbits, count) in other.by_facet_orbits {
+            *self.by_facet_orbits.entry(orbits).or_insert(0) += count;
+        }
+        self.signatures.extend(other.signatures);
+    }
+}
+
+/// Writes the number of facetings and how many have each number of facet
+/// orbits, such as `3 facetings: 1 with 1 facet orbit, 2 with 2 facet
+/// orbits`. The alternate format also lists the facet orbits of each one.
+impl Display for FacetingCount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} faceting{}", self.total, if self.total == 1 { "" } else { "s" })?;
+        for (i, (orbits, count)) in self.by_facet_orbits.iter().enumerate() {
+            write!(
+                f,
+                "{}{} with {} facet orbit{}",
+                if i == 0 { ": " } else { ", " },
+                count,
+                orbits,
+                if *orbits == 1 { "" } else { "s" }
+            )?;
+        }
+        if f.alternate() {
+            for facets in &self.signatures {
+                write!(f, "\n ")?;
+                for facet in facets {
+                    write!(f, " ({},{})", facet.0, facet.1)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A faceting found by [`Concrete::faceting`] or the methods of
+/// [`FacetingTables`], along with the facet orbits it was built from.
+#[derive(Clone, Debug)]
+pub struct FacetingResult {
+    /// The faceted polytope. Only the vertices on some of its edges are kept,
+    /// in the order they're first found, so it can have fewer vertices than
+    /// were faceted.
+    pub polytope: Concrete,
+
+    /// The facet orbits of the faceting, given as pairs of a hyperplane orbit
+    /// and one of its possible facets, in increasing order.
+    pub facets: Vec<(usize, usize)>,
+
+    /// The number of facets of the faceting from each facet orbit, in the same
+    /// order as [`Self::facets`].
+    pub facet_counts: Vec<usize>,
+
+    /// The name the faceting was output with by [`Concrete::faceting`].
+    pub name: Option<String>,
+}
+
+/// Writes the summary of the faceting, see [`Concrete`], followed by its
+/// number of facet orbits. The alternate format also lists the number of
+/// facets from each facet orbit.
+impl Display for FacetingResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let orbits = self.facet_orbit_count();
+        if f.alternate() {
+            write!(f, "{:#}", self.polytope)?;
+        } else {
+            write!(f, "{}", self.polytope)?;
+        }
+        write!(f, "; {} facet orbit{}", orbits, if orbits == 1 { "" } else { "s" })?;
+
+        if f.alternate() {
+            for (facet, count) in self.facets.iter().zip(&self.facet_counts) {
+                write!(f, "\n  ({},{}): {} facet{}", facet.0, facet.1, count, if *count == 1 { "" } else { "s" })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FacetingResult {
+    /// Initializes a new result from a faceting built by
+    /// [`FacetingTables::build`], given the facet orbit of each of its facets
+    /// and the order of the group it was found with.
+    fn new(polytope: Concrete, facets: Vec<(usize, usize)>, facet_orbits: &[(usize, usize)], group_order: usize) -> Self {
+        let facet_counts = facets
+            .iter()
+            .map(|facet| facet_orbits.iter().filter(|&orbit| orbit == facet).count())
+            .collect();
+
+        let result = Self {
+            polytope,
+            facets,
+            facet_counts,
+            name: None,
+        };
+        if cfg!(debug_assertions) {
+            if let Err(violation) = result.orbit_stabilizer_check(group_order) {
+                panic!("{}", violation);
+            }
+        }
+        result
+    }
+
+    /// Returns the number of facet orbits of the faceting. The noble
+    /// facetings are those with a single one.
+    pub fn facet_orbit_count(&self) -> usize {
+        self.facets.len()
+    }
+
+    /// Returns the total number of facets of the faceting.
+    pub fn facet_count(&self) -> usize {
+        self.facet_counts.iter().sum()
+    }
+
+    /// Checks that the number of facets from each facet orbit divides the
+    /// order of the group the faceting was found with, as the facets of an
+    /// orbit are in bijection with the cosets of the stabilizer of one of
+    /// them. Otherwise, the facet orbits were counted wrong.
+    pub fn orbit_stabilizer_check(&self, group_order: usize) -> Result<(), OrbitCountViolation> {
+        for (&facet, &facet_count) in self.facets.iter().zip(&self.facet_counts) {
+            if group_order.checked_rem(facet_count) != Some(0) {
+                return Err(OrbitCountViolation {
+                    facet,
+                    facet_count,
+                    group_order,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks whether the faceting is noble under its own symmetry group,
+    /// rather than under the group it was found with, which may be smaller
+    /// or act differently on it. This is done for the facetings found with
+    /// [`FacetingOptions::noble`] from a single facet orbit, and recorded in
+    /// the `noble` property of their metadata.
+    pub fn verify_noble(&self) -> NobleCertificate {
+        NobleCertificate::new(&self.polytope)
+    }
+}
+
+/// A facet orbit of a faceting whose number of facets doesn't divide the order
+/// of the group it was found with, see
+/// [`FacetingResult::orbit_stabilizer_check`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OrbitCountViolation {
+    /// The facet orbit, as a hyperplane orbit and one of its possible facets.
+    pub facet: (usize, usize),
+
+    /// The number of facets of the faceting from the orbit.
+    pub facet_count: usize,
+
+    /// The order of the group.
+    pub group_order: usize,
+}
+
+impl Display for OrbitCountViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "facet orbit ({},{}) has {} facets, which doesn't divide the group order {}",
+            self.facet.0, self.facet.1, self.facet_count, self.group_order
+        )
+    }
+}
+
+impl std::error::Error for OrbitCountViolation {}
+
+/// Whether a faceting is noble under its own symmetry group, that is, whether
+/// its symmetries are transitive on its vertices and on its facets. See
+/// [`FacetingResult::verify_noble`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NobleCertificate {
+    /// The faceting is noble.
+    Noble {
+        /// The order of the symmetry group.
+        group_order: usize,
+
+        /// The number of orbits of the elements of each rank, from the
+        /// vertices to the facets.
+        orbit_counts: Vec<usize>,
+    },
+
+    /// The faceting isn't noble, as two of its vertices or two of its facets
+    /// aren't related by any symmetry.
+    NotNoble {
+        /// The order of the symmetry group.
+        group_order: usize,
+
+        /// The number of orbits of the elements of each rank, from the
+        /// vertices to the facets.
+        orbit_counts: Vec<usize>,
+
+        /// The rank of the two elements, which is that of the facets if they
+        /// aren't all in one orbit.
+        rank: Rank,
+
+        /// The indices of the two elements.
+        elements: (usize, usize),
+    },
+
+    /// The symmetry group couldn't be computed, as every facet passes through
+    /// the origin.
+    Unknown,
+}
+
+impl NobleCertificate {
+    /// Checks whether a polytope is noble under its own symmetry group.
+    fn new(polytope: &Concrete) -> Self {
+        let rank = polytope.rank();
+        let vertex_map = match polytope.clone().get_symmetry_group() {
+            Some((_, vertex_map)) => vertex_map,
+            None => return Self::Unknown,
+        };
+
+        // The orbits of the elements of each rank, with the first element in
+        // another orbit than the first one.
+        let orbits: Vec<_> = (1..rank)
+            .map(|r| {
+                // The orbits are sorted by their smallest element.
+                let orbits = element_orbits_under(polytope, r, &vertex_map);
+                (orbits.len(), orbits.get(1).map(|orbit| orbit[0]))
+            })
+            .collect();
+
+        let group_order = vertex_map.len();
+        let orbit_counts = orbits.iter().map(|&(count, _)| count).collect();
+        let facets = orbits.last().and_then(|&(_, other)| other).map(|other| (rank - 1, other));
+        let vertices = orbits.first().and_then(|&(_, other)| other).map(|other| (1, other));
+
+        match facets.or(vertices) {
+            None => Self::Noble { group_order, orbit_counts },
+            Some((rank, other)) => Self::NotNoble {
+                group_order,
+                orbit_counts,
+                rank: Rank::new(rank),
+                elements: (0, other),
+            },
+        }
+    }
+
+    /// Returns whether the faceting is certified to be noble.
+    pub fn is_noble(&self) -> bool {
+        matches!(self, Self::Noble { .. })
+    }
+}
+
+impl Display for NobleCertificate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Noble { group_order, orbit_counts } => write!(
+                f,
+                "noble under its symmetry group of order {}, with element orbits {:?}",
+                group_order, orbit_counts
+            ),
+            Self::NotNoble {
+                group_order,
+                rank,
+                elements: (first, second),
+                ..
+            } => write!(
+                f,
+                "not noble, as the elements {} and {} of rank {} aren't related by its symmetry group of order {}",
+                first, second, rank, group_order
+            ),
+            Self::Unknown => write!(f, "unverified, as the symmetry group couldn't be computed"),
+        }
+    }
+}
+
+/// Merges some facets of a polytope with a given rank into the subelements of
+/// the polytope, with the facets given with global vertex indices and sorted
+/// by [`Ranks::element_sort_strong_with_local`], along with the facet orbit of
+/// each one. Returns the subelements, the global index of each vertex, and the
+/// facet orbit of each facet, with the repeated facets removed.
+fn merge_facets(rank: usize, mut facet_vec: Vec<Ranks>, facet_vec_orbits: Vec<(usize, usize)>) -> (Ranks, Vec<usize>, Vec<(usize, usize)>) {
+    let mut ranks = Ranks::new();
+    ranks.push(vec![Element::new(vec![].into(), vec![].into())].into()); // nullitope
+
+    // vertices
+    let mut to_new_idx = HashMap::new();
+    let mut to_old_idx = Vec::new();
+    let mut idx = 0;
+
+    for i in 0..facet_vec.len() {
+        let mut new_list = ElementList::new();
+        for j in 0..facet_vec[i][2].len() {
+            let mut new = Element::new(Subelements::new(), Superelements::new());
+            for sub in facet_vec[i][2][j].subs.clone() {
+                if to_new_idx.get(&sub).is_none() {
+                    to_new_idx.insert(sub, idx);
+                    to_old_idx.push(sub);
+                    idx += 1;
+                }
+                new.subs.push(*to_new_idx.get(&sub).unwrap())
+            }
+            new_list.push(new);
+        }
+        facet_vec[i][2] = new_list;
+    }
+    let mut new_rank = ElementList::new();
+    for _i in 0..idx {
+        new_rank.push(Element::new(vec![0].into(), vec![].into()));
+    }
+    ranks.push(new_rank);
+
+    for r in 2..rank-1 { // edges and up
+        let mut subs_to_idx = HashMap::new();
+        let mut idx_to_subs = Vec::new();
+        let mut idx = 0;
+
+        for facet in &facet_vec {
+            let els = &facet[r];
+            for el in els {
+                if subs_to_idx.get(&el.subs).is_none() {
+                    subs_to_idx.insert(el.subs.clone(), idx);
+                    idx_to_subs.push(el.subs.clone());
+                    idx += 1;
+                }
+            }
+        }
+        for i in 0..facet_vec.len() {
+            let mut new_list = ElementList::new();
+            for j in 0..facet_vec[i][r+1].len() {
+                let mut new = Element::new(Subelements::new(), Superelements::new());
+                for sub in &facet_vec[i][r+1][j].subs {
+                    let sub_subs = &facet_vec[i][r][*sub].subs;
+                    new.subs.push(*subs_to_idx.get(sub_subs).unwrap())
+                }
+                new_list.push(new);
+            }
+            facet_vec[i][r+1] = new_list;
+        }
+        let mut new_rank = ElementList::new();
+        for el in idx_to_subs {
+            new_rank.push(Element::new(el, vec![].into()));
+        }
+        ranks.push(new_rank);
+    }
+
+    let mut new_rank = ElementList::new();
+    let mut set = HashSet::new();
+    let mut facet_orbits = Vec::new(); // the facet orbit each facet comes from
+
+    for f_i in 0..facet_vec.len() {
+        facet_vec[f_i][rank-1][0].subs.sort();
+        let subs = facet_vec[f_i][rank-1][0].subs.clone();
+        if !set.contains(&subs) {
+            new_rank.push(Element::new(subs.clone(), Superelements::new()));
+            set.insert(subs);
+            facet_orbits.push(facet_vec_orbits[f_i]);
+        }
+    }
+    let n_r_len = new_rank.len();
+    ranks.push(new_rank); // facets
+
+    ranks.push(vec![Element::new(Subelements::from_iter(0..n_r_len), Superelements::new())].into()); // body
+
+    (ranks, to_old_idx, facet_orbits)
+}
+
+/// A complex found by [`FacetingTables::complexes`], whose ridges may be in
+/// more or less than two facets, along with the facet orbits it was built
+/// from.
+#[derive(Clone, Debug)]
+pub struct FacetingComplex {
+    /// The elements of the complex, with its vertices indexed as in
+    /// [`Self::vertices`].
+    pub complex: PartialAbstract,
+
+    /// The vertices of the complex.
+    pub vertices: Vec<Point<f64>>,
+
+    /// The facet orbits of the complex, given as pairs of a hyperplane orbit
+    /// and one of its possible facets, in increasing order.
+    pub facets: Vec<(usize, usize)>,
+}
+
+/// The facets around a vertex in a possible faceting, found by
+/// [`FacetingTables::vertex_stars`].
+#[derive(Clone, Debug)]
+pub struct VertexStar {
+    /// The vertex the facets are around.
+    pub vertex: usize,
+
+    /// The facet orbit of each facet around the vertex, given as a pair of a
+    /// hyperplane orbit and one of its possible facets, in the same order as
+    /// the facets of [`Self::polytope`].
+    pub facets: Vec<(usize, usize)>,
+
+    /// The facets around the vertex, for drawing. This isn't a valid polytope,
+    /// as the ridges on its boundary are only in one facet.
+    pub polytope: Concrete,
+}
+
+impl VertexStar {
+    /// Returns the number of facets from each facet orbit around the vertex,
+    /// sorted by facet orbit.
+    pub fn facet_counts(&self) -> BTreeMap<(usize, usize), usize> {
+        let mut counts = BTreeMap::new();
+        for &facet in &self.facets {
+            *counts.entry(facet).or_default() += 1;
+        }
+        counts
+    }
+}
+
+/// Returns the edges of each element of a rank of a facet, given with global
+/// vertex indices, as a sorted list of sorted pairs of vertices. These tell
+/// apart the elements of the facets a vertex star is made of.
+fn edge_sets(facet: &Ranks, rank: usize) -> Vec<Vec<Vec<usize>>> {
+    let mut sets: Vec<BTreeSet<Vec<usize>>> = facet[2]
+        .iter()
+        .map(|edge| {
+            let mut edge: Vec<usize> = edge.subs.iter().copied().collect();
+            edge.sort_unstable();
+            std::iter::once(edge).collect()
+        })
+        .collect();
+
+    for r in 3..=rank {
+        sets = facet[r]
+            .iter()
+            .map(|el| el.subs.iter().flat_map(|&sub| sets[sub].iter().cloned()).collect())
+            .collect();
+    }
+
+    sets.into_iter().map(|set| set.into_iter().collect()).collect()
+}
+
+/// Returns the edges of a facet, given with global vertex indices, as a
+/// sorted list of sorted pairs of vertices.
+fn edge_set(facet: &Ranks) -> Vec<Vec<usize>> {
+    let mut edges = edge_sets(facet, 2).concat();
+    edges.sort_unstable();
+    edges
+}
+
+/// A search for the vertex stars of a vertex, given by the copies of the
+/// possible facets through it and the ridges through it of each.
+struct StarSearch {
+    /// The ridges of each copy through the vertex.
+    copy_ridges: Vec<Vec<usize>>,
+
+    /// The copies through each ridge.
+    ridge_copies: Vec<Vec<usize>>,
+
+    /// How many copies each ridge must be in.
+    multiplicity: usize,
+
+    /// How many of the chosen copies each ridge is in.
+    counts: Vec<usize>,
+
+    /// The copies chosen so far.
+    chosen: Vec<usize>,
+
+    /// Whether each copy is chosen.
+    in_star: Vec<bool>,
+
+    /// Whether each copy was already tried, so that it can't be chosen.
+    banned: Vec<bool>,
+
+    /// The stars found, as sorted lists of copies.
+    found: Vec<Vec<usize>>,
+}
+
+impl StarSearch {
+    /// Initializes a search given the ridges of each copy, out of a number of
+    /// ridges.
+    fn new(copy_ridges: Vec<Vec<usize>>, ridge_count: usize, multiplicity: usize) -> Self {
+        let mut ridge_copies = vec![Vec::new(); ridge_count];
+        for (copy, ridges) in copy_ridges.iter().enumerate() {
+            for &ridge in ridges {
+                ridge_copies[ridge].push(copy);
+            }
+        }
+        let copy_count = copy_ridges.len();
+
+        Self {
+            copy_ridges,
+            ridge_copies,
+            multiplicity,
+            counts: vec![0; ridge_count],
+            chosen: Vec::new(),
+            in_star: vec![false; copy_count],
+            banned: vec![false; copy_count],
+            found: Vec::new(),
+        }
+    }
+
+    /// Chooses a copy, unless one of its ridges is already in enough chosen
+    /// copies. Returns whether it was chosen.
+    fn choose(&mut self, copy: usize) -> bool {
+        if self.copy_ridges[copy].iter().any(|&ridge| self.counts[ridge] == self.multiplicity) {
+            return false;
+        }
+        for &ridge in &self.copy_ridges[copy] {
+            self.counts[ridge] += 1;
+        }
+        self.chosen.push(copy);
+        self.in_star[copy] = true;
+        true
+    }
+
+    /// Undoes the last choice.
+    fn unchoose(&mut self) {
+        let copy = self.chosen.pop().unwrap();
+        for &ridge in &self.copy_ridges[copy] {
+            self.counts[ridge] -= 1;
+        }
+        self.in_star[copy] = false;
+    }
+
+    /// Finds every star starting with each copy, without the copies before
+    /// it. As the stars are completed a ridge at a time, their copies are
+    /// connected through their ridges.
+    fn run(&mut self) {
+        for copy in 0..self.copy_ridges.len() {
+            if self.choose(copy) {
+                self.complete();
+                self.unchoose();
+            }
+            self.banned[copy] = true;
+        }
+    }
+
+    /// Finds every star containing the chosen copies but none of the banned
+    /// ones, by covering the ridge with the fewest copies able to complete it
+    /// first.
+    fn complete(&mut self) {
+        let mut best: Option<Vec<usize>> = None;
+        for (ridge, &count) in self.counts.iter().enumerate() {
+            if count == 0 || count == self.multiplicity {
+                continue;
+            }
+            let options: Vec<usize> = self.ridge_copies[ridge]
+                .iter()
+                .copied()
+                .filter(|&copy| !self.banned[copy] && !self.in_star[copy])
+                .collect();
+            if best.as_ref().map_or(true, |best| options.len() < best.len()) {
+                best = Some(options);
+            }
+        }
+
+        match best {
+            None => {
+                let mut star = self.chosen.clone();
+                star.sort_unstable();
+                self.found.push(star);
+            }
+            Some(options) => {
+                for &copy in &options {
+                    if self.choose(copy) {
+                        self.complete();
+                        self.unchoose();
+                    }
+                    self.banned[copy] = true;
+                }
+                for &copy in &options {
+                    self.banned[copy] = false;
+                }
+            }
+        }
+    }
+}
+
+/// The possible facets of a hyperplane orbit as standalone polytopes, kept in
+/// the tables when [`FacetingOptions::collect_subfacetings`] is set. These are
+/// the facetings of the vertices on the hyperplane under its stabilizer.
+#[derive(Clone, Debug)]
+pub struct Subfacetings {
+    /// A hyperplane of the orbit.
+    pub hyperplane: Subspace<f64>,
+
+    /// The vertices on the hyperplane, as indices into the vertices faceted.
+    pub vertices: VertexList,
+
+    /// The symmetries of the vertices that fix the hyperplane, as
+    /// permutations of [`Self::vertices`] by their positions in the list.
+    pub stabilizer: Vec<VertexPermutation>,
+
+    /// The possible facets of the hyperplane orbit, in order, embedded in the
+    /// hyperplane.
+    pub facets: Vec<Concrete>,
+}
+
+/// The invariants of a possible facet of a hyperplane orbit, read off the
+/// tables without building the facet. These are the same as those of the
+/// polytope built by [`FacetingTables::subfaceting`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct FacetSummary {
+    /// The number of elements of each rank, from the minimal element up to
+    /// the facet itself.
+    pub element_counts: Vec<usize>,
+
+    /// The distinct lengths of the edges, in increasing order, as given by
+    /// [`ConcretePolytope::edge_lengths`].
+    pub edge_lengths: Vec<f64>,
+
+    /// The [`fingerprint`](crosscheck::fingerprint) of the facet.
+    pub fingerprint: u64,
+}
+
+impl FacetSummary {
+    /// Returns the number of vertices of the facet.
+    pub fn vertex_count(&self) -> usize {
+        self.element_counts.get(1).copied().unwrap_or(0)
+    }
+
+    /// Returns the number of edges of the facet.
+    pub fn edge_count(&self) -> usize {
+        self.element_counts.get(2).copied().unwrap_or(0)
+    }
+
+    /// Returns whether all edges of the facet have the same length.
+    pub fn is_equilateral(&self) -> bool {
+        self.edge_lengths.len() <= 1
+    }
+}
+
+#[cfg(test)]
+thread_local! {
+    /// The number of possible facets built by [`FacetingTables::subfaceting`]
+    /// on this thread.
+    pub(crate) static SUBFACETINGS_BUILT: std::cell::Cell<usize> = std::cell::Cell::new(0);
+}
+
+/// A possible facet of a hyperplane, along with its ridges, given as pairs of a
+/// ridge orbit of the hyperplane and an index within it.
+type PossibleFacet = (Ranks, Vec<(usize, usize)>);
+
+/// A combination of facets waiting to be checked while combining: the facets,
+/// the key every hyperplane orbit added to it must exceed, and the ridge
+/// multiplicities of every facet except the last.
+type Candidate = (Vec<(usize, usize)>, usize, Vec<usize>);
+
+/// An orbit of hyperplanes: a representative, the vertices on it, and the
+/// size of the orbit.
+type OrbitOfHyperplanes = (Subspace<f64>, VertexList, usize);
+
+/// Returns a rough estimate of the number of bytes taken up by a polytope.
+fn estimated_size(poly: &Concrete) -> usize {
+    let vertices = poly.vertices.iter().map(|v| v.len() * size_of::<f64>()).sum::<usize>();
+    let elements = (0..=poly.rank())
+        .flat_map(|r| poly[r].iter())
+        .map(|el| (el.subs.len() + el.sups.len()) * size_of::<usize>())
+        .sum::<usize>();
+    vertices + elements
+}
+
+/// Writes the facet orbits of a faceting as a signature, like `(0,1) (2,0)`.
+fn signature(facets: &[(usize, usize)]) -> String {
+    let facets: Vec<_> = facets.iter().map(|(hp, f)| format!("({},{})", hp, f)).collect();
+    facets.join(" ")
+}
+
+/// Returns the name of a faceting of a polytope, given by its index among the
+/// facetings found, if it was found among others, and its facet orbits.
+fn faceting_meta(source: &PolytopeMeta, idx: Option<usize>, facets: &[(usize, usize)]) -> PolytopeMeta {
+    let signature = signature(facets);
+    let faceting = match idx {
+        Some(idx) => format!("faceting #{}", idx),
+        None => "faceting".to_string(),
+    };
+
+    if source.name.is_some() {
+        source.derive_with(|name| format!("{} of {} ({})", faceting, name, signature))
+    } else {
+        PolytopeMeta::named(format!("{} ({})", faceting, signature))
+    }
+}
+
+/// Marks a faceting with the name of the uniform polytope it is, if any.
+fn label_uniform(poly: &mut Concrete) -> Option<UniformId> {
+    let id = poly.identify_uniform(UNIFORM_TOLERANCE)?;
+    poly.meta.properties.insert("uniform".to_string(), id.name.to_string());
+    Some(id)
+}
+
+/// Marks a faceting found with [`FacetingOptions::noble`] from a single facet
+/// orbit with whether it's noble under its own symmetry group.
+fn label_noble(poly: &mut Concrete, facets: &[(usize, usize)], options: &FacetingOptions) -> Option<NobleCertificate> {
+    if options.max_facet_orbits().is_none() || facets.len() != 1 {
+        return None;
+    }
+
+    let certificate = NobleCertificate::new(poly);
+    poly.meta.properties.insert("noble".to_string(), certificate.to_string());
+    Some(certificate)
+}
+
+/// Sorts the facetings found while combining, and removes the compounds of
+/// other facetings unless these were asked for.
+fn sort_facetings(mut facetings: Vec<Vec<(usize, usize)>>, include_compounds: bool) -> Vec<Vec<(usize, usize)>> {
+    facetings.sort_unstable();
+
+    if include_compounds {
+        facetings
+    } else {
+        debug!("Filtering mixed compounds...");
+        filter_irc(&facetings).into_iter().map(|idx| facetings[idx].clone()).collect()
+    }
+}
+
+/// A faceting kept for [`FacetingOptions::objective`], along with its score.
+/// Worse facetings compare greater, so that the worst one kept is at the top
+/// of a [`BinaryHeap`].
+#[derive(PartialEq, Eq)]
+struct Scored {
+    /// The score of the faceting.
+    score: OrderedFloat<f64>,
+
+    /// The facet orbits of the faceting.
+    facets: Vec<(usize, usize)>,
+}
+
+impl Ord for Scored {
+    fn cmp(&self, other: &Self) -> CmpOrdering {
+        other.score.cmp(&self.score).then_with(|| self.facets.cmp(&other.facets))
+    }
+}
+
+impl PartialOrd for Scored {
+    fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The facetings found so far, so that each one is only kept once. They're
+/// stored by their facet orbits under a hash of their facets, see
+/// [`FacetingTables::faceting_facets`], and the facets of those with the same
+/// hash are compared in full.
+#[derive(Default)]
+struct SeenFacetings(HashMap<u64, Vec<Vec<(usize, usize)>>>);
+
+impl SeenFacetings {
+    /// Adds a faceting given by its facet orbits, and returns whether no
+    /// faceting made of the same facets was added before.
+    fn insert(&mut self, tables: &FacetingTables, facets: &[(usize, usize)]) -> bool {
+        let key = tables.faceting_facets(facets);
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+
+        let same_hash = self.0.entry(hasher.finish()).or_default();
+        if same_hash.iter().any(|other| tables.faceting_facets(other) == key) {
+            return false;
+        }
+        same_hash.push(facets.to_vec());
+        true
+    }
+}
+
+/// The facetings found while combining facets, along with those found so
+/// far, so that each one is only kept once.
+enum Found<'a> {
+    /// The facet orbits of every faceting found.
+    All(Vec<Vec<(usize, usize)>>, SeenFacetings),
+
+    /// The best facetings found so far by an objective.
+    Best(&'a FacetingObjective, BinaryHeap<Scored>, SeenFacetings),
+}
+
+impl<'a> Found<'a> {
+    /// Starts with no facetings, keeping the best ones if the options have an
+    /// objective.
+    fn new(options: &'a FacetingOptions) -> Self {
+        match &options.objective {
+            Some(objective) => Self::Best(objective, BinaryHeap::new(), SeenFacetings::default()),
+            None => Self::All(Vec::new(), SeenFacetings::default()),
+        }
+    }
+
+    /// Returns the number of facetings kept.
+    fn len(&self) -> usize {
+        match self {
+            Self::All(list, _) => list.len(),
+            Self::Best(_, heap, _) => heap.len(),
+        }
+    }
+
+    /// Returns the facet orbits of the facetings kept, in no particular order.
+    fn facetings(&self) -> Vec<&Vec<(usize, usize)>> {
+        match self {
+            Self::All(list, _) => list.iter().collect(),
+            Self::Best(_, heap, _) => heap.iter().map(|scored| &scored.facets).collect(),
+        }
+    }
+
+    /// Returns whether the bound of the objective rules out every faceting
+    /// extending a candidate.
+    fn prunes(&self, candidate: &FacetingCandidate<'_>) -> bool {
+        match self {
+            Self::Best(objective, heap, _) if heap.len() >= objective.k => match (&objective.bound, heap.peek()) {
+                (Some(bound), Some(worst)) => bound(candidate) < worst.score.0,
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Adds a faceting given by its facet orbits. If there's an objective, the
+    /// faceting is built and scored right away, and dropped unless it's among
+    /// the best so far. Compounds are dropped then too unless they're
+    /// included, since there's no list of facetings to compare them against.
+    /// Facetings made of the same facets as one added before are dropped,
+    /// whatever facet orbits they were found with.
+    ///
+    /// Returns `false` if the faceting was dropped because there are already
+    /// as many as [`FacetingOptions::max_results`] allows, in which case the
+    /// search should stop.
+    fn push(&mut self, tables: &FacetingTables, facets: Vec<(usize, usize)>, options: &FacetingOptions, stats: &mut FacetingStats) -> bool {
+        let seen = match self {
+            Self::All(_, seen) | Self::Best(_, _, seen) => seen,
+        };
+        if !seen.insert(tables, &facets) {
+            trace!("{:?} dropped, as its facets were found before", facets);
+            stats.duplicate_count += 1;
+            return true;
+        }
+
+        match self {
+            Self::All(list, _) => {
+                if options.max_results.map_or(false, |max| list.len() >= max) {
+                    stats.truncated = true;
+                    return false;
+                }
+                list.push(facets);
+            }
+            Self::Best(objective, heap, _) => {
+                if !options.include_compounds && tables.is_compound(&facets, options.ridge_multiplicity) {
+                    return true;
+                }
+                let mut result = match tables.build(&facets) {
+                    Some((polytope, facet_orbits)) => FacetingResult::new(polytope, facets, &facet_orbits, tables.vertex_map.len()),
+                    None => return true,
+                };
+
+                result.polytope.meta = faceting_meta(&tables.meta, Some(stats.scored_count), &result.facets);
+                label_uniform(&mut result.polytope);
+                label_noble(&mut result.polytope, &result.facets, options);
+                stats.scored_count += 1;
+
+                let score = OrderedFloat(objective.score(&result));
+                heap.push(Scored { score, facets: result.facets });
+                if heap.len() > objective.k {
+                    heap.pop();
+                }
+            }
+        }
+        true
+    }
+
+    /// Returns the facet orbits of the facetings found. Without an objective
+    /// they're sorted and filtered by [`sort_facetings`], otherwise they're
+    /// sorted from best to worst.
+    fn into_facetings(self, include_compounds: bool) -> Vec<Vec<(usize, usize)>> {
+        match self {
+            Self::All(list, _) => sort_facetings(list, include_compounds),
+            Self::Best(_, heap, _) => heap.into_sorted_vec().into_iter().map(|scored| scored.facets).collect(),
+        }
+    }
+}
+
+/// The state of the search for facetings while combining facets. The search
+/// stops after every faceting found, and picks up where it left off when asked
+/// for the next one.
+struct Search {
+    /// The combinations of facets left to check.
+    stack: Vec<Candidate>,
+
+    /// The key of each hyperplane orbit. Every facet added to a candidate
+    /// comes from a hyperplane orbit whose key exceeds the candidate's.
+    hp_key: Vec<usize>,
+
+    /// Whether each possible facet of each hyperplane orbit can be added to a
+    /// candidate, see [`FacetingOptions::facet_filter`].
+    allowed: Vec<Vec<bool>>,
+
+    /// The hyperplane orbits not tried yet as the first facet, with the next
+    /// one at the back.
+    untried: Vec<usize>,
+
+    /// How often the facets of each hyperplane orbit gave exotic
+    /// combinations.
+    pruning: Vec<PruningCount>,
+
+    /// The number of hyperplane orbits tried so far as the first facet.
+    tried: usize,
+
+    /// The number of facetings found so far.
+    found: usize,
+
+    /// When the progress was last printed.
+    now: Instant,
+
+    /// Whether this search writes checkpoints, see
+    /// [`FacetingOptions::checkpoint`].
+    checkpoints: bool,
+
+    /// When the last checkpoint was written, and how many candidates had been
+    /// examined then.
+    checkpointed: (Instant, u64),
+}
+
+impl Search {
+    /// Starts the search for every faceting, trying the hyperplane orbits as
+    /// the first facet in the order given by the options.
+    fn new(tables: &FacetingTables, options: &FacetingOptions) -> Self {
+        // The hyperplane orbits are tried one at a time. A combination only uses facets from the orbit it started
+        // from and the ones not tried yet, which come after it in this key. Those not tried yet are ordered by index.
+        let hp_count = tables.possible_facets.len();
+        let mut untried = initial_orbit_order(
+            &tables.possible_facets.iter().map(Vec::len).collect::<Vec<_>>(),
+            options.orbit_order,
+        );
+        untried.reverse(); // so that we can pop from the back
+
+        Self {
+            stack: Vec::new(),
+            hp_key: (hp_count..2 * hp_count).collect(),
+            allowed: tables.allowed_facets(options),
+            untried,
+            pruning: vec![PruningCount::default(); hp_count],
+            tried: 0,
+            found: 0,
+            now: Instant::now(),
+            checkpoints: false,
+            checkpointed: (Instant::now(), 0),
+        }
+    }
+
+    /// Starts the search for the facetings containing some facets, which must
+    /// come from different hyperplane orbits and not cover any ridge too many
+    /// times. These facets are used even if the filter doesn't allow them.
+    fn from_partial(tables: &FacetingTables, partial: &[(usize, usize)], options: &FacetingOptions) -> Self {
+        // Every hyperplane orbit has a key above 0, so that facets can be added from any of them.
+        let hp_count = tables.possible_facets.len();
+
+        Self {
+            stack: vec![(
+                partial.to_vec(),
+                0,
+                tables.ridge_muls_of(&partial[..partial.len() - 1]),
+            )],
+            hp_key: (hp_count + 1..2 * hp_count + 1).collect(),
+            allowed: tables.allowed_facets(options),
+            untried: Vec::new(),
+            pruning: vec![PruningCount::default(); hp_count],
+            tried: 0,
+            found: 0,
+            now: Instant::now(),
+            checkpoints: false,
+            checkpointed: (Instant::now(), 0),
+        }
+    }
+
+    /// Returns whether a checkpoint is due, see
+    /// [`FacetingOptions::checkpoint`].
+    fn checkpoint_due(&self, options: &FacetingOptions, stats: &FacetingStats) -> bool {
+        match &options.checkpoint {
+            Some(checkpoint) if self.checkpoints => {
+                let (time, candidates) = self.checkpointed;
+                matches!(checkpoint.every_candidates, Some(every) if stats.candidates_examined - candidates >= every)
+                    || matches!(checkpoint.every, Some(every) if time.elapsed() >= every)
+            }
+            _ => false,
+        }
+    }
+
+    /// Moves on to the next hyperplane orbit to try as the first facet.
+    /// Returns `false` if there are none left.
+    fn next_root(&mut self, tables: &FacetingTables, options: &FacetingOptions, stats: &FacetingStats) -> bool {
+        // Between two orbits, we can reorder the ones left without finding anything twice.
+        if let OrbitOrder::Adaptive { interval } = options.orbit_order {
+            if self.tried > 0 && interval > 0 && self.tried % interval == 0 {
+                // The orbits to try first go at the back. The sort is stable, so this is deterministic.
+                let pruning = &self.pruning;
+                self.untried.sort_by(|a, b| {
+                    pruning[*a].rate().partial_cmp(&pruning[*b].rate()).unwrap()
+                });
+            }
+        }
+
+        if stats.cancelled {
+            return false;
+        }
+        let root_hp = match self.untried.pop() {
+            Some(root_hp) => root_hp,
+            None => return false,
+        };
+        self.hp_key[root_hp] = self.tried;
+        self.tried += 1;
+
+        let allowed = &self.allowed[root_hp];
+        self.stack = (0..tables.possible_facets[root_hp].len())
+            .filter(|&f| allowed[f])
+            .map(|f| (vec![(root_hp, f)], self.hp_key[root_hp], vec![0; tables.ridge_counts.len()]))
+            .collect();
+        true
+    }
+
+    /// Checks candidates until one is a faceting, and returns its facet orbits
+    /// with the compound facets split. Returns `None` once every candidate has
+    /// been checked, or if the search is cancelled.
+    ///
+    /// Checkpoints are only written between candidates, once the facetings
+    /// returned before have been added to the output.
+    fn next_faceting(
+        &mut self,
+        tables: &FacetingTables,
+        options: &FacetingOptions,
+        stats: &mut FacetingStats,
+        output: &Found<'_>,
+    ) -> Option<Vec<(usize, usize)>> {
+        loop {
+            loop {
+                if self.checkpoint_due(options, stats) {
+                    self.write_checkpoint(tables, options, stats, output);
+                }
+                let candidate = match self.stack.pop() {
+                    Some(candidate) => candidate,
+                    None => break,
+                };
+
+                if options.is_cancelled() {
+                    stats.stop_at(StopPoint::Combining { hyperplane_orbit: candidate.0.first().map(|&(hp, _)| hp) });
+                    if self.checkpoints {
+                        self.stack.push(candidate);
+                        self.write_checkpoint(tables, options, stats, output);
+                    }
+                    self.stack.clear();
+                    return None;
+                }
+
+                if let Some(facets) = self.check(candidate, tables, options, stats, output) {
+                    self.found += 1;
+                    return Some(facets);
+                }
+            }
+
+            if !self.next_root(tables, options, stats) {
+                return None;
+            }
+        }
+    }
+
+    /// Checks a candidate, and pushes the candidates extending it to the
+    /// stack. Returns the facet orbits of the candidate if it's a faceting
+    /// that's selected.
+    fn check(
+        &mut self,
+        (facets, min_key, cached_ridge_muls): Candidate,
+        tables: &FacetingTables,
+        options: &FacetingOptions,
+        stats: &mut FacetingStats,
+        output: &Found<'_>,
+    ) -> Option<Vec<(usize, usize)>> {
+        let noble = options.max_facet_orbits();
+        let FacetingOptions {
+            include_compounds,
+            ridge_multiplicity,
+            ..
+        } = *options;
+        let FacetingTables {
+            ref possible_facets,
+            ref ridge_idx_orbits,
+            ref ridge_muls,
+            ref ones,
+            ref pairing,
+            chiral_selection,
+            ..
+        } = *tables;
+        let hp_key = &self.hp_key;
+        let allowed = &self.allowed;
+        stats.candidates_examined += 1;
+
+        if let Some(progress) = &options.progress {
+            if self.now.elapsed().as_millis() > DELAY {
+                progress.report(FacetingProgress::Combining { found: self.found });
+                self.now = Instant::now();
+            }
+        }
+
+        let used_hps: HashSet<usize> = facets.iter().map(|facet| facet.0).collect();
+
+        // Later facets never come from hyperplanes with keys below `min_key`, so if the mirror image of one of the
+        // hyperplanes is there and unused, we'll never get a union of pairs.
+        if chiral_selection == ChiralSelection::PairedOnly {
+            let can_pair = used_hps.iter().all(|hp| match pairing[*hp] {
+                Some(pair) => hp_key[pair] >= min_key || used_hps.contains(&pair),
+                None => false,
+            });
+            if !can_pair {
+                trace!("{:?} rejected, as some hyperplane can't be paired with its mirror image", facets);
+                return None;
+            }
+        }
+
+        // The hyperplanes of a self-polar faceting must all have poles among the vertices.
+        if tables.options.require_self_polar.is_some() && used_hps.iter().any(|hp| tables.polar_pairing[*hp].is_none()) {
+            trace!("{:?} rejected, as some hyperplane has no pole among the vertices", facets);
+            return None;
+        }
+
+        let mut new_ridge_muls = cached_ridge_muls;
+
+        let &(hp, f) = facets.last().unwrap();
+        add_ridge_muls(&mut new_ridge_muls, &possible_facets[hp][f].1, &ridge_idx_orbits[hp], &ridge_muls[hp][f], ridge_multiplicity);
+
+        let mut valid = 0; // 0: valid, 1: exotic, 2: incomplete
+        for r in &new_ridge_muls {
+            if *r > ridge_multiplicity {
+                valid = 1;
+                break
+            }
+            if *r != 0 && *r != ridge_multiplicity {
+                valid = 2;
+            }
+        }
+        if facets.len() > 1 {
+            let count = &mut self.pruning[hp];
+            count.added += 1;
+            if valid == 1 {
+                count.exotic += 1;
+            }
+        }
+        let candidate = FacetingCandidate {
+            facets: &facets,
+            ridge_coverage: &new_ridge_muls,
+            complete: valid == 0,
+            tables,
+        };
+        let decision = match &options.candidate_callback {
+            Some(callback) if valid != 1 => callback.decide(&candidate),
+            _ => CandidateDecision::Accept,
+        };
+        if decision != CandidateDecision::Accept {
+            trace!("{:?} rejected by the callback: {:?}", facets, decision);
+            stats.candidates_rejected += 1;
+        }
+        if decision == CandidateDecision::RejectSubtree {
+            return None;
+        }
+        if valid != 1 && output.prunes(&candidate) {
+            trace!("{:?} pruned by the bound on the objective", facets);
+            stats.bound_pruned += 1;
+            return None;
+        }
+        match valid {
+            0 => {
+                let found = if decision == CandidateDecision::Accept && tables.is_selected(&facets, &used_hps) {
+                    trace!("{:?} accepted", facets);
+                    Some(tables.split_compound_facets(&facets))
+                } else {
+                    None
+                };
+
+                if include_compounds && noble != Some(facets.len()) {
+                    for (hp, list) in possible_facets.iter().enumerate() {
+                        if hp_key[hp] > min_key && !used_hps.contains(&hp) {
+                            for f in (0..list.len()).filter(|&f| allowed[hp][f]) {
+                                let mut new_facets = facets.clone();
+                                new_facets.push((hp, f));
+                                self.stack.push((new_facets, hp_key[hp], new_ridge_muls.clone()));
+                            }
+                        }
+                    }
+                }
+                found
+            }
+            2 => {
+                if noble == Some(facets.len()) {
+                    return None;
+                }
+                // We complete the first ridge orbit that's partially covered.
+                let (idx, mul) = new_ridge_muls
+                    .iter()
+                    .enumerate()
+                    .find(|(_, mul)| **mul != 0 && **mul != ridge_multiplicity)
+                    .unwrap();
+                let covering;
+                let completing = if ridge_multiplicity - mul == 1 {
+                    &ones[idx]
+                } else {
+                    covering = tables.covering_facets(idx, ridge_multiplicity - mul);
+                    &covering
+                };
+                for facet in completing {
+                    if hp_key[facet.0] > min_key && !used_hps.contains(&facet.0) && allowed[facet.0][facet.1] {
+                        let mut new_facets = facets.clone();
+                        new_facets.push(*facet);
+                        self.stack.push((new_facets, min_key, new_ridge_muls.clone()));
+                    }
+                }
+                None
+            }
+            _ => {
+                trace!("{:?} rejected, as it covers some ridge more than {} times", facets, ridge_multiplicity);
+                None
+            }
+        }
+    }
+}
+
+/// Returns the depth of a hyperplane, its distance from the center.
+fn hyperplane_depth(hyperplane: &Subspace<f64>, dim: usize) -> f64 {
+    hyperplane.distance(&Point::zeros(dim))
+}
+
+/// Returns some vertices sorted by their coordinates.
+fn coordinate_key<'a>(vertices: &'a [Point<f64>], list: &VertexList) -> Vec<&'a Point<f64>> {
+    let mut key: Vec<_> = list.iter().map(|&v| &vertices[v]).collect();
+    key.sort_by(|p, q| cmp_coordinates(p, q));
+    key
+}
+
+/// Compares two lists of vertices returned by [`coordinate_key`]
+/// lexicographically.
+fn cmp_keys(a: &[&Point<f64>], b: &[&Point<f64>]) -> CmpOrdering {
+    a.iter()
+        .zip(b)
+        .map(|(p, q)| cmp_coordinates(p, q))
+        .find(|&ordering| ordering != CmpOrdering::Equal)
+        .unwrap_or_else(|| a.len().cmp(&b.len()))
+}
+
+/// Sorts the hyperplane orbits from the outside in, by decreasing depth, then
+/// by decreasing number of vertices, and then by the coordinates of the
+/// vertices of their representatives. The orbits are indexed in this order,
+/// so that the indices don't depend on how the hyperplanes were found.
+fn sort_by_depth(hyperplane_orbits: &mut [(Subspace<f64>, VertexList, usize)], vertices: &[Point<f64>], dim: usize, tolerance: f64) {
+    hyperplane_orbits.sort_by(|a, b| {
+        let (depth_a, depth_b) = (hyperplane_depth(&a.0, dim), hyperplane_depth(&b.0, dim));
+        if (depth_a - depth_b).abs() > tolerance {
+            depth_b.partial_cmp(&depth_a).unwrap()
+        } else {
+            b.1.len().cmp(&a.1.len()).then_with(|| {
+                cmp_keys(&coordinate_key(vertices, &a.1), &coordinate_key(vertices, &b.1))
+            })
+        }
+    });
+}
+
+/// The possible facets of a hyperplane orbit, along with their ridges.
+struct HyperplaneFacets {
+    /// The possible facets, with local vertex indices, along with their
+    /// ridges.
+    possible: Vec<PossibleFacet>,
+
+    /// The same facets, with global vertex indices.
+    global: Vec<PossibleFacet>,
+
+    /// The possible facets that are compounds of other ones, mapped to their
+    /// components.
+    compound: HashMap<usize, (usize, usize)>,
+
+    /// The ridges of the possible facets, sorted into orbits under the
+    /// stabilizer of the hyperplane, with global vertex indices.
+    ridges: Vec<Vec<Ranks>>,
+
+    /// The number of ridges in each of these orbits.
+    ff_counts: Vec<usize>,
+
+    /// The degenerate possible facets, if they're flagged.
+    degenerate: Vec<usize>,
+
+    /// The number of degenerate possible facets found, whether or not they
+    /// were kept.
+    degenerate_count: usize,
+}
+
+impl HyperplaneFacets {
+    /// Takes the facetings of the vertices on a hyperplane from
+    /// [`faceting_subdim`], given the indices of these among all of the
+    /// vertices.
+    fn from_subdim((possible, ff_counts, mut ridges, compound): SubdimOutput, hp_v: &[usize]) -> Self {
+        let mut global = possible.clone();
+        for facet in &mut global {
+            globalize(&mut facet.0, hp_v);
+        }
+
+        for ridges_row_row in &mut ridges {
+            for ridge in ridges_row_row {
+                globalize(ridge, hp_v);
+                ridge.element_sort_strong();
+            }
+        }
+
+        Self {
+            possible,
+            global,
+            compound,
+            ridges,
+            ff_counts,
+            degenerate: Vec::new(),
+            degenerate_count: 0,
+        }
+    }
+
+    /// Looks for the possible facets whose vertices span less than the
+    /// hyperplane, and flags or removes them depending on
+    /// [`FacetingOptions::degenerate_facets`].
+    fn check_degenerate(&mut self, vertices: &[Point<f64>], rank: usize, options: &FacetingOptions) {
+        if options.degenerate_facets == DegenerateFacets::Keep {
+            return;
+        }
+
+        let degenerate: Vec<_> = (0..self.global.len())
+            .filter(|&f| is_degenerate(&self.global[f].0, vertices, rank, options.degeneracy_tolerance))
+            .collect();
+        self.degenerate_count = degenerate.len();
+
+        if options.degenerate_facets == DegenerateFacets::Flag {
+            self.degenerate = degenerate;
+        } else {
+            self.remove(degenerate);
+        }
+    }
+
+    /// Removes some possible facets, along with the compounds of them, and
+    /// renumbers the rest. Their ridges are kept, as ridge orbits that no facet
+    /// covers don't get in the way.
+    fn remove(&mut self, facets: Vec<usize>) {
+        if facets.is_empty() {
+            return;
+        }
+
+        let mut removed: HashSet<_> = facets.into_iter().collect();
+        loop {
+            let count = removed.len();
+            for (&f, (a, b)) in &self.compound {
+                if removed.contains(a) || removed.contains(b) {
+                    removed.insert(f);
+                }
+            }
+            if removed.len() == count {
+                break;
+            }
+        }
+
+        let mut new_idx = Vec::with_capacity(self.possible.len());
+        let mut count = 0;
+        for f in 0..self.possible.len() {
+            if removed.contains(&f) {
+                new_idx.push(None);
+            } else {
+                new_idx.push(Some(count));
+                count += 1;
+            }
+        }
+
+        let mut f = 0;
+        self.possible.retain(|_| {
+            f += 1;
+            new_idx[f - 1].is_some()
+        });
+        let mut f = 0;
+        self.global.retain(|_| {
+            f += 1;
+            new_idx[f - 1].is_some()
+        });
+        self.compound = self
+            .compound
+            .iter()
+            .filter_map(|(&f, &(a, b))| Some((new_idx[f]?, (new_idx[a]?, new_idx[b]?))))
+            .collect();
+    }
+}
+
+/// The vertices of a polytope being faceted, along with their symmetries. These
+/// don't depend on the edge lengths.
+struct FacetingSetup {
+    /// The vertices to facet.
+    vertices: Vec<Point<f64>>,
+
+    /// The same vertices, ordered so that they can be sorted.
+    vertices_ord: Vec<PointOrd<f64>>,
+
+    /// The symmetries of the vertices.
+    vertex_map: Vec<VertexPermutation>,
+
+    /// The orbits of the vertices under the symmetries.
+    vertex_orbits: Vec<VertexOrbit>,
+
+    /// The rows of the vertex map used when enumerating hyperplanes.
+    half_map: Vec<VertexPermutation>,
+
+    /// The central inversion, if it's a symmetry.
+    inversion: Option<VertexPermutation>,
+
+    /// A reflection of the vertices, used to pair up hyperplane orbits.
+    reflection: Option<VertexPermutation>,
+
+    /// Which facetings to output, once the reflection is known.
+    chiral_selection: ChiralSelection,
+
+    /// The rank of the polytope.
+    rank: usize,
+
+    /// The dimension of the space the vertices are in.
+    dim: usize,
+
+    /// The number of vertices, from the first one, whose hyperplanes are
+    /// already known. Only the hyperplanes through some other vertex are
+    /// enumerated.
+    known_vertices: usize,
+
+    /// The least and the greatest depth of the hyperplanes kept, given by
+    /// [`FacetingOptions::depth_filter`].
+    depth_bounds: Option<(f64, f64)>,
+
+    /// The indices of the vertices faceted among those passed in, if only
+    /// some of them are, see [`FacetingOptions::vertex_subset`].
+    subset: Option<Vec<usize>>,
+
+    /// The symmetries of the vertices found with a looser tolerance, if they
+    /// were asked for and there's more of them, see [`merge`].
+    near_map: Option<Vec<VertexPermutation>>,
+}
+
+impl FacetingSetup {
+    /// Sets up some vertices and their symmetries for faceting, outputting
+    /// every faceting.
+    fn new(vertices: Vec<Point<f64>>, vertex_map: Vec<VertexPermutation>, rank: usize, dim: usize) -> Self {
+        let vertices_ord = vertices.iter().map(|v| PointOrd::new(v.clone())).collect();
+        let vertex_orbits = VertexOrbit::orbits(vertices.len(), &vertex_map);
+
+        // If the group contains the central inversion, every hyperplane is in the same orbit as its antipode.
+        // We then only go through half of the group when enumerating hyperplanes, and get the rest by inverting.
+        let inversion = central_inversion(&vertices, &vertex_map);
+        let half_map = match &inversion {
+            Some(inversion) => half_vertex_map(&vertex_map, inversion),
+            None => vertex_map.clone(),
+        };
+
+        Self {
+            vertices,
+            vertices_ord,
+            vertex_map,
+            vertex_orbits,
+            half_map,
+            inversion,
+            reflection: None,
+            chiral_selection: ChiralSelection::Any,
+            rank,
+            dim,
+            known_vertices: 0,
+            depth_bounds: None,
+            subset: None,
+            near_map: None,
+        }
+    }
+
+    /// Returns the number of hyperplanes in the orbit of the one through some
+    /// vertices.
+    fn orbit_size(&self, list: &VertexList) -> usize {
+        let mut images = HashSet::new();
+        for row in &self.half_map {
+            let image = row.apply(list.as_slice());
+            if let Some(inversion) = &self.inversion {
+                images.insert(inversion.apply(image.as_slice()));
+            }
+            images.insert(image);
+        }
+        images.len()
+    }
+
+    /// Returns the image of some vertices under the symmetries that comes
+    /// first by [`coordinate_key`].
+    fn canonical_image(&self, list: &VertexList) -> VertexList {
+        let vertices = &self.vertices;
+
+        // Only the images through the first vertex in any of them can come
+        // first.
+        let first = list
+            .iter()
+            .flat_map(|&v| self.vertex_map.iter().map(move |row| row[v]))
+            .min_by(|&v, &w| cmp_coordinates(&vertices[v], &vertices[w]))
+            .expect("the vertex list is empty");
+
+        self.vertex_map
+            .iter()
+            .filter(|row| list.iter().any(|&v| row[v] == first))
+            .map(|row| {
+                let image = row.apply(list.as_slice());
+                (coordinate_key(vertices, &image), image)
+            })
+            .min_by(|(a, _), (b, _)| cmp_keys(a, b))
+            .unwrap()
+            .1
+    }
+
+    /// Enumerates the orbits of hyperplanes through the vertices, as a
+    /// hyperplane, the vertices on it, and the number of hyperplanes in the
+    /// orbit.
+    fn hyperplane_orbits(
+        &self,
+        edge_lengths: &EdgeLengthFilter,
+        options: &FacetingOptions,
+        stats: &mut FacetingStats,
+    ) -> Result<Vec<(Subspace<f64>, VertexList, usize)>, FacetingError> {
+        let FacetingOptions {
+            geometry: GeomContext { tolerance, .. },
+            min_inradius,
+            max_inradius,
+            exclude_hemis,
+            only_below_vertex,
+            ..
+        } = *options;
+        let Self {
+            ref vertices,
+            ref vertex_map,
+            ref vertex_orbits,
+            ref half_map,
+            ref inversion,
+            rank,
+            dim,
+            known_vertices,
+            ..
+        } = *self;
+
+        // Check that the hyperplanes can be enumerated in a reasonable time.
+        if options.hyperplanes.is_none() {
+            let structure = OrbitStructure::with_filter(vertices, vertex_map, edge_lengths);
+            info!("{}", structure);
+            if !structure.is_feasible() {
+                warn!("about {} vertex tuples to check, which will probably take too long", structure.estimated_tuples);
+                stats.warning_count += 1;
+            }
+            stats.estimated_tuples = stats.estimated_tuples.saturating_add(structure.estimated_tuples);
+            structure.check(options.abort_if_estimated_tuples_exceed)?;
+        }
+
+        info!("Enumerating hyperplanes...");
+        let stage_start = Instant::now();
+        let mut throttle = Throttle::new(options.progress.as_ref());
+
+        let mut hyperplane_orbits = Vec::new();
+
+        if let Some(hyperplanes) = &options.hyperplanes {
+            hyperplane_orbits.extend(self.given_hyperplane_orbits(hyperplanes, options)?);
+        }
+        else if only_below_vertex {
+            for v_orbit in vertex_orbits {
+                if options.is_cancelled() {
+                    stats.stop_at(StopPoint::Hyperplanes);
+                    break;
+                }
+                let mut map = BTreeMap::<OrderedFloat<f64>, Vec<usize>>::new();
+                let rep = v_orbit.representative();
+                let point = &vertices[rep];
+
+                for (idx, vertex) in vertices.iter().enumerate() {
+                    let dot = OrderedFloat(vertex.dot(point));
+                    if let Some(list) = map.get_mut(&dot) {
+                        list.push(idx);
+                    } else {
+                        map.insert(dot, vec![idx]);
+                    }
+                }
+
+                let mut checked = HashSet::new();
+
+                'd: for (_dot, l) in &map {
+                    let mut list = l.clone();
+                    list.sort_unstable();
+
+                    throttle.report(|| FacetingProgress::Hyperplanes { found: hyperplane_orbits.len() });
+
+                    // WLOG checks if the vertices are all the right distance away from the first vertex.
+                    for v in &list[1..] {
+                        if !edge_lengths.contains_within((&vertices[*v]-&vertices[list[0]]).norm(), tolerance) {
+                            continue 'd;
+                        }
+                    }
+
+                    // We define a hyperplane from the list of vertices.
+                    let points = list.iter().map(|x| &vertices[*x]);
+
+                    let hyperplane = Subspace::from_points_within(points, tolerance);
+
+                    if hyperplane.is_hyperplane() {
+                        let inradius = hyperplane.distance(&Point::zeros(dim));
+                        if let Some(min) = min_inradius {
+                            if inradius < min - tolerance {
+                                continue
+                            }
+                        }
+                        if let Some(max) = max_inradius {
+                            if inradius > max + tolerance {
+                                continue
+                            }
+                        }
+                        if exclude_hemis {
+                            if inradius.abs() < tolerance {
+                                continue
+                            }
+                        }
+
+                        let mut hyperplane_vertices = Vec::new();
+                        for (idx, v) in vertices.iter().enumerate() {
+                            if hyperplane.distance(v) < tolerance {
+                                hyperplane_vertices.push(idx);
+                            }
+                        }
+                        let hyperplane_vertices = VertexList::new(hyperplane_vertices);
+
+                        // Check if the hyperplane has been found already.
+                        let mut is_new = true;
+                        let mut counting = HashSet::<VertexList>::new();
+                        for row in half_map {
+                            let new_hp_v = row.apply(hyperplane_vertices.as_slice());
+
+                            if checked.contains(&new_hp_v) {
+                                is_new = false;
+                                break
+                            }
+
+                            if let Some(inversion) = &inversion {
+                                counting.insert(inversion.apply(new_hp_v.as_slice()));
+                            }
+                            counting.insert(new_hp_v);
+                        }
+                        if is_new {
+                            if let Some(inversion) = &inversion {
+                                checked.insert(inversion.apply(hyperplane_vertices.as_slice()));
+                            }
+                            checked.insert(hyperplane_vertices.clone());
+                            hyperplane_orbits.push((hyperplane, hyperplane_vertices, counting.len()));
+                        }
+                    }
+                }
+            }
+        }
+        else {
+
+            // Enumerate edges
+            let pair_orbits = pair_orbits(vertices, vertex_orbits, half_map, inversion.as_ref(), edge_lengths, tolerance, options.progress.as_ref());
+
+            debug!("{} edge orbit{}", pair_orbits.len(), if pair_orbits.len() == 1 {""} else {"s"});
+
+            // An edge length filter that no pair passes would silently give no facetings.
+            if pair_orbits.is_empty() && vertices.len() >= 2 && *edge_lengths != EdgeLengthFilter::default() {
+                let mut closest = [None; 3];
+                for (slot, length) in closest.iter_mut().zip(edge_lengths.closest_lengths(vertices, tolerance, 3)) {
+                    *slot = Some(length);
+                }
+                let err = FacetingError::NoAllowedEdges {
+                    requested: edge_lengths.single_length(),
+                    tolerance,
+                    closest,
+                };
+
+                if !options.allow_vacuous_edge_lengths {
+                    return Err(err);
+                }
+                warn!("{}", err);
+                stats.warning_count += 1;
+            }
+
+            // Enumerate subspaces between lines and hyperplanes
+
+            let mut tuple_orbits: Vec<Vec<usize>> = pair_orbits.iter().map(|orbit| orbit[0].clone()).collect();
+            'tuples: for number in 3..rank-1 {
+                let mut checked = HashSet::new();
+                let mut new_tuple_orbits = Vec::new();
+
+                for tuple in &tuple_orbits {
+                    if options.is_cancelled() {
+                        stats.stop_at(StopPoint::Hyperplanes);
+                        break 'tuples;
+                    }
+                    for new_vertex in tuple[tuple.len()-1]..vertices.len() {
+                        throttle.report(|| FacetingProgress::Subspaces { rank: number - 1, found: new_tuple_orbits.len() });
+
+                        if !edge_lengths.contains_within((&vertices[tuple[0]]-&vertices[new_vertex]).norm(), tolerance) {
+                            continue;
+                        }
+
+                        let mut new_tuple = tuple.clone();
+                        new_tuple.push(new_vertex);
+
+                        let mut already_seen = false;
+                        for row in half_map {
+                            if checked.contains(&row.apply(&new_tuple)) {
+                                already_seen = true;
+                                break;
+                            }
+                        }
+                        if already_seen {
+                            continue;
+                        }
+
+                        new_tuple.sort_unstable();
+
+                        let subspace = Subspace::from_points_within(new_tuple.iter().map(|x| &vertices[*x]), tolerance);
+                        if subspace.rank() == number-1 {
+                            new_tuple_orbits.push(new_tuple.clone());
+                        }
+
+                        if let Some(inversion) = &inversion {
+                            checked.insert(inversion.apply(&new_tuple));
+                        }
+                        checked.insert(VertexList::new(new_tuple));
+                    }
+                }
+                debug!("{} {}-plane orbit{}", new_tuple_orbits.len(), number-1, if new_tuple_orbits.len() == 1 {""} else {"s"});
+                tuple_orbits = new_tuple_orbits.iter().map(|x| x.clone()).collect();
+            }
+
+            // Enumerate hyperplanes
+            let mut checked = HashSet::new();
+
+            // A failed inradius check stops the search through a tuple, so the tuples of known vertices still need to
+            // be checked if there's any.
+            let stops_early = min_inradius.is_some() || max_inradius.is_some() || exclude_hemis;
+
+            for rep in tuple_orbits {
+                if options.is_cancelled() {
+                    stats.stop_at(StopPoint::Hyperplanes);
+                    break;
+                }
+                let last_vert = rep[rep.len()-1];
+
+                for new_vertex in last_vert+1..vertices.len() {
+                    let known = new_vertex < known_vertices;
+                    if known && !stops_early {
+                        continue;
+                    }
+
+                    let mut tuple = rep.clone();
+                    tuple.push(new_vertex);
+
+                    throttle.report(|| FacetingProgress::Hyperplanes { found: hyperplane_orbits.len() });
+
+                    if !edge_lengths.contains_within((&vertices[new_vertex]-&vertices[rep[0]]).norm(), tolerance) {
+                        continue;
+                    }
+
+                    let mut points = Vec::new();
+                    for v in tuple {
+                        points.push(vertices[v].clone());
+                    }
+
+                    let hyperplane = Subspace::from_points_within(points.iter(), tolerance);
+
+                    if hyperplane.is_hyperplane() {
+                        let inradius = hyperplane.distance(&Point::zeros(dim));
+                        if let Some(min) = min_inradius {
+                            if inradius < min - tolerance {
+                                break
+                            }
+                        }
+                        if let Some(max) = max_inradius {
+                            if inradius > max + tolerance {
+                                break
+                            }
+                        }
+                        if exclude_hemis {
+                            if inradius.abs() < tolerance {
+                                break
+                            }
+                        }
+                        if known {
+                            continue;
+                        }
+
+                        let mut hyperplane_vertices = Vec::new();
+                        for (idx, v) in vertices.iter().enumerate() {
+                            if hyperplane.distance(v) < tolerance {
+                                hyperplane_vertices.push(idx);
+                            }
+                        }
+                        let hyperplane_vertices = VertexList::new(hyperplane_vertices);
+
+                        // Check if the hyperplane has been found already.
+                        let mut is_new = true;
+                        let mut counting = HashSet::<VertexList>::new();
+                        for row in half_map {
+                            let new_hp_v = row.apply(hyperplane_vertices.as_slice());
+
+                            if checked.contains(&new_hp_v) {
+                                is_new = false;
+                                break
+                            }
+
+                            if let Some(inversion) = &inversion {
+                                counting.insert(inversion.apply(new_hp_v.as_slice()));
+                            }
+                            counting.insert(new_hp_v);
+                        }
+                        if is_new {
+                            if let Some(inversion) = &inversion {
+                                checked.insert(inversion.apply(hyperplane_vertices.as_slice()));
+                            }
+                            checked.insert(hyperplane_vertices.clone());
+                            hyperplane_orbits.push((hyperplane, hyperplane_vertices, counting.len()));
+                        }
+                    }
+                }
+            }
+        }
+
+        // Picks the representatives so that they only depend on the positions
+        // of the vertices, not on their order.
+        for (hyperplane, hyperplane_vertices, _) in &mut hyperplane_orbits {
+            *hyperplane_vertices = self.canonical_image(hyperplane_vertices);
+            *hyperplane = Subspace::from_points_within(hyperplane_vertices.iter().map(|&v| &vertices[v]), tolerance);
+        }
+        sort_by_depth(&mut hyperplane_orbits, vertices, dim, tolerance);
+
+        if let Some((min, max)) = self.depth_bounds {
+            let count = hyperplane_orbits.len();
+            hyperplane_orbits.retain(|(hyperplane, _, _)| {
+                let depth = hyperplane_depth(hyperplane, dim);
+                depth > min - tolerance && depth < max + tolerance
+            });
+
+            let removed = count - hyperplane_orbits.len();
+            debug!("{} hyperplane orbit{} removed by depth", removed, if removed == 1 {""} else {"s"});
+            stats.depth_filtered += removed;
+        }
+
+        let mut sum: u64 = 0;
+        for (idx, orbit) in hyperplane_orbits.iter().enumerate() {
+            debug!(
+                "hyperplane orbit {}: {} hyperplanes with {} vertices at depth {}",
+                idx,
+                orbit.2,
+                orbit.1.len(),
+                hyperplane_depth(&orbit.0, dim)
+            );
+            sum += orbit.2 as u64;
+        }
+
+        info!("{} hyperplanes in {} orbit{}", sum, hyperplane_orbits.len(), if hyperplane_orbits.len() == 1 {""} else {"s"});
+        stats.hyperplane_count += sum as usize;
+        stats.hyperplane_orbit_count += hyperplane_orbits.len();
+        stats.hyperplane_time += stage_start.elapsed();
+
+        // Every facet of a faceting is on one of the hyperplanes.
+        options.element_limits.check_rank(rank - 1, sum as usize)?;
+
+        Ok(hyperplane_orbits)
+    }
+
+    /// Returns the orbits of the hyperplanes given by
+    /// [`FacetingOptions::hyperplanes`], leaving out the ones filtered by
+    /// their inradius, like [`Self::hyperplane_orbits`] does before sorting
+    /// them.
+    fn given_hyperplane_orbits(
+        &self,
+        hyperplanes: &[Vec<usize>],
+        options: &FacetingOptions,
+    ) -> Result<Vec<OrbitOfHyperplanes>, FacetingError> {
+        let FacetingOptions {
+            geometry: GeomContext { tolerance, .. },
+            min_inradius,
+            max_inradius,
+            exclude_hemis,
+            ..
+        } = *options;
+        let vertices = &self.vertices;
+
+        let mut found = HashSet::new();
+        let mut hyperplane_orbits = Vec::new();
+
+        for (idx, list) in hyperplanes.iter().enumerate() {
+            if let Some(&vertex) = list.iter().find(|&&v| v >= vertices.len()) {
+                return Err(FacetingError::HyperplaneVertexOutOfRange {
+                    hyperplane: idx,
+                    vertex,
+                    vertex_count: vertices.len(),
+                });
+            }
+
+            let invalid = |rank| FacetingError::InvalidHyperplane {
+                hyperplane: idx,
+                rank,
+                expected: self.dim - 1,
+            };
+            if list.is_empty() {
+                return Err(invalid(0));
+            }
+            let hyperplane = Subspace::from_points_within(list.iter().map(|&v| &vertices[v]), tolerance);
+            if !hyperplane.is_hyperplane() {
+                return Err(invalid(hyperplane.rank()));
+            }
+
+            let inradius = hyperplane.distance(&Point::zeros(self.dim));
+            if matches!(min_inradius, Some(min) if inradius < min - tolerance)
+                || matches!(max_inradius, Some(max) if inradius > max + tolerance)
+                || (exclude_hemis && inradius.abs() < tolerance)
+            {
+                continue;
+            }
+
+            let hyperplane_vertices = (0..vertices.len())
+                .filter(|&v| hyperplane.distance(&vertices[v]) < tolerance)
+                .collect();
+            let hyperplane_vertices = self.canonical_image(&VertexList::new(hyperplane_vertices));
+            if found.insert(hyperplane_vertices.clone()) {
+                let size = self.orbit_size(&hyperplane_vertices);
+                hyperplane_orbits.push((hyperplane, hyperplane_vertices, size));
+            }
+        }
+
+        Ok(hyperplane_orbits)
+    }
+
+    /// Describes some hyperplane orbits found for the vertices, as returned
+    /// by [`Concrete::hyperplane_orbits`] and [`Stage::Hyperplanes`].
+    fn describe_hyperplane_orbits(&self, hyperplane_orbits: Vec<OrbitOfHyperplanes>) -> Vec<HyperplaneOrbit> {
+        hyperplane_orbits
+            .into_iter()
+            .map(|orbit| HyperplaneOrbit::new(orbit, &self.vertex_map, self.dim))
+            .collect()
+    }
+
+    /// Enumerates the hyperplane orbits like [`Self::hyperplane_orbits`], and
+    /// checks them for inconsistencies. If there are any and
+    /// [`FacetingOptions::tolerance_retry`] is set, they're enumerated again
+    /// with each of its tolerances in turn, until one gives consistent
+    /// orbits. Returns the orbits kept, along with the options with the
+    /// tolerance they were found with if it's not the first one.
+    fn consistent_hyperplane_orbits(
+        &self,
+        edge_lengths: &EdgeLengthFilter,
+        options: &FacetingOptions,
+        stats: &mut FacetingStats,
+    ) -> Result<(Vec<OrbitOfHyperplanes>, Option<FacetingOptions>), FacetingError> {
+        let before = stats.clone();
+        let hyperplane_orbits = self.hyperplane_orbits(edge_lengths, options, stats)?;
+        let inconsistencies = self.hyperplane_inconsistencies(&hyperplane_orbits, options.geometry.tolerance);
+        let first = match inconsistencies.first() {
+            Some(&first) => first,
+            None => return Ok((hyperplane_orbits, None)),
+        };
+        warn!("{} inconsistencies in the hyperplanes at tolerance {:e}, the first being that {}",
+            inconsistencies.len(), options.geometry.tolerance, first);
+        stats.warning_count += 1;
+
+        for &tolerance in options.tolerance_retry.iter().flatten() {
+            if stats.cancelled {
+                break;
+            }
+
+            info!("Retrying at tolerance {:e}...", tolerance);
+            let retry = FacetingOptions {
+                geometry: GeomContext {
+                    tolerance,
+                    ..options.geometry
+                },
+                ..options.clone()
+            };
+            let mut retry_stats = FacetingStats {
+                hyperplane_time: stats.hyperplane_time,
+                ..before.clone()
+            };
+            let retry_orbits = self.hyperplane_orbits(edge_lengths, &retry, &mut retry_stats)?;
+            let retry_inconsistencies = self.hyperplane_inconsistencies(&retry_orbits, tolerance);
+
+            if retry_inconsistencies.is_empty() {
+                info!("Consistent hyperplanes at tolerance {:e}", tolerance);
+                *stats = retry_stats;
+                stats.retry_tolerance = Some(tolerance);
+                return Ok((retry_orbits, Some(retry)));
+            }
+            info!("Still {} inconsistencies at tolerance {:e}", retry_inconsistencies.len(), tolerance);
+            stats.hyperplane_time = retry_stats.hyperplane_time;
+            if retry_stats.cancelled {
+                stats.stop_at(StopPoint::Hyperplanes);
+            }
+        }
+
+        stats.hyperplane_inconsistency_count += inconsistencies.len();
+        stats.first_hyperplane_inconsistency.get_or_insert(first);
+        Ok((hyperplane_orbits, None))
+    }
+
+    /// Checks that the images of each hyperplane under the symmetries, up to
+    /// [`CONSISTENCY_SAMPLE`] of them, have the images of its vertices on
+    /// them and no others, and that the number of hyperplanes in each orbit
+    /// agrees with the order of their stabilizers.
+    fn hyperplane_inconsistencies(
+        &self,
+        hyperplane_orbits: &[OrbitOfHyperplanes],
+        tolerance: f64,
+    ) -> Vec<HyperplaneInconsistency> {
+        let vertices = &self.vertices;
+        let mut inconsistencies = Vec::new();
+
+        for (hyperplane_orbit, (_, hp_v, orbit_size)) in hyperplane_orbits.iter().enumerate() {
+            let mut stabilizer_order = 0;
+            let mut checked = HashSet::new();
+
+            for (row, perm) in self.vertex_map.iter().enumerate() {
+                let image = perm.apply(hp_v.as_slice());
+                if &image == hp_v {
+                    stabilizer_order += 1;
+                }
+                if checked.len() == CONSISTENCY_SAMPLE || checked.contains(&image) {
+                    continue;
+                }
+
+                let hyperplane = Subspace::from_points_within(image.iter().map(|&v| &vertices[v]), tolerance);
+                if !hyperplane.is_hyperplane() {
+                    inconsistencies.push(HyperplaneInconsistency::NotSpanning { hyperplane_orbit, row });
+                } else {
+                    let found = vertices.iter().filter(|v| hyperplane.distance(v) < tolerance).count();
+                    if found != image.len() {
+                        inconsistencies.push(HyperplaneInconsistency::Membership {
+                            hyperplane_orbit,
+                            row,
+                            expected: image.len(),
+                            found,
+                        });
+                    }
+                }
+                checked.insert(image);
+            }
+
+            if stabilizer_order * orbit_size != self.vertex_map.len() {
+                inconsistencies.push(HyperplaneInconsistency::OrbitSize {
+                    hyperplane_orbit,
+                    orbit_size: *orbit_size,
+                    stabilizer_order,
+                    group_order: self.vertex_map.len(),
+                });
+            }
+        }
+
+        inconsistencies
+    }
+
+    /// Returns the mirror image of each hyperplane orbit, if the chiral
+    /// selection needs it.
+    fn pairing(&self, hyperplane_orbits: &[(Subspace<f64>, VertexList, usize)]) -> Vec<Option<usize>> {
+        if self.chiral_selection == ChiralSelection::Any {
+            Vec::new()
+        } else {
+            let hp_vs: Vec<_> = hyperplane_orbits.iter().map(|orbit| &orbit.1).collect();
+            let pairing = hyperplane_pairing(&hp_vs, &self.vertex_map, self.reflection.as_ref());
+            let self_paired = pairing.iter().enumerate().filter(|(idx, pair)| **pair == Some(*idx)).count();
+            debug!("{} self-paired hyperplane orbit{}", self_paired, if self_paired == 1 {""} else {"s"});
+            pairing
+        }
+    }
+
+    /// Facets the hyperplane of a hyperplane orbit with the edge lengths given
+    /// by the options, and finds the ridges of its possible facets. The memo
+    /// is used unless [`FacetingOptions::memoize_subdim`] is unset or noble
+    /// facetings are looked for, as the hyperplanes are then filtered using
+    /// the vertices of the whole polytope.
+    fn facet_hyperplane(
+        &self,
+        idx: usize,
+        orbit: &(Subspace<f64>, VertexList, usize),
+        options: &FacetingOptions,
+        memo: &mut SubdimMemo,
+    ) -> HyperplaneFacets {
+        let Self {
+            ref vertices_ord,
+            ref vertex_map,
+            rank,
+            ..
+        } = *self;
+
+        let edge_lengths = match options.per_orbit_edge_length.get(&idx) {
+            Some(filter) => filter.clone(),
+            None => options.edge_length.clone(),
+        };
+        let settings = SubdimSettings {
+            edge_lengths: &edge_lengths,
+            tolerance: options.geometry.tolerance,
+            max_per_hyperplane: options.max_per_hyperplane,
+            uniform: options.uniform,
+            cancel: options.cancel.as_deref(),
+            progress: options.progress.as_ref(),
+        };
+
+        let noble_package = if options.max_facet_orbits() == Some(1) {
+            Some((vertex_map.as_slice(), &orbit.1, orbit.2))
+        } else {
+            None
+        };
+
+        let memo = if options.memoize_subdim && noble_package.is_none() {
+            Some(memo)
+        } else {
+            None
+        };
+
+        let mut hp_facets = facet_subspace(rank, orbit.0.clone(), orbit.1.as_slice(), vertices_ord, vertex_map, settings, SubdimMode::Hyperplane(noble_package), memo);
+        hp_facets.check_degenerate(&self.vertices, rank, options);
+        hp_facets
+    }
+
+    /// Facets the hyperplanes, finds the ridges of the possible facets, and
+    /// collects everything needed to combine them into facetings.
+    fn tables(
+        &self,
+        hyperplane_orbits: &[(Subspace<f64>, VertexList, usize)],
+        edge_lengths: &EdgeLengthFilter,
+        options: &FacetingOptions,
+        meta: &PolytopeMeta,
+        stats: &mut FacetingStats,
+    ) -> FacetingTables {
+        let stage_start = Instant::now();
+
+        // The edge lengths are stored with the tables, so that they can be extended later.
+        let mut options = options.clone();
+        options.edge_length = edge_lengths.clone();
+
+        info!("Faceting hyperplanes...");
+
+        // Facet the hyperplanes
+        let mut facets = Vec::new();
+        let mut memo = SubdimMemo::default();
+        for (idx, orbit) in hyperplane_orbits.iter().enumerate() {
+            if options.is_cancelled() {
+                stats.stop_at(StopPoint::FacetTypes { hyperplane_orbit: idx });
+                break;
+            }
+            let hits = memo.hits;
+            let hp_facets = self.facet_hyperplane(idx, orbit, &options, &mut memo);
+            if options.is_cancelled() {
+                // The facets of this orbit may be missing some, but are kept.
+                stats.stop_at(StopPoint::FacetTypes { hyperplane_orbit: idx });
+            }
+            debug!("{}: {} facets, {}{}", idx, hp_facets.possible.len(), HyperplaneOrbit::new(orbit.clone(), &self.vertex_map, self.dim), if memo.hits > hits {" (congruent)"} else {""});
+            if let Some(progress) = &options.progress {
+                progress.report(FacetingProgress::HyperplaneFacets { done: idx + 1, total: hyperplane_orbits.len() });
+            }
+            facets.push(hp_facets);
+        }
+        stats.memoized_hyperplane_orbit_count += memo.hits;
+
+        stats.possible_facet_count += facets.iter().map(|hp_facets| hp_facets.possible.len()).sum::<usize>();
+        stats.degenerate_facet_count += facets.iter().map(|hp_facets| hp_facets.degenerate_count).sum::<usize>();
+        stats.facet_time += stage_start.elapsed();
+
+        // When cancelled, only the hyperplane orbits faceted so far are kept.
+        let hyperplane_orbits = &hyperplane_orbits[..facets.len()];
+        let pairing = self.pairing(hyperplane_orbits);
+        self.finish_tables(hyperplane_orbits, pairing, facets, options, meta, stats)
+    }
+
+    /// Finds the ridge orbits of the possible facets of each hyperplane orbit,
+    /// and collects everything needed to combine them into facetings.
+    fn finish_tables(
+        &self,
+        hyperplane_orbits: &[(Subspace<f64>, VertexList, usize)],
+        pairing: Vec<Option<usize>>,
+        facets: Vec<HyperplaneFacets>,
+        mut options: FacetingOptions,
+        meta: &PolytopeMeta,
+        stats: &mut FacetingStats,
+    ) -> FacetingTables {
+        let Self {
+            ref vertex_map,
+            chiral_selection,
+            rank,
+            ..
+        } = *self;
+
+        let stage_start = Instant::now();
+
+        // The insphere isn't known when extending the tables.
+        if let Some((min, max)) = self.depth_bounds {
+            options.depth_filter = Some(DepthFilter::Between(min, max));
+        }
+
+        info!("Computing ridges...");
+
+        let f_counts: Vec<usize> = hyperplane_orbits.iter().map(|orbit| orbit.2).collect();
+        let RidgeTables {
+            ridge_idx_orbits,
+            ridge_counts,
+            ridge_muls,
+            ones,
+        } = RidgeTables::new(&facets, &f_counts, vertex_map, options.progress.as_ref());
+
+        debug!("{} ridge orbit{}", ridge_counts.len(), if ridge_counts.len() == 1 {""} else {"s"});
+        stats.ridge_orbit_count += ridge_counts.len();
+        stats.ridge_time += stage_start.elapsed();
+
+        let mut possible_facets = Vec::new();
+        let mut possible_facets_global = Vec::new();
+        let mut compound_facets = Vec::new();
+        let mut ridges = Vec::new();
+        let mut ff_counts = Vec::new();
+        let mut degenerate_facets = Vec::new();
+        for hp_facets in facets {
+            possible_facets.push(hp_facets.possible);
+            possible_facets_global.push(hp_facets.global);
+            compound_facets.push(hp_facets.compound);
+            ridges.push(hp_facets.ridges);
+            ff_counts.push(hp_facets.ff_counts);
+            degenerate_facets.push(hp_facets.degenerate);
+        }
+
+        let mut tables = FacetingTables {
+            vertices: self.vertices.clone(),
+            vertex_map: vertex_map.clone(),
+            rank,
+            possible_facets,
+            possible_facets_global,
+            compound_facets,
+            ridge_idx_orbits,
+            ridge_counts,
+            ridge_muls,
+            ones,
+            hyperplanes: hyperplane_orbits.iter().map(|orbit| orbit.0.clone()).collect(),
+            hyperplane_vertices: hyperplane_orbits.iter().map(|orbit| orbit.1.clone()).collect(),
+            hyperplane_sizes: hyperplane_orbits.iter().map(|orbit| orbit.2).collect(),
+            depths: hyperplane_orbits.iter().map(|orbit| hyperplane_depth(&orbit.0, self.dim)).collect(),
+            ridges,
+            ff_counts,
+            degenerate_facets,
+            pairing,
+            chiral_selection,
+            polar_pairing: Vec::new(),
+            dim: self.dim,
+            options,
+            subfacetings: Vec::new(),
+            meta: meta.clone(),
+        };
+
+        if let Some(squared_radius) = tables.options.require_self_polar {
+            tables.polar_pairing = tables.polar_pairing(squared_radius);
+            let paired = tables.polar_pairing.iter().filter(|pair| pair.is_some()).count();
+            debug!("{} of {} hyperplane orbits polar to a vertex orbit", paired, tables.polar_pairing.len());
+        }
+        if tables.options.collect_subfacetings {
+            tables.subfacetings = tables.collect_subfacetings(stats);
+        }
+        tables
+    }
+}
+
+
+/// Everything needed to combine the possible facets of a polytope into
+/// facetings, for a fixed range of edge lengths. These are returned by
+/// [`Concrete::faceting_tables`], so that the facetings can be searched for in
+/// other ways than a full enumeration.
+///
+/// Facets are given as pairs of a hyperplane orbit and the index of one of its
+/// possible facets, as printed while faceting the hyperplanes.
+#[derive(Clone, Debug)]
+pub struct FacetingTables {
+    /// The vertices being faceted.
+    vertices: Vec<Point<f64>>,
+
+    /// The symmetries of the vertices.
+    vertex_map: Vec<VertexPermutation>,
+
+    /// The rank of the polytope.
+    rank: usize,
+
+    /// The possible facets of each hyperplane orbit, with local vertex
+    /// indices, along with their ridges.
+    possible_facets: Vec<Vec<PossibleFacet>>,
+
+    /// The same facets, with global vertex indices.
+    possible_facets_global: Vec<Vec<PossibleFacet>>,
+
+    /// The possible facets of each hyperplane orbit that are compounds of
+    /// other ones, mapped to their components.
+    compound_facets: Vec<HashMap<usize, (usize, usize)>>,
+
+    /// The ridge orbit of each ridge of each facet.
+    ridge_idx_orbits: Vec<Vec<Vec<usize>>>,
+
+    /// The number of ridges in each ridge orbit.
+    ridge_counts: Vec<usize>,
+
+    /// How many times the copies of each possible facet cover each ridge of
+    /// each ridge orbit.
+    ridge_muls: Vec<Vec<Vec<usize>>>,
+
+    /// The possible facets covering the ridges of each ridge orbit exactly
+    /// once.
+    ones: Vec<Vec<(usize, usize)>>,
+
+    /// A hyperplane of each hyperplane orbit.
+    hyperplanes: Vec<Subspace<f64>>,
+
+    /// The vertices on the hyperplane of each hyperplane orbit.
+    hyperplane_vertices: Vec<VertexList>,
+
+    /// The number of hyperplanes in each hyperplane orbit.
+    hyperplane_sizes: Vec<usize>,
+
+    /// The depth of each hyperplane orbit, which decreases with the index.
+    depths: Vec<f64>,
+
+    /// The ridges of the possible facets of each hyperplane orbit, sorted into
+    /// orbits under its stabilizer, with global vertex indices.
+    ridges: Vec<Vec<Vec<Ranks>>>,
+
+    /// The number of ridges in each of these orbits.
+    ff_counts: Vec<Vec<usize>>,
+
+    /// The possible facets of each hyperplane orbit whose vertices span less
+    /// than the hyperplane, if they're flagged.
+    degenerate_facets: Vec<Vec<usize>>,
+
+    /// The mirror image of each hyperplane orbit, if it's needed.
+    pairing: Vec<Option<usize>>,
+
+    /// Which facetings to output.
+    chiral_selection: ChiralSelection,
+
+    /// The vertex orbit of the pole of each hyperplane orbit, given by
+    /// [`FacetingTables::polar_pairing`], if only self-polar facetings are
+    /// output.
+    polar_pairing: Vec<Option<usize>>,
+
+    /// The dimension of the space the vertices are in.
+    dim: usize,
+
+    /// The options the tables were built with, along with the edge lengths
+    /// used.
+    options: FacetingOptions,
+
+    /// The possible facets of each hyperplane orbit as standalone polytopes,
+    /// if they were asked for.
+    subfacetings: Vec<Subfacetings>,
+
+    /// The metadata of the polytope being faceted.
+    meta: PolytopeMeta,
+}
+
+impl FacetingTables {
+    /// Returns the number of hyperplane orbits.
+    pub fn hyperplane_orbit_count(&self) -> usize {
+        self.possible_facets.len()
+    }
+
+    /// Returns the depth of a hyperplane orbit, the distance of its
+    /// hyperplanes from the center. The hyperplane orbits are sorted by
+    /// decreasing depth, and then by decreasing number of vertices.
+    pub fn depth(&self, hyperplane_orbit: usize) -> f64 {
+        self.depths[hyperplane_orbit]
+    }
+
+    /// Returns a hyperplane of a hyperplane orbit.
+    pub fn hyperplane(&self, hyperplane_orbit: usize) -> &Subspace<f64> {
+        &self.hyperplanes[hyperplane_orbit]
+    }
+
+    /// Returns the number of possible facets of a hyperplane orbit.
+    pub fn facet_count(&self, hyperplane_orbit: usize) -> usize {
+        self.possible_facets[hyperplane_orbit].len()
+    }
+
+    /// Returns the number of hyperplanes in a hyperplane orbit.
+    pub fn hyperplane_count(&self, hyperplane_orbit: usize) -> usize {
+        self.hyperplane_sizes[hyperplane_orbit]
+    }
+
+    /// Returns whether a possible facet of a hyperplane orbit was flagged as
+    /// degenerate, with vertices spanning less than the hyperplane. Facets are
+    /// only flagged with [`DegenerateFacets::Flag`].
+    pub fn is_degenerate(&self, hyperplane_orbit: usize, facet: usize) -> bool {
+        self.degenerate_facets[hyperplane_orbit].binary_search(&facet).is_ok()
+    }
+
+    /// Returns the index of the orbit of each vertex, with the orbits sorted
+    /// as in [`VertexOrbit::orbits`].
+    pub fn vertex_orbit_indices(&self) -> Vec<usize> {
+        let mut orbit_of = vec![0; self.vertices.len()];
+        for (idx, orbit) in VertexOrbit::orbits(self.vertices.len(), &self.vertex_map).iter().enumerate() {
+            for &v in orbit.iter() {
+                orbit_of[v] = idx;
+            }
+        }
+        orbit_of
+    }
+
+    /// Pairs each hyperplane orbit with the vertex orbit of its poles, with
+    /// respect to the sphere about the origin with some squared radius. The
+    /// polarity commutes with the symmetries, so the poles of a hyperplane
+    /// orbit are either all vertices of the same orbit, or none of them are.
+    ///
+    /// Returns the index of the vertex orbit, as given by
+    /// [`Self::vertex_orbit_indices`], for each hyperplane orbit, or `None`
+    /// if its poles aren't vertices.
+    pub fn polar_pairing(&self, squared_radius: f64) -> Vec<Option<usize>> {
+        polar_pairing(&self.hyperplanes, &self.vertices, &self.vertex_orbit_indices(), squared_radius)
+    }
+
+    /// Returns whether a faceting is its own polar, by the pairing stored in
+    /// the tables: every hyperplane orbit used must be paired, and the vertex
+    /// orbits paired with them must be exactly those of the vertices used.
+    fn is_self_polar(&self, facets: &[(usize, usize)]) -> bool {
+        let mut poles = HashSet::new();
+        for &(hp, _) in facets {
+            match self.polar_pairing[hp] {
+                Some(orbit) => poles.insert(orbit),
+                None => return false,
+            };
+        }
+
+        let vertex_orbit_of = self.vertex_orbit_indices();
+        let mut used = HashSet::new();
+        for &(hp, f) in facets {
+            for edge in &self.possible_facets_global[hp][f].0[2] {
+                used.extend(edge.subs.iter().map(|&v| vertex_orbit_of[v]));
+            }
+        }
+        poles == used
+    }
+
+    /// Returns the possible facets of each hyperplane orbit as standalone
+    /// polytopes, along with the hyperplane and its stabilizer. These are only
+    /// kept when [`FacetingOptions::collect_subfacetings`] is set, and the
+    /// list is empty otherwise.
+    pub fn subfacetings(&self) -> &[Subfacetings] {
+        &self.subfacetings
+    }
+
+    /// Builds the possible facets of every hyperplane orbit as standalone
+    /// polytopes, and counts them in the stats.
+    fn collect_subfacetings(&self, stats: &mut FacetingStats) -> Vec<Subfacetings> {
+        (0..self.hyperplane_orbit_count())
+            .map(|hp| {
+                let facets: Vec<_> = (0..self.facet_count(hp)).map(|f| self.subfaceting(hp, f)).collect();
+                stats.subfaceting_count += facets.len();
+                stats.subfaceting_bytes += facets.iter().map(estimated_size).sum::<usize>();
+
+                Subfacetings {
+                    hyperplane: self.hyperplanes[hp].clone(),
+                    vertices: self.hyperplane_vertices[hp].clone(),
+                    stabilizer: induced_stabilizer(&self.vertex_map, self.hyperplane_vertices[hp].as_slice()),
+                    facets,
+                }
+            })
+            .collect()
+    }
+
+    /// Builds a possible facet of a hyperplane orbit as a standalone polytope,
+    /// with the vertices on its edges in the order they're first found. The
+    /// facet is named after its facet orbit, like in the faceting names.
+    pub fn subfaceting(&self, hp: usize, f: usize) -> Concrete {
+        #[cfg(test)]
+        SUBFACETINGS_BUILT.with(|count| count.set(count.get() + 1));
+
+        let facet = &self.possible_facets_global[hp][f].0;
+        let mut to_new_idx = HashMap::new();
+        let mut vertices = Vec::new();
+        let mut edges = Vec::new();
+
+        for edge in &facet[2] {
+            let mut subs = Subelements::new();
+            for &v in &edge.subs {
+                let idx = *to_new_idx.entry(v).or_insert_with(|| {
+                    vertices.push(self.vertices[v].clone());
+                    vertices.len() - 1
+                });
+                subs.push(idx);
+            }
+            edges.push(subs);
+        }
+
+        let mut builder = AbstractBuilder::new();
+        builder.push_min();
+        builder.push_vertices(vertices.len());
+        builder.push_empty();
+        for subs in edges {
+            builder.push_subs(subs);
+        }
+        for r in 3..self.rank {
+            builder.push_empty();
+            for el in &facet[r] {
+                builder.push_subs(el.subs.clone());
+            }
+        }
+
+        // Safety: the possible facets are polytopes, built the same way as the facetings.
+        let abs = unsafe { builder.build() };
+        let meta = if self.meta.name.is_some() {
+            self.meta.derive_with(|name| format!("facet ({},{}) of {}", hp, f, name))
+        } else {
+            PolytopeMeta::named(format!("facet ({},{})", hp, f))
+        };
+
+        Concrete::new(vertices, abs).with_meta(meta)
+    }
+
+    /// Returns the invariants of a possible facet of a hyperplane orbit,
+    /// computed from its elements and the coordinates of its vertices without
+    /// building it like [`Self::subfaceting`] does.
+    pub fn facet_summary(&self, hp: usize, f: usize) -> FacetSummary {
+        let facet = &self.possible_facets_global[hp][f].0;
+
+        // The number of edges through each vertex of the facet.
+        let mut vertex_degrees = HashMap::new();
+        let mut lengths = Vec::with_capacity(facet[2].len());
+        for edge in &facet[2] {
+            for &v in &edge.subs {
+                *vertex_degrees.entry(v).or_insert(0) += 1;
+            }
+            lengths.push((&self.vertices[edge.subs[0]] - &self.vertices[edge.subs[1]]).norm());
+        }
+        lengths.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        lengths.dedup_by(|a, b| *a - *b < f64::EPS);
+
+        let mut degrees = vec![vec![(0, vertex_degrees.len())], vertex_degrees.values().map(|&sups| (1, sups)).collect()];
+        for r in 2..self.rank {
+            let mut sups = vec![0; facet[r].len()];
+            if r + 1 < self.rank {
+                for el in &facet[r + 1] {
+                    for &sub in &el.subs {
+                        sups[sub] += 1;
+                    }
+                }
+            }
+            degrees.push(facet[r].iter().zip(sups).map(|(el, sups)| (el.subs.len(), sups)).collect());
+        }
+
+        FacetSummary {
+            element_counts: degrees.iter().map(Vec::len).collect(),
+            edge_lengths: lengths,
+            fingerprint: crosscheck::degree_fingerprint(degrees),
+        }
+    }
+
+    /// Returns the possible facets of a hyperplane orbit, along with their
+    /// ridges.
+    fn hyperplane_facets(&self, hyperplane_orbit: usize) -> HyperplaneFacets {
+        HyperplaneFacets {
+            possible: self.possible_facets[hyperplane_orbit].clone(),
+            global: self.possible_facets_global[hyperplane_orbit].clone(),
+            compound: self.compound_facets[hyperplane_orbit].clone(),
+            ridges: self.ridges[hyperplane_orbit].clone(),
+            ff_counts: self.ff_counts[hyperplane_orbit].clone(),
+            degenerate: self.degenerate_facets[hyperplane_orbit].clone(),
+            degenerate_count: self.degenerate_facets[hyperplane_orbit].len(),
+        }
+    }
+
+    /// Returns the tables for the vertices faceted along with some new points,
+    /// as if they had been computed from scratch with the same options. The
+    /// new points come after the old vertices, and the updated vertex map
+    /// must be made out of the rows of the old one, in the same order,
+    /// extended to them.
+    ///
+    /// Only the hyperplanes through some new point are enumerated, and only
+    /// the hyperplanes with new points on them are faceted again. The others
+    /// keep their possible facets, unless these depend on the rest of the
+    /// vertices, as when looking for noble facetings.
+    ///
+    /// The new points don't come with a reflection, so every faceting is
+    /// output regardless of the chiral selection.
+    ///
+    /// # Panics
+    /// Panics if the updated vertex map doesn't extend the old one.
+    pub fn extend_with_vertices(
+        &self,
+        new_points: &[Point<f64>],
+        updated_vertex_map: Vec<Vec<usize>>,
+    ) -> Result<FacetingTables, FacetingError> {
+        self.extend_with_vertices_with_stats(new_points, updated_vertex_map).map(|(tables, _)| tables)
+    }
+
+    /// Extends the tables to some new points like
+    /// [`Self::extend_with_vertices`], and also returns the statistics of the
+    /// stages run, including how many hyperplane orbits were carried over.
+    pub fn extend_with_vertices_with_stats(
+        &self,
+        new_points: &[Point<f64>],
+        updated_vertex_map: Vec<Vec<usize>>,
+    ) -> Result<(FacetingTables, FacetingStats), FacetingError> {
+        let old_count = self.vertices.len();
+        let vertex_map = normalize_vertex_map(updated_vertex_map.into_iter().map(VertexPermutation::from).collect());
+        assert!(
+            vertex_map.len() == self.vertex_map.len()
+                && vertex_map.iter().zip(&self.vertex_map).all(|(new, old)| {
+                    new.len() == old_count + new_points.len() && new.0[..old_count] == old.0[..]
+                }),
+            "the updated vertex map must extend the old one"
+        );
+
+        let mut vertices = self.vertices.clone();
+        vertices.extend_from_slice(new_points);
+        let mut setup = FacetingSetup::new(vertices, vertex_map, self.rank, self.dim);
+        setup.depth_bounds = match self.options.depth_filter {
+            Some(filter) => Some(filter.bounds(|| None)?),
+            None => None,
+        };
+        if self.chiral_selection != ChiralSelection::Any {
+            info!("No reflection is known for the new vertices, outputting every faceting");
+        }
+
+        let mut stats = FacetingStats {
+            vertex_count: setup.vertices.len(),
+            vertex_orbit_count: setup.vertex_orbits.len(),
+            group_order: setup.vertex_map.len(),
+            central_symmetry: setup.inversion.is_some(),
+            ..Default::default()
+        };
+        let options = &self.options;
+
+        let hyperplane_orbits = if options.only_below_vertex {
+            // The hyperplanes below the new vertices can go through old vertices only, so we find them all again.
+            setup.hyperplane_orbits(&options.edge_length, options, &mut stats)?
+        } else {
+            setup.known_vertices = old_count;
+            let mut hyperplane_orbits =
+                setup.hyperplane_orbits(&options.edge_length, options, &mut stats)?;
+
+            // The old hyperplanes are still found from the same vertices, and may have gained new ones.
+            let mut found: HashSet<_> = hyperplane_orbits.iter().map(|orbit| orbit.1.clone()).collect();
+            for (hyperplane, hp_v) in self.hyperplanes.iter().zip(&self.hyperplane_vertices) {
+                let mut list = hp_v.as_slice().to_vec();
+                list.extend((old_count..setup.vertices.len()).filter(|&v| hyperplane.distance(&setup.vertices[v]) < options.geometry.tolerance));
+                let list = setup.canonical_image(&VertexList::new(list));
+
+                if found.insert(list.clone()) {
+                    let hyperplane = Subspace::from_points_within(list.iter().map(|&v| &setup.vertices[v]), options.geometry.tolerance);
+                    let size = setup.orbit_size(&list);
+                    hyperplane_orbits.push((hyperplane, list, size));
+                }
+            }
+
+            sort_by_depth(&mut hyperplane_orbits, &setup.vertices, setup.dim, options.geometry.tolerance);
+            stats.hyperplane_orbit_count = hyperplane_orbits.len();
+            stats.hyperplane_count = hyperplane_orbits.iter().map(|orbit| orbit.2).sum();
+            hyperplane_orbits
+        };
+
+        let stage_start = Instant::now();
+        let pairing = setup.pairing(&hyperplane_orbits);
+        let old_orbits: HashMap<_, _> = self.hyperplane_vertices.iter().enumerate().map(|(idx, hp_v)| (hp_v, idx)).collect();
+
+        info!("Faceting hyperplanes...");
+
+        let mut facets = Vec::new();
+        let mut memo = SubdimMemo::default();
+        for (idx, orbit) in hyperplane_orbits.iter().enumerate() {
+            // A hyperplane with the same vertices as before has the same possible facets, as long as these don't
+            // depend on the other vertices or on the index of the orbit.
+            let old_idx = old_orbits.get(&orbit.1).copied().filter(|&old_idx| {
+                options.max_facet_orbits() != Some(1)
+                    && options.per_orbit_edge_length.get(&old_idx) == options.per_orbit_edge_length.get(&idx)
+            });
+
+            let hits = memo.hits;
+            let hp_facets = match old_idx {
+                Some(old_idx) => {
+                    stats.reused_hyperplane_orbit_count += 1;
+                    self.hyperplane_facets(old_idx)
+                }
+                None => setup.facet_hyperplane(idx, orbit, options, &mut memo),
+            };
+
+            debug!("{}: {} facets, {}{}", idx, hp_facets.possible.len(), HyperplaneOrbit::new(orbit.clone(), &setup.vertex_map, setup.dim),
+                if old_idx.is_some() {" (reused)"} else if memo.hits > hits {" (congruent)"} else {""});
+            if let Some(progress) = &options.progress {
+                progress.report(FacetingProgress::HyperplaneFacets { done: idx + 1, total: hyperplane_orbits.len() });
+            }
+            facets.push(hp_facets);
+        }
+
+        stats.possible_facet_count += facets.iter().map(|hp_facets| hp_facets.possible.len()).sum::<usize>();
+        stats.degenerate_facet_count += facets.iter().map(|hp_facets| hp_facets.degenerate_count).sum::<usize>();
+        stats.memoized_hyperplane_orbit_count += memo.hits;
+        stats.facet_time += stage_start.elapsed();
+
+        let tables = setup.finish_tables(&hyperplane_orbits, pairing, facets, options.clone(), &self.meta, &mut stats);
+        Ok((tables, stats))
+    }
+
+    /// Returns how many times the copies of some facets cover each ridge of
+    /// each ridge orbit.
+    fn ridge_muls_of(&self, facets: &[(usize, usize)]) -> Vec<usize> {
+        let mut muls = vec![0; self.ridge_counts.len()];
+        for &(hp, f) in facets {
+            for ridge_idx in &self.possible_facets[hp][f].1 {
+                let ridge_orbit = self.ridge_idx_orbits[hp][ridge_idx.0][ridge_idx.1];
+                muls[ridge_orbit] += self.ridge_muls[hp][f][ridge_orbit];
+            }
+        }
+        muls
+    }
+
+    /// Returns the possible facets covering the ridges of an orbit at most a
+    /// given number of times, but at least once.
+    fn covering_facets(&self, ridge_orbit: usize, max_mul: usize) -> Vec<(usize, usize)> {
+        let mut covering = Vec::new();
+        for (hp, muls) in self.ridge_muls.iter().enumerate() {
+            for (f, muls) in muls.iter().enumerate() {
+                if (1..=max_mul).contains(&muls[ridge_orbit]) {
+                    covering.push((hp, f));
+                }
+            }
+        }
+        covering
+    }
+
+    /// Returns the facets of a faceting given by its facet orbits. Every image
+    /// of every facet is taken as its sorted edges with global vertex indices,
+    /// so that facetings made of the same facets give the same set, whatever
+    /// facet orbits they were found with. Compound facets are split into their
+    /// components first.
+    fn faceting_facets(&self, facets: &[(usize, usize)]) -> BTreeSet<Vec<(usize, usize)>> {
+        let mut key = BTreeSet::new();
+        for (hp, f) in self.split_compound_facets(facets) {
+            let facet = &self.possible_facets_global[hp][f].0;
+            for row in &self.vertex_map {
+                let mut edges: Vec<_> = facet[2]
+                    .iter()
+                    .map(|edge| {
+                        let (v, w) = (row[edge.subs[0]], row[edge.subs[1]]);
+                        (v.min(w), v.max(w))
+                    })
+                    .collect();
+                edges.sort_unstable();
+                key.insert(edges);
+            }
+        }
+        key
+    }
+
+    /// Returns whether a faceting, given by its facet orbits, is a compound of
+    /// others, that is, whether some of its facet orbits already cover every
+    /// ridge as many times as the ridge multiplicity or not at all.
+    fn is_compound(&self, facets: &[(usize, usize)], ridge_multiplicity: usize) -> bool {
+        // The complement of such a subset is one too, so we only check the subsets without the last facet.
+        let (_, rest) = match facets.split_last() {
+            Some(split) => split,
+            None => return false,
+        };
+
+        (1..1u64 << rest.len()).any(|mask| {
+            let subset: Vec<_> = rest
+                .iter()
+                .enumerate()
+                .filter(|(idx, _)| mask & (1 << idx) != 0)
+                .map(|(_, facet)| *facet)
+                .collect();
+            self.ridge_muls_of(&subset).iter().all(|&mul| mul == 0 || mul == ridge_multiplicity)
+        })
+    }
+
+    /// Splits the compound facets of a faceting into their components, and
+    /// sorts the result.
+    fn split_compound_facets(&self, facets: &[(usize, usize)]) -> Vec<(usize, usize)> {
+        let mut new_facets = Vec::new();
+
+        for (hp, idx) in facets {
+            let mut queue = VecDeque::new();
+            queue.push_back(*idx);
+            while let Some(next) = queue.pop_front() {
+                if let Some(components) = self.compound_facets[*hp].get(&next) {
+                    queue.push_back(components.0);
+                    queue.push_back(components.1);
+                } else {
+                    new_facets.push((*hp, next));
+                }
+            }
+        }
+        new_facets.sort_unstable();
+        new_facets
+    }
+
+    /// Returns whether a faceting using some hyperplane orbits is selected by
+    /// the chiral selection, and is its own polar if that's required.
+    fn is_selected(&self, facets: &[(usize, usize)], used_hps: &HashSet<usize>) -> bool {
+        if self.options.require_self_polar.is_some() && !self.is_self_polar(facets) {
+            return false;
+        }
+        if matches!(self.options.facet_orbit_count(), Some(count) if !count.contains(facets.len())) {
+            return false;
+        }
+
+        match self.chiral_selection {
+            ChiralSelection::Any => true,
+            ChiralSelection::PairedOnly => is_paired(&self.pairing, used_hps),
+            ChiralSelection::UnpairedOnly => !is_paired(&self.pairing, used_hps),
+        }
+    }
+
+    /// Returns whether each possible facet of each hyperplane orbit is allowed
+    /// by [`FacetingOptions::facet_filter`].
+    fn allowed_facets(&self, options: &FacetingOptions) -> Vec<Vec<bool>> {
+        self.possible_facets_global
+            .iter()
+            .enumerate()
+            .map(|(hp, list)| match &options.facet_filter {
+                Some(filter) => list.iter().enumerate().map(|(f, facet)| filter.allows(hp, f, &facet.0)).collect(),
+                None => vec![true; list.len()],
+            })
+            .collect()
+    }
+
+    /// Returns every facet of a faceting given by its facet orbits, with
+    /// global vertex indices, sorted by [`Ranks::element_sort_strong`].
+    fn facet_images(&self, facets: &[(usize, usize)]) -> Vec<Ranks> {
+        let mut images = HashSet::new();
+        for &(hp, f) in facets {
+            let facet = &self.possible_facets_global[hp][f].0;
+            for row in &self.vertex_map {
+                let mut image = row.apply_to_ranks(facet);
+                image.element_sort_strong();
+                images.insert(image);
+            }
+        }
+        images.into_iter().collect()
+    }
+
+    /// Keeps only the first of the facetings that are congruent under some
+    /// symmetries of the vertices, given by their vertex map. Returns the
+    /// facetings kept, and how many were left out.
+    fn dedup_congruent(
+        &self,
+        facetings: Vec<Vec<(usize, usize)>>,
+        full_map: &[VertexPermutation],
+    ) -> (Vec<Vec<(usize, usize)>>, usize) {
+        // The facets are numbered as they're found, so that the image of a
+        // faceting under a symmetry can be written as a sorted list of
+        // numbers. The least of these is the same for congruent facetings.
+        let mut facet_ids = HashMap::new();
+        let mut seen = HashSet::new();
+        let mut kept = Vec::new();
+        let mut removed = 0;
+
+        for facets in facetings {
+            let images = self.facet_images(&facets);
+            let key = full_map
+                .iter()
+                .map(|row| {
+                    let mut ids: Vec<_> = images
+                        .iter()
+                        .map(|facet| {
+                            let mut image = row.apply_to_ranks(facet);
+                            image.element_sort_strong();
+                            let next = facet_ids.len();
+                            *facet_ids.entry(image).or_insert(next)
+                        })
+                        .collect();
+                    ids.sort_unstable();
+                    ids
+                })
+                .min();
+
+            if seen.insert(key) {
+                kept.push(facets);
+            } else {
+                removed += 1;
+            }
+        }
+
+        (kept, removed)
+    }
+
+    /// Combines the possible facets into every faceting, trying the hyperplane
+    /// orbits as the first facet in the order given by the options.
+    fn combine(&self, options: &FacetingOptions, stats: &mut FacetingStats) -> Vec<Vec<(usize, usize)>> {
+        let mut search = Search::new(self, options);
+        search.checkpoints = true;
+        self.combine_from(search, Found::new(options), options, stats)
+    }
+
+    /// Goes on with a search for every faceting, adding the facetings found
+    /// to some output.
+    fn combine_from(
+        &self,
+        mut search: Search,
+        mut output: Found<'_>,
+        options: &FacetingOptions,
+        stats: &mut FacetingStats,
+    ) -> Vec<Vec<(usize, usize)>> {
+        while let Some(facets) = search.next_faceting(self, options, stats, &output) {
+            if !output.push(self, facets, options, stats) {
+                break;
+            }
+        }
+
+        info!("{} facetings", output.len());
+        output.into_facetings(options.include_compounds)
+    }
+
+    /// Sets up the search for facetings as a [`TwofoldCover`] problem, whose
+    /// items are the ridge orbits and whose options are the possible facets,
+    /// grouped by hyperplane orbit. Returns the problem along with the facet
+    /// of each option.
+    fn ridge_cover(&self, options: &FacetingOptions) -> (TwofoldCover, Vec<(usize, usize)>) {
+        let mut cover = TwofoldCover::new(self.ridge_counts.len()).with_cancel(options.cancel.clone());
+        let mut facets = Vec::new();
+        let allowed = self.allowed_facets(options);
+
+        for (hp, list) in self.possible_facets.iter().enumerate() {
+            for f in (0..list.len()).filter(|&f| allowed[hp][f]) {
+                let items: Vec<_> = self
+                    .ridge_muls_of(&[(hp, f)])
+                    .into_iter()
+                    .enumerate()
+                    .filter(|&(_, mul)| mul != 0)
+                    .collect();
+                cover.push_option(hp, &items);
+                facets.push((hp, f));
+            }
+        }
+
+        (cover, facets)
+    }
+
+    /// Combines the possible facets into every faceting, going through the
+    /// ridge orbits instead of the hyperplane orbits. Finds the same facetings
+    /// as [`Self::combine`].
+    fn combine_ridge_first(&self, options: &FacetingOptions, stats: &mut FacetingStats) -> Vec<Vec<(usize, usize)>> {
+        // The ridge orbits can only be gone through when each ridge is in two facets.
+        if options.ridge_multiplicity != 2 {
+            return self.combine(options, stats);
+        }
+
+        let (cover, facets) = self.ridge_cover(options);
+        let mut output = Found::new(options);
+        let mut push = |solution: &[usize]| {
+            let chosen: Vec<_> = solution.iter().map(|&idx| facets[idx]).collect();
+            let used_hps: HashSet<usize> = chosen.iter().map(|facet| facet.0).collect();
+            let accepted = match &options.candidate_callback {
+                Some(callback) => {
+                    let candidate = FacetingCandidate {
+                        facets: &chosen,
+                        ridge_coverage: &self.ridge_muls_of(&chosen),
+                        complete: true,
+                        tables: self,
+                    };
+                    callback.decide(&candidate) == CandidateDecision::Accept
+                }
+                None => true,
+            };
+            !accepted || !self.is_selected(&chosen, &used_hps) || output.push(self, self.split_compound_facets(&chosen), options, stats)
+        };
+
+        let cover_stats = if options.include_compounds {
+            let (solutions, cover_stats) = cover.solutions(options.max_facet_orbits());
+            for solution in &solutions {
+                if !push(solution) {
+                    break;
+                }
+            }
+            cover_stats
+        } else {
+            cover.minimal_solutions(options.max_facet_orbits(), push)
+        };
+        stats.candidates_examined += cover_stats.nodes;
+        stats.cancelled |= cover_stats.cancelled;
+
+        info!("{} facetings", output.len());
+        output.into_facetings(options.include_compounds)
+    }
+
+    /// Finds every faceting like [`Self::complete`] does for an empty
+    /// selection, but searching ridge orbit by ridge orbit. For every ridge
+    /// orbit, the facetings whose first covered ridge orbit is that one are
+    /// found by repeatedly choosing facets to cover the ridge orbit covered
+    /// only once with the fewest facets able to complete it.
+    ///
+    /// This is much faster when there are many more hyperplane orbits than
+    /// ridge orbits.
+    pub fn enumerate_ridge_first(&self, options: &FacetingOptions) -> Vec<FacetingResult> {
+        let mut stats = FacetingStats::default();
+        let facetings = self.combine_ridge_first(options, &mut stats);
+        self.results(facetings, options, &mut stats)
+    }
+
+    /// Builds the facetings found, skipping those that aren't dyadic, and
+    /// those left out by [`FacetingOptions::exclude_compounds`] or
+    /// [`FacetingOptions::exclude_fissaries`].
+    fn results(
+        &self,
+        facetings: Vec<Vec<(usize, usize)>>,
+        options: &FacetingOptions,
+        stats: &mut FacetingStats,
+    ) -> Vec<FacetingResult> {
+        let mut results = Vec::new();
+        for facets in facetings {
+            if let Some((polytope, facet_orbits)) = self.build(&facets) {
+                if options.excludes(&polytope, stats) {
+                    continue;
+                }
+
+                let mut result = FacetingResult::new(polytope, facets, &facet_orbits, self.vertex_map.len());
+                result.polytope.meta = faceting_meta(&self.meta, Some(results.len()), &result.facets);
+                label_uniform(&mut result.polytope);
+                label_noble(&mut result.polytope, &result.facets, options);
+                results.push(result);
+            }
+        }
+        results
+    }
+
+    /// Builds the structure given by some facet orbits, whether or not it
+    /// closes up into a polytope, along with its vertices. This can be used to
+    /// inspect why a combination of facets never completes to a faceting.
+    pub fn build_partial(&self, facets: &[(usize, usize)]) -> (PartialAbstract, Vec<Point<f64>>) {
+        let (ranks, to_old_idx, _) = self.facet_ranks(facets);
+        let vertices = to_old_idx.into_iter().map(|i| self.vertices[i].clone()).collect();
+        (PartialAbstract::new(ranks), vertices)
+    }
+
+    /// Enumerates the vertex stars of a vertex orbit: the ways to put copies of
+    /// the possible facets around a representative vertex, so that each ridge
+    /// through it is in none of them, or in as many as
+    /// [`FacetingOptions::ridge_multiplicity`] asks for. These are much quicker
+    /// to find than the facetings themselves, and every faceting that isn't
+    /// fissary has one of them around each of its vertices.
+    ///
+    /// Only the stars whose facets are connected through their ridges are
+    /// returned, as the others are unions of these. Stars related by a
+    /// symmetry fixing the vertex are only returned once, and the possible
+    /// facets that are compounds of others aren't used.
+    ///
+    /// # Panics
+    /// Panics if there's no such vertex orbit.
+    pub fn vertex_stars(&self, vertex_orbit: usize) -> Vec<VertexStar> {
+        let Self {
+            ref vertex_map,
+            ref possible_facets,
+            ref possible_facets_global,
+            rank,
+            ..
+        } = *self;
+        let vertex = VertexOrbit::orbits(self.vertices.len(), vertex_map)[vertex_orbit].representative();
+
+        // The copies of the possible facets through the vertex, told apart by their edges.
+        let mut copies = Vec::new();
+        let mut copy_orbits = Vec::new();
+        let mut copy_idxs = HashMap::new();
+        let mut copy_ridges = Vec::new();
+        let mut ridge_idxs = HashMap::new();
+
+        for (hp, list) in possible_facets_global.iter().enumerate() {
+            for (f, (facet, _)) in list.iter().enumerate() {
+                if self.compound_facets[hp].contains_key(&f) {
+                    continue;
+                }
+
+                for row in vertex_map {
+                    let mut copy = row.apply_to_ranks(facet);
+                    let edges = edge_set(&copy);
+                    if !edges.iter().any(|edge| edge.contains(&vertex)) || copy_idxs.contains_key(&(hp, f, edges.clone())) {
+                        continue;
+                    }
+                    copy_idxs.insert((hp, f, edges), copies.len());
+
+                    let mut ridges = Vec::new();
+                    for ridge in edge_sets(&copy, rank - 2) {
+                        if ridge.iter().any(|edge| edge.contains(&vertex)) {
+                            let next_idx = ridge_idxs.len();
+                            ridges.push(*ridge_idxs.entry(ridge).or_insert(next_idx));
+                        }
+                    }
+                    copy_ridges.push(ridges);
+
+                    copy.element_sort_strong_with_local(&possible_facets[hp][f].0);
+                    copies.push(copy);
+                    copy_orbits.push((hp, f));
+                }
+            }
+        }
+
+        let mut search = StarSearch::new(copy_ridges, ridge_idxs.len(), self.options.ridge_multiplicity);
+        search.run();
+
+        // Each copy mapped by each symmetry fixing the vertex.
+        let stabilizer: Vec<Vec<usize>> = vertex_map
+            .iter()
+            .filter(|row| row[vertex] == vertex)
+            .map(|row| {
+                copies
+                    .iter()
+                    .zip(&copy_orbits)
+                    .map(|(copy, &(hp, f))| copy_idxs[&(hp, f, edge_set(&row.apply_to_ranks(copy)))])
+                    .collect()
+            })
+            .collect();
+
+        let mut seen = HashSet::new();
+        let mut stars = Vec::new();
+        for star in search.found {
+            let canonical = stabilizer
+                .iter()
+                .map(|images| {
+                    let mut image: Vec<usize> = star.iter().map(|&copy| images[copy]).collect();
+                    image.sort_unstable();
+                    image
+                })
+                .min()
+                .unwrap_or_else(|| star.clone());
+            if !seen.insert(canonical) {
+                continue;
+            }
+
+            let facets: Vec<_> = star.iter().map(|&copy| copy_orbits[copy]).collect();
+            let (ranks, to_old_idx, _) = merge_facets(
+                rank,
+                star.iter().map(|&copy| copies[copy].clone()).collect(),
+                facets.clone(),
+            );
+
+            let abs = unsafe {
+                let mut builder = AbstractBuilder::new();
+                for rank in ranks {
+                    builder.push_empty();
+                    for el in rank {
+                        builder.push_subs(el.subs);
+                    }
+                }
+                builder.build()
+            };
+
+            stars.push(VertexStar {
+                vertex,
+                facets,
+                polytope: Concrete {
+                    vertices: to_old_idx.into_iter().map(|i| self.vertices[i].clone()).collect(),
+                    abs,
+                    meta: PolytopeMeta::default(),
+                    history: None,
+                },
+            });
+        }
+
+        stars
+    }
+
+    /// Returns the subelements of a faceting given by its facet orbits, the
+    /// indices of its vertices among those being faceted, and the facet orbit
+    /// of each of its facets.
+    fn facet_ranks(&self, facets: &[(usize, usize)]) -> (Ranks, Vec<usize>, Vec<(usize, usize)>) {
+        let Self {
+            ref vertex_map,
+            ref possible_facets,
+            ref possible_facets_global,
+            rank,
+            ..
+        } = *self;
+
+        let mut facet_set = HashSet::new();
+        let mut facet_vec = Vec::new();
+        let mut facet_vec_orbits = Vec::new();
+
+        for &facet_orbit in facets {
+            let facet = &possible_facets_global[facet_orbit.0][facet_orbit.1].0;
+            let facet_local = &possible_facets[facet_orbit.0][facet_orbit.1].0;
+
+            let mut of_this_orbit = HashSet::new();
+            for row in vertex_map {
+                let mut new_facet = row.apply_to_ranks(facet);
+                let mut edges = new_facet[2].clone();
+                for edge in &mut edges {
+                    edge.subs.sort();
+                }
+                edges.0.sort_by(|a, b| a.subs.cmp(&b.subs));
+                if let Some(_) = of_this_orbit.get(&edges) {
+                    continue;
+                }
+                of_this_orbit.insert(edges);
+
+                new_facet.element_sort_strong_with_local(facet_local);
+                facet_set.insert(new_facet.clone());
+                facet_vec.push(new_facet); // have to do this so you can predict the facet index
+                                        // also it makes the facets sorted by type so that's cool
+                facet_vec_orbits.push(facet_orbit);
+            }
+        }
+
+        merge_facets(rank, facet_vec, facet_vec_orbits)
+    }
+
+    /// Builds a faceting from its facet orbits. Returns the faceting along with
+    /// the facet orbit of each of its facets, or `None` if it's not dyadic.
+    fn build(&self, facets: &[(usize, usize)]) -> Option<(Concrete, Vec<(usize, usize)>)> {
+        let vertices = &self.vertices;
+        let (ranks, to_old_idx, facet_orbits) = self.facet_ranks(facets);
+
+        let abs = unsafe {
+            let mut builder = AbstractBuilder::new();
+            for rank in ranks {
+                builder.push_empty();
+                for el in rank {
+                    builder.push_subs(el.subs);
+                }
+            }
+
+            if builder.ranks().is_dyadic().is_err() {
+                return None;
+            }
+            builder.build()
+        };
+
+        let mut new_vertices = Vec::new();
+        for i in to_old_idx {
+            new_vertices.push(vertices[i].clone());
+        }
+
+        let poly = Concrete {
+            vertices: new_vertices.into(),
+            abs,
+            meta: PolytopeMeta::default(),
+            history: None,
+        };
+
+        // Every facet was taken from a hyperplane, so this can only fail from a
+        // bookkeeping bug. The hyperplanes themselves are only found up to the
+        // tolerance of the options.
+        if cfg!(debug_assertions) {
+            if let Err(err) = poly.verify_facet_planarity(PLANARITY_TOLERANCE.max(self.options.geometry.tolerance)) {
+                let facet = match err {
+                    PlanarityError::Degenerate { facet, .. } | PlanarityError::NotPlanar { facet, .. } => facet,
+                };
+                let (hp, f) = facet_orbits[facet];
+                panic!("faceting {:?}: {} (facet orbit ({},{}) of hyperplane orbit {})", facets, err, hp, f, hp);
+            }
+        }
+
+        Some((poly, facet_orbits))
+    }
+
+    /// Finds the facetings that contain some facets, given as pairs of a
+    /// hyperplane orbit and one of its possible facets. The facets must come
+    /// from different hyperplane orbits, and no ridge may be covered more
+    /// times than the ridge multiplicity by them. Completing an empty
+    /// selection finds every faceting.
+    ///
+    /// Unless [`FacetingOptions::include_compounds`] is set, every faceting
+    /// that's a compound of others is left out, even if the others don't
+    /// contain the facets.
+    pub fn complete(
+        &self,
+        partial: &[(usize, usize)],
+        options: &FacetingOptions,
+    ) -> Result<Vec<FacetingResult>, FacetingError> {
+        options.check_polytopes()?;
+        Ok(self.results(self.complete_facets(partial, options)?, options, &mut FacetingStats::default()))
+    }
+
+    /// Returns an iterator over every faceting that can be combined from the
+    /// tables, see [`FacetingIter`].
+    pub fn into_faceting_iter(self, options: &FacetingOptions) -> Result<FacetingIter, FacetingError> {
+        FacetingIter::new(self, options, FacetingStats::default())
+    }
+
+    /// Rebuilds the single faceting given by some facet orbits, as recorded in
+    /// [`FacetingResult::facets`], without searching for any others. Compound
+    /// facets are split into their components first, like they are when the
+    /// facetings are found, so the faceting is built in the same way.
+    ///
+    /// The facets must exist in these tables, and must cover every ridge twice
+    /// or not at all. Facets recorded from the same polytope, group and
+    /// options always do, so an error other than
+    /// [`ReplayError::InvalidFacet`] means the tables have changed.
+    pub fn build_single(&self, facets: &[(usize, usize)]) -> Result<FacetingResult, ReplayError> {
+        if facets.is_empty() {
+            return Err(ReplayError::NoFacets);
+        }
+        for &(hp, f) in facets {
+            if hp >= self.possible_facets.len() || f >= self.possible_facets[hp].len() {
+                return Err(ReplayError::InvalidFacet { hyperplane_orbit: hp, facet: f });
+            }
+        }
+
+        let facets = self.split_compound_facets(facets);
+        if let Some((ridge_orbit, &coverage)) = self
+            .ridge_muls_of(&facets)
+            .iter()
+            .enumerate()
+            .find(|(_, &mul)| mul != 0 && mul != 2)
+        {
+            return Err(ReplayError::OpenRidges { ridge_orbit, coverage });
+        }
+
+        let (polytope, facet_orbits) = self.build(&facets).ok_or(ReplayError::NotDyadic)?;
+        let mut result = FacetingResult::new(polytope, facets, &facet_orbits, self.vertex_map.len());
+        result.polytope.meta = faceting_meta(&self.meta, None, &result.facets);
+        label_uniform(&mut result.polytope);
+        Ok(result)
+    }
+
+    /// Finds the complexes that contain some facets like [`Self::complete`]
+    /// does, where every ridge is in as many facets as
+    /// [`FacetingOptions::ridge_multiplicity`]. These are only polytopes when
+    /// it's 2, so they're returned as partial polytopes, which won't be
+    /// dyadic otherwise. The objective in the options is ignored, as it
+    /// scores polytopes.
+    pub fn complexes(
+        &self,
+        partial: &[(usize, usize)],
+        options: &FacetingOptions,
+    ) -> Result<Vec<FacetingComplex>, FacetingError> {
+        options.check_ridge_multiplicity()?;
+        let options = FacetingOptions {
+            objective: None,
+            ..options.clone()
+        };
+
+        Ok(self
+            .complete_facets(partial, &options)?
+            .into_iter()
+            .map(|facets| {
+                let (complex, vertices) = self.build_partial(&facets);
+                FacetingComplex { complex, vertices, facets }
+            })
+            .collect())
+    }
+
+    /// Finds the facet orbits of the facetings that contain some facets, as
+    /// described in [`Self::complete`].
+    fn complete_facets(
+        &self,
+        partial: &[(usize, usize)],
+        options: &FacetingOptions,
+    ) -> Result<Vec<Vec<(usize, usize)>>, FacetingError> {
+        let mut stats = FacetingStats::default();
+
+        let facetings = if partial.is_empty() {
+            self.combine(options, &mut stats)
+        } else {
+            let mut used_hps = HashSet::new();
+            for &(hp, f) in partial {
+                if hp >= self.possible_facets.len() || f >= self.possible_facets[hp].len() {
+                    return Err(FacetingError::InvalidFacet { hyperplane_orbit: hp, facet: f });
+                }
+                if !used_hps.insert(hp) {
+                    return Err(FacetingError::RepeatedHyperplaneOrbit(hp));
+                }
+            }
+            if let Some(ridge_orbit) = self.ridge_muls_of(partial).iter().position(|&mul| mul > options.ridge_multiplicity) {
+                return Err(FacetingError::OverCoveredRidge { ridge_orbit });
+            }
+
+            let mut found = Found::new(options);
+            let mut search = Search::from_partial(self, partial, options);
+            while let Some(facets) = search.next_faceting(self, options, &mut stats, &found) {
+                if !found.push(self, facets, options, &mut stats) {
+                    break;
+                }
+            }
+            match found {
+                Found::All(mut output, _) => {
+                    output.sort_unstable();
+
+                    // The facetings the compounds would be made of don't all contain the partial selection, so we
+                    // can't just look for them among the others.
+                    if !options.include_compounds {
+                        output.retain(|facets| !self.is_compound(facets, options.ridge_multiplicity));
+                    }
+                    output
+                }
+                // The compounds were already dropped.
+                best => best.into_facetings(true),
+            }
+        };
+
+        Ok(facetings)
+    }
+}
+
+/// An iterator over the facetings of a polytope, which builds each one as
+/// soon as it's found, see [`Concrete::faceting_iter`]. It owns the state of
+/// the search, so that it can be stopped at any point, or the facetings
+/// written out one at a time without keeping them all in memory.
+///
+/// Unlike [`Concrete::faceting`], which sorts the facetings once they've all
+/// been found, this returns them in the order they're found in. Compounds are
+/// left out unless [`FacetingOptions::include_compounds`] is set, by checking
+/// each faceting on its own like [`FacetingTables::complete`] does, and so are
+/// the facetings caught by [`FacetingOptions::exclude_compounds`] and
+/// [`FacetingOptions::exclude_fissaries`].
+/// [`FacetingOptions::objective`] is ignored, as the best facetings can only
+/// be known at the end.
+pub struct FacetingIter {
+    /// The tables the facets are combined from.
+    tables: FacetingTables,
+
+    /// The options of the search.
+    options: FacetingOptions,
+
+    /// The state of the search.
+    search: Search,
+
+    /// The statistics of the faceting so far.
+    stats: FacetingStats,
+
+    /// The facetings found so far.
+    seen: SeenFacetings,
+}
+
+impl FacetingIter {
+    /// Starts the search for the facetings from some tables, carrying on some
+    /// statistics.
+    fn new(tables: FacetingTables, options: &FacetingOptions, stats: FacetingStats) -> Result<Self, FacetingError> {
+        options.check_polytopes()?;
+        let options = FacetingOptions {
+            objective: None,
+            ..options.clone()
+        };
+
+        Ok(Self {
+            search: Search::new(&tables, &options),
+            tables,
+            options,
+            stats,
+            seen: SeenFacetings::default(),
+        })
+    }
+
+    /// Returns the tables the facets are combined from.
+    pub fn tables(&self) -> &FacetingTables {
+        &self.tables
+    }
+
+    /// Returns the statistics of the faceting so far. Whether the faceting
+    /// was cancelled can be told from these once the iterator runs out.
+    pub fn stats(&self) -> &FacetingStats {
+        &self.stats
+    }
+}
+
+impl Iterator for FacetingIter {
+    type Item = FacetingResult;
+
+    fn next(&mut self) -> Option<FacetingResult> {
+        let Self {
+            ref tables,
+            ref options,
+            ref mut search,
+            ref mut stats,
+            ref mut seen,
+        } = *self;
+        let no_bound = Found::All(Vec::new(), SeenFacetings::default());
+        if stats.truncated {
+            return None;
+        }
+
+        loop {
+            let stage_start = Instant::now();
+            let facets = search.next_faceting(tables, options, stats, &no_bound);
+            stats.combine_time += stage_start.elapsed();
+            let facets = facets?;
+
+            if !options.include_compounds && tables.is_compound(&facets, options.ridge_multiplicity) {
+                continue;
+            }
+            if !seen.insert(tables, &facets) {
+                stats.duplicate_count += 1;
+                continue;
+            }
+
+            let stage_start = Instant::now();
+            let built = tables.build(&facets);
+            stats.build_time += stage_start.elapsed();
+
+            if let Some((polytope, facet_orbits)) = built {
+                if options.excludes(&polytope, stats) {
+                    continue;
+                }
+                if options.max_results.map_or(false, |max| stats.faceting_count >= max) {
+                    stats.truncated = true;
+                    return None;
+                }
+
+                let mut result = FacetingResult::new(polytope, facets, &facet_orbits, tables.vertex_map.len());
+                result.polytope.meta = faceting_meta(&tables.meta, Some(stats.faceting_count), &result.facets);
+                label_uniform(&mut result.polytope);
+                label_noble(&mut result.polytope, &result.facets, options);
+                stats.faceting_count += 1;
+                return Some(result);
+            }
+        }
+    }
+}
+
+impl Concrete {
+    /// Returns the vertex map of an orientation-reversing symmetry of the
+    /// polytope, if it has one. If a sorted subset of the vertices is given,
+    /// the symmetry must send it to itself, and its vertex map is restricted
+    /// to it, see [`VertexPermutation::restrict`]. The symmetry is looked for
+    /// in the full symmetry group if it's given, and computed otherwise.
+    fn orientation_reversing_map(
+        &mut self,
+        group: Option<Group<vec::IntoIter<Matrix<f64>>>>,
+        subset: Option<&[usize]>,
+        geometry: Option<&GeomContext>,
+    ) -> Option<VertexPermutation> {
+        let (group, vertex_map) = match group {
+            Some(group) => {
+                let vertex_map = self.get_vertex_map_in(group.clone(), geometry);
+                (group, vertex_map)
+            }
+            None => self.get_symmetry_group_in(geometry)?,
+        };
+        group
+            .zip(vertex_map)
+            .filter(|(el, _)| el.determinant() < 0.)
+            .find_map(|(_, row)| {
+                let row = VertexPermutation::from(row);
+                match subset {
+                    Some(subset) => row.restrict(subset),
+                    None => Some(row),
+                }
+            })
+    }
+
+    /// Splits the elements of a given rank into their orbits under a symmetry
+    /// group, given as for the faceting. The rank is given as for
+    /// [`Ranked::el_count`], so that the vertices have rank 1, the edges rank 2,
+    /// and so on. Each orbit is a list of element indices, and the orbits are
+    /// sorted by their smallest element, which goes first.
+    ///
+    /// The polytope is transitive on its elements of a rank exactly when they
+    /// form a single orbit. An error is returned if the symmetry group can't
+    /// be computed or isn't a group of permutations of the vertices.
+    pub fn element_orbits(&mut self, rank: usize, symmetry: GroupEnum) -> Result<Vec<Vec<usize>>, FacetingError> {
+        let vertex_map = self.symmetry_vertex_map(symmetry, self.vertex_count(), None)?;
+        Ok(element_orbits_under(self, rank, &vertex_map))
+    }
+
+    /// Returns the vertex map of a symmetry group of the polytope, given as
+    /// any of the inputs to the faceting. Repeated rows are removed, and the
+    /// identity is put first, see [`normalize_vertex_map`].
+    ///
+    /// The vertex map is checked to be a group of permutations of the
+    /// vertices. When the rotation group is computed, its orbits are also
+    /// checked against those of the full group.
+    pub(super) fn symmetry_vertex_map(
+        &mut self,
+        symmetry: GroupEnum,
+        vertex_count: usize,
+        geometry: Option<&GeomContext>,
+    ) -> Result<Vec<VertexPermutation>, FacetingError> {
+        self.named_symmetry_vertex_map(symmetry, vertex_count, geometry, false)
+            .map(|(vertex_map, _)| vertex_map)
+    }
+
+    /// Returns the vertex map of a symmetry group of the polytope like
+    /// [`Self::symmetry_vertex_map`], along with the name of the group when
+    /// asked to identify it and it's recognized. Only the groups given by
+    /// their matrices can be identified.
+    fn named_symmetry_vertex_map(
+        &mut self,
+        symmetry: GroupEnum,
+        vertex_count: usize,
+        geometry: Option<&GeomContext>,
+        identify: bool,
+    ) -> Result<(Vec<VertexPermutation>, Option<GroupName>), FacetingError> {
+        let normalize = |vertex_map: Vec<Vec<usize>>| {
+            normalize_vertex_map(vertex_map.into_iter().map(VertexPermutation::from).collect())
+        };
+
+        let (vertex_map, name) = match symmetry {
+            GroupEnum::ConcGroup(group) => {
+                info!("Computing vertex map...");
+                let vertex_map = self.get_vertex_map_in(group.clone(), geometry);
+                let name = log_group("Symmetry", &group, &vertex_map, identify);
+                (normalize(vertex_map), name)
+            },
+            GroupEnum::VertexMap(a) => (normalize_vertex_map(a.into_rows()), None),
+            GroupEnum::ChiralOf(group) => {
+                info!("Computing rotation vertex map...");
+                let rotations = group.rotations().cache();
+                let vertex_map = self.get_vertex_map_in(rotations.clone(), geometry);
+                let name = log_group("Rotation symmetry", &rotations, &vertex_map, identify);
+                (normalize(vertex_map), name)
+            },
+            GroupEnum::Chiral(chiral) => {
+                if chiral {
+                    info!("Computing rotation symmetry group...");
+                    let (group, full_map) = self.get_symmetry_group_in(geometry).ok_or(FacetingError::NoSymmetryGroup)?;
+                    let (rotations, vertex_map) = rotation_subgroup(self.rank() - 1, group, &full_map);
+                    let name = log_group("Rotation symmetry", &rotations, &vertex_map, identify);
+
+                    // A rotation group that's wrong shows up as orbits that don't match the full group.
+                    let full_map = normalize(full_map);
+                    let vertex_map = normalize(vertex_map);
+                    validate_vertex_map(&full_map, vertex_count)?;
+                    validate_vertex_map(&vertex_map, vertex_count)?;
+                    check_rotation_orbits(&vertex_map, &full_map, vertex_count)?;
+                    return Ok((vertex_map, name));
+                }
+                else {
+                    info!("Computing symmetry group...");
+                    let g = self.get_symmetry_group_in(geometry).ok_or(FacetingError::NoSymmetryGroup)?;
+                    let name = log_group("Symmetry", &g.0, &g.1, identify);
+                    (normalize(g.1), name)
+                }
+            },
+        };
+
+        validate_vertex_map(&vertex_map, vertex_count)?;
+        Ok((vertex_map, name))
+    }
+
+    /// Checks that the order of the symmetry group computed for the polytope
+    /// isn't suspiciously small compared to what the distances between its
+    /// vertices allow. Warns about it if it is, and returns an error unless
+    /// the options allow it.
+    fn check_group_order(
+        &self,
+        group_order: usize,
+        chiral: bool,
+        options: &FacetingOptions,
+        stats: &mut FacetingStats,
+    ) -> Result<(), FacetingError> {
+        if group_order > 1 && options.min_group_fraction.is_none() {
+            return Ok(());
+        }
+
+        let spectrum = self.distance_spectrum(SPECTRUM_TOLERANCE);
+        // At least half of the symmetries are rotations.
+        let bound = if chiral {
+            (spectrum.automorphism_bound / 2).max(1)
+        } else {
+            spectrum.automorphism_bound
+        };
+        let too_small = (group_order == 1 && bound > 1)
+            || matches!(options.min_group_fraction, Some(fraction) if (group_order as f64) < fraction * bound as f64);
+        if !too_small {
+            return Ok(());
+        }
+
+        let err = FacetingError::TrivialGroup {
+            group_order,
+            automorphism_bound: bound,
+            residual: spectrum.residual,
+        };
+        warn!("{}", err);
+        stats.warning_count += 1;
+        if options.allow_trivial_group {
+            Ok(())
+        } else {
+            Err(err)
+        }
+    }
+
+    /// Computes the symmetries of the vertices to facet, which are shared by
+    /// every edge length.
+    fn faceting_setup(
+        &mut self,
+        vertices: Vec<Point<f64>>,
+        symmetry: GroupEnum,
+        options: &FacetingOptions,
+        stats: &mut FacetingStats,
+    ) -> Result<FacetingSetup, FacetingError> {
+        let rank = Rank::from(self.rank());
+        if rank < MIN_FACETING_RANK {
+            return Err(FacetingError::UnsupportedRank(rank));
+        }
+        let mut chiral_selection = options.chiral_selection;
+
+        let computed = match symmetry {
+            GroupEnum::Chiral(chiral) => Some(chiral),
+            _ => None,
+        };
+        // The group the rotations were taken from already has the reflections.
+        let full_group = match &symmetry {
+            GroupEnum::ChiralOf(group) if chiral_selection != ChiralSelection::Any && options.reflection.is_none() => {
+                Some(group.clone())
+            }
+            _ => None,
+        };
+        let near_symmetry = if options.detect_split_orbits || options.auto_merge {
+            merge::near_symmetry(&symmetry)
+        } else {
+            None
+        };
+        let given_count = vertices.len();
+        let (mut vertex_map, mut group_name) =
+            self.named_symmetry_vertex_map(symmetry, given_count, Some(&options.geometry), options.identify_group)?;
+        if let Some(chiral) = computed {
+            self.check_group_order(vertex_map.len(), chiral, options, stats)?;
+        }
+
+        // Only the symmetries that send the subset to itself act on it.
+        let mut vertices = vertices;
+        let subset = match &options.vertex_subset {
+            Some(vertex_subset) => {
+                let subset = vertex_subset.resolve(&vertex_map, given_count)?;
+                info!("Faceting {} of the {} vertices", subset.len(), given_count);
+                vertices = subset.iter().map(|&v| vertices[v].clone()).collect();
+                vertex_map = restrict_vertex_map(&vertex_map, &subset);
+                group_name = None;
+                Some(subset)
+            }
+            None => None,
+        };
+        stats.group_order = vertex_map.len();
+        stats.group_name = group_name;
+
+        info!("Matching vertices...");
+
+        // Checking every r-tuple of vertices would take too long, so we put pairs into orbits first to reduce the number.
+        // I don't think we need to store the whole orbits at this point, but they might be useful if we want to improve the algorithm.
+        let vertex_count = vertices.len();
+        let mut setup = FacetingSetup::new(vertices, vertex_map, self.rank(), self.dim().unwrap());
+        let orbit_idx = setup.vertex_orbits.len();
+
+        info!("{} vertices in {} orbit{}", vertex_count, orbit_idx, if orbit_idx == 1 {""} else {"s"});
+        stats.vertex_count = vertex_count;
+        stats.vertex_orbit_count = orbit_idx;
+        stats.central_symmetry = setup.inversion.is_some();
+
+        // A reflection is needed to tell apart the mirror images among the hyperplane orbits.
+        let mut reflection = None;
+        if chiral_selection != ChiralSelection::Any {
+            if options.reflection.is_some() {
+                reflection = options.reflection.clone();
+            } else if given_count == self.vertices.len() {
+                info!("Computing orientation-reversing symmetry...");
+                reflection = self.orientation_reversing_map(full_group, subset.as_deref(), Some(&options.geometry));
+                if reflection.is_none() {
+                    info!("The vertices are chiral, so every faceting is too");
+                }
+            } else {
+                info!("No reflection was given for these vertices, outputting every faceting");
+                chiral_selection = ChiralSelection::Any;
+            }
+        }
+
+        setup.reflection = reflection;
+        setup.chiral_selection = chiral_selection;
+        if let Some(near_symmetry) = near_symmetry {
+            setup.near_map = self.near_vertex_map(near_symmetry, given_count, subset.as_deref(), &setup.vertex_map, options, stats);
+        }
+        setup.subset = subset;
+        setup.depth_bounds = match options.depth_filter {
+            Some(filter) => Some(filter.bounds(|| self.insphere_radius())?),
+            None => None,
+        };
+        Ok(setup)
+    }
+
+    /// Facets the hyperplanes through some vertices with the edge lengths given
+    /// by the options, and returns the tables used to combine the facets. This
+    /// allows facetings to be searched for one at a time with
+    /// [`FacetingTables::complete`].
+    pub fn faceting_tables(
+        &mut self,
+        vertices: Vec<Point<f64>>,
+        symmetry: GroupEnum,
+        options: &FacetingOptions,
+    ) -> Result<FacetingTables, FacetingError> {
+        self.faceting_tables_with_stats(vertices, symmetry, options).map(|(tables, _)| tables)
+    }
+
+    /// Returns the tables used to combine the facets like
+    /// [`Self::faceting_tables`], along with the statistics of the stages run
+    /// to get them.
+    pub fn faceting_tables_with_stats(
+        &mut self,
+        vertices: Vec<Point<f64>>,
+        symmetry: GroupEnum,
+        options: &FacetingOptions,
+    ) -> Result<(FacetingTables, FacetingStats), FacetingError> {
+        options.check_ridge_multiplicity()?;
+        let mut stats = FacetingStats::default();
+        let setup = self.faceting_setup(vertices, symmetry, options, &mut stats)?;
+        let (hyperplane_orbits, retry) =
+            setup.consistent_hyperplane_orbits(&options.edge_length, options, &mut stats)?;
+        let options = retry.as_ref().unwrap_or(options);
+        let (merged, hyperplane_orbits) = setup.check_split_orbits(hyperplane_orbits, options, &mut stats);
+        let setup = merged.as_ref().unwrap_or(&setup);
+        let tables = setup.tables(&hyperplane_orbits, &options.edge_length, options, &self.meta, &mut stats);
+        Ok((tables, stats))
+    }
+
+    /// Enumerates the facetings of a polytope under a provided symmetry group or vertex map.
+    /// If the symmetry group is not provided, it uses the full symmetry of the polytope.
+    pub fn faceting(
+        &mut self,
+        vertices: Vec<Point<f64>>,
+        symmetry: GroupEnum,
+        options: &FacetingOptions,
+    ) -> Vec<FacetingResult> {
+        self.faceting_with_stats(vertices, symmetry, options).0
+    }
+
+    /// Returns an iterator over the facetings of a polytope, which builds each
+    /// one as soon as it's found. The hyperplanes are faceted right away, and
+    /// an error is returned if that's aborted. See [`FacetingIter`] for how
+    /// the facetings differ from those of [`Self::faceting`].
+    pub fn faceting_iter(
+        &mut self,
+        vertices: Vec<Point<f64>>,
+        symmetry: GroupEnum,
+        options: &FacetingOptions,
+    ) -> Result<FacetingIter, FacetingError> {
+        options.check_polytopes()?;
+        let (tables, stats) = self.faceting_tables_with_stats(vertices, symmetry, options)?;
+        FacetingIter::new(tables, options, stats)
+    }
+
+    /// Enumerates the orbits of hyperplanes through the vertices of the
+    /// polytope under a symmetry group, without faceting them. If an edge
+    /// length is given, only the hyperplanes a faceting with that edge length
+    /// could use are kept. The orbits are found and sorted just like in
+    /// [`Self::faceting`]. See [`Self::try_hyperplane_orbits`] for the
+    /// statistics and the other options.
+    pub fn hyperplane_orbits(&mut self, symmetry: GroupEnum, edge_length: Option<f64>) -> Result<Vec<HyperplaneOrbit>, FacetingError> {
+        let vertices = self.vertices.to_vec();
+        let options = FacetingOptions {
+            edge_length: edge_length.map_or(EdgeLengthFilter::Any, EdgeLengthFilter::Single),
+            ..Default::default()
+        };
+
+        self.try_hyperplane_orbits(vertices, symmetry, &options).map(|(orbits, _)| orbits)
+    }
+
+    /// Builds the possible facets of each hyperplane orbit of the polytope
+    /// under a symmetry group as standalone polytopes, without combining them
+    /// into facetings, see [`FacetingTables::subfaceting`]. The edge length is
+    /// used like in [`Self::hyperplane_orbits`]. See
+    /// [`Self::try_possible_facets`] for the statistics and the other options.
+    pub fn possible_facets(&mut self, symmetry: GroupEnum, edge_length: Option<f64>) -> Result<Vec<Vec<Concrete>>, FacetingError> {
+        let vertices = self.vertices.to_vec();
+        let options = FacetingOptions {
+            edge_length: edge_length.map_or(EdgeLengthFilter::Any, EdgeLengthFilter::Single),
+            ..Default::default()
+        };
+
+        self.try_possible_facets(vertices, symmetry, &options).map(|(facets, _)| facets)
+    }
+
+    /// Builds the possible facets of each hyperplane orbit through some
+    /// vertices as standalone polytopes, with the edge lengths and filters
+    /// given by the options, and returns them along with the statistics of
+    /// the stages run to find them. These are the facets of the tables
+    /// [`Stage::FacetTypes`] stops at, for a single edge length.
+    pub fn try_possible_facets(
+        &mut self,
+        vertices: Vec<Point<f64>>,
+        symmetry: GroupEnum,
+        options: &FacetingOptions,
+    ) -> Result<(Vec<Vec<Concrete>>, FacetingStats), FacetingError> {
+        let (tables, stats) = self.faceting_tables_with_stats(vertices, symmetry, options)?;
+        let facets = (0..tables.hyperplane_orbit_count())
+            .map(|hp| (0..tables.facet_count(hp)).map(|f| tables.subfaceting(hp, f)).collect())
+            .collect();
+        Ok((facets, stats))
+    }
+
+    /// Enumerates the orbits of hyperplanes through some vertices with the
+    /// edge lengths and filters given by the options, and returns them along
+    /// with the statistics of the enumeration. These are the orbits
+    /// [`Stage::Hyperplanes`] stops at, for a single edge length.
+    pub fn try_hyperplane_orbits(
+        &mut self,
+        vertices: Vec<Point<f64>>,
+        symmetry: GroupEnum,
+        options: &FacetingOptions,
+    ) -> Result<(Vec<HyperplaneOrbit>, FacetingStats), FacetingError> {
+        let mut stats = FacetingStats::default();
+        let setup = self.faceting_setup(vertices, symmetry, options, &mut stats)?;
+        let (hyperplane_orbits, retry) =
+            setup.consistent_hyperplane_orbits(&options.edge_length, options, &mut stats)?;
+        let (merged, hyperplane_orbits) =
+            setup.check_split_orbits(hyperplane_orbits, retry.as_ref().unwrap_or(options), &mut stats);
+        let setup = merged.as_ref().unwrap_or(&setup);
+        Ok((setup.describe_hyperplane_orbits(hyperplane_orbits), stats))
+    }
+
+    /// Picks up a search for facetings from a checkpoint written with
+    /// [`FacetingOptions::checkpoint`], and returns every faceting found,
+    /// including those found before the checkpoint, along with the stats of
+    /// the whole search. The facetings are the same as those of a search that
+    /// wasn't interrupted, and they're built like those of
+    /// [`FacetingTables::complete`].
+    ///
+    /// The tables are read from the checkpoint, but the options aren't, so
+    /// they should be the ones the search was started with. Further
+    /// checkpoints are written if they ask for them.
+    pub fn faceting_resume<P: AsRef<Path>>(
+        state_path: P,
+        options: &FacetingOptions,
+    ) -> CacheResult<(Vec<FacetingResult>, FacetingStats)> {
+        match options.with_timer() {
+            Some((timed, timer)) => {
+                let (results, mut stats) = Self::faceting_resume_untimed(state_path, &timed)?;
+                timer.finish(options, &mut stats);
+                Ok((results, stats))
+            }
+            None => Self::faceting_resume_untimed(state_path, options),
+        }
+    }
+
+    /// Picks up a search like [`Self::faceting_resume`], ignoring
+    /// [`FacetingOptions::time_limit`].
+    fn faceting_resume_untimed<P: AsRef<Path>>(
+        state_path: P,
+        options: &FacetingOptions,
+    ) -> CacheResult<(Vec<FacetingResult>, FacetingStats)> {
+        let Checkpoint {
+            tables,
+            mut search,
+            facetings,
+            mut stats,
+        } = Checkpoint::load(BufReader::new(File::open(state_path)?))?;
+        search.allowed = tables.allowed_facets(options);
+        search.checkpoints = true;
+
+        // The facetings found before were already scored, if there's an objective.
+        let mut output = Found::new(options);
+        for facets in facetings {
+            output.push(&tables, facets, options, &mut FacetingStats::default());
+        }
+
+        let facetings = tables.combine_from(search, output, options, &mut stats);
+        let results = tables.results(facetings, options, &mut stats);
+        stats.faceting_count = results.len();
+        Ok((results, stats))
+    }
+
+    /// Enumerates the facetings of a polytope like [`Self::faceting`], and also
+    /// returns some statistics about the run. If the faceting is aborted, the
+    /// error is printed and nothing is returned.
+    pub fn faceting_with_stats(
+        &mut self,
+        vertices: Vec<Point<f64>>,
+        symmetry: GroupEnum,
+        options: &FacetingOptions,
+    ) -> (Vec<FacetingResult>, FacetingStats) {
+        match self.try_faceting_with_stats(vertices, symmetry, options) {
+            Ok(result) => result,
+            Err(err) => {
+                warn!("Faceting aborted: {}", err);
+                (Vec::new(), FacetingStats::default())
+            }
+        }
+    }
+
+    /// Enumerates the facetings of a polytope like [`Self::faceting_with_stats`],
+    /// but returns an error if the faceting is aborted. Nothing is returned if
+    /// the faceting stops early, see [`FacetingOptions::stop_after`].
+    pub fn try_faceting_with_stats(
+        &mut self,
+        vertices: Vec<Point<f64>>,
+        symmetry: GroupEnum,
+        options: &FacetingOptions,
+    ) -> Result<(Vec<FacetingResult>, FacetingStats), FacetingError> {
+        let (output, stats) = self.try_faceting_staged(vertices, symmetry, options)?;
+        match output {
+            PartialFacetingOutput::Full(output) => Ok((output, stats)),
+            _ => Ok((Vec::new(), stats)),
+        }
+    }
+
+    /// Counts the facetings of a polytope without building them, which is much
+    /// faster than [`Self::try_faceting_with_stats`] when they're only needed
+    /// for their number. This runs the faceting up to [`Stage::Count`],
+    /// whatever [`FacetingOptions::stop_after`] is set to. With
+    /// [`FacetingOptions::any_single_edge_length`], the counts for each edge
+    /// length are added up.
+    pub fn count_facetings(
+        &mut self,
+        vertices: Vec<Point<f64>>,
+        symmetry: GroupEnum,
+        options: &FacetingOptions,
+    ) -> Result<(FacetingCount, FacetingStats), FacetingError> {
+        let options = FacetingOptions {
+            stop_after: Stage::Count,
+            ..options.clone()
+        };
+        let (output, stats) = self.try_faceting_staged(vertices, symmetry, &options)?;
 
-                                new_ridge.element_sort_strong();
-                                if set.get(&new_ridge).is_none() {
-                                    set.insert(new_ridge);
-                                    count += 1;
-                                }
-                            }
-                            ridge_orbits.insert(ridge, (orbit_idx, count));
-                            r_i_o_row_row.push(orbit_idx);
-                            ridge_counts.push(count);
-                            orbit_idx += 1;
-                            
-                            if now.elapsed().as_millis() > DELAY {
-                                print!("{}{}/{} hp, {} ridges", CL, hp_i, hyperplane_orbits.len(), ridge_orbits.len());
-                                std::io::stdout().flush().unwrap();
-                                now = Instant::now();
-                            }
-                        }
-                    }
-                    r_i_o_row.push(r_i_o_row_row);
-                }
-                ridge_idx_orbits.push(r_i_o_row);
+        let mut count = FacetingCount::default();
+        if let PartialFacetingOutput::Count(counts) = output {
+            for other in counts {
+                count.append(other);
+            }
+        }
+        Ok((count, stats))
+    }
 
-                print!("{}{}/{} hp, {} ridges", CL, hp_i+1, hyperplane_orbits.len(), ridge_orbits.len());
-                std::io::stdout().flush().unwrap();
+    /// Runs the faceting like [`Self::try_faceting_with_stats`] up to the
+    /// stage given by [`FacetingOptions::stop_after`], and returns what that
+    /// stage found.
+    pub fn try_faceting_staged(
+        &mut self,
+        vertices: Vec<Point<f64>>,
+        symmetry: GroupEnum,
+        options: &FacetingOptions,
+    ) -> Result<(PartialFacetingOutput, FacetingStats), FacetingError> {
+        match options.with_timer() {
+            Some((timed, timer)) => {
+                let (output, mut stats) = self.try_faceting_staged_untimed(vertices, symmetry, &timed)?;
+                timer.finish(options, &mut stats);
+                Ok((output, stats))
             }
+            None => self.try_faceting_staged_untimed(vertices, symmetry, options),
+        }
+    }
 
-            // Actually do the faceting
-            println!("\n\nCombining...");
+    /// Runs the faceting like [`Self::try_faceting_staged`], ignoring
+    /// [`FacetingOptions::time_limit`].
+    fn try_faceting_staged_untimed(
+        &mut self,
+        vertices: Vec<Point<f64>>,
+        symmetry: GroupEnum,
+        options: &FacetingOptions,
+    ) -> Result<(PartialFacetingOutput, FacetingStats), FacetingError> {
+        let FacetingOptions {
+            any_single_edge_length,
+            mark_fissary,
+            label_facets,
+            save,
+            save_facets,
+            save_to_file,
+            ref file_path,
+            stop_after,
+            check_conditioning,
+            ..
+        } = *options;
+        if stop_after == Stage::Full {
+            options.check_polytopes()?;
+        } else {
+            options.check_ridge_multiplicity()?;
+        }
 
-            let mut ridge_muls = Vec::new();
-            let mut ones = vec![Vec::<(usize, usize)>::new(); ridge_counts.len()];
+        let mut stats = FacetingStats::default();
+        let rank = Rank::from(self.rank());
 
-            for (hp, list) in possible_facets.iter().enumerate() {
-                let mut ridge_muls_hp = Vec::new();
-                for (f, _) in list.iter().enumerate() {
-                    let mut ridge_muls_facet = vec![0; ridge_counts.len()];
+        if rank < MIN_FACETING_RANK {
+            warn!("faceting polytopes of rank less than {} is not supported", MIN_FACETING_RANK);
+            stats.warning_count += 1;
+            return Ok((PartialFacetingOutput::empty(stop_after), stats))
+        }
 
-                    let f_count = f_counts[hp];
-    
-                    let ridge_idxs_local = &possible_facets[hp][f].1;
-                    for ridge_idx in ridge_idxs_local {
-                        let ridge_orbit = ridge_idx_orbits[hp][ridge_idx.0][ridge_idx.1];
-                        let ridge_count = ff_counts[hp][ridge_idx.0];
-                        let total_ridge_count = ridge_counts[ridge_orbit];
-                        let mul = f_count * ridge_count / total_ridge_count;
-
-                        if mul == 1 {
-                            ones[ridge_orbit].push((hp, f));
-                        }
-        
-                        ridge_muls_facet[ridge_orbit] = mul;
-                    }
+        if check_conditioning {
+            let report = conditioning_report(&vertices, f64::EPS);
+            if report.rating != Conditioning::Good {
+                warn!("{}", report);
+                warn!("offending vertices: {:?}", report.offending_vertices());
+                stats.warning_count += 1;
+            }
+            stats.conditioning = Some(report.rating);
+        }
 
-                    ridge_muls_hp.push(ridge_muls_facet);
+        let full_symmetry = matches!(symmetry, GroupEnum::Chiral(false));
+        let full_group = match &symmetry {
+            GroupEnum::ChiralOf(group) if options.dedup_congruent => Some(group.clone()),
+            _ => None,
+        };
+        let given_count = vertices.len();
+        let setup = self.faceting_setup(vertices, symmetry, options, &mut stats)?;
+        let FacetingSetup {
+            ref vertices,
+            ref vertex_orbits,
+            ref subset,
+            ..
+        } = setup;
+
+        // Congruent facetings are only found more than once under a smaller group than the full one.
+        let mut full_map = None;
+        if options.dedup_congruent && !full_symmetry {
+            if given_count == self.vertices.len() {
+                let vertex_map = match full_group {
+                    Some(group) => Some(self.get_vertex_map_in(group, Some(&options.geometry))),
+                    None => {
+                        info!("Computing symmetry group to compare facetings...");
+                        self.get_symmetry_group_in(Some(&options.geometry)).map(|(_, vertex_map)| vertex_map)
+                    }
+                };
+                match vertex_map {
+                    Some(vertex_map) => {
+                        let vertex_map = normalize_vertex_map(vertex_map.into_iter().map(VertexPermutation::from).collect());
+                        full_map = Some(match subset {
+                            Some(subset) => restrict_vertex_map(&vertex_map, subset),
+                            None => vertex_map,
+                        });
+                    }
+                    None => {
+                        warn!("the symmetry group couldn't be computed, so congruent facetings aren't removed");
+                        stats.warning_count += 1;
+                    }
                 }
-                ridge_muls.push(ridge_muls_hp);
+            } else {
+                warn!("the vertices faceted aren't those of the polytope, so congruent facetings aren't removed");
+                stats.warning_count += 1;
             }
+        }
+        let mut output = Vec::new();
 
-            let mut output_facets = Vec::new();
+        let mut possible_lengths_set = BTreeSet::<OrderedFloat<f64>>::new();
+        let mut possible_lengths = Vec::new();
 
-            let mut facets_queue = VecDeque::<(
-                Vec<(usize, usize)>, // list of facets
-                usize, // min hyperplane
-                Vec<usize> // cached ridge muls
-            )>::new();
+        if any_single_edge_length {
+            info!("Computing edge lengths...");
 
-            for (hp, list) in possible_facets.iter().enumerate() {
-                for f in 0..list.len() {
-                    facets_queue.push_back((
-                        vec![(hp, f)],
-                        hp,
-                        vec![0; ridge_counts.len()]
-                    ));
+            for orbit in vertex_orbits {
+                let rep = orbit.representative();
+                for i in rep+1..vertices.len() {
+                    possible_lengths_set.insert(OrderedFloat((vertices[rep].clone() - vertices[i].clone()).norm()));
                 }
             }
+            let mut possible_lengths_ordf: Vec<&OrderedFloat<f64>> = possible_lengths_set.iter().collect();
+            possible_lengths_ordf.sort_unstable();
 
-            while let Some((facets, min_hp, cached_ridge_muls)) = facets_queue.pop_back() {
-
-                if now.elapsed().as_millis() > DELAY {
-                    print!("{}", CL);
-                    print!("{:.115}", format!("{} facetings, {:?}", output_facets.len(), facets));
-                    std::io::stdout().flush().unwrap();
-                    now = Instant::now();
+            if possible_lengths_ordf.len() > 0 {
+                possible_lengths.push(possible_lengths_ordf[0].0);
+            }
+            for idx in 0..possible_lengths_ordf.len()-1 {
+                let len1 = possible_lengths_ordf[idx].0;
+                let len2 = possible_lengths_ordf[idx+1].0;
+                if len2-len1 > options.geometry.tolerance {
+                    possible_lengths.push(len2);
                 }
+            }
 
-                let mut new_ridge_muls = cached_ridge_muls.clone();
+            info!("Found {} edge lengths: {:?}", possible_lengths.len(), possible_lengths);
+        }
+        let mut edge_length_idx = 0;
 
-                let last_facet = facets.last().unwrap();
+        // Moves on to the next edge length, if there's one left to check.
+        let next_edge_length = |edge_length_idx: &mut usize, cancelled: bool| {
+            *edge_length_idx += 1;
+            any_single_edge_length && !cancelled && *edge_length_idx < possible_lengths.len()
+        };
+        let mut hyperplane_output = Vec::new();
+        let mut tables_output = Vec::new();
+        let mut count_output = Vec::new();
 
-                'a: loop {
-                    let hp = last_facet.0;
-                    let f = last_facet.1;
+        loop {
+            let mut edge_lengths = options.edge_length.clone();
+            if any_single_edge_length {
+                let edge_length = possible_lengths[edge_length_idx];
+                edge_lengths = EdgeLengthFilter::exact(edge_length);
+                info!("Checking edge length {} ({}/{})", edge_length, edge_length_idx+1, possible_lengths.len());
+            }
 
-                    let ridge_idxs_local = &possible_facets[hp][f].1;
-                    for ridge_idx in ridge_idxs_local {
-                        let ridge_orbit = ridge_idx_orbits[hp][ridge_idx.0][ridge_idx.1];
-                        let mul = ridge_muls[hp][f][ridge_orbit];
-        
-                        new_ridge_muls[ridge_orbit] += mul;
-                        if new_ridge_muls[ridge_orbit] > 2 {
-                            break 'a;
-                        }
-                    }
-                    break;
-                }
-                let mut valid = 0; // 0: valid, 1: exotic, 2: incomplete
-                for r in &new_ridge_muls {
-                    if *r > 2 {
-                        valid = 1;
-                        break
-                    }
-                    if *r == 1 {
-                        valid = 2;
-                    }
+            let (hyperplane_orbits, retry) = setup.consistent_hyperplane_orbits(&edge_lengths, options, &mut stats)?;
+            let options = retry.as_ref().unwrap_or(options);
+            let (merged, hyperplane_orbits) = setup.check_split_orbits(hyperplane_orbits, options, &mut stats);
+            let setup = merged.as_ref().unwrap_or(&setup);
+            if stop_after == Stage::Hyperplanes {
+                hyperplane_output.push(setup.describe_hyperplane_orbits(hyperplane_orbits));
+                if next_edge_length(&mut edge_length_idx, stats.cancelled || stats.truncated) {
+                    continue;
                 }
-                match valid {
-                    0 => {
-                        // Split compound facets into their components.
-                        let mut new_facets = Vec::new();
-        
-                        for (hp, idx) in &facets {
-                            let mut all_components = Vec::<usize>::new();
-                            let mut queue = VecDeque::new();
-                            queue.push_back(*idx);
-                            while let Some(next) = queue.pop_front() {
-                                if let Some(components) = compound_facets[*hp].get(&next) {
-                                    queue.push_back(components.0);
-                                    queue.push_back(components.1);
-                                } else {
-                                    all_components.push(next);
-                                }
-                            }
-                            for component in all_components {
-                                new_facets.push((*hp, component));
-                            }
-                        }
-                        new_facets.sort_unstable();
-        
-                        output_facets.push(new_facets);
+                break;
+            }
 
-                        if let Some(max_facets) = noble {
-                            if facets.len() == max_facets {
-                                continue;
-                            }
-                        }
-                        if include_compounds {
-                            let mut used_hps = HashSet::new();
-                            for facet in facets.iter().skip(1) {
-                                used_hps.insert(facet.0);
-                            }
-                            for (hp, list) in possible_facets.iter().enumerate().skip(min_hp+1) {
-                                if !used_hps.contains(&hp) {
-                                    for f in 0..list.len() {
-                                        let mut new_facets = facets.clone();
-                                        new_facets.push((hp, f));
-                                        facets_queue.push_back((new_facets, hp, new_ridge_muls.clone()));
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    1 => {}
-                    2 => {
-                        if let Some(max_facets) = noble {
-                            if facets.len() == max_facets {
-                                continue;
-                            }
-                        }
-                        let mut used_hps = HashSet::new();
-                        for facet in facets.iter().skip(1) {
-                            used_hps.insert(facet.0);
-                        }
-                        for (idx, mul) in new_ridge_muls.iter().enumerate() {
-                            if *mul == 1 {
-                                for facet in ones[idx]
-                                    .iter()
-                                    .skip(binary(&ones[idx], min_hp))
-                                {
-                                    if !used_hps.contains(&facet.0) {
-                                        let mut new_facets = facets.clone();
-                                        new_facets.push(*facet);
-                                        facets_queue.push_back((new_facets, min_hp, new_ridge_muls.clone()));
-                                    }
-                                }
-                                break;
-                            }
-                        }
-                    }
-                    _ => {}
+            let tables = setup.tables(&hyperplane_orbits, &edge_lengths, options, &self.meta, &mut stats);
+            if stop_after == Stage::FacetTypes {
+                tables_output.push(tables);
+                if next_edge_length(&mut edge_length_idx, stats.cancelled || stats.truncated) {
+                    continue;
                 }
+                break;
             }
 
-            println!("{}{} facetings", CL, output_facets.len());
+            // Actually do the faceting
+            info!("Combining...");
+            let mut stage_start = Instant::now();
+
+            // The facetings found for the previous edge lengths count towards the maximum.
+            let output_facets = match options.max_results {
+                Some(max) => {
+                    let options = FacetingOptions {
+                        max_results: Some(max.saturating_sub(stats.faceting_count)),
+                        ..options.clone()
+                    };
+                    tables.combine(&options, &mut stats)
+                }
+                None => tables.combine(options, &mut stats),
+            };
+
+            let output_facets = match &full_map {
+                Some(full_map) => {
+                    let found = output_facets.len();
+                    let (kept, removed) = tables.dedup_congruent(output_facets, full_map);
+                    info!("Found {} facetings, {} up to congruence", found, kept.len());
+                    stats.congruent_count += removed;
+                    kept
+                }
+                None => output_facets,
+            };
 
-            output_facets.sort_unstable();
+            stats.faceting_count += output_facets.len();
+            stats.combine_time += stage_start.elapsed();
+            stage_start = Instant::now();
 
-            if !include_compounds {
-                println!("\nFiltering mixed compounds...");
-                let output_idxs = filter_irc(&output_facets);
-                let mut output_new = Vec::new();
-                for idx in output_idxs {
-                    output_new.push(output_facets[idx].clone());
+            if stop_after == Stage::Count {
+                info!("Found {} facetings", output_facets.len());
+                count_output.push(FacetingCount::new(output_facets));
+                if next_edge_length(&mut edge_length_idx, stats.cancelled || stats.truncated) {
+                    continue;
                 }
-                output_facets = output_new;
+                break;
             }
 
             // Output the faceted polytopes. We will build them from their sets of facet orbits.
 
-            println!("Found {} facetings", output_facets.len());
-            println!("\nBuilding...");
+            info!("Found {} facetings", output_facets.len());
+            info!("Building...");
             let mut used_facets = HashMap::new(); // used for outputting the facets at the end if `save_facets` is `true`.
             let mut faceting_idx = 0; // We used to use `output.len()` but this doesn't work if you skip outputting the polytopes.
+            let mut throttle = Throttle::new(options.progress.as_ref());
+            let total = output_facets.len();
+
+            for (done, facets) in output_facets.into_iter().enumerate() {
+                throttle.report(|| FacetingProgress::Building { done, total });
+                let mut facets_fmt = String::new();
+                for facet in &facets {
+                    facets_fmt.push_str(&format!(" ({},{})", facet.0, facet.1));
+                }
 
-            for facets in output_facets {
-                if !save && !save_facets {
-                    let mut facets_fmt = String::new();
-                    for facet in &facets {
-                        facets_fmt.push_str(&format!(" ({},{})", facet.0, facet.1));
-                    }
-                    println!("Faceting {}:{}", faceting_idx, facets_fmt);
+                // If we're only outputting the facets, we don't need to build the facetings whose facets we have,
+                // unless we need to know whether to exclude them.
+                if !save && !options.exclude_compounds && !options.exclude_fissaries && (!save_facets || facets.iter().all(|facet| used_facets.contains_key(facet))) {
+                    debug!("Faceting {}:{}", faceting_idx, facets_fmt);
 
                     faceting_idx += 1;
                     continue
                 }
 
-                let mut facet_set = HashSet::new();
-                let mut used_facets_current = Vec::new();
-                let mut facet_vec = Vec::new();
-
-                if !save {
-                    let mut already_found_all = true;
-                    for facet in &facets {
-                        if used_facets.get(facet).is_none() {
-                            already_found_all = false;
-                            break
-                        }
-                    }
-
-                    if already_found_all { 
-                        let mut facets_fmt = String::new();
-                        for facet in &facets {
-                            facets_fmt.push_str(&format!(" ({},{})", facet.0, facet.1));
-                        }
-                        println!("Faceting {}:{}", faceting_idx, facets_fmt);
-
-                        faceting_idx += 1;
-                        continue
-                    }
+                let (mut poly, facet_orbits) = match tables.build(&facets) {
+                    Some(built) => built,
+                    None => continue,
+                };
+                if options.excludes(&poly, &mut stats) {
+                    stats.faceting_count -= 1;
+                    continue;
                 }
 
-                for facet_orbit in facets.clone() {
-                    if save_facets {
-                        if used_facets.get(&facet_orbit).is_none() {
-                            used_facets_current.push((facet_orbit, facet_set.len()));
-                        }
-                    }
-                    let facet = &possible_facets_global[facet_orbit.0][facet_orbit.1].0;
-                    let facet_local = &possible_facets[facet_orbit.0][facet_orbit.1].0;
-
-                    let mut of_this_orbit = HashSet::new();
-                    for row in &vertex_map {
-                        let mut new_facet = facet.clone();
-        
-                        let mut new_list = ElementList::new();
-                        for i in 0..new_facet[2].len() {
-                            let mut new = Element::new(Subelements::new(), Superelements::new());
-                            for sub in &new_facet[2][i].subs {
-                                new.subs.push(row[*sub])
-                            }
-                            new_list.push(new);
-                        }
-                        let mut edges = new_list.clone();
-                        for edge in &mut edges {
-                            edge.subs.sort();
-                        }
-                        edges.0.sort_by(|a, b| a.subs.cmp(&b.subs));
-                        if let Some(_) = of_this_orbit.get(&edges) {
-                            continue;
-                        }
-                        of_this_orbit.insert(edges);
-                        new_facet[2] = new_list;
-
-                        new_facet.element_sort_strong_with_local(facet_local);
-                        facet_set.insert(new_facet.clone());
-                        facet_vec.push(new_facet); // have to do this so you can predict the facet index
-                                                // also it makes the facets sorted by type so that's cool
+                let mut fissary_status = "";
+                if mark_fissary {
+                    if poly.is_compound() {
+                        fissary_status = " [C]";
+                    } else if poly.is_fissary() {
+                        fissary_status = " [F]";
                     }
                 }
 
-                let mut ranks = Ranks::new();
-                ranks.push(vec![Element::new(vec![].into(), vec![].into())].into()); // nullitope
+                poly.meta = faceting_meta(&self.meta, Some(faceting_idx), &facets);
+                let uniform = label_uniform(&mut poly).map_or(String::new(), |id| format!(" = {}", id));
+                let not_noble = match label_noble(&mut poly, &facets, options) {
+                    Some(certificate) if !certificate.is_noble() => format!(" ({})", certificate),
+                    _ => String::new(),
+                };
 
-                // vertices
-                let mut to_new_idx = HashMap::new();
-                let mut to_old_idx = Vec::new();
-                let mut idx = 0;
+                if save {
+                    let name = format!("faceting {}{}{}{}",
+                        if any_single_edge_length {edge_length_idx.to_string() + "."} else {"".to_string()},
+                        faceting_idx,
+                        if label_facets {" -".to_owned() + &facets_fmt.to_string()} else {"".to_string()},
+                        fissary_status
+                    );
 
-                for i in 0..facet_vec.len() {
-                    let mut new_list = ElementList::new();
-                    for j in 0..facet_vec[i][2].len() {
-                        let mut new = Element::new(Subelements::new(), Superelements::new());
-                        for sub in facet_vec[i][2][j].subs.clone() {
-                            if to_new_idx.get(&sub).is_none() {
-                                to_new_idx.insert(sub, idx);
-                                to_old_idx.push(sub);
-                                idx += 1;
-                            }
-                            new.subs.push(*to_new_idx.get(&sub).unwrap())
+                    if save_to_file {
+                        let mut path = PathBuf::from(&file_path);
+                        path.push(format!("{}.off", name));
+                        match poly.to_path(&path, Default::default()) {
+                            Err(why) => panic!("couldn't write to {}: {}", path.display(), why),
+                            Ok(_) => (),
                         }
-                        new_list.push(new);
+                    } else {
+                        let mut result = FacetingResult::new(poly.clone(), facets.clone(), &facet_orbits, tables.vertex_map.len());
+                        result.name = Some(name);
+                        output.push(result);
                     }
-                    facet_vec[i][2] = new_list;
                 }
-                let mut new_rank = ElementList::new();
-                for _i in 0..idx {
-                    new_rank.push(Element::new(vec![0].into(), vec![].into()));
-                }
-                ranks.push(new_rank);
 
-                for r in 2..rank-1 { // edges and up
-                    let mut subs_to_idx = HashMap::new();
-                    let mut idx_to_subs = Vec::new();
-                    let mut idx = 0;
-        
-                    for facet in &facet_vec {
-                        let els = &facet[r];
-                        for el in els {
-                            if subs_to_idx.get(&el.subs).is_none() {
-                                subs_to_idx.insert(el.subs.clone(), idx);
-                                idx_to_subs.push(el.subs.clone());
-                                idx += 1;
-                            }
-                        }
-                    }
-                    for i in 0..facet_vec.len() {
-                        let mut new_list = ElementList::new();
-                        for j in 0..facet_vec[i][r+1].len() {
-                            let mut new = Element::new(Subelements::new(), Superelements::new());
-                            for sub in &facet_vec[i][r+1][j].subs {
-                                let sub_subs = &facet_vec[i][r][*sub].subs;
-                                new.subs.push(*subs_to_idx.get(sub_subs).unwrap())
-                            }
-                            new_list.push(new);
-                        }
-                        facet_vec[i][r+1] = new_list;
-                    }
-                    let mut new_rank = ElementList::new();
-                    for el in idx_to_subs {
-                        new_rank.push(Element::new(el, vec![].into()));
-                    }
-                    ranks.push(new_rank);
-                }
-        
-                let mut new_rank = ElementList::new();
-                let mut set = HashSet::new();
-        
-                for f_i in 0..facet_vec.len() {
-                    facet_vec[f_i][rank-1][0].subs.sort();
-                    let subs = facet_vec[f_i][rank-1][0].subs.clone();
-                    if !set.contains(&subs) {
-                        new_rank.push(Element::new(subs.clone(), Superelements::new()));
-                        set.insert(subs);
+                if save_facets {
+                    for (idx, orbit) in facet_orbits.iter().enumerate() {
+                        used_facets.entry(*orbit).or_insert_with(|| {
+                            let count = facet_orbits.iter().filter(|&other| other == orbit).count();
+                            (poly.facet(idx).unwrap(), count)
+                        });
                     }
                 }
-                let n_r_len = new_rank.len();
-                ranks.push(new_rank); // facets
-        
-                ranks.push(vec![Element::new(Subelements::from_iter(0..n_r_len), Superelements::new())].into()); // body
-        
-                unsafe {
-                    let mut builder = AbstractBuilder::new();
-                    for rank in ranks {
-                        builder.push_empty();
-                        for el in rank {
-                            builder.push_subs(el.subs);
-                        }
-                    }
-        
-                    if builder.ranks().is_dyadic().is_ok() {
-                        let mut abs = builder.build();
-                        let mut new_vertices = Vec::new();
-                        for i in to_old_idx {
-                            new_vertices.push(vertices[i].clone());
-                        }
-
-                        let poly = Concrete {
-                            vertices: new_vertices,
-                            abs: abs.clone(),
-                        };
-
-                        let mut fissary_status = "";
-                        if mark_fissary {
-                            abs.element_sort();
-                            
-                            if abs.is_compound() {
-                                fissary_status = " [C]";
-                            } else if poly.is_fissary() {
-                                fissary_status = " [F]";
-                            }
-                        }
-                        
-                        let mut facets_fmt = String::new();
-                        for facet in &facets {
-                            facets_fmt.push_str(&format!(" ({},{})", facet.0, facet.1));
-                        }
-
-                        if save {
-                            let name = format!("faceting {}{}{}{}",
-                                if any_single_edge_length {edge_length_idx.to_string() + "."} else {"".to_string()},
-                                faceting_idx,
-                                if label_facets {" -".to_owned() + &facets_fmt.to_string()} else {"".to_string()},
-                                fissary_status
-                            );
-
-                            if save_to_file {
-                                let mut path = PathBuf::from(&file_path);
-                                path.push(format!("{}.off", name));
-                                match poly.to_path(&path, Default::default()) {
-                                    Err(why) => panic!("couldn't write to {}: {}", path.display(), why),
-                                    Ok(_) => (),
-                                }
-                            } else {
-                                output.push((poly.clone(), Some(name)));
-                            }
-                        }
 
-                        if save_facets {
-                            for (orbit, idx) in used_facets_current {
-                                used_facets.insert(orbit, poly.facet(idx).unwrap());
-                            }
-                        }
-                        
-                        println!("Faceting {}:{}{}", faceting_idx, facets_fmt, fissary_status);
+                debug!("Faceting {}:{}{}{}{}", faceting_idx, facets_fmt, fissary_status, uniform, not_noble);
 
-                        faceting_idx += 1;
-                    }
-                }
+                faceting_idx += 1;
             }
 
             if save_facets {
-                let mut used_facets_vec: Vec<(&(usize, usize), &Concrete)> = used_facets.iter().collect();
+                let mut used_facets_vec: Vec<_> = used_facets.iter().collect();
                 used_facets_vec.sort_by(|a,b| a.0.cmp(b.0));
 
                 for i in used_facets_vec {
-                    let mut poly = i.1.clone();
+                    let mut poly = i.1.0.clone();
                     poly.flatten();
                     if let Some(sphere) = poly.circumsphere() {
                         poly.recenter_with(&sphere.center);
@@ -2195,20 +7916,487 @@ impl Concrete {
                             Ok(_) => (),
                         }
                     } else {  
-                        output.push((poly, Some(format!("facet ({},{})", i.0.0, i.0.1))));
+                        // Facets are output with the orbit they come from, and
+                        // how many of them the first faceting using them has.
+                        output.push(FacetingResult {
+                            polytope: poly,
+                            facets: vec![*i.0],
+                            facet_counts: vec![i.1.1],
+                            name: Some(format!("facet ({},{})", i.0.0, i.0.1)),
+                        });
                     }
                 }
             }
 
-            if any_single_edge_length {
-                edge_length_idx += 1;
-                if edge_length_idx < possible_lengths.len() {
-                    continue;
+            stats.build_time += stage_start.elapsed();
+
+            if next_edge_length(&mut edge_length_idx, stats.cancelled || stats.truncated) {
+                continue;
+            }
+            break;
+        }
+
+        // The edge lengths are checked up to the one the enumeration stopped at.
+        if any_single_edge_length && stats.stop_point.is_some() {
+            stats.stop_edge_length = Some(possible_lengths[edge_length_idx - 1]);
+        }
+
+        info!("Faceting complete");
+        let output = match stop_after {
+            Stage::Hyperplanes => PartialFacetingOutput::Hyperplanes(hyperplane_output),
+            Stage::FacetTypes => PartialFacetingOutput::FacetTypes(tables_output),
+            Stage::Count => PartialFacetingOutput::Count(count_output),
+            Stage::Full => PartialFacetingOutput::Full(output),
+        };
+        Ok((output, stats))
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The filters are built in their simplest form, and their bounds are
+    /// derived from the lengths they allow.
+    #[test]
+    fn edge_length_filter() {
+        assert_eq!(EdgeLengthFilter::new(None, None, Vec::new()), EdgeLengthFilter::Any);
+        assert_eq!(EdgeLengthFilter::between(1.0, 1.0), EdgeLengthFilter::Single(1.0));
+        assert_eq!(EdgeLengthFilter::set(vec![2.0]), EdgeLengthFilter::Single(2.0));
+        assert_eq!(
+            EdgeLengthFilter::new(Some(1.0), None, vec![0.5, 2.0, 1.0]),
+            EdgeLengthFilter::Set(vec![2.0, 1.0])
+        );
+
+        let set = EdgeLengthFilter::set(vec![2.0, 1.0]);
+        assert_eq!((set.min(), set.max()), (Some(1.0), Some(2.0)));
+        assert!(set.contains(1.0) && !set.contains(1.5));
+        let range = EdgeLengthFilter::between(1.0, 2.0);
+        assert_eq!(range.lengths(), &[] as &[f64]);
+        assert!(range.contains(1.5) && !range.contains(2.5));
+    }
+
+    /// Returns the vertices and the vertex map of the full symmetry group of a
+    /// polytope.
+    fn symmetry(mut poly: Concrete) -> (Vec<Point<f64>>, Vec<VertexPermutation>) {
+        let vertex_map = poly.get_symmetry_group().unwrap().1;
+        (poly.vertices.into_vec(), vertex_map.into_iter().map(VertexPermutation::from).collect())
+    }
+
+    #[test]
+    fn central_inversion_cube() {
+        let (vertices, vertex_map) = symmetry(Concrete::hypercube(4));
+        let inversion = central_inversion(&vertices, &vertex_map).unwrap();
+        for (v, w) in inversion.iter().enumerate() {
+            assert!((&vertices[v] + &vertices[*w]).norm() < f64::EPS);
+        }
+
+        let half = half_vertex_map(&vertex_map, &inversion);
+        assert_eq!(half.len(), vertex_map.len() / 2);
+
+        // Every row is either in the half or is the inverse of a row in it.
+        let half_set: HashSet<_> = half.iter().cloned().collect();
+        for row in &vertex_map {
+            let inverted = inversion.compose(row);
+            assert!(half_set.contains(row) != half_set.contains(&inverted));
+        }
+    }
+
+    #[test]
+    fn central_inversion_tetrahedron() {
+        let (vertices, vertex_map) = symmetry(Concrete::simplex(4));
+        assert!(central_inversion(&vertices, &vertex_map).is_none());
+    }
+
+    #[test]
+    fn orbit_structure_cube() {
+        let (vertices, vertex_map) = symmetry(Concrete::hypercube(4));
+        let edge = (&vertices[0] - &vertices[1]).norm();
+        let edge = vertices.iter().map(|v| (v - &vertices[0]).norm()).fold(edge, |a, b| {
+            if b > f64::EPS { a.min(b) } else { a }
+        });
+
+        // Any pair of the 7 other vertices, and a sixth of the copies.
+        let structure = OrbitStructure::new(&vertices, &vertex_map, None, None);
+        assert_eq!(structure.orbit_sizes, vec![8]);
+        assert_eq!(structure.estimated_tuples, 4);
+        assert!(structure.is_feasible());
+
+        // Only the 3 vertices at an edge length are left.
+        let structure = OrbitStructure::new(&vertices, &vertex_map, Some(edge), Some(edge));
+        assert_eq!(structure.estimated_tuples, 1);
+
+        assert!(structure.check(Some(1)).is_ok());
+        assert!(structure.check(Some(0)).is_err());
+    }
+
+    /// Enumerating the pairs of vertices with half of the symmetries and the
+    /// central inversion, as done for the polytope itself, finds the same
+    /// orbits as with all of them, as done for its hyperplanes.
+    #[test]
+    fn pair_orbits_inversion() {
+        let (vertices, vertex_map) = symmetry(Concrete::hypercube(4));
+        let vertex_orbits = VertexOrbit::orbits(vertices.len(), &vertex_map);
+        let inversion = central_inversion(&vertices, &vertex_map).unwrap();
+        let half = half_vertex_map(&vertex_map, &inversion);
+
+        let sorted = |orbits: Vec<Vec<Vec<usize>>>| {
+            orbits
+                .into_iter()
+                .map(|mut orbit| {
+                    orbit.sort_unstable();
+                    orbit
+                })
+                .collect::<Vec<_>>()
+        };
+        let full = sorted(pair_orbits(&vertices, &vertex_orbits, &vertex_map, None, &Default::default(), f64::EPS, None));
+        let halved = sorted(pair_orbits(&vertices, &vertex_orbits, &half, Some(&inversion), &Default::default(), f64::EPS, None));
+
+        // The edges, face diagonals and space diagonals.
+        assert_eq!(full.iter().map(Vec::len).collect::<Vec<_>>(), vec![12, 12, 4]);
+        assert_eq!(halved, full);
+    }
+
+    /// Enumerating the pairs of vertices one representative at a time finds
+    /// the same orbits, in the same order, as marking every pair found in a
+    /// matrix, including for groups with many vertex orbits.
+    #[test]
+    fn pair_orbits_match_matrix() {
+        fn matrix_pair_orbits(vertex_count: usize, vertex_orbits: &[VertexOrbit], vertex_map: &[VertexPermutation]) -> Vec<Vec<Vec<usize>>> {
+            let mut pair_orbits = Vec::new();
+            let mut checked = vec![vec![false; vertex_count]; vertex_count];
+            for orbit in vertex_orbits {
+                let rep = orbit.representative();
+                for vertex in rep + 1..vertex_count {
+                    if !checked[rep][vertex] {
+                        let mut new_orbit = Vec::new();
+                        for row in vertex_map {
+                            let (c1, c2) = (row[rep].min(row[vertex]), row[rep].max(row[vertex]));
+                            if !checked[c1][c2] {
+                                new_orbit.push(vec![c1, c2]);
+                                checked[c1][c2] = true;
+                            }
+                        }
+                        pair_orbits.push(new_orbit);
+                    }
+                }
+            }
+            pair_orbits
+        }
+
+        for poly in &[Concrete::hypercube(4), Concrete::polygon(3).prism(), Concrete::polygon(4).duoprism(&Concrete::polygon(5))] {
+            let (vertices, vertex_map) = symmetry(poly.clone());
+            let inversion = central_inversion(&vertices, &vertex_map);
+
+            // The full group, the central inversion, and the identity alone.
+            let mut groups = vec![vertex_map, vec![VertexPermutation::identity(vertices.len())]];
+            if let Some(inversion) = inversion {
+                groups.push(vec![VertexPermutation::identity(vertices.len()), inversion]);
+            }
+
+            for group in &groups {
+                let vertex_orbits = VertexOrbit::orbits(vertices.len(), group);
+                assert_eq!(
+                    pair_orbits(&vertices, &vertex_orbits, group, None, &Default::default(), f64::EPS, None),
+                    matrix_pair_orbits(vertices.len(), &vertex_orbits, group)
+                );
+            }
+        }
+    }
+
+    /// Faceting the vertices of a polyhedron as a subspace of the space
+    /// they're in gives the same facetings as faceting the polyhedron, along
+    /// with their compounds.
+    #[test]
+    fn subdim_matches_faceting() {
+        let settings = SubdimSettings {
+            edge_lengths: &EdgeLengthFilter::default(),
+            tolerance: f64::EPS,
+            max_per_hyperplane: None,
+            uniform: false,
+            cancel: None,
+            progress: None,
+        };
+
+        for poly in &[Concrete::simplex(4), Concrete::hypercube(4), Concrete::orthoplex(4)] {
+            let mut faceted = poly.clone();
+            let mut expected: Vec<_> = faceted
+                .faceting(poly.vertices.to_vec(), GroupEnum::Chiral(false), &Default::default())
+                .into_iter()
+                .map(|faceting| (0..=faceting.polytope.rank()).map(|r| faceting.polytope.el_count(r)).collect::<Vec<_>>())
+                .collect();
+            expected.sort();
+
+            let (vertices, vertex_map) = symmetry(poly.clone());
+            let space = Subspace::from_points(vertices.iter());
+            let points = vertices.into_iter().map(PointOrd::new).collect();
+            let (facetings, _, _, compounds) = faceting_subdim(4, space, points, vertex_map, settings, SubdimMode::Nested);
+
+            let mut counts: Vec<_> = facetings
+                .iter()
+                .enumerate()
+                .filter(|(idx, _)| !compounds.contains_key(idx))
+                .map(|(_, (ranks, _))| ranks.iter().map(|list| list.len()).collect::<Vec<_>>())
+                .collect();
+            counts.sort();
+            assert_eq!(counts, expected);
+        }
+    }
+
+    /// Builds faceting tables where each hyperplane orbit has a single possible
+    /// facet, covering some ridge orbits once each.
+    fn synthetic_tables(ridge_orbit_count: usize, facets: &[Vec<usize>]) -> FacetingTables {
+        let facets: Vec<_> = facets.iter().map(|ridge_orbits| vec![ridge_orbits.clone()]).collect();
+        synthetic_orbit_tables(ridge_orbit_count, &facets)
+    }
+
+    /// Builds faceting tables where each hyperplane orbit has some possible
+    /// facets, possibly none, each covering some ridge orbits once each. Each
+    /// possible facet is a triangle on vertices of its own, under a trivial
+    /// symmetry group.
+    fn synthetic_orbit_tables(ridge_orbit_count: usize, orbits: &[Vec<Vec<usize>>]) -> FacetingTables {
+        let mut vertex_count = 0;
+        let mut ones = vec![Vec::new(); ridge_orbit_count];
+        let mut possible_facets = Vec::new();
+        let mut ridge_idx_orbits = Vec::new();
+        let mut ridge_muls = Vec::new();
+
+        for (hp, facets) in orbits.iter().enumerate() {
+            let mut hp_facets = Vec::new();
+            let mut hp_muls = Vec::new();
+            for (f, ridge_orbits) in facets.iter().enumerate() {
+                let ridges = (0..ridge_orbits.len()).map(|idx| (f, idx)).collect();
+                hp_facets.push((polygon(&[vertex_count, vertex_count + 1, vertex_count + 2]), ridges));
+                vertex_count += 3;
+
+                let mut muls = vec![0; ridge_orbit_count];
+                for &orbit in ridge_orbits {
+                    muls[orbit] = 1;
+                    ones[orbit].push((hp, f));
                 }
+                hp_muls.push(muls);
             }
+            possible_facets.push(hp_facets);
+            ridge_idx_orbits.push(facets.clone());
+            ridge_muls.push(hp_muls);
+        }
+        let facets = orbits;
+
+        FacetingTables {
+            vertices: Vec::new(),
+            vertex_map: vec![VertexPermutation((0..vertex_count).collect())],
+            rank: 4,
+            possible_facets_global: possible_facets.clone(),
+            possible_facets,
+            compound_facets: vec![HashMap::new(); facets.len()],
+            ridge_idx_orbits,
+            ridge_counts: vec![1; ridge_orbit_count],
+            ridge_muls,
+            ones,
+            hyperplanes: vec![Subspace::new(Point::zeros(4)); facets.len()],
+            hyperplane_vertices: vec![VertexList::new(Vec::new()); facets.len()],
+            hyperplane_sizes: vec![1; facets.len()],
+            depths: vec![0.0; facets.len()],
+            ridges: vec![Vec::new(); facets.len()],
+            ff_counts: vec![Vec::new(); facets.len()],
+            degenerate_facets: vec![Vec::new(); facets.len()],
+            pairing: Vec::new(),
+            chiral_selection: ChiralSelection::Any,
+            polar_pairing: Vec::new(),
+            dim: 3,
+            options: FacetingOptions::default(),
+            subfacetings: Vec::new(),
+            meta: PolytopeMeta::default(),
+        }
+    }
+
+    /// With many more hyperplane orbits than ridge orbits, going through the
+    /// ridge orbits finds the same facetings while examining fewer candidates.
+    #[test]
+    fn ridge_first_arrangement() {
+        const RIDGE_ORBITS: usize = 18;
+
+        // Each facet covers three ridge orbits, picked by a linear congruential generator.
+        let mut state = 1u64;
+        let facets: Vec<Vec<usize>> = (0..110)
+            .map(|_| {
+                let mut orbits = Vec::new();
+                while orbits.len() < 3 {
+                    state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                    let orbit = (state >> 33) as usize % RIDGE_ORBITS;
+                    if !orbits.contains(&orbit) {
+                        orbits.push(orbit);
+                    }
+                }
+                orbits
+            })
+            .collect();
+        let tables = synthetic_tables(RIDGE_ORBITS, &facets);
+
+        let options = FacetingOptions {
+            noble: Some(4),
+            ..Default::default()
+        };
+        let mut facet_stats = FacetingStats::default();
+        let facet_first = tables.combine(&options, &mut facet_stats);
+        let mut ridge_stats = FacetingStats::default();
+        let ridge_first = tables.combine_ridge_first(&options, &mut ridge_stats);
+
+        assert!(!facet_first.is_empty());
+        assert_eq!(ridge_first, facet_first);
+        assert!(
+            ridge_stats.candidates_examined < facet_stats.candidates_examined,
+            "{} candidates going by ridges, {} going by facets",
+            ridge_stats.candidates_examined,
+            facet_stats.candidates_examined
+        );
+    }
+
+    /// Returns every selection of possible facets from different hyperplane
+    /// orbits that covers each ridge orbit twice or not at all, sorted.
+    fn brute_force_facetings(tables: &FacetingTables) -> Vec<Vec<(usize, usize)>> {
+        let mut selections = vec![Vec::new()];
+        for (hp, list) in tables.possible_facets.iter().enumerate() {
+            let mut extended = Vec::new();
+            for selection in &selections {
+                for f in 0..list.len() {
+                    let mut new_selection: Vec<(usize, usize)> = selection.clone();
+                    new_selection.push((hp, f));
+                    extended.push(new_selection);
+                }
+            }
+            selections.extend(extended);
+        }
+
+        let mut facetings: Vec<_> = selections
+            .into_iter()
+            .filter(|facets| !facets.is_empty() && tables.ridge_muls_of(facets).iter().all(|&mul| mul == 0 || mul == 2))
+            .collect();
+        facetings.sort();
+        facetings
+    }
+
+    /// Hyperplane orbits without any possible facets, at the start, in the
+    /// middle or at the end of the tables, don't keep any combination of the
+    /// others from being visited, in any order.
+    #[test]
+    fn empty_orbits() {
+        // Two triangles of facets around three ridge orbits, and a facet
+        // covering all of them.
+        let triangle = |a, b, c| vec![vec![a, b], vec![b, c], vec![a, c]];
+        let shapes = [
+            vec![vec![], triangle(0, 1, 2), vec![vec![0, 1, 2]], triangle(0, 1, 2)],
+            vec![triangle(0, 1, 2), vec![], vec![vec![0, 1, 2]], vec![], triangle(0, 1, 2)],
+            vec![triangle(0, 1, 2), vec![vec![0, 1, 2]], triangle(0, 1, 2), vec![]],
+            vec![vec![], vec![], vec![vec![0, 1], vec![1, 2], vec![0, 2]], vec![]],
+            vec![vec![], vec![]],
+        ];
+        let orders = [OrbitOrder::Index, OrbitOrder::FewestFacetsFirst, OrbitOrder::Adaptive { interval: 1 }];
+
+        for shape in &shapes {
+            let tables = synthetic_orbit_tables(3, shape);
+            let expected = brute_force_facetings(&tables);
+
+            for &orbit_order in &orders {
+                let options = FacetingOptions {
+                    include_compounds: true,
+                    orbit_order,
+                    ..Default::default()
+                };
+                let mut facetings = tables.combine(&options, &mut FacetingStats::default());
+                facetings.sort();
+                assert_eq!(facetings, expected, "{:?} in order {:?}", shape, orbit_order);
+            }
+
+            let options = FacetingOptions {
+                include_compounds: true,
+                ..Default::default()
+            };
+            let mut facetings = tables.combine_ridge_first(&options, &mut FacetingStats::default());
+            facetings.sort();
+            assert_eq!(facetings, expected, "{:?} going by ridges", shape);
+        }
+    }
+
+    /// Facetings made of the same facets are only found once, even if their
+    /// facets come from different facet orbits, whether compounds are
+    /// included or not.
+    #[test]
+    fn duplicate_facetings() {
+        // The only facets of the first two hyperplane orbits are the same.
+        let mut tables = synthetic_tables(1, &[vec![0], vec![0], vec![0]]);
+        tables.possible_facets_global[1] = tables.possible_facets_global[0].clone();
+
+        let options = FacetingOptions {
+            include_compounds: true,
+            ..Default::default()
+        };
+        let mut stats = FacetingStats::default();
+        let facetings = tables.combine(&options, &mut stats);
+        assert_eq!(facetings, vec![vec![(0, 0), (1, 0)], vec![(0, 0), (2, 0)]]);
+        assert_eq!(stats.duplicate_count, 1);
+        assert!(stats.to_string().contains(", 1 duplicate removed"));
+
+        let mut stats = FacetingStats::default();
+        let mut facetings = tables.combine_ridge_first(&options, &mut stats);
+        facetings.sort();
+        assert_eq!(facetings.len(), 2);
+        assert_eq!(stats.duplicate_count, 1);
+
+        let mut stats = FacetingStats::default();
+        let facetings = tables.combine(&FacetingOptions::default(), &mut stats);
+        assert_eq!(facetings.len(), 2);
+        assert_eq!(stats.duplicate_count, 1);
+    }
 
-            println!("\nFaceting complete\n");
-            return output
+    /// Returns a facet of a polyhedron whose edges join consecutive vertices
+    /// of a cycle.
+    fn polygon(cycle: &[usize]) -> Ranks {
+        let mut edges = ElementList::new();
+        for (idx, &v) in cycle.iter().enumerate() {
+            let w = cycle[(idx + 1) % cycle.len()];
+            edges.push(Element::new(vec![v, w].into(), Superelements::new()));
         }
+
+        let mut ranks = Ranks::new();
+        ranks.push(vec![Element::new(Subelements::new(), Superelements::new())].into());
+        ranks.push(ElementList::new());
+        ranks.push(edges);
+        ranks
+    }
+
+    /// A quadrilateral on four collinear vertices of a plane is degenerate,
+    /// unlike one on the corners of a square, unless the tolerance is large
+    /// enough to flatten the square too.
+    #[test]
+    fn degenerate_facets() {
+        let vertices: Vec<_> = [[0.0, 0.0, 1.0], [1.0, 0.0, 1.0], [2.0, 0.0, 1.0], [3.0, 0.0, 1.0], [0.0, 1.0, 1.0], [1.0, 1.0, 1.0]]
+            .iter()
+            .map(|v| Point::from_column_slice(v))
+            .collect();
+
+        assert!(is_degenerate(&polygon(&[0, 1, 3, 2]), &vertices, 4, f64::EPS));
+        assert!(!is_degenerate(&polygon(&[0, 1, 5, 4]), &vertices, 4, f64::EPS));
+        assert!(is_degenerate(&polygon(&[0, 1, 5, 4]), &vertices, 4, 2.0));
+    }
+
+    /// Removing a possible facet also removes the compounds of it, and the
+    /// others are renumbered.
+    #[test]
+    fn remove_facets() {
+        let facet = |idx| (Ranks::new(), vec![(0, idx)]);
+        let mut hp_facets = HyperplaneFacets {
+            possible: (0..5).map(facet).collect(),
+            global: (0..5).map(facet).collect(),
+            compound: vec![(2, (0, 1)), (4, (1, 3))].into_iter().collect(),
+            ridges: Vec::new(),
+            ff_counts: Vec::new(),
+            degenerate: Vec::new(),
+            degenerate_count: 0,
+        };
+
+        hp_facets.remove(vec![0]);
+        assert_eq!(hp_facets.possible, vec![facet(1), facet(3), facet(4)]);
+        assert_eq!(hp_facets.global, hp_facets.possible);
+        assert_eq!(hp_facets.compound, vec![(2, (0, 1))].into_iter().collect());
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,1517 @@
+//! A compact binary format for [`FacetingTables`], so that the tables of big
+//! polytopes can be computed once and loaded back quickly.
+//!
+//! A file starts with the magic bytes `MIRAFTAB`, the major and minor version
+//! of the format as little-endian `u16`s, the [`arrangement_hash`] of the
+//! vertices faceted, and the length of the payload, both as little-endian
+//! `u64`s. Then comes the payload, followed by its FNV-1a hash as a
+//! little-endian `u64`, which catches most corrupted files before they're
+//! parsed.
+//!
+//! In the payload, every count and index is written as an unsigned LEB128
+//! varint, every float as its little-endian bits, and the vertices on each
+//! hyperplane as a bitset over all the vertices. The hyperplanes, their
+//! depths, the possible facets with global vertex indices, and the
+//! subfacetings are all rebuilt from the rest when loading instead of being
+//! stored.
+//!
+//! Files with a different major version, or a newer minor version, are
+//! refused: minor versions only ever add to the format.
+//!
+//! Version 1.1 adds [`FacetingOptions::require_self_polar`] after the other
+//! options. Version 1.2 adds the [`EdgeLengthFilter::lengths`] of
+//! [`FacetingOptions::edge_length`] after that, followed by those of each
+//! filter in [`FacetingOptions::per_orbit_edge_length`], in the order the
+//! filters were written in. Before, only the [`EdgeLengthFilter::min`] and
+//! [`EdgeLengthFilter::max`] of the filters were written. Version 1.3 adds [`FacetingOptions::degenerate_facets`] and
+//! [`FacetingOptions::degeneracy_tolerance`] after those, and the degenerate
+//! facets flagged in each hyperplane orbit after the metadata. Version 1.4
+//! adds [`FacetingOptions::tolerance`] after the options of version 1.3.
+//! Version 1.5 adds [`FacetingOptions::hyperplanes`] after that, as whether
+//! they're given followed by the list of vertices of each one. Version 1.6
+//! adds [`FacetingOptions::facet_orbits`] after those. Version 1.7 adds
+//! [`FacetingOptions::vertex_subset`] after that, as its kind followed by its
+//! indices. The vertices stored are then only those of the subset. Version 1.8
+//! adds [`FacetingOptions::geometry`] after that, as whether it's given
+//! followed by its tolerance, center policy and quantum. The geometry context
+//! is now always given, and its tolerance is also the one of version 1.4.
+//! Version 1.9 adds [`FacetingOptions::memoize_subdim`] after that, since it
+//! changes the order of the possible facets. Since version 1.10,
+//! [`FacetingOptions::noble`] is always written as none, and the numbers of
+//! facet orbits from [`FacetingOptions::facet_orbit_count`] are written in
+//! place of [`FacetingOptions::facet_orbits`]. Before, the noble option was a
+//! maximum number of facet orbits, and is read back as such.
+//!
+//! Checkpoint files, written while combining facets with
+//! [`FacetingOptions::checkpoint`], have the same header and versions but
+//! start with the magic bytes `MIRAFCKP`. Their payload is that of a cache
+//! file, followed by the state of the search: the candidates left to check,
+//! the keys of the hyperplane orbits, the orbits not tried yet and how often
+//! each one gave exotic combinations, the counts of orbits tried and
+//! facetings found, the facet orbits of the facetings kept so far, and the
+//! counts of candidates examined, rejected, pruned and scored.
+
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    fs::{self, File},
+    io::{BufWriter, Read, Write},
+    time::Instant,
+};
+
+use super::{
+    globalize, hyperplane_depth, ChiralSelection, DegenerateFacets, DepthFilter, EdgeLengthFilter, FacetOrbitCount, FacetingOptions, FacetingStats,
+    FacetingTables, Found, OrbitOrder, PruningCount, Search, VertexSubset,
+};
+use crate::{
+    abs::{Element, ElementList, Ranks, Subelements, Superelements},
+    conc::{
+        meta::PolytopeMeta,
+        symmetry::{VertexList, VertexPermutation},
+    },
+    file::IoError,
+    float::Float,
+    geometry::{CenterPolicy, GeomContext, Point, Subspace},
+};
+
+use log::{debug, warn};
+use vec_like::*;
+
+/// The bytes every cache file starts with.
+const MAGIC: &[u8; 8] = b"MIRAFTAB";
+
+/// The bytes every checkpoint file starts with.
+const CHECKPOINT_MAGIC: &[u8; 8] = b"MIRAFCKP";
+
+/// The major version of the format, which changes whenever old files can't be
+/// read anymore.
+pub const MAJOR_VERSION: u16 = 1;
+
+/// The minor version of the format, which changes whenever something is added
+/// to it.
+pub const MINOR_VERSION: u16 = 10;
+
+/// The number of bytes before the payload.
+const HEADER_LEN: usize = 8 + 2 + 2 + 8 + 8;
+
+/// Any error encountered while loading the faceting tables from a cache file.
+#[derive(Debug)]
+pub enum CacheError {
+    /// Some generic I/O error occured.
+    IoError(IoError),
+
+    /// The file doesn't start with the magic bytes.
+    NotACache,
+
+    /// The file was written in a version of the format that can't be read.
+    UnsupportedVersion {
+        /// The major version of the file.
+        major: u16,
+
+        /// The minor version of the file.
+        minor: u16,
+    },
+
+    /// The file holds the tables of a different set of vertices.
+    ArrangementMismatch {
+        /// The hash of the vertices the tables were asked for.
+        expected: u64,
+
+        /// The hash of the vertices in the file.
+        found: u64,
+    },
+
+    /// The file ended before the tables did.
+    Truncated,
+
+    /// The file is damaged or inconsistent.
+    Corrupted(&'static str),
+}
+
+impl Display for CacheError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IoError(err) => write!(f, "IO error: {}", err),
+            Self::NotACache => write!(f, "not a faceting cache file"),
+            Self::UnsupportedVersion { major, minor } => write!(
+                f,
+                "the file has format version {}.{}, but only versions {}.0 to {}.{} can be read",
+                major, minor, MAJOR_VERSION, MAJOR_VERSION, MINOR_VERSION
+            ),
+            Self::ArrangementMismatch { expected, found } => write!(
+                f,
+                "the file holds the tables of other vertices (hash {:016x} instead of {:016x})",
+                found, expected
+            ),
+            Self::Truncated => write!(f, "the file ends unexpectedly"),
+            Self::Corrupted(reason) => write!(f, "the file is corrupted: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for CacheError {}
+
+/// [`IoError`] is a type of [`CacheError`].
+impl From<IoError> for CacheError {
+    fn from(err: IoError) -> Self {
+        Self::IoError(err)
+    }
+}
+
+/// The result of loading the faceting tables from a cache file.
+pub type CacheResult<T> = Result<T, CacheError>;
+
+/// The 64-bit FNV-1a hash of some bytes.
+pub(super) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Returns a hash of some vertices, in order. The coordinates are rounded to
+/// a multiple of [`f64::EPS`] first, so that vertices that are equal up to
+/// floating point error usually have the same hash.
+pub fn arrangement_hash(vertices: &[Point<f64>]) -> u64 {
+    let mut encoder = Encoder::default();
+    encoder.usize(vertices.len());
+    encoder.usize(vertices.first().map_or(0, |v| v.len()));
+    for v in vertices {
+        for &c in v.iter() {
+            // Adding zero turns negative zero into positive zero.
+            encoder.f64((c / f64::EPS).round() + 0.0);
+        }
+    }
+    fnv1a(&encoder.bytes)
+}
+
+/// The number of bytes in a bitset over some vertices.
+fn bitset_len(vertex_count: usize) -> usize {
+    (vertex_count + 7) >> 3
+}
+
+/// Writes the payload of a cache file.
+#[derive(Default)]
+struct Encoder {
+    /// The bytes written so far.
+    bytes: Vec<u8>,
+}
+
+impl Encoder {
+    /// Writes a byte.
+    fn u8(&mut self, byte: u8) {
+        self.bytes.push(byte);
+    }
+
+    /// Writes a boolean as a byte.
+    fn bool(&mut self, b: bool) {
+        self.u8(b as u8);
+    }
+
+    /// Writes a number as a varint.
+    fn u64(&mut self, mut n: u64) {
+        while n >= 0x80 {
+            self.u8((n as u8) | 0x80);
+            n >>= 7;
+        }
+        self.u8(n as u8);
+    }
+
+    /// Writes a count or an index as a varint.
+    fn usize(&mut self, n: usize) {
+        self.u64(n as u64);
+    }
+
+    /// Writes a float.
+    fn f64(&mut self, x: f64) {
+        self.bytes.extend_from_slice(&x.to_bits().to_le_bytes());
+    }
+
+    /// Writes an optional count.
+    fn opt_usize(&mut self, n: Option<usize>) {
+        self.bool(n.is_some());
+        if let Some(n) = n {
+            self.usize(n);
+        }
+    }
+
+    /// Writes an optional number.
+    fn opt_u64(&mut self, n: Option<u64>) {
+        self.bool(n.is_some());
+        if let Some(n) = n {
+            self.u64(n);
+        }
+    }
+
+    /// Writes an optional float.
+    fn opt_f64(&mut self, x: Option<f64>) {
+        self.bool(x.is_some());
+        if let Some(x) = x {
+            self.f64(x);
+        }
+    }
+
+    /// Writes a list of floats.
+    fn f64s(&mut self, xs: &[f64]) {
+        self.usize(xs.len());
+        for &x in xs {
+            self.f64(x);
+        }
+    }
+
+    /// Writes a string as its length and its UTF-8 bytes.
+    fn str(&mut self, s: &str) {
+        self.usize(s.len());
+        self.bytes.extend_from_slice(s.as_bytes());
+    }
+
+    /// Writes a list of indices.
+    fn indices<'a, I: ExactSizeIterator<Item = &'a usize>>(&mut self, indices: I) {
+        self.usize(indices.len());
+        for &idx in indices {
+            self.usize(idx);
+        }
+    }
+
+    /// Writes a list of pairs of indices.
+    fn pairs(&mut self, pairs: &[(usize, usize)]) {
+        self.usize(pairs.len());
+        for &(a, b) in pairs {
+            self.usize(a);
+            self.usize(b);
+        }
+    }
+
+    /// Writes a set of vertices as a bitset over all of them.
+    fn bitset(&mut self, list: &VertexList, vertex_count: usize) {
+        let mut bits = vec![0u8; bitset_len(vertex_count)];
+        for &v in list.iter() {
+            bits[v / 8] |= 1 << (v % 8);
+        }
+        self.bytes.extend_from_slice(&bits);
+    }
+
+    /// Writes the elements of a polytope in the format used by the faceting
+    /// algorithm.
+    fn ranks(&mut self, ranks: &Ranks) {
+        self.usize(ranks.len());
+        for rank in 0..ranks.len() {
+            let list = &ranks[rank];
+            self.usize(list.len());
+            for el in list {
+                self.indices(el.subs.iter());
+                self.indices(el.sups.iter());
+            }
+        }
+    }
+
+    /// Writes the options the tables were built with. The cancellation flag,
+    /// the candidate callback, the facet filter, and the objective can't be
+    /// written.
+    fn options(&mut self, options: &FacetingOptions) {
+        self.bool(options.any_single_edge_length);
+        self.opt_f64(options.edge_length.min());
+        self.opt_f64(options.edge_length.max());
+        self.opt_f64(options.min_inradius);
+        self.opt_f64(options.max_inradius);
+        match options.depth_filter {
+            None => self.u8(0),
+            Some(DepthFilter::Min(min)) => {
+                self.u8(1);
+                self.f64(min);
+            }
+            Some(DepthFilter::Max(max)) => {
+                self.u8(2);
+                self.f64(max);
+            }
+            Some(DepthFilter::Between(min, max)) => {
+                self.u8(3);
+                self.f64(min);
+                self.f64(max);
+            }
+            Some(DepthFilter::RelativeToInsphere(ratio)) => {
+                self.u8(4);
+                self.f64(ratio);
+            }
+        }
+        self.bool(options.exclude_hemis);
+        self.bool(options.only_below_vertex);
+        self.opt_usize(None);
+        self.opt_usize(options.max_per_hyperplane);
+        self.bool(options.uniform);
+        self.bool(options.include_compounds);
+        self.bool(options.mark_fissary);
+        self.bool(options.label_facets);
+        self.bool(options.save);
+        self.bool(options.save_facets);
+        self.bool(options.save_to_file);
+        self.str(&options.file_path);
+        self.u8(match options.chiral_selection {
+            ChiralSelection::Any => 0,
+            ChiralSelection::PairedOnly => 1,
+            ChiralSelection::UnpairedOnly => 2,
+        });
+        self.bool(options.reflection.is_some());
+        if let Some(reflection) = &options.reflection {
+            self.indices(reflection.iter());
+        }
+        self.opt_u64(options.abort_if_estimated_tuples_exceed);
+
+        let mut per_orbit: Vec<_> = options.per_orbit_edge_length.iter().collect();
+        per_orbit.sort_by_key(|(&idx, _)| idx);
+        self.usize(per_orbit.len());
+        for &(&idx, filter) in &per_orbit {
+            self.usize(idx);
+            self.opt_f64(filter.min());
+            self.opt_f64(filter.max());
+        }
+
+        match options.orbit_order {
+            OrbitOrder::Index => self.u8(0),
+            OrbitOrder::FewestFacetsFirst => self.u8(1),
+            OrbitOrder::Adaptive { interval } => {
+                self.u8(2);
+                self.usize(interval);
+            }
+        }
+        self.bool(options.allow_trivial_group);
+        self.opt_f64(options.min_group_fraction);
+        self.bool(options.collect_subfacetings);
+        self.usize(options.ridge_multiplicity);
+        self.opt_f64(options.require_self_polar);
+
+        self.f64s(options.edge_length.lengths());
+        for (_, filter) in per_orbit {
+            self.f64s(filter.lengths());
+        }
+
+        self.u8(match options.degenerate_facets {
+            DegenerateFacets::Keep => 0,
+            DegenerateFacets::Flag => 1,
+            DegenerateFacets::Exclude => 2,
+        });
+        self.f64(options.degeneracy_tolerance);
+        self.f64(options.geometry.tolerance);
+
+        self.bool(options.hyperplanes.is_some());
+        if let Some(hyperplanes) = &options.hyperplanes {
+            self.usize(hyperplanes.len());
+            for list in hyperplanes {
+                self.indices(list.iter());
+            }
+        }
+
+        match options.facet_orbit_count() {
+            None => self.u8(0),
+            Some(FacetOrbitCount::Exact(count)) => {
+                self.u8(1);
+                self.usize(count);
+            }
+            Some(FacetOrbitCount::AtMost(max)) => {
+                self.u8(2);
+                self.usize(max);
+            }
+            Some(FacetOrbitCount::Between(min, max)) => {
+                self.u8(3);
+                self.usize(min);
+                self.usize(max);
+            }
+        }
+
+        match &options.vertex_subset {
+            None => self.u8(0),
+            Some(VertexSubset::Vertices(vertices)) => {
+                self.u8(1);
+                self.indices(vertices.iter());
+            }
+            Some(VertexSubset::Orbits(orbits)) => {
+                self.u8(2);
+                self.indices(orbits.iter());
+            }
+        }
+
+        let geometry = options.geometry;
+        self.bool(true);
+        self.f64(geometry.tolerance);
+        self.u8(match geometry.center {
+            CenterPolicy::Keep => 0,
+            CenterPolicy::Gravicenter => 1,
+            CenterPolicy::Circumcenter => 2,
+        });
+        self.opt_f64(geometry.quantum);
+        self.bool(options.memoize_subdim);
+    }
+}
+
+/// Reads the payload of a cache file.
+struct Decoder<'a> {
+    /// The bytes left to read.
+    bytes: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    /// Reads some bytes.
+    fn take(&mut self, len: usize) -> CacheResult<&'a [u8]> {
+        if len > self.bytes.len() {
+            return Err(CacheError::Truncated);
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    /// Reads a byte.
+    fn u8(&mut self) -> CacheResult<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    /// Reads a boolean.
+    fn bool(&mut self) -> CacheResult<bool> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(CacheError::Corrupted("invalid boolean")),
+        }
+    }
+
+    /// Reads a varint.
+    fn u64(&mut self) -> CacheResult<u64> {
+        let mut n = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.u8()?;
+            n |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(n);
+            }
+        }
+        Err(CacheError::Corrupted("varint too long"))
+    }
+
+    /// Reads a count or an index.
+    fn usize(&mut self) -> CacheResult<usize> {
+        let n = self.u64()?;
+        if n > usize::MAX as u64 {
+            return Err(CacheError::Corrupted("number too big"));
+        }
+        Ok(n as usize)
+    }
+
+    /// Reads an index, which must be less than a bound.
+    fn index(&mut self, bound: usize) -> CacheResult<usize> {
+        let idx = self.usize()?;
+        if idx < bound {
+            Ok(idx)
+        } else {
+            Err(CacheError::Corrupted("index out of range"))
+        }
+    }
+
+    /// Reads the length of a list whose entries take up at least a byte each.
+    /// Checking it against the bytes left means that a corrupted length can't
+    /// make us allocate too much memory.
+    fn len(&mut self) -> CacheResult<usize> {
+        let len = self.usize()?;
+        if len > self.bytes.len() {
+            return Err(CacheError::Truncated);
+        }
+        Ok(len)
+    }
+
+    /// Reads a float.
+    fn f64(&mut self) -> CacheResult<f64> {
+        let mut bits = [0; 8];
+        bits.copy_from_slice(self.take(8)?);
+        Ok(f64::from_bits(u64::from_le_bytes(bits)))
+    }
+
+    /// Reads an optional count.
+    fn opt_usize(&mut self) -> CacheResult<Option<usize>> {
+        Ok(if self.bool()? { Some(self.usize()?) } else { None })
+    }
+
+    /// Reads an optional number.
+    fn opt_u64(&mut self) -> CacheResult<Option<u64>> {
+        Ok(if self.bool()? { Some(self.u64()?) } else { None })
+    }
+
+    /// Reads an optional float.
+    fn opt_f64(&mut self) -> CacheResult<Option<f64>> {
+        Ok(if self.bool()? { Some(self.f64()?) } else { None })
+    }
+
+    /// Reads a string.
+    fn str(&mut self) -> CacheResult<String> {
+        let len = self.len()?;
+        String::from_utf8(self.take(len)?.to_vec()).map_err(|_| CacheError::Corrupted("invalid string"))
+    }
+
+    /// Reads a list.
+    fn list<T, F: FnMut(&mut Self) -> CacheResult<T>>(&mut self, mut read: F) -> CacheResult<Vec<T>> {
+        let len = self.len()?;
+        let mut list = Vec::with_capacity(len);
+        for _ in 0..len {
+            list.push(read(self)?);
+        }
+        Ok(list)
+    }
+
+    /// Reads a list of counts.
+    fn counts(&mut self) -> CacheResult<Vec<usize>> {
+        self.list(Self::usize)
+    }
+
+    /// Reads a list of indices less than a bound.
+    fn indices(&mut self, bound: usize) -> CacheResult<Vec<usize>> {
+        self.list(|decoder| decoder.index(bound))
+    }
+
+    /// Reads a list of pairs of indices.
+    fn pairs(&mut self) -> CacheResult<Vec<(usize, usize)>> {
+        self.list(|decoder| Ok((decoder.usize()?, decoder.usize()?)))
+    }
+
+    /// Reads a permutation of some vertices.
+    fn permutation(&mut self, vertex_count: usize) -> CacheResult<VertexPermutation> {
+        let row = self.indices(vertex_count)?;
+        if row.len() == vertex_count {
+            Ok(row.into())
+        } else {
+            Err(CacheError::Corrupted("wrong permutation length"))
+        }
+    }
+
+    /// Reads a nonempty set of vertices written as a bitset.
+    fn bitset(&mut self, vertex_count: usize) -> CacheResult<VertexList> {
+        let bits = self.take(bitset_len(vertex_count))?;
+        let list: Vec<_> = (0..vertex_count).filter(|&v| bits[v / 8] & (1 << (v % 8)) != 0).collect();
+        if list.is_empty() || list.len() < bits.iter().map(|byte| byte.count_ones() as usize).sum() {
+            return Err(CacheError::Corrupted("invalid vertex set"));
+        }
+        Ok(VertexList::new(list))
+    }
+
+    /// Reads the elements of a polytope.
+    fn ranks(&mut self) -> CacheResult<Ranks> {
+        let ranks = self.list(|decoder| {
+            let list = decoder.list(|decoder| {
+                let subs: Subelements = decoder.counts()?.into();
+                let sups: Superelements = decoder.counts()?.into();
+                Ok(Element::new(subs, sups))
+            })?;
+            Ok(ElementList::from(list))
+        })?;
+        Ok(ranks.into())
+    }
+
+    /// Reads the options the tables were built with.
+    fn options(&mut self, vertex_count: usize, minor: u16) -> CacheResult<FacetingOptions> {
+        let any_single_edge_length = self.bool()?;
+        // The allowed lengths are only read further on.
+        let min_edge_length = self.opt_f64()?;
+        let max_edge_length = self.opt_f64()?;
+        let mut options = FacetingOptions {
+            any_single_edge_length,
+            min_inradius: self.opt_f64()?,
+            max_inradius: self.opt_f64()?,
+            ..Default::default()
+        };
+        options.depth_filter = match self.u8()? {
+            0 => None,
+            1 => Some(DepthFilter::Min(self.f64()?)),
+            2 => Some(DepthFilter::Max(self.f64()?)),
+            3 => Some(DepthFilter::Between(self.f64()?, self.f64()?)),
+            4 => Some(DepthFilter::RelativeToInsphere(self.f64()?)),
+            _ => return Err(CacheError::Corrupted("invalid depth filter")),
+        };
+        options.exclude_hemis = self.bool()?;
+        options.only_below_vertex = self.bool()?;
+        let noble = self.opt_usize()?;
+        options.max_per_hyperplane = self.opt_usize()?;
+        options.uniform = self.bool()?;
+        options.include_compounds = self.bool()?;
+        options.mark_fissary = self.bool()?;
+        options.label_facets = self.bool()?;
+        options.save = self.bool()?;
+        options.save_facets = self.bool()?;
+        options.save_to_file = self.bool()?;
+        options.file_path = self.str()?;
+        options.chiral_selection = match self.u8()? {
+            0 => ChiralSelection::Any,
+            1 => ChiralSelection::PairedOnly,
+            2 => ChiralSelection::UnpairedOnly,
+            _ => return Err(CacheError::Corrupted("invalid chiral selection")),
+        };
+        options.reflection = if self.bool()? {
+            Some(self.permutation(vertex_count)?)
+        } else {
+            None
+        };
+        options.abort_if_estimated_tuples_exceed = self.opt_u64()?;
+
+        let per_orbit = self.list(|decoder| Ok((decoder.usize()?, decoder.opt_f64()?, decoder.opt_f64()?)))?;
+
+        options.orbit_order = match self.u8()? {
+            0 => OrbitOrder::Index,
+            1 => OrbitOrder::FewestFacetsFirst,
+            2 => OrbitOrder::Adaptive {
+                interval: self.usize()?,
+            },
+            _ => return Err(CacheError::Corrupted("invalid orbit order")),
+        };
+        options.allow_trivial_group = self.bool()?;
+        options.min_group_fraction = self.opt_f64()?;
+        options.collect_subfacetings = self.bool()?;
+        options.ridge_multiplicity = self.usize()?;
+        if minor >= 1 {
+            options.require_self_polar = self.opt_f64()?;
+        }
+
+        let mut lengths = Vec::new();
+        if minor >= 2 {
+            for _ in 0..=per_orbit.len() {
+                lengths.push(self.list(Self::f64)?);
+            }
+        }
+        let mut lengths = lengths.into_iter();
+        options.edge_length = EdgeLengthFilter::new(min_edge_length, max_edge_length, lengths.next().unwrap_or_default());
+        options.per_orbit_edge_length = per_orbit
+            .into_iter()
+            .map(|(idx, min, max)| (idx, EdgeLengthFilter::new(min, max, lengths.next().unwrap_or_default())))
+            .collect();
+
+        if minor >= 3 {
+            options.degenerate_facets = match self.u8()? {
+                0 => DegenerateFacets::Keep,
+                1 => DegenerateFacets::Flag,
+                2 => DegenerateFacets::Exclude,
+                _ => return Err(CacheError::Corrupted("invalid degenerate facet handling")),
+            };
+            options.degeneracy_tolerance = self.f64()?;
+        }
+        if minor >= 4 {
+            options.geometry.tolerance = self.f64()?;
+        }
+        if minor >= 5 && self.bool()? {
+            options.hyperplanes = Some(self.list(|decoder| decoder.indices(vertex_count))?);
+        }
+        if minor >= 6 {
+            options.facet_orbits = match self.u8()? {
+                0 => None,
+                1 => Some(FacetOrbitCount::Exact(self.usize()?)),
+                2 => Some(FacetOrbitCount::AtMost(self.usize()?)),
+                3 => Some(FacetOrbitCount::Between(self.usize()?, self.usize()?)),
+                _ => return Err(CacheError::Corrupted("invalid facet orbit count")),
+            };
+        }
+        if minor >= 7 {
+            options.vertex_subset = match self.u8()? {
+                0 => None,
+                1 => Some(VertexSubset::Vertices(self.counts()?)),
+                2 => Some(VertexSubset::Orbits(self.counts()?)),
+                _ => return Err(CacheError::Corrupted("invalid vertex subset")),
+            };
+        }
+        if minor >= 8 && self.bool()? {
+            options.geometry = GeomContext {
+                tolerance: self.f64()?,
+                center: match self.u8()? {
+                    0 => CenterPolicy::Keep,
+                    1 => CenterPolicy::Gravicenter,
+                    2 => CenterPolicy::Circumcenter,
+                    _ => return Err(CacheError::Corrupted("invalid center policy")),
+                },
+                quantum: self.opt_f64()?,
+            };
+        }
+        if minor >= 9 {
+            options.memoize_subdim = self.bool()?;
+        }
+
+        // The noble option used to cap the facet orbits.
+        if let Some(noble) = noble {
+            options.facet_orbits = Some(match options.facet_orbits {
+                None => FacetOrbitCount::AtMost(noble),
+                Some(count) => {
+                    let (min, max) = count.bounds();
+                    FacetOrbitCount::Between(min, max.min(noble))
+                }
+            });
+        }
+        Ok(options)
+    }
+}
+
+/// Writes a file in the format described in the [module docs](self), with
+/// some magic bytes, hash of the vertices and payload.
+fn write_file<W: Write>(mut writer: W, magic: &[u8; 8], hash: u64, payload: &[u8]) -> Result<(), IoError> {
+    writer.write_all(magic)?;
+    writer.write_all(&MAJOR_VERSION.to_le_bytes())?;
+    writer.write_all(&MINOR_VERSION.to_le_bytes())?;
+    writer.write_all(&hash.to_le_bytes())?;
+    writer.write_all(&(payload.len() as u64).to_le_bytes())?;
+    writer.write_all(payload)?;
+    writer.write_all(&fnv1a(payload).to_le_bytes())?;
+    Ok(())
+}
+
+/// Checks the header and the checksum of a file written by [`write_file`]
+/// with some magic bytes, and the hash of its vertices if one is expected.
+/// Returns the minor version of the file, the hash of its vertices, and its
+/// payload.
+fn read_file<'a>(bytes: &'a [u8], magic: &[u8; 8], expected: Option<u64>) -> CacheResult<(u16, u64, &'a [u8])> {
+    if bytes.len() < magic.len() || &bytes[..magic.len()] != magic {
+        return Err(CacheError::NotACache);
+    }
+    if bytes.len() < HEADER_LEN {
+        return Err(CacheError::Truncated);
+    }
+
+    let u16_at = |idx: usize| u16::from_le_bytes([bytes[idx], bytes[idx + 1]]);
+    let u64_at = |idx: usize| {
+        let mut le = [0; 8];
+        le.copy_from_slice(&bytes[idx..idx + 8]);
+        u64::from_le_bytes(le)
+    };
+
+    let (major, minor) = (u16_at(8), u16_at(10));
+    if major != MAJOR_VERSION || minor > MINOR_VERSION {
+        return Err(CacheError::UnsupportedVersion { major, minor });
+    }
+
+    let found = u64_at(12);
+    if let Some(expected) = expected {
+        if found != expected {
+            return Err(CacheError::ArrangementMismatch { expected, found });
+        }
+    }
+
+    let payload_len = u64_at(20);
+    let rest = (bytes.len() - HEADER_LEN) as u64;
+    if rest < payload_len.saturating_add(8) {
+        return Err(CacheError::Truncated);
+    } else if rest > payload_len + 8 {
+        return Err(CacheError::Corrupted("trailing bytes"));
+    }
+
+    let end = HEADER_LEN + payload_len as usize;
+    let payload = &bytes[HEADER_LEN..end];
+    if fnv1a(payload) != u64_at(end) {
+        return Err(CacheError::Corrupted("checksum mismatch"));
+    }
+    Ok((minor, found, payload))
+}
+
+impl FacetingTables {
+    /// Writes the tables in the binary format described in the
+    /// [module docs](self). The options that can't be written, namely the
+    /// cancellation flag, the candidate callback, the facet filter and the
+    /// objective, are left out.
+    pub fn save_binary<W: Write>(&self, writer: W) -> Result<(), IoError> {
+        let mut encoder = Encoder::default();
+        self.encode(&mut encoder);
+        write_file(writer, MAGIC, arrangement_hash(&self.vertices), &encoder.bytes)
+    }
+
+    /// Writes the payload of a cache file.
+    fn encode(&self, encoder: &mut Encoder) {
+        let vertex_count = self.vertices.len();
+
+        encoder.usize(vertex_count);
+        encoder.usize(self.dim);
+        for v in &self.vertices {
+            for &c in v.iter() {
+                encoder.f64(c);
+            }
+        }
+        encoder.usize(self.vertex_map.len());
+        for row in &self.vertex_map {
+            encoder.indices(row.iter());
+        }
+        encoder.usize(self.rank);
+
+        encoder.usize(self.hyperplane_vertices.len());
+        for hp in 0..self.hyperplane_vertices.len() {
+            encoder.bitset(&self.hyperplane_vertices[hp], vertex_count);
+            encoder.usize(self.hyperplane_sizes[hp]);
+
+            encoder.usize(self.possible_facets[hp].len());
+            for (ranks, ridges) in &self.possible_facets[hp] {
+                encoder.ranks(ranks);
+                encoder.pairs(ridges);
+            }
+
+            let mut compound: Vec<_> = self.compound_facets[hp].iter().collect();
+            compound.sort_by_key(|(&f, _)| f);
+            encoder.usize(compound.len());
+            for (&f, &(a, b)) in compound {
+                encoder.usize(f);
+                encoder.usize(a);
+                encoder.usize(b);
+            }
+
+            encoder.usize(self.ridges[hp].len());
+            for ridge_orbit in &self.ridges[hp] {
+                encoder.usize(ridge_orbit.len());
+                for ridge in ridge_orbit {
+                    encoder.ranks(ridge);
+                }
+            }
+            encoder.indices(self.ff_counts[hp].iter());
+
+            encoder.usize(self.ridge_idx_orbits[hp].len());
+            for list in &self.ridge_idx_orbits[hp] {
+                encoder.indices(list.iter());
+            }
+
+            // Most facets only cover a few ridge orbits, so only those are
+            // written.
+            for muls in &self.ridge_muls[hp] {
+                let nonzero: Vec<_> = muls.iter().enumerate().filter(|(_, &mul)| mul != 0).collect();
+                encoder.usize(nonzero.len());
+                for (ridge_orbit, &mul) in nonzero {
+                    encoder.usize(ridge_orbit);
+                    encoder.usize(mul);
+                }
+            }
+        }
+
+        encoder.indices(self.ridge_counts.iter());
+        encoder.usize(self.ones.len());
+        for list in &self.ones {
+            encoder.pairs(list);
+        }
+
+        encoder.usize(self.pairing.len());
+        for pair in &self.pairing {
+            encoder.opt_usize(*pair);
+        }
+        encoder.options(&self.options);
+        encoder.str(&ron::to_string(&self.meta).unwrap_or_default());
+        for list in &self.degenerate_facets {
+            encoder.indices(list.iter());
+        }
+    }
+
+    /// Reads tables written by [`Self::save_binary`], checking that they were
+    /// built for some given vertices, in the same order.
+    ///
+    /// The cancellation flag, the candidate callback, the facet filter and the
+    /// objective of the options are left unset. If the tables were built with
+    /// [`FacetingOptions::collect_subfacetings`], the subfacetings are built
+    /// again.
+    pub fn load_binary<R: Read>(mut reader: R, vertices: &[Point<f64>]) -> CacheResult<Self> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+
+        let (minor, hash, payload) = read_file(&bytes, MAGIC, Some(arrangement_hash(vertices)))?;
+        let mut decoder = Decoder { bytes: payload };
+        let tables = Self::decode(&mut decoder, minor)?;
+        if !decoder.bytes.is_empty() {
+            return Err(CacheError::Corrupted("trailing bytes"));
+        }
+        if arrangement_hash(&tables.vertices) != hash {
+            return Err(CacheError::Corrupted("the vertices don't match the header"));
+        }
+        Ok(tables)
+    }
+
+    /// Reads the tables from the start of the payload of a cache file with
+    /// some minor version.
+    fn decode(decoder: &mut Decoder<'_>, minor: u16) -> CacheResult<Self> {
+        let vertex_count = decoder.usize()?;
+        let dim = decoder.usize()?;
+        if vertex_count.saturating_mul(dim).saturating_mul(8) > decoder.bytes.len() {
+            return Err(CacheError::Truncated);
+        }
+        let mut vertices = Vec::with_capacity(vertex_count);
+        for _ in 0..vertex_count {
+            let coordinates = (0..dim).map(|_| decoder.f64()).collect::<CacheResult<Vec<_>>>()?;
+            vertices.push(Point::from_vec(coordinates));
+        }
+        let vertex_map = decoder.list(|decoder| decoder.permutation(vertex_count))?;
+        let rank = decoder.usize()?;
+
+        let hyperplane_count = decoder.len()?;
+        let mut hyperplane_vertices = Vec::with_capacity(hyperplane_count);
+        let mut hyperplane_sizes = Vec::with_capacity(hyperplane_count);
+        let mut possible_facets = Vec::with_capacity(hyperplane_count);
+        let mut compound_facets = Vec::with_capacity(hyperplane_count);
+        let mut ridges = Vec::with_capacity(hyperplane_count);
+        let mut ff_counts = Vec::with_capacity(hyperplane_count);
+        let mut ridge_idx_orbits = Vec::with_capacity(hyperplane_count);
+        let mut sparse_muls = Vec::with_capacity(hyperplane_count);
+
+        for _ in 0..hyperplane_count {
+            hyperplane_vertices.push(decoder.bitset(vertex_count)?);
+            hyperplane_sizes.push(decoder.usize()?);
+
+            let facets = decoder.list(|decoder| Ok((decoder.ranks()?, decoder.pairs()?)))?;
+            let facet_count = facets.len();
+            possible_facets.push(facets);
+
+            let compound = decoder.list(|decoder| {
+                let f = decoder.index(facet_count)?;
+                Ok((f, (decoder.usize()?, decoder.usize()?)))
+            })?;
+            compound_facets.push(compound.into_iter().collect::<HashMap<_, _>>());
+
+            ridges.push(decoder.list(|decoder| decoder.list(Decoder::ranks))?);
+            ff_counts.push(decoder.counts()?);
+            ridge_idx_orbits.push(decoder.list(Decoder::counts)?);
+
+            let mut muls = Vec::with_capacity(facet_count);
+            for _ in 0..facet_count {
+                muls.push(decoder.list(|decoder| Ok((decoder.usize()?, decoder.usize()?)))?);
+            }
+            sparse_muls.push(muls);
+        }
+
+        let ridge_counts = decoder.counts()?;
+        let ridge_orbit_count = ridge_counts.len();
+        let mut ridge_muls = Vec::with_capacity(hyperplane_count);
+        for muls in sparse_muls {
+            let mut dense = Vec::with_capacity(muls.len());
+            for facet_muls in muls {
+                let mut row = vec![0; ridge_orbit_count];
+                for (ridge_orbit, mul) in facet_muls {
+                    *row.get_mut(ridge_orbit)
+                        .ok_or(CacheError::Corrupted("index out of range"))? = mul;
+                }
+                dense.push(row);
+            }
+            ridge_muls.push(dense);
+        }
+
+        let ones = decoder.list(Decoder::pairs)?;
+        let pairing = decoder.list(|decoder| {
+            Ok(match decoder.opt_usize()? {
+                Some(idx) if idx >= hyperplane_count => return Err(CacheError::Corrupted("index out of range")),
+                pair => pair,
+            })
+        })?;
+        let options = decoder.options(vertex_count, minor)?;
+        let meta: PolytopeMeta =
+            ron::from_str(&decoder.str()?).map_err(|_| CacheError::Corrupted("invalid metadata"))?;
+        let mut degenerate_facets = vec![Vec::new(); hyperplane_count];
+        if minor >= 3 {
+            for (hp, list) in degenerate_facets.iter_mut().enumerate() {
+                *list = decoder.indices(possible_facets[hp].len())?;
+                list.sort_unstable();
+            }
+        }
+
+        if ones.len() != ridge_orbit_count || !(pairing.is_empty() || pairing.len() == hyperplane_count) {
+            return Err(CacheError::Corrupted("inconsistent table sizes"));
+        }
+
+        // The possible facets with global vertex indices only differ from the
+        // local ones in their edges.
+        let mut possible_facets_global = possible_facets.clone();
+        for (hp, facets) in possible_facets_global.iter_mut().enumerate() {
+            let hp_v = hyperplane_vertices[hp].as_slice();
+            for (ranks, _) in facets {
+                let in_range = ranks.len() > 2 && (&ranks[2]).into_iter().all(|edge| edge.subs.iter().all(|&v| v < hp_v.len()));
+                if !in_range {
+                    return Err(CacheError::Corrupted("index out of range"));
+                }
+                globalize(ranks, hp_v);
+            }
+        }
+
+        let hyperplanes: Vec<_> = hyperplane_vertices
+            .iter()
+            .map(|list| Subspace::from_points_within(list.iter().map(|&v| &vertices[v]), options.geometry.tolerance))
+            .collect();
+        let depths = hyperplanes.iter().map(|hyperplane| hyperplane_depth(hyperplane, dim)).collect();
+
+        let mut tables = Self {
+            vertices,
+            vertex_map,
+            rank,
+            possible_facets,
+            possible_facets_global,
+            compound_facets,
+            ridge_idx_orbits,
+            ridge_counts,
+            ridge_muls,
+            ones,
+            hyperplanes,
+            hyperplane_vertices,
+            hyperplane_sizes,
+            depths,
+            ridges,
+            ff_counts,
+            degenerate_facets,
+            chiral_selection: options.chiral_selection,
+            pairing,
+            polar_pairing: Vec::new(),
+            dim,
+            options,
+            subfacetings: Vec::new(),
+            meta,
+        };
+
+        if let Some(squared_radius) = tables.options.require_self_polar {
+            tables.polar_pairing = tables.polar_pairing(squared_radius);
+        }
+        if tables.options.collect_subfacetings {
+            tables.subfacetings = tables.collect_subfacetings(&mut FacetingStats::default());
+        }
+        Ok(tables)
+    }
+}
+
+/// The state of a search for facetings read from a checkpoint file, see
+/// [`FacetingOptions::checkpoint`](super::FacetingOptions::checkpoint).
+pub(super) struct Checkpoint {
+    /// The tables the facets are combined from.
+    pub(super) tables: FacetingTables,
+
+    /// The search, without the facets it's allowed to use, which depend on
+    /// the options it's resumed with.
+    pub(super) search: Search,
+
+    /// The facet orbits of the facetings found before the checkpoint.
+    pub(super) facetings: Vec<Vec<(usize, usize)>>,
+
+    /// The counts of candidates kept in the stats of the search.
+    pub(super) stats: FacetingStats,
+}
+
+impl Checkpoint {
+    /// Reads a checkpoint file.
+    pub(super) fn load<R: Read>(mut reader: R) -> CacheResult<Self> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+
+        let (minor, hash, payload) = read_file(&bytes, CHECKPOINT_MAGIC, None)?;
+        let mut decoder = Decoder { bytes: payload };
+        let tables = FacetingTables::decode(&mut decoder, minor)?;
+        if arrangement_hash(&tables.vertices) != hash {
+            return Err(CacheError::Corrupted("the vertices don't match the header"));
+        }
+
+        let hp_count = tables.possible_facets.len();
+        let ridge_count = tables.ridge_counts.len();
+        let facet_pairs = |decoder: &mut Decoder<'_>| {
+            let facets = decoder.pairs()?;
+            if facets
+                .iter()
+                .any(|&(hp, f)| hp >= hp_count || f >= tables.possible_facets[hp].len())
+            {
+                return Err(CacheError::Corrupted("invalid facet"));
+            }
+            Ok(facets)
+        };
+
+        let stack = decoder.list(|decoder| {
+            let facets = facet_pairs(decoder)?;
+            let min_key = decoder.usize()?;
+            let ridge_muls = decoder.counts()?;
+            if facets.is_empty() || ridge_muls.len() != ridge_count {
+                return Err(CacheError::Corrupted("invalid candidate"));
+            }
+            Ok((facets, min_key, ridge_muls))
+        })?;
+        let hp_key = decoder.counts()?;
+        let untried = decoder.indices(hp_count)?;
+        let pruning = decoder.list(|decoder| {
+            Ok(PruningCount {
+                added: decoder.u64()?,
+                exotic: decoder.u64()?,
+            })
+        })?;
+        if hp_key.len() != hp_count || pruning.len() != hp_count {
+            return Err(CacheError::Corrupted("invalid search state"));
+        }
+        let tried = decoder.usize()?;
+        let found = decoder.usize()?;
+
+        let facetings = decoder.list(facet_pairs)?;
+        let stats = FacetingStats {
+            candidates_examined: decoder.u64()?,
+            candidates_rejected: decoder.u64()?,
+            bound_pruned: decoder.u64()?,
+            scored_count: decoder.usize()?,
+            ..Default::default()
+        };
+        if !decoder.bytes.is_empty() {
+            return Err(CacheError::Corrupted("trailing bytes"));
+        }
+
+        let search = Search {
+            stack,
+            hp_key,
+            allowed: Vec::new(),
+            untried,
+            pruning,
+            tried,
+            found,
+            now: Instant::now(),
+            checkpoints: false,
+            checkpointed: (Instant::now(), stats.candidates_examined),
+        };
+        Ok(Self {
+            tables,
+            search,
+            facetings,
+            stats,
+        })
+    }
+}
+
+impl Search {
+    /// Writes the state of the search, along with the tables and the
+    /// facetings found so far, to the file of
+    /// [`FacetingOptions::checkpoint`](super::FacetingOptions::checkpoint).
+    /// The file is written next to it first and then moved over it, so that
+    /// the last checkpoint is kept if writing is interrupted. Errors are
+    /// logged as warnings, and don't stop the search.
+    pub(super) fn write_checkpoint(
+        &mut self,
+        tables: &FacetingTables,
+        options: &FacetingOptions,
+        stats: &mut FacetingStats,
+        output: &Found<'_>,
+    ) {
+        let path = match &options.checkpoint {
+            Some(checkpoint) => &checkpoint.path,
+            None => return,
+        };
+        self.checkpointed = (Instant::now(), stats.candidates_examined);
+
+        let mut encoder = Encoder::default();
+        tables.encode(&mut encoder);
+        encoder.usize(self.stack.len());
+        for (facets, min_key, ridge_muls) in &self.stack {
+            encoder.pairs(facets);
+            encoder.usize(*min_key);
+            encoder.indices(ridge_muls.iter());
+        }
+        encoder.indices(self.hp_key.iter());
+        encoder.indices(self.untried.iter());
+        encoder.usize(self.pruning.len());
+        for count in &self.pruning {
+            encoder.u64(count.added);
+            encoder.u64(count.exotic);
+        }
+        encoder.usize(self.tried);
+        encoder.usize(self.found);
+
+        let facetings = output.facetings();
+        encoder.usize(facetings.len());
+        for facets in facetings {
+            encoder.pairs(facets);
+        }
+        encoder.u64(stats.candidates_examined);
+        encoder.u64(stats.candidates_rejected);
+        encoder.u64(stats.bound_pruned);
+        encoder.usize(stats.scored_count);
+
+        let mut partial = path.clone().into_os_string();
+        partial.push(".partial");
+        let written = File::create(&partial)
+            .and_then(|file| {
+                let mut writer = BufWriter::new(file);
+                write_file(&mut writer, CHECKPOINT_MAGIC, arrangement_hash(&tables.vertices), &encoder.bytes)?;
+                writer.flush()
+            })
+            .and_then(|_| fs::rename(&partial, path));
+
+        match written {
+            Ok(()) => debug!("Wrote a checkpoint to {}", path.display()),
+            Err(err) => {
+                warn!("Couldn't write a checkpoint to {}: {}", path.display(), err);
+                stats.warning_count += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        conc::{faceting::GroupEnum, Concrete},
+        Polytope,
+    };
+
+    /// Returns the faceting tables of the cube.
+    fn cube_tables() -> FacetingTables {
+        let mut cube = Concrete::hypercube(4);
+        let vertices = cube.vertices.to_vec();
+        cube.faceting_tables(vertices, GroupEnum::Chiral(false), &Default::default())
+            .unwrap()
+    }
+
+    /// Returns the bytes of a cache file.
+    fn save(tables: &FacetingTables) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        tables.save_binary(&mut bytes).unwrap();
+        bytes
+    }
+
+    /// Numbers are read back as they were written, and varints that don't end
+    /// are caught.
+    #[test]
+    fn varints() {
+        let numbers = [0, 1, 127, 128, 300, u32::MAX as u64, u64::MAX];
+        let mut encoder = Encoder::default();
+        for &n in &numbers {
+            encoder.u64(n);
+        }
+        encoder.f64(-0.5);
+
+        let mut decoder = Decoder { bytes: &encoder.bytes };
+        for &n in &numbers {
+            assert_eq!(decoder.u64().unwrap(), n);
+        }
+        assert_eq!(decoder.f64().unwrap(), -0.5);
+        assert!(matches!(decoder.u8(), Err(CacheError::Truncated)));
+
+        let mut decoder = Decoder { bytes: &[0xff; 11] };
+        assert!(matches!(decoder.u64(), Err(CacheError::Corrupted(_))));
+    }
+
+    /// The tables of the cube are the same after saving and loading them.
+    #[test]
+    fn round_trip() {
+        let tables = cube_tables();
+        let loaded = FacetingTables::load_binary(save(&tables).as_slice(), &tables.vertices).unwrap();
+
+        assert_eq!(loaded.vertex_map, tables.vertex_map);
+        assert_eq!(loaded.rank, tables.rank);
+        assert_eq!(loaded.dim, tables.dim);
+        assert_eq!(loaded.possible_facets, tables.possible_facets);
+        assert_eq!(loaded.possible_facets_global, tables.possible_facets_global);
+        assert_eq!(loaded.compound_facets, tables.compound_facets);
+        assert_eq!(loaded.ridge_idx_orbits, tables.ridge_idx_orbits);
+        assert_eq!(loaded.ridge_counts, tables.ridge_counts);
+        assert_eq!(loaded.ridge_muls, tables.ridge_muls);
+        assert_eq!(loaded.ones, tables.ones);
+        assert_eq!(loaded.hyperplane_vertices, tables.hyperplane_vertices);
+        assert_eq!(loaded.hyperplane_sizes, tables.hyperplane_sizes);
+        assert_eq!(loaded.depths, tables.depths);
+        assert_eq!(loaded.ridges, tables.ridges);
+        assert_eq!(loaded.ff_counts, tables.ff_counts);
+        assert_eq!(loaded.degenerate_facets, tables.degenerate_facets);
+        assert_eq!(loaded.pairing, tables.pairing);
+        assert_eq!(loaded.meta, tables.meta);
+        assert_eq!(save(&loaded), save(&tables));
+    }
+
+    /// The allowed edge lengths are saved along with the other options.
+    #[test]
+    fn edge_lengths() {
+        use super::super::{EdgeLengthFilter, FacetingOptions};
+
+        let mut cube = Concrete::hypercube(4);
+        let vertices = cube.vertices.to_vec();
+        let mut options = FacetingOptions {
+            edge_length: EdgeLengthFilter::set(vec![1.0, 2f64.sqrt()]),
+            ..Default::default()
+        };
+        options.per_orbit_edge_length.insert(0, EdgeLengthFilter::set(vec![1.0]));
+        options.per_orbit_edge_length.insert(1, EdgeLengthFilter::between(1.0, 2.0));
+        let tables = cube.faceting_tables(vertices, GroupEnum::Chiral(false), &options).unwrap();
+        let loaded = FacetingTables::load_binary(save(&tables).as_slice(), &tables.vertices).unwrap();
+
+        assert_eq!(loaded.options.edge_length, options.edge_length);
+        assert_eq!(loaded.options.per_orbit_edge_length, options.per_orbit_edge_length);
+    }
+
+    /// The degenerate facets flagged are saved along with the options used to
+    /// find them.
+    #[test]
+    fn degenerate_facets() {
+        use super::super::FacetingOptions;
+
+        let mut cube = Concrete::hypercube(4);
+        let vertices = cube.vertices.to_vec();
+        let options = FacetingOptions {
+            degenerate_facets: DegenerateFacets::Flag,
+            degeneracy_tolerance: 10.0,
+            ..Default::default()
+        };
+        let tables = cube.faceting_tables(vertices, GroupEnum::Chiral(false), &options).unwrap();
+        assert!(tables.degenerate_facets.iter().any(|list| !list.is_empty()));
+
+        let loaded = FacetingTables::load_binary(save(&tables).as_slice(), &tables.vertices).unwrap();
+        assert_eq!(loaded.degenerate_facets, tables.degenerate_facets);
+        assert_eq!(loaded.options.degenerate_facets, DegenerateFacets::Flag);
+        assert_eq!(loaded.options.degeneracy_tolerance, 10.0);
+    }
+
+    /// The tolerance is saved along with the other options, and used to
+    /// rebuild the hyperplanes.
+    #[test]
+    fn tolerance() {
+        use super::super::FacetingOptions;
+        use crate::geometry::GeomContext;
+
+        let mut cube = Concrete::hypercube(4);
+        let vertices = cube.vertices.to_vec();
+        let options = FacetingOptions::with_geometry(GeomContext::with_tolerance(1e-9));
+        let tables = cube.faceting_tables(vertices, GroupEnum::Chiral(false), &options).unwrap();
+        let loaded = FacetingTables::load_binary(save(&tables).as_slice(), &tables.vertices).unwrap();
+
+        assert_eq!(loaded.options.tolerance(), 1e-9);
+        assert_eq!(save(&loaded), save(&tables));
+    }
+
+    /// The hyperplanes given to facet into are saved along with the other
+    /// options.
+    #[test]
+    fn hyperplanes() {
+        use super::super::FacetingOptions;
+
+        let mut cube = Concrete::hypercube(4);
+        let vertices = cube.vertices.to_vec();
+        let face = cube.abs.element_vertices(3, 0).unwrap();
+        let options = FacetingOptions {
+            hyperplanes: Some(vec![face]),
+            ..Default::default()
+        };
+        let tables = cube.faceting_tables(vertices, GroupEnum::Chiral(false), &options).unwrap();
+        let loaded = FacetingTables::load_binary(save(&tables).as_slice(), &tables.vertices).unwrap();
+
+        assert_eq!(loaded.options.hyperplanes, options.hyperplanes);
+        assert_eq!(save(&loaded), save(&tables));
+    }
+
+    /// The range of facet orbit counts is saved and loaded back.
+    #[test]
+    fn facet_orbits() {
+        use super::super::FacetingOptions;
+
+        let mut cube = Concrete::hypercube(4);
+        let vertices = cube.vertices.to_vec();
+        let options = FacetingOptions {
+            facet_orbits: Some(FacetOrbitCount::Between(1, 2)),
+            ..Default::default()
+        };
+        let tables = cube.faceting_tables(vertices, GroupEnum::Chiral(false), &options).unwrap();
+        let loaded = FacetingTables::load_binary(save(&tables).as_slice(), &tables.vertices).unwrap();
+
+        assert_eq!(loaded.options.facet_orbits, options.facet_orbits);
+        assert_eq!(save(&loaded), save(&tables));
+    }
+
+    /// The vertex subset is saved along with the other options, and the tables
+    /// only store the vertices in it.
+    #[test]
+    fn vertex_subset() {
+        use super::super::{FacetingOptions, VertexSubset};
+
+        let mut cube = Concrete::hypercube(4);
+        let vertices = cube.vertices.to_vec();
+        let options = FacetingOptions {
+            vertex_subset: Some(VertexSubset::Vertices(vec![0, 3, 5, 6])),
+            ..Default::default()
+        };
+        let tables = cube.faceting_tables(vertices, GroupEnum::Chiral(false), &options).unwrap();
+        assert_eq!(tables.vertices.len(), 4);
+        let loaded = FacetingTables::load_binary(save(&tables).as_slice(), &tables.vertices).unwrap();
+
+        assert_eq!(loaded.options.vertex_subset, options.vertex_subset);
+        assert_eq!(save(&loaded), save(&tables));
+    }
+
+    /// The geometry context is saved along with the other options.
+    #[test]
+    fn geometry() {
+        use super::super::FacetingOptions;
+        use crate::geometry::{CenterPolicy, GeomContext};
+
+        let mut cube = Concrete::hypercube(4);
+        let vertices = cube.vertices.to_vec();
+        let options = FacetingOptions::with_geometry(GeomContext {
+            tolerance: 1e-6,
+            center: CenterPolicy::Gravicenter,
+            quantum: Some(1e-9),
+        });
+        let tables = cube.faceting_tables(vertices, GroupEnum::Chiral(false), &options).unwrap();
+        let loaded = FacetingTables::load_binary(save(&tables).as_slice(), &tables.vertices).unwrap();
+
+        assert_eq!(loaded.options.geometry, options.geometry);
+        assert_eq!(save(&loaded), save(&tables));
+    }
+
+    /// Whether the possible facets were memoized is saved along with the
+    /// other options, as it changes their order.
+    #[test]
+    fn memoize_subdim() {
+        use super::super::FacetingOptions;
+
+        let mut cube = Concrete::hypercube(4);
+        let vertices = cube.vertices.to_vec();
+        let options = FacetingOptions {
+            memoize_subdim: true,
+            ..Default::default()
+        };
+        let tables = cube.faceting_tables(vertices, GroupEnum::Chiral(false), &options).unwrap();
+        let loaded = FacetingTables::load_binary(save(&tables).as_slice(), &tables.vertices).unwrap();
+
+        assert!(loaded.options.memoize_subdim);
+        assert_eq!(save(&loaded), save(&tables));
+    }
+
+    /// The noble option is saved as the exact number of facet orbits it
+    /// stands for.
+    #[test]
+    fn noble() {
+        use super::super::FacetingOptions;
+
+        let mut cube = Concrete::hypercube(4);
+        let vertices = cube.vertices.to_vec();
+        let options = FacetingOptions {
+            noble: Some(1),
+            ..Default::default()
+        };
+        let tables = cube.faceting_tables(vertices, GroupEnum::Chiral(false), &options).unwrap();
+        let loaded = FacetingTables::load_binary(save(&tables).as_slice(), &tables.vertices).unwrap();
+
+        assert_eq!(loaded.options.noble, None);
+        assert_eq!(loaded.options.facet_orbits, Some(FacetOrbitCount::Exact(1)));
+        assert_eq!(save(&loaded), save(&tables));
+    }
+
+    /// Moving the vertices of a clone of a polytope changes its arrangement
+    /// hash but not that of the original, so that only the original can load
+    /// the tables built for it.
+    #[test]
+    fn shared_vertices() {
+        let tables = cube_tables();
+        let cube = Concrete::hypercube(4);
+        let mut moved = cube.clone();
+        assert!(moved.vertices.ptr_eq(&cube.vertices));
+
+        moved.vertices[0][0] += 0.5;
+        assert!(!moved.vertices.ptr_eq(&cube.vertices));
+        assert_ne!(arrangement_hash(&moved.vertices), arrangement_hash(&cube.vertices));
+        assert_eq!(arrangement_hash(&cube.vertices), arrangement_hash(&tables.vertices));
+
+        let bytes = save(&tables);
+        assert!(FacetingTables::load_binary(bytes.as_slice(), &cube.vertices).is_ok());
+        assert!(matches!(
+            FacetingTables::load_binary(bytes.as_slice(), &moved.vertices),
+            Err(CacheError::ArrangementMismatch { .. })
+        ));
+    }
+
+    /// Damaging any byte of the payload, even when the checksum is fixed to
+    /// match, gives an error or some tables, but never a panic.
+    #[test]
+    fn damaged_payload() {
+        let tables = cube_tables();
+        let bytes = save(&tables);
+        let payload = &bytes[HEADER_LEN..bytes.len() - 8];
+
+        for idx in 0..payload.len() {
+            for &mask in &[0x01, 0x80, 0xff] {
+                let mut damaged = payload.to_vec();
+                damaged[idx] ^= mask;
+                let _ = FacetingTables::decode(&mut Decoder { bytes: &damaged }, MINOR_VERSION);
+            }
+        }
+    }
+}
@@ -0,0 +1,141 @@
+//! Chains faceting surveys into the next dimension: every isogonal polytope
+//! found by a faceting gives an isogonal polytope of one more dimension as
+//! its prism, which can be faceted in turn.
+//!
+//! The prisms record where they came from in their metadata: the name of the
+//! faceting under `prism of`, its index among the results under `faceting`,
+//! its facet orbits under `facets`, and the height of the prism under
+//! `height`.
+
+use std::fmt::Display;
+
+use super::{signature, FacetingResult};
+use crate::{
+    conc::{Concrete, ConcretePolytope},
+    float::Float,
+};
+
+use log::warn;
+
+/// How to choose the height of the prisms built by [`chain_prisms`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HeightPolicy {
+    /// Every prism has the same height.
+    Fixed(f64),
+
+    /// Every prism is as high as the edges of its base are long, so that all
+    /// of its edges have the same length. Bases with edges of different
+    /// lengths are skipped.
+    EqualEdges,
+}
+
+/// Why a faceting was skipped by [`chain_prisms_with_warnings`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum ChainWarning {
+    /// The edges of the faceting have different lengths, so no height makes
+    /// the edges of its prism all the same length.
+    MixedEdgeLengths {
+        /// The index of the faceting among the results.
+        result: usize,
+
+        /// The lengths of the edges of the faceting, in increasing order.
+        lengths: Vec<f64>,
+    },
+
+    /// The height chosen for the prism of the faceting isn't a positive
+    /// number, such as when its edges have no length.
+    DegenerateHeight {
+        /// The index of the faceting among the results.
+        result: usize,
+
+        /// The height chosen.
+        height: f64,
+    },
+}
+
+impl Display for ChainWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MixedEdgeLengths { result, lengths } => write!(
+                f,
+                "skipping the prism of faceting {}, whose edges have {} different lengths",
+                result,
+                lengths.len()
+            ),
+            Self::DegenerateHeight { result, height } => write!(
+                f,
+                "skipping the prism of faceting {}, which would have height {}",
+                result, height
+            ),
+        }
+    }
+}
+
+/// Returns the height of the prism of a faceting under a policy.
+fn prism_height(idx: usize, polytope: &Concrete, height_policy: HeightPolicy) -> Result<f64, ChainWarning> {
+    let height = match height_policy {
+        HeightPolicy::Fixed(height) => height,
+        HeightPolicy::EqualEdges => match polytope.edge_lengths().as_slice() {
+            [length] => *length,
+            [] => 0.0,
+            lengths => {
+                return Err(ChainWarning::MixedEdgeLengths {
+                    result: idx,
+                    lengths: lengths.to_vec(),
+                })
+            }
+        },
+    };
+
+    if height > f64::EPS {
+        Ok(height)
+    } else {
+        Err(ChainWarning::DegenerateHeight { result: idx, height })
+    }
+}
+
+/// Builds the prism of each faceting found, with heights chosen by a policy,
+/// and returns them along with the reasons the facetings without a prism were
+/// skipped for. See the [module docs](self) for the metadata of the prisms.
+pub fn chain_prisms_with_warnings(
+    results: &[FacetingResult],
+    height_policy: HeightPolicy,
+) -> (Vec<Concrete>, Vec<ChainWarning>) {
+    let mut prisms = Vec::new();
+    let mut warnings = Vec::new();
+
+    for (idx, result) in results.iter().enumerate() {
+        let polytope = &result.polytope;
+        let height = match prism_height(idx, polytope, height_policy) {
+            Ok(height) => height,
+            Err(warning) => {
+                warnings.push(warning);
+                continue;
+            }
+        };
+
+        let mut prism = polytope.prism_with(height);
+        prism.meta = polytope.meta.derive("prism");
+        let properties = &mut prism.meta.properties;
+        if let Some(name) = polytope.meta.name.as_ref().or(result.name.as_ref()) {
+            properties.insert("prism of".to_string(), name.clone());
+        }
+        properties.insert("faceting".to_string(), idx.to_string());
+        properties.insert("facets".to_string(), signature(&result.facets));
+        properties.insert("height".to_string(), height.to_string());
+        prisms.push(prism);
+    }
+
+    (prisms, warnings)
+}
+
+/// Builds the prism of each faceting found like
+/// [`chain_prisms_with_warnings`], logging a warning for each faceting that's
+/// skipped.
+pub fn chain_prisms(results: &[FacetingResult], height_policy: HeightPolicy) -> Vec<Concrete> {
+    let (prisms, warnings) = chain_prisms_with_warnings(results, height_policy);
+    for warning in warnings {
+        warn!("{}", warning);
+    }
+    prisms
+}
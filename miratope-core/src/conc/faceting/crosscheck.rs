@@ -0,0 +1,404 @@
+//! Cross-checks of the faceting algorithm, which run it twice through
+//! different code paths and compare the facetings found. These take twice as
+//! long as a single run, and are meant to validate enumerations before
+//! they're published.
+//!
+//! The facetings of the two runs are compared through their
+//! [`result_fingerprint`]s, counted with multiplicity, so that the comparison
+//! doesn't depend on the order the facetings are found in. A faceting that
+//! only one run found is reported along with its facet orbits, like the
+//! signatures in the names of facetings.
+
+use std::{cmp::Ordering, collections::BTreeMap, fmt::Display};
+
+use super::{cache::fnv1a, FacetingError, FacetingOptions, FacetingResult, GroupEnum, OrbitOrder};
+use crate::{
+    abs::{Abstract, Ranked},
+    conc::{symmetry::VertexMap, Concrete},
+};
+
+use log::info;
+use vec_like::*;
+
+/// A change made to the facetings found by a run, to check that the
+/// discrepancies it causes are caught.
+#[cfg(test)]
+type Fault = fn(&mut Vec<FacetingResult>);
+
+#[cfg(test)]
+thread_local! {
+    /// The fault injected into the second run of every cross-check on this
+    /// thread.
+    static FAULT: std::cell::Cell<Option<Fault>> = std::cell::Cell::new(None);
+}
+
+/// Returns a hash of the element counts of a polytope, and of how many
+/// subelements and superelements its elements of each rank have. Isomorphic
+/// polytopes have the same fingerprint, though some that aren't do too.
+pub fn fingerprint(abs: &Abstract) -> u64 {
+    degree_fingerprint(
+        abs.iter()
+            .map(|elements| elements.iter().map(|el| (el.subs.len(), el.sups.len())).collect()),
+    )
+}
+
+/// Returns the [`fingerprint`] of a polytope given by the numbers of
+/// subelements and superelements of its elements of each rank, from the
+/// minimal element up.
+pub(super) fn degree_fingerprint<I: IntoIterator<Item = Vec<(usize, usize)>>>(ranks: I) -> u64 {
+    let mut bytes = Vec::new();
+    for mut degrees in ranks {
+        bytes.extend_from_slice(&(degrees.len() as u64).to_le_bytes());
+        degrees.sort_unstable();
+        for (subs, sups) in degrees {
+            bytes.extend_from_slice(&(subs as u64).to_le_bytes());
+            bytes.extend_from_slice(&(sups as u64).to_le_bytes());
+        }
+    }
+    fnv1a(&bytes)
+}
+
+/// Returns a hash of the [`fingerprint`] of a faceting, and of the sorted
+/// numbers of facets in its facet orbits. Unlike its facet orbits, this
+/// doesn't depend on how the possible facets of each hyperplane orbit were
+/// numbered.
+pub fn result_fingerprint(result: &FacetingResult) -> u64 {
+    let mut counts = result.facet_counts.clone();
+    counts.sort_unstable();
+
+    let mut bytes = fingerprint(&result.polytope.abs).to_le_bytes().to_vec();
+    for count in counts {
+        bytes.extend_from_slice(&(count as u64).to_le_bytes());
+    }
+    fnv1a(&bytes)
+}
+
+/// One of the ways a cross-check runs the faceting, which overrides some
+/// of the options it's given.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CrosscheckConfig {
+    /// The order in which the hyperplane orbits are tried, see
+    /// [`FacetingOptions::orbit_order`].
+    pub orbit_order: OrbitOrder,
+
+    /// Whether congruent hyperplane orbits are only faceted once, see
+    /// [`FacetingOptions::memoize_subdim`].
+    pub memoize_subdim: bool,
+
+    /// Whether the facets are combined ridge orbit by ridge orbit, see
+    /// [`FacetingTables::enumerate_ridge_first`](super::FacetingTables::enumerate_ridge_first),
+    /// instead of hyperplane orbit by hyperplane orbit. When the ridge
+    /// multiplicity isn't 2, the facets are combined by hyperplane orbit
+    /// either way.
+    pub ridge_first: bool,
+}
+
+impl CrosscheckConfig {
+    /// The configurations [`Concrete::faceting_crosscheck`] compares. The
+    /// first one facets every hyperplane orbit through the memo and combines
+    /// the facets by hyperplane orbit in the order they were found, and the
+    /// second one facets every hyperplane orbit on its own and combines the
+    /// facets by ridge orbit.
+    pub const PAIR: [Self; 2] = [
+        Self {
+            orbit_order: OrbitOrder::Index,
+            memoize_subdim: true,
+            ridge_first: false,
+        },
+        Self {
+            orbit_order: OrbitOrder::FewestFacetsFirst,
+            memoize_subdim: false,
+            ridge_first: true,
+        },
+    ];
+
+    /// Returns the options to run the faceting with in this configuration.
+    /// The facetings are never truncated, as two truncated enumerations
+    /// can't be compared.
+    fn options(self, options: &FacetingOptions) -> FacetingOptions {
+        FacetingOptions {
+            orbit_order: self.orbit_order,
+            memoize_subdim: self.memoize_subdim,
+            max_results: None,
+            objective: None,
+            ..options.clone()
+        }
+    }
+}
+
+impl Display for CrosscheckConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?} orbit order, {}, {} first",
+            self.orbit_order,
+            if self.memoize_subdim { "memoized" } else { "not memoized" },
+            if self.ridge_first { "ridges" } else { "hyperplanes" }
+        )
+    }
+}
+
+/// A faceting found by only one of the runs of a cross-check.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnmatchedFaceting {
+    /// The fingerprint of the faceting, see [`result_fingerprint`].
+    pub fingerprint: u64,
+
+    /// The facet orbits of the faceting, see [`FacetingResult::facets`].
+    pub facets: Vec<(usize, usize)>,
+}
+
+impl Display for UnmatchedFaceting {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:016x}:", self.fingerprint)?;
+        for (hp, facet) in &self.facets {
+            write!(f, " ({},{})", hp, facet)?;
+        }
+        Ok(())
+    }
+}
+
+/// The facetings that only one of the runs of a cross-check found.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Discrepancy {
+    /// The configurations of the two runs.
+    pub configs: [CrosscheckConfig; 2],
+
+    /// The facetings only found by the first run, or found more times by it.
+    pub only_first: Vec<UnmatchedFaceting>,
+
+    /// The facetings only found by the second run, or found more times by it.
+    pub only_second: Vec<UnmatchedFaceting>,
+}
+
+impl Display for Discrepancy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (config, unmatched) in self.configs.iter().zip([&self.only_first, &self.only_second]) {
+            write!(f, "{} faceting(s) only found with {}", unmatched.len(), config)?;
+            for faceting in unmatched {
+                write!(f, "\n  {}", faceting)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl Discrepancy {
+    /// Compares the facetings found by two runs, and returns those that only
+    /// one of them found, if there are any. Facetings with the same
+    /// fingerprint and facet orbits are matched first, and the rest with the
+    /// same fingerprint are then matched in order.
+    fn between(configs: [CrosscheckConfig; 2], first: &[FacetingResult], second: &[FacetingResult]) -> Option<Self> {
+        let by_fingerprint = |results: &[FacetingResult]| {
+            let mut groups: BTreeMap<u64, Vec<Vec<(usize, usize)>>> = BTreeMap::new();
+            for result in results {
+                groups.entry(result_fingerprint(result)).or_default().push(result.facets.clone());
+            }
+            groups
+        };
+        let first = by_fingerprint(first);
+        let mut second = by_fingerprint(second);
+
+        let mut only_first = Vec::new();
+        let mut only_second = Vec::new();
+        let unmatched = |fingerprint: u64, facets: Vec<(usize, usize)>| UnmatchedFaceting { fingerprint, facets };
+
+        for (fingerprint, mut facetings) in first {
+            let mut others = second.remove(&fingerprint).unwrap_or_default();
+            facetings.retain(|facets| match others.iter().position(|other| other == facets) {
+                Some(idx) => {
+                    others.swap_remove(idx);
+                    false
+                }
+                None => true,
+            });
+
+            match facetings.len().cmp(&others.len()) {
+                Ordering::Greater => {
+                    let surplus = facetings.split_off(others.len());
+                    only_first.extend(surplus.into_iter().map(|facets| unmatched(fingerprint, facets)));
+                }
+                Ordering::Less => {
+                    let surplus = others.split_off(facetings.len());
+                    only_second.extend(surplus.into_iter().map(|facets| unmatched(fingerprint, facets)));
+                }
+                Ordering::Equal => {}
+            }
+        }
+        for (fingerprint, facetings) in second {
+            only_second.extend(facetings.into_iter().map(|facets| unmatched(fingerprint, facets)));
+        }
+
+        if only_first.is_empty() && only_second.is_empty() {
+            None
+        } else {
+            Some(Self {
+                configs,
+                only_first,
+                only_second,
+            })
+        }
+    }
+}
+
+/// The outcome of a cross-check.
+#[derive(Clone, Debug)]
+pub enum Crosscheck {
+    /// Both runs found the same facetings, which are those of the first run.
+    Verified(Vec<FacetingResult>),
+
+    /// The runs found different facetings.
+    Discrepancy(Discrepancy),
+}
+
+impl Crosscheck {
+    /// Returns whether both runs found the same facetings.
+    pub fn is_verified(&self) -> bool {
+        matches!(self, Self::Verified(_))
+    }
+}
+
+impl Concrete {
+    /// Enumerates the facetings of the polytope under a symmetry group twice,
+    /// with the configurations in [`CrosscheckConfig::PAIR`], and checks that
+    /// both runs find the same facetings. See the [module docs](self) for how
+    /// they're compared.
+    pub fn faceting_crosscheck(
+        &mut self,
+        symmetry: GroupEnum,
+        options: &FacetingOptions,
+    ) -> Result<Crosscheck, FacetingError> {
+        self.faceting_crosscheck_with(symmetry, options, CrosscheckConfig::PAIR)
+    }
+
+    /// Cross-checks the faceting of the polytope like
+    /// [`Self::faceting_crosscheck`], with two given configurations. The
+    /// symmetry group is only computed once, and both runs use its vertex
+    /// map. Each run builds its own tables from scratch with
+    /// [`Self::faceting_tables`], so the facetings are those of
+    /// [`FacetingTables::complete`](super::FacetingTables::complete).
+    pub fn faceting_crosscheck_with(
+        &mut self,
+        symmetry: GroupEnum,
+        options: &FacetingOptions,
+        configs: [CrosscheckConfig; 2],
+    ) -> Result<Crosscheck, FacetingError> {
+        let vertex_map =
+            VertexMap::from(self.symmetry_vertex_map(symmetry, self.vertex_count(), Some(&options.geometry))?);
+
+        let first = self.crosscheck_run(vertex_map.clone(), options, configs[0])?;
+        let second = self.crosscheck_run(vertex_map, options, configs[1])?;
+        #[cfg(test)]
+        let second = FAULT.with(|fault| {
+            let mut second = second;
+            if let Some(fault) = fault.get() {
+                fault(&mut second);
+            }
+            second
+        });
+
+        Ok(match Discrepancy::between(configs, &first, &second) {
+            Some(discrepancy) => Crosscheck::Discrepancy(discrepancy),
+            None => Crosscheck::Verified(first),
+        })
+    }
+
+    /// Runs the faceting for a cross-check in a given configuration.
+    fn crosscheck_run(
+        &mut self,
+        vertex_map: VertexMap,
+        options: &FacetingOptions,
+        config: CrosscheckConfig,
+    ) -> Result<Vec<FacetingResult>, FacetingError> {
+        info!("Cross-check run: {}", config);
+        let options = config.options(options);
+        options.check_polytopes()?;
+        let vertices = self.vertices.to_vec();
+        let tables = self.faceting_tables(vertices, GroupEnum::VertexMap(vertex_map), &options)?;
+
+        if config.ridge_first {
+            Ok(tables.enumerate_ridge_first(&options))
+        } else {
+            tables.complete(&[], &options)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Polytope;
+
+    /// Cross-checks the facetings of a cube, with a fault injected into the
+    /// second run.
+    fn crosscheck_cube(fault: Option<Fault>) -> Crosscheck {
+        FAULT.with(|cell| cell.set(fault));
+        let crosscheck = Concrete::hypercube(4)
+            .faceting_crosscheck(GroupEnum::Chiral(false), &FacetingOptions::default())
+            .unwrap();
+        FAULT.with(|cell| cell.set(None));
+        crosscheck
+    }
+
+    /// Both runs find the same facetings when nothing goes wrong.
+    #[test]
+    fn verified() {
+        match crosscheck_cube(None) {
+            Crosscheck::Verified(results) => assert!(!results.is_empty()),
+            Crosscheck::Discrepancy(discrepancy) => panic!("unexpected discrepancy:\n{}", discrepancy),
+        }
+    }
+
+    /// A faceting lost by the second run is reported.
+    #[test]
+    fn lost_faceting() {
+        let crosscheck = crosscheck_cube(Some(|results| {
+            results.remove(0);
+        }));
+        let expected = match crosscheck_cube(None) {
+            Crosscheck::Verified(results) => results[0].clone(),
+            Crosscheck::Discrepancy(_) => unreachable!(),
+        };
+
+        match crosscheck {
+            Crosscheck::Discrepancy(discrepancy) => {
+                assert_eq!(discrepancy.configs, CrosscheckConfig::PAIR);
+                assert!(discrepancy.only_second.is_empty());
+                assert_eq!(
+                    discrepancy.only_first,
+                    vec![UnmatchedFaceting {
+                        fingerprint: result_fingerprint(&expected),
+                        facets: expected.facets,
+                    }]
+                );
+            }
+            Crosscheck::Verified(_) => panic!("the lost faceting wasn't caught"),
+        }
+    }
+
+    /// A faceting built wrong by the second run is reported on both sides,
+    /// and so is a faceting found twice.
+    #[test]
+    fn wrong_faceting() {
+        let crosscheck = crosscheck_cube(Some(|results| {
+            let last = results.len() - 1;
+            results[last].facet_counts[0] += 1;
+            results.push(results[0].clone());
+        }));
+
+        match crosscheck {
+            Crosscheck::Discrepancy(discrepancy) => {
+                assert_eq!(discrepancy.only_first.len(), 1);
+                assert_eq!(discrepancy.only_second.len(), 2);
+                let wrong = &discrepancy.only_first[0];
+                assert!(discrepancy
+                    .only_second
+                    .iter()
+                    .any(|other| other.facets == wrong.facets && other.fingerprint != wrong.fingerprint));
+                assert!(discrepancy.to_string().starts_with("1 faceting(s) only found with Index orbit order"));
+            }
+            Crosscheck::Verified(_) => panic!("the wrong faceting wasn't caught"),
+        }
+    }
+}
@@ -0,0 +1,339 @@
+//! Reuses the facetings of a hyperplane for the later hyperplanes whose
+//! vertices are congruent to its own, under conjugate stabilizers. These are
+//! faceted in the same way up to relabeling their vertices, even when no
+//! symmetry of the polytope sends one hyperplane to the other, as with the
+//! pentagonal planes of an icosidodecahedron.
+
+use std::collections::{HashMap, HashSet};
+
+use super::{EdgeLengthFilter, PossibleFacet};
+use crate::{abs::Ranks, conc::symmetry::VertexPermutation, geometry::PointOrd};
+
+/// What [`super::faceting_subdim`] returns: the facetings of some points with
+/// their facet orbits, the number of hyperplanes in each hyperplane orbit,
+/// the possible facets of each of these, and the compound facetings mapped to
+/// their components.
+pub(super) type SubdimOutput = (
+    Vec<PossibleFacet>,
+    Vec<usize>,
+    Vec<Vec<Ranks>>,
+    HashMap<usize, (usize, usize)>,
+);
+
+/// The number of steps the search for a relabeling can take before giving up,
+/// in which case the hyperplane is just faceted again.
+const SEARCH_LIMIT: usize = 1_000_000;
+
+/// The vertices on a hyperplane and their stabilizer, in a form that can be
+/// compared with those of other hyperplanes.
+pub(super) struct Shape {
+    /// The distance between each pair of vertices.
+    distances: Vec<Vec<f64>>,
+
+    /// The distances from each vertex to the others, sorted.
+    profiles: Vec<Vec<f64>>,
+
+    /// The permutations of the vertices in the stabilizer.
+    stabilizer: HashSet<Vec<usize>>,
+
+    /// The number of rows of the stabilizer, repeated ones included.
+    stabilizer_len: usize,
+}
+
+impl Shape {
+    /// Reads off the shape of some vertices under their stabilizer.
+    pub fn new(points: &[PointOrd<f64>], stabilizer: &[VertexPermutation]) -> Self {
+        let distances: Vec<Vec<f64>> = points
+            .iter()
+            .map(|p| points.iter().map(|q| (&p.0 - &q.0).norm()).collect())
+            .collect();
+
+        let profiles = distances
+            .iter()
+            .map(|row| {
+                let mut row = row.clone();
+                row.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                row
+            })
+            .collect();
+
+        Self {
+            distances,
+            profiles,
+            stabilizer: stabilizer.iter().map(|row| row.0.clone()).collect(),
+            stabilizer_len: stabilizer.len(),
+        }
+    }
+
+    /// The key the shapes are stored under. Only shapes with the same key can
+    /// match.
+    fn key(&self) -> (usize, usize) {
+        (self.distances.len(), self.stabilizer_len)
+    }
+
+    /// Returns a bijection from these vertices to those of another shape that
+    /// preserves the distances between them, and conjugates one stabilizer
+    /// into the other, if one is found.
+    fn matching(&self, other: &Self, tolerance: f64) -> Option<Vec<usize>> {
+        let close = |a: &[f64], b: &[f64]| a.iter().zip(b).all(|(x, y)| (x - y).abs() < tolerance);
+
+        // The vertices each vertex may be sent to.
+        let candidates: Vec<Vec<usize>> = self
+            .profiles
+            .iter()
+            .map(|profile| {
+                (0..other.profiles.len())
+                    .filter(|&w| close(profile, &other.profiles[w]))
+                    .collect()
+            })
+            .collect();
+        if candidates.iter().any(Vec::is_empty) {
+            return None;
+        }
+
+        let mut search = Search {
+            from: self,
+            to: other,
+            candidates,
+            tolerance,
+            map: Vec::new(),
+            used: vec![false; other.distances.len()],
+            steps: 0,
+        };
+        if search.extend() {
+            Some(search.map)
+        } else {
+            None
+        }
+    }
+
+    /// Returns whether a bijection conjugates the stabilizer of this shape
+    /// into that of another.
+    fn conjugates(&self, other: &Self, map: &[usize]) -> bool {
+        self.stabilizer.iter().all(|row| {
+            let mut image = vec![0; map.len()];
+            for (v, &w) in row.iter().enumerate() {
+                image[map[v]] = map[w];
+            }
+            other.stabilizer.contains(&image)
+        })
+    }
+}
+
+/// A backtracking search for a bijection between the vertices of two shapes,
+/// assigning the vertices in order.
+struct Search<'a> {
+    /// The shape the bijection starts from.
+    from: &'a Shape,
+
+    /// The shape the bijection ends in.
+    to: &'a Shape,
+
+    /// The vertices each vertex may be sent to.
+    candidates: Vec<Vec<usize>>,
+
+    /// How close two distances must be to count as equal.
+    tolerance: f64,
+
+    /// The images of the vertices assigned so far.
+    map: Vec<usize>,
+
+    /// Whether each vertex of the other shape is already an image.
+    used: Vec<bool>,
+
+    /// The number of assignments tried so far.
+    steps: usize,
+}
+
+impl<'a> Search<'a> {
+    /// Tries to extend the bijection to the remaining vertices.
+    fn extend(&mut self) -> bool {
+        let v = self.map.len();
+        if v == self.from.distances.len() {
+            return self.from.conjugates(self.to, &self.map);
+        }
+
+        for idx in 0..self.candidates[v].len() {
+            let w = self.candidates[v][idx];
+            self.steps += 1;
+            if self.used[w] || self.steps > SEARCH_LIMIT {
+                continue;
+            }
+
+            let fits = self.map.iter().enumerate().all(|(u, &image)| {
+                (self.from.distances[v][u] - self.to.distances[w][image]).abs() < self.tolerance
+            });
+            if !fits {
+                continue;
+            }
+
+            self.map.push(w);
+            self.used[w] = true;
+            if self.extend() {
+                return true;
+            }
+            self.map.pop();
+            self.used[w] = false;
+        }
+
+        false
+    }
+}
+
+/// The shape of a hyperplane faceted, along with its edge lengths and the
+/// output for it.
+type MemoEntry = (Shape, EdgeLengthFilter, SubdimOutput);
+
+/// The outputs of [`super::faceting_subdim`] for the hyperplanes faceted so
+/// far, stored under the shapes of their vertices.
+#[derive(Default)]
+pub(super) struct SubdimMemo {
+    /// The shapes faceted so far along with their edge lengths and outputs,
+    /// stored under their keys.
+    entries: HashMap<(usize, usize), Vec<MemoEntry>>,
+
+    /// The number of hyperplanes whose outputs were reused.
+    pub hits: usize,
+}
+
+impl SubdimMemo {
+    /// Returns the output for a hyperplane of some shape with some edge
+    /// lengths, relabeled from one with a congruent shape, if there is one.
+    pub fn get(&mut self, shape: &Shape, edge_lengths: &EdgeLengthFilter, tolerance: f64) -> Option<SubdimOutput> {
+        let entries = self.entries.get(&shape.key())?;
+        let relabeled = entries.iter().find_map(|(other, other_lengths, output)| {
+            if other_lengths != edge_lengths {
+                return None;
+            }
+            shape.matching(other, tolerance).map(|map| relabel(output, &map))
+        });
+
+        if relabeled.is_some() {
+            self.hits += 1;
+        }
+        relabeled
+    }
+
+    /// Stores the output for a hyperplane of some shape with some edge
+    /// lengths.
+    pub fn insert(&mut self, shape: Shape, edge_lengths: &EdgeLengthFilter, output: &SubdimOutput) {
+        self.entries
+            .entry(shape.key())
+            .or_default()
+            .push((shape, edge_lengths.clone(), output.clone()));
+    }
+}
+
+/// Relabels the vertices in an output computed for another shape, given the
+/// image of each vertex in that shape.
+fn relabel(output: &SubdimOutput, map: &[usize]) -> SubdimOutput {
+    let (facetings, counts, ridges, compounds) = output;
+    let inverse = VertexPermutation(map.to_vec()).inverse();
+
+    let facetings = facetings
+        .iter()
+        .map(|(ranks, facets)| (inverse.apply_to_ranks(ranks), facets.clone()))
+        .collect();
+    let ridges = ridges
+        .iter()
+        .map(|orbit| orbit.iter().map(|ridge| inverse.apply_to_ranks(ridge)).collect())
+        .collect();
+
+    (facetings, counts.clone(), ridges, compounds.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Point;
+
+    /// The vertices of a rectangle, starting from a corner.
+    fn rectangle(width: f64, height: f64, first: usize) -> Vec<PointOrd<f64>> {
+        let corners = [(0.0, 0.0), (width, 0.0), (width, height), (0.0, height)];
+        (0..4)
+            .map(|i| {
+                let (x, y) = corners[(first + i) % 4];
+                PointOrd::new(Point::from_vec(vec![x, y]))
+            })
+            .collect()
+    }
+
+    /// The symmetries of a rectangle that fix its sides, in the labeling of
+    /// [`rectangle`] with the first corner at 0.
+    fn rectangle_symmetries() -> Vec<VertexPermutation> {
+        vec![
+            VertexPermutation(vec![0, 1, 2, 3]),
+            VertexPermutation(vec![1, 0, 3, 2]),
+            VertexPermutation(vec![3, 2, 1, 0]),
+            VertexPermutation(vec![2, 3, 0, 1]),
+        ]
+    }
+
+    /// A rectangle is congruent to itself with its corners relabeled, but not
+    /// to a square.
+    #[test]
+    fn matching() {
+        let shape = Shape::new(&rectangle(1.0, 2.0, 0), &rectangle_symmetries());
+
+        // Relabel the corners by a rotation.
+        let rotation = VertexPermutation(vec![1, 2, 3, 0]);
+        let symmetries: Vec<_> = rectangle_symmetries()
+            .iter()
+            .map(|row| rotation.compose(row).compose(&rotation.inverse()))
+            .collect();
+        let rotated = Shape::new(&rectangle(1.0, 2.0, 3), &symmetries);
+
+        let map = rotated.matching(&shape, 1e-9).unwrap();
+        for v in 0..4 {
+            for w in 0..4 {
+                assert!((rotated.distances[v][w] - shape.distances[map[v]][map[w]]).abs() < 1e-9);
+            }
+        }
+
+        let square = Shape::new(&rectangle(1.0, 1.0, 0), &rectangle_symmetries());
+        assert!(square.matching(&shape, 1e-9).is_none());
+    }
+
+    /// Congruent vertices whose stabilizers aren't conjugate don't match.
+    #[test]
+    fn stabilizers() {
+        let symmetries = rectangle_symmetries();
+        let reflect_x = vec![symmetries[0].clone(), symmetries[1].clone()];
+        let reflect_y = vec![symmetries[0].clone(), symmetries[2].clone()];
+
+        let shape = Shape::new(&rectangle(1.0, 2.0, 0), &reflect_x);
+        let same = Shape::new(&rectangle(1.0, 2.0, 0), &reflect_x);
+        let other = Shape::new(&rectangle(1.0, 2.0, 0), &reflect_y);
+        assert!(same.matching(&shape, 1e-9).is_some());
+        assert!(other.matching(&shape, 1e-9).is_none());
+    }
+
+    /// The output stored for a shape is relabeled for a congruent one.
+    #[test]
+    fn memo() {
+        let lengths = EdgeLengthFilter::default();
+        let edge = crate::ranks![[], [[0], [0], [0], [0]], [[0, 1]]];
+        let output = (vec![(edge, vec![(0, 0)])], vec![1], Vec::new(), HashMap::new());
+
+        let mut memo = SubdimMemo::default();
+        memo.insert(Shape::new(&rectangle(1.0, 2.0, 0), &rectangle_symmetries()), &lengths, &output);
+
+        let rotation = VertexPermutation(vec![1, 2, 3, 0]);
+        let symmetries: Vec<_> = rectangle_symmetries()
+            .iter()
+            .map(|row| rotation.compose(row).compose(&rotation.inverse()))
+            .collect();
+        let rotated = Shape::new(&rectangle(1.0, 2.0, 3), &symmetries);
+
+        // The edge from the first corner along the short side.
+        let (facetings, ..) = memo.get(&rotated, &lengths, 1e-9).unwrap();
+        let edge = &facetings[0].0[2][0].subs;
+        assert!((rotated.distances[edge[0]][edge[1]] - 1.0).abs() < 1e-9);
+        assert_eq!(memo.hits, 1);
+
+        // Other edge lengths aren't reused.
+        let other_lengths = EdgeLengthFilter::Range { min: Some(1.5), max: None };
+        assert!(memo.get(&rotated, &other_lengths, 1e-9).is_none());
+        assert_eq!(memo.hits, 1);
+    }
+}
@@ -0,0 +1,348 @@
+//! Detection of hyperplane orbits that numerical noise split apart.
+//!
+//! The hyperplane orbits are orbits under the symmetries computed for the
+//! vertices, so a symmetry that barely misses the tolerance splits each orbit
+//! it would have joined into several. When
+//! [`FacetingOptions::detect_split_orbits`] is set, the symmetries are
+//! computed again with a tolerance [`MERGE_TOLERANCE_FACTOR`] times looser.
+//! Each hyperplane orbit that one of these sends to an earlier orbit is then
+//! reported as a [`MergeCandidate`], if it looks like the same orbit: their
+//! hyperplanes have as many vertices, their depths are close, and the
+//! isometry closest to the symmetry relating them moves the vertices and the
+//! hyperplanes by less than the looser tolerance.
+//!
+//! With [`FacetingOptions::auto_merge`], the hyperplane orbits are regrouped
+//! under the looser symmetries, and every hyperplane orbit is faceted again
+//! under them, as the stabilizers of all of them grow along with the group.
+
+use std::{collections::HashSet, fmt::Display};
+
+use super::{
+    hyperplane_depth, sort_by_depth, FacetingError, FacetingOptions, FacetingSetup, FacetingStats, GroupEnum,
+    OrbitOfHyperplanes,
+};
+use crate::{
+    conc::{
+        symmetry::{restrict_vertex_map, validate_vertex_map, VertexList, VertexPermutation},
+        Concrete,
+    },
+    geometry::{GeomContext, Matrix, Point, Subspace},
+};
+
+use log::{debug, info, warn};
+
+/// How many times looser than [`FacetingOptions::tolerance`] the tolerance
+/// used to look for split hyperplane orbits is.
+pub const MERGE_TOLERANCE_FACTOR: f64 = 10.0;
+
+/// Two hyperplane orbits that look like a single orbit split apart by the
+/// tolerance, along with the evidence for it. The distances are all less than
+/// [`MERGE_TOLERANCE_FACTOR`] times the tolerance.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MergeCandidate {
+    /// The index of the hyperplane orbit found first.
+    pub first: usize,
+
+    /// The index of the hyperplane orbit that looks like part of the first.
+    pub second: usize,
+
+    /// The number of vertices on each of their hyperplanes.
+    pub vertex_count: usize,
+
+    /// The difference between the depths of the two orbits.
+    pub depth_difference: f64,
+
+    /// How far the image of the hyperplane of the first orbit under the
+    /// closest isometry is from the hyperplane of the second orbit that it's
+    /// sent to, measured at the vertices on it.
+    pub plane_distance: f64,
+
+    /// How far the closest isometry sends any vertex from its image under the
+    /// symmetry.
+    pub miss: f64,
+
+    /// The symmetry found with the looser tolerance, as a permutation of the
+    /// vertices, which sends the vertices on the hyperplane of the first orbit
+    /// to those on a hyperplane of the second.
+    pub symmetry: VertexPermutation,
+}
+
+impl Display for MergeCandidate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "hyperplane orbits {} and {} may be a single orbit: {} vertices each, depths {:e} apart, \
+            planes {:e} apart, related by a symmetry missing by {:e}",
+            self.first, self.second, self.vertex_count, self.depth_difference, self.plane_distance, self.miss
+        )
+    }
+}
+
+/// Returns the symmetry to compute again with a looser tolerance, or `None`
+/// for a vertex map, which has no tolerance to loosen.
+pub(super) fn near_symmetry(symmetry: &GroupEnum) -> Option<GroupEnum> {
+    match symmetry {
+        GroupEnum::ConcGroup(group) => Some(GroupEnum::ConcGroup(group.clone())),
+        GroupEnum::ChiralOf(group) => Some(GroupEnum::ChiralOf(group.clone())),
+        GroupEnum::Chiral(chiral) => Some(GroupEnum::Chiral(*chiral)),
+        GroupEnum::VertexMap(_) => None,
+    }
+}
+
+/// Returns the orthogonal matrix that sends the vertices closest to their
+/// images under a permutation, by the least squares.
+fn closest_isometry(vertices: &[Point<f64>], symmetry: &VertexPermutation) -> Matrix<f64> {
+    let dim = vertices.first().map_or(0, |v| v.len());
+    let mut correlation = Matrix::zeros(dim, dim);
+    for (v, &image) in vertices.iter().zip(symmetry.iter()) {
+        correlation += &vertices[image] * v.transpose();
+    }
+
+    let svd = correlation.svd(true, true);
+    svd.u.unwrap() * svd.v_t.unwrap()
+}
+
+impl Concrete {
+    /// Computes the symmetries of the vertices to facet like
+    /// [`Self::symmetry_vertex_map`], with a tolerance
+    /// [`MERGE_TOLERANCE_FACTOR`] times looser than that of the options.
+    /// Returns `None` if there aren't more of them than the symmetries found
+    /// with the tolerance of the options, or if they don't form a group.
+    pub(super) fn near_vertex_map(
+        &mut self,
+        symmetry: GroupEnum,
+        vertex_count: usize,
+        subset: Option<&[usize]>,
+        vertex_map: &[VertexPermutation],
+        options: &FacetingOptions,
+        stats: &mut FacetingStats,
+    ) -> Option<Vec<VertexPermutation>> {
+        let geometry = GeomContext {
+            tolerance: options.geometry.tolerance * MERGE_TOLERANCE_FACTOR,
+            ..options.geometry
+        };
+
+        info!("Computing symmetries at tolerance {:e}...", geometry.tolerance);
+        let near_map = match self.symmetry_vertex_map(symmetry, vertex_count, Some(&geometry)) {
+            Ok(near_map) => near_map,
+            Err(err) => {
+                warn!("the symmetries at tolerance {:e} couldn't be computed: {}", geometry.tolerance, err);
+                stats.warning_count += 1;
+                return None;
+            }
+        };
+        let near_map = match subset {
+            Some(subset) => restrict_vertex_map(&near_map, subset),
+            None => near_map,
+        };
+
+        if near_map.len() <= vertex_map.len() {
+            debug!("No more symmetries at tolerance {:e}", geometry.tolerance);
+            return None;
+        }
+        if let Err(err) = validate_vertex_map(&near_map, near_map[0].len()) {
+            warn!("the symmetries at tolerance {:e} don't form a group: {}", geometry.tolerance, err);
+            stats.warning_count += 1;
+            return None;
+        }
+
+        debug!("{} symmetries at tolerance {:e}, against {}", near_map.len(), geometry.tolerance, vertex_map.len());
+        Some(near_map)
+    }
+}
+
+impl FacetingSetup {
+    /// Sets up the same vertices for faceting under other symmetries.
+    fn with_vertex_map(&self, vertex_map: Vec<VertexPermutation>) -> Self {
+        let mut setup = Self::new(self.vertices.clone(), vertex_map, self.rank, self.dim);
+        setup.reflection = self.reflection.clone();
+        setup.chiral_selection = self.chiral_selection;
+        setup.known_vertices = self.known_vertices;
+        setup.depth_bounds = self.depth_bounds;
+        setup.subset = self.subset.clone();
+        setup
+    }
+
+    /// Returns the evidence that two hyperplane orbits are a single one, if
+    /// it's strong enough.
+    fn merge_candidate(
+        &self,
+        (first, first_orbit): (usize, &OrbitOfHyperplanes),
+        (second, second_orbit): (usize, &OrbitOfHyperplanes),
+        symmetry: &VertexPermutation,
+        tolerance: f64,
+    ) -> Option<MergeCandidate> {
+        let vertices = &self.vertices;
+        let isometry = closest_isometry(vertices, symmetry);
+        let miss = vertices
+            .iter()
+            .zip(symmetry.iter())
+            .map(|(v, &image)| (&isometry * v - &vertices[image]).norm())
+            .fold(0.0, f64::max);
+
+        let image = symmetry.apply(first_orbit.1.as_slice());
+        let hyperplane = Subspace::from_points_within(image.iter().map(|&v| &vertices[v]), tolerance);
+        let plane_distance = first_orbit
+            .1
+            .iter()
+            .map(|&v| hyperplane.distance(&(&isometry * &vertices[v])))
+            .fold(0.0, f64::max);
+
+        let depth_difference =
+            (hyperplane_depth(&first_orbit.0, self.dim) - hyperplane_depth(&second_orbit.0, self.dim)).abs();
+
+        if miss < tolerance && plane_distance < tolerance && depth_difference < tolerance {
+            Some(MergeCandidate {
+                first,
+                second,
+                vertex_count: first_orbit.1.len(),
+                depth_difference,
+                plane_distance,
+                miss,
+                symmetry: symmetry.clone(),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Pairs each hyperplane orbit that looks like part of an earlier one with
+    /// the first such orbit, under the symmetries found with a looser
+    /// tolerance.
+    fn merge_candidates(
+        &self,
+        hyperplane_orbits: &[OrbitOfHyperplanes],
+        near_map: &[VertexPermutation],
+        tolerance: f64,
+    ) -> Vec<MergeCandidate> {
+        // The hyperplanes of each orbit, by the vertices on them.
+        let hyperplanes: Vec<HashSet<VertexList>> = hyperplane_orbits
+            .iter()
+            .map(|orbit| self.vertex_map.iter().map(|row| row.apply(orbit.1.as_slice())).collect())
+            .collect();
+        let depths: Vec<_> = hyperplane_orbits
+            .iter()
+            .map(|orbit| hyperplane_depth(&orbit.0, self.dim))
+            .collect();
+
+        let mut candidates = Vec::new();
+        let mut merged = vec![false; hyperplane_orbits.len()];
+        for (second, second_orbit) in hyperplane_orbits.iter().enumerate() {
+            for (first, first_orbit) in hyperplane_orbits[..second].iter().enumerate() {
+                if merged[first]
+                    || first_orbit.1.len() != second_orbit.1.len()
+                    || (depths[first] - depths[second]).abs() >= tolerance
+                {
+                    continue;
+                }
+
+                let candidate = near_map
+                    .iter()
+                    .filter(|row| hyperplanes[second].contains(&row.apply(first_orbit.1.as_slice())))
+                    .find_map(|row| self.merge_candidate((first, first_orbit), (second, second_orbit), row, tolerance));
+                if let Some(candidate) = candidate {
+                    candidates.push(candidate);
+                    merged[second] = true;
+                    break;
+                }
+            }
+        }
+
+        candidates
+    }
+
+    /// Regroups some hyperplane orbits into orbits under the symmetries of
+    /// the setup, keeping the first of the orbits merged into each.
+    fn regroup(&self, hyperplane_orbits: &[OrbitOfHyperplanes], tolerance: f64) -> Vec<OrbitOfHyperplanes> {
+        let mut seen = HashSet::new();
+        let mut regrouped = Vec::new();
+
+        for (_, hp_v, _) in hyperplane_orbits {
+            if seen.contains(hp_v) {
+                continue;
+            }
+            seen.extend(self.vertex_map.iter().map(|row| row.apply(hp_v.as_slice())));
+
+            let hp_v = self.canonical_image(hp_v);
+            let hyperplane = Subspace::from_points_within(hp_v.iter().map(|&v| &self.vertices[v]), tolerance);
+            let size = self.orbit_size(&hp_v);
+            regrouped.push((hyperplane, hp_v, size));
+        }
+
+        sort_by_depth(&mut regrouped, &self.vertices, self.dim, tolerance);
+        regrouped
+    }
+
+    /// Looks for hyperplane orbits split apart by the tolerance, if the
+    /// symmetries at a looser tolerance were computed, and warns about them.
+    /// With [`FacetingOptions::auto_merge`], returns the setup under the
+    /// looser symmetries along with the merged orbits, which the tables must
+    /// be built from. Otherwise the orbits are returned as they are.
+    pub(super) fn check_split_orbits(
+        &self,
+        hyperplane_orbits: Vec<OrbitOfHyperplanes>,
+        options: &FacetingOptions,
+        stats: &mut FacetingStats,
+    ) -> (Option<Self>, Vec<OrbitOfHyperplanes>) {
+        let near_map = match &self.near_map {
+            Some(near_map) => near_map,
+            None => return (None, hyperplane_orbits),
+        };
+
+        let tolerance = options.geometry.tolerance * MERGE_TOLERANCE_FACTOR;
+        let candidates = self.merge_candidates(&hyperplane_orbits, near_map, tolerance);
+        if candidates.is_empty() {
+            return (None, hyperplane_orbits);
+        }
+        for candidate in &candidates {
+            warn!("{}", candidate);
+        }
+        stats.merge_candidate_count += candidates.len();
+        stats.warning_count += 1;
+        if !options.auto_merge {
+            return (None, hyperplane_orbits);
+        }
+
+        let setup = self.with_vertex_map(near_map.clone());
+        let merged = setup.regroup(&hyperplane_orbits, options.geometry.tolerance);
+        let removed = hyperplane_orbits.len() - merged.len();
+        info!("Merged {} hyperplane orbits into {}", hyperplane_orbits.len(), merged.len());
+
+        stats.merged_orbit_count += removed;
+        stats.hyperplane_orbit_count -= removed;
+        stats.group_order = setup.vertex_map.len();
+        stats.vertex_orbit_count = setup.vertex_orbits.len();
+        stats.central_symmetry = setup.inversion.is_some();
+        (Some(setup), merged)
+    }
+}
+
+impl Concrete {
+    /// Enumerates the hyperplane orbits like [`Self::try_hyperplane_orbits`],
+    /// without merging any, and returns the pairs of them that look like a
+    /// single orbit split apart by the tolerance. The orbits are numbered as
+    /// they're returned by [`Self::try_hyperplane_orbits`] without
+    /// [`FacetingOptions::auto_merge`].
+    pub fn split_hyperplane_orbits(
+        &mut self,
+        vertices: Vec<Point<f64>>,
+        symmetry: GroupEnum,
+        options: &FacetingOptions,
+    ) -> Result<Vec<MergeCandidate>, FacetingError> {
+        let options = FacetingOptions {
+            detect_split_orbits: true,
+            auto_merge: false,
+            ..options.clone()
+        };
+        let mut stats = FacetingStats::default();
+        let setup = self.faceting_setup(vertices, symmetry, &options, &mut stats)?;
+        let (hyperplane_orbits, retry) =
+            setup.consistent_hyperplane_orbits(&options.edge_length, &options, &mut stats)?;
+        let tolerance = retry.as_ref().unwrap_or(&options).geometry.tolerance * MERGE_TOLERANCE_FACTOR;
+
+        Ok(match &setup.near_map {
+            Some(near_map) => setup.merge_candidates(&hyperplane_orbits, near_map, tolerance),
+            None => Vec::new(),
+        })
+    }
+}
@@ -0,0 +1,640 @@
+//! A SQLite database for the outputs of large faceting surveys, where many
+//! polytopes are faceted and their results need to be queried afterwards.
+//!
+//! The database has a table for each of the following:
+//!
+//! - `arrangements`: the vertex arrangements faceted, keyed by their
+//!   [`arrangement_hash`], with their metadata.
+//! - `runs`: the faceting runs on each arrangement, with their main options and
+//!   statistics.
+//! - `hyperplane_orbits`: the hyperplane orbits of a run, with their sizes and
+//!   depths.
+//! - `facet_types`: the possible facets of each hyperplane orbit, with their
+//!   invariants. These are read off the faceting tables by
+//!   [`FacetingTables::facet_summary`], without building the facets.
+//! - `results`: the facetings found by a run, with their signatures,
+//!   invariants, coordinates and abstract structure.
+//!
+//! The signature of a faceting is its list of facet orbits, written like in
+//! the names of facetings, such as `(0,1) (2,0)`. Its [`fingerprint`] is a
+//! hash of some of its combinatorial invariants, so that isomorphic
+//! facetings always have the same one. The coordinates are stored as the
+//! little-endian bits of each of them, and the abstract structure as the
+//! subelements of each element from the minimal element up.
+
+use std::{fmt::Display, path::Path};
+
+pub use super::crosscheck::fingerprint;
+
+use super::{cache::arrangement_hash, signature, FacetingOptions, FacetingResult, FacetingStats, FacetingTables};
+use crate::{
+    abs::{Abstract, AbstractError, Ranked},
+    conc::{meta::PolytopeMeta, Concrete},
+    geometry::Point,
+    Polytope,
+};
+
+use rusqlite::{params, Connection, OptionalExtension};
+use vec_like::*;
+
+/// The tables of a survey database, created when it's opened if they don't
+/// exist yet.
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS arrangements (
+    id INTEGER PRIMARY KEY,
+    hash INTEGER NOT NULL UNIQUE,
+    vertex_count INTEGER NOT NULL,
+    dim INTEGER NOT NULL,
+    name TEXT,
+    meta TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS runs (
+    id INTEGER PRIMARY KEY,
+    arrangement INTEGER NOT NULL REFERENCES arrangements(id),
+    min_edge_length REAL,
+    max_edge_length REAL,
+    noble INTEGER,
+    ridge_multiplicity INTEGER NOT NULL,
+    tolerance REAL NOT NULL,
+    vertex_count INTEGER NOT NULL,
+    group_order INTEGER NOT NULL,
+    hyperplane_orbit_count INTEGER NOT NULL,
+    possible_facet_count INTEGER NOT NULL,
+    faceting_count INTEGER NOT NULL,
+    cancelled INTEGER NOT NULL,
+    stats TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS hyperplane_orbits (
+    run INTEGER NOT NULL REFERENCES runs(id),
+    orbit INTEGER NOT NULL,
+    hyperplane_count INTEGER NOT NULL,
+    vertex_count INTEGER NOT NULL,
+    depth REAL NOT NULL,
+    PRIMARY KEY (run, orbit)
+);
+CREATE TABLE IF NOT EXISTS facet_types (
+    run INTEGER NOT NULL REFERENCES runs(id),
+    orbit INTEGER NOT NULL,
+    facet INTEGER NOT NULL,
+    element_counts TEXT NOT NULL,
+    fingerprint INTEGER NOT NULL,
+    degenerate INTEGER NOT NULL,
+    vertex_count INTEGER NOT NULL,
+    edge_length_count INTEGER NOT NULL,
+    equilateral INTEGER NOT NULL,
+    PRIMARY KEY (run, orbit, facet)
+);
+CREATE TABLE IF NOT EXISTS results (
+    id INTEGER PRIMARY KEY,
+    run INTEGER NOT NULL REFERENCES runs(id),
+    name TEXT,
+    signature TEXT NOT NULL,
+    facet_counts TEXT NOT NULL,
+    fingerprint INTEGER NOT NULL,
+    element_counts TEXT NOT NULL,
+    euler_characteristic INTEGER NOT NULL,
+    orientable INTEGER NOT NULL,
+    compound INTEGER NOT NULL,
+    dim INTEGER NOT NULL,
+    vertices BLOB NOT NULL,
+    abstract BLOB NOT NULL
+);
+CREATE INDEX IF NOT EXISTS results_signature ON results(signature);
+CREATE INDEX IF NOT EXISTS results_fingerprint ON results(fingerprint);
+";
+
+/// Any error encountered while reading or writing a survey database.
+#[derive(Debug)]
+pub enum SurveyError {
+    /// SQLite returned an error.
+    Sqlite(rusqlite::Error),
+
+    /// A stored faceting couldn't be read back.
+    Corrupted(&'static str),
+
+    /// The abstract structure of a stored faceting isn't a polytope.
+    Abstract(AbstractError),
+}
+
+impl Display for SurveyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Sqlite(err) => write!(f, "SQLite error: {}", err),
+            Self::Corrupted(what) => write!(f, "corrupted survey database: {}", what),
+            Self::Abstract(err) => write!(f, "invalid stored polytope: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for SurveyError {}
+
+impl From<rusqlite::Error> for SurveyError {
+    fn from(err: rusqlite::Error) -> Self {
+        Self::Sqlite(err)
+    }
+}
+
+impl From<AbstractError> for SurveyError {
+    fn from(err: AbstractError) -> Self {
+        Self::Abstract(err)
+    }
+}
+
+/// The result of an operation on a survey database.
+pub type SurveyResult<T> = Result<T, SurveyError>;
+
+/// Reads a signature written by [`signature`].
+fn parse_signature(signature: &str) -> SurveyResult<Vec<(usize, usize)>> {
+    signature
+        .split_whitespace()
+        .map(|facet| {
+            let pair = facet.strip_prefix('(').and_then(|facet| facet.strip_suffix(')'));
+            let (hp, f) = pair
+                .and_then(|pair| pair.split_once(','))
+                .ok_or(SurveyError::Corrupted("invalid signature"))?;
+            match (hp.parse(), f.parse()) {
+                (Ok(hp), Ok(f)) => Ok((hp, f)),
+                _ => Err(SurveyError::Corrupted("invalid signature")),
+            }
+        })
+        .collect()
+}
+
+/// Writes some counts separated by spaces.
+fn counts_text<I: IntoIterator<Item = usize>>(counts: I) -> String {
+    let counts: Vec<_> = counts.into_iter().map(|count| count.to_string()).collect();
+    counts.join(" ")
+}
+
+/// Reads counts written by [`counts_text`].
+fn parse_counts(text: &str) -> SurveyResult<Vec<usize>> {
+    text.split_whitespace()
+        .map(|count| count.parse().map_err(|_| SurveyError::Corrupted("invalid counts")))
+        .collect()
+}
+
+/// Returns the number of elements of each rank of a polytope.
+fn element_counts(abs: &Abstract) -> String {
+    counts_text(abs.iter().map(|elements| elements.len()))
+}
+
+/// A faceting stored in a survey database, along with where it came from.
+#[derive(Clone, Debug)]
+pub struct StoredResult {
+    /// The row of the faceting in the `results` table.
+    pub id: i64,
+
+    /// The run that found the faceting.
+    pub run: i64,
+
+    /// The faceting itself. Its polytope only has the name it was stored
+    /// with as metadata.
+    pub result: FacetingResult,
+
+    /// The fingerprint of the faceting, see [`fingerprint`].
+    pub fingerprint: u64,
+}
+
+/// A database to store the outputs of a faceting survey in. See the
+/// [module docs](self) for its tables.
+pub struct SurveyDb {
+    /// The connection to the database.
+    conn: Connection,
+}
+
+impl SurveyDb {
+    /// Opens a survey database at a path, creating it and its tables if
+    /// needed.
+    pub fn open<P: AsRef<Path>>(path: P) -> SurveyResult<Self> {
+        Self::new(Connection::open(path)?)
+    }
+
+    /// Opens a survey database that only lives in memory.
+    pub fn open_in_memory() -> SurveyResult<Self> {
+        Self::new(Connection::open_in_memory()?)
+    }
+
+    /// Creates the tables on a connection if they don't exist yet.
+    fn new(conn: Connection) -> SurveyResult<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    /// Returns the connection to the database, to run other queries on.
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// Adds a vertex arrangement to the database if it isn't there yet, and
+    /// returns its row either way.
+    pub fn insert_arrangement(&self, vertices: &[Point<f64>], meta: &PolytopeMeta) -> SurveyResult<i64> {
+        let hash = arrangement_hash(vertices) as i64;
+        let existing = self
+            .conn
+            .query_row("SELECT id FROM arrangements WHERE hash = ?1", params![hash], |row| row.get(0))
+            .optional()?;
+        if let Some(id) = existing {
+            return Ok(id);
+        }
+
+        self.conn.execute(
+            "INSERT INTO arrangements (hash, vertex_count, dim, name, meta) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                hash,
+                vertices.len() as i64,
+                vertices.first().map_or(0, |v| v.len()) as i64,
+                meta.name,
+                ron::to_string(meta).unwrap_or_default(),
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Adds a faceting run on an arrangement, and returns its row.
+    pub fn insert_run(&self, arrangement: i64, options: &FacetingOptions, stats: &FacetingStats) -> SurveyResult<i64> {
+        self.conn.execute(
+            "INSERT INTO runs (arrangement, min_edge_length, max_edge_length, noble, ridge_multiplicity, tolerance,
+                vertex_count, group_order, hyperplane_orbit_count, possible_facet_count, faceting_count, cancelled, stats)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            params![
+                arrangement,
+                options.edge_length.min(),
+                options.edge_length.max(),
+                options.max_facet_orbits().map(|noble| noble as i64),
+                options.ridge_multiplicity as i64,
+                options.geometry.tolerance,
+                stats.vertex_count as i64,
+                stats.group_order as i64,
+                stats.hyperplane_orbit_count as i64,
+                stats.possible_facet_count as i64,
+                stats.faceting_count as i64,
+                stats.cancelled,
+                stats.to_string(),
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Adds the hyperplane orbits and the possible facets of some tables to a
+    /// run. The possible facets are stored through their
+    /// [`FacetingTables::facet_summary`], so none of them are built.
+    pub fn insert_tables(&self, run: i64, tables: &FacetingTables) -> SurveyResult<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for hp in 0..tables.hyperplane_orbit_count() {
+            tx.execute(
+                "INSERT INTO hyperplane_orbits (run, orbit, hyperplane_count, vertex_count, depth) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    run,
+                    hp as i64,
+                    tables.hyperplane_count(hp) as i64,
+                    tables.hyperplane_vertices[hp].len() as i64,
+                    tables.depth(hp),
+                ],
+            )?;
+
+            for f in 0..tables.facet_count(hp) {
+                let summary = tables.facet_summary(hp, f);
+                tx.execute(
+                    "INSERT INTO facet_types (run, orbit, facet, element_counts, fingerprint, degenerate,
+                        vertex_count, edge_length_count, equilateral)
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                    params![
+                        run,
+                        hp as i64,
+                        f as i64,
+                        counts_text(summary.element_counts.iter().copied()),
+                        summary.fingerprint as i64,
+                        tables.is_degenerate(hp, f),
+                        summary.vertex_count() as i64,
+                        summary.edge_lengths.len() as i64,
+                        summary.is_equilateral(),
+                    ],
+                )?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Adds a faceting found by a run, and returns its row.
+    pub fn insert_result(&self, run: i64, result: &FacetingResult) -> SurveyResult<i64> {
+        let poly = &result.polytope;
+        let abs = &poly.abs;
+        let mut vertices = Vec::new();
+        for v in poly.vertices.iter() {
+            for c in v.iter() {
+                vertices.extend_from_slice(&c.to_le_bytes());
+            }
+        }
+        let subelements: Vec<Vec<Vec<usize>>> = abs
+            .iter()
+            .map(|elements| elements.iter().map(|el| el.subs.iter().copied().collect()).collect())
+            .collect();
+        let orientable = match abs.known_orientability() {
+            Some(orientable) => orientable,
+            None => abs.clone().orientable_mut(),
+        };
+        let subelements = ron::to_string(&subelements).map_err(|_| SurveyError::Corrupted("unserializable polytope"))?;
+
+        self.conn.execute(
+            "INSERT INTO results (run, name, signature, facet_counts, fingerprint, element_counts, euler_characteristic,
+                orientable, compound, dim, vertices, abstract)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            params![
+                run,
+                result.name,
+                signature(&result.facets),
+                counts_text(result.facet_counts.iter().copied()),
+                fingerprint(abs) as i64,
+                element_counts(abs),
+                abs.euler_characteristic() as i64,
+                orientable,
+                abs.is_compound(),
+                poly.vertices.first().map_or(0, |v| v.len()) as i64,
+                vertices,
+                subelements.into_bytes(),
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Adds every faceting found by a run in a single transaction, and returns
+    /// their rows.
+    pub fn insert_results(&self, run: i64, results: &[FacetingResult]) -> SurveyResult<Vec<i64>> {
+        let tx = self.conn.unchecked_transaction()?;
+        let ids = results
+            .iter()
+            .map(|result| self.insert_result(run, result))
+            .collect::<SurveyResult<_>>()?;
+        tx.commit()?;
+        Ok(ids)
+    }
+
+    /// Adds an arrangement, a run on it and every faceting it found, and
+    /// returns the row of the run.
+    pub fn insert_survey(
+        &self,
+        vertices: &[Point<f64>],
+        meta: &PolytopeMeta,
+        options: &FacetingOptions,
+        stats: &FacetingStats,
+        results: &[FacetingResult],
+    ) -> SurveyResult<i64> {
+        let arrangement = self.insert_arrangement(vertices, meta)?;
+        let run = self.insert_run(arrangement, options, stats)?;
+        self.insert_results(run, results)?;
+        Ok(run)
+    }
+
+    /// Reads back a faceting stored with [`Self::insert_result`].
+    pub fn load_result(&self, id: i64) -> SurveyResult<StoredResult> {
+        type Row = (i64, Option<String>, String, String, i64, i64, Vec<u8>, Vec<u8>);
+        let (run, name, signature, facet_counts, fingerprint, dim, vertices, subelements): Row = self.conn.query_row(
+            "SELECT run, name, signature, facet_counts, fingerprint, dim, vertices, abstract FROM results WHERE id = ?1",
+            params![id],
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                    row.get(5)?,
+                    row.get(6)?,
+                    row.get(7)?,
+                ))
+            },
+        )?;
+
+        let subelements = String::from_utf8(subelements).map_err(|_| SurveyError::Corrupted("invalid polytope"))?;
+        let subelements: Vec<Vec<Vec<usize>>> =
+            ron::from_str(&subelements).map_err(|_| SurveyError::Corrupted("invalid polytope"))?;
+        let abs = Abstract::from_subelements(subelements)?;
+
+        let dim = dim as usize;
+        if vertices.len() != 8 * dim * abs.vertex_count() {
+            return Err(SurveyError::Corrupted("wrong number of coordinates"));
+        }
+        let coordinates: Vec<f64> = vertices
+            .chunks_exact(8)
+            .map(|bytes| {
+                let mut bits = [0; 8];
+                bits.copy_from_slice(bytes);
+                f64::from_le_bytes(bits)
+            })
+            .collect();
+        let vertices = (0..abs.vertex_count())
+            .map(|v| Point::from_column_slice(&coordinates[v * dim..(v + 1) * dim]))
+            .collect();
+
+        let mut polytope = Concrete::new(vertices, abs);
+        if let Some(name) = &name {
+            polytope.meta = PolytopeMeta::named(name.clone());
+        }
+        Ok(StoredResult {
+            id,
+            run,
+            result: FacetingResult {
+                polytope,
+                facets: parse_signature(&signature)?,
+                facet_counts: parse_counts(&facet_counts)?,
+                name,
+            },
+            fingerprint: fingerprint as u64,
+        })
+    }
+
+    /// Returns the rows of the facetings with some facet orbits, in the order
+    /// they were added.
+    pub fn results_by_signature(&self, facets: &[(usize, usize)]) -> SurveyResult<Vec<i64>> {
+        let mut statement = self.conn.prepare("SELECT id FROM results WHERE signature = ?1 ORDER BY id")?;
+        let ids = statement
+            .query_map(params![signature(facets)], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        Ok(ids)
+    }
+
+    /// Returns the fingerprints shared by facetings of more than one vertex
+    /// arrangement, each with the rows of its facetings in the order they
+    /// were added. These are the candidates for the same faceting showing up
+    /// in different surveys.
+    pub fn shared_fingerprints(&self) -> SurveyResult<Vec<(u64, Vec<i64>)>> {
+        let mut statement = self.conn.prepare(
+            "SELECT results.fingerprint, results.id FROM results
+            JOIN runs ON results.run = runs.id
+            WHERE results.fingerprint IN (
+                SELECT results.fingerprint FROM results
+                JOIN runs ON results.run = runs.id
+                GROUP BY results.fingerprint
+                HAVING COUNT(DISTINCT runs.arrangement) > 1
+            )
+            ORDER BY results.fingerprint, results.id",
+        )?;
+        let rows = statement
+            .query_map([], |row| Ok((row.get::<_, i64>(0)? as u64, row.get(1)?)))?
+            .collect::<Result<Vec<(u64, i64)>, _>>()?;
+
+        let mut shared: Vec<(u64, Vec<i64>)> = Vec::new();
+        for (fingerprint, id) in rows {
+            match shared.last_mut() {
+                Some((last, ids)) if *last == fingerprint => ids.push(id),
+                _ => shared.push((fingerprint, vec![id])),
+            }
+        }
+        Ok(shared)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        conc::{
+            faceting::{GroupEnum, PartialFacetingOutput, Stage, SUBFACETINGS_BUILT},
+            ConcretePolytope,
+        },
+        file::FromFile,
+    };
+
+    /// Facets a polytope under its full symmetry group, and stores the results.
+    fn survey(db: &SurveyDb, mut poly: Concrete) -> (i64, Vec<FacetingResult>, Vec<i64>) {
+        let vertices = poly.vertices.to_vec();
+        let options = FacetingOptions::default();
+        let tables = poly.faceting_tables(vertices.clone(), GroupEnum::Chiral(false), &options).unwrap();
+        let (facetings, stats) = poly
+            .try_faceting_with_stats(vertices.clone(), GroupEnum::Chiral(false), &options)
+            .unwrap();
+
+        let run = db.insert_survey(&vertices, &poly.meta, &options, &stats, &[]).unwrap();
+        db.insert_tables(run, &tables).unwrap();
+        let ids = db.insert_results(run, &facetings).unwrap();
+        (run, facetings, ids)
+    }
+
+    /// Checks that the identity is an isomorphism between two polytopes whose
+    /// subelements may be listed in different orders.
+    fn assert_identity_isomorphism(p: &Abstract, q: &Abstract) {
+        let mut p = p.clone();
+        p.element_sort();
+        assert!(p.iter().eq(q.iter()));
+    }
+
+    /// Stored facetings are read back as the same polytopes, and the canned
+    /// queries find them.
+    #[test]
+    fn round_trip() {
+        let db = SurveyDb::open_in_memory().unwrap();
+        let (run, facetings, ids) = survey(&db, Concrete::hypercube(4));
+        assert_eq!(ids.len(), facetings.len());
+
+        for (id, faceting) in ids.iter().zip(&facetings) {
+            let stored = db.load_result(*id).unwrap();
+            assert_eq!(stored.run, run);
+            assert_eq!(stored.result.facets, faceting.facets);
+            assert_eq!(stored.result.facet_counts, faceting.facet_counts);
+            assert_eq!(stored.result.name, faceting.name);
+            assert_eq!(stored.result.polytope.vertices.to_vec(), faceting.polytope.vertices.to_vec());
+            assert_identity_isomorphism(&faceting.polytope.abs, &stored.result.polytope.abs);
+            assert_eq!(stored.fingerprint, fingerprint(&faceting.polytope.abs));
+
+            assert!(db.results_by_signature(&faceting.facets).unwrap().contains(id));
+        }
+
+        let (hyperplane_orbits, facet_types): (i64, i64) = db
+            .connection()
+            .query_row(
+                "SELECT (SELECT COUNT(*) FROM hyperplane_orbits), (SELECT COUNT(*) FROM facet_types)",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert!(hyperplane_orbits > 0);
+        assert!(facet_types >= hyperplane_orbits);
+        assert!(db.results_by_signature(&[(100, 0)]).unwrap().is_empty());
+    }
+
+    /// The cube shows up as a faceting of itself and of a scaled copy of
+    /// itself, which are different arrangements with the same facetings.
+    #[test]
+    fn shared_fingerprints() {
+        let db = SurveyDb::open_in_memory().unwrap();
+        let cube = Concrete::hypercube(4);
+        let mut scaled = cube.clone();
+        scaled.scale(2.0);
+
+        let (_, first, first_ids) = survey(&db, cube.clone());
+        assert!(db.shared_fingerprints().unwrap().is_empty());
+
+        // The same arrangement isn't added twice.
+        let (_, _, again_ids) = survey(&db, cube);
+        assert!(db.shared_fingerprints().unwrap().is_empty());
+        let arrangements: i64 = db
+            .connection()
+            .query_row("SELECT COUNT(*) FROM arrangements", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(arrangements, 1);
+
+        let (_, _, scaled_ids) = survey(&db, scaled);
+        let shared = db.shared_fingerprints().unwrap();
+        assert_eq!(shared.iter().map(|(_, ids)| ids.len()).sum::<usize>(), 3 * first.len());
+        for (fingerprint, ids) in shared {
+            for id in ids {
+                assert_eq!(db.load_result(id).unwrap().fingerprint, fingerprint);
+                assert!(first_ids.contains(&id) || again_ids.contains(&id) || scaled_ids.contains(&id));
+            }
+        }
+    }
+
+    /// The facet types of the 24-cell are stored without building any of
+    /// them, with the same invariants as the facets built from the tables.
+    #[test]
+    fn facet_type_summaries() {
+        let mut poly = Concrete::from_off(include_str!("../../../tests/fixtures/ico.off")).unwrap();
+        let vertices = poly.vertices.to_vec();
+        let options = FacetingOptions {
+            stop_after: Stage::FacetTypes,
+            ..Default::default()
+        };
+        let (output, stats) = poly
+            .try_faceting_staged(vertices.clone(), GroupEnum::Chiral(false), &options)
+            .unwrap();
+        let tables = match output {
+            PartialFacetingOutput::FacetTypes(mut tables) if tables.len() == 1 => tables.remove(0),
+            output => panic!("unexpected output: {:?}", output),
+        };
+
+        let db = SurveyDb::open_in_memory().unwrap();
+        let run = db.insert_survey(&vertices, &poly.meta, &options, &stats, &[]).unwrap();
+        SUBFACETINGS_BUILT.with(|count| count.set(0));
+        db.insert_tables(run, &tables).unwrap();
+        assert_eq!(SUBFACETINGS_BUILT.with(|count| count.get()), 0);
+
+        type Row = (i64, i64, String, i64, i64, i64, bool);
+        let mut statement = db
+            .connection()
+            .prepare(
+                "SELECT orbit, facet, element_counts, fingerprint, vertex_count, edge_length_count, equilateral
+                FROM facet_types WHERE run = ?1 ORDER BY orbit, facet",
+            )
+            .unwrap();
+        let rows = statement
+            .query_map(params![run], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?, row.get(6)?))
+            })
+            .unwrap()
+            .collect::<Result<Vec<Row>, _>>()
+            .unwrap();
+        assert_eq!(rows.len(), stats.possible_facet_count);
+        assert!(!rows.is_empty());
+
+        for (hp, f, counts, hash, vertex_count, edge_length_count, equilateral) in rows {
+            let facet = tables.subfaceting(hp as usize, f as usize);
+            assert_eq!(counts, element_counts(&facet.abs));
+            assert_eq!(hash as u64, fingerprint(&facet.abs));
+            assert_eq!(vertex_count as usize, facet.vertex_count());
+            assert_eq!(edge_length_count as usize, facet.edge_lengths().len());
+            assert_eq!(equilateral, facet.is_equilateral());
+        }
+        assert_eq!(SUBFACETINGS_BUILT.with(|count| count.get()), stats.possible_facet_count);
+    }
+}
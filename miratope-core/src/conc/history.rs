@@ -0,0 +1,365 @@
+//! Declares the [`OperationLog`] type, which records the operations that
+//! change a [`Concrete`] polytope in place, so that they can be undone or
+//! replayed on another polytope.
+
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use super::{meta::PolytopeMeta, Concrete, ConcretePolytope};
+use crate::{
+    geometry::{Hypersphere, Matrix, Point, Subspace},
+    Polytope,
+};
+
+/// The property of the metadata of a polytope the log of its operations is
+/// stored in when it's saved to a file.
+pub const HISTORY_PROPERTY: &str = "history";
+
+/// An operation that changes a polytope in place, which can be recorded in an
+/// [`OperationLog`].
+pub trait LoggedOp {
+    /// Performs the operation on a polytope, recording it in its log if it
+    /// has one.
+    fn perform(&self, poly: &mut Concrete) -> Result<(), HistoryError>;
+
+    /// Describes the operation in a few words, such as "scale by 2".
+    fn describe(&self) -> String;
+
+    /// Returns the operation that undoes this one, or `None` if it can't be
+    /// undone.
+    fn inverse(&self) -> Option<Operation>;
+}
+
+/// An operation on a polytope that can be recorded, along with everything
+/// needed to perform it again.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Operation {
+    /// Scales the polytope by a factor, see [`ConcretePolytope::scale`].
+    Scale(f64),
+
+    /// Moves the polytope by a vector. Recentering the polytope moves it by
+    /// the opposite of its center, see [`ConcretePolytope::recenter_with`].
+    Translate(Point<f64>),
+
+    /// Applies a linear transformation to the polytope, see
+    /// [`ConcretePolytope::apply`].
+    Transform(Matrix<f64>),
+
+    /// Replaces the polytope by its dual with respect to a hypersphere, see
+    /// [`ConcretePolytope::try_dual_mut_with`].
+    Dual {
+        /// The center of the hypersphere.
+        center: Point<f64>,
+
+        /// The squared radius of the hypersphere.
+        squared_radius: f64,
+    },
+
+    /// Replaces the polytope by its Petrial, see [`Polytope::petrial_mut`].
+    Petrial,
+
+    /// Projects the polytope onto a subspace, see
+    /// [`ConcretePolytope::flatten_into`]. The coordinates of the polytope
+    /// are lost, so this can't be undone.
+    Flatten {
+        /// An orthogonal basis for the subspace.
+        basis: Vec<Point<f64>>,
+
+        /// A point on the subspace.
+        offset: Point<f64>,
+    },
+}
+
+impl Operation {
+    /// Returns the operation that takes the dual of a polytope with respect to
+    /// a hypersphere.
+    pub fn dual(sphere: &Hypersphere<f64>) -> Self {
+        Self::Dual {
+            center: sphere.center.clone(),
+            squared_radius: sphere.squared_radius,
+        }
+    }
+
+    /// Returns the operation that projects a polytope onto a subspace.
+    pub fn flatten(subspace: &Subspace<f64>) -> Self {
+        Self::Flatten {
+            basis: subspace.basis.clone(),
+            offset: subspace.offset.clone(),
+        }
+    }
+}
+
+impl LoggedOp for Operation {
+    fn perform(&self, poly: &mut Concrete) -> Result<(), HistoryError> {
+        match self {
+            Self::Scale(k) => poly.scale(*k),
+            Self::Translate(v) => poly.recenter_with(&-v),
+            Self::Transform(m) => {
+                // Applying a transformation consumes the polytope.
+                let taken = std::mem::replace(poly, Concrete::nullitope());
+                *poly = taken.apply(m);
+            }
+            Self::Dual { center, squared_radius } => {
+                let sphere = Hypersphere {
+                    center: center.clone(),
+                    squared_radius: *squared_radius,
+                };
+                poly.try_dual_mut_with(&sphere).map_err(|_| HistoryError::Failed(self.clone()))?;
+            }
+            Self::Petrial => {
+                if !poly.petrial_mut() {
+                    return Err(HistoryError::Failed(self.clone()));
+                }
+            }
+            Self::Flatten { basis, offset } => poly.flatten_into(&Subspace {
+                basis: basis.clone(),
+                offset: offset.clone(),
+            }),
+        }
+
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Self::Scale(k) => format!("scale by {}", k),
+            Self::Translate(v) => format!("translate by {:?}", v.as_slice()),
+            Self::Transform(_) => "apply a linear transformation".to_string(),
+            Self::Dual { center, squared_radius } => format!(
+                "dual with respect to the hypersphere at {:?} with squared radius {}",
+                center.as_slice(),
+                squared_radius
+            ),
+            Self::Petrial => "Petrial".to_string(),
+            Self::Flatten { basis, .. } => format!("flatten into {} dimensions", basis.len()),
+        }
+    }
+
+    fn inverse(&self) -> Option<Operation> {
+        match self {
+            Self::Scale(k) => (*k != 0.0).then(|| Self::Scale(1.0 / k)),
+            Self::Translate(v) => Some(Self::Translate(-v)),
+            Self::Transform(m) => m.clone().try_inverse().map(Self::Transform),
+
+            // Reciprocating twice with respect to the same hypersphere, or
+            // taking the Petrial twice, gives back the original polytope.
+            Self::Dual { .. } | Self::Petrial => Some(self.clone()),
+            Self::Flatten { .. } => None,
+        }
+    }
+}
+
+impl Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.describe())
+    }
+}
+
+/// An operation recorded in an [`OperationLog`], along with the metadata the
+/// polytope had before it, which is restored when the operation is undone.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LogEntry {
+    /// The operation performed.
+    pub operation: Operation,
+
+    /// The metadata of the polytope before the operation.
+    pub meta: PolytopeMeta,
+}
+
+/// The operations performed in place on a polytope, in order. A polytope only
+/// records them if it has a log attached, see [`Concrete::with_history`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct OperationLog {
+    /// The entries of the log, from the oldest to the newest.
+    pub entries: Vec<LogEntry>,
+}
+
+impl OperationLog {
+    /// Initializes a new empty log.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns the number of operations in the log.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether the log has no operations.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns an iterator over the operations in the log, from the oldest to
+    /// the newest.
+    pub fn operations(&self) -> impl Iterator<Item = &Operation> {
+        self.entries.iter().map(|entry| &entry.operation)
+    }
+
+    /// Returns whether every operation in the log can be undone.
+    pub fn is_invertible(&self) -> bool {
+        self.operations().all(|op| op.inverse().is_some())
+    }
+
+    /// Serializes the log in RON.
+    pub fn to_ron(&self) -> String {
+        ron::to_string(self).unwrap_or_default()
+    }
+
+    /// Reads a log serialized in RON, or returns `None` if it's invalid.
+    pub fn from_ron(src: &str) -> Option<Self> {
+        ron::from_str(src).ok()
+    }
+}
+
+/// An error while undoing or replaying the operations of a log.
+#[derive(Clone, Debug, PartialEq)]
+pub enum HistoryError {
+    /// The polytope has no log attached.
+    NoHistory,
+
+    /// There are no operations left to undo.
+    NothingToUndo,
+
+    /// The last operation can't be undone.
+    NotInvertible(Operation),
+
+    /// The operation couldn't be performed on the polytope, as when taking
+    /// the dual with respect to a hypersphere centered on a facet.
+    Failed(Operation),
+}
+
+impl Display for HistoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoHistory => write!(f, "the polytope isn't recording its operations"),
+            Self::NothingToUndo => write!(f, "there are no operations to undo"),
+            Self::NotInvertible(op) => write!(f, "the operation \"{}\" can't be undone", op),
+            Self::Failed(op) => write!(f, "the operation \"{}\" couldn't be performed", op),
+        }
+    }
+}
+
+impl std::error::Error for HistoryError {}
+
+impl Concrete {
+    /// Attaches an empty log to the polytope, so that the operations that
+    /// change it in place are recorded from then on.
+    pub fn with_history(mut self) -> Self {
+        self.history = Some(OperationLog::new());
+        self
+    }
+
+    /// Records an operation performed on the polytope, if it has a log
+    /// attached. The metadata is the one the polytope had before it, if the
+    /// operation changed it.
+    pub(crate) fn record(&mut self, operation: Operation, meta: Option<PolytopeMeta>) {
+        let current = &self.meta;
+        if let Some(history) = &mut self.history {
+            history.entries.push(LogEntry {
+                operation,
+                meta: meta.unwrap_or_else(|| current.clone()),
+            });
+        }
+    }
+
+    /// Returns the metadata of the polytope with its log, if it has a
+    /// nonempty one, stored as the [`HISTORY_PROPERTY`] property. This is
+    /// what's saved to files.
+    pub fn meta_with_history(&self) -> PolytopeMeta {
+        let mut meta = self.meta.clone();
+        if let Some(history) = self.history.as_ref().filter(|history| !history.is_empty()) {
+            meta.properties.insert(HISTORY_PROPERTY.to_string(), history.to_ron());
+        }
+        meta
+    }
+
+    /// Attaches the log stored in the [`HISTORY_PROPERTY`] property of the
+    /// metadata to the polytope, if it's valid, and removes the property.
+    pub fn take_history_from_meta(&mut self) {
+        let history = self.meta.properties.get(HISTORY_PROPERTY).and_then(|src| OperationLog::from_ron(src));
+        if history.is_some() {
+            self.meta.properties.remove(HISTORY_PROPERTY);
+            self.history = history;
+        }
+    }
+
+    /// Undoes the last operation recorded in the log of the polytope, and
+    /// returns it. The metadata the polytope had before it is restored. If
+    /// the operation can't be undone, the polytope is left unchanged.
+    ///
+    /// The polytope is given back up to the order of its elements, as the
+    /// Petrial of the Petrial of a polytope can have its faces in another
+    /// order.
+    pub fn undo(&mut self) -> Result<Operation, HistoryError> {
+        let history = self.history.as_ref().ok_or(HistoryError::NoHistory)?;
+        let entry = history.entries.last().ok_or(HistoryError::NothingToUndo)?;
+        let inverse = entry
+            .operation
+            .inverse()
+            .ok_or_else(|| HistoryError::NotInvertible(entry.operation.clone()))?;
+
+        // The inverse isn't recorded.
+        let mut history = self.history.take().unwrap();
+        let result = inverse.perform(self);
+        let entry = if result.is_ok() { history.entries.pop() } else { None };
+        self.history = Some(history);
+        result?;
+
+        let entry = entry.unwrap();
+        self.meta = entry.meta;
+        Ok(entry.operation)
+    }
+
+    /// Performs the operations of a log on a copy of a polytope, which gets
+    /// the log attached. Replaying the log of a polytope on the polytope it
+    /// started from gives it back.
+    pub fn replay(log: &OperationLog, base: &Concrete) -> Result<Concrete, HistoryError> {
+        let mut poly = base.clone().with_history();
+        for op in log.operations() {
+            op.perform(&mut poly)?;
+        }
+        Ok(poly)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{abs::Ranked, float::Float};
+
+    /// Checks that two polytopes have the same vertices, up to a tolerance.
+    fn assert_same_vertices(p: &Concrete, q: &Concrete) {
+        assert_eq!(p.vertices.len(), q.vertices.len());
+        for (v, w) in p.vertices.iter().zip(q.vertices.iter()) {
+            assert!((v - w).norm() < f64::EPS, "{:?} != {:?}", v, w);
+        }
+    }
+
+    /// Every invertible operation is undone by its inverse.
+    #[test]
+    fn inverses() {
+        let cube = Concrete::hypercube(4);
+        let sphere = Hypersphere::with_squared_radius(Point::from_vec(vec![0.1, 0.0, 0.0]), 2.0);
+        let ops = [
+            Operation::Scale(3.0),
+            Operation::Translate(Point::from_vec(vec![1.0, 2.0, 3.0])),
+            Operation::Transform(Matrix::from_vec(3, 3, vec![1.0, 2.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 2.0])),
+            Operation::dual(&sphere),
+            Operation::Petrial,
+        ];
+
+        for op in &ops {
+            let mut poly = cube.clone();
+            op.perform(&mut poly).unwrap();
+            op.inverse().unwrap().perform(&mut poly).unwrap();
+            assert_same_vertices(&poly, &cube);
+            assert!(poly.abs.el_count_iter().eq(cube.abs.el_count_iter()));
+        }
+
+        assert_eq!(Operation::Scale(0.0).inverse(), None);
+        assert_eq!(Operation::Transform(Matrix::zeros(3, 3)).inverse(), None);
+        assert_eq!(Operation::flatten(&Subspace::from_points(cube.vertices.iter())).inverse(), None);
+    }
+}
@@ -0,0 +1,438 @@
+//! Finds the pairs of facets of a polytope that cross each other.
+//!
+//! Two facets cross when the convex hulls of their parts on the intersection
+//! of their hyperplanes overlap somewhere other than in the convex hull of an
+//! element they share. The parts on the intersection are found by cutting
+//! the segments between the vertices of each facet by the hyperplane of the
+//! other, and whether they overlap is a small linear program, which is solved
+//! in the coordinates of the intersection.
+//!
+//! The overlap is probed along both directions of each axis of the
+//! intersection. For polyhedra the intersection is a line, so this finds both
+//! ends of the segment along which the facets cross.
+
+use super::{Concrete, ConcretePolytope};
+use crate::{
+    abs::Ranked,
+    geometry::{Hyperplane, Point, Subspace},
+};
+
+use vec_like::*;
+
+/// The smallest pivot used when solving the linear programs.
+const PIVOT_EPS: f64 = 1e-12;
+
+/// Two facets of a polytope that cross each other.
+#[derive(Clone, Debug)]
+pub struct FacetCrossing {
+    /// The indices of both facets, the smaller one first.
+    pub facets: (usize, usize),
+
+    /// The intersection of the hyperplanes of both facets, which contains the
+    /// region where they cross.
+    pub subspace: Subspace<f64>,
+
+    /// The extreme points of the overlap of both facets along the axes of
+    /// [`Self::subspace`], without repetitions.
+    pub points: Vec<Point<f64>>,
+}
+
+/// A facet together with the data used to compare it to the others.
+struct FacetData {
+    /// The indices of the vertices of the facet, sorted.
+    vertices: Vec<usize>,
+
+    /// The indices of the elements of the facet of each rank below it,
+    /// sorted.
+    elements: Vec<Vec<usize>>,
+
+    /// The hyperplane of the facet.
+    hyperplane: Hyperplane<f64>,
+
+    /// The center of a sphere containing the facet.
+    center: Point<f64>,
+
+    /// The radius of a sphere containing the facet.
+    radius: f64,
+}
+
+/// A linear program in equality form, solved by the simplex method with
+/// Bland's rule.
+struct Tableau {
+    /// The rows of the tableau, with the right hand side last. The columns of
+    /// the variables come first, followed by those of the artificial
+    /// variables.
+    rows: Vec<Vec<f64>>,
+
+    /// The variable in the basis of each row.
+    basis: Vec<usize>,
+
+    /// The number of variables, not counting the artificial ones.
+    var_count: usize,
+}
+
+impl Tableau {
+    /// Sets up the constraints `a · x = b` and `x ≥ 0`, and finds a feasible
+    /// solution for them. Returns `None` if the residual of the best solution
+    /// is bigger than the tolerance.
+    fn feasible(a: Vec<Vec<f64>>, b: Vec<f64>, tolerance: f64) -> Option<Self> {
+        let var_count = a.first().map_or(0, Vec::len);
+        let row_count = a.len();
+        let rows = a
+            .into_iter()
+            .zip(b)
+            .enumerate()
+            .map(|(i, (mut row, b))| {
+                // The right hand sides must be non-negative.
+                let sign = if b < 0.0 { -1.0 } else { 1.0 };
+                row.iter_mut().for_each(|x| *x *= sign);
+                row.extend((0..row_count).map(|j| if i == j { 1.0 } else { 0.0 }));
+                row.push(b * sign);
+                row
+            })
+            .collect();
+        let mut tableau = Self {
+            rows,
+            basis: (var_count..var_count + row_count).collect(),
+            var_count,
+        };
+
+        // Minimizes the sum of the artificial variables.
+        let mut objective = vec![0.0; var_count + row_count + 1];
+        for row in &tableau.rows {
+            for (j, x) in row[..var_count].iter().enumerate() {
+                objective[j] += x;
+            }
+            objective[var_count + row_count] += row[var_count + row_count];
+        }
+        tableau.optimize(&mut objective, var_count);
+        if objective[var_count + row_count] > tolerance {
+            return None;
+        }
+
+        // Moves the artificial variables left out of the basis where possible.
+        for i in 0..row_count {
+            if tableau.basis[i] >= var_count {
+                if let Some(j) = (0..var_count).find(|&j| tableau.rows[i][j].abs() > PIVOT_EPS) {
+                    tableau.pivot(&mut objective, i, j);
+                }
+            }
+        }
+
+        Some(tableau)
+    }
+
+    /// Maximizes a linear function of the variables, starting from a feasible
+    /// solution, and returns the values of the variables at the maximum.
+    fn maximize(mut self, costs: &[f64]) -> Vec<f64> {
+        let width = self.rows[0].len();
+        let mut objective = vec![0.0; width];
+        objective[..self.var_count].copy_from_slice(costs);
+        for (row, &var) in self.rows.iter().zip(&self.basis) {
+            if let Some(&cost) = costs.get(var) {
+                for (o, x) in objective.iter_mut().zip(row) {
+                    *o -= cost * x;
+                }
+            }
+        }
+
+        self.optimize(&mut objective, self.var_count);
+        self.solution()
+    }
+
+    /// Returns the values of the variables in the current solution.
+    fn solution(&self) -> Vec<f64> {
+        let mut values = vec![0.0; self.var_count];
+        for (row, &var) in self.rows.iter().zip(&self.basis) {
+            if var < self.var_count {
+                values[var] = *row.last().unwrap();
+            }
+        }
+        values
+    }
+
+    /// Pivots on an entry of the tableau.
+    fn pivot(&mut self, objective: &mut [f64], row: usize, col: usize) {
+        let pivot = self.rows[row][col];
+        self.rows[row].iter_mut().for_each(|x| *x /= pivot);
+        let pivot_row = self.rows[row].clone();
+
+        for r in self.rows.iter_mut().enumerate().filter(|&(i, _)| i != row).map(|(_, r)| r) {
+            let factor = r[col];
+            if factor != 0.0 {
+                r.iter_mut().zip(&pivot_row).for_each(|(x, p)| *x -= factor * p);
+            }
+        }
+
+        let factor = objective[col];
+        objective.iter_mut().zip(&pivot_row).for_each(|(x, p)| *x -= factor * p);
+        self.basis[row] = col;
+    }
+
+    /// Pivots until no variable among the first ones can improve the
+    /// objective, whose entries are the reduced costs of the variables and
+    /// the negated value of the objective last.
+    fn optimize(&mut self, objective: &mut [f64], entering: usize) {
+        while let Some(col) = (0..entering).find(|&j| objective[j] > PIVOT_EPS) {
+            let row = self
+                .rows
+                .iter()
+                .enumerate()
+                .filter(|(_, r)| r[col] > PIVOT_EPS)
+                .map(|(i, r)| (r.last().unwrap() / r[col], self.basis[i], i))
+                .min_by(|x, y| x.partial_cmp(y).unwrap());
+
+            match row {
+                Some((_, _, row)) => self.pivot(objective, row, col),
+                // The programs solved here are always bounded.
+                None => return,
+            }
+        }
+    }
+}
+
+/// Returns the points where the segments between some points cross a
+/// hyperplane, together with the points on it.
+fn section(points: &[&Point<f64>], hyperplane: &Hyperplane<f64>, tolerance: f64) -> Vec<Point<f64>> {
+    let distances: Vec<_> = points.iter().map(|p| hyperplane.distance(p)).collect();
+    let mut section = Vec::new();
+
+    for (i, (&p, &d)) in points.iter().zip(&distances).enumerate() {
+        if d.abs() <= tolerance {
+            section.push(p.clone());
+        } else if d > 0.0 {
+            for (&q, &e) in points.iter().zip(&distances).skip(i + 1).chain(points.iter().zip(&distances).take(i)) {
+                if e < -tolerance {
+                    section.push(p + (q - p) * (d / (d - e)));
+                }
+            }
+        }
+    }
+
+    section
+}
+
+/// Returns the extreme points of the overlap of the convex hulls of two sets
+/// of points along the given directions, or `None` if the hulls don't
+/// overlap. If no directions are given, a single point of the overlap is
+/// returned.
+fn overlap(x: &[Point<f64>], y: &[Point<f64>], directions: &[Point<f64>], tolerance: f64) -> Option<Vec<Point<f64>>> {
+    let dim = x[0].len();
+
+    // The weights of the points of both sets must add up to one each, and
+    // give the same point.
+    let mut a: Vec<Vec<f64>> = (0..dim)
+        .map(|m| x.iter().map(|p| p[m]).chain(y.iter().map(|q| -q[m])).collect())
+        .collect();
+    a.push(x.iter().map(|_| 1.0).chain(y.iter().map(|_| 0.0)).collect());
+    a.push(x.iter().map(|_| 0.0).chain(y.iter().map(|_| 1.0)).collect());
+    let mut b = vec![0.0; dim];
+    b.extend([1.0, 1.0]);
+
+    let tableau = Tableau::feasible(a, b, tolerance)?;
+    let point = |weights: Vec<f64>| -> Point<f64> {
+        x.iter().zip(weights).fold(Point::zeros(dim), |sum, (p, w)| sum + p * w)
+    };
+
+    if directions.is_empty() {
+        return Some(vec![point(tableau.solution())]);
+    }
+
+    let mut points: Vec<Point<f64>> = Vec::new();
+    for direction in directions {
+        let costs: Vec<_> = x.iter().map(|p| p.dot(direction)).chain(y.iter().map(|_| 0.0)).collect();
+        let tableau = Tableau {
+            rows: tableau.rows.clone(),
+            basis: tableau.basis.clone(),
+            var_count: tableau.var_count,
+        };
+        let p = point(tableau.maximize(&costs));
+        if points.iter().all(|q| (q - &p).norm() > tolerance) {
+            points.push(p);
+        }
+    }
+
+    Some(points)
+}
+
+/// Returns whether a point is in the convex hull of some others.
+fn in_hull(point: &Point<f64>, points: &[Point<f64>], tolerance: f64) -> bool {
+    if points.is_empty() {
+        return false;
+    }
+
+    let mut a: Vec<Vec<f64>> = (0..point.len()).map(|m| points.iter().map(|p| p[m]).collect()).collect();
+    a.push(vec![1.0; points.len()]);
+    let mut b: Vec<_> = point.iter().copied().collect();
+    b.push(1.0);
+
+    Tableau::feasible(a, b, tolerance).is_some()
+}
+
+impl Concrete {
+    /// Finds the pairs of facets that cross each other, as described in the
+    /// [module docs](self). Facets whose vertices don't span a hyperplane are
+    /// skipped, as are pairs of facets in parallel hyperplanes.
+    ///
+    /// Pairs of facets whose bounding spheres are disjoint are skipped before
+    /// anything else, sweeping through the facets by the first coordinate of
+    /// their centers, so that most pairs are never compared on big polytopes.
+    pub fn facet_intersections(&self, tolerance: f64) -> Vec<FacetCrossing> {
+        let rank = self.rank();
+        if rank < 3 || self.dim_or() + 1 != rank {
+            return Vec::new();
+        }
+
+        let facets: Vec<Option<FacetData>> = (0..self.el_count(rank - 1))
+            .map(|idx| {
+                let mut vertices = self.abs.element_vertices(rank - 1, idx)?;
+                vertices.sort_unstable();
+                let points: Vec<_> = vertices.iter().map(|&v| &self.vertices[v]).collect();
+                let subspace = Subspace::from_points_within(points.iter().copied(), tolerance);
+                let hyperplane = Hyperplane::from_subspace(subspace)?;
+                let center = points.iter().fold(Point::zeros(self.dim_or()), |sum, &p| sum + p) / points.len() as f64;
+                let radius = points.iter().map(|&p| (p - &center).norm()).fold(0.0, f64::max);
+
+                // Gets the subelements of the subelements, down to the vertices.
+                let mut elements = vec![Vec::new(); rank - 1];
+                elements.push(vec![idx]);
+                for r in (1..rank).rev() {
+                    let mut subs: Vec<_> = elements[r].iter().flat_map(|&el| self.abs[(r, el)].subs.iter().copied()).collect();
+                    subs.sort_unstable();
+                    subs.dedup();
+                    elements[r - 1] = subs;
+                }
+                elements.pop();
+
+                Some(FacetData {
+                    vertices,
+                    elements,
+                    hyperplane,
+                    center,
+                    radius,
+                })
+            })
+            .collect();
+
+        // Sweeps through the facets by the lowest first coordinate of their
+        // bounding spheres.
+        let mut order: Vec<_> = (0..facets.len()).filter(|&idx| facets[idx].is_some()).collect();
+        let start = |idx: usize| {
+            let facet = facets[idx].as_ref().unwrap();
+            facet.center[0] - facet.radius
+        };
+        order.sort_by(|&i, &j| start(i).partial_cmp(&start(j)).unwrap());
+
+        let mut crossings = Vec::new();
+        for (n, &i) in order.iter().enumerate() {
+            let f = facets[i].as_ref().unwrap();
+            let end = f.center[0] + f.radius + tolerance;
+
+            for &j in order[n + 1..].iter().take_while(|&&j| start(j) <= end) {
+                let g = facets[j].as_ref().unwrap();
+                if (&f.center - &g.center).norm() > f.radius + g.radius + tolerance {
+                    continue;
+                }
+
+                let (i, j) = if i < j { (i, j) } else { (j, i) };
+                let (f, g) = (facets[i].as_ref().unwrap(), facets[j].as_ref().unwrap());
+                if let Some(crossing) = self.facet_crossing(i, f, j, g, tolerance) {
+                    crossings.push(crossing);
+                }
+            }
+        }
+
+        crossings.sort_by_key(|crossing| crossing.facets);
+        crossings
+    }
+
+    /// Checks whether two facets cross each other.
+    fn facet_crossing(&self, i: usize, f: &FacetData, j: usize, g: &FacetData, tolerance: f64) -> Option<FacetCrossing> {
+        let subspace = f.hyperplane.subspace.intersect_hyperplane(&g.hyperplane)?;
+        let points = |facet: &FacetData| -> Vec<&Point<f64>> { facet.vertices.iter().map(|&v| &self.vertices[v]).collect() };
+
+        let x: Vec<_> = section(&points(f), &g.hyperplane, tolerance).iter().map(|p| subspace.flatten(p)).collect();
+        let y: Vec<_> = section(&points(g), &f.hyperplane, tolerance).iter().map(|p| subspace.flatten(p)).collect();
+        if x.is_empty() || y.is_empty() {
+            return None;
+        }
+
+        let rank = subspace.rank();
+        let directions: Vec<Point<f64>> = (0..rank)
+            .flat_map(|m| {
+                let mut e = Point::zeros(rank);
+                e[m] = 1.0;
+                vec![e.clone(), -e]
+            })
+            .collect();
+        let overlap = overlap(&x, &y, &directions, tolerance)?;
+
+        // The facets don't cross if they only overlap on an element they
+        // share.
+        for (r, (f_elements, g_elements)) in f.elements.iter().zip(&g.elements).enumerate().skip(1) {
+            for &idx in f_elements.iter().filter(|idx| g_elements.binary_search(idx).is_ok()) {
+                let shared: Vec<_> = self
+                    .abs
+                    .element_vertices(r, idx)
+                    .unwrap()
+                    .into_iter()
+                    .map(|v| subspace.flatten(&self.vertices[v]))
+                    .collect();
+                if overlap.iter().all(|p| in_hull(p, &shared, tolerance)) {
+                    return None;
+                }
+            }
+        }
+
+        let points = overlap
+            .iter()
+            .map(|p| subspace.basis.iter().zip(p.iter()).fold(subspace.offset.clone(), |sum, (b, &c)| sum + b * c))
+            .collect();
+        Some(FacetCrossing {
+            facets: (i, j),
+            subspace,
+            points,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Polytope;
+
+    /// The convex hulls of two segments on a line overlap along their common
+    /// part.
+    #[test]
+    fn segment_overlap() {
+        let point = |x: f64| -> Point<f64> { vec![x].into() };
+        let directions = [point(1.0), point(-1.0)];
+
+        let overlap = overlap(&[point(0.0), point(2.0)], &[point(3.0), point(1.0)], &directions, 1e-9).unwrap();
+        assert_eq!(overlap.len(), 2);
+        assert!((overlap[0][0] - 2.0).abs() < 1e-9);
+        assert!((overlap[1][0] - 1.0).abs() < 1e-9);
+
+        assert!(overlap_is_none(&[point(0.0), point(1.0)], &[point(2.0), point(3.0)]));
+        assert!(in_hull(&point(0.5), &[point(0.0), point(1.0)], 1e-9));
+        assert!(!in_hull(&point(1.5), &[point(0.0), point(1.0)], 1e-9));
+    }
+
+    /// Returns whether the convex hulls of two sets of points are disjoint.
+    fn overlap_is_none(x: &[Point<f64>], y: &[Point<f64>]) -> bool {
+        overlap(x, y, &[], 1e-9).is_none()
+    }
+
+    /// The edges of a pentagram cross, but those of a pentagon don't.
+    #[test]
+    fn polygons() {
+        let crossings = Concrete::star_polygon(5, 2).facet_intersections(1e-9);
+        assert_eq!(crossings.len(), 5);
+        for crossing in &crossings {
+            assert_eq!(crossing.subspace.rank(), 0);
+            assert_eq!(crossing.points.len(), 1);
+        }
+
+        assert!(Concrete::polygon(5).facet_intersections(1e-9).is_empty());
+    }
+}
@@ -0,0 +1,73 @@
+//! Declares the [`PolytopeMeta`] type, which stores the name and provenance of
+//! a polytope.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// The metadata attached to a [`Concrete`](super::Concrete) polytope: an
+/// optional name, an optional source, and any free-form key-value pairs.
+///
+/// Operations on polytopes derive the name of their output from the names of
+/// their inputs, so that "cube" becomes "dual of cube". An operation on
+/// several polytopes only names its output if all of its inputs are named.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PolytopeMeta {
+    /// The name of the polytope.
+    pub name: Option<String>,
+
+    /// Where the polytope came from, usually a file path.
+    pub source: Option<String>,
+
+    /// Any other data attached to the polytope.
+    pub properties: BTreeMap<String, String>,
+}
+
+impl PolytopeMeta {
+    /// Returns the metadata of a polytope with a given name and nothing else.
+    pub fn named<T: Into<String>>(name: T) -> Self {
+        Self {
+            name: Some(name.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Returns whether there's no metadata at all.
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.source.is_none() && self.properties.is_empty()
+    }
+
+    /// Returns the metadata of a polytope built from this one by some
+    /// operation, named as "`operation` of `name`". The source is kept, but
+    /// the properties, which describe this polytope in particular, aren't.
+    pub fn derive(&self, operation: &str) -> Self {
+        self.derive_with(|name| format!("{} of {}", operation, name))
+    }
+
+    /// Returns the metadata of a polytope built from this one, with the name
+    /// given by a function of this one's.
+    pub fn derive_with<F: FnOnce(&str) -> String>(&self, name: F) -> Self {
+        Self {
+            name: self.name.as_deref().map(name),
+            source: self.source.clone(),
+            properties: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the metadata of a polytope built from this one and another one
+    /// by some operation, named as "`operation` of `name` and `other_name`".
+    pub fn combine(&self, other: &Self, operation: &str) -> Self {
+        let name = match (&self.name, &other.name) {
+            (Some(name), Some(other_name)) => {
+                Some(format!("{} of {} and {}", operation, name, other_name))
+            }
+            _ => None,
+        };
+
+        Self {
+            name,
+            ..Default::default()
+        }
+    }
+}
@@ -1,19 +1,38 @@
 //! Declares the [`Concrete`] polytope type and all associated data structures.
 
+// Progress and diagnostics go through the `log` facade, so that callers
+// choose where they end up.
+#![deny(clippy::print_stdout)]
+
+pub mod clip;
+pub mod conditioning;
+pub mod containment;
 pub mod cycle;
 pub mod element_types;
+pub mod embed;
 pub mod faceting;
+pub mod history;
+pub mod intersection;
+pub mod meta;
+pub mod polygon_faceting;
+pub mod stellation;
 pub mod symmetry;
+pub mod uniform;
+pub mod shared_vertices;
 
 use std::{
     collections::{HashMap, HashSet},
     ops::{Index, IndexMut}, iter,
 };
 
+use self::{history::{Operation, OperationLog}, meta::PolytopeMeta, shared_vertices::SharedVertices};
 use super::{
     abs::{
+        adjacency::AdjacencyGraph,
         flag::{Flag, FlagChanges, FlagEvent, OrientedFlagIter},
-        Abstract, ElementList, Ranked, SubelementList,
+        limits::{ElementLimits, TooLarge},
+        product::{predicted_counts, ProductType},
+        Abstract, DualCorrespondence, ElementList, Rank, Ranked, SubelementList,
     },
     DualError, Polytope,
 };
@@ -24,6 +43,7 @@ use crate::{
 };
 
 use approx::{abs_diff_eq, abs_diff_ne};
+use log::warn;
 use partitions::{PartitionVec, partition_vec};
 use rayon::prelude::*;
 use vec_like::*;
@@ -32,15 +52,70 @@ use vec_like::*;
 /// which is an [`Abstract`] together with its corresponding vertices.
 #[derive(Debug, Clone)]
 pub struct Concrete {
-    /// The list of vertices as points in Euclidean space.
+    /// The list of vertices as points in Euclidean space. These are shared
+    /// between clones of the polytope until either of them changes them.
     // todo: come up with a more compact representation, making use of the fact
     // all points have the same length?
-    pub vertices: Vec<Point<f64>>,
+    pub vertices: SharedVertices,
 
     /// The underlying abstract polytope.
     pub abs: Abstract,
+
+    /// The name and provenance of the polytope.
+    pub meta: PolytopeMeta,
+
+    /// The operations performed on the polytope in place, if they're being
+    /// recorded, see [`Concrete::with_history`].
+    pub history: Option<OperationLog>,
+}
+
+/// Represents an error found while checking that the facets of a polytope are
+/// planar.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PlanarityError {
+    /// The vertices of a facet span fewer dimensions than they should.
+    Degenerate {
+        /// The index of the facet.
+        facet: usize,
+
+        /// The rank of the affine hull of its vertices.
+        rank: usize,
+    },
+
+    /// A vertex of a facet lies outside of the affine hull of the others.
+    NotPlanar {
+        /// The index of the facet.
+        facet: usize,
+
+        /// The index of the vertex furthest away from the facet's hull.
+        vertex: usize,
+
+        /// The distance from that vertex to the hull.
+        distance: f64,
+    },
 }
 
+impl std::fmt::Display for PlanarityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Degenerate { facet, rank } => {
+                write!(f, "facet {} has vertices spanning only rank {}", facet, rank)
+            }
+            Self::NotPlanar {
+                facet,
+                vertex,
+                distance,
+            } => write!(
+                f,
+                "vertex {} of facet {} is at distance {} from its hull",
+                vertex, facet, distance
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PlanarityError {}
+
 impl Index<usize> for Concrete {
     type Output = ElementList;
 
@@ -93,8 +168,138 @@ impl Concrete {
             }
         }
 
-        // With no further info, we create a generic name for the polytope.
-        Self { vertices, abs }
+        // With no further info, the polytope is left unnamed.
+        Self {
+            vertices: vertices.into(),
+            abs,
+            meta: PolytopeMeta::default(),
+            history: None,
+        }
+    }
+
+    /// Replaces the metadata of the polytope.
+    pub fn with_meta(mut self, meta: PolytopeMeta) -> Self {
+        self.meta = meta;
+        self
+    }
+
+    /// Returns the facet adjacency graph of the polytope, see
+    /// [`Abstract::facet_adjacency`].
+    pub fn facet_adjacency(&self) -> AdjacencyGraph {
+        self.abs.facet_adjacency()
+    }
+
+    /// Returns the dual of a polytope with respect to the unit hypersphere,
+    /// along with the correspondence between the elements of both. In case of
+    /// failing, returns the index of a facet through the inversion center.
+    pub fn try_dual_with_correspondence(&self) -> Result<(Self, DualCorrespondence), DualError> {
+        let dual = self.try_dual()?;
+        Ok((dual, self.abs.dual_correspondence()))
+    }
+
+    /// Builds the product of two polytopes, or returns an error without
+    /// building anything if it would have more elements of some rank than the
+    /// limits allow. See [`Abstract::try_product`].
+    pub fn try_product(&self, other: &Self, product: ProductType, limits: &ElementLimits) -> Result<Self, TooLarge> {
+        limits.check(&predicted_counts(&self.abs, &other.abs, product))?;
+
+        Ok(match product {
+            ProductType::Pyramid => self.duopyramid(other),
+            ProductType::Prism => self.duoprism(other),
+            ProductType::Tegum => self.duotegum(other),
+            ProductType::Comb => self.duocomb(other),
+        })
+    }
+
+    /// Takes the product of an iterator over polytopes, checking the limits
+    /// before building each of the intermediate products. See
+    /// [`Abstract::try_multiproduct`].
+    pub fn try_multiproduct<'a, I: IntoIterator<Item = &'a Self>>(
+        factors: I,
+        product: ProductType,
+        limits: &ElementLimits,
+    ) -> Result<Self, TooLarge> {
+        crate::try_multiproduct(
+            factors,
+            |p, q| p.try_product(q, product, limits),
+            || match product {
+                ProductType::Pyramid | ProductType::Comb => Self::nullitope(),
+                ProductType::Prism | ProductType::Tegum => Self::point(),
+            },
+        )
+    }
+
+    /// Checks that the vertices of every facet span a subspace of exactly one
+    /// dimension less than the polytope, up to a given tolerance. Returns the
+    /// first offending facet otherwise.
+    pub fn verify_facet_planarity(&self, tolerance: f64) -> Result<(), PlanarityError> {
+        let rank = self.rank();
+        if rank < 3 {
+            return Ok(());
+        }
+
+        // The rank of the hull of each facet.
+        let hull_rank = rank - 2;
+
+        for facet in 0..self.el_count(rank - 1) {
+            let vertices = self.abs.element_vertices(rank - 1, facet).unwrap();
+            let mut iter = vertices.iter();
+            let mut hull = match iter.next() {
+                Some(&v) => Subspace::new(self.vertices[v].clone()),
+                None => return Err(PlanarityError::Degenerate { facet, rank: 0 }),
+            };
+
+            // Builds the hull from the first vertices that generate it.
+            for &v in iter {
+                if hull.rank() == hull_rank {
+                    break;
+                }
+                hull.add(&self.vertices[v]);
+            }
+
+            if hull.rank() < hull_rank {
+                return Err(PlanarityError::Degenerate {
+                    facet,
+                    rank: hull.rank(),
+                });
+            }
+
+            // Finds the vertex furthest away from the hull.
+            let mut worst = (0, 0.0);
+            for &v in &vertices {
+                let distance = hull.distance(&self.vertices[v]);
+                if distance > worst.1 {
+                    worst = (v, distance);
+                }
+            }
+
+            if worst.1 > tolerance {
+                return Err(PlanarityError::NotPlanar {
+                    facet,
+                    vertex: worst.0,
+                    distance: worst.1,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes a one-line summary of the polytope like that of [`Abstract`], along
+/// with its name and the dimension of the space it lives in, such as `Cube
+/// (rank-4 polytope in 3D): 8 vertices, 12 edges, 6 faces; χ=2`.
+impl std::fmt::Display for Concrete {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self.dim() {
+            Some(dim) => format!("rank-{} polytope in {}D", self.rank(), dim),
+            None => format!("rank-{} polytope", self.rank()),
+        };
+        match &self.meta.name {
+            Some(name) => write!(f, "{} ({})", name, kind)?,
+            None => write!(f, "{}", kind)?,
+        }
+        self.abs.fmt_counts(f)
     }
 }
 
@@ -113,6 +318,10 @@ impl Polytope for Concrete {
         self.abs
     }
 
+    fn orientable_mut(&mut self) -> bool {
+        self.abs.orientable_mut()
+    }
+
     /// Builds the unique polytope of rank −1.
     fn nullitope() -> Self {
         Self::new(Vec::new(), Abstract::nullitope())
@@ -154,7 +363,14 @@ impl Polytope for Concrete {
     /// polytope in place. If unsuccessful, leaves the polytope unchanged and
     /// returns `false`.
     fn petrial_mut(&mut self) -> bool {
-        self.abs.petrial_mut()
+        if self.abs.petrial_mut() {
+            let petrial_meta = self.meta.derive("Petrial");
+            let meta = std::mem::replace(&mut self.meta, petrial_meta);
+            self.record(Operation::Petrial, Some(meta));
+            true
+        } else {
+            false
+        }
     }
 
     /// Builds the Petrie polygon of a polytope from a given flag, or returns
@@ -163,13 +379,16 @@ impl Polytope for Concrete {
         let vertices = self.abs.petrie_polygon_vertices(flag)?;
         let n = vertices.len();
 
-        Some(Self::new(
-            vertices
-                .into_iter()
-                .map(|idx| self.vertices[idx].clone())
-                .collect(),
-            Abstract::polygon(n),
-        ))
+        Some(
+            Self::new(
+                vertices
+                    .into_iter()
+                    .map(|idx| self.vertices[idx].clone())
+                    .collect(),
+                Abstract::polygon(n),
+            )
+            .with_meta(self.meta.derive("Petrie polygon")),
+        )
     }
 
     /// "Appends" a polytope into another, creating a compound polytope.
@@ -179,11 +398,12 @@ impl Polytope for Concrete {
     fn comp_append(&mut self, mut p: Self) {
         self.abs.comp_append(p.abs);
         self.vertices.append(&mut p.vertices);
+        self.meta = self.meta.combine(&p.meta, "compound");
     }
 
     /// Gets the element with a given rank and index as a polytope, or returns
     /// `None` if such an element doesn't exist.
-    fn element(&self, rank: usize, idx: usize) -> Option<Self> {
+    fn element(&self, rank: impl Into<Rank>, idx: usize) -> Option<Self> {
         let (vertices, abs) = self.abs.element_and_vertices(rank, idx)?;
 
         Some(Self::new(
@@ -196,11 +416,11 @@ impl Polytope for Concrete {
     }
 
     /// Gets the element figure with a given rank and index as a polytope.
-    fn element_fig(&self, rank: usize, idx: usize) -> Result<Option<Self>, Self::DualError> {
-        if rank <= self.rank() {
+    fn element_fig(&self, rank: impl Into<Rank>, idx: usize) -> Result<Option<Self>, Self::DualError> {
+        if let Some(co_rank) = Rank::from(self.rank()).checked_diff(rank.into()) {
             // todo: this is quite inefficient for a small element figure since
             // we take the dual of the entire thing.
-            if let Some(mut element_fig) = self.try_dual()?.element(self.rank() - rank, idx) {
+            if let Some(mut element_fig) = self.try_dual()?.element(co_rank, idx) {
                 let subspace = Subspace::from_points(element_fig.vertices.iter());
                 element_fig.flatten();
                 element_fig.recenter_with(
@@ -299,7 +519,7 @@ impl Polytope for Concrete {
             unsafe {
                 if builder.ranks().is_dyadic().is_ok() {
                     let abs = builder.build();
-                    let conc = Concrete{abs, vertices};
+                    let conc = Concrete{abs, vertices: vertices.into(), meta: PolytopeMeta::default(), history: None};
                     output.push(conc);
                 }
             }
@@ -328,6 +548,7 @@ impl Polytope for Concrete {
                 .collect(),
             abs,
         )
+        .with_meta(self.meta.derive("omnitruncate"))
     }
 
     /// Builds a [duopyramid](https://polytope.miraheze.org/wiki/Pyramid_product)
@@ -348,6 +569,7 @@ impl Polytope for Concrete {
             duoprism_vertices(&self.vertices, &p.vertices),
             self.abs.duoprism(&p.abs),
         )
+        .with_meta(self.meta.combine(&p.meta, "duoprism"))
     }
 
     /// Builds a [duotegum](https://polytope.miraheze.org/wiki/Tegum_product)
@@ -368,12 +590,14 @@ impl Polytope for Concrete {
             duoprism_vertices(&self.vertices, &other.vertices),
             self.abs.duocomb(other.abs()),
         )
+        .with_meta(self.meta.combine(&other.meta, "duocomb"))
     }
 
     /// Builds a [ditope](https://polytope.miraheze.org/wiki/Ditope) of a given
     /// polytope in place.
     fn ditope_mut(&mut self) {
         self.abs.ditope_mut();
+        self.meta = self.meta.derive("ditope");
     }
 
     /// Builds a [hosotope](https://polytope.miraheze.org/wiki/hosotope) of a
@@ -383,22 +607,27 @@ impl Polytope for Concrete {
             vec![vec![-0.5].into(), vec![0.5].into()],
             self.abs.hosotope(),
         )
+        .with_meta(self.meta.derive("hosotope"))
     }
 
     /// Builds a [hosotope](https://polytope.miraheze.org/wiki/hosotope) of a
     /// given polytope in place.
     fn hosotope_mut(&mut self) {
-        self.vertices = vec![vec![-0.5].into(), vec![0.5].into()];
+        self.vertices = vec![vec![-0.5].into(), vec![0.5].into()].into();
         self.abs.hosotope_mut();
+        self.meta = self.meta.derive("hosotope");
     }
 
     /// Builds a [star product](https://en.wikipedia.org/wiki/Star_product)
     /// of two polytopes.
     fn star_product(&self, other: &Self) -> Self {
-        Self::new(
-            self.vertices.clone(),
-            self.abs.star_product(&other.abs),
-        )
+        Self {
+            vertices: self.vertices.clone(),
+            abs: self.abs.star_product(&other.abs),
+            meta: PolytopeMeta::default(),
+            history: None,
+        }
+        .with_meta(self.meta.combine(&other.meta, "star product"))
     }
 
     /// Attempts to build an antiprism based on a given polytope. Uses the unit
@@ -414,12 +643,10 @@ impl Polytope for Concrete {
 
     /// Builds a [simplex](https://polytope.miraheze.org/wiki/Simplex) with a
     /// given rank.
-    fn simplex(rank: usize) -> Self {
-        if rank == 0 {
-            Self::nullitope()
-        } else {
-            let dim = rank - 1;
-            let mut vertices = Vec::with_capacity(rank);
+    fn simplex(rank: impl Into<Rank>) -> Self {
+        let rank = rank.into();
+        if let Some(dim) = rank.dim() {
+            let mut vertices = Vec::with_capacity(rank.get());
 
             // Adds all points with a single entry equal to √2/2, and all others
             // equal to 0.
@@ -437,16 +664,17 @@ impl Polytope for Concrete {
             let mut simplex = Concrete::new(vertices, Abstract::simplex(rank));
             simplex.recenter();
             simplex
+        } else {
+            Self::nullitope()
         }
     }
 
     /// Builds an [orthoplex](https://polytope.miraheze.org/wiki/Orthoplex) with
     /// a given rank.
-    fn orthoplex(rank: usize) -> Self {
-        if rank == 0 {
-            Self::nullitope()
-        } else {
-            Self::multitegum(iter::repeat(&Self::dyad_with(f64::SQRT_2)).take(rank - 1))
+    fn orthoplex(rank: impl Into<Rank>) -> Self {
+        match rank.into().facet() {
+            None => Self::nullitope(),
+            Some(facet) => Self::multitegum(iter::repeat(&Self::dyad_with(f64::SQRT_2)).take(facet.get())),
         }
     }
 
@@ -454,6 +682,69 @@ impl Polytope for Concrete {
     fn untangle_faces(&mut self) {
         self.abs.untangle_faces();
     }
+
+    /// Splits the elements of a given rank into their components. Where more
+    /// than two subelements of an element go through a ridge, they're paired
+    /// up according to the order in which they wind around it.
+    fn untangle_elements(&mut self, rank: impl Into<Rank>) {
+        let rank = rank.into();
+        let vertices = &self.vertices;
+        self.abs.untangle_elements_with(rank, |abs, idx, ridge, subs| {
+            sort_around_ridge(abs, vertices, rank, idx, ridge, subs)
+        });
+    }
+}
+
+/// Sorts the subelements of an element through one of its ridges by their
+/// angle around it, within the affine hull of the element. Each subelement is
+/// represented by the average of its vertices. Returns `None` if the
+/// directions to these don't span a plane.
+fn sort_around_ridge(
+    abs: &Abstract,
+    vertices: &[Point<f64>],
+    rank: Rank,
+    idx: usize,
+    ridge: usize,
+    subs: &[usize],
+) -> Option<Vec<usize>> {
+    let dim = vertices.first()?.len();
+    let vertices_of = |rank: Rank, idx| -> Option<Vec<&Point<f64>>> {
+        Some(abs.element_vertices(rank, idx)?.into_iter().map(|v| &vertices[v]).collect())
+    };
+
+    let element = Subspace::from_points(vertices_of(rank, idx)?.into_iter());
+    let ridge = Subspace::from_points(vertices_of(rank.ridge()?, ridge)?.into_iter());
+
+    let mut directions = Vec::with_capacity(subs.len());
+    for &sub in subs {
+        let sub_vertices = vertices_of(rank.facet()?, sub)?;
+        let center = sub_vertices.iter().fold(Point::zeros(dim), |acc, &v| acc + v) / sub_vertices.len() as f64;
+        let center = element.project(&center);
+        directions.push(&center - ridge.project(&center));
+    }
+
+    // An orthonormal basis of the plane of the directions.
+    let e1 = directions[0].clone();
+    if e1.norm() < f64::EPS {
+        return None;
+    }
+    let e1 = e1.normalize();
+    let e2 = directions
+        .iter()
+        .map(|d| d - &e1 * d.dot(&e1))
+        .max_by(|a, b| a.norm().partial_cmp(&b.norm()).unwrap_or(std::cmp::Ordering::Equal))?;
+    if e2.norm() < f64::EPS {
+        return None;
+    }
+    let e2 = e2.normalize();
+
+    let mut angles: Vec<_> = subs
+        .iter()
+        .zip(&directions)
+        .map(|(&sub, d)| (d.dot(&e2).atan2(d.dot(&e1)), sub))
+        .collect();
+    angles.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+    Some(angles.into_iter().map(|(_, sub)| sub).collect())
 }
 
 /// Generates the vertices for either a tegum or a pyramid product with two
@@ -613,6 +904,7 @@ pub trait ConcretePolytope: Polytope {
         for v in self.vertices_mut() {
             *v *= k;
         }
+        self.con_mut().record(Operation::Scale(k), None);
     }
 
     /// Recenters a polytope so that the gravicenter is at the origin.
@@ -627,6 +919,7 @@ pub trait ConcretePolytope: Polytope {
         for v in self.vertices_mut() {
             *v -= p;
         }
+        self.con_mut().record(Operation::Translate(-p), None);
     }
 
     /// Applies a linear transformation to all vertices of a polytope.
@@ -634,6 +927,7 @@ pub trait ConcretePolytope: Polytope {
         for v in self.vertices_mut() {
             *v = m * v as &_;
         }
+        self.con_mut().record(Operation::Transform(m.clone()), None);
 
         self
     }
@@ -677,11 +971,37 @@ pub trait ConcretePolytope: Polytope {
         })
     }
 
+    /// Calculates the distance from the origin to the closest facet
+    /// hyperplane, which is the radius of the insphere when the polytope is
+    /// centered at the origin. Returns `None` if the polytope has no facets,
+    /// or if some facet doesn't span a hyperplane.
+    fn insphere_radius(&self) -> Option<f64> {
+        // The facets of points have no vertices to span anything.
+        if self.rank() < 2 {
+            return None;
+        }
+
+        let facet_rank = self.rank() - 1;
+        let mut radius: Option<f64> = None;
+
+        for idx in 0..self.el_count(facet_rank) {
+            let hyperplane = Subspace::from_points(self.element_vertices_ref(facet_rank, idx)?.into_iter());
+            if !hyperplane.is_hyperplane() {
+                return None;
+            }
+
+            let distance = hyperplane.distance(&Point::zeros(self.dim_or()));
+            radius = Some(radius.map_or(distance, |radius| radius.min(distance)));
+        }
+
+        radius
+    }
+
     /// Calculates the gravicenter of a polytope, or returns `None` in the case
     /// of the nullitope.
     fn gravicenter(&self) -> Option<Point<f64>> {
         (!self.is_nullitope())
-            .then(|| self.vertices().iter().sum::<Point<f64>>() / (self.vertex_count()) as f64)
+            .then(|| kahan_sum_points(self.dim_or(), self.vertices()) / (self.vertex_count()) as f64)
     }
 
     /// Gets the least and greatest distance of a vertex of the polytope,
@@ -725,15 +1045,13 @@ pub trait ConcretePolytope: Polytope {
 
         // Every other element maps to the average of the locations of their
         // subelements.
+        let dim = self.dim_or();
         for (r, elements) in self.ranks().iter().enumerate().skip(2) {
             map.push(
                 elements
                     .iter()
                     .map(|el| {
-                        el.subs
-                            .iter()
-                            .map(|&idx| &map[(r - 1, idx)])
-                            .sum::<Point<f64>>()
+                        kahan_sum_points(dim, el.subs.iter().map(|&idx| &map[(r - 1, idx)]))
                             / f64::usize(el.subs.len())
                     })
                     .collect(),
@@ -749,6 +1067,16 @@ pub trait ConcretePolytope: Polytope {
         Some((&self.vertices()[edge.subs[0]] - &self.vertices()[edge.subs[1]]).norm())
     }
 
+    /// Returns the distinct lengths of the edges of the polytope, in
+    /// increasing order. Lengths that differ by less than [`Float::EPS`] are
+    /// taken to be the same.
+    fn edge_lengths(&self) -> Vec<f64> {
+        let mut lengths: Vec<f64> = (0..self.edge_count()).filter_map(|idx| self.edge_len(idx)).collect();
+        lengths.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        lengths.dedup_by(|a, b| *a - *b < f64::EPS);
+        lengths
+    }
+
     /// Checks whether a polytope is equilateral to a fixed precision, and with
     /// a specified edge length.
     fn is_equilateral_with(&self, len: f64) -> bool {
@@ -871,7 +1199,7 @@ pub trait ConcretePolytope: Polytope {
 
     /// Gets the references to the (geometric) vertices of an element on the
     /// polytope.
-    fn element_vertices_ref(&self, rank: usize, idx: usize) -> Option<Vec<&Point<f64>>> {
+    fn element_vertices_ref(&self, rank: impl Into<Rank>, idx: usize) -> Option<Vec<&Point<f64>>> {
         Some(
             self.abs()
                 .element_vertices(rank, idx)?
@@ -995,6 +1323,12 @@ pub trait ConcretePolytope: Polytope {
     
     /// Checks if is fissary.
     fn is_fissary(&self) -> bool;
+
+    /// Returns whether the polytope is a compound, see
+    /// [`Abstract::is_compound`].
+    fn is_compound(&self) -> bool {
+        self.abs().is_compound()
+    }
     
     /// Compounds coplanar facets
     fn fuse_facets(&self) -> Self;
@@ -1078,7 +1412,7 @@ impl ConcretePolytope for Concrete {
                 })
                 .collect_into_vec(&mut projections);
         } else {
-            projections = self.vertices.clone();
+            projections = self.vertices.to_vec();
         }
 
         // Reciprocates the projected points.
@@ -1088,8 +1422,11 @@ impl ConcretePolytope for Concrete {
             }
         }
 
-        self.vertices = projections;
+        self.vertices = projections.into();
         self.abs.dual_mut();
+        let dual_meta = self.meta.derive("dual");
+        let meta = std::mem::replace(&mut self.meta, dual_meta);
+        self.record(Operation::dual(sphere), Some(meta));
         Ok(())
     }
 
@@ -1139,7 +1476,7 @@ impl ConcretePolytope for Concrete {
             new_vertices[dual_vertex_indices[idx]] = v;
         }
 
-        Self::new(new_vertices, abs)
+        Self::new(new_vertices, abs).with_meta(self.meta.derive("antiprism"))
     }
 
     /// Generates a duopyramid from two given polytopes with a given height and
@@ -1162,6 +1499,7 @@ impl ConcretePolytope for Concrete {
             ),
             Abstract::duopyramid(&self.abs, &other.abs),
         )
+        .with_meta(self.meta.combine(&other.meta, "duopyramid"))
     }
 
     /// Builds a [duotegum](https://polytope.miraheze.org/wiki/Tegum_product)
@@ -1171,6 +1509,7 @@ impl ConcretePolytope for Concrete {
             duopyramid_vertices(&p.vertices, &q.vertices, p_offset, q_offset, 0.0, true),
             p.abs.duotegum(&q.abs),
         )
+        .with_meta(p.meta.combine(&q.meta, "duotegum"))
     }
 
     /// Projects the vertices of the polytope into the lowest dimension possible.
@@ -1187,6 +1526,7 @@ impl ConcretePolytope for Concrete {
             for v in &mut self.vertices {
                 *v = subspace.flatten(v);
             }
+            self.record(Operation::flatten(subspace), None);
         }
     }
 
@@ -1318,13 +1658,13 @@ impl ConcretePolytope for Concrete {
         unsafe {
             let mut abs = builder.build();
             abs.untangle_faces();
-            Self::new(vertices, abs)
+            Self::new(vertices, abs).with_meta(self.meta.derive("cross-section"))
         }
     }
 
     fn truncate_with(&self, truncate_type: Vec<usize>, depth: Vec<f64>) -> Self {
         if truncate_type.is_empty() {
-            println!("Cannot truncate with no active nodes!");
+            warn!("Cannot truncate with no active nodes!");
             return self.clone()
         }
         let (abs, subflags) = self.abs().truncate_and_flags(truncate_type.clone());
@@ -1340,7 +1680,7 @@ impl ConcretePolytope for Concrete {
         }
         //dbg!(abs.clone());
 
-        Self::new(vertex_coords, abs)
+        Self::new(vertex_coords, abs).with_meta(self.meta.derive("truncation"))
     }
     
     /// Checks if is fissary.
@@ -1411,13 +1751,18 @@ impl ConcretePolytope for Concrete {
         compound_ordered.iter().for_each(|x| builder.push_subs(x.1.clone()));
         
         builder.push_max();
-        unsafe { Self::new(self.vertices.clone(),builder.build()) }
+        Self {
+            vertices: self.vertices.clone(),
+            abs: unsafe { builder.build() },
+            meta: PolytopeMeta::default(),
+            history: None,
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{Concrete, ConcretePolytope};
+    use super::{Concrete, ConcretePolytope, PlanarityError};
     use crate::{float::Float, Polytope};
 
     use approx::abs_diff_eq;
@@ -1585,4 +1930,202 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    /// Checks that data attached to the facets of a cube ends up on the
+    /// corresponding vertices of the octahedron.
+    fn dual_tags() {
+        use crate::{abs::ElementTags, geometry::Point};
+
+        let cube = Concrete::hypercube(4);
+        let tags = ElementTags::from_fn(&cube, |r, idx| {
+            match cube.element(r, idx) {
+                Some(el) if !el.vertices.is_empty() => {
+                    el.vertices.iter().sum::<Point<f64>>() / el.vertices.len() as f64
+                }
+                _ => Point::zeros(3),
+            }
+        });
+
+        let (dual, correspondence) = cube.try_dual_with_correspondence().unwrap();
+        let dual_tags = tags.dualize(&correspondence);
+        for (vertex, centroid) in dual.vertices.iter().zip(dual_tags[1].iter()) {
+            assert!(abs_diff_eq!(
+                (vertex.normalize() - centroid.normalize()).norm(),
+                0.0,
+                epsilon = f64::EPS
+            ));
+        }
+    }
+
+    #[test]
+    /// Checks that moving a vertex of a cube off its facets is caught.
+    fn facet_planarity() {
+        let mut cube = Concrete::hypercube(4);
+        assert_eq!(cube.verify_facet_planarity(f64::EPS), Ok(()));
+
+        cube.vertices[0][0] += 0.1;
+        assert!(matches!(
+            cube.verify_facet_planarity(f64::EPS),
+            Err(PlanarityError::NotPlanar { .. })
+        ));
+    }
+
+    #[test]
+    /// Checks that the names of polytopes are carried through operations.
+    fn meta() {
+        use super::meta::PolytopeMeta;
+
+        let square = Concrete::polygon(4).with_meta(PolytopeMeta::named("square"));
+        let triangle = Concrete::polygon(3).with_meta(PolytopeMeta::named("triangle"));
+
+        assert_eq!(
+            square.try_dual().unwrap().meta.name.as_deref(),
+            Some("dual of square")
+        );
+        assert_eq!(
+            square.duoprism(&triangle).meta.name.as_deref(),
+            Some("duoprism of square and triangle")
+        );
+        assert_eq!(square.duoprism(&Concrete::polygon(3)).meta.name, None);
+
+        let mut compound = square.clone();
+        compound.comp_append(triangle);
+        assert_eq!(
+            compound.meta.name.as_deref(),
+            Some("compound of square and triangle")
+        );
+    }
+
+    #[test]
+    /// Checks that untangling the elements of a polytope keeps its
+    /// cross-sections from having self-intersecting faces. The cells of the
+    /// polytope are two tetrahedra that share an edge, as a single element,
+    /// so that any section through the edge and both tetrahedra meets them in
+    /// a pair of triangles sharing a vertex.
+    fn untangled_cross_section() {
+        use crate::{
+            abs::Ranked,
+            file::FromFile,
+            geometry::{Hyperplane, Vector},
+        };
+        use std::collections::HashMap;
+
+        /// Returns whether every face of a polytope is a single cycle, so that
+        /// each of its vertices is on exactly two of its edges.
+        fn simple_faces(poly: &Concrete) -> bool {
+            (&poly[3]).into_iter().all(|face| {
+                let mut degrees = HashMap::new();
+                for &edge in &face.subs {
+                    for &vertex in &poly[(2, edge)].subs {
+                        *degrees.entry(vertex).or_insert(0) += 1;
+                    }
+                }
+
+                degrees.values().all(|&degree| degree == 2)
+            })
+        }
+
+        let tetrahedra = Concrete::from_off(
+            "OFF\n6 8 0\n\
+            0 0 1\n0 0 -1\n1 1 0\n1 -1 0\n-1 1 0\n-1 -1 0\n\
+            3 0 1 2\n3 0 1 3\n3 0 2 3\n3 1 2 3\n\
+            3 0 1 4\n3 0 1 5\n3 0 4 5\n3 1 4 5\n",
+        )
+        .unwrap();
+
+        let tangled = tetrahedra.pyramid().pyramid();
+        assert_eq!(tangled.rank(), 6);
+        assert_eq!(tangled.dim(), Some(5));
+
+        let mut normal = Vector::zeros(5);
+        normal[2] = 1.0;
+        let slice = Hyperplane::new(normal, 0.3);
+        assert!(!simple_faces(&tangled.cross_section(&slice)));
+
+        let mut untangled = tangled.clone();
+        untangled.untangle_all();
+        assert_eq!(untangled.el_count(4), tangled.el_count(4) + 1);
+        assert!(simple_faces(&untangled.cross_section(&slice)));
+    }
+    /// The insphere of the hypercubes touches the middle of each facet, and
+    /// polytopes without facets don't have one.
+    #[test]
+    fn insphere_radius() {
+        for rank in 3..=5 {
+            let radius = Concrete::hypercube(rank).insphere_radius().unwrap();
+            assert!(abs_diff_eq!(radius, 0.5, epsilon = f64::EPS));
+        }
+
+        assert_eq!(Concrete::point().insphere_radius(), None);
+        assert_eq!(Concrete::nullitope().insphere_radius(), None);
+    }
+
+    /// Returns a square with unit edges, written out by hand.
+    fn square() -> Concrete {
+        let vertices = vec![
+            vec![0.0, 0.0].into(),
+            vec![1.0, 0.0].into(),
+            vec![1.0, 1.0].into(),
+            vec![0.0, 1.0].into(),
+        ];
+        let abs = crate::abstract_poly![
+            [[]],
+            [[0], [0], [0], [0]],
+            [[0, 1], [1, 2], [2, 3], [0, 3]],
+            [[0, 1, 2, 3]],
+        ];
+        Concrete::new(vertices, abs)
+    }
+
+    /// A square written out by hand has the area and edges it should.
+    #[test]
+    fn hand_written_square() {
+        test_volume(square(), Some(1.0));
+        assert_eq!(square().edge_lengths(), vec![1.0]);
+    }
+
+    /// A tetrahedron with its vertices on a plane has degenerate facets.
+    #[test]
+    fn flat_tetrahedron() {
+        let vertices = vec![
+            vec![0.0, 0.0, 0.0].into(),
+            vec![1.0, 0.0, 0.0].into(),
+            vec![2.0, 0.0, 0.0].into(),
+            vec![0.0, 1.0, 0.0].into(),
+        ];
+        let abs = crate::abstract_poly![
+            [[]],
+            [[0], [0], [0], [0]],
+            [[0, 1], [0, 2], [0, 3], [1, 2], [1, 3], [2, 3]],
+            [[0, 1, 3], [0, 2, 4], [1, 2, 5], [3, 4, 5]],
+            [[0, 1, 2, 3]],
+        ];
+        let tetrahedron = Concrete::new(vertices, abs);
+
+        // The first facet is on three collinear vertices.
+        assert!(matches!(
+            tetrahedron.verify_facet_planarity(f64::EPS),
+            Err(PlanarityError::Degenerate { facet: 0, rank: 1 })
+        ));
+    }
+
+    /// The summaries of polytopes give their names and the dimension they
+    /// live in.
+    #[test]
+    fn display() {
+        use super::meta::PolytopeMeta;
+
+        let mut pentagon = Concrete::polygon(5).with_meta(PolytopeMeta::named("pentagon"));
+        assert_eq!(pentagon.to_string(), "pentagon (rank-3 polytope in 2D): 5 vertices, 5 edges; χ=0");
+        pentagon.orientable_mut();
+        assert_eq!(pentagon.to_string(), "pentagon (rank-3 polytope in 2D): 5 vertices, 5 edges; orientable; χ=0");
+
+        assert_eq!(
+            Concrete::orthoplex(5).to_string(),
+            "rank-5 polytope in 4D: 8 vertices, 24 edges, 32 faces, 16 cells; χ=0"
+        );
+        assert_eq!(Concrete::dyad().to_string(), "rank-2 polytope in 1D: 2 vertices; χ=2");
+        assert_eq!(Concrete::nullitope().to_string(), "rank-0 polytope");
+    }
 }
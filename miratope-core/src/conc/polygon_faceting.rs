@@ -0,0 +1,295 @@
+//! Enumerates the facetings of polygons: the closed polygons, stars, and
+//! compounds on a symmetric arrangement of points in the plane.
+//!
+//! A faceting of a polygon is a set of edges between its vertices that's
+//! invariant under the symmetry group, such that every vertex is in either
+//! none or two of them. It's made out of orbits of edges, so we first sort the
+//! pairs of vertices into orbits and then combine these.
+
+use std::collections::{BTreeSet, HashSet};
+
+use super::{
+    cycle::CycleList,
+    faceting::GroupEnum,
+    meta::PolytopeMeta,
+    symmetry::VertexPermutation,
+    Concrete, ConcretePolytope,
+};
+use crate::{
+    abs::{AbstractBuilder, SubelementList},
+    float::Float,
+    geometry::{Point, Subspace},
+};
+
+use log::{debug, info, warn};
+use vec_like::*;
+
+/// The settings for [`Concrete::polygon_facetings`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PolygonFacetingOptions {
+    /// The length of every edge, if fixed.
+    pub edge_length: Option<f64>,
+
+    /// The number of times the faceting winds around the center, if fixed. For
+    /// a compound, this is the sum over its components, so that `{n/d}` winds
+    /// `d` times around the center whether or not it's a compound.
+    pub winding: Option<usize>,
+
+    /// Whether every vertex must be used.
+    pub every_vertex: bool,
+
+    /// Whether to only output single polygons, rather than compounds.
+    pub connected_only: bool,
+
+    /// Whether to output the compounds of other facetings.
+    pub include_compounds: bool,
+}
+
+/// The orbits of the edges between some vertices under a vertex map.
+struct EdgeOrbits {
+    /// The edges in each orbit.
+    edges: Vec<Vec<[usize; 2]>>,
+
+    /// The number of edges of each orbit through each vertex.
+    degrees: Vec<Vec<usize>>,
+}
+
+impl EdgeOrbits {
+    /// Sorts the edges between some vertices into orbits, keeping only those
+    /// with a given length, if any. Orbits with more than two edges through
+    /// some vertex can't be in any faceting, so they're skipped.
+    fn new(vertices: &[Point<f64>], vertex_map: &[VertexPermutation], edge_length: Option<f64>) -> Self {
+        let vertex_count = vertices.len();
+        let mut edges = Vec::new();
+        let mut degrees = Vec::new();
+        let mut seen = HashSet::new();
+
+        for i in 0..vertex_count {
+            for j in i + 1..vertex_count {
+                if seen.contains(&[i, j]) {
+                    continue;
+                }
+                if let Some(length) = edge_length {
+                    if ((&vertices[i] - &vertices[j]).norm() - length).abs() > f64::EPS {
+                        continue;
+                    }
+                }
+
+                let orbit: BTreeSet<_> = vertex_map
+                    .iter()
+                    .map(|row| {
+                        let (a, b) = (row[i], row[j]);
+                        if a < b {
+                            [a, b]
+                        } else {
+                            [b, a]
+                        }
+                    })
+                    .collect();
+
+                let mut degree = vec![0; vertex_count];
+                for edge in &orbit {
+                    seen.insert(*edge);
+                    degree[edge[0]] += 1;
+                    degree[edge[1]] += 1;
+                }
+                if degree.iter().all(|&d| d <= 2) {
+                    edges.push(orbit.into_iter().collect());
+                    degrees.push(degree);
+                }
+            }
+        }
+
+        Self { edges, degrees }
+    }
+
+    /// Returns the number of orbits.
+    fn len(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Finds every set of orbits through every vertex either twice or not at
+    /// all, as sorted lists of orbits. Unless compounds are asked for, only
+    /// the sets without such a proper subset are returned.
+    fn combine(&self, include_compounds: bool) -> Vec<Vec<usize>> {
+        let mut found = BTreeSet::new();
+
+        for root in 0..self.len() {
+            let mut stack = vec![(vec![root], self.degrees[root].clone())];
+
+            while let Some((orbits, degrees)) = stack.pop() {
+                // Every completion goes exactly once more through a vertex
+                // that's been gone through once, so we only try the orbits
+                // through it.
+                match degrees.iter().position(|&d| d == 1) {
+                    Some(vertex) => {
+                        for orbit in root + 1..self.len() {
+                            if self.degrees[orbit][vertex] != 0 && !orbits.contains(&orbit) {
+                                self.push(&mut stack, &orbits, &degrees, orbit);
+                            }
+                        }
+                    }
+                    None => {
+                        let mut sorted = orbits.clone();
+                        sorted.sort_unstable();
+                        found.insert(sorted);
+
+                        if include_compounds {
+                            for orbit in root + 1..self.len() {
+                                if !orbits.contains(&orbit) {
+                                    self.push(&mut stack, &orbits, &degrees, orbit);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        let found: Vec<_> = found.into_iter().collect();
+        if include_compounds {
+            return found;
+        }
+
+        // A set containing another is the union of that one and its
+        // complement.
+        found
+            .iter()
+            .filter(|set| {
+                !found
+                    .iter()
+                    .any(|other| other.len() < set.len() && other.iter().all(|orbit| set.contains(orbit)))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Pushes a set of orbits with another one added to the stack, unless
+    /// this goes through some vertex more than twice.
+    fn push(&self, stack: &mut Vec<(Vec<usize>, Vec<usize>)>, orbits: &[usize], degrees: &[usize], orbit: usize) {
+        let new_degrees: Vec<_> = degrees.iter().zip(&self.degrees[orbit]).map(|(a, b)| a + b).collect();
+        if new_degrees.iter().all(|&d| d <= 2) {
+            let mut new_orbits = orbits.to_vec();
+            new_orbits.push(orbit);
+            stack.push((new_orbits, new_degrees));
+        }
+    }
+}
+
+/// Returns the number of times a closed polygon winds around the origin,
+/// given its vertices in cyclic order as coordinates in the plane, or `None`
+/// if one of its edges goes through the origin.
+fn winding_number(points: &[(f64, f64)]) -> Option<usize> {
+    let mut angle = 0.0;
+    for (idx, &(x0, y0)) in points.iter().enumerate() {
+        let (x1, y1) = points[(idx + 1) % points.len()];
+        let (cross, dot) = (x0 * y1 - y0 * x1, x0 * x1 + y0 * y1);
+        if cross.abs() < f64::EPS && dot < 0.0 {
+            return None;
+        }
+        angle += cross.atan2(dot);
+    }
+
+    Some((angle / f64::TAU).round().abs() as usize)
+}
+
+impl Concrete {
+    /// Enumerates the facetings of a polygon, or of any arrangement of points
+    /// in a plane: the closed polygons, stars, and compounds on its vertices
+    /// that are invariant under a symmetry group.
+    ///
+    /// Compounds of other facetings are only returned if asked for, but
+    /// compounds whose components aren't facetings by themselves always are,
+    /// like the hexagram on the vertices of a hexagon.
+    pub fn polygon_facetings(&self, symmetry: GroupEnum, options: &PolygonFacetingOptions) -> Vec<Concrete> {
+        let mut poly = self.clone();
+        let vertex_count = poly.vertices.len();
+        let center = match poly.gravicenter() {
+            Some(center) => center,
+            None => return Vec::new(),
+        };
+
+        let plane = Subspace::from_points(poly.vertices.iter());
+        if plane.basis.len() != 2 {
+            warn!("The vertices don't span a plane!");
+            return Vec::new();
+        }
+        let coordinates: Vec<_> = poly
+            .vertices
+            .iter()
+            .map(|v| {
+                let v = v - &center;
+                (v.dot(&plane.basis[0]), v.dot(&plane.basis[1]))
+            })
+            .collect();
+
+        let vertex_map = match poly.symmetry_vertex_map(symmetry, vertex_count, None) {
+            Ok(vertex_map) => vertex_map,
+            Err(err) => {
+                warn!("Faceting aborted: {}", err);
+                return Vec::new();
+            }
+        };
+        let orbits = EdgeOrbits::new(&poly.vertices, &vertex_map, options.edge_length);
+        debug!("{} edge orbits", orbits.len());
+
+        let mut facetings = Vec::new();
+        for set in orbits.combine(options.include_compounds) {
+            let edges: Vec<_> = set.iter().flat_map(|&orbit| orbits.edges[orbit].iter()).collect();
+            let cycles = CycleList::from_edges(edges.iter().map(|edge| &edge[..]));
+
+            let used: Vec<_> = (0..vertex_count)
+                .filter(|v| edges.iter().any(|edge| edge.contains(v)))
+                .collect();
+            if options.every_vertex && used.len() != vertex_count {
+                continue;
+            }
+            if options.connected_only && cycles.len() != 1 {
+                continue;
+            }
+
+            let winding = cycles.iter().try_fold(0, |total, cycle| {
+                let points: Vec<_> = cycle.iter().map(|&v| coordinates[v]).collect();
+                winding_number(&points).map(|winding| total + winding)
+            });
+            if options.winding.is_some() && winding != options.winding {
+                continue;
+            }
+
+            let mut new_idx = vec![usize::MAX; vertex_count];
+            for (idx, &v) in used.iter().enumerate() {
+                new_idx[v] = idx;
+            }
+            let mut edge_list = SubelementList::with_capacity(edges.len());
+            for edge in &edges {
+                let (a, b) = (new_idx[edge[0]], new_idx[edge[1]]);
+                edge_list.push(vec![a.min(b), a.max(b)].into());
+            }
+
+            let mut builder = AbstractBuilder::with_rank_capacity(3);
+            builder.push_min();
+            builder.push_vertices(used.len());
+            builder.push(edge_list);
+            builder.push_max();
+
+            // Safety: every vertex is in exactly two edges, so this is a
+            // valid polygon or compound of polygons.
+            let abs = unsafe { builder.build() };
+            let mut faceting = Concrete::new(used.iter().map(|&v| poly.vertices[v].clone()).collect(), abs);
+
+            let idx = facetings.len();
+            faceting.meta = if self.meta.name.is_some() {
+                self.meta.derive_with(|name| format!("faceting #{} of {}", idx, name))
+            } else {
+                PolytopeMeta::named(format!("faceting #{}", idx))
+            };
+            if let Some(winding) = winding {
+                faceting.meta.properties.insert("winding".to_string(), winding.to_string());
+            }
+            facetings.push(faceting);
+        }
+
+        info!("Found {} facetings", facetings.len());
+        facetings
+    }
+}
@@ -0,0 +1,140 @@
+//! Declares the [`SharedVertices`] type, which stores the vertices of a
+//! [`Concrete`](super::Concrete) polytope so that they can be shared between
+//! polytopes.
+
+use std::{
+    iter::FromIterator,
+    ops::{Deref, DerefMut},
+    slice,
+    sync::Arc,
+};
+
+use crate::geometry::Point;
+
+/// The vertices of a concrete polytope. Cloning them is cheap, as the clones
+/// share the same list. Changing the vertices through a mutable reference
+/// copies the list first if it's shared, so that the other clones aren't
+/// changed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SharedVertices(Arc<Vec<Point<f64>>>);
+
+impl SharedVertices {
+    /// Initializes an empty list of vertices.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the vertices as a vector, copying them only if they're shared.
+    pub fn into_vec(self) -> Vec<Point<f64>> {
+        Arc::try_unwrap(self.0).unwrap_or_else(|vertices| (*vertices).clone())
+    }
+
+    /// Returns whether these vertices share their list with some others.
+    pub fn is_shared(&self) -> bool {
+        Arc::strong_count(&self.0) > 1
+    }
+
+    /// Returns whether two lists of vertices are the same list, rather than
+    /// just equal.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Deref for SharedVertices {
+    type Target = Vec<Point<f64>>;
+
+    fn deref(&self) -> &Vec<Point<f64>> {
+        &self.0
+    }
+}
+
+impl DerefMut for SharedVertices {
+    /// Returns a mutable reference to the vertices, copying them first if
+    /// they're shared.
+    fn deref_mut(&mut self) -> &mut Vec<Point<f64>> {
+        Arc::make_mut(&mut self.0)
+    }
+}
+
+impl From<Vec<Point<f64>>> for SharedVertices {
+    fn from(vertices: Vec<Point<f64>>) -> Self {
+        Self(Arc::new(vertices))
+    }
+}
+
+impl From<SharedVertices> for Vec<Point<f64>> {
+    fn from(vertices: SharedVertices) -> Self {
+        vertices.into_vec()
+    }
+}
+
+impl FromIterator<Point<f64>> for SharedVertices {
+    fn from_iter<I: IntoIterator<Item = Point<f64>>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl<'a> IntoIterator for &'a SharedVertices {
+    type Item = &'a Point<f64>;
+    type IntoIter = slice::Iter<'a, Point<f64>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut SharedVertices {
+    type Item = &'a mut Point<f64>;
+    type IntoIter = slice::IterMut<'a, Point<f64>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.deref_mut().iter_mut()
+    }
+}
+
+impl IntoIterator for SharedVertices {
+    type Item = Point<f64>;
+    type IntoIter = std::vec::IntoIter<Point<f64>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_vec().into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Some vertices on a line.
+    fn line(count: usize) -> SharedVertices {
+        (0..count).map(|i| Point::from_vec(vec![i as f64])).collect()
+    }
+
+    /// Clones share their vertices until one of them is changed.
+    #[test]
+    fn copy_on_write() {
+        let vertices = line(3);
+        let mut clone = vertices.clone();
+        assert!(clone.ptr_eq(&vertices));
+        assert!(vertices.is_shared());
+
+        clone[0][0] = 5.0;
+        assert!(!clone.ptr_eq(&vertices));
+        assert!(!vertices.is_shared());
+        assert_eq!(vertices[0][0], 0.0);
+        assert_eq!(clone[0][0], 5.0);
+    }
+
+    /// SharedVertices that aren't shared are changed in place.
+    #[test]
+    fn unshared() {
+        let mut vertices = line(3);
+        let before = vertices.as_ptr();
+        for v in &mut vertices {
+            v[0] += 1.0;
+        }
+        assert_eq!(vertices.as_ptr(), before);
+        assert_eq!(vertices.into_vec()[2][0], 3.0);
+    }
+}
@@ -0,0 +1,175 @@
+//! Finds the stellations of a polytope as the duals of the facetings of its
+//! dual.
+//!
+//! Reciprocation about a sphere swaps the facet hyperplanes of a polytope with
+//! the vertices of its dual. A stellation keeps the facet hyperplanes of a
+//! polytope and joins them up differently, so its dual keeps the vertices of
+//! the dual and joins them up differently: it's a faceting of the dual.
+
+use super::{
+    faceting::{EdgeLengthFilter, FacetingOptions, GroupEnum},
+    Concrete, ConcretePolytope,
+};
+use crate::{float::Float, geometry::{Hypersphere, Point}};
+
+use log::warn;
+
+/// The point about which a polytope is reciprocated to find its stellations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StellationCenter {
+    /// The center of the circumsphere, or the gravicenter if there's none.
+    Circumcenter,
+
+    /// The gravicenter of the vertices.
+    Gravicenter,
+}
+
+/// The settings for [`Concrete::stellations_via_dual`].
+///
+/// The faceting of the dual is configured by [`Self::faceting`], except for
+/// its edge length and inradius bounds, which are replaced by the bounds on
+/// the dihedral angles and the vertex distances of the stellations. The
+/// facetings are always returned rather than saved, and their facets aren't.
+#[derive(Clone, Debug)]
+pub struct StellationOptions {
+    /// The options for the faceting of the dual.
+    pub faceting: FacetingOptions,
+
+    /// The point about which the polytope is reciprocated.
+    pub center: StellationCenter,
+
+    /// The minimum allowed dihedral angle between two facets sharing a ridge,
+    /// in radians. This is π minus the angle between their normals. It's only
+    /// enforced if the facet hyperplanes of the polytope are all at the same
+    /// distance from the center.
+    pub min_dihedral_angle: Option<f64>,
+
+    /// The maximum allowed dihedral angle between two facets sharing a ridge,
+    /// in radians. This is π minus the angle between their normals. It's only
+    /// enforced if the facet hyperplanes of the polytope are all at the same
+    /// distance from the center.
+    pub max_dihedral_angle: Option<f64>,
+
+    /// The minimum allowed distance from a vertex to the center.
+    pub min_vertex_distance: Option<f64>,
+
+    /// The maximum allowed distance from a vertex to the center.
+    pub max_vertex_distance: Option<f64>,
+}
+
+impl Default for StellationOptions {
+    fn default() -> Self {
+        Self {
+            faceting: FacetingOptions::default(),
+            center: StellationCenter::Circumcenter,
+            min_dihedral_angle: None,
+            max_dihedral_angle: None,
+            min_vertex_distance: None,
+            max_vertex_distance: None,
+        }
+    }
+}
+
+impl StellationOptions {
+    /// Returns the faceting options for the dual, where the dual vertices lie
+    /// at the given distances from the origin.
+    ///
+    /// Two facets sharing a ridge become two dual vertices joined by an edge.
+    /// If the dual vertices lie on a sphere of radius ρ, the edge between the
+    /// duals of two facets at a dihedral angle θ has length 2ρ·cos(θ/2), and a
+    /// dual facet hyperplane at distance d from the origin comes from a vertex
+    /// at distance 1/d.
+    fn dual_faceting_options(&self, dual_radii: &[f64]) -> FacetingOptions {
+        let mut options = self.faceting.clone();
+        options.save = true;
+        options.save_facets = false;
+        options.save_to_file = false;
+
+        options.edge_length = EdgeLengthFilter::Any;
+        if self.min_dihedral_angle.is_some() || self.max_dihedral_angle.is_some() {
+            let radius = dual_radii[0];
+            if dual_radii.iter().all(|r| (r - radius).abs() < f64::EPS.fsqrt() * radius) {
+                let chord = |angle: f64| 2.0 * radius * (angle / 2.0).cos();
+                options.edge_length = EdgeLengthFilter::new(
+                    self.max_dihedral_angle.map(chord),
+                    self.min_dihedral_angle.map(chord),
+                    Vec::new(),
+                );
+            } else {
+                warn!("The facets aren't all at the same distance from the center, ignoring the dihedral angle bounds.");
+            }
+        }
+
+        options.min_inradius = self.max_vertex_distance.map(|d| 1.0 / d);
+        options.max_inradius = self.min_vertex_distance.map(|d| 1.0 / d);
+        options
+    }
+}
+
+impl Concrete {
+    /// Returns the point about which the stellations are found.
+    fn stellation_center(&self, center: StellationCenter) -> Option<Point<f64>> {
+        match center {
+            StellationCenter::Circumcenter => self
+                .circumsphere()
+                .map(|sphere| sphere.center)
+                .or_else(|| self.gravicenter()),
+            StellationCenter::Gravicenter => self.gravicenter(),
+        }
+    }
+
+    /// Enumerates the stellations of a polytope, by reciprocating it about a
+    /// unit sphere, faceting the dual, and reciprocating each faceting back.
+    ///
+    /// The symmetry is the one used to facet the dual. A group of matrices
+    /// acts about the center, and a vertex map acts on the vertices of the
+    /// dual, which are the facets of the polytope in order.
+    ///
+    /// Returns nothing if the polytope has a facet through the center, and
+    /// skips the facetings whose duals are degenerate.
+    pub fn stellations_via_dual(&mut self, symmetry: GroupEnum, options: &StellationOptions) -> Vec<Concrete> {
+        let center = match self.stellation_center(options.center) {
+            Some(center) => center,
+            None => return Vec::new(),
+        };
+        let sphere = Hypersphere::unit(center.len());
+
+        let mut poly = self.clone();
+        poly.recenter_with(&center);
+        let mut dual = match poly.try_dual_with(&sphere) {
+            Ok(dual) => dual,
+            Err(err) => {
+                warn!("Can't stellate by faceting the dual: {}", err);
+                return Vec::new();
+            }
+        };
+
+        let dual_radii: Vec<_> = dual.vertices.iter().map(|v| v.norm()).collect();
+        let faceting_options = options.dual_faceting_options(&dual_radii);
+        let vertices = dual.vertices.to_vec();
+        let facetings = dual.faceting(vertices, symmetry, &faceting_options);
+
+        let mut stellations = Vec::new();
+        for (idx, faceting) in facetings.into_iter().enumerate() {
+            let faceting = faceting.polytope;
+            let mut stellation = match faceting.try_dual_with(&sphere) {
+                Ok(stellation) => stellation,
+                Err(err) => {
+                    warn!("Skipping faceting #{} of the dual: {}", idx, err);
+                    continue;
+                }
+            };
+            stellation.recenter_with(&-&center);
+
+            let index = stellations.len();
+            stellation.meta = self.meta.derive_with(|name| format!("stellation #{} of {}", index, name));
+            if let Some(name) = faceting.meta.name {
+                stellation.meta.properties.insert("dual of".to_string(), name);
+            }
+            stellation.meta.properties.insert("faceting of dual".to_string(), idx.to_string());
+            stellations.push(stellation);
+        }
+
+        stellations
+    }
+}
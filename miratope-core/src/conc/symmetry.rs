@@ -1,16 +1,17 @@
 //! The code used to get the symmetry of a polytope and do operations based on that.
 
-use std::{collections::{BTreeMap, HashSet}, vec, iter::FromIterator};
+use std::{collections::{BTreeMap, HashMap, HashSet}, fmt::Display, vec, iter::FromIterator, ops::Index};
 
 use crate::{
-    abs::{Ranked, flag::{FlagIter, Flag}},
+    abs::{AbstractBuilder, Element, ElementList, Ranked, Ranks, Subelements, Superelements, flag::{FlagIter, Flag}},
     conc::Concrete,
     float::Float,
     group::Group,
-    geometry::{Matrix, Point, PointOrd, Subspace},
+    geometry::{GeomContext, Matrix, Point, PointOrd, Subspace},
     Polytope,
 };
 
+use log::warn;
 use vec_like::*;
 
 use super::ConcretePolytope;
@@ -37,25 +38,68 @@ impl Flag {
     }
 }
 
+/// Some points sorted by [`GeomContext::cmp_points`], so that the images of
+/// the vertices of a polytope under an isometry can be looked up.
+struct PointLookup<'a> {
+    /// The context the points are compared in.
+    context: &'a GeomContext,
+
+    /// The points along with their indices, in order.
+    sorted: Vec<(&'a Point<f64>, usize)>,
+}
+
+impl<'a> PointLookup<'a> {
+    /// Sorts some points.
+    fn new<I: Iterator<Item = &'a Point<f64>>>(points: I, context: &'a GeomContext) -> Self {
+        let mut sorted: Vec<_> = points.zip(0..).collect();
+        sorted.sort_by(|(p, _), (q, _)| context.cmp_points(p, q));
+        Self { context, sorted }
+    }
+
+    /// Returns the index of a point equal to a given one, if any.
+    fn get(&self, p: &Point<f64>) -> Option<usize> {
+        self.sorted
+            .binary_search_by(|(q, _)| self.context.cmp_points(q, p))
+            .ok()
+            .map(|idx| self.sorted[idx].1)
+    }
+}
+
 impl Concrete {
     /// Computes the symmetry group of a polytope, along with a list of vertex mappings.
     pub fn get_symmetry_group(&mut self) -> Option<(Group<vec::IntoIter<Matrix<f64>>>, Vec<Vec<usize>>)> {
+        self.get_symmetry_group_in(None)
+    }
+
+    /// Computes the symmetry group of a polytope like
+    /// [`Self::get_symmetry_group`], comparing the vertices with the tolerance
+    /// of a geometry context, or of the default one if none is given. The
+    /// symmetries are isometries about the center given by the context.
+    pub fn get_symmetry_group_in(
+        &mut self,
+        context: Option<&GeomContext>,
+    ) -> Option<(Group<vec::IntoIter<Matrix<f64>>>, Vec<Vec<usize>>)> {
+        let context = context.copied().unwrap_or_default();
         let mut fixed = self.clone(); // We'll relabel the facets if needed so the first facet isn't hemi.
+        if let Some(center) = context.center_of(self) {
+            fixed.recenter_with(&center);
+        }
 
         let mut facet_idx = 0;
         if self.rank() > 1 {
             while facet_idx < self.el_count(self.rank()-1) {
-                let facet_space = Subspace::from_points(
-                    self.abs.element_and_vertices(self.rank()-1, facet_idx).unwrap().0.iter().map(|x| &self.vertices[*x])
+                let facet_space = Subspace::from_points_in(
+                    self.abs.element_and_vertices(self.rank()-1, facet_idx).unwrap().0.iter().map(|x| &fixed.vertices[*x]),
+                    &context,
                 );
-                if facet_space.distance(&Point::zeros(self.dim().unwrap())) > f64::EPS {
+                if facet_space.distance(&Point::zeros(self.dim().unwrap())) > context.tolerance {
                     break;
                 }
                 facet_idx += 1;
             }
 
             if facet_idx == self.el_count(self.rank()-1) {
-                println!("Symmetry calculation failed. All facets pass through the origin.");
+                warn!("Symmetry calculation failed. All facets pass through the origin.");
                 return None
             }
 
@@ -77,13 +121,9 @@ impl Concrete {
 
         fixed.element_sort();
         let flag_iter = FlagIter::new(&fixed.abs);
-        let (types, types_map_back) = &fixed.element_types_common();
+        let (types, types_map_back) = &fixed.element_types_common_in(&context);
 
-        let mut vertices_pointord = Vec::<PointOrd<f64>>::new();
-        for v in &self.vertices {
-            vertices_pointord.push(PointOrd::new(v.clone()));
-        }
-        let vertices = BTreeMap::from_iter((vertices_pointord).into_iter().zip(0..));
+        let vertices = PointLookup::new(fixed.vertices.iter(), &context);
         let mut vertex_map: Vec<Vec<usize>> = Vec::new();
 
         // Sets of elements' vertex sets.
@@ -117,11 +157,10 @@ impl Concrete {
 
                 // check if vertices match up
                 let mut vertex_map_row = vec![0; fixed.vertices.len()];
-                for vertex in &vertices {
-                    let new_vertex = PointOrd::new(isometry.clone() * vertex.0.matrix());
-                    match vertices.get(&new_vertex) {
-                        Some(idx) => {
-                            vertex_map_row[*vertex.1] = *idx;
+                for (idx, vertex) in fixed.vertices.iter().enumerate() {
+                    match vertices.get(&(&isometry * vertex)) {
+                        Some(new_idx) => {
+                            vertex_map_row[idx] = new_idx;
                         }
                         None => {
                             continue 'a;
@@ -153,44 +192,60 @@ impl Concrete {
 
     /// Computes the rotation subgroup of a polytope, along with a list of vertex mappings.
     pub fn get_rotation_group(&mut self) -> Option<(Group<vec::IntoIter<Matrix<f64>>>, Vec<Vec<usize>>)> {
-        if let Some((full_group, full_vertex_map)) = self.get_symmetry_group() {
-            let mut rotation_group = Vec::new();
-            let mut vertex_map = Vec::new();
-    
-            for (idx, el) in full_group.enumerate() {
-                if el.determinant() > 0. {
-                    rotation_group.push(el);
-                    vertex_map.push(full_vertex_map[idx].clone());
-                }
-            }
-    
-            unsafe {
-                Some((Group::new(&self.rank()-1, rotation_group.into_iter()), vertex_map))
-            }
-        }
-        else {
-            None
-        }
+        let (full_group, full_vertex_map) = self.get_symmetry_group()?;
+        Some(rotation_subgroup(self.rank() - 1, full_group, &full_vertex_map))
+    }
+
+    /// Returns a canonical point for each vertex orbit of the polytope under a
+    /// symmetry group, along with the size of the orbit. The vertex map must
+    /// be that of the group. The points are given by
+    /// [`Group::fundamental_domain_representative`].
+    pub fn orbit_representatives<I: Iterator<Item = Matrix<f64>> + Clone>(
+        &self,
+        group: &Group<I>,
+        vertex_map: &[VertexPermutation],
+    ) -> Vec<(Point<f64>, usize)> {
+        let simple_roots = group.simple_roots();
+
+        VertexOrbit::orbits(self.vertices.len(), vertex_map)
+            .into_iter()
+            .map(|orbit| {
+                let vertex = &self.vertices[orbit.representative()];
+                (group.representative_with_roots(vertex, &simple_roots), orbit.len())
+            })
+            .collect()
     }
 
     /// Fills in the vertex map.
     /// A vertex map is an array of (group element, vertex index) with values being the index of the vertex after applying the transformation.
     pub fn get_vertex_map(&mut self, group: Group<vec::IntoIter<Matrix<f64>>>) -> Vec<Vec<usize>> {
-        let mut vertices = Vec::<PointOrd<f64>>::new();
-        for v in &self.vertices {
-            vertices.push(PointOrd::new(v.clone()));
-        }
-        let vertices = BTreeMap::from_iter((vertices).into_iter().zip(0..));
+        self.get_vertex_map_in(group, None)
+    }
+
+    /// Fills in the vertex map like [`Self::get_vertex_map`], comparing the
+    /// vertices with the tolerance of a geometry context, or of the default
+    /// one if none is given. The isometries act about the center given by the
+    /// context.
+    pub fn get_vertex_map_in(
+        &mut self,
+        group: Group<vec::IntoIter<Matrix<f64>>>,
+        context: Option<&GeomContext>,
+    ) -> Vec<Vec<usize>> {
+        let context = context.copied().unwrap_or_default();
+        let centered: Vec<_> = match context.center_of(self) {
+            Some(center) => self.vertices.iter().map(|v| v - &center).collect(),
+            None => self.vertices.to_vec(),
+        };
+        let vertices = PointLookup::new(centered.iter(), &context);
 
         let mut vertex_map: Vec<Vec<usize>> = Vec::new();
 
         for isometry in group {
-            let mut vertex_map_row = Vec::<usize>::new();
-            for vertex in &vertices {
-                let new_vertex = PointOrd::new(isometry.clone() * vertex.0.matrix());
-                match vertices.get(&new_vertex) {
-                    Some(idx) => {
-                        vertex_map_row.push(*idx);
+            let mut vertex_map_row = vec![0; centered.len()];
+            for (idx, vertex) in centered.iter().enumerate() {
+                match vertices.get(&(&isometry * vertex)) {
+                    Some(new_idx) => {
+                        vertex_map_row[idx] = new_idx;
                     }
                     None => {
                         unreachable!();
@@ -201,6 +256,361 @@ impl Concrete {
         }
         vertex_map
     }
+
+    /// Splits the elements of a given rank into their orbits under a vertex
+    /// map, by mapping their vertex sets. The vertex map must contain every
+    /// element of the group, as the one from [`Self::get_symmetry_group`]
+    /// does. Returns the orbit of each element, numbered in order of their
+    /// first element.
+    pub(crate) fn element_orbit_indices(&self, rank: usize, vertex_map: &[Vec<usize>]) -> Vec<usize> {
+        let mut orbits = vec![0; self.el_count(rank)];
+        for (orbit, elements) in element_orbits_under(self, rank, vertex_map).into_iter().enumerate() {
+            for idx in elements {
+                orbits[idx] = orbit;
+            }
+        }
+        orbits
+    }
+
+    /// Builds the compound of a polytope and its image under a linear
+    /// transformation, usually a reflection. Vertices of the image that
+    /// coincide with vertices of the polytope are merged with them.
+    pub fn with_mirror_compound(&self, reflection: &Matrix<f64>) -> Concrete {
+        let mut mirror = self.clone().apply(reflection);
+        mirror.meta = self.meta.derive("mirror image");
+
+        let mut compound = self.clone();
+        compound.comp_append(mirror);
+        compound.merge_vertices();
+        compound.element_sort();
+        compound
+    }
+
+    /// Merges any coincident vertices of a polytope, keeping the first of
+    /// each.
+    fn merge_vertices(&mut self) {
+        let mut indices = BTreeMap::new();
+        let mut vertex_map = Vec::with_capacity(self.vertices.len());
+        let mut vertices = Vec::new();
+        for vertex in &self.vertices {
+            let idx = *indices.entry(PointOrd::new(vertex.clone())).or_insert_with(|| {
+                vertices.push(vertex.clone());
+                vertices.len() - 1
+            });
+            vertex_map.push(idx);
+        }
+
+        if vertices.len() == self.vertices.len() {
+            return;
+        }
+
+        let mut builder = AbstractBuilder::new();
+        for (r, elements) in self.abs.ranks().iter().enumerate() {
+            builder.push_empty();
+            match r {
+                1 => {
+                    for _ in 0..vertices.len() {
+                        builder.push_subs(vec![0].into());
+                    }
+                }
+                2 => {
+                    for edge in elements {
+                        builder.push_subs(edge.subs.iter().map(|v| vertex_map[*v]).collect());
+                    }
+                }
+                _ => {
+                    for el in elements {
+                        builder.push_subs(el.subs.clone());
+                    }
+                }
+            }
+        }
+
+        // Safety: identifying points that were in different components
+        // keeps the structure of every element above the vertices.
+        self.abs = unsafe { builder.build() };
+        self.vertices = vertices.into();
+    }
+
+    /// Checks whether a polytope is a compound of two mirror images. If so,
+    /// returns one of the components along with an orientation-reversing
+    /// isometry taking it to the other.
+    pub fn is_enantiomorph_compound(&self) -> Option<(Concrete, Matrix<f64>)> {
+        let mut poly = self.clone();
+        poly.element_sort();
+        let mut components = poly.defiss();
+        if components.len() != 2 {
+            return None;
+        }
+
+        let other = components.pop().unwrap();
+        let component = components.pop().unwrap();
+        let isometry = find_isometry(&component, &other, |det| det < 0.0)?;
+        Some((component, isometry))
+    }
+}
+
+/// Finds a linear isometry taking a polytope to another, whose determinant
+/// satisfies a predicate.
+pub(crate) fn find_isometry<F: Fn(f64) -> bool>(p: &Concrete, q: &Concrete, det_filter: F) -> Option<Matrix<f64>> {
+    let rank = p.rank();
+    if rank < 2 || rank != q.rank() || (1..=rank).any(|r| p.el_count(r) != q.el_count(r)) {
+        return None;
+    }
+
+    let mut p = p.clone();
+    let mut q = q.clone();
+    p.element_sort();
+    q.element_sort();
+
+    let base_basis_inverse = p.first_flag().vertex_sequence(&p).try_inverse()?;
+    let vertices = BTreeMap::from_iter(q.vertices.iter().map(|v| PointOrd::new(v.clone())).zip(0..));
+
+    // Sets of elements' vertex sets.
+    let elements = Vec::<HashSet<Vec<usize>>>::from_iter(
+        (0..rank).map(|i| HashSet::from_iter(
+            (0..q.el_count(i)).map(|j| {
+                let mut vec = q.abs.element_vertices(i, j).unwrap();
+                vec.sort_unstable();
+                vec
+            }))
+        )
+    );
+
+    let identity = Matrix::<f64>::identity(rank - 1, rank - 1);
+
+    'a: for flag in FlagIter::new(&q.abs) {
+        let isometry = flag.clone().vertex_sequence(&q) * &base_basis_inverse;
+        if !det_filter(isometry.determinant()) || (isometry.transpose() * &isometry - &identity).norm() > f64::EPS.fsqrt() {
+            continue;
+        }
+
+        // check if vertices match up
+        let mut vertex_map_row = Vec::with_capacity(p.vertices.len());
+        for vertex in &p.vertices {
+            match vertices.get(&PointOrd::new(&isometry * vertex)) {
+                Some(idx) => vertex_map_row.push(*idx),
+                None => continue 'a,
+            }
+        }
+
+        // check if elements match up
+        for (r, elements) in elements.iter().enumerate().skip(2) {
+            for idx in 0..p.el_count(r) {
+                let mut new_element_vertices: Vec<usize> = p.abs.element_vertices(r, idx).unwrap().iter().map(|x| vertex_map_row[*x]).collect();
+                new_element_vertices.sort_unstable();
+                if !elements.contains(&new_element_vertices) {
+                    continue 'a;
+                }
+            }
+        }
+
+        return Some(isometry);
+    }
+
+    None
+}
+
+/// The most tuples of vertices counted by [`Concrete::distance_spectrum`]
+/// before giving up.
+const MAX_AUTOMORPHISM_BOUND: u64 = 1 << 20;
+
+/// The vertices of a polytope sorted into classes by their distances to the
+/// others, as returned by [`Concrete::distance_spectrum`]. A symmetry can only
+/// take a vertex to another in the same class, which bounds how many
+/// symmetries there can be, no matter how imprecise the coordinates are.
+#[derive(Clone, Debug)]
+pub struct DistanceSpectrum {
+    /// The classes of vertices whose sorted distances to the others match,
+    /// sorted by their smallest vertex.
+    pub classes: Vec<Vec<usize>>,
+
+    /// An upper bound on the number of isometries permuting the vertices, or
+    /// rather of the ways to map an affine basis of vertices to vertices in
+    /// the same classes with the same distances between them. It stops
+    /// counting at 2²⁰.
+    pub automorphism_bound: u64,
+
+    /// The greatest difference between the sorted distances of two vertices
+    /// in the same class. Symmetries are only found when this is negligible.
+    pub residual: f64,
+}
+
+impl Concrete {
+    /// Sorts the vertices into classes by their distances to the others, and
+    /// bounds the order of the symmetry group by them. Distances are compared
+    /// up to a tolerance relative to the greatest distance between vertices.
+    pub fn distance_spectrum(&self, tolerance: f64) -> DistanceSpectrum {
+        let vertices = &self.vertices;
+        let distance = |i: usize, j: usize| (&vertices[i] - &vertices[j]).norm();
+        let profiles: Vec<Vec<f64>> = (0..vertices.len())
+            .map(|i| {
+                let mut profile: Vec<_> = (0..vertices.len()).filter(|&j| j != i).map(|j| distance(i, j)).collect();
+                profile.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                profile
+            })
+            .collect();
+        let diameter = profiles.iter().filter_map(|profile| profile.last()).fold(0.0, |a: f64, &b| a.max(b));
+        let tolerance = tolerance * diameter;
+        let difference = |a: &[f64], b: &[f64]| a.iter().zip(b).map(|(x, y)| (x - y).abs()).fold(0.0, f64::max);
+
+        // Each vertex goes in the first class whose first vertex it matches.
+        let mut classes: Vec<Vec<usize>> = Vec::new();
+        let mut class_of = Vec::with_capacity(vertices.len());
+        let mut residual = 0.0;
+        for (i, profile) in profiles.iter().enumerate() {
+            let class = classes.iter().position(|class| difference(&profiles[class[0]], profile) <= tolerance);
+            match class {
+                Some(class) => {
+                    residual = classes[class]
+                        .iter()
+                        .map(|&j| difference(&profiles[j], profile))
+                        .fold(residual, f64::max);
+                    classes[class].push(i);
+                    class_of.push(class);
+                }
+                None => {
+                    class_of.push(classes.len());
+                    classes.push(vec![i]);
+                }
+            }
+        }
+
+        // An isometry is determined by where it takes an affine basis.
+        let mut basis = Vec::new();
+        if let Some(first) = vertices.first() {
+            let mut subspace = Subspace::new(first.clone());
+            basis.push(0);
+            for (idx, v) in vertices.iter().enumerate().skip(1) {
+                if subspace.add(v).is_some() {
+                    basis.push(idx);
+                }
+            }
+        }
+
+        let mut images = Vec::with_capacity(basis.len());
+        let automorphism_bound = count_images(&basis, &classes, &class_of, &distance, tolerance, &mut images);
+
+        DistanceSpectrum {
+            classes,
+            automorphism_bound,
+            residual,
+        }
+    }
+}
+
+/// Counts the ways to extend some images of the first vertices of a basis to
+/// the whole basis, keeping each vertex in its class and the distances
+/// between them, up to [`MAX_AUTOMORPHISM_BOUND`].
+fn count_images<F: Fn(usize, usize) -> f64>(
+    basis: &[usize],
+    classes: &[Vec<usize>],
+    class_of: &[usize],
+    distance: &F,
+    tolerance: f64,
+    images: &mut Vec<usize>,
+) -> u64 {
+    let next = images.len();
+    if next == basis.len() {
+        return 1;
+    }
+
+    let mut count = 0;
+    for &image in &classes[class_of[basis[next]]] {
+        let fits = !images.contains(&image)
+            && images
+                .iter()
+                .zip(basis)
+                .all(|(&prev, &b)| (distance(prev, image) - distance(b, basis[next])).abs() <= tolerance);
+        if fits {
+            images.push(image);
+            count += count_images(basis, classes, class_of, distance, tolerance, images);
+            images.pop();
+            if count >= MAX_AUTOMORPHISM_BOUND {
+                return MAX_AUTOMORPHISM_BOUND;
+            }
+        }
+    }
+    count
+}
+
+/// The number of orbits of the elements of each rank of a polytope under its
+/// full symmetry group, as returned by [`Concrete::transitivity`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransitivityReport {
+    /// The order of the symmetry group.
+    pub group_order: usize,
+
+    /// The number of orbits of the elements of each rank, from the vertices
+    /// to the facets.
+    pub orbit_counts: Vec<usize>,
+}
+
+impl TransitivityReport {
+    /// Returns the number of orbits of the elements of a given rank, given as
+    /// for [`Ranked::el_count`], so that the vertices have rank 1. Returns
+    /// `None` for the minimal and maximal elements and for ranks the polytope
+    /// doesn't have.
+    pub fn orbit_count(&self, rank: usize) -> Option<usize> {
+        self.orbit_counts.get(rank.checked_sub(1)?).copied()
+    }
+
+    /// Returns whether the symmetries are transitive on the elements of a
+    /// given rank, given as for [`Self::orbit_count`].
+    pub fn is_transitive_on(&self, rank: usize) -> bool {
+        self.orbit_count(rank) == Some(1)
+    }
+
+    /// Returns whether the polytope is vertex-transitive, or isogonal.
+    pub fn is_vertex_transitive(&self) -> bool {
+        self.is_transitive_on(1)
+    }
+
+    /// Returns whether the polytope is edge-transitive, or isotoxal.
+    pub fn is_edge_transitive(&self) -> bool {
+        self.is_transitive_on(2)
+    }
+
+    /// Returns whether the polytope is facet-transitive, or isotopic.
+    pub fn is_facet_transitive(&self) -> bool {
+        self.orbit_counts.last() == Some(&1)
+    }
+
+    /// Returns whether the polytope is noble, that is, both vertex- and
+    /// facet-transitive.
+    pub fn is_noble(&self) -> bool {
+        self.is_vertex_transitive() && self.is_facet_transitive()
+    }
+}
+
+impl Display for TransitivityReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "symmetry group of order {}, with element orbits {:?}",
+            self.group_order, self.orbit_counts
+        )
+    }
+}
+
+impl Concrete {
+    /// Counts the orbits of the elements of each rank under the full symmetry
+    /// group of the polytope, from the vertices to the facets. The elements
+    /// are matched by their sets of vertices, so this can tell apart whether
+    /// a faceting is isogonal, isotoxal, isotopic, or noble. Returns `None`
+    /// if the symmetry group can't be computed, see
+    /// [`Self::get_symmetry_group`].
+    pub fn transitivity(&mut self) -> Option<TransitivityReport> {
+        let (_, vertex_map) = self.get_symmetry_group()?;
+        let orbit_counts = (1..self.rank())
+            .map(|rank| element_orbits_under(self, rank, &vertex_map).len())
+            .collect();
+
+        Some(TransitivityReport {
+            group_order: vertex_map.len(),
+            orbit_counts,
+        })
+    }
 }
 
 /// A set of vertices.
@@ -208,7 +618,20 @@ pub struct Vertices(pub Vec<Point<f64>>);
 
 impl Vertices {
     /// Uses the provided symmetry group on the vertices, also outputs the new vertex map.
+    ///
+    /// # Panics
+    /// Panics if the matrices of the group don't actually form a group, see
+    /// [`Self::try_copy_by_symmetry`].
     pub fn copy_by_symmetry(&self, group: Group<vec::IntoIter<Matrix<f64>>>) -> (Self, Vec<Vec<usize>>) {
+        self.try_copy_by_symmetry(group)
+            .expect("the matrices of the group aren't closed under composition")
+    }
+
+    /// Uses the provided symmetry group on the vertices, also outputs the new
+    /// vertex map. Returns `None` if some matrix sends a copied vertex outside
+    /// of the copies, which happens when the matrices don't form a group, such
+    /// as when they're read from a file.
+    pub fn try_copy_by_symmetry(&self, group: Group<vec::IntoIter<Matrix<f64>>>) -> Option<(Self, Vec<Vec<usize>>)> {
         let mut vertices = BTreeMap::<PointOrd<f64>, usize>::new();
         let mut vertices_vec = Vec::new();
         let mut c = 0;
@@ -232,21 +655,840 @@ impl Vertices {
             let mut vertex_map_row = Vec::<usize>::new();
             for vertex in &vertices_vec {
                 let new_vertex = PointOrd::new(isometry.clone() * vertex.matrix());
-                match vertices.get(&new_vertex) {
-                    Some(idx) => {
-                        vertex_map_row.push(*idx);
-                    }
-                    None => {
-                        unreachable!();
-                    }
-                }
+                vertex_map_row.push(*vertices.get(&new_vertex)?);
             }
             vertex_map.push(vertex_map_row);
         }
-        
-        (
+
+        Some((
             Vertices(Vec::from_iter(vertices_vec.into_iter().map(|point| point.0))),
             vertex_map,
-        )
+        ))
     }
-}
\ No newline at end of file
+}
+
+/// Returns the rotations in a symmetry group of the given dimension, along
+/// with their rows of its vertex map.
+pub fn rotation_subgroup(
+    dim: usize,
+    full_group: Group<vec::IntoIter<Matrix<f64>>>,
+    full_vertex_map: &[Vec<usize>],
+) -> (Group<vec::IntoIter<Matrix<f64>>>, Vec<Vec<usize>>) {
+    let mut rotation_group = Vec::new();
+    let mut vertex_map = Vec::new();
+
+    for (idx, el) in full_group.enumerate() {
+        if el.determinant() > 0. {
+            rotation_group.push(el);
+            vertex_map.push(full_vertex_map[idx].clone());
+        }
+    }
+
+    // Safety: the rotations in a group form a group.
+    unsafe { (Group::new(dim, rotation_group.into_iter()), vertex_map) }
+}
+
+/// A permutation of the vertices of a polytope, such as a row of a vertex map.
+/// It sends the vertex `i` to the vertex `self[i]`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct VertexPermutation(pub Vec<usize>);
+
+impl VertexPermutation {
+    /// Returns the identity permutation on a number of vertices.
+    pub fn identity(vertex_count: usize) -> Self {
+        Self((0..vertex_count).collect())
+    }
+
+    /// Returns the number of vertices the permutation acts on.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns whether the permutation acts on no vertices.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns an iterator over the images of the vertices.
+    pub fn iter(&self) -> std::slice::Iter<'_, usize> {
+        self.0.iter()
+    }
+
+    /// Returns the permutation that applies `other` and then `self`.
+    pub fn compose(&self, other: &Self) -> Self {
+        Self(other.iter().map(|v| self[*v]).collect())
+    }
+
+    /// Returns the inverse permutation.
+    pub fn inverse(&self) -> Self {
+        let mut inverse = vec![0; self.len()];
+        for (v, w) in self.iter().enumerate() {
+            inverse[*w] = v;
+        }
+        Self(inverse)
+    }
+
+    /// Applies the permutation to some vertices, and returns their images as a
+    /// sorted list.
+    pub fn apply(&self, vertices: &[usize]) -> VertexList {
+        VertexList::new(vertices.iter().map(|v| self[*v]).collect())
+    }
+
+    /// Applies the permutation to a polytope in the format used by the
+    /// faceting algorithm, where the edges store vertex indices and the rest
+    /// of the ranks only refer to the edges. The edges keep their order, and
+    /// lose their superelements.
+    pub fn apply_to_ranks(&self, ranks: &Ranks) -> Ranks {
+        let mut new_ranks = ranks.clone();
+
+        let mut new_list = ElementList::new();
+        for edge in &ranks[2] {
+            let mut new = Element::new(Subelements::new(), Superelements::new());
+            for sub in &edge.subs {
+                new.subs.push(self[*sub]);
+            }
+            new_list.push(new);
+        }
+        new_ranks[2] = new_list;
+
+        new_ranks
+    }
+
+    /// Restricts the permutation to a sorted subset of the vertices, which are
+    /// renumbered by their position in it. Returns `None` if the permutation
+    /// doesn't send the subset to itself.
+    pub fn restrict(&self, subset: &[usize]) -> Option<Self> {
+        subset
+            .iter()
+            .map(|&v| subset.binary_search(&self[v]).ok())
+            .collect::<Option<Vec<_>>>()
+            .map(Self)
+    }
+}
+
+/// Returns the rows of a vertex map that send a sorted subset of the vertices
+/// to itself. These form the setwise stabilizer of the subset.
+pub fn stabilizer(vertex_map: &[VertexPermutation], subset: &[usize]) -> Vec<VertexPermutation> {
+    vertex_map
+        .iter()
+        .filter(|row| subset.iter().all(|&v| subset.binary_search(&row[v]).is_ok()))
+        .cloned()
+        .collect()
+}
+
+/// Returns the permutations that the rows of the stabilizer of a sorted subset
+/// of the vertices induce on it, see [`stabilizer`], with the vertices
+/// renumbered by their position in the subset. There's one permutation for
+/// each row of the stabilizer, so those that act the same on the subset give
+/// the same permutation more than once.
+pub fn induced_stabilizer(vertex_map: &[VertexPermutation], subset: &[usize]) -> Vec<VertexPermutation> {
+    vertex_map.iter().filter_map(|row| row.restrict(subset)).collect()
+}
+
+/// Restricts a vertex map to a sorted subset of the vertices, keeping only the
+/// rows that send the subset to itself and renumbering the vertices by their
+/// position in it. The result is normalized, see [`normalize_vertex_map`], as
+/// different rows may act the same on the subset.
+pub fn restrict_vertex_map(vertex_map: &[VertexPermutation], subset: &[usize]) -> Vec<VertexPermutation> {
+    normalize_vertex_map(induced_stabilizer(vertex_map, subset))
+}
+
+/// Removes the repeated rows of a vertex map, keeping the first copy of each,
+/// and moves the identity to the front, adding it if it's missing. Afterwards,
+/// the number of rows is the order of the group they form.
+pub fn normalize_vertex_map(vertex_map: Vec<VertexPermutation>) -> Vec<VertexPermutation> {
+    let identity = match vertex_map.first() {
+        Some(row) => VertexPermutation::identity(row.len()),
+        None => return vertex_map,
+    };
+
+    let mut seen = HashSet::new();
+    seen.insert(identity.clone());
+    let mut normalized = vec![identity];
+    for row in vertex_map {
+        if !seen.contains(&row) {
+            seen.insert(row.clone());
+            normalized.push(row);
+        }
+    }
+
+    normalized
+}
+
+/// Any way in which a vertex map fails to be that of a symmetry group.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VertexMapError {
+    /// A row doesn't act on every vertex.
+    WrongLength {
+        /// The index of the row.
+        row: usize,
+
+        /// The length of the row.
+        len: usize,
+
+        /// The number of vertices.
+        vertex_count: usize,
+    },
+
+    /// A row sends two vertices to the same one, or a vertex to one that
+    /// doesn't exist.
+    NotAPermutation {
+        /// The index of the row.
+        row: usize,
+    },
+
+    /// No row is the identity.
+    MissingIdentity,
+
+    /// Applying one row and then another gives a permutation that isn't a
+    /// row.
+    NotClosed {
+        /// The index of the row applied first.
+        first: usize,
+
+        /// The index of the row applied second.
+        second: usize,
+    },
+
+    /// The rotation group doesn't have index 1 or 2 in the full group.
+    RotationIndex {
+        /// The order of the rotation group.
+        rotation_order: usize,
+
+        /// The order of the full group.
+        full_order: usize,
+    },
+
+    /// An orbit of the full group isn't split into one or two orbits of the
+    /// same size by the rotation group.
+    InconsistentOrbit {
+        /// A vertex in the orbit.
+        vertex: usize,
+
+        /// The size of its orbit under the full group.
+        full_size: usize,
+
+        /// The size of its orbit under the rotation group.
+        rotation_size: usize,
+    },
+}
+
+impl Display for VertexMapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::WrongLength { row, len, vertex_count } => write!(
+                f,
+                "row {} of the vertex map has length {}, but there are {} vertices",
+                row, len, vertex_count
+            ),
+            Self::NotAPermutation { row } => write!(f, "row {} of the vertex map isn't a permutation", row),
+            Self::MissingIdentity => write!(f, "no row of the vertex map is the identity"),
+            Self::NotClosed { first, second } => write!(
+                f,
+                "applying row {} and then row {} of the vertex map gives a permutation that isn't a row, \
+                so the rows don't form a group",
+                first, second
+            ),
+            Self::RotationIndex { rotation_order, full_order } => write!(
+                f,
+                "the rotation group has order {}, which isn't the order {} of the full group or half of it",
+                rotation_order, full_order
+            ),
+            Self::InconsistentOrbit { vertex, full_size, rotation_size } => write!(
+                f,
+                "vertex {} has an orbit of size {} under the full group, but one of size {} under the rotation \
+                group, which should be the same or half",
+                vertex, full_size, rotation_size
+            ),
+        }
+    }
+}
+
+impl std::error::Error for VertexMapError {}
+
+/// Checks that a vertex map is made out of permutations of some vertices that
+/// form a group, as the map of a symmetry group should be. The rows should be
+/// distinct, as after [`normalize_vertex_map`].
+///
+/// Rather than composing every pair of rows, this builds a generating set
+/// from some of the rows, and checks that the rows are closed under composing
+/// with the generators. This only takes a time proportional to the order of
+/// the group times its number of generators.
+pub fn validate_vertex_map(vertex_map: &[VertexPermutation], vertex_count: usize) -> Result<(), VertexMapError> {
+    check_permutations(vertex_map, vertex_count)?;
+
+    let index_of: HashMap<&VertexPermutation, usize> = vertex_map.iter().enumerate().map(|(idx, row)| (row, idx)).collect();
+    let identity = VertexPermutation::identity(vertex_count);
+    let identity_idx = match index_of.get(&identity) {
+        Some(&idx) => idx,
+        None => return Err(VertexMapError::MissingIdentity),
+    };
+
+    // The rows reached from the identity by the generators so far.
+    let mut reached = vec![false; vertex_map.len()];
+    reached[identity_idx] = true;
+    let mut reached_list = vec![identity_idx];
+    let mut generators = Vec::new();
+
+    for new in 0..vertex_map.len() {
+        if reached[new] {
+            continue;
+        }
+        generators.push(new);
+
+        // Every reached row has to be composed with the new generator, and
+        // every row reached from now on with all of them.
+        let mut queue: Vec<(usize, usize)> = reached_list.iter().map(|&row| (row, new)).collect();
+        while let Some((row, generator)) = queue.pop() {
+            let product = vertex_map[generator].compose(&vertex_map[row]);
+            let idx = match index_of.get(&product) {
+                Some(&idx) => idx,
+                None => return Err(VertexMapError::NotClosed { first: row, second: generator }),
+            };
+
+            if !reached[idx] {
+                reached[idx] = true;
+                reached_list.push(idx);
+                queue.extend(generators.iter().map(|&generator| (idx, generator)));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that every row of a vertex map is a permutation of some vertices.
+fn check_permutations(vertex_map: &[VertexPermutation], vertex_count: usize) -> Result<(), VertexMapError> {
+    for (row, perm) in vertex_map.iter().enumerate() {
+        if perm.len() != vertex_count {
+            return Err(VertexMapError::WrongLength {
+                row,
+                len: perm.len(),
+                vertex_count,
+            });
+        }
+
+        let mut seen = vec![false; vertex_count];
+        for &v in perm.iter() {
+            if v >= vertex_count || seen[v] {
+                return Err(VertexMapError::NotAPermutation { row });
+            }
+            seen[v] = true;
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that a rotation group is consistent with the full symmetry group it
+/// comes from, given by their vertex maps: it must have index 1 or 2, and
+/// must split each orbit of the full group into one orbit or two of the same
+/// size.
+pub fn check_rotation_orbits(
+    rotations: &[VertexPermutation],
+    full: &[VertexPermutation],
+    vertex_count: usize,
+) -> Result<(), VertexMapError> {
+    if rotations.len() != full.len() && 2 * rotations.len() != full.len() {
+        return Err(VertexMapError::RotationIndex {
+            rotation_order: rotations.len(),
+            full_order: full.len(),
+        });
+    }
+
+    let rotation_orbits = VertexOrbit::orbits(vertex_count, rotations);
+    for orbit in VertexOrbit::orbits(vertex_count, full) {
+        let parts: Vec<_> = rotation_orbits
+            .iter()
+            .filter(|part| orbit.iter().any(|&v| part.contains(v)))
+            .collect();
+        let consistent = match parts.as_slice() {
+            [part] => part.len() == orbit.len(),
+            [a, b] => a.len() == b.len() && a.len() + b.len() == orbit.len(),
+            _ => false,
+        };
+
+        if !consistent {
+            let vertex = orbit.representative();
+            let rotation_size = rotation_orbits.iter().find(|part| part.contains(vertex)).map_or(0, VertexOrbit::len);
+            return Err(VertexMapError::InconsistentOrbit {
+                vertex,
+                full_size: orbit.len(),
+                rotation_size,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+impl Index<usize> for VertexPermutation {
+    type Output = usize;
+
+    fn index(&self, index: usize) -> &usize {
+        &self.0[index]
+    }
+}
+
+impl From<Vec<usize>> for VertexPermutation {
+    fn from(vec: Vec<usize>) -> Self {
+        Self(vec)
+    }
+}
+
+/// The vertex map of a symmetry group: the permutation of the vertices given
+/// by each of its symmetries, one per row.
+///
+/// A vertex map converted from its rows isn't checked until it's used, such
+/// as by the faceting. Use [`Self::new_checked`] to check it right away.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VertexMap(Vec<VertexPermutation>);
+
+impl VertexMap {
+    /// Builds a vertex map on some vertices from its rows, after checking that
+    /// each of them is a permutation of the vertices. If `closed` is set, the
+    /// rows are also checked to form a group, see [`validate_vertex_map`].
+    pub fn new_checked(rows: Vec<Vec<usize>>, vertex_count: usize, closed: bool) -> Result<Self, VertexMapError> {
+        let vertex_map = Self::from(rows);
+        if closed {
+            validate_vertex_map(&vertex_map.0, vertex_count)?;
+        } else {
+            check_permutations(&vertex_map.0, vertex_count)?;
+        }
+        Ok(vertex_map)
+    }
+
+    /// Returns the trivial vertex map on some vertices, whose only row is the
+    /// identity.
+    pub fn identity(vertex_count: usize) -> Self {
+        Self(vec![VertexPermutation::identity(vertex_count)])
+    }
+
+    /// Returns the rows of the vertex map.
+    pub fn rows(&self) -> &[VertexPermutation] {
+        &self.0
+    }
+
+    /// Returns the rows of the vertex map, consuming it.
+    pub fn into_rows(self) -> Vec<VertexPermutation> {
+        self.0
+    }
+
+    /// Returns the number of rows of the vertex map.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns whether the vertex map has no rows.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the number of distinct rows of the vertex map, which is the
+    /// order of the group when they form one.
+    pub fn order(&self) -> usize {
+        self.0.iter().collect::<HashSet<_>>().len()
+    }
+
+    /// Returns the index of a row equal to a permutation, if there's any.
+    pub fn position(&self, perm: &VertexPermutation) -> Option<usize> {
+        self.0.iter().position(|row| row == perm)
+    }
+
+    /// Returns the permutation that applies the row `first` and then the row
+    /// `second`.
+    ///
+    /// # Panics
+    /// Panics if there's no such rows.
+    pub fn compose(&self, first: usize, second: usize) -> VertexPermutation {
+        self.0[second].compose(&self.0[first])
+    }
+
+    /// Returns the inverse of a row.
+    ///
+    /// # Panics
+    /// Panics if there's no such row.
+    pub fn inverse(&self, row: usize) -> VertexPermutation {
+        self.0[row].inverse()
+    }
+
+    /// Returns the orbit of a vertex: the vertices it's sent to by applying
+    /// the rows any number of times.
+    ///
+    /// # Panics
+    /// Panics if some row doesn't act on a vertex in the orbit.
+    pub fn orbit_of(&self, vertex: usize) -> VertexOrbit {
+        let mut orbit = vec![vertex];
+        let mut seen = HashSet::new();
+        seen.insert(vertex);
+
+        let mut idx = 0;
+        while let Some(&v) = orbit.get(idx) {
+            for row in &self.0 {
+                if seen.insert(row[v]) {
+                    orbit.push(row[v]);
+                }
+            }
+            idx += 1;
+        }
+
+        orbit.sort_unstable();
+        VertexOrbit(orbit)
+    }
+}
+
+impl From<Vec<VertexPermutation>> for VertexMap {
+    fn from(rows: Vec<VertexPermutation>) -> Self {
+        Self(rows)
+    }
+}
+
+impl From<Vec<Vec<usize>>> for VertexMap {
+    fn from(rows: Vec<Vec<usize>>) -> Self {
+        Self(rows.into_iter().map(VertexPermutation::from).collect())
+    }
+}
+
+/// A set of vertex indices, such as the vertices on a hyperplane. It's always
+/// kept sorted, so that equal sets compare equal.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct VertexList(Vec<usize>);
+
+impl VertexList {
+    /// Builds a list from some vertex indices in any order.
+    pub fn new(mut vertices: Vec<usize>) -> Self {
+        vertices.sort_unstable();
+        Self(vertices)
+    }
+
+    /// Returns the number of vertices in the list.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns whether the list is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns whether the list contains a vertex.
+    pub fn contains(&self, vertex: usize) -> bool {
+        self.0.binary_search(&vertex).is_ok()
+    }
+
+    /// Returns an iterator over the vertices, in increasing order.
+    pub fn iter(&self) -> std::slice::Iter<'_, usize> {
+        self.0.iter()
+    }
+
+    /// Returns the vertices as a slice.
+    pub fn as_slice(&self) -> &[usize] {
+        &self.0
+    }
+
+    /// Returns the inner vector of vertices.
+    pub fn into_vec(self) -> Vec<usize> {
+        self.0
+    }
+}
+
+impl Index<usize> for VertexList {
+    type Output = usize;
+
+    fn index(&self, index: usize) -> &usize {
+        &self.0[index]
+    }
+}
+
+impl<'a> IntoIterator for &'a VertexList {
+    type Item = &'a usize;
+    type IntoIter = std::slice::Iter<'a, usize>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl From<Vec<usize>> for VertexList {
+    fn from(vec: Vec<usize>) -> Self {
+        Self::new(vec)
+    }
+}
+
+/// An orbit of vertices under a group. Its first vertex is used as the
+/// representative.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VertexOrbit(Vec<usize>);
+
+impl VertexOrbit {
+    /// Splits the vertices into their orbits under a vertex map. The orbits
+    /// are sorted by their smallest vertex, which is also their representative.
+    pub fn orbits(vertex_count: usize, vertex_map: &[VertexPermutation]) -> Vec<Self> {
+        orbits_by(vertex_count, vertex_map, |v, row| Some(row[v]))
+            .into_iter()
+            .map(Self)
+            .collect()
+    }
+
+    /// Returns the representative of the orbit.
+    pub fn representative(&self) -> usize {
+        self.0[0]
+    }
+
+    /// Returns whether the orbit contains a vertex.
+    pub fn contains(&self, vertex: usize) -> bool {
+        self.0.contains(&vertex)
+    }
+
+    /// Returns the number of vertices in the orbit.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns whether the orbit is empty. This never happens for orbits
+    /// returned by [`Self::orbits`].
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns an iterator over the vertices of the orbit.
+    pub fn iter(&self) -> std::slice::Iter<'_, usize> {
+        self.0.iter()
+    }
+}
+
+/// Splits some objects into their orbits under a vertex map, given the image
+/// of each object under each of its rows. Objects without an image under some
+/// row are left out of that step. The orbits are sorted by their smallest
+/// object, which goes first even if the identity isn't the first row.
+fn orbits_by<R, F: FnMut(usize, &R) -> Option<usize>>(
+    count: usize,
+    vertex_map: &[R],
+    mut image: F,
+) -> Vec<Vec<usize>> {
+    let mut orbits = Vec::new();
+    let mut checked = vec![false; count];
+
+    for start in 0..count {
+        if !checked[start] {
+            // We found a new orbit.
+            let mut orbit = vec![start];
+            checked[start] = true;
+            for row in vertex_map {
+                if let Some(c) = image(start, row) {
+                    if !checked[c] {
+                        orbit.push(c);
+                        checked[c] = true;
+                    }
+                }
+            }
+            orbits.push(orbit);
+        }
+    }
+
+    orbits
+}
+
+/// Splits the elements of a given rank into their orbits under a vertex map,
+/// by mapping their vertex sets. The rank is given as for
+/// [`Ranked::el_count`], so that the vertices have rank 1. The orbits are
+/// sorted by their smallest element.
+///
+/// For the vertices, these are the orbits from [`VertexOrbit::orbits`]. Any
+/// rows indexed by the vertices can be used, like those of a vertex map from
+/// [`Concrete::get_symmetry_group`].
+pub(crate) fn element_orbits_under<R: Index<usize, Output = usize>>(
+    poly: &Concrete,
+    rank: usize,
+    vertex_map: &[R],
+) -> Vec<Vec<usize>> {
+    if rank == 1 {
+        return orbits_by(poly.vertex_count(), vertex_map, |v, row| Some(row[v]));
+    }
+
+    let element_vertices: Vec<_> = (0..poly.el_count(rank))
+        .map(|idx| {
+            let mut list = poly.abs.element_vertices(rank, idx).unwrap();
+            list.sort_unstable();
+            list
+        })
+        .collect();
+    let index: HashMap<_, _> = element_vertices.iter().enumerate().map(|(idx, list)| (list, idx)).collect();
+
+    orbits_by(element_vertices.len(), vertex_map, |idx, row| {
+        let mut image: Vec<_> = element_vertices[idx].iter().map(|&v| row[v]).collect();
+        image.sort_unstable();
+        index.get(&image).copied()
+    })
+}
+
+impl From<VertexOrbit> for VertexList {
+    fn from(orbit: VertexOrbit) -> Self {
+        Self::new(orbit.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A triangle, stored like the faceting algorithm stores its facets.
+    fn triangle() -> Ranks {
+        vec![
+            vec![Element::new(vec![].into(), vec![].into())].into(),
+            vec![Element::new(vec![0].into(), vec![].into()); 3].into(),
+            vec![
+                Element::new(vec![0, 1].into(), vec![].into()),
+                Element::new(vec![1, 2].into(), vec![].into()),
+                Element::new(vec![2, 0].into(), vec![].into()),
+            ]
+            .into(),
+            vec![Element::new(vec![0, 1, 2].into(), vec![].into())].into(),
+        ]
+        .into()
+    }
+
+    #[test]
+    fn compose_inverse() {
+        let p = VertexPermutation(vec![1, 2, 0, 3]);
+        let q = VertexPermutation(vec![3, 0, 1, 2]);
+
+        assert_eq!(p.compose(&q), VertexPermutation(vec![3, 1, 2, 0]));
+        assert_eq!(p.compose(&p.inverse()), VertexPermutation::identity(4));
+        assert_eq!(p.apply(&[0, 2]), VertexList::new(vec![0, 1]));
+    }
+
+    #[test]
+    fn apply_to_ranks() {
+        let triangle = triangle();
+        let p = VertexPermutation(vec![1, 2, 0]);
+        let image = p.apply_to_ranks(&triangle);
+        let edges = |ranks: &Ranks| -> Vec<Vec<usize>> {
+            ranks[2].iter().map(|el| el.subs.iter().copied().collect()).collect()
+        };
+        assert_eq!(edges(&image), vec![vec![1, 2], vec![2, 0], vec![0, 1]]);
+
+        // Only the edges change.
+        assert_eq!(image[3], triangle[3]);
+        assert_eq!(edges(&p.inverse().apply_to_ranks(&image)), edges(&triangle));
+    }
+
+    #[test]
+    fn orbits() {
+        // The group generated by swapping 0 and 1.
+        let vertex_map = vec![VertexPermutation::identity(3), VertexPermutation(vec![1, 0, 2])];
+        let orbits = VertexOrbit::orbits(3, &vertex_map);
+
+        assert_eq!(orbits.len(), 2);
+        assert_eq!(orbits[0].representative(), 0);
+        assert!(orbits[0].contains(1));
+        assert_eq!(orbits[1].len(), 1);
+
+        // The same group, without the identity.
+        let orbits = VertexOrbit::orbits(3, &vertex_map[1..]);
+        assert_eq!(orbits.len(), 2);
+        assert_eq!(orbits[0].representative(), 0);
+        assert_eq!(orbits[1].representative(), 2);
+    }
+
+    #[test]
+    fn normalize() {
+        let swap = VertexPermutation(vec![1, 0, 2]);
+        let identity = VertexPermutation::identity(3);
+
+        let normalized = vec![identity.clone(), swap.clone()];
+        assert_eq!(normalize_vertex_map(vec![swap.clone(), identity.clone(), swap.clone()]), normalized);
+        assert_eq!(normalize_vertex_map(vec![swap.clone(), swap]), normalized);
+        assert_eq!(normalize_vertex_map(normalized.clone()), normalized);
+        assert!(normalize_vertex_map(Vec::new()).is_empty());
+    }
+
+    /// Builds a vertex map from its rows.
+    fn vertex_map(rows: &[&[usize]]) -> Vec<VertexPermutation> {
+        rows.iter().map(|row| VertexPermutation(row.to_vec())).collect()
+    }
+
+    #[test]
+    fn validate() {
+        // The cyclic group generated by a 4-cycle.
+        let cyclic = vertex_map(&[&[0, 1, 2, 3], &[1, 2, 3, 0], &[2, 3, 0, 1], &[3, 0, 1, 2]]);
+        assert_eq!(validate_vertex_map(&cyclic, 4), Ok(()));
+
+        // The symmetries of a cube are a group, however they're generated.
+        let mut cube = Concrete::hypercube(4);
+        let rows = cube.get_symmetry_group().unwrap().1;
+        let full = normalize_vertex_map(rows.into_iter().map(VertexPermutation).collect());
+        assert_eq!(validate_vertex_map(&full, 8), Ok(()));
+
+        assert_eq!(
+            validate_vertex_map(&cyclic[..3], 4),
+            Err(VertexMapError::NotClosed { first: 2, second: 1 })
+        );
+        assert_eq!(validate_vertex_map(&cyclic[1..], 4), Err(VertexMapError::MissingIdentity));
+        assert_eq!(
+            validate_vertex_map(&vertex_map(&[&[0, 1, 2, 3], &[0, 0, 2, 3]]), 4),
+            Err(VertexMapError::NotAPermutation { row: 1 })
+        );
+        assert_eq!(
+            validate_vertex_map(&cyclic, 5),
+            Err(VertexMapError::WrongLength { row: 0, len: 4, vertex_count: 5 })
+        );
+    }
+
+    #[test]
+    fn checked_vertex_map() {
+        let rows = vec![vec![0, 1, 2, 3], vec![1, 2, 3, 0], vec![2, 3, 0, 1], vec![3, 0, 1, 2]];
+        let cyclic = VertexMap::new_checked(rows.clone(), 4, true).unwrap();
+        assert_eq!(cyclic.order(), 4);
+        assert_eq!(cyclic.compose(1, 2), VertexPermutation(vec![3, 0, 1, 2]));
+        assert_eq!(cyclic.position(&cyclic.compose(1, 1)), Some(2));
+        assert_eq!(cyclic.inverse(1), VertexPermutation(vec![3, 0, 1, 2]));
+        assert_eq!(cyclic.orbit_of(2).len(), 4);
+        assert_eq!(cyclic.orbit_of(2).representative(), 0);
+
+        // Only the permutations are checked unless the map has to be closed.
+        assert_eq!(
+            VertexMap::new_checked(rows[..3].to_vec(), 4, true),
+            Err(VertexMapError::NotClosed { first: 2, second: 1 })
+        );
+        let open = VertexMap::new_checked(rows[..2].to_vec(), 4, false).unwrap();
+        assert_eq!(open.orbit_of(3).len(), 4);
+        assert_eq!(
+            VertexMap::new_checked(vec![vec![0, 1, 2], vec![1, 2, 2]], 3, false),
+            Err(VertexMapError::NotAPermutation { row: 1 })
+        );
+        assert_eq!(
+            VertexMap::new_checked(vec![vec![0, 1]], 3, false),
+            Err(VertexMapError::WrongLength { row: 0, len: 2, vertex_count: 3 })
+        );
+
+        // Repeated rows only count once.
+        let repeated = VertexMap::from(vec![vec![0, 1], vec![1, 0], vec![1, 0]]);
+        assert_eq!(repeated.len(), 3);
+        assert_eq!(repeated.order(), 2);
+        assert_eq!(VertexMap::identity(3).orbit_of(1).len(), 1);
+    }
+
+    #[test]
+    fn rotation_orbits() {
+        // Swapping 0 and 1 is a reflection, which splits their orbit.
+        let full = vertex_map(&[&[0, 1, 2], &[1, 0, 2]]);
+        let rotations = vertex_map(&[&[0, 1, 2]]);
+        assert_eq!(check_rotation_orbits(&rotations, &full, 3), Ok(()));
+        assert_eq!(check_rotation_orbits(&full, &full, 3), Ok(()));
+
+        // A rotation group that isn't a subgroup of the full group.
+        let full = vertex_map(&[&[0, 1, 2, 3], &[0, 1, 3, 2]]);
+        let rotations = vertex_map(&[&[0, 1, 2, 3], &[1, 0, 2, 3]]);
+        assert_eq!(
+            check_rotation_orbits(&rotations, &full, 4),
+            Err(VertexMapError::InconsistentOrbit { vertex: 0, full_size: 1, rotation_size: 2 })
+        );
+
+        let full = vertex_map(&[&[0, 1, 2], &[1, 2, 0], &[2, 0, 1]]);
+        assert_eq!(
+            check_rotation_orbits(&full[..1], &full, 3),
+            Err(VertexMapError::RotationIndex { rotation_order: 1, full_order: 3 })
+        );
+    }
+}
@@ -0,0 +1,260 @@
+//! Identifies uniform polytopes by matching a few of their invariants against
+//! a table of known ones.
+//!
+//! The table contains the 75 uniform polyhedra and the 47 convex uniform
+//! polychora that aren't prisms. Each entry stores the element counts of the
+//! polytope, its vertex configuration, and its circumradius, which together
+//! single out almost every entry. The few that share them with some other
+//! polytope, like the rhombicuboctahedron does with the pseudo-
+//! rhombicuboctahedron, also store canonical coordinates, which are compared
+//! by congruence.
+
+mod table;
+
+use std::fmt::Display;
+
+use super::{cycle::CycleList, symmetry::find_isometry, Concrete, ConcretePolytope};
+use crate::{abs::Ranked, file::FromFile, float::Float, geometry::Point};
+
+use vec_like::*;
+
+pub use table::UNIFORM_TABLE;
+
+/// An entry in the table of known uniform polytopes.
+#[derive(Clone, Copy, Debug)]
+pub struct UniformEntry {
+    /// The name of the polytope.
+    pub name: &'static str,
+
+    /// The number of elements of each rank, from the vertices to the facets.
+    pub counts: &'static [usize],
+
+    /// The vertex configuration, as returned by
+    /// [`Concrete::vertex_configuration`].
+    pub vertex_configuration: &'static str,
+
+    /// The circumradius of the polytope with unit edge length.
+    pub circumradius: f64,
+
+    /// The coordinates of the polytope as an OFF file, for the entries that
+    /// can't be told apart from other polytopes by their invariants.
+    pub canonical: Option<&'static str>,
+}
+
+/// A known uniform polytope, as found by [`Concrete::identify_uniform`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UniformId {
+    /// The index of the polytope in [`UNIFORM_TABLE`].
+    pub index: usize,
+
+    /// The name of the polytope.
+    pub name: &'static str,
+}
+
+impl UniformId {
+    /// Returns the table entry of the polytope.
+    pub fn entry(&self) -> &'static UniformEntry {
+        &UNIFORM_TABLE[self.index]
+    }
+}
+
+impl Display for UniformId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name)
+    }
+}
+
+/// Returns the label of a regular polygon in a vertex configuration, given its
+/// vertices in cyclic order. This is its number of vertices, followed by its
+/// density if this isn't 1.
+fn polygon_label(p: &Concrete, face: usize) -> (usize, usize) {
+    let edges = p[3][face].subs.iter().map(|e| p[2][*e].subs.as_slice());
+    let cycle = &CycleList::from_edges(edges)[0];
+    let n = cycle.len();
+    let center = cycle.iter().map(|v| &p.vertices[*v]).sum::<Point<f64>>() / n as f64;
+
+    // The angles subtended by the edges add up to a full turn for each time
+    // the polygon winds around its center.
+    let mut angle = 0.0;
+    for i in 0..n {
+        let u = (&p.vertices[cycle[i]] - &center).normalize();
+        let v = (&p.vertices[cycle[(i + 1) % n]] - &center).normalize();
+        angle += u.dot(&v).clamp(-1.0, 1.0).acos();
+    }
+
+    (n, (angle / f64::TAU).round().max(1.0) as usize)
+}
+
+/// Writes a polygon label as `n` or `n/d`.
+fn fmt_label((n, d): (usize, usize)) -> String {
+    if d == 1 {
+        n.to_string()
+    } else {
+        format!("{}/{}", n, d)
+    }
+}
+
+/// Returns the lexicographically least way to read off a cyclic sequence,
+/// starting anywhere and going either way.
+fn canonical_cycle<T: Clone + Ord>(cycle: &[T]) -> Vec<T> {
+    let n = cycle.len();
+    let mut reversed = cycle.to_vec();
+    reversed.reverse();
+
+    let mut best = cycle.to_vec();
+    for seq in [cycle, reversed.as_slice()].iter() {
+        for start in 0..n {
+            let rotated: Vec<_> = seq[start..].iter().chain(&seq[..start]).cloned().collect();
+            if rotated < best {
+                best = rotated;
+            }
+        }
+    }
+
+    best
+}
+
+impl Concrete {
+    /// Returns the vertex configuration of a polyhedral component at a vertex,
+    /// given the faces in it containing the vertex. This lists the polygons
+    /// around the vertex in cyclic order, as in `3.5/2.3.5/2`.
+    fn polyhedral_configuration(&self, vertex: usize, faces: &[usize]) -> String {
+        // Faces are adjacent around the vertex when they share an edge through it.
+        let pairs = self[1][vertex].sups.iter().filter_map(|edge| {
+            let adjacent: Vec<_> = self[2][*edge]
+                .sups
+                .iter()
+                .copied()
+                .filter(|face| faces.contains(face))
+                .collect();
+            if adjacent.len() == 2 {
+                Some(adjacent)
+            } else {
+                None
+            }
+        });
+
+        let mut cycles: Vec<_> = CycleList::from_edges(pairs.collect::<Vec<_>>())
+            .into_iter()
+            .map(|cycle| {
+                let labels: Vec<_> = cycle.iter().map(|face| polygon_label(self, *face)).collect();
+                canonical_cycle(&labels)
+                    .into_iter()
+                    .map(fmt_label)
+                    .collect::<Vec<_>>()
+                    .join(".")
+            })
+            .collect();
+        cycles.sort();
+        cycles.join(" + ")
+    }
+
+    /// Returns the vertex configuration of a polyhedron or polychoron at a
+    /// given vertex, or `None` for any other rank.
+    ///
+    /// For a polyhedron, this lists the polygons around the vertex in cyclic
+    /// order, as in `3.5/2.3.5/2`. For a polychoron, this lists the vertex
+    /// configurations of the cells at the vertex in sorted order, as in
+    /// `(3.3.3), (3.3.3), (3.3.3.3)`.
+    pub fn vertex_configuration(&self, vertex: usize) -> Option<String> {
+        let mut faces: Vec<_> = self[1][vertex]
+            .sups
+            .iter()
+            .flat_map(|edge| self[2][*edge].sups.iter().copied())
+            .collect();
+        faces.sort_unstable();
+        faces.dedup();
+
+        match self.rank() {
+            4 => Some(self.polyhedral_configuration(vertex, &faces)),
+            5 => {
+                let mut cells: Vec<_> = faces
+                    .iter()
+                    .flat_map(|face| self[3][*face].sups.iter().copied())
+                    .collect();
+                cells.sort_unstable();
+                cells.dedup();
+
+                let mut configurations: Vec<_> = cells
+                    .into_iter()
+                    .map(|cell| {
+                        let cell_faces: Vec<_> = faces
+                            .iter()
+                            .copied()
+                            .filter(|face| self[4][cell].subs.contains(face))
+                            .collect();
+                        format!("({})", self.polyhedral_configuration(vertex, &cell_faces))
+                    })
+                    .collect();
+                configurations.sort();
+                Some(configurations.join(", "))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the circumradius of a polytope with unit edge length, assuming
+    /// that it's equilateral and that its vertices lie on a sphere centered at
+    /// their gravicenter. Returns `None` if either of these fails, up to a
+    /// relative tolerance.
+    pub fn uniform_circumradius(&self, tolerance: f64) -> Option<f64> {
+        let edge_len = self.edge_len(0)?;
+        if (0..self.edge_count()).any(|idx| (self.edge_len(idx).unwrap() - edge_len).abs() > tolerance * edge_len) {
+            return None;
+        }
+
+        let center = self.gravicenter()?;
+        let radius = (&self.vertices[0] - &center).norm();
+        if self.vertices.iter().any(|v| ((v - &center).norm() - radius).abs() > tolerance * edge_len) {
+            return None;
+        }
+
+        Some(radius / edge_len)
+    }
+
+    /// Returns a copy of the polytope centered at its gravicenter with unit
+    /// edge length.
+    fn normalized(&self) -> Option<Concrete> {
+        let mut poly = self.clone();
+        poly.recenter();
+        poly.scale(1.0 / self.edge_len(0)?);
+        Some(poly)
+    }
+
+    /// Identifies a polytope as one of the uniform polytopes in
+    /// [`UNIFORM_TABLE`], up to a relative tolerance. Returns `None` if it's
+    /// not one of them.
+    ///
+    /// Every polytope with the element counts, vertex configuration, and
+    /// circumradius of an entry is identified with it, except for the entries
+    /// with canonical coordinates, which must also be congruent to it.
+    pub fn identify_uniform(&self, tolerance: f64) -> Option<UniformId> {
+        let rank = self.rank();
+        if rank != 4 && rank != 5 {
+            return None;
+        }
+
+        let circumradius = self.uniform_circumradius(tolerance)?;
+        let counts: Vec<_> = (1..rank).map(|r| self.el_count(r)).collect();
+        let configuration = self.vertex_configuration(0)?;
+
+        let mut candidates = UNIFORM_TABLE.iter().enumerate().filter(|(_, entry)| {
+            entry.counts == counts.as_slice()
+                && entry.vertex_configuration == configuration
+                && (entry.circumradius - circumradius).abs() <= tolerance
+        });
+
+        let id = |(index, entry): (usize, &UniformEntry)| UniformId { index, name: entry.name };
+        let first = candidates.next()?;
+        if first.1.canonical.is_none() {
+            return Some(id(first));
+        }
+
+        // Tells the ambiguous cases apart by their coordinates.
+        let poly = self.normalized()?;
+        std::iter::once(first).chain(candidates).find_map(|(index, entry)| {
+            let canonical = Concrete::from_off(entry.canonical?).ok()?.normalized()?;
+            find_isometry(&canonical, &poly, |_| true).map(|_| id((index, entry)))
+        })
+    }
+}
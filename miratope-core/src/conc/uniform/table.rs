@@ -0,0 +1,864 @@
+//! The table of known uniform polytopes, generated from the files in `lib`.
+
+// Some circumradii happen to be well-known constants.
+#![allow(clippy::approx_constant)]
+
+use super::UniformEntry;
+
+/// The uniform polyhedra and the convex uniform polychora other than prisms.
+pub const UNIFORM_TABLE: &[UniformEntry] = &[
+    UniformEntry {
+        name: "cotco",
+        counts: &[48, 72, 20],
+        vertex_configuration: "6.8.8/3",
+        circumradius: 1.3228756555322954,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "gaquatid",
+        counts: &[120, 180, 62],
+        vertex_configuration: "4.6.10/3",
+        circumradius: 1.0206841173941286,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "girco",
+        counts: &[48, 72, 26],
+        vertex_configuration: "4.6.8",
+        circumradius: 2.3176109128927664,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "grid",
+        counts: &[120, 180, 62],
+        vertex_configuration: "4.6.10",
+        circumradius: 3.8023944998512933,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "idtid",
+        counts: &[120, 180, 44],
+        vertex_configuration: "6.10.10/3",
+        circumradius: 2.0,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "quitco",
+        counts: &[48, 72, 26],
+        vertex_configuration: "4.6.8/3",
+        circumradius: 1.0623933623853068,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "quitdid",
+        counts: &[120, 180, 54],
+        vertex_configuration: "4.10.10/3",
+        circumradius: 1.6583123951777,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "cho",
+        counts: &[12, 24, 10],
+        vertex_configuration: "4.6.4.6",
+        circumradius: 1.0,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "co",
+        counts: &[12, 24, 14],
+        vertex_configuration: "3.4.3.4",
+        circumradius: 1.0,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "did",
+        counts: &[30, 60, 24],
+        vertex_configuration: "5.5/2.5.5/2",
+        circumradius: 1.0,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "ditdid",
+        counts: &[20, 60, 24],
+        vertex_configuration: "5.5/2.5.5/2.5.5/2",
+        circumradius: 0.8660254037844385,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "geihid",
+        counts: &[30, 60, 26],
+        vertex_configuration: "3.10/3.3.10/3",
+        circumradius: 0.6180339887498949,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "gid",
+        counts: &[30, 60, 32],
+        vertex_configuration: "3.5/2.3.5/2",
+        circumradius: 0.6180339887498949,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "gidhei",
+        counts: &[30, 60, 22],
+        vertex_configuration: "5.6.5.6",
+        circumradius: 1.0,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "gidhid",
+        counts: &[30, 60, 18],
+        vertex_configuration: "5/2.10/3.5/2.10/3",
+        circumradius: 0.6180339887498949,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "gidtid",
+        counts: &[20, 60, 32],
+        vertex_configuration: "3.5.3.5.3.5",
+        circumradius: 0.8660254037844387,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "id",
+        counts: &[30, 60, 32],
+        vertex_configuration: "3.5.3.5",
+        circumradius: 1.618033988749895,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "oho",
+        counts: &[12, 24, 12],
+        vertex_configuration: "3.6.3.6",
+        circumradius: 1.0,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "seihid",
+        counts: &[30, 60, 26],
+        vertex_configuration: "3.10.3.10",
+        circumradius: 1.618033988749895,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "sidhei",
+        counts: &[30, 60, 22],
+        vertex_configuration: "5/2.6.5/2.6",
+        circumradius: 1.0,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "sidhid",
+        counts: &[30, 60, 18],
+        vertex_configuration: "5.10.5.10",
+        circumradius: 1.618033988749895,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "sidtid",
+        counts: &[20, 60, 32],
+        vertex_configuration: "3.5/2.3.5/2.3.5/2",
+        circumradius: 0.8660254037844387,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "thah",
+        counts: &[6, 12, 7],
+        vertex_configuration: "3.4.3.4",
+        circumradius: 0.7071067811865476,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "cube",
+        counts: &[8, 12, 6],
+        vertex_configuration: "4.4.4",
+        circumradius: 0.8660254037844386,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "doe",
+        counts: &[20, 30, 12],
+        vertex_configuration: "5.5.5",
+        circumradius: 1.4012585384440734,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "gad",
+        counts: &[12, 30, 12],
+        vertex_configuration: "5.5.5.5.5",
+        circumradius: 0.9510565162951535,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "gike",
+        counts: &[12, 30, 20],
+        vertex_configuration: "3.3.3.3.3",
+        circumradius: 0.5877852522924731,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "gissid",
+        counts: &[20, 30, 12],
+        vertex_configuration: "5/2.5/2.5/2",
+        circumradius: 0.5352331346596348,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "ike",
+        counts: &[12, 30, 20],
+        vertex_configuration: "3.3.3.3.3",
+        circumradius: 0.9510565162951535,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "oct",
+        counts: &[6, 12, 8],
+        vertex_configuration: "3.3.3.3",
+        circumradius: 0.7071067811865476,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "sissid",
+        counts: &[12, 30, 12],
+        vertex_configuration: "5/2.5/2.5/2.5/2.5/2",
+        circumradius: 0.5877852522924731,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "tet",
+        counts: &[4, 6, 4],
+        vertex_configuration: "3.3.3",
+        circumradius: 0.6123724356957946,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "gidrid",
+        counts: &[60, 240, 124],
+        vertex_configuration: "3.4.5/2.4.3.4.5/2.4",
+        circumradius: 0.7071067811865476,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "girsid",
+        counts: &[60, 150, 92],
+        vertex_configuration: "3.3.3.3.5/2",
+        circumradius: 0.5800015047136147,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "gisdid",
+        counts: &[60, 180, 104],
+        vertex_configuration: "3.3.3.5/2.3.5/2",
+        circumradius: 0.7071067811865476,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "gisid",
+        counts: &[60, 150, 92],
+        vertex_configuration: "3.3.3.3.5/2",
+        circumradius: 0.6450202372957783,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "gosid",
+        counts: &[60, 150, 92],
+        vertex_configuration: "3.3.3.3.5/2",
+        circumradius: 0.8160806747999233,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "isdid",
+        counts: &[60, 150, 84],
+        vertex_configuration: "3.3.5.3.5/2",
+        circumradius: 0.8516302281174122,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "seside",
+        counts: &[60, 180, 112],
+        vertex_configuration: "3.3.3.3.3.5/2",
+        circumradius: 1.4581903307387027,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "siddid",
+        counts: &[60, 150, 84],
+        vertex_configuration: "3.3.5.3.5/2",
+        circumradius: 1.2744398820380218,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "sided",
+        counts: &[60, 180, 104],
+        vertex_configuration: "3.3.3.5.3.5/2",
+        circumradius: 1.1268979127999392,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "sirsid",
+        counts: &[60, 180, 112],
+        vertex_configuration: "3.3.3.3.3.5/2",
+        circumradius: 0.5806948001339209,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "snic",
+        counts: &[24, 60, 38],
+        vertex_configuration: "3.3.3.3.4",
+        circumradius: 1.3437133737446016,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "snid",
+        counts: &[60, 150, 92],
+        vertex_configuration: "3.3.3.3.5",
+        circumradius: 2.1558373751156394,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "gaddid",
+        counts: &[60, 120, 44],
+        vertex_configuration: "3.10/3.5/2.10/3",
+        circumradius: 0.7168905233717422,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "gidditdid",
+        counts: &[60, 120, 44],
+        vertex_configuration: "3.10/3.5.10/3",
+        circumradius: 1.1342285961998928,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "giddy",
+        counts: &[60, 120, 32],
+        vertex_configuration: "6.10/3.6.10/3",
+        circumradius: 1.1342285961998928,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "giid",
+        counts: &[60, 120, 52],
+        vertex_configuration: "3.6.5.6",
+        circumradius: 1.1342285961998926,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "gird",
+        counts: &[60, 120, 42],
+        vertex_configuration: "4.10/3.4.10/3",
+        circumradius: 0.7168905233717421,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "gocco",
+        counts: &[24, 48, 20],
+        vertex_configuration: "3.8/3.4.8/3",
+        circumradius: 0.7368128791039503,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "groh",
+        counts: &[24, 48, 18],
+        vertex_configuration: "4.8/3.4.8/3",
+        circumradius: 0.7368128791039503,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "ided",
+        counts: &[60, 120, 44],
+        vertex_configuration: "5.6.5/2.6",
+        circumradius: 1.3228756555322954,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "qrid",
+        counts: &[60, 120, 62],
+        vertex_configuration: "3.4.5/2.4",
+        circumradius: 0.7168905233717422,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "querco",
+        counts: &[24, 48, 26],
+        vertex_configuration: "3.4.4.4",
+        circumradius: 0.7368128791039503,
+        canonical: Some(include_str!("querco.off")),
+    },
+    UniformEntry {
+        name: "raded",
+        counts: &[60, 120, 54],
+        vertex_configuration: "4.5.4.5/2",
+        circumradius: 1.3228756555322954,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "ri",
+        counts: &[60, 120, 50],
+        vertex_configuration: "4.6.4.6",
+        circumradius: 1.3228756555322954,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "saddid",
+        counts: &[60, 120, 44],
+        vertex_configuration: "3.10.5.10",
+        circumradius: 2.2329505094156903,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "sidditdid",
+        counts: &[60, 120, 44],
+        vertex_configuration: "3.10.5/2.10",
+        circumradius: 1.721489323685285,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "siddy",
+        counts: &[60, 120, 32],
+        vertex_configuration: "6.10.6.10",
+        circumradius: 1.721489323685285,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "siid",
+        counts: &[60, 120, 52],
+        vertex_configuration: "3.6.5/2.6",
+        circumradius: 1.721489323685285,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "sirco",
+        counts: &[24, 48, 26],
+        vertex_configuration: "3.4.4.4",
+        circumradius: 1.3989663259659066,
+        canonical: Some(include_str!("sirco.off")),
+    },
+    UniformEntry {
+        name: "sird",
+        counts: &[60, 120, 42],
+        vertex_configuration: "4.10.4.10",
+        circumradius: 2.2329505094156903,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "socco",
+        counts: &[24, 48, 20],
+        vertex_configuration: "3.8.4.8",
+        circumradius: 1.3989663259659066,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "srid",
+        counts: &[60, 120, 62],
+        vertex_configuration: "3.4.5.4",
+        circumradius: 2.2329505094156903,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "sroh",
+        counts: &[24, 48, 18],
+        vertex_configuration: "4.8.4.8",
+        circumradius: 1.3989663259659069,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "quit gissid",
+        counts: &[60, 90, 32],
+        vertex_configuration: "3.10/3.10/3",
+        circumradius: 0.6575504103777097,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "quit sissid",
+        counts: &[60, 90, 24],
+        vertex_configuration: "5.10/3.10/3",
+        circumradius: 0.8529111994004015,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "quith",
+        counts: &[24, 36, 14],
+        vertex_configuration: "3.8/3.8/3",
+        circumradius: 0.5794708255183387,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "ti",
+        counts: &[60, 90, 32],
+        vertex_configuration: "5.6.6",
+        circumradius: 2.4780186590676156,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "tic",
+        counts: &[24, 36, 14],
+        vertex_configuration: "3.8.8",
+        circumradius: 1.778823645663925,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "tid",
+        counts: &[60, 90, 32],
+        vertex_configuration: "3.10.10",
+        circumradius: 2.969449015863398,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "tiggy",
+        counts: &[60, 90, 32],
+        vertex_configuration: "5/2.6.6",
+        circumradius: 1.0532917569755953,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "tigid",
+        counts: &[60, 90, 24],
+        vertex_configuration: "5/2.10.10",
+        circumradius: 1.8768437563999218,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "toe",
+        counts: &[24, 36, 14],
+        vertex_configuration: "4.6.6",
+        circumradius: 1.5811388300841898,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "tut",
+        counts: &[12, 18, 8],
+        vertex_configuration: "3.6.6",
+        circumradius: 1.1726039399558574,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Bitruncated hecatonicosachoron",
+        counts: &[3600, 7200, 4320, 720],
+        vertex_configuration: "(3.6.6), (3.6.6), (5.6.6), (5.6.6)",
+        circumradius: 7.579633877618875,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Bitruncated icositetrachoron",
+        counts: &[288, 576, 336, 48],
+        vertex_configuration: "(3.8.8), (3.8.8), (3.8.8), (3.8.8)",
+        circumradius: 3.4142135623730825,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Bitruncated pentachoron",
+        counts: &[30, 60, 40, 10],
+        vertex_configuration: "(3.6.6), (3.6.6), (3.6.6), (3.6.6)",
+        circumradius: 1.4142135623730938,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Bitruncated tesseract",
+        counts: &[96, 192, 120, 24],
+        vertex_configuration: "(3.6.6), (3.6.6), (4.6.6), (4.6.6)",
+        circumradius: 2.121320343559636,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Cantellated hecatonicosachoron",
+        counts: &[3600, 10800, 9120, 1920],
+        vertex_configuration: "(3.3.3.3), (3.4.4), (3.4.4), (3.4.5.4), (3.4.5.4)",
+        circumradius: 6.7350337619790706,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Cantellated hexacosichoron",
+        counts: &[3600, 10800, 8640, 1440],
+        vertex_configuration: "(3.4.3.4), (3.4.3.4), (3.5.3.5), (4.4.5), (4.4.5)",
+        circumradius: 6.073593978856194,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Cantellated icositetrachoron",
+        counts: &[288, 864, 720, 144],
+        vertex_configuration: "(3.4.3.4), (3.4.4), (3.4.4), (3.4.4.4), (3.4.4.4)",
+        circumradius: 2.61312592975274,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Cantellated pentachoron",
+        counts: &[30, 90, 80, 20],
+        vertex_configuration: "(3.3.3.3), (3.4.3.4), (3.4.3.4), (3.4.4), (3.4.4)",
+        circumradius: 1.183215956619923,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Cantellated tesseract",
+        counts: &[96, 288, 248, 56],
+        vertex_configuration: "(3.3.3.3), (3.4.4), (3.4.4), (3.4.4.4), (3.4.4.4)",
+        circumradius: 1.8477590650225704,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Cantitruncated hecatonicosachoron",
+        counts: &[7200, 14400, 9120, 1920],
+        vertex_configuration: "(3.4.4), (3.6.6), (4.6.10), (4.6.10)",
+        circumradius: 11.252106619206577,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Cantitruncated hexacosichoron",
+        counts: &[7200, 14400, 8640, 1440],
+        vertex_configuration: "(4.4.5), (4.6.6), (4.6.6), (5.6.6)",
+        circumradius: 9.124101248616121,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Cantitruncated icositetrachoron",
+        counts: &[576, 1152, 720, 144],
+        vertex_configuration: "(3.4.4), (3.8.8), (4.6.8), (4.6.8)",
+        circumradius: 4.299451287575959,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Cantitruncated pentachoron",
+        counts: &[60, 120, 80, 20],
+        vertex_configuration: "(3.4.4), (3.6.6), (4.6.6), (4.6.6)",
+        circumradius: 1.8439088914585806,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Cantitruncated tesseract",
+        counts: &[192, 384, 248, 56],
+        vertex_configuration: "(3.4.4), (3.6.6), (4.6.8), (4.6.8)",
+        circumradius: 3.0059164835259007,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Grand antiprism",
+        counts: &[100, 500, 720, 320],
+        vertex_configuration: "(3.3.3), (3.3.3), (3.3.3), (3.3.3), (3.3.3), (3.3.3), (3.3.3), (3.3.3), (3.3.3), (3.3.3), (3.3.3), (3.3.3), (3.3.3.5), (3.3.3.5)",
+        circumradius: 1.6180339887498896,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Omnitruncated hecatonicosachoron",
+        counts: &[14400, 28800, 17040, 2640],
+        vertex_configuration: "(4.4.10), (4.4.6), (4.6.10), (4.6.6)",
+        circumradius: 12.786651132724007,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Omnitruncated icositetrachoron",
+        counts: &[1152, 2304, 1392, 240],
+        vertex_configuration: "(4.4.6), (4.4.6), (4.6.8), (4.6.8)",
+        circumradius: 5.169905420929639,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Omnitruncated pentachoron",
+        counts: &[120, 240, 150, 30],
+        vertex_configuration: "(4.4.6), (4.4.6), (4.6.6), (4.6.6)",
+        circumradius: 2.2360679774997942,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Omnitruncated tesseract",
+        counts: &[384, 768, 464, 80],
+        vertex_configuration: "(4.4.6), (4.4.8), (4.6.6), (4.6.8)",
+        circumradius: 3.4989485116416343,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Rectified hecatonicosachoron",
+        counts: &[1200, 3600, 3120, 720],
+        vertex_configuration: "(3.3.3), (3.3.3), (3.5.3.5), (3.5.3.5), (3.5.3.5)",
+        circumradius: 4.534567884457016,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Rectified hexacosichoron",
+        counts: &[720, 3600, 3600, 720],
+        vertex_configuration: "(3.3.3.3), (3.3.3.3), (3.3.3.3), (3.3.3.3), (3.3.3.3), (3.3.3.3.3), (3.3.3.3.3)",
+        circumradius: 3.0776835371752598,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Rectified icositetrachoron",
+        counts: &[96, 288, 240, 48],
+        vertex_configuration: "(3.4.3.4), (3.4.3.4), (3.4.3.4), (4.4.4), (4.4.4)",
+        circumradius: 1.7320508075688807,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Rectified pentachoron",
+        counts: &[10, 30, 30, 10],
+        vertex_configuration: "(3.3.3), (3.3.3), (3.3.3.3), (3.3.3.3), (3.3.3.3)",
+        circumradius: 0.7745966692414837,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Rectified tesseract",
+        counts: &[32, 96, 88, 24],
+        vertex_configuration: "(3.3.3), (3.3.3), (3.4.3.4), (3.4.3.4), (3.4.3.4)",
+        circumradius: 1.224744871391589,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Runcinated hecatonicosachoron",
+        counts: &[2400, 7200, 7440, 2640],
+        vertex_configuration: "(3.3.3), (3.4.4), (3.4.4), (3.4.4), (4.4.5), (4.4.5), (4.4.5), (5.5.5)",
+        circumradius: 5.236067977499822,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Runcinated icositetrachoron",
+        counts: &[144, 576, 672, 240],
+        vertex_configuration: "(3.3.3.3), (3.3.3.3), (3.4.4), (3.4.4), (3.4.4), (3.4.4), (3.4.4), (3.4.4), (3.4.4), (3.4.4)",
+        circumradius: 1.8477590650225735,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Runcinated pentachoron",
+        counts: &[20, 60, 70, 30],
+        vertex_configuration: "(3.3.3), (3.3.3), (3.4.4), (3.4.4), (3.4.4), (3.4.4), (3.4.4), (3.4.4)",
+        circumradius: 1.0,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Runcinated tesseract",
+        counts: &[64, 192, 208, 80],
+        vertex_configuration: "(3.3.3), (3.4.4), (3.4.4), (3.4.4), (4.4.4), (4.4.4), (4.4.4), (4.4.4)",
+        circumradius: 1.4856334612503022,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Runcitruncated hecatonicosachoron",
+        counts: &[7200, 18000, 13440, 2640],
+        vertex_configuration: "(3.10.10), (3.4.3.4), (3.4.4), (4.4.10), (4.4.10)",
+        circumradius: 9.744610178324189,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Runcitruncated hexacosichoron",
+        counts: &[7200, 18000, 13440, 2640],
+        vertex_configuration: "(3.4.5.4), (3.6.6), (4.4.5), (4.4.6), (4.4.6)",
+        circumradius: 8.278950396185262,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Runcitruncated hexadecachoron",
+        counts: &[192, 480, 368, 80],
+        vertex_configuration: "(3.4.4.4), (3.6.6), (4.4.4), (4.4.6), (4.4.6)",
+        circumradius: 2.370932378529516,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Runcitruncated icositetrachoron",
+        counts: &[576, 1440, 1104, 240],
+        vertex_configuration: "(3.4.4), (3.4.4.4), (4.4.6), (4.4.6), (4.6.6)",
+        circumradius: 3.4989485116416326,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Runcitruncated pentachoron",
+        counts: &[60, 150, 120, 30],
+        vertex_configuration: "(3.4.3.4), (3.4.4), (3.6.6), (4.4.6), (4.4.6)",
+        circumradius: 1.6124515496597125,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Runcitruncated tesseract",
+        counts: &[192, 480, 368, 80],
+        vertex_configuration: "(3.4.3.4), (3.4.4), (3.8.8), (4.4.8), (4.4.8)",
+        circumradius: 2.6131259297527496,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Semisnub icositetrachoron",
+        counts: &[96, 432, 480, 144],
+        vertex_configuration: "(3.3.3), (3.3.3), (3.3.3), (3.3.3), (3.3.3), (3.3.3.3.3), (3.3.3.3.3), (3.3.3.3.3)",
+        circumradius: 1.6180339887498958,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Truncated hecatonicosachoron",
+        counts: &[2400, 4800, 3120, 720],
+        vertex_configuration: "(3.10.10), (3.10.10), (3.10.10), (3.3.3)",
+        circumradius: 8.218334360592552,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Truncated hexacosichoron",
+        counts: &[1440, 4320, 3600, 720],
+        vertex_configuration: "(3.3.3.3.3), (3.6.6), (3.6.6), (3.6.6), (3.6.6), (3.6.6)",
+        circumradius: 4.643523005084503,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Truncated hexadecachoron",
+        counts: &[48, 120, 96, 24],
+        vertex_configuration: "(3.3.3.3), (3.6.6), (3.6.6), (3.6.6), (3.6.6)",
+        circumradius: 1.5811388300841933,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Truncated icositetrachoron",
+        counts: &[192, 384, 240, 48],
+        vertex_configuration: "(4.4.4), (4.6.6), (4.6.6), (4.6.6)",
+        circumradius: 2.645751311064619,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Truncated pentachoron",
+        counts: &[20, 40, 30, 10],
+        vertex_configuration: "(3.3.3), (3.6.6), (3.6.6), (3.6.6)",
+        circumradius: 1.2649110640673504,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Truncated tesseract",
+        counts: &[64, 128, 88, 24],
+        vertex_configuration: "(3.3.3), (3.8.8), (3.8.8), (3.8.8)",
+        circumradius: 2.1497256437879764,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Hecatonicosachoron",
+        counts: &[600, 1200, 720, 120],
+        vertex_configuration: "(5.5.5), (5.5.5), (5.5.5), (5.5.5)",
+        circumradius: 3.702459173643826,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Hexacosichoron",
+        counts: &[120, 720, 1200, 600],
+        vertex_configuration: "(3.3.3), (3.3.3), (3.3.3), (3.3.3), (3.3.3), (3.3.3), (3.3.3), (3.3.3), (3.3.3), (3.3.3), (3.3.3), (3.3.3), (3.3.3), (3.3.3), (3.3.3), (3.3.3), (3.3.3), (3.3.3), (3.3.3), (3.3.3)",
+        circumradius: 1.6180339887498905,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Hexadecachoron",
+        counts: &[8, 24, 32, 16],
+        vertex_configuration: "(3.3.3), (3.3.3), (3.3.3), (3.3.3), (3.3.3), (3.3.3), (3.3.3), (3.3.3)",
+        circumradius: 0.7071067811865476,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Icositetrachoron",
+        counts: &[24, 96, 96, 24],
+        vertex_configuration: "(3.3.3.3), (3.3.3.3), (3.3.3.3), (3.3.3.3), (3.3.3.3), (3.3.3.3)",
+        circumradius: 1.0,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Pentachoron",
+        counts: &[5, 10, 10, 5],
+        vertex_configuration: "(3.3.3), (3.3.3), (3.3.3), (3.3.3)",
+        circumradius: 0.632455532033676,
+        canonical: None,
+    },
+    UniformEntry {
+        name: "Tesseract",
+        counts: &[16, 32, 24, 8],
+        vertex_configuration: "(4.4.4), (4.4.4), (4.4.4), (4.4.4)",
+        circumradius: 1.0,
+        canonical: None,
+    },
+];
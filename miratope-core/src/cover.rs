@@ -0,0 +1,456 @@
+//! A solver for a variant of the exact cover problem, in which every item must
+//! be covered exactly twice or not at all.
+//!
+//! This is the condition for a set of facets to make up a dyadic polytope:
+//! every ridge must be in exactly two of the facets, or in none of them.
+
+use std::{
+    collections::{BTreeSet, HashSet},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+/// An option of a [`TwofoldCover`] problem.
+#[derive(Clone, Debug)]
+struct CoverOption {
+    /// At most one option of each group can be chosen.
+    group: usize,
+
+    /// The items the option covers, along with how many times it covers them.
+    items: Vec<(usize, usize)>,
+}
+
+/// The outcome of a search for the solutions of a [`TwofoldCover`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CoverStats {
+    /// The number of partial selections of options examined.
+    pub nodes: u64,
+
+    /// Whether the search was cancelled before finishing.
+    pub cancelled: bool,
+}
+
+/// Asks for the sets of options that cover every item exactly twice or not at
+/// all, choosing at most one option from each group.
+///
+/// The search goes item by item, rather than option by option. A solution is
+/// found from the least item it covers, and then by repeatedly completing
+/// whichever item covered once has the fewest options left to complete it.
+/// This is much faster than going option by option when there are many more
+/// options than items.
+#[derive(Clone, Debug, Default)]
+pub struct TwofoldCover {
+    /// The number of items to cover.
+    item_count: usize,
+
+    /// The options to choose from.
+    options: Vec<CoverOption>,
+
+    /// The options covering each item, along with how many times they cover it.
+    by_item: Vec<Vec<(usize, usize)>>,
+
+    /// A flag that can be set from another thread to stop the search.
+    cancel: Option<Arc<AtomicBool>>,
+}
+
+impl TwofoldCover {
+    /// Initializes a problem with a given number of items and no options.
+    pub fn new(item_count: usize) -> Self {
+        Self {
+            item_count,
+            options: Vec::new(),
+            by_item: vec![Vec::new(); item_count],
+            cancel: None,
+        }
+    }
+
+    /// Sets a flag that can be set from another thread to stop the search.
+    pub fn with_cancel(mut self, cancel: Option<Arc<AtomicBool>>) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    /// Returns the number of items.
+    pub fn item_count(&self) -> usize {
+        self.item_count
+    }
+
+    /// Returns the number of options.
+    pub fn option_count(&self) -> usize {
+        self.options.len()
+    }
+
+    /// Adds an option in a given group, covering some items some number of
+    /// times each, and returns its index. Items listed more than once have
+    /// their multiplicities added.
+    ///
+    /// # Panics
+    /// Panics if any of the items is out of bounds.
+    pub fn push_option(&mut self, group: usize, items: &[(usize, usize)]) -> usize {
+        let idx = self.options.len();
+        let mut merged: Vec<(usize, usize)> = Vec::new();
+        for &(item, mul) in items {
+            assert!(item < self.item_count, "item {} out of bounds", item);
+            match merged.iter_mut().find(|(other, _)| *other == item) {
+                Some((_, total)) => *total += mul,
+                None => merged.push((item, mul)),
+            }
+        }
+        merged.retain(|&(_, mul)| mul != 0);
+        merged.sort_unstable();
+
+        for &(item, mul) in &merged {
+            self.by_item[item].push((idx, mul));
+        }
+        self.options.push(CoverOption { group, items: merged });
+        idx
+    }
+
+    /// Returns the options covering an item, along with how many times they
+    /// cover it.
+    pub fn options_covering(&self, item: usize) -> &[(usize, usize)] {
+        &self.by_item[item]
+    }
+
+    /// Returns whether the search has been cancelled.
+    fn is_cancelled(&self) -> bool {
+        match &self.cancel {
+            Some(cancel) => cancel.load(Ordering::Relaxed),
+            None => false,
+        }
+    }
+
+    /// Returns which options can be part of a solution at all. An option that
+    /// covers something more than twice can't, and neither can an option that
+    /// covers something once if no option from another group can complete it.
+    fn viable_options(&self) -> Vec<bool> {
+        let mut viable: Vec<_> = self
+            .options
+            .iter()
+            .map(|option| option.items.iter().all(|&(_, mul)| mul <= 2))
+            .collect();
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (idx, option) in self.options.iter().enumerate() {
+                if !viable[idx] {
+                    continue;
+                }
+
+                let completable = option.items.iter().all(|&(item, mul)| {
+                    mul == 2
+                        || self.by_item[item].iter().any(|&(other, other_mul)| {
+                            other_mul == 1 && viable[other] && self.options[other].group != option.group
+                        })
+                });
+                if !completable {
+                    viable[idx] = false;
+                    changed = true;
+                }
+            }
+        }
+
+        viable
+    }
+
+    /// Finds every solution that doesn't contain another one, with at most a
+    /// given number of options, and calls a function on each, given as the
+    /// sorted indices of its options. The search stops early if the function
+    /// returns `false`.
+    pub fn minimal_solutions<F: FnMut(&[usize]) -> bool>(&self, max_size: Option<usize>, mut visit: F) -> CoverStats {
+        let mut check_nodes = 0;
+        let mut search = Search::new(self, self.viable_options(), max_size);
+        search.roots(&mut |search| {
+            let mut solution = search.chosen.clone();
+            solution.sort_unstable();
+            let (smaller, nodes) = self.contains_smaller_solution(&solution);
+            check_nodes += nodes;
+            smaller || visit(&solution)
+        });
+
+        CoverStats {
+            nodes: search.nodes + check_nodes,
+            cancelled: search.cancelled,
+        }
+    }
+
+    /// Finds every solution with at most a given number of options, as the
+    /// sorted indices of its options, in lexicographic order.
+    ///
+    /// Every solution is a union of minimal ones covering different items,
+    /// which is how these are found.
+    pub fn solutions(&self, max_size: Option<usize>) -> (Vec<Vec<usize>>, CoverStats) {
+        let mut minimal = Vec::new();
+        let stats = self.minimal_solutions(max_size, |solution| {
+            minimal.push(solution.to_vec());
+            true
+        });
+
+        let mut solutions: BTreeSet<Vec<usize>> = minimal.iter().cloned().collect();
+        let mut last: Vec<Vec<usize>> = minimal.clone();
+        while !last.is_empty() {
+            let mut next = Vec::new();
+            for solution in &last {
+                for other in &minimal {
+                    if matches!(max_size, Some(max) if solution.len() + other.len() > max)
+                        || other[0] <= solution[0]
+                        || !self.disjoint(solution, other)
+                    {
+                        continue;
+                    }
+
+                    let mut union: Vec<_> = solution.iter().chain(other).copied().collect();
+                    union.sort_unstable();
+                    if solutions.insert(union.clone()) {
+                        next.push(union);
+                    }
+                }
+            }
+            last = next;
+        }
+
+        (solutions.into_iter().collect(), stats)
+    }
+
+    /// Returns whether two sets of options share no items and no groups.
+    fn disjoint(&self, a: &[usize], b: &[usize]) -> bool {
+        let groups: HashSet<_> = a.iter().map(|&o| self.options[o].group).collect();
+        let items: HashSet<_> = a.iter().flat_map(|&o| self.options[o].items.iter().map(|&(item, _)| item)).collect();
+        b.iter().all(|&o| {
+            let option = &self.options[o];
+            !groups.contains(&option.group) && option.items.iter().all(|(item, _)| !items.contains(item))
+        })
+    }
+
+    /// Returns whether a solution contains a smaller one, along with the number
+    /// of nodes examined to find out. If it does, the rest of it is a solution
+    /// too, so we only look for those without its first option.
+    fn contains_smaller_solution(&self, solution: &[usize]) -> (bool, u64) {
+        let mut viable = vec![false; self.options.len()];
+        for &option in &solution[1..] {
+            viable[option] = true;
+        }
+
+        let mut search = Search::new(self, viable, None);
+        let smaller = !search.roots(&mut |_| false);
+        (smaller, search.nodes)
+    }
+}
+
+/// The state of a search for the solutions of a [`TwofoldCover`].
+struct Search<'a> {
+    /// The problem being solved.
+    problem: &'a TwofoldCover,
+
+    /// Which options can still be chosen.
+    viable: Vec<bool>,
+
+    /// How many times each item is covered by the options chosen.
+    coverage: Vec<usize>,
+
+    /// The groups of the options chosen.
+    used_groups: HashSet<usize>,
+
+    /// The options chosen.
+    chosen: Vec<usize>,
+
+    /// The maximum number of options to choose.
+    max_size: Option<usize>,
+
+    /// The number of partial selections examined.
+    nodes: u64,
+
+    /// Whether the search was cancelled.
+    cancelled: bool,
+}
+
+impl<'a> Search<'a> {
+    /// Initializes a search using only some of the options.
+    fn new(problem: &'a TwofoldCover, viable: Vec<bool>, max_size: Option<usize>) -> Self {
+        Self {
+            problem,
+            viable,
+            coverage: vec![0; problem.item_count],
+            used_groups: HashSet::new(),
+            chosen: Vec::new(),
+            max_size,
+            nodes: 0,
+            cancelled: false,
+        }
+    }
+
+    /// Returns whether an option can be added to the ones chosen.
+    fn fits(&self, idx: usize) -> bool {
+        let option = &self.problem.options[idx];
+        self.viable[idx]
+            && !self.used_groups.contains(&option.group)
+            && option.items.iter().all(|&(item, mul)| self.coverage[item] + mul <= 2)
+    }
+
+    /// Adds an option to the ones chosen.
+    fn push(&mut self, idx: usize) {
+        let option = &self.problem.options[idx];
+        for &(item, mul) in &option.items {
+            self.coverage[item] += mul;
+        }
+        self.used_groups.insert(option.group);
+        self.chosen.push(idx);
+    }
+
+    /// Removes the last option chosen.
+    fn pop(&mut self) {
+        let idx = self.chosen.pop().unwrap();
+        let option = &self.problem.options[idx];
+        for &(item, mul) in &option.items {
+            self.coverage[item] -= mul;
+        }
+        self.used_groups.remove(&option.group);
+    }
+
+    /// Returns the options that can complete the item covered once with the
+    /// fewest of them, or `None` if no item is covered once.
+    fn branch(&self) -> Option<Vec<usize>> {
+        let mut best: Option<Vec<usize>> = None;
+        for (item, &coverage) in self.coverage.iter().enumerate() {
+            if coverage != 1 {
+                continue;
+            }
+
+            let candidates: Vec<_> = self.problem.by_item[item]
+                .iter()
+                .filter(|&&(idx, mul)| mul == 1 && self.fits(idx))
+                .map(|&(idx, _)| idx)
+                .collect();
+            let better = match &best {
+                Some(best) => candidates.len() < best.len(),
+                None => true,
+            };
+            if better {
+                let dead_end = candidates.is_empty();
+                best = Some(candidates);
+                if dead_end {
+                    break;
+                }
+            }
+        }
+        best
+    }
+
+    /// Completes the options chosen in every possible way, calling a function
+    /// on every solution found. Returns `false` if the search was stopped.
+    fn complete<F: FnMut(&Self) -> bool>(&mut self, on_solution: &mut F) -> bool {
+        self.nodes += 1;
+        if self.problem.is_cancelled() {
+            self.cancelled = true;
+            return false;
+        }
+
+        let candidates = match self.branch() {
+            Some(candidates) => candidates,
+            None => return on_solution(self),
+        };
+        if matches!(self.max_size, Some(max) if self.chosen.len() >= max) {
+            return true;
+        }
+
+        for idx in candidates {
+            self.push(idx);
+            let go_on = self.complete(on_solution);
+            self.pop();
+            if !go_on {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Finds the solutions, starting from the least item each one covers.
+    /// Returns `false` if the search was stopped.
+    fn roots<F: FnMut(&Self) -> bool>(&mut self, on_solution: &mut F) -> bool {
+        for root in 0..self.problem.item_count {
+            for &(idx, _) in &self.problem.by_item[root] {
+                if !self.fits(idx) {
+                    continue;
+                }
+
+                self.push(idx);
+                let go_on = self.complete(on_solution);
+                self.pop();
+                if !go_on {
+                    return false;
+                }
+
+                // Every solution with this option has been found. Leaving it
+                // out also means that two options covering the root once are
+                // only ever chosen in one order.
+                self.viable[idx] = false;
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the minimal solutions of a problem.
+    fn minimal(problem: &TwofoldCover) -> Vec<Vec<usize>> {
+        let mut solutions = Vec::new();
+        problem.minimal_solutions(None, |solution| {
+            solutions.push(solution.to_vec());
+            true
+        });
+        solutions.sort();
+        solutions
+    }
+
+    /// The edges of a square and of a triangle sharing none of its vertices,
+    /// with a diagonal of the square thrown in.
+    fn square_and_triangle() -> TwofoldCover {
+        let mut problem = TwofoldCover::new(7);
+        for (group, edge) in [[0, 1], [1, 2], [2, 3], [3, 0], [4, 5], [5, 6], [6, 4], [0, 2]].iter().enumerate() {
+            problem.push_option(group, &[(edge[0], 1), (edge[1], 1)]);
+        }
+        problem
+    }
+
+    #[test]
+    fn cycles() {
+        let problem = square_and_triangle();
+        assert_eq!(
+            minimal(&problem),
+            vec![vec![0, 1, 2, 3], vec![0, 1, 7], vec![2, 3, 7], vec![4, 5, 6]]
+        );
+
+        let (solutions, _) = problem.solutions(None);
+        assert_eq!(solutions.len(), 7);
+        assert!(solutions.contains(&vec![0, 1, 2, 3, 4, 5, 6]));
+        assert!(solutions.contains(&vec![0, 1, 4, 5, 6, 7]));
+
+        let (solutions, _) = problem.solutions(Some(3));
+        assert_eq!(solutions, vec![vec![0, 1, 7], vec![2, 3, 7], vec![4, 5, 6]]);
+    }
+
+    #[test]
+    fn groups() {
+        let mut problem = TwofoldCover::new(2);
+        problem.push_option(0, &[(0, 1), (1, 1)]);
+        problem.push_option(0, &[(0, 1), (1, 1)]);
+        problem.push_option(1, &[(0, 2)]);
+        problem.push_option(2, &[(0, 1), (1, 1)]);
+        problem.push_option(3, &[(0, 3)]);
+
+        assert_eq!(minimal(&problem), vec![vec![0, 3], vec![1, 3], vec![2]]);
+    }
+
+    #[test]
+    fn cancelled() {
+        let problem = square_and_triangle().with_cancel(Some(Arc::new(AtomicBool::new(true))));
+        let stats = problem.minimal_solutions(None, |_| panic!("the search should have stopped"));
+        assert!(stats.cancelled);
+    }
+}
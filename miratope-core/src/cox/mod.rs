@@ -93,9 +93,9 @@ impl Cox<f64> {
     }
 
     /// Creates a Coxeter matrix from a linear diagram, whose edges are
-    /// described by the vector.
+    /// described by the vector. The diagram has one more node than edges.
     pub fn from_lin_diagram(diagram: &[f64]) -> Self {
-        Self::from_lin_diagram_iter(diagram.iter().copied(), diagram.len())
+        Self::from_lin_diagram_iter(diagram.iter().copied(), diagram.len() + 1)
     }
 
     /// Returns the Coxeter matrix for the I2(x) group.
@@ -198,3 +198,33 @@ impl Cox<f64> {
         self.gen_iter().map(Into::into)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The I2(x) matrix is 2 × 2, with the edge value off the diagonal.
+    #[test]
+    fn i2() {
+        assert_eq!(Cox::i2(5.0), Cox::new(dmatrix![1.0, 5.0; 5.0, 1.0]));
+        assert_eq!(Cox::i2(5.0).group().unwrap().count(), 10);
+    }
+
+    /// A linear diagram has one more node than edges.
+    #[test]
+    fn from_lin_diagram() {
+        assert_eq!(Cox::from_lin_diagram(&[]), Cox::trivial());
+        assert_eq!(Cox::from_lin_diagram(&[3.0, 3.0]), Cox::a(3));
+        assert_eq!(Cox::from_lin_diagram(&[4.0, 3.0, 3.0]), Cox::b(4));
+        assert_eq!(
+            Cox::from_lin_diagram(&[3.0, 4.0, 3.0]),
+            Cox::new(dmatrix![
+                1.0, 3.0, 2.0, 2.0;
+                3.0, 1.0, 4.0, 2.0;
+                2.0, 4.0, 1.0, 3.0;
+                2.0, 2.0, 3.0, 1.0
+            ])
+        );
+        assert_eq!(Cox::from_lin_diagram(&[3.0, 4.0, 3.0]).group().unwrap().count(), 1152);
+    }
+}
@@ -0,0 +1,279 @@
+//! Recognizes coordinates that are close to some simple closed form, so that
+//! exported files can be annotated with them.
+//!
+//! Coordinates like φ/2 or √2 can't be written exactly as decimals. Finding
+//! the form they come from makes the exported files easier to read, and lets
+//! other programs rebuild the exact values. The recognition only ever
+//! annotates: the written floats are the same either way.
+
+use std::fmt::Display;
+
+use serde::Serialize;
+
+/// The golden ratio, (1 + √5) / 2.
+const PHI: f64 = 1.618_033_988_749_895;
+
+/// The largest denominator of a recognized form.
+const MAX_DENOMINATOR: i64 = 12;
+
+/// The largest coefficient of the irrational part of a recognized form.
+const MAX_COEFFICIENT: i64 = 12;
+
+/// The square roots that can show up in a recognized form. These are the
+/// square-free numbers up to 15, other than 5, whose forms are written in
+/// terms of φ instead.
+const RADICANDS: [i64; 9] = [2, 3, 6, 7, 10, 11, 13, 14, 15];
+
+/// The default tolerance for recognizing a coordinate.
+pub const DEFAULT_TOLERANCE: f64 = 1e-9;
+
+/// A closed form for a number. Every form is written in lowest terms, with a
+/// positive denominator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "form", rename_all = "snake_case")]
+pub enum ExactForm {
+    /// A rational number `numerator / denominator`.
+    Rational {
+        /// The numerator.
+        numerator: i64,
+
+        /// The denominator.
+        denominator: i64,
+    },
+
+    /// An element of the golden field, `(rational + coefficient φ) /
+    /// denominator`.
+    Golden {
+        /// The rational part of the numerator.
+        rational: i64,
+
+        /// The coefficient of φ in the numerator.
+        coefficient: i64,
+
+        /// The denominator.
+        denominator: i64,
+    },
+
+    /// A quadratic irrational `(rational + coefficient √radicand) /
+    /// denominator`.
+    Quadratic {
+        /// The rational part of the numerator.
+        rational: i64,
+
+        /// The coefficient of the square root in the numerator.
+        coefficient: i64,
+
+        /// The square-free number under the square root.
+        radicand: i64,
+
+        /// The denominator.
+        denominator: i64,
+    },
+}
+
+impl ExactForm {
+    /// Returns the value of the form as a float.
+    pub fn value(self) -> f64 {
+        match self {
+            Self::Rational { numerator, denominator } => numerator as f64 / denominator as f64,
+            Self::Golden {
+                rational,
+                coefficient,
+                denominator,
+            } => (rational as f64 + coefficient as f64 * PHI) / denominator as f64,
+            Self::Quadratic {
+                rational,
+                coefficient,
+                radicand,
+                denominator,
+            } => (rational as f64 + coefficient as f64 * (radicand as f64).sqrt()) / denominator as f64,
+        }
+    }
+}
+
+/// Writes the numerator of an irrational form, given the symbol for its
+/// irrational part.
+fn write_numerator(
+    f: &mut std::fmt::Formatter<'_>,
+    rational: i64,
+    coefficient: i64,
+    symbol: &str,
+) -> std::fmt::Result {
+    if rational != 0 {
+        write!(f, "{}", rational)?;
+        f.write_str(if coefficient < 0 { "-" } else { "+" })?;
+    } else if coefficient < 0 {
+        f.write_str("-")?;
+    }
+
+    if coefficient.abs() != 1 {
+        write!(f, "{}", coefficient.abs())?;
+    }
+    f.write_str(symbol)
+}
+
+/// Writes an irrational form, with parentheses around the numerator only when
+/// they're needed.
+fn write_irrational(
+    f: &mut std::fmt::Formatter<'_>,
+    rational: i64,
+    coefficient: i64,
+    symbol: &str,
+    denominator: i64,
+) -> std::fmt::Result {
+    let parens = rational != 0 && denominator != 1;
+    if parens {
+        f.write_str("(")?;
+    }
+    write_numerator(f, rational, coefficient, symbol)?;
+    if parens {
+        f.write_str(")")?;
+    }
+
+    if denominator != 1 {
+        write!(f, "/{}", denominator)?;
+    }
+    Ok(())
+}
+
+impl Display for ExactForm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Rational { numerator, denominator } => {
+                write!(f, "{}", numerator)?;
+                if denominator != 1 {
+                    write!(f, "/{}", denominator)?;
+                }
+                Ok(())
+            }
+            Self::Golden {
+                rational,
+                coefficient,
+                denominator,
+            } => write_irrational(f, rational, coefficient, "φ", denominator),
+            Self::Quadratic {
+                rational,
+                coefficient,
+                radicand,
+                denominator,
+            } => write_irrational(f, rational, coefficient, &format!("√{}", radicand), denominator),
+        }
+    }
+}
+
+/// Returns the integer closest to `(x - irrational) * denominator`, if it
+/// gives back `x` up to the tolerance when taken as the rational part of a
+/// form with that irrational part and denominator.
+fn rational_part(x: f64, irrational: f64, denominator: i64, tolerance: f64) -> Option<i64> {
+    let rational = (x * denominator as f64 - irrational).round();
+    if rational.abs() > i64::MAX as f64 {
+        return None;
+    }
+
+    if ((rational + irrational) / denominator as f64 - x).abs() <= tolerance {
+        Some(rational as i64)
+    } else {
+        None
+    }
+}
+
+/// Returns the coefficients from 1 up to the maximum, alternating in sign.
+fn coefficients() -> impl Iterator<Item = i64> {
+    (1..=MAX_COEFFICIENT).flat_map(|c| [c, -c])
+}
+
+/// Attempts to find a simple closed form within a tolerance of a number. The
+/// forms are tried from the smallest denominator up, and for each denominator
+/// rationals are tried first, then golden field elements, then other
+/// quadratic irrationals, each from the smallest coefficient up.
+///
+/// This is a best-effort search: it only tries denominators and coefficients
+/// up to 12, and the square roots of the square-free numbers up to 15.
+pub fn recognize_constant(x: f64, tolerance: f64) -> Option<ExactForm> {
+    if !x.is_finite() {
+        return None;
+    }
+
+    for denominator in 1..=MAX_DENOMINATOR {
+        if let Some(numerator) = rational_part(x, 0.0, denominator, tolerance) {
+            return Some(ExactForm::Rational { numerator, denominator });
+        }
+
+        for coefficient in coefficients() {
+            if let Some(rational) = rational_part(x, coefficient as f64 * PHI, denominator, tolerance) {
+                return Some(ExactForm::Golden {
+                    rational,
+                    coefficient,
+                    denominator,
+                });
+            }
+        }
+
+        for coefficient in coefficients() {
+            for &radicand in &RADICANDS {
+                let irrational = coefficient as f64 * (radicand as f64).sqrt();
+                if let Some(rational) = rational_part(x, irrational, denominator, tolerance) {
+                    return Some(ExactForm::Quadratic {
+                        rational,
+                        coefficient,
+                        radicand,
+                        denominator,
+                    });
+                }
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Recognizes a number and writes down its form.
+    fn recognized(x: f64) -> Option<String> {
+        recognize_constant(x, DEFAULT_TOLERANCE).map(|form| form.to_string())
+    }
+
+    /// The usual constants are found in lowest terms, and written like they
+    /// would be by hand.
+    #[test]
+    fn forms() {
+        assert_eq!(recognized(0.0).as_deref(), Some("0"));
+        assert_eq!(recognized(-1.5).as_deref(), Some("-3/2"));
+        assert_eq!(recognized(PHI).as_deref(), Some("φ"));
+        assert_eq!(recognized(PHI / 2.0).as_deref(), Some("φ/2"));
+        assert_eq!(recognized(1.0 / PHI).as_deref(), Some("-1+φ"));
+        assert_eq!(recognized(-(1.0 + PHI) / 3.0).as_deref(), Some("(-1-φ)/3"));
+        assert_eq!(recognized(2f64.sqrt()).as_deref(), Some("√2"));
+        assert_eq!(recognized(-(3f64.sqrt()) / 6.0).as_deref(), Some("-√3/6"));
+        assert_eq!(recognized((1.0 + 2.0 * 2f64.sqrt()) / 7.0).as_deref(), Some("(1+2√2)/7"));
+    }
+
+    /// The value of a form is what it was recognized from.
+    #[test]
+    fn value() {
+        for &x in &[0.25, PHI, 5f64.sqrt(), -(1.0 + 3f64.sqrt()) / 4.0] {
+            let form = recognize_constant(x, DEFAULT_TOLERANCE).unwrap();
+            assert!((form.value() - x).abs() <= DEFAULT_TOLERANCE);
+        }
+    }
+
+    /// Numbers with no simple form, and ones that aren't finite, aren't
+    /// matched to anything.
+    #[test]
+    fn noise() {
+        // A linear congruential generator, so that the numbers are the same on
+        // every run.
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        for _ in 0..1000 {
+            state = state.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1_442_695_040_888_963_407);
+            let x = (state >> 11) as f64 / (1u64 << 53) as f64 * 20.0 - 10.0;
+            assert_eq!(recognize_constant(x, DEFAULT_TOLERANCE), None, "{} was matched", x);
+        }
+
+        assert_eq!(recognize_constant(f64::NAN, DEFAULT_TOLERANCE), None);
+        assert_eq!(recognize_constant(f64::INFINITY, DEFAULT_TOLERANCE), None);
+    }
+}
@@ -1,5 +1,6 @@
 //! Reading from and writing to files in various different formats.
 
+pub mod exact;
 pub mod ggb;
 pub mod off;
 
@@ -13,7 +14,7 @@ use off::OffParseError;
 use zip::result::ZipError;
 
 pub use std::io::Error as IoError;
-use std::{fs::File, string::FromUtf8Error, fmt::Display};
+use std::{ffi::OsStr, fmt::Display, fs::File, path::Path, string::FromUtf8Error};
 
 /// Any error encountered while trying to load a polytope.
 #[derive(Debug)]
@@ -103,9 +104,12 @@ pub trait FromFile: Sized {
     /// 3D.
     fn from_ggb(file: File) -> GgbResult<Self>;
 
+    /// Records the path a polytope was loaded from. Does nothing by default.
+    fn set_source(&mut self, _path: &Path) {}
+
     /// Loads a polytope from a file path.
-    fn from_path<U: AsRef<std::path::Path>>(fp: &U) -> FileResult<'_, Self> {
-        use std::{ffi::OsStr, fs};
+    fn from_path<U: AsRef<Path>>(fp: &U) -> FileResult<'_, Self> {
+        use std::fs;
 
         let ext = fp
             .as_ref()
@@ -113,19 +117,22 @@ pub trait FromFile: Sized {
             .and_then(OsStr::to_str)
             .unwrap_or_default();
 
-        match ext {
+        let mut poly = match ext {
             // Reads the file as an OFF file.
             "off" => match String::from_utf8(fs::read(fp)?) {
-                Ok(src) => Ok(Self::from_off(&src)?),
-                Err(err) => Err(err.into()),
+                Ok(src) => Self::from_off(&src)?,
+                Err(err) => return Err(err.into()),
             },
 
             // Reads the file as a GGB file.
-            "ggb" => Ok(Self::from_ggb(fs::File::open(fp)?)?),
+            "ggb" => Self::from_ggb(fs::File::open(fp)?)?,
 
             // Could not recognize the file extension.
-            ext => Err(FileError::InvalidExtension(ext)),
-        }
+            ext => return Err(FileError::InvalidExtension(ext)),
+        };
+
+        poly.set_source(fp.as_ref());
+        Ok(poly)
     }
 }
 
@@ -151,6 +158,30 @@ impl FromFile for Concrete {
             Err(GgbError::InvalidGgb)
         }
     }
+
+    /// Records the path as the source of the polytope, and names the polytope
+    /// after the file, unless the file already stored these.
+    fn set_source(&mut self, path: &Path) {
+        if self.meta.source.is_none() {
+            self.meta.source = Some(path.display().to_string());
+        }
+
+        if self.meta.name.is_none() {
+            self.meta.name = path
+                .file_stem()
+                .and_then(OsStr::to_str)
+                .map(ToString::to_string);
+        }
+    }
+}
+
+/// Writes a coordinate with a given number of digits after the decimal point,
+/// or with as many as needed to read it back exactly if none is given.
+pub fn format_coordinate(x: f64, precision: Option<usize>) -> String {
+    match precision {
+        Some(precision) => format!("{:.*}", precision, x),
+        None => x.to_string(),
+    }
 }
 
 /// A position in a file.
@@ -3,11 +3,14 @@
 
 use std::{collections::{HashMap, HashSet}, fmt::Display, io::Error as IoError, path::Path, str::FromStr};
 
-use super::Position;
+use super::{
+    exact::{recognize_constant, DEFAULT_TOLERANCE},
+    format_coordinate, Position,
+};
 
 use crate::{
-    abs::{AbstractBuilder, Ranked, SubelementList, Subelements},
-    conc::{cycle::CycleList, Concrete, element_types::EL_NAMES},
+    abs::{AbstractBuilder, ElementTags, Ranked, SubelementList, Subelements},
+    conc::{cycle::CycleList, element_types::EL_NAMES, meta::PolytopeMeta, Concrete},
     geometry::Point,
     Polytope, COMPONENTS
 };
@@ -193,6 +196,22 @@ impl<'a> TokenIter<'a> {
         })
     }
 
+    /// Returns the rest of the line the last token was on, up to any comment,
+    /// and skips it. Returns `None` if the token ended its line.
+    fn rest_of_line(&mut self) -> Option<&'a str> {
+        if self.position.column == 0 {
+            return None;
+        }
+
+        let rest = if self.comment {
+            None
+        } else {
+            self.iter.as_str().split(&['\n', '#'][..]).next()
+        };
+        self.comment = true;
+        rest
+    }
+
     /// Reads and parses the next token from the OFF file.
     pub fn parse_next<U: FromStr>(&mut self) -> OffParseResult<U> {
         self.next()
@@ -222,6 +241,30 @@ pub struct OffReader<'a> {
 
     /// The underlying abstract polytope.
     abs: AbstractBuilder,
+
+    /// The colors at the end of the face lines, if any.
+    face_colors: Vec<Option<Rgba>>,
+}
+
+/// Parses the color after the vertices of a face. This must be made of three
+/// or four components, either all integers from 0 to 255 or all floats from 0
+/// to 1. If the alpha component is missing, it's taken to be opaque.
+fn parse_color(src: &str) -> Option<Rgba> {
+    let tokens: Vec<_> = src.split_whitespace().collect();
+    if tokens.len() != 3 && tokens.len() != 4 {
+        return None;
+    }
+
+    let integers = tokens.iter().all(|token| token.bytes().all(|b| b.is_ascii_digit()));
+    let mut color = [1.0; 4];
+    for (c, token) in color.iter_mut().zip(&tokens) {
+        *c = if integers {
+            token.parse::<u8>().ok()? as f64 / 255.0
+        } else {
+            token.parse().ok()?
+        };
+    }
+    Some(color)
 }
 
 impl<'a> OffReader<'a> {
@@ -230,6 +273,7 @@ impl<'a> OffReader<'a> {
         Self {
             iter: TokenIter::new(src),
             abs: AbstractBuilder::new(),
+            face_colors: Vec::new(),
         }
     }
 
@@ -343,15 +387,15 @@ impl<'a> OffReader<'a> {
                 }
             }
 
+            // Reads the colour info, and goes to the end of the line in order
+            // to ignore anything else.
+            let color = self.iter.rest_of_line().and_then(parse_color);
+
             // If these are truly faces and not just components, we add them.
             // Hopefully the compiler can optimize this better, I'm lazy.
             if rank != 3 {
                 faces.push(face);
-            }
-
-            // Goes to the end of the line in order to ignore things like colour info.
-            if self.iter.position.column != 0 {
-                self.iter.comment = true;
+                self.face_colors.push(color);
             }
         }
 
@@ -394,26 +438,51 @@ impl<'a> OffReader<'a> {
         Ok(els_subs)
     }
 
-    /*
-    /// Returns the [`Name`] stored in the OFF file, if any.
-    fn name(&self) -> Option<Name<Con>> {
+    /// Returns the [`PolytopeMeta`] stored in the OFF file, if any.
+    fn meta(&self) -> PolytopeMeta {
         self.src()
             .lines()
             .next()
-            .map(Concrete::name_from_src)
-            .flatten()
-    }*/
+            .and_then(Concrete::meta_from_src)
+            .unwrap_or_default()
+    }
 
-    /// Builds a concrete polytope from the OFF reader.
-    pub fn build(mut self) -> OffParseResult<Concrete> {
+    /// Builds a concrete polytope from the OFF reader, along with its
+    /// metadata.
+    pub fn build(self) -> OffParseResult<Concrete> {
+        self.build_with_colors().map(|(poly, _)| poly)
+    }
+
+    /// Builds a concrete polytope from the OFF reader, along with its
+    /// metadata and the colors of its faces. Only the faces of polytopes of
+    /// rank at least 4 are tagged, and only if their lines have a color.
+    pub fn build_with_colors(self) -> OffParseResult<(Concrete, ElementTags<Option<Rgba>>)> {
+        let meta = self.meta();
+        let (poly, face_colors) = self.build_polytope()?;
+        let mut poly = poly.with_meta(meta);
+        poly.take_history_from_meta();
+        let colors = ElementTags::from_fn(&poly, |r, idx| {
+            if r == 3 {
+                face_colors.get(idx).copied().flatten()
+            } else {
+                None
+            }
+        });
+
+        Ok((poly, colors))
+    }
+
+    /// Builds a concrete polytope from the OFF reader, along with the colors
+    /// of its faces.
+    fn build_polytope(mut self) -> OffParseResult<(Concrete, Vec<Option<Rgba>>)> {
         // Reads the rank of the polytope.
         let rank = self.rank()?;
 
         // Deals with dumb degenerate cases.
         match rank {
-            0 => return Ok(Concrete::nullitope()),
-            1 => return Ok(Concrete::point()),
-            2 => return Ok(Concrete::dyad()),
+            0 => return Ok((Concrete::nullitope(), Vec::new())),
+            1 => return Ok((Concrete::point(), Vec::new())),
+            2 => return Ok((Concrete::dyad(), Vec::new())),
             _ => {}
         }
 
@@ -447,48 +516,126 @@ impl<'a> OffReader<'a> {
         // Builds the concrete polytope.
 
         // Safety: TODO this isn't actually safe. We need to do some checking.
-        Ok(Concrete::new(vertices, unsafe { self.abs.build() }))
+        Ok((Concrete::new(vertices, unsafe { self.abs.build() }), self.face_colors))
     }
 }
 
-/*
 impl Concrete {
-    /// Gets the name from the first line of an OFF file.
-    fn name_from_src(first_line: &str) -> Option<Name<Con>> {
+    /// Reads a polytope from an OFF file, along with the colors of its faces.
+    /// See [`OffReader::build_with_colors`].
+    pub fn from_off_with_colors(src: &str) -> OffParseResult<(Self, ElementTags<Option<Rgba>>)> {
+        OffReader::new(src).build_with_colors()
+    }
+
+    /// Gets the metadata from the first line of an OFF file.
+    fn meta_from_src(first_line: &str) -> Option<PolytopeMeta> {
         let mut fl_iter = first_line.char_indices();
 
         if let Some((_, '#')) = fl_iter.next() {
             let (idx, _) = fl_iter.next()?;
-            if let Ok(new_name) = ron::from_str(&first_line[idx..]) {
-                return Some(new_name);
+            if let Ok(meta) = ron::from_str(&first_line[idx..]) {
+                return Some(meta);
             }
         }
 
         None
     }
 
-    /// Gets the name from an OFF file, assuming it's stored in RON in the first
-    /// line of the file.
-    pub fn name_from_off<T: AsRef<Path>>(path: T) -> Option<Name<Con>> {
+    /// Gets the metadata from an OFF file, assuming it's stored in RON in the
+    /// first line of the file.
+    pub fn meta_from_off<T: AsRef<Path>>(path: T) -> Option<PolytopeMeta> {
         use std::io::{BufRead, BufReader};
 
-        let file = BufReader::new(fs::File::open(path).ok()?);
+        let file = BufReader::new(std::fs::File::open(path).ok()?);
         let first_line = file.lines().next()?.ok()?;
 
-        Self::name_from_src(&first_line)
+        Self::meta_from_src(&first_line)
     }
-}*/
+}
+
+/// A color, given by its red, green, blue and alpha components, from 0 to 1.
+pub type Rgba = [f64; 4];
+
+/// How to color the faces of a polytope in an OFF file. The colors are written
+/// after the vertices of each face, and only for polytopes of rank at least 4.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorBy {
+    /// Don't color the faces.
+    None,
+
+    /// Color each face by the orbit of the facets it's on under the symmetry
+    /// group of the polytope. A face on facets from several orbits gets the
+    /// color of the first one. For polyhedra, this is the same as
+    /// [`Self::FaceOrbit`].
+    FacetOrbit,
+
+    /// Color each face by its orbit under the symmetry group of the polytope.
+    FaceOrbit,
+}
+
+impl Default for ColorBy {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+/// Returns the color of an orbit. The hues go around the color wheel by the
+/// golden angle, so that consecutive orbits get contrasting colors however
+/// many there are.
+pub fn orbit_color(orbit: usize) -> Rgba {
+    const SATURATION: f64 = 0.6;
+    const VALUE: f64 = 0.9;
+
+    let hue = (orbit as f64 * 0.618_033_988_749_895).fract() * 6.0;
+    let sector = hue.floor();
+    let fract = hue - sector;
+    let (p, q, t) = (
+        VALUE * (1.0 - SATURATION),
+        VALUE * (1.0 - SATURATION * fract),
+        VALUE * (1.0 - SATURATION * (1.0 - fract)),
+    );
+
+    let (r, g, b) = match sector as u8 {
+        0 => (VALUE, t, p),
+        1 => (q, VALUE, p),
+        2 => (p, VALUE, t),
+        3 => (p, q, VALUE),
+        4 => (t, p, VALUE),
+        _ => (VALUE, p, q),
+    };
+    [r, g, b, 1.0]
+}
 
 /// A set of options to be used when saving the OFF file.
 #[derive(Clone, Copy)]
 pub struct OffOptions {
     /// Whether the OFF file should have comments specifying each face type.
     pub comments: bool,
+
+    /// The number of digits written after the decimal point of each
+    /// coordinate. If `None`, the coordinates are written with as many digits
+    /// as needed to read them back exactly.
+    pub precision: Option<usize>,
+
+    /// Whether each vertex should be followed by a comment with the closed
+    /// forms of its coordinates, as found by [`recognize_constant`]. The
+    /// coordinates that aren't recognized are written as `?`. This is
+    /// independent of [`Self::comments`], and doesn't change the coordinates
+    /// themselves.
+    pub exact: bool,
+
+    /// How to color the faces.
+    pub color_by: ColorBy,
 }
 
 impl Default for OffOptions {
     fn default() -> Self {
-        OffOptions { comments: true }
+        OffOptions {
+            comments: true,
+            precision: None,
+            exact: false,
+            color_by: ColorBy::None,
+        }
     }
 }
 
@@ -511,6 +658,10 @@ pub enum OffWriteError {
         /// The index of the second edge.
         idx1: usize,
     },
+
+    /// The faces were to be colored by orbit, but the symmetry group of the
+    /// polytope couldn't be computed.
+    NoSymmetryGroup,
 }
 
 impl Display for OffWriteError {
@@ -524,6 +675,7 @@ impl Display for OffWriteError {
                 "cannot write coincident edges with indices {} and {}",
                 idx0, idx1
             ),
+            Self::NoSymmetryGroup => write!(f, "cannot color faces by orbit without a symmetry group"),
         }
     }
 }
@@ -689,15 +841,68 @@ impl<'a> OffWriter<'a> {
         }
 
         // Adds the coordinates.
+        let OffOptions { precision, exact, .. } = self.options;
         for v in &self.poly.vertices {
-            for c in v {
-                self.push_to_str(c);
+            for &c in v {
+                self.push_str(format_coordinate(c, precision));
                 self.push(' ');
             }
+
+            if exact {
+                self.push('#');
+                for &c in v {
+                    self.push(' ');
+                    match recognize_constant(c, DEFAULT_TOLERANCE) {
+                        Some(form) => self.push_to_str(form),
+                        None => self.push('?'),
+                    }
+                }
+            }
             self.push('\n');
         }
     }
 
+    /// Returns the orbit that gives the color of each face, as set by
+    /// [`OffOptions::color_by`].
+    ///
+    /// This method should only be called when rank >= 4.
+    fn face_orbits(&self) -> OffWriteResult<Option<Vec<usize>>> {
+        let color_by = self.options.color_by;
+        if color_by == ColorBy::None {
+            return Ok(None);
+        }
+
+        let (_, vertex_map) = self
+            .poly
+            .clone()
+            .get_symmetry_group()
+            .ok_or(OffWriteError::NoSymmetryGroup)?;
+        let rank = self.rank();
+        if color_by == ColorBy::FaceOrbit || rank == 4 {
+            return Ok(Some(self.poly.element_orbit_indices(3, &vertex_map)));
+        }
+
+        // Goes up from each face to the facets it's on.
+        let facet_orbits = self.poly.element_orbit_indices(rank - 1, &vertex_map);
+        let orbits = (0..self.el_count(3))
+            .map(|idx| {
+                let mut elements = vec![idx];
+                for r in 3..rank - 1 {
+                    let mut sups: Vec<_> = elements
+                        .iter()
+                        .flat_map(|&el| self.poly[(r, el)].sups.iter().copied())
+                        .collect();
+                    sups.sort_unstable();
+                    sups.dedup();
+                    elements = sups;
+                }
+
+                elements.into_iter().map(|facet| facet_orbits[facet]).min().unwrap_or_default()
+            })
+            .collect();
+        Ok(Some(orbits))
+    }
+
     /// Gets and writes the faces of a polytope into an OFF file.
     ///
     /// This method should only be called when rank >= 3.
@@ -724,6 +929,7 @@ impl<'a> OffWriter<'a> {
                 self.push('\n');
             }
         } else {
+            let face_orbits = self.face_orbits()?;
             for (idx, face) in self.poly[3].iter().enumerate() {
                 self.push_to_str(face.subs.len());
                 let mut cycles =
@@ -737,6 +943,13 @@ impl<'a> OffWriter<'a> {
                     self.push(' ');
                     self.push_to_str(v);
                 }
+
+                if let Some(face_orbits) = &face_orbits {
+                    for c in orbit_color(face_orbits[idx]).iter() {
+                        self.push(' ');
+                        self.push_str(format_coordinate(*c, Some(3)));
+                    }
+                }
                 self.push('\n');
             }
         }
@@ -771,11 +984,13 @@ impl<'a> OffWriter<'a> {
     pub fn build(mut self) -> OffWriteResult<String> {
         let rank = self.poly.rank();
 
-        // Serialized name.
-        /* self.off.push_str("# ");
-        self.off
-            .push_str(&ron::to_string(&self.polytope.name).unwrap_or_default());
-        self.off.push('\n'); */
+        // Serialized metadata, along with the operation log.
+        let meta = self.poly.meta_with_history();
+        if !meta.is_empty() {
+            self.push_str("# ");
+            self.push_str(ron::to_string(&meta).unwrap_or_default());
+            self.push('\n');
+        }
 
         // Blatant advertising.
         if self.comments() {
@@ -869,6 +1084,7 @@ impl Concrete {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::conc::ConcretePolytope;
     use crate::file::FromFile;
     use crate::test;
 
@@ -973,4 +1189,122 @@ mod tests {
     fn parse() {
         unwrap_off("OFF\n10 foo bar")
     }
+
+    /// Checks that the metadata of a polytope survives being saved and loaded.
+    #[test]
+    fn meta() {
+        let mut meta = PolytopeMeta::named("cube");
+        meta.source = Some("cube.off".to_string());
+        meta.properties.insert("family".to_string(), "measure \"polytopes\"".to_string());
+        let cube = Concrete::hypercube(4).with_meta(meta.clone());
+
+        for &comments in &[true, false] {
+            let off = cube
+                .to_off(OffOptions {
+                    comments,
+                    ..Default::default()
+                })
+                .unwrap();
+            assert_eq!(Concrete::from_off(&off).unwrap().meta, meta);
+        }
+
+        // So does the log of its operations.
+        let mut scaled = cube.clone().with_history();
+        scaled.scale(2.0);
+        let off = scaled.to_off(Default::default()).unwrap();
+        let read = Concrete::from_off(&off).unwrap();
+        assert_eq!(read.meta, meta);
+        assert_eq!(read.history, scaled.history);
+
+        // Unnamed polytopes don't get the extra line.
+        let off = Concrete::hypercube(4).to_off(Default::default()).unwrap();
+        assert!(off.starts_with(&format!("# {}", HEADER)));
+    }
+    /// The precision and the closed forms of the coordinates only change
+    /// what's written, and the closed forms never change the coordinates.
+    #[test]
+    fn coordinates() {
+        let ike = Concrete::from_off(include_str!("../../../tests/fixtures/ike.off")).unwrap();
+        let full = ike.to_off(Default::default()).unwrap();
+        let exact = ike
+            .to_off(OffOptions {
+                exact: true,
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert!(exact.contains("0 1/2 φ/2\n"));
+        assert!(!exact.contains('?'));
+        assert_eq!(Concrete::from_off(&exact).unwrap().vertices, Concrete::from_off(&full).unwrap().vertices);
+
+        let rounded = ike
+            .to_off(OffOptions {
+                precision: Some(3),
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(rounded.contains("0.000 0.500 0.809 \n"));
+        for (v, w) in Concrete::from_off(&rounded).unwrap().vertices.iter().zip(&ike.vertices) {
+            assert!((v - w).norm() < 1e-3);
+        }
+    }
+
+    /// Coloring the faces of the rhombicuboctahedron by orbit tells apart the
+    /// squares on the axes from the diagonal ones, and the colors are read
+    /// back from the file.
+    #[test]
+    fn colors() {
+        use crate::conc::ConcretePolytope;
+        use std::collections::BTreeMap;
+
+        let mut cube = Concrete::hypercube(4);
+        cube.element_sort();
+        let sirco = cube.truncate_with(vec![0, 2], vec![1.0, 0.0, std::f64::consts::SQRT_2]);
+        let off = sirco
+            .to_off(OffOptions {
+                color_by: ColorBy::FaceOrbit,
+                ..Default::default()
+            })
+            .unwrap();
+        let (poly, colors) = Concrete::from_off_with_colors(&off).unwrap();
+
+        // The number of faces of each color, by their number of sides.
+        let mut counts = BTreeMap::new();
+        for (idx, face) in poly[3].iter().enumerate() {
+            let color = colors[(3, idx)].unwrap();
+            let key = (face.subs.len(), color.iter().map(|c| (c * 1000.0).round() as u32).collect::<Vec<_>>());
+            *counts.entry(key).or_insert(0) += 1;
+        }
+        let mut sizes: Vec<_> = counts.iter().map(|((sides, _), &count)| (*sides, count)).collect();
+        sizes.sort_unstable();
+        assert_eq!(sizes, vec![(3, 8), (4, 6), (4, 12)]);
+        assert!(colors[1].iter().all(Option::is_none));
+
+        // Every facet of the tesseract is in the same orbit.
+        let off = Concrete::hypercube(5)
+            .to_off(OffOptions {
+                color_by: ColorBy::FacetOrbit,
+                ..Default::default()
+            })
+            .unwrap();
+        let (_, colors) = Concrete::from_off_with_colors(&off).unwrap();
+        for color in &colors[3] {
+            for (c, d) in color.unwrap().iter().zip(&orbit_color(0)) {
+                assert!((c - d).abs() < 1e-3);
+            }
+        }
+
+        let off = sirco.to_off(Default::default()).unwrap();
+        let (_, colors) = Concrete::from_off_with_colors(&off).unwrap();
+        assert!(colors[3].iter().all(Option::is_none));
+    }
+
+    /// Colors are read both as integers and as floats, with or without alpha.
+    #[test]
+    fn parse_colors() {
+        assert_eq!(parse_color(" 255 0 51 "), Some([1.0, 0.0, 0.2, 1.0]));
+        assert_eq!(parse_color("1.0 0.5 0 0.25"), Some([1.0, 0.5, 0.0, 0.25]));
+        assert_eq!(parse_color("3"), None);
+        assert_eq!(parse_color("256 0 0"), None);
+    }
 }
@@ -13,13 +13,15 @@ pub type VectorSlice<'a, T> = nalgebra::DVectorSlice<'a, T>;
 pub type Matrix<T> = nalgebra::DMatrix<T>;
 
 use std::{
-    borrow::Cow,
+    borrow::{Borrow, Cow},
+    cmp::Ordering,
+    iter,
     ops::{Index, IndexMut},
 };
 
 use crate::{
     float::Float,
-    ElementMap, conc::Concrete, abs::Ranked, Polytope,
+    ElementMap, conc::{Concrete, ConcretePolytope}, abs::{Rank, Ranked}, Polytope,
 };
 
 use approx::{abs_diff_eq, abs_diff_ne};
@@ -93,12 +95,76 @@ impl<T: Float> Hypersphere<T> {
     }
 }
 
+/// Adds up some numbers with Kahan's compensated summation. This keeps the
+/// rounding error from growing with the number of terms, so that the result
+/// barely depends on their order.
+pub fn kahan_sum<T: Float, I: IntoIterator<Item = T>>(iter: I) -> T {
+    let mut sum = T::ZERO;
+    let mut compensation = T::ZERO;
+
+    for x in iter {
+        let y = x - compensation;
+        let t = sum + y;
+        compensation = (t - sum) - y;
+        sum = t;
+    }
+
+    sum
+}
+
+/// Takes the dot product of two vectors using [`kahan_sum`].
+pub fn kahan_dot<T: Float>(v: &Vector<T>, w: &Vector<T>) -> T {
+    kahan_sum(v.iter().zip(w.iter()).map(|(&x, &y)| x * y))
+}
+
+/// Adds up some points of a given dimension coordinatewise, using Kahan's
+/// compensated summation like [`kahan_sum`].
+pub fn kahan_sum_points<T: Float, P: Borrow<Point<T>>, I: IntoIterator<Item = P>>(
+    dim: usize,
+    iter: I,
+) -> Point<T> {
+    let mut sum = Point::zeros(dim);
+    let mut compensation = Point::<T>::zeros(dim);
+
+    for p in iter {
+        for (i, &x) in p.borrow().iter().enumerate() {
+            let y = x - compensation[i];
+            let t = sum[i] + y;
+            compensation[i] = (t - sum[i]) - y;
+            sum[i] = t;
+        }
+    }
+
+    sum
+}
+
+/// Compares two points lexicographically by their coordinates. Unlike
+/// [`PointOrd`], this doesn't treat nearby coordinates as equal, so it's a
+/// total order on points without NaN values.
+///
+/// # Panics
+/// Panics if either point has NaN values.
+pub fn cmp_coordinates<T: Float>(p: &Point<T>, q: &Point<T>) -> Ordering {
+    p.iter()
+        .zip(q.iter())
+        .map(|(x, y)| x.partial_cmp(y).expect("Point has NaN values"))
+        .find(|&ordering| ordering != Ordering::Equal)
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Collects some points sorted by [`cmp_coordinates`].
+fn sorted_points<'a, T: Float, U: Iterator<Item = &'a Point<T>>>(points: U) -> Vec<&'a Point<T>> {
+    let mut points: Vec<_> = points.collect();
+    points.sort_by(|p, q| cmp_coordinates(p, q));
+    points
+}
+
 /// Represents an (affine) subspace, passing through a given point and generated
 /// by a given basis.
 ///
 /// TODO: Use asserts to guarantee that the basis is an orthogonal basis of unit
 /// vectors.
-#[derive(Clone,)]
+#[derive(Clone, Debug)]
 pub struct Subspace<T: Float> {
     /// An orthogonal basis for the subspace, defined by unit vectors.
     pub basis: Vec<Vector<T>>,
@@ -144,12 +210,24 @@ impl<T: Float> Subspace<T> {
     /// subspace remains unchanged and we return `None`. Otherwise, a new basis
     /// vector is added, and we return a reference to it.
     ///
+    /// The first coordinate of the new basis vector that isn't close to zero
+    /// is always positive.
+    ///
     /// # Todo:
     /// Implement the [Gram-Schmidt process](https://en.wikipedia.org/wiki/Gram%E2%80%93Schmidt_process#Numerical_stability).
     pub fn add(&mut self, p: &Point<T>) -> Option<&Point<T>> {
+        self.add_within(p, T::EPS)
+    }
+
+    /// Adds a point to the subspace, like [`Self::add`], but only if it's
+    /// farther than some tolerance from it.
+    pub fn add_within(&mut self, p: &Point<T>, tolerance: T) -> Option<&Point<T>> {
         let mut v = p - self.project(p);
 
-        if v.normalize_mut() > T::EPS {
+        if v.normalize_mut() > tolerance {
+            if matches!(v.iter().find(|x| x.fabs() > T::EPS), Some(&x) if x < T::ZERO) {
+                v = -v;
+            }
             self.basis.push(v);
             self.basis.last()
         } else {
@@ -161,9 +239,20 @@ impl<T: Float> Subspace<T> {
     /// of full rank, we return it early. Otherwise, we traverse through the
     /// entire iterator.
     ///
+    /// The points are sorted by [`cmp_coordinates`] first, so that the
+    /// subspace, its offset and its basis don't depend on their order.
+    ///
     /// Consider using [`Self::from_points_with`] if you expect your subspace to
     /// have an exact rank.
-    pub fn from_points<'a, U: Iterator<Item = &'a Point<T>>>(mut iter: U) -> Self {
+    pub fn from_points<'a, U: Iterator<Item = &'a Point<T>>>(iter: U) -> Self {
+        Self::from_points_within(iter, T::EPS)
+    }
+
+    /// Creates a subspace from an iterator over points, like
+    /// [`Self::from_points`], but ignoring the points closer than some
+    /// tolerance to the subspace spanned by the previous ones.
+    pub fn from_points_within<'a, U: Iterator<Item = &'a Point<T>>>(iter: U, tolerance: T) -> Self {
+        let mut iter = sorted_points(iter).into_iter();
         let mut subspace = Self::new(
             iter.next()
                 .expect("A hyperplane can't be created from an empty point array!")
@@ -173,7 +262,7 @@ impl<T: Float> Subspace<T> {
         for p in iter {
             // If the subspace is of full rank, we don't need to check any
             // more points.
-            if subspace.add(p).is_some() && subspace.is_full_rank() {
+            if subspace.add_within(p, tolerance).is_some() && subspace.is_full_rank() {
                 return subspace;
             }
         }
@@ -189,11 +278,13 @@ impl<T: Float> Subspace<T> {
     /// `None` after traversing all of them.
     ///
     /// This method is only faster than the usual one when the specified rank
-    /// isn't equal to the dimension of the points.
+    /// isn't equal to the dimension of the points. Like the usual one, it sorts
+    /// the points first.
     pub fn from_points_with<'a, U: Iterator<Item = &'a Point<T>>>(
-        mut points: U,
+        points: U,
         rank: usize,
     ) -> Option<Self> {
+        let mut points = sorted_points(points).into_iter();
         let mut subspace = Self::new(
             points
                 .next()
@@ -213,20 +304,15 @@ impl<T: Float> Subspace<T> {
     /// Projects a point onto the subspace.
     pub fn project(&self, p: &Point<T>) -> Point<T> {
         let p = p - &self.offset;
-        let mut q = self.offset.clone();
-
-        for b in &self.basis {
-            q += b * p.dot(b);
-        }
-
-        q
+        let terms = self.basis.iter().map(|b| b * kahan_dot(&p, b));
+        kahan_sum_points(self.dim(), iter::once(self.offset.clone()).chain(terms))
     }
 
     /// Projects a point onto the subspace, but returns lower-dimensional
     /// coordinates in the subspace's basis.
     pub fn flatten(&self, p: &Point<T>) -> Point<T> {
         let p = p - &self.offset;
-        Point::from_iterator(self.rank(), self.basis.iter().map(|b| p.dot(b)))
+        Point::from_iterator(self.rank(), self.basis.iter().map(|b| kahan_dot(&p, b)))
     }
 
     /// Projects a set of points onto the subspace, but returns
@@ -258,6 +344,30 @@ impl<T: Float> Subspace<T> {
         (p - self.project(p)).try_normalize(T::EPS)
     }
 
+    /// Intersects the subspace with a hyperplane. Returns the subspace itself
+    /// if it's contained in the hyperplane, and `None` if it's parallel to it
+    /// but not contained in it.
+    pub fn intersect_hyperplane(&self, hyperplane: &Hyperplane<T>) -> Option<Self> {
+        // The direction within the subspace along which the distance to the
+        // hyperplane changes the fastest.
+        let slope: Vec<T> = self.basis.iter().map(|b| kahan_dot(b, &hyperplane.normal)).collect();
+        let squared_slope = kahan_sum(slope.iter().map(|&c| c * c));
+        let distance = hyperplane.distance(&self.offset);
+
+        if squared_slope.fsqrt() < T::EPS {
+            return abs_diff_eq!(distance, T::ZERO, epsilon = T::EPS).then(|| self.clone());
+        }
+
+        let steepest = kahan_sum_points(self.dim(), self.basis.iter().zip(&slope).map(|(b, &c)| b * c)) / squared_slope;
+        let offset = &self.offset - &steepest * distance;
+        let mut subspace = Self::new(offset.clone());
+        for b in &self.basis {
+            subspace.add(&(&offset + b - &steepest * kahan_dot(b, &hyperplane.normal)));
+        }
+
+        Some(subspace)
+    }
+
     // Computes a set of independent vectors that span the orthogonal
     // complement of the subspace.
     /* pub fn orthogonal_comp(&self) -> Vec<Vector> {
@@ -265,9 +375,17 @@ impl<T: Float> Subspace<T> {
     } */
 }
 
+impl Subspace<f64> {
+    /// Creates a subspace from an iterator over points, like
+    /// [`Self::from_points_within`] with the tolerance of a geometry context.
+    pub fn from_points_in<'a, U: Iterator<Item = &'a Point<f64>>>(iter: U, context: &GeomContext) -> Self {
+        Self::from_points_within(iter, context.tolerance)
+    }
+}
+
 impl Concrete {
     /// Computes the affine hull of an element.
-    pub fn affine_hull(&self, rank: usize, idx: usize) -> Subspace<f64> {
+    pub fn affine_hull(&self, rank: impl Into<Rank>, idx: usize) -> Subspace<f64> {
         Subspace::from_points(
             &mut self.element(rank, idx).unwrap().vertices.iter(),
         )
@@ -275,11 +393,19 @@ impl Concrete {
 
     /// Computes the affine hulls of all elements and puts them in an `ElementMap`.
     pub fn element_map_affine_hulls(&self) -> ElementMap<Subspace<f64>> {
+        self.element_map_affine_hulls_in(&GeomContext::default())
+    }
+
+    /// Computes the affine hulls of all elements like
+    /// [`Self::element_map_affine_hulls`], ignoring the vertices closer to
+    /// the hull of the others than the tolerance of a geometry context.
+    pub fn element_map_affine_hulls_in(&self, context: &GeomContext) -> ElementMap<Subspace<f64>> {
         let mut element_map = ElementMap::new();
         for r in 1..self.rank() {
             element_map.push(Vec::new());
-            for (idx, _el) in self[r].iter().enumerate() {
-                element_map[r-1].push(self.affine_hull(r, idx));
+            for idx in 0..self.el_count(r) {
+                let vertices = self.element(r, idx).unwrap().vertices;
+                element_map[r-1].push(Subspace::from_points_in(vertices.iter(), context));
             }
         }
         element_map
@@ -312,6 +438,40 @@ impl<T: Float> Hyperplane<T> {
         Self { subspace, normal }
     }
 
+    /// Builds an oriented hyperplane from a subspace, if it's a hyperplane.
+    /// The normal vector points away from the origin, unless the hyperplane
+    /// passes through it.
+    pub fn from_subspace(subspace: Subspace<T>) -> Option<Self> {
+        if !subspace.is_hyperplane() {
+            return None;
+        }
+
+        // Any coordinate axis that's far enough from the subspace gives the
+        // normal, once its components along the subspace are removed.
+        let dim = subspace.dim();
+        let mut normal = (0..dim)
+            .map(|i| {
+                let mut e = Vector::zeros(dim);
+                e[i] = T::ONE;
+                for b in &subspace.basis {
+                    e -= b * b[i];
+                }
+                e
+            })
+            .max_by(|v, w| v.norm().partial_cmp(&w.norm()).unwrap())?
+            .normalize();
+
+        if kahan_dot(&subspace.offset, &normal) < T::ZERO {
+            normal = -normal;
+        }
+        Some(Self { subspace, normal })
+    }
+
+    /// Returns the unit normal vector of the hyperplane.
+    pub fn normal(&self) -> &Vector<T> {
+        &self.normal
+    }
+
     /// Projects a point onto the hyperplane.
     pub fn project(&self, p: &Point<T>) -> Point<T> {
         self.subspace.project(p)
@@ -423,14 +583,14 @@ impl<T: Float, R: Dim, C: Dim> PartialOrd for MatrixOrdMxN<T, R, C>
 where
     DefaultAllocator: Allocator<T, R, C>,
 {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         for (x, y) in self.iter().zip(other.iter()) {
             if abs_diff_ne!(x, y, epsilon = T::EPS) {
                 return x.partial_cmp(y);
             }
         }
 
-        Some(std::cmp::Ordering::Equal)
+        Some(Ordering::Equal)
     }
 }
 
@@ -438,7 +598,7 @@ impl<T: Float, R: Dim, C: Dim> Ord for MatrixOrdMxN<T, R, C>
 where
     DefaultAllocator: Allocator<T, R, C>,
 {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+    fn cmp(&self, other: &Self) -> Ordering {
         self.partial_cmp(other).expect("Matrix has NaN values")
     }
 }
@@ -480,6 +640,103 @@ pub type MatrixOrd<T> = MatrixOrdMxN<T, Dynamic, Dynamic>;
 /// [`MatrixOrdMxN`].
 pub type PointOrd<T> = MatrixOrdMxN<T, Dynamic, U1>;
 
+/// The point about which a polytope is taken to be centered when computing its
+/// symmetries, which are isometries fixing the origin.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CenterPolicy {
+    /// The polytope is used as it is, so it should already be centered at the
+    /// origin.
+    Keep,
+
+    /// The gravicenter of the vertices.
+    Gravicenter,
+
+    /// The center of the circumsphere, or the gravicenter if there's none.
+    Circumcenter,
+}
+
+impl Default for CenterPolicy {
+    fn default() -> Self {
+        Self::Keep
+    }
+}
+
+/// The geometric settings shared by the operations of a workflow, such as
+/// computing the symmetries of a polytope and then faceting it, so that they
+/// don't end up comparing points with different tolerances.
+///
+/// The default context is the one used by the methods that don't take one,
+/// like [`Concrete::get_symmetry_group`] and [`Subspace::from_points`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GeomContext {
+    /// The distance below which two coordinates or a point and a subspace are
+    /// considered to coincide.
+    pub tolerance: f64,
+
+    /// The point about which symmetries are computed.
+    pub center: CenterPolicy,
+
+    /// If set, coordinates are rounded to the nearest multiple of this before
+    /// being compared, which makes the comparison of points that are close to
+    /// each other consistent.
+    pub quantum: Option<f64>,
+}
+
+impl Default for GeomContext {
+    fn default() -> Self {
+        Self {
+            tolerance: f64::EPS,
+            center: CenterPolicy::Keep,
+            quantum: None,
+        }
+    }
+}
+
+impl GeomContext {
+    /// Returns the default context with another tolerance.
+    pub fn with_tolerance(tolerance: f64) -> Self {
+        Self {
+            tolerance,
+            ..Default::default()
+        }
+    }
+
+    /// Rounds a coordinate to the grid given by [`Self::quantum`], if any.
+    pub fn quantize(&self, x: f64) -> f64 {
+        match self.quantum {
+            Some(quantum) if quantum > 0.0 => (x / quantum).round() * quantum,
+            _ => x,
+        }
+    }
+
+    /// Compares two points lexicographically, like [`PointOrd`] does, but
+    /// with the tolerance of the context and after rounding the coordinates
+    /// to its grid.
+    pub fn cmp_points(&self, p: &Point<f64>, q: &Point<f64>) -> Ordering {
+        for (&x, &y) in p.iter().zip(q.iter()) {
+            let (x, y) = (self.quantize(x), self.quantize(y));
+            if abs_diff_ne!(x, y, epsilon = self.tolerance) {
+                return x.partial_cmp(&y).expect("Point has NaN values");
+            }
+        }
+
+        Ordering::Equal
+    }
+
+    /// Returns the point a polytope is centered at under [`Self::center`], or
+    /// `None` if it's used as it is or its center can't be found.
+    pub fn center_of(&self, poly: &Concrete) -> Option<Point<f64>> {
+        match self.center {
+            CenterPolicy::Keep => None,
+            CenterPolicy::Gravicenter => poly.gravicenter(),
+            CenterPolicy::Circumcenter => poly
+                .circumsphere()
+                .map(|sphere| sphere.center)
+                .or_else(|| poly.gravicenter()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -518,4 +775,21 @@ mod tests {
             dvector![4.0 / 3.0, 4.0 / 3.0, 4.0 / 3.0, 4.0 / 3.0],
         );
     }
+
+    #[test]
+    /// Compares points up to the tolerance of a geometry context, after
+    /// rounding them to its quantum.
+    pub fn cmp_points() {
+        let context = GeomContext::with_tolerance(1e-3);
+        assert_eq!(context.cmp_points(&dvector![1.0, 2.0], &dvector![1.0005, 1.9995]), Ordering::Equal);
+        assert_eq!(context.cmp_points(&dvector![1.0, 2.0], &dvector![1.0005, 2.1]), Ordering::Less);
+        assert_eq!(context.cmp_points(&dvector![1.1, 0.0], &dvector![1.0, 2.0]), Ordering::Greater);
+
+        let context = GeomContext {
+            quantum: Some(0.25),
+            ..Default::default()
+        };
+        assert_eq!(context.quantize(0.3), 0.25);
+        assert_eq!(context.cmp_points(&dvector![0.3, 0.9], &dvector![0.2, 1.1]), Ordering::Equal);
+    }
 }
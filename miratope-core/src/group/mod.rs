@@ -3,13 +3,16 @@
 pub mod cyclic;
 pub mod gen_iter;
 pub mod group_item;
+pub mod name;
 pub mod pairs;
 pub mod permutation;
 
 pub use gen_iter::*;
 
 use std::{
-    array, iter,
+    array,
+    collections::{BTreeMap, BTreeSet, HashSet},
+    iter,
     iter::{Filter, Map, Once},
     vec,
 };
@@ -17,12 +20,13 @@ use std::{
 use crate::{
     cox::{cd::CdResult, Cox},
     float::Float,
-    geometry::Matrix,
+    geometry::{Matrix, MatrixOrd, Point, PointOrd},
 };
 
 use self::{
     cyclic::Cyclic,
     group_item::GroupItem,
+    name::{GroupName, Profile},
     pairs::{AsPair, PairMap},
     permutation::{PermutationIter, SPermutation},
 };
@@ -31,6 +35,7 @@ use nalgebra::{
     allocator::Allocator, dmatrix, Const, DefaultAllocator, Quaternion, Rotation, UnitQuaternion,
 };
 
+use gcd::Gcd;
 use unchecked_unwrap::UncheckedUnwrap;
 
 /// The type of the dimension associated to an iterator.
@@ -479,6 +484,536 @@ impl<T: Float, I: Iterator<Item = Matrix<T>>> Group<I> {
     */
 }
 
+/// Reflection groups and fundamental domains.
+///
+/// The roots of the reflections are normalized, and a root is positive when
+/// its first nonzero coordinate is. This lexicographic order picks out the
+/// simple roots, which bound the dominant Weyl chamber: the points whose dot
+/// product with every simple root is nonnegative.
+impl<I: Iterator<Item = Matrix<f64>> + Clone> Group<I>
+where
+    I::Item: GroupItem,
+{
+    /// Returns the positive roots of the reflections in the group, in the
+    /// order the reflections are found.
+    pub fn positive_roots(&self) -> Vec<Point<f64>> {
+        let mut roots: Vec<Point<f64>> = Vec::new();
+
+        for el in self.clone() {
+            let dim = el.nrows();
+            let id = Matrix::identity(dim, dim);
+
+            // A reflection is an involution fixing a hyperplane.
+            if (&el * &el - &id).norm() > f64::EPS
+                || (el.trace() - (dim as f64 - 2.0)).abs() > f64::EPS
+            {
+                continue;
+            }
+
+            // Every column of I - R is a multiple of the root, so we take the longest.
+            let diff = id - el;
+            let col = (0..dim)
+                .max_by(|&i, &j| diff.column(i).norm().partial_cmp(&diff.column(j).norm()).unwrap())
+                .unwrap();
+            let mut root: Point<f64> = diff.column(col).normalize();
+
+            if !is_positive(&root) {
+                root = -root;
+            }
+            if roots.iter().all(|other| (other - &root).norm() > f64::EPS) {
+                roots.push(root);
+            }
+        }
+
+        roots
+    }
+
+    /// Returns the simple roots of the reflections in the group. These are the
+    /// positive roots whose reflections send every other positive root to a
+    /// positive root. If the group contains no reflections, this is empty.
+    pub fn simple_roots(&self) -> Vec<Point<f64>> {
+        let roots = self.positive_roots();
+
+        roots
+            .iter()
+            .filter(|root| {
+                roots.iter().all(|other| {
+                    let image = other - *root * (2.0 * other.dot(root));
+                    (other - *root).norm() < f64::EPS || is_positive(&image)
+                })
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Returns a canonical representative of the orbit of a point under the
+    /// group. This is the lexicographically largest image of the point inside
+    /// the dominant Weyl chamber of the reflections in the group. If the group
+    /// is generated by reflections, it's the only image there. If it contains
+    /// no reflections, it's the lexicographically largest image overall.
+    pub fn fundamental_domain_representative(&self, point: &Point<f64>) -> Point<f64> {
+        self.representative_with_roots(point, &self.simple_roots())
+    }
+
+    /// Returns the representative of the orbit of a point given by
+    /// [`Self::fundamental_domain_representative`], given the simple roots.
+    pub(crate) fn representative_with_roots(&self, point: &Point<f64>, simple_roots: &[Point<f64>]) -> Point<f64> {
+        self.clone()
+            .map(|el| el * point)
+            .filter(|image| simple_roots.iter().all(|root| image.dot(root) > -f64::EPS))
+            .map(PointOrd::new)
+            .max()
+            .map_or_else(|| point.clone(), |image| image.0)
+    }
+}
+
+/// The elements and subgroups of a finite matrix group. Matrices whose
+/// entries differ by less than [`Float::EPS`] count as the same element, like
+/// they do for [`MatrixOrd`].
+impl<I: Iterator<Item = Matrix<f64>> + Clone> Group<I>
+where
+    I::Item: GroupItem,
+{
+    /// Returns the elements of the group in the order they're first found,
+    /// leaving out any repeats.
+    pub fn elements(&self) -> Vec<Matrix<f64>> {
+        let mut seen = BTreeSet::new();
+        self.clone().filter(|el| seen.insert(MatrixOrd::new(el.clone()))).collect()
+    }
+
+    /// Returns the order of the group, without consuming it.
+    pub fn order(&self) -> usize {
+        let mut seen = BTreeSet::new();
+        self.clone().filter(|el| seen.insert(MatrixOrd::new(el.clone()))).count()
+    }
+
+    /// Returns every subgroup of a given index, each with its elements in the
+    /// order of [`Self::elements`]. Nothing is returned if the index doesn't
+    /// divide the order.
+    ///
+    /// Every subgroup is generated by its cyclic subgroups, so the subgroups
+    /// are found by joining cyclic subgroups one at a time, starting from the
+    /// trivial group and only going through subgroups whose order divides that
+    /// of the subgroups asked for. This is only practical for groups with up
+    /// to a few thousand elements, and indices small enough that the
+    /// subgroups don't have too many subgroups themselves.
+    ///
+    /// # Panics
+    /// Panics if the elements of the group aren't closed under multiplication.
+    pub fn subgroups_of_index(&self, index: usize) -> Vec<Group<vec::IntoIter<Matrix<f64>>>> {
+        let elements = self.elements();
+        if index == 0 || elements.len() % index != 0 {
+            return Vec::new();
+        }
+        let order = elements.len() / index;
+        let table = ElementTable::new(&elements).with_products();
+        let identity = table
+            .position(Matrix::identity(self.dim, self.dim))
+            .expect("the group has no identity");
+
+        // The cyclic subgroups that can be part of a subgroup, by their generators.
+        let mut cyclic = BTreeMap::new();
+        for el in 0..elements.len() {
+            if let Some(members) = table.closure(identity, &[el], order) {
+                if order % members.len() == 0 {
+                    cyclic.entry(members).or_insert(el);
+                }
+            }
+        }
+
+        // Every subgroup found, with a set of generators for it.
+        let mut found: BTreeMap<Vec<usize>, Vec<usize>> =
+            cyclic.iter().map(|(members, &gen)| (members.clone(), vec![gen])).collect();
+        let mut queue: Vec<_> = found.keys().cloned().collect();
+
+        while let Some(members) = queue.pop() {
+            if members.len() == order {
+                continue;
+            }
+
+            let gens = found[&members].clone();
+            for &gen in cyclic.values() {
+                if members.binary_search(&gen).is_ok() {
+                    continue;
+                }
+
+                let mut new_gens = gens.clone();
+                new_gens.push(gen);
+                if let Some(joined) = table.closure(identity, &new_gens, order) {
+                    if order % joined.len() == 0 && !found.contains_key(&joined) {
+                        found.insert(joined.clone(), new_gens);
+                        queue.push(joined);
+                    }
+                }
+            }
+        }
+
+        found
+            .into_keys()
+            .filter(|members| members.len() == order)
+            .map(|members| {
+                let subgroup: Vec<_> = members.into_iter().map(|idx| elements[idx].clone()).collect();
+
+                // Safety: the elements are closed under multiplication.
+                unsafe { Group::new(self.dim, subgroup.into_iter()) }
+            })
+            .collect()
+    }
+
+    /// Returns every subgroup of index 2 along with its kind, sorted by the
+    /// kind so that the rotations come first if they're one of them. Each has
+    /// its elements in the order of [`Self::elements`].
+    ///
+    /// These are the kernels of the maps from the group onto the group of
+    /// order 2. The squares of the elements are in all of them, and the
+    /// cosets of the subgroup they generate form a product of groups of order
+    /// 2, whose subgroups of index 2 give those of the group. Unlike
+    /// [`Self::subgroups_of_index`], this doesn't go through the other
+    /// subgroups, so it's also practical for larger groups.
+    ///
+    /// # Panics
+    /// Panics if the elements of the group aren't closed under multiplication.
+    pub fn index_two_subgroups(&self) -> Vec<(Group<vec::IntoIter<Matrix<f64>>>, SubgroupKind)> {
+        let elements = self.elements();
+        let table = ElementTable::new(&elements);
+        let identity = table
+            .position(Matrix::identity(self.dim, self.dim))
+            .expect("the group has no identity");
+
+        // The subgroup generated by the squares, adding a square to the
+        // generators only when it's not in it yet.
+        let mut gens = Vec::new();
+        let mut squares = vec![identity];
+        for el in 0..elements.len() {
+            let square = table.mul(el, el);
+            if squares.binary_search(&square).is_err() {
+                gens.push(square);
+                squares = table
+                    .closure(identity, &gens, elements.len())
+                    .expect("the subgroup is larger than the group");
+            }
+        }
+
+        // Labels the coset of each element by a vector over the integers
+        // modulo 2, stored as bits.
+        let mut labels = vec![None; elements.len()];
+        for &el in &squares {
+            labels[el] = Some(0u64);
+        }
+        let mut bits = 0;
+        for el in 0..elements.len() {
+            if labels[el].is_some() {
+                continue;
+            }
+
+            let bit = 1 << bits;
+            bits += 1;
+            let labelled: Vec<_> = (0..elements.len())
+                .filter_map(|other| labels[other].map(|label| (other, label)))
+                .collect();
+            for (other, label) in labelled {
+                labels[table.mul(other, el)] = Some(label | bit);
+            }
+        }
+
+        let inversion = table.position(-Matrix::identity(self.dim, self.dim));
+        let mut subgroups: Vec<_> = (1..1u64 << bits)
+            .map(|functional| {
+                let members: Vec<_> = (0..elements.len())
+                    .filter(|&el| (labels[el].unwrap() & functional).count_ones() % 2 == 0)
+                    .collect();
+
+                let kind = if members.iter().all(|&el| elements[el].determinant() > 0.0) {
+                    SubgroupKind::Rotation
+                } else if inversion.map_or(false, |inv| members.binary_search(&inv).is_ok()) {
+                    SubgroupKind::Central
+                } else if inversion.is_some() {
+                    SubgroupKind::Hybrid
+                } else {
+                    SubgroupKind::Other
+                };
+
+                let subgroup: Vec<_> = members.into_iter().map(|idx| elements[idx].clone()).collect();
+
+                // Safety: the kernel of a homomorphism is a subgroup.
+                (unsafe { Group::new(self.dim, subgroup.into_iter()) }, kind)
+            })
+            .collect();
+
+        subgroups.sort_by_key(|(_, kind)| *kind);
+        subgroups
+    }
+
+    /// Returns the conjugacy classes of the group, each with its elements in
+    /// the order of [`Self::elements`]. The classes are sorted by the order of
+    /// their elements and then by their size, so that the identity comes
+    /// first.
+    ///
+    /// Conjugate elements have the same order, determinant and trace, so the
+    /// elements are first sorted into buckets by these, and each bucket is
+    /// then split by conjugating one of its elements by the whole group. When
+    /// the invariants already tell the classes apart, this stops as soon as
+    /// the whole bucket is found.
+    ///
+    /// # Panics
+    /// Panics if the elements of the group aren't closed under multiplication.
+    pub fn conjugacy_classes(&self) -> Vec<Vec<Matrix<f64>>> {
+        let elements = self.elements();
+        let table = ElementTable::new(&elements);
+        let identity = table
+            .position(Matrix::identity(self.dim, self.dim))
+            .expect("the group has no identity");
+        let inverses: Vec<_> = elements
+            .iter()
+            .map(|el| {
+                el.clone()
+                    .try_inverse()
+                    .and_then(|inv| table.position(inv))
+                    .expect("an element has no inverse in the group")
+            })
+            .collect();
+
+        let mut buckets: BTreeMap<_, Vec<usize>> = BTreeMap::new();
+        for (idx, el) in elements.iter().enumerate() {
+            let key = (table.element_order(identity, idx), el.determinant() > 0.0);
+            buckets.entry(key).or_default().push(idx);
+        }
+
+        let mut classes = Vec::new();
+        for ((order, _), mut bucket) in buckets {
+            // Splits the bucket wherever the trace jumps.
+            let trace = |idx: usize| elements[idx].trace();
+            bucket.sort_by(|&a, &b| trace(a).partial_cmp(&trace(b)).unwrap());
+            let mut start = 0;
+            for end in 1..=bucket.len() {
+                if end < bucket.len() && trace(bucket[end]) - trace(bucket[end - 1]) < f64::EPS {
+                    continue;
+                }
+
+                let mut remaining: BTreeSet<_> = bucket[start..end].iter().copied().collect();
+                while let Some(&el) = remaining.iter().next() {
+                    let mut class = BTreeSet::new();
+                    class.insert(el);
+                    for (g, &inverse) in inverses.iter().enumerate() {
+                        if class.len() == remaining.len() {
+                            break;
+                        }
+                        class.insert(table.mul(table.mul(g, el), inverse));
+                    }
+
+                    for member in &class {
+                        remaining.remove(member);
+                    }
+                    classes.push((order, class));
+                }
+                start = end;
+            }
+        }
+
+        classes.sort_by_key(|(order, class)| (*order, class.len()));
+        classes
+            .into_iter()
+            .map(|(_, class)| class.into_iter().map(|idx| elements[idx].clone()).collect())
+            .collect()
+    }
+
+    /// Recognizes the group as a direct product of finite Coxeter groups, or
+    /// as the rotations of one, from its order and the orders of its elements.
+    /// See the [`name`] module for how, and for the groups that can't be told
+    /// apart this way.
+    ///
+    /// # Panics
+    /// Panics if the elements of the group aren't closed under multiplication.
+    pub fn identify(&self) -> GroupName {
+        self.profile().identify()
+    }
+
+    /// Recognizes the group like [`Self::identify`], with the orders of its
+    /// elements read off from how they permute some points instead. The vertex
+    /// map has a row for each element, in the order the group returns them,
+    /// and the points must span the space so that no two elements permute them
+    /// the same way. This works for groups whose matrices are only close to
+    /// being closed under multiplication, like the symmetries of a polytope
+    /// found up to a tolerance.
+    pub fn identify_with_vertex_map(&self, vertex_map: &[Vec<usize>]) -> GroupName {
+        let mut seen = HashSet::new();
+        let mut elements = Vec::new();
+        let mut orders = Vec::new();
+        for (el, row) in self.clone().zip(vertex_map) {
+            if seen.insert(row) {
+                elements.push(el);
+                orders.push(permutation_order(row));
+            }
+        }
+
+        Profile::of(self.dim, &elements, &orders).identify()
+    }
+
+    /// Computes the invariants of the group that it's identified by.
+    fn profile(&self) -> Profile {
+        let elements = self.elements();
+        let table = ElementTable::new(&elements);
+        let identity = table
+            .position(Matrix::identity(self.dim, self.dim))
+            .expect("the group has no identity");
+        let orders: Vec<_> = (0..elements.len())
+            .map(|idx| table.element_order(identity, idx))
+            .collect();
+
+        Profile::of(self.dim, &elements, &orders)
+    }
+}
+
+/// How a subgroup of index 2 of a matrix group sits in it, as returned by
+/// [`Group::index_two_subgroups`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SubgroupKind {
+    /// The rotations of the group, like the chiral icosahedral group in the
+    /// full one.
+    Rotation,
+
+    /// A subgroup with the central inversion and other elements than
+    /// rotations, like the pyritohedral group in the full octahedral one.
+    Central,
+
+    /// A subgroup made of half of the rotations, and of the central inversion
+    /// composed with the other half, when the group has the central inversion
+    /// but the subgroup doesn't. This is the ± group of the half of the
+    /// rotations, like the full tetrahedral group in the full octahedral one.
+    Hybrid,
+
+    /// Any other subgroup, when the group doesn't have the central inversion.
+    Other,
+}
+
+/// Returns the order of a permutation, the least common multiple of the
+/// lengths of its cycles.
+fn permutation_order(perm: &[usize]) -> usize {
+    let mut seen = vec![false; perm.len()];
+    let mut order = 1;
+    for start in 0..perm.len() {
+        let mut len = 0;
+        let mut idx = start;
+        while !seen[idx] {
+            seen[idx] = true;
+            idx = perm[idx];
+            len += 1;
+        }
+        if len != 0 {
+            order = order / order.gcd(len) * len;
+        }
+    }
+    order
+}
+
+/// The elements of a finite matrix group, referred to by their indices, along
+/// with their multiplication table if it was computed.
+struct ElementTable<'a> {
+    /// The elements of the group.
+    elements: &'a [Matrix<f64>],
+
+    /// The index of each element.
+    indices: BTreeMap<MatrixOrd<f64>, usize>,
+
+    /// The index of the product of every two elements, row by row, or nothing
+    /// if the products are computed as they're needed.
+    products: Vec<usize>,
+}
+
+impl<'a> ElementTable<'a> {
+    /// Indexes the elements of a group, without multiplying them.
+    fn new(elements: &'a [Matrix<f64>]) -> Self {
+        let indices = elements
+            .iter()
+            .enumerate()
+            .map(|(idx, el)| (MatrixOrd::new(el.clone()), idx))
+            .collect();
+
+        Self {
+            elements,
+            indices,
+            products: Vec::new(),
+        }
+    }
+
+    /// Computes the product of every two elements beforehand, which is worth
+    /// it when most of them are needed.
+    ///
+    /// # Panics
+    /// Panics if the elements aren't closed under multiplication.
+    fn with_products(mut self) -> Self {
+        let len = self.elements.len();
+        let mut products = Vec::with_capacity(len * len);
+        for a in 0..len {
+            for b in 0..len {
+                products.push(self.mul(a, b));
+            }
+        }
+
+        self.products = products;
+        self
+    }
+
+    /// Returns the index of an element, if it's in the group.
+    fn position(&self, el: Matrix<f64>) -> Option<usize> {
+        self.indices.get(&MatrixOrd::new(el)).copied()
+    }
+
+    /// Returns the index of the product of two elements.
+    ///
+    /// # Panics
+    /// Panics if the product isn't in the group.
+    fn mul(&self, a: usize, b: usize) -> usize {
+        if self.products.is_empty() {
+            self.position(&self.elements[a] * &self.elements[b])
+                .expect("the elements aren't closed under multiplication")
+        } else {
+            self.products[a * self.elements.len() + b]
+        }
+    }
+
+    /// Returns the order of an element, given the index of the identity.
+    fn element_order(&self, identity: usize, el: usize) -> usize {
+        let mut power = el;
+        let mut order = 1;
+        while power != identity {
+            power = self.mul(power, el);
+            order += 1;
+        }
+        order
+    }
+
+    /// Returns the sorted indices of the elements of the subgroup generated by
+    /// some elements, or `None` if it has more than a given number of them.
+    fn closure(&self, identity: usize, gens: &[usize], max: usize) -> Option<Vec<usize>> {
+        let mut members = vec![identity];
+        let mut seen = BTreeSet::new();
+        seen.insert(identity);
+
+        let mut idx = 0;
+        while let Some(&el) = members.get(idx) {
+            for &gen in gens {
+                let product = self.mul(el, gen);
+                if seen.insert(product) {
+                    if members.len() == max {
+                        return None;
+                    }
+                    members.push(product);
+                }
+            }
+            idx += 1;
+        }
+
+        members.sort_unstable();
+        Some(members)
+    }
+}
+
+/// Returns whether the first nonzero coordinate of a vector is positive.
+fn is_positive(vector: &Point<f64>) -> bool {
+    PointOrd::new(vector.clone()) > PointOrd::new(Point::zeros(vector.len()))
+}
+
 /// Converts a matrix into a unit quaternion.
 fn mat_to_quat<T: Float>(mat: &Matrix<T>) -> UnitQuaternion<T> {
     UnitQuaternion::from_rotation_matrix(&Rotation::from_matrix_unchecked(
@@ -681,6 +1216,185 @@ mod tests {
         test(parse_unwrap("o3o3o3o3o *c3o"), 51840, 25920, "E6");
     }
 
+    /// Tests the roots of the BC3 group and the representatives of its orbits.
+    #[test]
+    fn roots() {
+        let group = Group::hypercube(3).cache();
+        let simple_roots = group.simple_roots();
+        assert_eq!(group.positive_roots().len(), 9);
+        assert_eq!(simple_roots.len(), 3);
+
+        let point = Point::from_vec(vec![0.3, -1.2, 0.7]);
+        let rep = group.fundamental_domain_representative(&point);
+        assert!((rep.norm() - point.norm()).abs() < f64::EPS);
+        for root in &simple_roots {
+            assert!(rep.dot(root) > -f64::EPS);
+        }
+
+        // Without reflections, we just take the largest image.
+        let rotations = group.rotations().cache();
+        assert!(rotations.simple_roots().is_empty());
+        let largest = rotations
+            .clone()
+            .map(|el| PointOrd::new(el * &point))
+            .max()
+            .unwrap();
+        assert_eq!(PointOrd::new(rotations.fundamental_domain_representative(&point)), largest);
+    }
+
+    /// Tests listing the elements of a group without repeats.
+    #[test]
+    fn elements() {
+        let group = Group::hypercube(3).cache();
+        assert_eq!(group.order(), 48);
+        assert_eq!(group.clone().count(), 48);
+
+        let twice: Vec<_> = group.clone().chain(group.clone()).collect();
+        let twice = unsafe { Group::new(3, twice.into_iter()) };
+        assert_eq!(twice.clone().count(), 96);
+        assert_eq!(twice.elements(), group.elements());
+        assert_eq!(twice.order(), 48);
+    }
+
+    /// Tests the subgroups of a few indices of the H3 and BC3 groups.
+    #[test]
+    fn subgroups_of_index() {
+        let h3 = parse_unwrap("o5o3o").cache();
+        assert_eq!(h3.order(), 120);
+
+        // The only subgroup of index 2 is the rotation group.
+        let halves = h3.subgroups_of_index(2);
+        assert_eq!(halves.len(), 1);
+        assert!(halves[0].clone().all(|el| el.determinant() > 0.0));
+
+        // The pyritohedral groups, which all contain the central inversion.
+        let pyritohedral = h3.subgroups_of_index(5);
+        assert_eq!(pyritohedral.len(), 5);
+        for subgroup in &pyritohedral {
+            assert_eq!(subgroup.order(), 24);
+            assert!(subgroup.clone().any(|el| (el + Matrix::identity(3, 3)).norm() < f64::EPS));
+        }
+
+        // The rotations, the pyritohedral group, and the full tetrahedral group.
+        assert_eq!(Group::hypercube(3).subgroups_of_index(2).len(), 3);
+
+        assert!(h3.subgroups_of_index(7).is_empty());
+        assert_eq!(h3.subgroups_of_index(120).len(), 1);
+        assert_eq!(h3.subgroups_of_index(1)[0].order(), 120);
+    }
+
+    /// Tests the subgroups of index 2 of the H3 and BC3 groups, and of the
+    /// pyritohedral group.
+    #[test]
+    fn index_two_subgroups() {
+        let h3 = parse_unwrap("o5o3o").cache();
+        let halves = h3.index_two_subgroups();
+        assert_eq!(halves.len(), 1);
+        assert_eq!(halves[0].1, SubgroupKind::Rotation);
+        assert_eq!(halves[0].0.order(), 60);
+        assert_eq!(halves[0].0.elements(), h3.clone().rotations().cache().elements());
+
+        // The pyritohedral group only has the chiral tetrahedral group.
+        let pyritohedral = h3.subgroups_of_index(5).swap_remove(0);
+        let halves = pyritohedral.index_two_subgroups();
+        assert_eq!(halves.len(), 1);
+        assert_eq!(halves[0].1, SubgroupKind::Rotation);
+        assert_eq!(halves[0].0.order(), 12);
+
+        // The chiral octahedral, pyritohedral and full tetrahedral groups.
+        let bc3 = Group::hypercube(3).cache();
+        let halves = bc3.index_two_subgroups();
+        let kinds: Vec<_> = halves.iter().map(|(_, kind)| *kind).collect();
+        assert_eq!(kinds, vec![SubgroupKind::Rotation, SubgroupKind::Central, SubgroupKind::Hybrid]);
+        for (subgroup, _) in &halves {
+            assert_eq!(subgroup.order(), 24);
+        }
+        let mut expected: Vec<_> = bc3.subgroups_of_index(2).iter().map(Group::elements).collect();
+        let mut found: Vec<_> = halves.iter().map(|(subgroup, _)| subgroup.elements()).collect();
+        let key = |elements: &Vec<Matrix<f64>>| elements.iter().map(|el| MatrixOrd::new(el.clone())).collect::<Vec<_>>();
+        expected.sort_by_key(key);
+        found.sort_by_key(key);
+        assert_eq!(found, expected);
+
+        // The symmetries of a rectangle in 3D space, without the central inversion.
+        let rectangle = Group::direct_product(Group::reflection_at(1, 0), Group::reflection_at(2, 0)).cache();
+        let kinds: Vec<_> = rectangle.index_two_subgroups().into_iter().map(|(_, kind)| kind).collect();
+        assert_eq!(kinds, vec![SubgroupKind::Rotation, SubgroupKind::Other, SubgroupKind::Other]);
+    }
+
+    /// Tests the conjugacy classes of a few groups against their known sizes.
+    #[test]
+    fn conjugacy_classes() {
+        let sizes = |group: Group<GenIter<Matrix<f64>>>| {
+            let mut sizes: Vec<_> = group.conjugacy_classes().iter().map(Vec::len).collect();
+            sizes.sort_unstable();
+            sizes
+        };
+
+        // The symmetric group on 4 elements.
+        assert_eq!(sizes(Group::simplex(3)), vec![1, 3, 6, 6, 8]);
+        assert_eq!(sizes(Group::hypercube(3)), vec![1, 1, 3, 3, 6, 6, 6, 6, 8, 8]);
+        assert_eq!(sizes(parse_unwrap("o5o3o")), vec![1, 1, 12, 12, 12, 12, 15, 15, 20, 20]);
+        assert_eq!(sizes(parse_unwrap("o3o4o3o")).len(), 25);
+
+        let classes = Group::hypercube(3).conjugacy_classes();
+        assert_eq!(classes[0], vec![Matrix::identity(3, 3)]);
+        assert_eq!(classes.iter().map(Vec::len).sum::<usize>(), 48);
+    }
+
+    /// Tests recognizing Coxeter groups, their products and their rotations.
+    #[test]
+    fn identify() {
+        let name = |group: Group<GenIter<Matrix<f64>>>| group.identify().to_string();
+        assert_eq!(name(Group::simplex(2)), "A2");
+        assert_eq!(name(Group::simplex(4)), "A4");
+        assert_eq!(name(Group::hypercube(3)), "B3");
+        assert_eq!(name(Group::hypercube(4)), "B4");
+        assert_eq!(name(Group::demihypercube(4)), "D4");
+        assert_eq!(name(parse_unwrap("o3o4o3o")), "F4");
+        assert_eq!(name(parse_unwrap("o5o3o")), "H3");
+        assert_eq!(name(parse_unwrap("o5o")), "I2(5)");
+        assert_eq!(name(parse_unwrap("o7o")), "I2(7)");
+        assert_eq!(name(parse_unwrap("o o3o")), "A1×A2");
+        assert_eq!(name(parse_unwrap("o5o o")), "A1×I2(5)");
+
+        // Not every group is a Coxeter group or its rotations.
+        assert_eq!(Group::trivial(3).identify(), GroupName::Coxeter(Vec::new()));
+        assert_eq!(Group::central_inv(3).identify(), GroupName::Unknown);
+        assert_eq!(Group::central_inv(2).identify().to_string(), "(A1×A1)⁺");
+
+        // The groups are told apart by how many dimensions they move.
+        assert_eq!(Group::simplex(2).pad(1).cache().identify().to_string(), "A2");
+        assert_eq!(Group::hypercube(3).rotations().cache().identify().to_string(), "B3⁺");
+        assert_eq!(parse_unwrap("o5o3o").rotations().cache().identify().to_string(), "H3⁺");
+        let a3 = parse_unwrap("o3o3o");
+        assert_eq!(Group::direct_product(a3.clone(), a3).cache().identify().to_string(), "A3×A3");
+    }
+
+    /// Tests recognizing a group whose matrices are slightly off, from how it
+    /// permutes the vertices of a cube.
+    #[test]
+    fn identify_with_vertex_map() {
+        let elements = Group::hypercube(3).elements();
+        let vertices: Vec<Point<f64>> = (0..8)
+            .map(|idx| Point::from_fn(3, |i, _| if idx >> i & 1 == 1 { 1.0 } else { -1.0 }))
+            .collect();
+        let vertex_map: Vec<Vec<usize>> = elements
+            .iter()
+            .map(|el| {
+                vertices
+                    .iter()
+                    .map(|v| vertices.iter().position(|w| (el * v - w).norm() < 1e-9).unwrap())
+                    .collect()
+            })
+            .collect();
+
+        let perturbed: Vec<_> = elements.iter().map(|el| el.add_scalar(1e-7)).collect();
+        // Safety: identifying the group this way never multiplies its elements.
+        let group = unsafe { Group::new(3, perturbed.into_iter()) };
+        assert_eq!(group.identify_with_vertex_map(&vertex_map).to_string(), "B3");
+    }
+
     #[test]
     fn pairs() {
         assert_eq!(
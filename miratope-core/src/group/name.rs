@@ -0,0 +1,511 @@
+//! Recognizes the finite Coxeter groups, their direct products and their
+//! rotation subgroups from a few invariants of their elements, so that the
+//! symmetry groups of polytopes can be told by name.
+//!
+//! A group is compared to a product of irreducible Coxeter groups through its
+//! order, the rank of the space it moves, its number of reflections, and how
+//! many of its elements have each order and sign of the determinant. These
+//! can be worked out for a product from those of its factors, so only the
+//! irreducible groups are ever looked at. Those of the A, B, D and I2 families
+//! are worked out from the cycles of their elements as (signed) permutations,
+//! and only the exceptional groups are built. No characters are computed, so
+//! some groups that aren't the same have the same invariants. The first match
+//! is returned, with the factors in the order of [`CoxeterFactor`].
+
+use std::{collections::BTreeMap, fmt::Display};
+
+use super::{GenIter, Group};
+use crate::{cox::Cox, geometry::Matrix};
+
+use gcd::Gcd;
+
+/// An irreducible finite Coxeter group. The factors of a product are sorted
+/// by the order of the variants, and then by their rank.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CoxeterFactor {
+    /// The symmetries of a simplex with a given rank, for ranks from 1.
+    A(usize),
+
+    /// The symmetries of a hypercube with a given rank, for ranks from 2.
+    B(usize),
+
+    /// The symmetries of a demihypercube with a given rank, for ranks from 4.
+    D(usize),
+
+    /// The E6, E7 and E8 groups.
+    E(usize),
+
+    /// The symmetries of the 24-cell.
+    F4,
+
+    /// The symmetries of the dodecahedron and the 120-cell, for ranks 3 and
+    /// 4.
+    H(usize),
+
+    /// The symmetries of a polygon with a given number of sides, from 5 up.
+    /// The triangle and the square have A2 and B2 instead.
+    I2(usize),
+}
+
+impl CoxeterFactor {
+    /// Returns the number of dimensions the group moves.
+    pub fn rank(self) -> usize {
+        match self {
+            Self::A(n) | Self::B(n) | Self::D(n) | Self::E(n) | Self::H(n) => n,
+            Self::F4 => 4,
+            Self::I2(_) => 2,
+        }
+    }
+
+    /// Returns the order of the group.
+    pub fn order(self) -> usize {
+        let factorial = |n: usize| (1..=n).product::<usize>();
+        match self {
+            Self::A(n) => factorial(n + 1),
+            Self::B(n) => (1 << n) * factorial(n),
+            Self::D(n) => (1 << (n - 1)) * factorial(n),
+            Self::E(6) => 51840,
+            Self::E(7) => 2903040,
+            Self::E(_) => 696729600,
+            Self::F4 => 1152,
+            Self::H(3) => 120,
+            Self::H(_) => 14400,
+            Self::I2(p) => 2 * p,
+        }
+    }
+
+    /// Builds the group in as many dimensions as its rank.
+    fn group(self) -> Option<Group<GenIter<Matrix<f64>>>> {
+        let cox = match self {
+            Self::A(n) => Cox::a(n),
+            Self::B(n) => Cox::b(n),
+            Self::D(n) => Cox::d(n),
+            Self::E(n) => Cox::e(n),
+            Self::F4 => Cox::from_lin_diagram(&[3.0, 4.0, 3.0]),
+            Self::H(n) => Cox::h(n),
+            Self::I2(p) => Cox::i2(p as f64),
+        };
+        cox.group()
+    }
+
+    /// Returns the invariants of the group. Those of the simplex, hypercube,
+    /// demihypercube and polygonal groups are worked out directly, and the
+    /// exceptional groups are built.
+    fn profile(self) -> Option<Profile> {
+        match self {
+            Self::A(n) => Some(Profile::permutations(n)),
+            Self::B(n) => Some(Profile::signed_permutations(n, false)),
+            Self::D(n) => Some(Profile::signed_permutations(n, true)),
+            Self::I2(p) => {
+                let mut histogram = BTreeMap::new();
+                for k in 0..p {
+                    *histogram.entry((p / k.gcd(p), true)).or_insert(0) += 1;
+                }
+                histogram.insert((2, false), p);
+                Some(Profile {
+                    order: 2 * p,
+                    rank: 2,
+                    reflections: p,
+                    histogram,
+                })
+            }
+            _ => Some(self.group()?.profile()),
+        }
+    }
+
+    /// Returns every irreducible Coxeter group that moves at most some number
+    /// of dimensions, and whose order divides some number.
+    fn candidates(max_rank: usize, order: usize) -> Vec<Self> {
+        let mut candidates = Vec::new();
+        candidates.extend((1..=max_rank).map(Self::A));
+        candidates.extend((2..=max_rank).map(Self::B));
+        candidates.extend((4..=max_rank).map(Self::D));
+        candidates.extend((6..=max_rank.min(8)).map(Self::E));
+        if max_rank >= 4 {
+            candidates.push(Self::F4);
+        }
+        candidates.extend((3..=max_rank.min(4)).map(Self::H));
+        if max_rank >= 2 {
+            candidates.extend((5..=order / 2).map(Self::I2));
+        }
+
+        candidates.retain(|factor| order % factor.order() == 0);
+        candidates
+    }
+}
+
+impl Display for CoxeterFactor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::A(n) => write!(f, "A{}", n),
+            Self::B(n) => write!(f, "B{}", n),
+            Self::D(n) => write!(f, "D{}", n),
+            Self::E(n) => write!(f, "E{}", n),
+            Self::F4 => write!(f, "F4"),
+            Self::H(n) => write!(f, "H{}", n),
+            Self::I2(p) => write!(f, "I2({})", p),
+        }
+    }
+}
+
+/// The name of a finite matrix group, as given by [`Group::identify`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GroupName {
+    /// A direct product of irreducible Coxeter groups acting on orthogonal
+    /// subspaces, with its factors sorted. The trivial group has no factors.
+    Coxeter(Vec<CoxeterFactor>),
+
+    /// The rotations of a direct product of irreducible Coxeter groups, with
+    /// its factors sorted.
+    Rotation(Vec<CoxeterFactor>),
+
+    /// A group that isn't any of the others.
+    Unknown,
+}
+
+impl GroupName {
+    /// Returns whether the group was recognized.
+    pub fn is_known(&self) -> bool {
+        !matches!(self, Self::Unknown)
+    }
+}
+
+/// Writes the factors of a product, separated by `×`.
+fn write_factors(f: &mut std::fmt::Formatter<'_>, factors: &[CoxeterFactor]) -> std::fmt::Result {
+    for (idx, factor) in factors.iter().enumerate() {
+        if idx != 0 {
+            write!(f, "×")?;
+        }
+        write!(f, "{}", factor)?;
+    }
+    Ok(())
+}
+
+impl Display for GroupName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Coxeter(factors) if factors.is_empty() => write!(f, "trivial"),
+            Self::Coxeter(factors) => write_factors(f, factors),
+            Self::Rotation(factors) if factors.len() == 1 => write!(f, "{}⁺", factors[0]),
+            Self::Rotation(factors) => {
+                write!(f, "(")?;
+                write_factors(f, factors)?;
+                write!(f, ")⁺")
+            }
+            Self::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// Returns the factorial of a number.
+fn factorial(n: usize) -> usize {
+    (1..=n).product()
+}
+
+/// Returns the number of ways to choose `k` things out of `n`.
+fn binomial(n: usize, k: usize) -> usize {
+    (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1))
+}
+
+/// Calls a function on every way to split `n` points into cycles, given as
+/// the number of cycles of each length, from the longest. The cycles found so
+/// far are kept in `cycles`.
+fn for_each_cycle_type<F: FnMut(&[(usize, usize)])>(n: usize, cycles: &mut Vec<(usize, usize)>, f: &mut F) {
+    let max = cycles.last().map_or(n, |&(len, _)| len - 1).min(n);
+    if n == 0 {
+        f(cycles);
+        return;
+    }
+
+    for len in (1..=max).rev() {
+        for count in 1..=n / len {
+            cycles.push((len, count));
+            for_each_cycle_type(n - len * count, cycles, f);
+            cycles.pop();
+        }
+    }
+}
+
+/// Returns the number of permutations of `n` points with some numbers of
+/// cycles of each length. That's `n!` divided by `k^m m!` for each length `k`
+/// with `m` cycles.
+fn class_size(n: usize, cycles: &[(usize, usize)]) -> usize {
+    let mut size = factorial(n);
+    for &(len, count) in cycles {
+        size /= len.pow(count as u32) * factorial(count);
+    }
+    size
+}
+
+/// The invariants of a finite matrix group that it's identified by.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct Profile {
+    /// The order of the group.
+    order: usize,
+
+    /// The number of dimensions moved by the group, those orthogonal to the
+    /// points every element fixes.
+    rank: usize,
+
+    /// The number of reflections in the group.
+    reflections: usize,
+
+    /// The number of elements with each order, and with a positive or
+    /// negative determinant.
+    histogram: BTreeMap<(usize, bool), usize>,
+}
+
+impl Profile {
+    /// Computes the invariants of a group of matrices in some dimensions from
+    /// its elements and their orders.
+    ///
+    /// Only the determinants and traces of the elements are used, so they may
+    /// be off by a small error. The number of dimensions fixed by the group is
+    /// the average of the traces.
+    pub(super) fn of(dim: usize, elements: &[Matrix<f64>], orders: &[usize]) -> Self {
+        let mut histogram = BTreeMap::new();
+        let mut reflections = 0;
+        let mut trace_sum = 0.0;
+        for (el, &order) in elements.iter().zip(orders) {
+            let positive = el.determinant() > 0.0;
+            *histogram.entry((order, positive)).or_insert(0) += 1;
+
+            // The trace of an involution is an integer.
+            let trace = el.trace();
+            if !positive && order == 2 && (trace - (dim as f64 - 2.0)).abs() < 0.5 {
+                reflections += 1;
+            }
+            trace_sum += trace;
+        }
+
+        let fixed = (trace_sum / elements.len() as f64).round() as usize;
+        Self {
+            order: elements.len(),
+            rank: dim - fixed,
+            reflections,
+            histogram,
+        }
+    }
+
+    /// Returns the invariants of the symmetric group on `n + 1` points, acting
+    /// on the `n` dimensions of the simplex, that is, of the A<sub>n</sub>
+    /// group.
+    ///
+    /// An element is a permutation, whose order is the least common multiple
+    /// of the lengths of its cycles, and whose determinant is its sign.
+    fn permutations(n: usize) -> Self {
+        let mut histogram = BTreeMap::new();
+        for_each_cycle_type(n + 1, &mut Vec::new(), &mut |cycles| {
+            let mut order = 1;
+            let mut positive = true;
+            for &(len, count) in cycles {
+                order = order / order.gcd(len) * len;
+                positive ^= len % 2 == 0 && count % 2 == 1;
+            }
+            *histogram.entry((order, positive)).or_insert(0) += class_size(n + 1, cycles);
+        });
+
+        Self {
+            order: histogram.values().sum(),
+            rank: n,
+            reflections: n * (n + 1) / 2,
+            histogram,
+        }
+    }
+
+    /// Returns the invariants of the signed permutations of `n` coordinates,
+    /// that is, of the B<sub>n</sub> group, or of those with an even number of
+    /// sign changes, the D<sub>n</sub> group.
+    ///
+    /// Each cycle of a signed permutation is positive or negative according to
+    /// the product of its signs. A cycle of length k has order k if it's
+    /// positive and 2k if it's negative, and its determinant is the product of
+    /// its signs and of the sign of the cycle. There are 2<sup>k - 1</sup>
+    /// ways to choose the signs of each, and the element is in D<sub>n</sub>
+    /// when it has an even number of negative cycles.
+    fn signed_permutations(n: usize, even: bool) -> Self {
+        let mut histogram = BTreeMap::new();
+        for_each_cycle_type(n, &mut Vec::new(), &mut |cycles| {
+            // Splits the cycles of each length into positive and negative ones.
+            let mut splits = vec![Vec::new()];
+            for &(len, count) in cycles {
+                splits = splits
+                    .into_iter()
+                    .flat_map(|split: Vec<(usize, usize, usize)>| {
+                        (0..=count).map(move |negative| {
+                            let mut split = split.clone();
+                            split.push((len, count - negative, negative));
+                            split
+                        })
+                    })
+                    .collect();
+            }
+
+            for split in splits {
+                let mut order = 1;
+                let mut positive = true;
+                let mut negative_cycles = 0;
+                let mut size = class_size(n, cycles);
+                for &(len, pos, neg) in &split {
+                    if pos > 0 {
+                        order = order / order.gcd(len) * len;
+                    }
+                    if neg > 0 {
+                        order = order / order.gcd(2 * len) * (2 * len);
+                    }
+                    positive ^= (len % 2 == 0) && (pos + neg) % 2 == 1;
+                    positive ^= neg % 2 == 1;
+                    negative_cycles += neg;
+
+                    // Which of the cycles of this length are negative, and
+                    // the signs within each cycle.
+                    size *= binomial(pos + neg, neg) << ((len - 1) * (pos + neg));
+                }
+                if even && negative_cycles % 2 == 1 {
+                    continue;
+                }
+                *histogram.entry((order, positive)).or_insert(0) += size;
+            }
+        });
+
+        Self {
+            order: histogram.values().sum(),
+            rank: n,
+            reflections: if even { n * (n - 1) } else { n * n },
+            histogram,
+        }
+    }
+
+    /// Returns the invariants of the direct product of two groups acting on
+    /// orthogonal subspaces.
+    fn product(&self, other: &Self) -> Self {
+        let mut histogram = BTreeMap::new();
+        for (&(order1, positive1), &count1) in &self.histogram {
+            for (&(order2, positive2), &count2) in &other.histogram {
+                let order = order1 / order1.gcd(order2) * order2;
+                *histogram.entry((order, positive1 == positive2)).or_insert(0) += count1 * count2;
+            }
+        }
+
+        Self {
+            order: self.order * other.order,
+            rank: self.rank + other.rank,
+            reflections: self.reflections + other.reflections,
+            histogram,
+        }
+    }
+
+    /// Returns the invariants of the rotations of a group, except for the rank,
+    /// which is left as is.
+    fn rotations(&self) -> Self {
+        let histogram: BTreeMap<_, _> = self
+            .histogram
+            .iter()
+            .filter(|((_, positive), _)| *positive)
+            .map(|(&key, &count)| (key, count))
+            .collect();
+
+        Self {
+            order: histogram.values().sum(),
+            rank: self.rank,
+            reflections: 0,
+            histogram,
+        }
+    }
+
+    /// Returns the name of the group with these invariants.
+    pub(super) fn identify(&self) -> GroupName {
+        if self.order == 1 {
+            return GroupName::Coxeter(Vec::new());
+        }
+
+        // A group without reflections can only be the rotations of a
+        // Coxeter group twice as large.
+        let chiral = self.histogram.keys().all(|(_, positive)| *positive);
+        let target = if chiral { 2 * self.order } else { self.order };
+        let profiles: Vec<_> = CoxeterFactor::candidates(self.rank, target)
+            .into_iter()
+            .filter_map(|factor| Some((factor, factor.profile()?)))
+            .collect();
+
+        let mut factors = Vec::new();
+        let found = self.search(&profiles, 0, &mut factors, None, target, chiral);
+        match (found, chiral) {
+            (true, false) => GroupName::Coxeter(factors),
+            (true, true) => GroupName::Rotation(factors),
+            (false, _) => GroupName::Unknown,
+        }
+    }
+
+    /// Looks for a product of the irreducible groups given, from some index
+    /// on, that completes the factors found so far into a group matching these
+    /// invariants, or whose rotations do. Leaves the factors of the first one
+    /// found in the list, and returns whether there was any.
+    fn search(
+        &self,
+        profiles: &[(CoxeterFactor, Profile)],
+        start: usize,
+        factors: &mut Vec<CoxeterFactor>,
+        product: Option<&Profile>,
+        remaining: usize,
+        chiral: bool,
+    ) -> bool {
+        if remaining == 1 {
+            let product = match product {
+                Some(product) => product,
+                None => return false,
+            };
+            return if chiral {
+                product.rank == self.rank && &product.rotations() == self
+            } else {
+                product == self
+            };
+        }
+
+        let rank = product.map_or(0, |product| product.rank);
+        for (idx, (factor, profile)) in profiles.iter().enumerate().skip(start) {
+            if remaining % profile.order != 0 || rank + profile.rank > self.rank {
+                continue;
+            }
+
+            let joined = match product {
+                Some(product) => product.product(profile),
+                None => profile.clone(),
+            };
+            factors.push(*factor);
+            if self.search(profiles, idx, factors, Some(&joined), remaining / profile.order, chiral) {
+                return true;
+            }
+            factors.pop();
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The invariants worked out for the simplex, hypercube and
+    /// demihypercube groups are those of the groups built.
+    #[test]
+    fn closed_forms() {
+        let factors = [
+            CoxeterFactor::A(1),
+            CoxeterFactor::A(2),
+            CoxeterFactor::A(3),
+            CoxeterFactor::A(4),
+            CoxeterFactor::B(2),
+            CoxeterFactor::B(3),
+            CoxeterFactor::B(4),
+            CoxeterFactor::D(4),
+            CoxeterFactor::D(5),
+        ];
+
+        for &factor in &factors {
+            let profile = factor.profile().unwrap();
+            assert_eq!(profile, factor.group().unwrap().profile(), "{}", factor);
+            assert_eq!(profile.order, factor.order(), "{}", factor);
+        }
+    }
+}
@@ -24,7 +24,10 @@
 //! a look at the [`miratope`](https://crates.io/crates/miratope) crate instead.
 
 pub mod abs;
+#[cfg(feature = "cli")]
+pub mod cli;
 pub mod conc;
+pub mod cover;
 pub mod cox;
 pub mod file;
 pub mod float;
@@ -36,7 +39,7 @@ use std::{collections::HashSet, error::Error, iter, ops::IndexMut};
 use abs::{
     flag::{Flag, FlagIter, OrientedFlag, OrientedFlagIter},
     ranked::Ranks,
-    Abstract, Element, ElementList, ElementMap, Ranked,
+    Abstract, Element, ElementList, ElementMap, Rank, Ranked,
 };
 
 use vec_like::VecLike;
@@ -210,8 +213,8 @@ pub trait Polytope:
 
     /// Builds a [simplex](https://polytope.miraheze.org/wiki/Simplex) with a
     /// given rank.
-    fn simplex(rank: usize) -> Self {
-        Self::multipyramid(iter::repeat(&Self::point()).take(rank))
+    fn simplex(rank: impl Into<Rank>) -> Self {
+        Self::multipyramid(iter::repeat(&Self::point()).take(rank.into().get()))
     }
 
     /// Builds a regular [tetrahedron](https://polytope.miraheze.org/wiki/Tetrahedron).
@@ -221,11 +224,10 @@ pub trait Polytope:
 
     /// Builds a [hypercube](https://polytope.miraheze.org/wiki/Hypercube) with
     /// a given rank.
-    fn hypercube(rank: usize) -> Self {
-        if rank == 0 {
-            Self::nullitope()
-        } else {
-            Self::multiprism(iter::repeat(&Self::dyad()).take(rank - 1))
+    fn hypercube(rank: impl Into<Rank>) -> Self {
+        match rank.into().facet() {
+            None => Self::nullitope(),
+            Some(facet) => Self::multiprism(iter::repeat(&Self::dyad()).take(facet.get())),
         }
     }
 
@@ -236,7 +238,7 @@ pub trait Polytope:
 
     /// Builds an [orthoplex](https://polytope.miraheze.org/wiki/Orthoplex) with
     /// a given rank.
-    fn orthoplex(rank: usize) -> Self;
+    fn orthoplex(rank: impl Into<Rank>) -> Self;
 
     /// Builds a regular [octahedron](https://polytope.miraheze.org/wiki/Octahedron).
     fn octahedron() -> Self {
@@ -264,10 +266,10 @@ pub trait Polytope:
     }
 
     /// Gets the element with a given rank and index as a polytope, if it exists.
-    fn element(&self, rank: usize, idx: usize) -> Option<Self>;
+    fn element(&self, rank: impl Into<Rank>, idx: usize) -> Option<Self>;
 
     /// Gets the element figure with a given rank and index as a polytope.
-    fn element_fig(&self, rank: usize, idx: usize) -> Result<Option<Self>, Self::DualError>;
+    fn element_fig(&self, rank: impl Into<Rank>, idx: usize) -> Result<Option<Self>, Self::DualError>;
 
     /// Gets the section defined by two elements with given ranks and indices as
     /// a polytope, or returns `None` in case no section is defined by these
@@ -440,6 +442,21 @@ pub trait Polytope:
     /// Splits compound faces into their components.
     fn untangle_faces(&mut self);
 
+    /// Splits the elements of a given rank into their components, the sets
+    /// of subelements connected through their ridges, and lists the
+    /// subelements of each in the order they're traversed. Does nothing for
+    /// ranks below 3 or above the rank of the facets.
+    fn untangle_elements(&mut self, rank: impl Into<Rank>);
+
+    /// Untangles the elements of every rank, from the faces up to the facets.
+    /// Going upwards means each element is split along subelements that were
+    /// already split themselves.
+    fn untangle_all(&mut self) {
+        for rank in 3..self.rank() {
+            self.untangle_elements(rank);
+        }
+    }
+
     /// Determines whether a given polytope is
     /// [orientable](https://polytope.miraheze.org/wiki/Orientability).
     ///
@@ -477,6 +494,24 @@ where
     }
 }
 
+/// Returns the multiproduct of an iterator over polytopes like
+/// [`multiproduct`], but stops at the first product that fails.
+pub(crate) fn try_multiproduct<'a, T, E, I, P, D>(factors: I, product: P, default: D) -> Result<T, E>
+where
+    T: Clone + 'a,
+    I: IntoIterator<Item = &'a T>,
+    P: Fn(&T, &T) -> Result<T, E>,
+    D: Fn() -> T,
+{
+    let mut factors = factors.into_iter();
+
+    match (factors.next(), factors.next()) {
+        (None, _) => Ok(default()),
+        (Some(factor), None) => Ok(factor.clone()),
+        (Some(first), Some(second)) => factors.try_fold(product(first, second)?, |p, q| product(&p, q)),
+    }
+}
+
 /// Tests whether a polytope's element counts match the expected element counts,
 /// and whether a polytope is valid.
 #[cfg(test)]
@@ -0,0 +1,239 @@
+//! Runs the faceting command line interface on some small polytopes.
+#![cfg(feature = "cli")]
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+use miratope_core::{abs::Ranked, conc::Concrete, file::FromFile};
+
+/// Returns the path to a test fixture, relative to the crate root. The CLI is
+/// run from there, so that the sources written in the outputs don't depend on
+/// where the crate is.
+fn fixture(name: &str) -> PathBuf {
+    Path::new("tests").join("fixtures").join(name)
+}
+
+/// Returns an empty output directory for a test.
+fn out_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("miratope-cli-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+/// Runs the CLI with some arguments from the crate root.
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_miratope-cli"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .arg("facet")
+        .args(args)
+        .output()
+        .expect("couldn't run the CLI")
+}
+
+/// Runs the CLI with some arguments, and checks that it succeeds.
+fn facet(args: &[&str]) {
+    let output = run(args);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
+
+/// Returns a file written by the CLI with the vertex cycles of its elements
+/// rotated to start at their least vertex, and oriented towards the least of
+/// its neighbors. The order the cycles are traced in isn't fixed, so this is
+/// what's compared between runs.
+fn normalize_cycles(contents: &str) -> String {
+    let mut in_elements = false;
+    let mut lines = Vec::new();
+
+    for line in contents.lines() {
+        if line.starts_with('#') {
+            in_elements = line != "# Vertices" && lines.iter().any(|l: &String| l == "# Vertices");
+        } else if in_elements && !line.is_empty() {
+            let mut indices: Vec<usize> = line.split_whitespace().map(|idx| idx.parse().unwrap()).collect();
+            let mut cycle = indices.split_off(1);
+            let min = cycle.iter().enumerate().min_by_key(|&(_, v)| v).unwrap().0;
+            cycle.rotate_left(min);
+            if cycle.len() > 2 && cycle[cycle.len() - 1] < cycle[1] {
+                cycle[1..].reverse();
+            }
+            indices.append(&mut cycle);
+            let indices: Vec<_> = indices.iter().map(ToString::to_string).collect();
+            lines.push(indices.join(" "));
+            continue;
+        }
+        lines.push(line.to_string());
+    }
+
+    lines.join("\n")
+}
+
+/// Checks that the files written by the CLI to a directory are those in a
+/// directory of golden fixtures, under `tests/fixtures/golden`.
+fn assert_golden(out: &Path, name: &str) {
+    let golden = Path::new(env!("CARGO_MANIFEST_DIR")).join(fixture("golden")).join(name);
+    let files = |dir: &Path| {
+        let mut files: Vec<_> = fs::read_dir(dir).unwrap().map(|entry| entry.unwrap().file_name()).collect();
+        files.sort();
+        files
+    };
+
+    let names = files(out);
+    assert_eq!(names, files(&golden));
+    for file in names {
+        let read = |dir: &Path| normalize_cycles(&fs::read_to_string(dir.join(&file)).unwrap());
+        assert_eq!(read(out), read(&golden), "{:?} differs from its golden fixture", file);
+    }
+}
+
+/// Reads the element counts of the OFF files in a directory, sorted, after
+/// checking that all of their facets are planar.
+fn element_counts(dir: &PathBuf) -> Vec<Vec<usize>> {
+    let mut counts: Vec<Vec<usize>> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| {
+            let poly = Concrete::from_path(&entry.unwrap().path()).unwrap();
+            poly.verify_facet_planarity(1e-5).unwrap();
+            (0..=poly.rank()).map(|r| poly.el_count(r)).collect()
+        })
+        .collect();
+    counts.sort();
+    counts
+}
+
+/// The only faceting of the octahedron is itself.
+#[test]
+fn octahedron() {
+    let out = out_dir("oct");
+    facet(&[fixture("oct.off").to_str().unwrap(), "--out", out.to_str().unwrap()]);
+    assert_eq!(element_counts(&out), vec![vec![1, 6, 12, 8, 1]]);
+    assert_golden(&out, "oct");
+    fs::remove_dir_all(out).unwrap();
+}
+
+/// The cube has itself and the stella octangula as facetings.
+#[test]
+fn cube() {
+    let out = out_dir("cube");
+    facet(&[fixture("cube.off").to_str().unwrap(), "--out", out.to_str().unwrap()]);
+    assert_eq!(
+        element_counts(&out),
+        vec![vec![1, 8, 12, 6, 1], vec![1, 8, 12, 8, 1]]
+    );
+
+    // The outputs remember where they came from.
+    for entry in fs::read_dir(&out).unwrap() {
+        let meta = Concrete::from_path(&entry.unwrap().path()).unwrap().meta;
+        assert!(meta.name.unwrap().contains(" of cube ("));
+        assert!(meta.source.unwrap().ends_with("cube.off"));
+    }
+    assert_golden(&out, "cube");
+    fs::remove_dir_all(out).unwrap();
+}
+
+/// Facetings can be written as JSON.
+#[test]
+fn json() {
+    let out = out_dir("json");
+    facet(&[
+        fixture("oct.off").to_str().unwrap(),
+        "--out",
+        out.to_str().unwrap(),
+        "--format",
+        "json",
+    ]);
+
+    let files: Vec<_> = fs::read_dir(&out).unwrap().map(|e| e.unwrap().path()).collect();
+    assert_eq!(files.len(), 1);
+    let json = fs::read_to_string(&files[0]).unwrap();
+    assert!(json.contains("\"rank\":4"));
+    assert_golden(&out, "json");
+    fs::remove_dir_all(out).unwrap();
+}
+
+/// The closed forms of the coordinates of the icosahedron are all found, and
+/// written alongside the rounded coordinates.
+#[test]
+fn exact() {
+    let out = out_dir("exact");
+    facet(&[
+        fixture("ike.off").to_str().unwrap(),
+        "--out",
+        out.to_str().unwrap(),
+        "--format",
+        "json",
+        "--precision",
+        "4",
+        "--exact",
+    ]);
+
+    for entry in fs::read_dir(&out).unwrap() {
+        let json = fs::read_to_string(entry.unwrap().path()).unwrap();
+        assert!(json.contains("0.809,"));
+        assert!(json.contains("\"exact\":[["));
+        assert!(json.contains("{\"coefficient\":1,\"denominator\":2,\"form\":\"golden\",\"rational\":0}"));
+        assert!(!json.contains("null"));
+    }
+    assert_golden(&out, "exact");
+    fs::remove_dir_all(out).unwrap();
+}
+
+/// Groups read from files that aren't closed under composition are refused
+/// before faceting, whether their matrices send the copies of the vertices to
+/// each other or not.
+#[test]
+fn open_group() {
+    let out = out_dir("group");
+    for group in &["quarter_turn.grp", "twelfth_turn.grp"] {
+        let output = run(&[
+            fixture("cube.off").to_str().unwrap(),
+            "--group",
+            fixture(group).to_str().unwrap(),
+            "--out",
+            out.to_str().unwrap(),
+        ]);
+        assert_eq!(output.status.code(), Some(1));
+        assert!(String::from_utf8_lossy(&output.stderr).contains("error: invalid group file: "));
+    }
+    assert!(!out.exists());
+}
+
+/// The facetings are also added to a survey database, and running the CLI on
+/// the same polytope again adds another run on the same arrangement.
+#[cfg(feature = "sqlite")]
+#[test]
+fn survey_db() {
+    use miratope_core::conc::faceting::survey::SurveyDb;
+
+    let out = out_dir("db");
+    let db_path = out.with_extension("db");
+    let _ = fs::remove_file(&db_path);
+    for _ in 0..2 {
+        facet(&[
+            fixture("cube.off").to_str().unwrap(),
+            "--out",
+            out.to_str().unwrap(),
+            "--db",
+            db_path.to_str().unwrap(),
+        ]);
+    }
+
+    let db = SurveyDb::open(&db_path).unwrap();
+    let count = |table: &str| -> i64 {
+        db.connection()
+            .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0))
+            .unwrap()
+    };
+    assert_eq!(count("arrangements"), 1);
+    assert_eq!(count("runs"), 2);
+    assert_eq!(count("results"), 4);
+    assert!(db.shared_fingerprints().unwrap().is_empty());
+
+    let stored = db.load_result(1).unwrap().result.polytope;
+    let stored_counts: Vec<_> = (0..=stored.rank()).map(|r| stored.el_count(r)).collect();
+    assert!(element_counts(&out).contains(&stored_counts));
+
+    fs::remove_dir_all(out).unwrap();
+    fs::remove_file(db_path).unwrap();
+}
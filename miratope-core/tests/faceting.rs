@@ -0,0 +1,2936 @@
+//! Runs the faceting algorithm on some small polytopes.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use miratope_core::{
+    abs::{
+        limits::{ElementLimits, TooLarge},
+        partial::{PartialAbstract, Violation},
+        Rank, Ranked,
+    },
+    conc::{
+        conditioning::Conditioning,
+        faceting::{
+            cache::{CacheError, MINOR_VERSION},
+            chain::{chain_prisms, chain_prisms_with_warnings, ChainWarning, HeightPolicy},
+            crosscheck::Crosscheck, CandidateCallback, CheckpointOptions, CandidateDecision, ChiralSelection, DegenerateFacets, DepthFilter, EdgeLengthFilter, FacetFilter, FacetOrbitCount, FacetingError,
+            FacetingObjective, HyperplaneOrbit, NobleCertificate, OrbitCountViolation,
+            FacetingOptions, FacetingResult, FacetingStats, FacetingTables, GroupEnum, OrbitOrder,
+            PartialFacetingOutput, ReplayError, Stage, StopPoint, VertexSubset,
+        },
+        meta::PolytopeMeta,
+        symmetry::{VertexMap, VertexMapError},
+        Concrete, ConcretePolytope,
+    },
+    file::FromFile,
+    float::Float,
+    geometry::{CenterPolicy, GeomContext, Matrix, Point, Subspace},
+    group::{
+        name::{CoxeterFactor, GroupName},
+        SubgroupKind,
+    },
+    Polytope,
+};
+
+/// Returns the element counts of some facetings, after checking that all of
+/// their facets are planar.
+fn element_counts(facetings: &[FacetingResult]) -> Vec<Vec<usize>> {
+    facetings
+        .iter()
+        .map(|FacetingResult { polytope: p, .. }| {
+            p.verify_facet_planarity(1e-5).unwrap();
+            (0..=p.rank()).map(|r| p.el_count(r)).collect()
+        })
+        .collect()
+}
+
+/// Facets a polytope under its full symmetry group, and returns the element
+/// counts of the facetings, sorted, along with the stats.
+fn facet(mut poly: Concrete) -> (Vec<Vec<usize>>, FacetingStats) {
+    let vertices = poly.vertices.to_vec();
+    let (facetings, stats) =
+        poly.faceting_with_stats(vertices, GroupEnum::Chiral(false), &Default::default());
+
+    let mut counts = element_counts(&facetings);
+    counts.sort();
+    (counts, stats)
+}
+
+#[test]
+fn tetrahedron() {
+    let (counts, stats) = facet(Concrete::simplex(4));
+    assert!(!stats.central_symmetry);
+    assert_eq!(counts, vec![vec![1, 4, 6, 4, 1]]);
+}
+
+#[test]
+fn cube() {
+    let (counts, stats) = facet(Concrete::hypercube(4));
+    assert!(stats.central_symmetry);
+    assert_eq!(counts, vec![vec![1, 8, 12, 6, 1], vec![1, 8, 12, 8, 1]]);
+}
+
+/// The symmetry group of the cube is only recognized when asked for.
+#[test]
+fn group_name() {
+    let mut cube = Concrete::hypercube(4);
+    let vertices = cube.vertices.to_vec();
+    let (_, stats) = cube.faceting_with_stats(vertices.clone(), GroupEnum::Chiral(false), &Default::default());
+    assert_eq!(stats.group_name, None);
+
+    let options = FacetingOptions {
+        identify_group: true,
+        ..Default::default()
+    };
+    let (_, stats) = cube.faceting_with_stats(vertices.clone(), GroupEnum::Chiral(false), &options);
+    assert_eq!(stats.group_name, Some(GroupName::Coxeter(vec![CoxeterFactor::B(3)])));
+    assert!(stats.to_string().contains("group B3 of order 48"));
+
+    let (_, stats) = cube.faceting_with_stats(vertices, GroupEnum::Chiral(true), &options);
+    assert_eq!(stats.group_name, Some(GroupName::Rotation(vec![CoxeterFactor::B(3)])));
+}
+
+/// Facetings are named after the polytope they come from and their facets.
+#[test]
+fn names() {
+    let mut poly = Concrete::hypercube(4).with_meta(PolytopeMeta::named("cube"));
+    let vertices = poly.vertices.to_vec();
+    let facetings = poly.faceting(vertices, GroupEnum::Chiral(false), &Default::default());
+
+    assert_eq!(facetings.len(), 2);
+    for (idx, FacetingResult { polytope: faceting, .. }) in facetings.iter().enumerate() {
+        let name = faceting.meta.name.as_ref().unwrap();
+        assert!(name.starts_with(&format!("faceting #{} of cube (", idx)));
+    }
+}
+
+/// The 24-cell is centrally symmetric, and the hyperplanes and facetings must
+/// be the same as the ones found without using this.
+#[test]
+fn icositetrachoron() {
+    let poly = Concrete::from_off(include_str!("fixtures/ico.off")).unwrap();
+    let (counts, stats) = facet(poly);
+
+    assert!(stats.central_symmetry);
+    assert_eq!(stats.group_order, 1152);
+    assert_eq!(stats.hyperplane_count, 888);
+    assert_eq!(stats.hyperplane_orbit_count, 9);
+    assert_eq!(stats.possible_facet_count, 32);
+    assert_eq!(stats.faceting_count, 12);
+    assert_eq!(counts.len(), 12);
+}
+
+/// Setting the cancellation flag beforehand stops the enumeration.
+#[test]
+fn cancelled() {
+    use std::sync::{atomic::AtomicBool, Arc};
+
+    let mut poly = Concrete::hypercube(4);
+    let vertices = poly.vertices.to_vec();
+    let options = FacetingOptions {
+        cancel: Some(Arc::new(AtomicBool::new(true))),
+        ..Default::default()
+    };
+    let (facetings, stats) = poly.faceting_with_stats(vertices, GroupEnum::Chiral(false), &options);
+
+    assert!(stats.cancelled);
+    assert!(!stats.timed_out);
+    assert_eq!(stats.stop_point, Some(StopPoint::Hyperplanes));
+    assert!(facetings.is_empty());
+}
+
+/// Setting the cancellation flag partway through the combining stage keeps
+/// the facetings found so far, each of them a valid polytope.
+#[test]
+fn cancelled_while_combining() {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    let poly = Concrete::hypercube(4);
+    let (all, _) = facet_with_callback(&poly, None);
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&cancel);
+    let options = FacetingOptions {
+        facet_orbits: Some(FacetOrbitCount::AtMost(2)),
+        cancel: Some(cancel),
+        candidate_callback: Some(CandidateCallback::new(move |candidate| {
+            if candidate.complete {
+                flag.store(true, Ordering::Relaxed);
+            }
+            CandidateDecision::Accept
+        })),
+        ..Default::default()
+    };
+
+    let mut poly = poly;
+    let (found, stats) = std::thread::spawn(move || {
+        let vertices = poly.vertices.to_vec();
+        poly.faceting_with_stats(vertices, GroupEnum::Chiral(false), &options)
+    })
+    .join()
+    .unwrap();
+
+    assert!(stats.cancelled);
+    assert!(matches!(stats.stop_point, Some(StopPoint::Combining { hyperplane_orbit: Some(_) })));
+    assert!(!found.is_empty() && found.len() < all.len());
+    for faceting in &found {
+        assert!(faceting.polytope.ranks().is_valid().is_ok());
+    }
+}
+
+/// A time limit that isn't reached changes nothing.
+#[test]
+fn generous_time_limit() {
+    use std::time::Duration;
+
+    let mut poly = Concrete::from_off(include_str!("fixtures/ico.off")).unwrap();
+    let (expected, _) = facet(poly.clone());
+
+    let vertices = poly.vertices.to_vec();
+    let options = FacetingOptions {
+        time_limit: Some(Duration::from_secs(600)),
+        ..Default::default()
+    };
+    let (facetings, stats) = poly.faceting_with_stats(vertices, GroupEnum::Chiral(false), &options);
+    let mut counts = element_counts(&facetings);
+    counts.sort();
+
+    assert!(stats.is_exhaustive());
+    assert!(!stats.timed_out);
+    assert_eq!(stats.stop_point, None);
+    assert_eq!(counts, expected);
+}
+
+/// A time limit that's far too short stops the enumeration soon after it
+/// runs out, and says where.
+#[test]
+fn tiny_time_limit() {
+    use std::time::{Duration, Instant};
+
+    let mut poly = Concrete::from_off(include_str!("fixtures/ico.off")).unwrap();
+    let vertices = poly.vertices.to_vec();
+    let options = FacetingOptions {
+        time_limit: Some(Duration::from_millis(1)),
+        ..Default::default()
+    };
+
+    let start = Instant::now();
+    let (_, stats) = poly.faceting_with_stats(vertices, GroupEnum::Chiral(false), &options);
+
+    assert!(start.elapsed() < Duration::from_secs(10));
+    assert!(stats.timed_out && stats.cancelled);
+    let report = stats.to_string();
+    assert!(report.contains("(timed out)"));
+    assert!(report.contains("stopped while"));
+    assert!(!stats.is_exhaustive());
+    assert!(stats.stop_point.is_some());
+}
+
+/// The time limit also stops faceting for every edge length, which says
+/// which one it stopped at.
+#[test]
+fn time_limit_edge_lengths() {
+    use std::time::Duration;
+
+    let mut poly = Concrete::from_off(include_str!("fixtures/ico.off")).unwrap();
+    let vertices = poly.vertices.to_vec();
+    let options = FacetingOptions {
+        any_single_edge_length: true,
+        time_limit: Some(Duration::from_millis(1)),
+        ..Default::default()
+    };
+    let (_, stats) = poly.faceting_with_stats(vertices, GroupEnum::Chiral(false), &options);
+
+    assert!(stats.timed_out);
+    assert!(stats.stop_point.is_some());
+    assert!(stats.stop_edge_length.is_some());
+}
+
+/// The faceting stops cleanly when there are more hyperplanes than facets
+/// allowed, and goes on when there are exactly as many.
+#[test]
+fn element_limits() {
+    let mut poly = Concrete::hypercube(4);
+    let vertices = poly.vertices.to_vec();
+    let (all, stats) = poly.faceting_with_stats(vertices.clone(), GroupEnum::Chiral(false), &Default::default());
+    let hyperplanes = stats.hyperplane_count;
+
+    let options = FacetingOptions {
+        element_limits: ElementLimits::new(hyperplanes),
+        ..Default::default()
+    };
+    let (found, _) = poly
+        .try_faceting_with_stats(vertices.clone(), GroupEnum::Chiral(false), &options)
+        .unwrap();
+    assert_eq!(found.len(), all.len());
+
+    let options = FacetingOptions {
+        element_limits: ElementLimits::new(hyperplanes - 1),
+        ..Default::default()
+    };
+    match poly.try_faceting_with_stats(vertices, GroupEnum::Chiral(false), &options) {
+        Err(FacetingError::TooLarge(err)) => assert_eq!(
+            err,
+            TooLarge {
+                rank: 3,
+                predicted: hyperplanes,
+                limit: hyperplanes - 1,
+            }
+        ),
+        _ => panic!("the faceting should stop"),
+    }
+}
+
+/// The facetings are output with the facet orbits they were built from, and
+/// the number of facets from each of them, which are those of the hyperplanes
+/// in the orbit.
+#[test]
+fn result_metadata() {
+    let mut poly = Concrete::from_off(include_str!("fixtures/ico.off")).unwrap();
+    let vertices = poly.vertices.to_vec();
+    let tables = poly
+        .faceting_tables(vertices.clone(), GroupEnum::Chiral(false), &Default::default())
+        .unwrap();
+    let facetings = poly.faceting(vertices, GroupEnum::Chiral(false), &Default::default());
+
+    assert_eq!(facetings.len(), 12);
+    for faceting in &facetings {
+        let rank = faceting.polytope.rank();
+        assert_eq!(faceting.facet_orbit_count(), faceting.facets.len());
+        assert_eq!(faceting.facet_counts.len(), faceting.facets.len());
+        assert_eq!(faceting.facet_count(), faceting.polytope.el_count(rank - 1));
+        assert!(faceting.name.as_ref().unwrap().starts_with("faceting "));
+
+        for (&(hp, _), &count) in faceting.facets.iter().zip(&faceting.facet_counts) {
+            assert_eq!(count, tables.hyperplane_count(hp));
+        }
+    }
+    assert!(facetings.iter().any(|faceting| faceting.facet_orbit_count() == 1));
+}
+
+/// The iterator finds the same facetings as the faceting, one at a time.
+#[test]
+fn faceting_iter() {
+    let mut poly = Concrete::from_off(include_str!("fixtures/ico.off")).unwrap();
+    let vertices = poly.vertices.to_vec();
+    let (all, all_stats) = poly.faceting_with_stats(vertices.clone(), GroupEnum::Chiral(false), &Default::default());
+
+    let mut iter = poly
+        .faceting_iter(vertices.clone(), GroupEnum::Chiral(false), &Default::default())
+        .unwrap();
+    let found: Vec<_> = iter.by_ref().collect();
+    assert_eq!(iter.stats().faceting_count, all.len());
+    assert_eq!(iter.stats().candidates_examined, all_stats.candidates_examined);
+
+    let facet_lists = |facetings: &[FacetingResult]| {
+        let mut lists: Vec<_> = facetings.iter().map(|faceting| faceting.facets.clone()).collect();
+        lists.sort();
+        lists
+    };
+    assert_eq!(facet_lists(&found), facet_lists(&all));
+    let mut counts = element_counts(&found);
+    counts.sort();
+    let mut all_counts = element_counts(&all);
+    all_counts.sort();
+    assert_eq!(counts, all_counts);
+
+    // Stopping after the first faceting leaves the rest of the search undone.
+    let mut iter = poly
+        .faceting_iter(vertices, GroupEnum::Chiral(false), &Default::default())
+        .unwrap();
+    assert!(iter.next().is_some());
+    assert!(iter.stats().candidates_examined < all_stats.candidates_examined);
+}
+
+/// Counts the facetings of a polytope with some chiral selection, without
+/// building them.
+fn count_chiral(poly: &Concrete, chiral: bool, options: FacetingOptions) -> usize {
+    let mut poly = poly.clone();
+    let vertices = poly.vertices.to_vec();
+    let options = FacetingOptions {
+        save: false,
+        ..options
+    };
+    let (_, stats) = poly.faceting_with_stats(vertices, GroupEnum::Chiral(chiral), &options);
+    stats.faceting_count
+}
+
+/// The snub cube has no reflection symmetry, so none of its facetings can be
+/// reflexible.
+#[test]
+fn snub_cube_selection() {
+    let poly = Concrete::from_off(include_str!("fixtures/snic.off")).unwrap();
+    let count = |chiral_selection| {
+        count_chiral(
+            &poly,
+            false,
+            FacetingOptions {
+                noble: Some(1),
+                chiral_selection,
+                ..Default::default()
+            },
+        )
+    };
+
+    let any = count(ChiralSelection::Any);
+    assert!(any > 0);
+    assert_eq!(count(ChiralSelection::PairedOnly), 0);
+    assert_eq!(count(ChiralSelection::UnpairedOnly), any);
+}
+
+/// Under the rotation group of the truncated octahedron, some hyperplane orbits
+/// come in mirror pairs.
+#[test]
+fn truncated_octahedron_selection() {
+    let poly = Concrete::from_off(include_str!("fixtures/toe.off")).unwrap();
+    let count = |chiral_selection| {
+        count_chiral(
+            &poly,
+            true,
+            FacetingOptions {
+                facet_orbits: Some(FacetOrbitCount::AtMost(2)),
+                chiral_selection,
+                ..Default::default()
+            },
+        )
+    };
+
+    assert_eq!(count(ChiralSelection::Any), 105);
+    assert_eq!(count(ChiralSelection::PairedOnly), 99);
+    assert_eq!(count(ChiralSelection::UnpairedOnly), 6);
+}
+
+/// The faceting stops before enumerating hyperplanes if there's too much work.
+#[test]
+fn too_many_tuples() {
+    let mut poly = Concrete::from_off(include_str!("fixtures/ico.off")).unwrap();
+    let vertices = poly.vertices.to_vec();
+    let options = FacetingOptions {
+        abort_if_estimated_tuples_exceed: Some(10),
+        ..Default::default()
+    };
+
+    match poly.try_faceting_with_stats(vertices, GroupEnum::Chiral(false), &options) {
+        Err(FacetingError::TooManyTuples { estimated, limit }) => {
+            assert!(estimated > limit);
+            assert_eq!(limit, 10);
+        }
+        Err(err) => panic!("unexpected error: {}", err),
+        Ok(_) => panic!("faceting should have been aborted"),
+    }
+}
+
+/// A triangular prism whose bases have edges of length √3, with height 1.
+fn triangular_prism() -> Concrete {
+    Concrete::polygon(3).prism_with(1.0)
+}
+
+/// Returns the element counts of the facetings of the triangular prism with
+/// some options.
+fn facet_prism(options: FacetingOptions) -> Vec<Vec<usize>> {
+    let mut poly = triangular_prism();
+    let vertices = poly.vertices.to_vec();
+    let facetings = poly.faceting(vertices, GroupEnum::Chiral(false), &options);
+    element_counts(&facetings)
+}
+
+/// The prism has two edge lengths, so no single one gives it back, but it can
+/// be found by filtering the triangles and the rectangles separately.
+#[test]
+fn per_orbit_edge_length() {
+    let side = 3f64.sqrt();
+    let prism = vec![1, 6, 9, 5, 1];
+
+    for length in &[side, 1.0] {
+        let found = facet_prism(FacetingOptions {
+            edge_length: EdgeLengthFilter::exact(*length),
+            ..Default::default()
+        });
+        assert!(!found.contains(&prism));
+    }
+
+    let mut per_orbit_edge_length = HashMap::new();
+    // Both are at the same depth, so the rectangles come first, having more
+    // vertices, then the bases.
+    per_orbit_edge_length.insert(0, EdgeLengthFilter::between(1.0, side));
+    per_orbit_edge_length.insert(1, EdgeLengthFilter::exact(side));
+    let found = facet_prism(FacetingOptions {
+        per_orbit_edge_length,
+        ..Default::default()
+    });
+    assert!(found.contains(&prism));
+}
+
+/// Allowing only the edge lengths of the prism finds it, along with other
+/// facetings with the same edge lengths, but none with its diagonals as edges.
+#[test]
+fn edge_length_set() {
+    let prism = triangular_prism();
+    let lengths = prism.edge_lengths();
+    assert_eq!(lengths.len(), 2);
+    assert!((lengths[0] - 1.0).abs() < 1e-9 && (lengths[1] - 3f64.sqrt()).abs() < 1e-9);
+
+    let mut poly = prism.clone();
+    let vertices = poly.vertices.to_vec();
+    let options = FacetingOptions {
+        edge_length: EdgeLengthFilter::set(lengths.clone()),
+        ..Default::default()
+    };
+    let found = poly.faceting(vertices.clone(), GroupEnum::Chiral(false), &options);
+    assert!(element_counts(&found).contains(&vec![1, 6, 9, 5, 1]));
+
+    let filter = EdgeLengthFilter::edges_of(&prism);
+    for faceting in &found {
+        for length in faceting.polytope.edge_lengths() {
+            assert!(filter.contains(length), "edge of length {}", length);
+        }
+    }
+
+    let all = poly.faceting(vertices, GroupEnum::Chiral(false), &Default::default());
+    assert!(found.len() < all.len());
+}
+
+/// Returns the names of the facetings of a polytope under its rotation group
+/// with at most two facet orbits, along with the number of candidates checked.
+fn facet_ordered(poly: &Concrete, orbit_order: OrbitOrder) -> (Vec<String>, u64) {
+    let mut poly = poly.clone();
+    let vertices = poly.vertices.to_vec();
+    let options = FacetingOptions {
+        facet_orbits: Some(FacetOrbitCount::AtMost(2)),
+        orbit_order,
+        ..Default::default()
+    };
+    let (facetings, stats) = poly.faceting_with_stats(vertices, GroupEnum::Chiral(true), &options);
+    let names = facetings
+        .into_iter()
+        .map(|faceting| faceting.polytope.meta.name.unwrap())
+        .collect();
+    (names, stats.candidates_examined)
+}
+
+/// Reordering the hyperplane orbits as we go finds the same facetings with
+/// fewer candidates.
+#[test]
+fn adaptive_orbit_order() {
+    let poly = Concrete::from_off(include_str!("fixtures/snic.off")).unwrap();
+    let (names, candidates) = facet_ordered(&poly, OrbitOrder::Index);
+    assert_eq!(names.len(), 104);
+
+    let (fewest_names, _) = facet_ordered(&poly, OrbitOrder::FewestFacetsFirst);
+    assert_eq!(fewest_names, names);
+
+    let (adaptive_names, adaptive_candidates) =
+        facet_ordered(&poly, OrbitOrder::Adaptive { interval: 1 });
+    assert_eq!(adaptive_names, names);
+    assert!(adaptive_candidates < candidates);
+}
+
+/// Completing a selection of facets finds exactly the facetings that contain
+/// them, and completing nothing finds every faceting.
+#[test]
+fn complete() {
+    let mut poly = Concrete::from_off(include_str!("fixtures/ico.off")).unwrap();
+    let vertices = poly.vertices.to_vec();
+    let options = FacetingOptions::default();
+    let names: Vec<_> = poly
+        .faceting(vertices.clone(), GroupEnum::Chiral(false), &options)
+        .into_iter()
+        .map(|faceting| faceting.polytope.meta.name.unwrap())
+        .collect();
+
+    let tables = poly
+        .faceting_tables(vertices, GroupEnum::Chiral(false), &options)
+        .unwrap();
+    let all = tables.complete(&[], &options).unwrap();
+    let all_names: Vec<_> = all
+        .iter()
+        .map(|result| result.polytope.meta.name.clone().unwrap())
+        .collect();
+    assert_eq!(all_names, names);
+
+    for hp in 0..tables.hyperplane_orbit_count() {
+        for f in 0..tables.facet_count(hp) {
+            let containing: Vec<_> = all
+                .iter()
+                .filter(|result| result.facets.contains(&(hp, f)))
+                .map(|result| result.facets.clone())
+                .collect();
+            // Some facets cover a ridge more than twice by themselves.
+            let completed: Vec<_> = match tables.complete(&[(hp, f)], &options) {
+                Ok(results) => results.into_iter().map(|result| result.facets).collect(),
+                Err(FacetingError::OverCoveredRidge { .. }) => Vec::new(),
+                Err(err) => panic!("unexpected error: {}", err),
+            };
+            assert_eq!(completed, containing, "facet ({},{})", hp, f);
+        }
+    }
+}
+
+/// Every faceting found can be rebuilt from its facet orbits alone, and facet
+/// orbits that don't make up a faceting are told apart from ones that don't
+/// exist.
+#[test]
+fn build_single() {
+    let mut poly = Concrete::from_off(include_str!("fixtures/ico.off")).unwrap();
+    let vertices = poly.vertices.to_vec();
+    let options = FacetingOptions::default();
+    let tables = poly
+        .faceting_tables(vertices, GroupEnum::Chiral(false), &options)
+        .unwrap();
+    let all = tables.complete(&[], &options).unwrap();
+
+    for result in &all {
+        let replay = tables.build_single(&result.facets).unwrap();
+        assert_eq!(replay.facets, result.facets);
+        assert_eq!(replay.facet_counts, result.facet_counts);
+        assert_eq!(replay.polytope.vertices, result.polytope.vertices);
+        assert_eq!(element_counts(std::slice::from_ref(&replay)), element_counts(std::slice::from_ref(result)));
+        assert!(replay.polytope.meta.name.unwrap().starts_with("faceting ("));
+    }
+
+    let hp_count = tables.hyperplane_orbit_count();
+    assert_eq!(
+        tables.build_single(&[(hp_count, 0)]).unwrap_err(),
+        ReplayError::InvalidFacet { hyperplane_orbit: hp_count, facet: 0 }
+    );
+    assert_eq!(
+        tables.build_single(&[(0, tables.facet_count(0))]).unwrap_err(),
+        ReplayError::InvalidFacet { hyperplane_orbit: 0, facet: tables.facet_count(0) }
+    );
+    assert_eq!(tables.build_single(&[]).unwrap_err(), ReplayError::NoFacets);
+
+    // Some facets cover a ridge more than twice by themselves.
+    let over_covering = (0..hp_count)
+        .flat_map(|hp| (0..tables.facet_count(hp)).map(move |f| (hp, f)))
+        .find(|facet| matches!(tables.complete(&[*facet], &options), Err(FacetingError::OverCoveredRidge { .. })))
+        .unwrap();
+    assert!(matches!(
+        tables.build_single(&[over_covering]),
+        Err(ReplayError::OpenRidges { .. })
+    ));
+}
+
+/// The possible facets of the 24-cell are kept as polyhedra on their
+/// hyperplanes only when asked for. The outermost hyperplanes hold its
+/// octahedra.
+#[test]
+fn subfacetings() {
+    let mut poly = Concrete::from_off(include_str!("fixtures/ico.off")).unwrap();
+    let vertices = poly.vertices.to_vec();
+
+    let (tables, stats) = poly
+        .faceting_tables_with_stats(vertices.clone(), GroupEnum::Chiral(false), &Default::default())
+        .unwrap();
+    assert!(tables.subfacetings().is_empty());
+    assert_eq!((stats.subfaceting_count, stats.subfaceting_bytes), (0, 0));
+
+    let options = FacetingOptions {
+        collect_subfacetings: true,
+        ..Default::default()
+    };
+    let (tables, stats) = poly
+        .faceting_tables_with_stats(vertices, GroupEnum::Chiral(false), &options)
+        .unwrap();
+    let subfacetings = tables.subfacetings();
+    assert_eq!(subfacetings.len(), tables.hyperplane_orbit_count());
+    assert_eq!(
+        stats.subfaceting_count,
+        (0..tables.hyperplane_orbit_count()).map(|hp| tables.facet_count(hp)).sum::<usize>()
+    );
+    assert!(stats.subfaceting_bytes > 0);
+
+    for (hp, orbit) in subfacetings.iter().enumerate() {
+        assert_eq!(orbit.facets.len(), tables.facet_count(hp));
+        assert!(orbit.stabilizer.iter().all(|row| row.len() == orbit.vertices.len()));
+
+        for facet in &orbit.facets {
+            assert_eq!(facet.rank(), 4);
+            assert!(facet.ranks().is_valid().is_ok());
+            assert!(facet.vertices.iter().all(|v| orbit.hyperplane.distance(v) < 1e-7));
+        }
+    }
+
+    assert!(subfacetings[0]
+        .facets
+        .iter()
+        .any(|facet| facet.abs.el_count_iter().collect::<Vec<_>>() == vec![1, 6, 12, 8, 1]));
+}
+
+/// Polytopes below rank 4 can't be faceted, and asking for their tables is an
+/// error rather than a panic.
+#[test]
+fn low_rank() {
+    for mut poly in [Concrete::point(), Concrete::dyad(), Concrete::polygon(5)] {
+        let vertices = poly.vertices.to_vec();
+        let rank = poly.rank();
+        assert!(matches!(
+            poly.faceting_tables(vertices.clone(), GroupEnum::Chiral(false), &Default::default()),
+            Err(FacetingError::UnsupportedRank(r)) if r == rank
+        ));
+        assert!(poly
+            .faceting(vertices, GroupEnum::Chiral(false), &Default::default())
+            .is_empty());
+    }
+}
+
+/// Facets that don't exist or can't go together can't be completed.
+#[test]
+fn complete_invalid() {
+    let mut poly = Concrete::hypercube(4);
+    let vertices = poly.vertices.to_vec();
+    let options = FacetingOptions::default();
+    let tables = poly
+        .faceting_tables(vertices, GroupEnum::Chiral(false), &options)
+        .unwrap();
+    let count = tables.hyperplane_orbit_count();
+
+    assert!(matches!(
+        tables.complete(&[(count, 0)], &options),
+        Err(FacetingError::InvalidFacet { .. })
+    ));
+    assert!(matches!(
+        tables.complete(&[(0, 0), (0, 0)], &options),
+        Err(FacetingError::RepeatedHyperplaneOrbit(0))
+    ));
+}
+
+/// The facetings close up when exported as partial structures, while the
+/// facets that aren't in any faceting leave some ridges open or overfull.
+#[test]
+fn build_partial() {
+    let mut poly = Concrete::hypercube(4);
+    let vertices = poly.vertices.to_vec();
+    let options = FacetingOptions::default();
+    let tables = poly
+        .faceting_tables(vertices, GroupEnum::Chiral(false), &options)
+        .unwrap();
+
+    let all = tables.complete(&[], &options).unwrap();
+    for result in &all {
+        let (partial, vertices) = tables.build_partial(&result.facets);
+        assert!(partial.is_valid());
+        assert_eq!(vertices.len(), result.polytope.vertices.len());
+    }
+
+    let mut open_count = 0;
+    for hp in 0..tables.hyperplane_orbit_count() {
+        for f in 0..tables.facet_count(hp) {
+            if all.iter().any(|result| result.facets == [(hp, f)]) {
+                continue;
+            }
+
+            let (partial, _) = tables.build_partial(&[(hp, f)]);
+            assert!(partial
+                .violations()
+                .iter()
+                .any(|v| matches!(v, Violation::Dyadic { count, .. } if *count != 2)));
+            assert!(partial.try_finalize().is_err());
+            open_count += 1;
+        }
+    }
+    assert!(open_count > 0);
+}
+
+/// With the default ridge multiplicity the complexes are the facetings, while
+/// a higher one gives complexes that are only returned as partial
+/// structures.
+#[test]
+fn ridge_multiplicity() {
+    let mut poly = Concrete::hypercube(4);
+    let vertices = poly.vertices.to_vec();
+    let options = FacetingOptions::default();
+    let tables = poly
+        .faceting_tables(vertices.clone(), GroupEnum::Chiral(false), &options)
+        .unwrap();
+
+    let facetings: Vec<_> = tables
+        .complete(&[], &options)
+        .unwrap()
+        .into_iter()
+        .map(|result| result.facets)
+        .collect();
+    let complexes: Vec<_> = tables
+        .complexes(&[], &options)
+        .unwrap()
+        .into_iter()
+        .map(|complex| complex.facets)
+        .collect();
+    assert_eq!(complexes, facetings);
+
+    let triple = FacetingOptions {
+        ridge_multiplicity: 3,
+        ..Default::default()
+    };
+    let complexes = tables.complexes(&[], &triple).unwrap();
+    assert!(!complexes.is_empty());
+    for complex in &complexes {
+        let ranks = complex.complex.ranks();
+        assert!((&ranks[2]).into_iter().all(|ridge| (&ridge.sups).into_iter().count() == 3));
+    }
+
+    assert!(matches!(
+        tables.complete(&[], &triple),
+        Err(FacetingError::NotPolytopes { ridge_multiplicity: 3 })
+    ));
+    assert!(matches!(
+        poly.try_faceting_with_stats(vertices.clone(), GroupEnum::Chiral(false), &triple).map(|_| ()),
+        Err(FacetingError::NotPolytopes { ridge_multiplicity: 3 })
+    ));
+
+    let zero = FacetingOptions {
+        ridge_multiplicity: 0,
+        ..Default::default()
+    };
+    assert!(matches!(
+        tables.complexes(&[], &zero),
+        Err(FacetingError::ZeroRidgeMultiplicity)
+    ));
+    assert!(matches!(
+        poly.faceting_tables(vertices, GroupEnum::Chiral(false), &zero),
+        Err(FacetingError::ZeroRidgeMultiplicity)
+    ));
+}
+
+/// Only keeping the hyperplanes deeper than the faces of the dodecahedron
+/// leaves out the facetings with any of these faces, such as the dodecahedron
+/// itself.
+#[test]
+fn depth_filter() {
+    let mut doe = Concrete::from_off(include_str!("fixtures/ike.off"))
+        .unwrap()
+        .try_dual()
+        .unwrap();
+    let vertices = doe.vertices.to_vec();
+    let max_depth = doe.insphere_radius().unwrap() * 0.99;
+
+    let tables = doe
+        .faceting_tables(vertices.clone(), GroupEnum::Chiral(false), &Default::default())
+        .unwrap();
+    let all = tables.complete(&[], &Default::default()).unwrap();
+    let deeper: Vec<_> = all
+        .iter()
+        .filter(|result| result.facets.iter().all(|&(hp, _)| tables.depth(hp) < max_depth))
+        .cloned()
+        .collect();
+    assert!(!deeper.is_empty() && deeper.len() < all.len());
+    let mut expected = element_counts(&deeper);
+    expected.sort();
+
+    for &filter in &[DepthFilter::Max(max_depth), DepthFilter::RelativeToInsphere(0.99)] {
+        let options = FacetingOptions {
+            depth_filter: Some(filter),
+            ..Default::default()
+        };
+        let (facetings, stats) = doe
+            .try_faceting_with_stats(vertices.clone(), GroupEnum::Chiral(false), &options)
+            .unwrap();
+        let mut counts = element_counts(&facetings);
+        counts.sort();
+
+        assert_eq!(counts, expected);
+        assert!(stats.depth_filtered > 0);
+        assert_eq!(stats.hyperplane_orbit_count + stats.depth_filtered, tables.hyperplane_orbit_count());
+    }
+}
+
+/// Some facetings of the truncated pentachoron are found from two sets of
+/// facet orbits, as some of its hyperplane orbits have several possible facets
+/// with the same images. Each of them is only output once.
+#[test]
+fn duplicate_facetings() {
+    let mut tip = Concrete::from_off(include_str!("fixtures/tip.off")).unwrap();
+    let vertices = tip.vertices.to_vec();
+    let first = vec![(0, 0), (6, 29), (8, 3), (13, 2), (15, 0), (17, 3)];
+    let second = vec![(0, 0), (6, 24), (8, 5), (13, 2), (15, 0), (17, 5)];
+    let options = FacetingOptions {
+        facet_filter: Some(FacetFilter::Allow(first.iter().chain(&second).copied().collect())),
+        ..Default::default()
+    };
+    let (facetings, stats) = tip.faceting_with_stats(vertices, GroupEnum::Chiral(false), &options);
+    assert_eq!(stats.duplicate_count, 1);
+    assert_eq!(facetings.len(), 1);
+    assert!(stats.to_string().contains("duplicate"));
+    let found: Vec<_> = facetings.iter().map(|faceting| &faceting.facets).collect();
+    assert!(found.contains(&&first) != found.contains(&&second));
+}
+
+/// Repeating the rows of a vertex map, reordering them, or leaving out the
+/// identity doesn't change the facetings or how the orbits are counted.
+#[test]
+fn redundant_vertex_map() {
+    for poly in [Concrete::hypercube(4), Concrete::from_off(include_str!("fixtures/toe.off")).unwrap()] {
+        let (_, vertex_map) = poly.clone().get_symmetry_group().unwrap();
+        let facet_with = |vertex_map: Vec<Vec<usize>>| {
+            let mut poly = poly.clone();
+            let vertices = poly.vertices.to_vec();
+            let options = FacetingOptions {
+                facet_orbits: Some(FacetOrbitCount::AtMost(2)),
+                ..Default::default()
+            };
+            let (facetings, stats) = poly
+                .try_faceting_with_stats(vertices, GroupEnum::VertexMap(vertex_map.into()), &options)
+                .unwrap();
+            let names: Vec<_> = facetings.iter().map(|faceting| faceting.polytope.meta.name.clone()).collect();
+            let counts = (
+                stats.group_order,
+                stats.vertex_orbit_count,
+                stats.hyperplane_count,
+                stats.hyperplane_orbit_count,
+                stats.possible_facet_count,
+                stats.ridge_orbit_count,
+            );
+            (names, element_counts(&facetings), counts)
+        };
+
+        let expected = facet_with(vertex_map.clone());
+        assert!(!expected.0.is_empty());
+        assert_eq!(expected.2 .0, vertex_map.len());
+
+        let doubled = vertex_map.iter().flat_map(|row| vec![row.clone(); 2]).collect();
+        assert_eq!(facet_with(doubled), expected);
+
+        let identity: Vec<_> = (0..poly.vertices.len()).collect();
+        let mut shuffled: Vec<_> = vertex_map.iter().filter(|&row| *row != identity).cloned().collect();
+        shuffle(&mut shuffled, 7);
+        shuffled.extend(shuffled.clone());
+        assert_eq!(facet_with(shuffled), expected);
+    }
+}
+
+/// Shuffles a list with a linear congruential generator, so that the shuffles
+/// are the same on every run.
+fn shuffle<T>(list: &mut [T], seed: u64) {
+    let mut state = seed;
+    for i in (1..list.len()).rev() {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        list.swap(i, (state >> 33) as usize % (i + 1));
+    }
+}
+
+/// Returns a polytope with its vertices reordered, so that the new vertex `i`
+/// is the old vertex `order[i]`.
+fn reorder_vertices(poly: &Concrete, order: &[usize]) -> Concrete {
+    let mut new_idx = vec![0; order.len()];
+    for (i, &v) in order.iter().enumerate() {
+        new_idx[v] = i;
+    }
+
+    let mut ranks = poly.abs.ranks().clone();
+    ranks[1] = order.iter().map(|&v| ranks[(1, v)].clone()).collect();
+    for edge in &mut ranks[2] {
+        edge.subs = (&edge.subs).into_iter().map(|&v| new_idx[v]).collect();
+    }
+
+    let abs = PartialAbstract::new(ranks).try_finalize().unwrap();
+    let vertices = order.iter().map(|&v| poly.vertices[v].clone()).collect();
+    Concrete::new(vertices, abs)
+}
+
+/// The hyperplane orbits of the 24-cell, their depths, and their
+/// representatives don't depend on the order of its vertices.
+#[test]
+fn vertex_order() {
+    let poly = Concrete::from_off(include_str!("fixtures/ico.off")).unwrap();
+
+    // The depth, the offset and basis, and the sorted vertex coordinates of
+    // the representative of each orbit, and the total number of hyperplanes.
+    let orbit_structure = |order: &[usize]| {
+        let mut poly = reorder_vertices(&poly, order);
+        let vertices = poly.vertices.to_vec();
+        let (tables, stats) = poly
+            .faceting_tables_with_stats(vertices, GroupEnum::Chiral(false), &Default::default())
+            .unwrap();
+
+        let orbits: Vec<_> = (0..tables.hyperplane_orbit_count())
+            .map(|hp| {
+                let hyperplane = tables.hyperplane(hp);
+                let mut on_hyperplane: Vec<Vec<f64>> = poly
+                    .vertices
+                    .iter()
+                    .filter(|v| hyperplane.distance(v) < 1e-7)
+                    .map(|v| v.iter().copied().collect())
+                    .collect();
+                on_hyperplane.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+                (tables.depth(hp), hyperplane.offset.clone(), hyperplane.basis.clone(), on_hyperplane)
+            })
+            .collect();
+        (orbits, stats.hyperplane_count)
+    };
+
+    let mut order: Vec<_> = (0..poly.vertices.len()).collect();
+    let expected = orbit_structure(&order);
+    assert!(!expected.0.is_empty());
+
+    for seed in 0..20 {
+        shuffle(&mut order, seed);
+        assert_eq!(orbit_structure(&order), expected, "seed {}", seed);
+    }
+}
+
+/// The hyperplane orbits are sorted from the outside in, and the outermost ones
+/// of the 24-cell are its octahedra.
+#[test]
+fn depth_order() {
+    let mut poly = Concrete::from_off(include_str!("fixtures/ico.off")).unwrap();
+    let vertices = poly.vertices.to_vec();
+    let tables = poly
+        .faceting_tables(vertices, GroupEnum::Chiral(false), &Default::default())
+        .unwrap();
+
+    let depths: Vec<_> = (0..tables.hyperplane_orbit_count())
+        .map(|hp| tables.depth(hp))
+        .collect();
+    assert!(depths.windows(2).all(|pair| pair[0] >= pair[1] - 1e-7));
+
+    let facets = tables.complete(&[], &Default::default()).unwrap();
+    assert!(facets.iter().any(|result| result.facets == vec![(0, 0)]));
+}
+
+/// Returns the vertex map of some points under the symmetry group of a
+/// polytope.
+fn vertex_map_of(poly: &Concrete, points: &[Point<f64>]) -> Vec<Vec<usize>> {
+    let (group, _) = poly.clone().get_symmetry_group().unwrap();
+    group
+        .map(|isometry| {
+            points
+                .iter()
+                .map(|p| {
+                    let image = &isometry * p;
+                    points.iter().position(|q| (q - &image).norm() < 1e-7).unwrap()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Extends the faceting tables of a polytope to some new points, checks that
+/// they agree with the tables computed from scratch, and returns the number of
+/// hyperplane orbits whose facets were reused.
+fn check_extension(poly: &Concrete, new_points: &[Point<f64>]) -> usize {
+    let vertex_count = poly.vertices.len();
+    let mut points = poly.vertices.to_vec();
+    points.extend_from_slice(new_points);
+    let vertex_map = vertex_map_of(poly, &points);
+    let old_map: Vec<Vec<usize>> = vertex_map.iter().map(|row| row[..vertex_count].to_vec()).collect();
+    let options = FacetingOptions::default();
+
+    let (old, _) = poly
+        .clone()
+        .faceting_tables_with_stats(poly.vertices.to_vec(), GroupEnum::VertexMap(old_map.into()), &options)
+        .unwrap();
+    let (extended, stats) = old.extend_with_vertices_with_stats(new_points, vertex_map.clone()).unwrap();
+    let (scratch, scratch_stats) = poly
+        .clone()
+        .faceting_tables_with_stats(points, GroupEnum::VertexMap(vertex_map.into()), &options)
+        .unwrap();
+
+    assert_eq!(extended.hyperplane_orbit_count(), scratch.hyperplane_orbit_count());
+    for hp in 0..scratch.hyperplane_orbit_count() {
+        assert_eq!(extended.hyperplane(hp).offset, scratch.hyperplane(hp).offset);
+        assert_eq!(extended.hyperplane(hp).basis, scratch.hyperplane(hp).basis);
+        assert_eq!(extended.depth(hp), scratch.depth(hp));
+        assert_eq!(extended.hyperplane_count(hp), scratch.hyperplane_count(hp));
+        assert_eq!(extended.facet_count(hp), scratch.facet_count(hp));
+    }
+
+    assert_eq!(stats.hyperplane_count, scratch_stats.hyperplane_count);
+    assert_eq!(stats.possible_facet_count, scratch_stats.possible_facet_count);
+    assert_eq!(stats.ridge_orbit_count, scratch_stats.ridge_orbit_count);
+
+    let facets = |results: Vec<FacetingResult>| -> FacetLists { results.into_iter().map(|result| result.facets).collect() };
+    let extended_facets = facets(extended.complete(&[], &options).unwrap());
+    assert!(!extended_facets.is_empty());
+    assert_eq!(extended_facets, facets(scratch.complete(&[], &options).unwrap()));
+
+    assert!(stats.reused_hyperplane_orbit_count <= scratch.hyperplane_orbit_count());
+    stats.reused_hyperplane_orbit_count
+}
+
+/// Returns the centers of the faces of a cube, at some distance from its
+/// center.
+fn face_centers(cube: &Concrete, distance: f64) -> Vec<Point<f64>> {
+    (0..cube.el_count(3))
+        .map(|idx| {
+            let center = cube.element(3, idx).unwrap().gravicenter().unwrap();
+            let norm = center.norm();
+            center * (distance / norm)
+        })
+        .collect()
+}
+
+/// Extending the tables of the cube to its face centers gives the same
+/// tables as computing them from scratch. Every hyperplane through vertices
+/// of the cube goes through one of its face centers, so none of their facets
+/// can be reused, but this isn't the case once they're pushed out.
+#[test]
+fn extend_with_vertices() {
+    let cube = Concrete::cube();
+    assert_eq!(check_extension(&cube, &face_centers(&cube, 0.5)), 0);
+    assert_eq!(check_extension(&cube, &face_centers(&cube, 1.0)), 2);
+}
+
+/// Facetings that are known uniform polytopes are labeled as such.
+#[test]
+fn uniform_labels() {
+    let mut poly = Concrete::from_off(include_str!("fixtures/ico.off")).unwrap();
+    let vertices = poly.vertices.to_vec();
+    let facetings = poly.faceting(vertices, GroupEnum::Chiral(false), &Default::default());
+
+    let labels: Vec<_> = facetings
+        .iter()
+        .filter_map(|faceting| faceting.polytope.meta.properties.get("uniform"))
+        .collect();
+    assert!(labels.contains(&&"Icositetrachoron".to_string()));
+}
+
+/// The facet orbits of some facetings.
+type FacetLists = Vec<Vec<(usize, usize)>>;
+
+/// Returns the facets of the facetings of a polytope found by going through
+/// the hyperplane orbits, and by going through the ridge orbits.
+fn facet_both_ways(poly: &Concrete, chiral: bool, options: &FacetingOptions) -> (FacetLists, FacetLists) {
+    let mut poly = poly.clone();
+    let vertices = poly.vertices.to_vec();
+    let tables = poly
+        .faceting_tables(vertices, GroupEnum::Chiral(chiral), options)
+        .unwrap();
+
+    let facets = |results: Vec<FacetingResult>| {
+        let mut facets: Vec<_> = results.into_iter().map(|result| result.facets).collect();
+        facets.dedup();
+        facets
+    };
+    (
+        facets(tables.complete(&[], options).unwrap()),
+        facets(tables.enumerate_ridge_first(options)),
+    )
+}
+
+/// Going through the ridge orbits finds the same facetings as going through
+/// the hyperplane orbits.
+#[test]
+fn ridge_first() {
+    let noble = |noble| FacetingOptions {
+        noble: Some(noble),
+        ..Default::default()
+    };
+    let cases = vec![
+        (Concrete::simplex(4), false, FacetingOptions::default()),
+        (Concrete::hypercube(4), false, FacetingOptions::default()),
+        (triangular_prism(), false, FacetingOptions::default()),
+        (
+            triangular_prism(),
+            false,
+            FacetingOptions {
+                include_compounds: true,
+                ..Default::default()
+            },
+        ),
+        (Concrete::from_off(include_str!("fixtures/ico.off")).unwrap(), false, FacetingOptions::default()),
+        (Concrete::from_off(include_str!("fixtures/toe.off")).unwrap(), true, noble(2)),
+        (Concrete::from_off(include_str!("fixtures/snic.off")).unwrap(), true, noble(2)),
+    ];
+
+    for (poly, chiral, options) in cases {
+        let (facet_first, ridge_first) = facet_both_ways(&poly, chiral, &options);
+        assert!(!facet_first.is_empty());
+        assert_eq!(ridge_first, facet_first);
+    }
+}
+
+/// The cross-check finds the same facetings with both of its configurations,
+/// and these are the ones the faceting finds.
+#[test]
+fn crosscheck() {
+    let noble = FacetingOptions {
+        facet_orbits: Some(FacetOrbitCount::AtMost(2)),
+        ..Default::default()
+    };
+    let cases = vec![
+        (triangular_prism(), false, FacetingOptions::default()),
+        (Concrete::from_off(include_str!("fixtures/ico.off")).unwrap(), false, FacetingOptions::default()),
+        (Concrete::from_off(include_str!("fixtures/toe.off")).unwrap(), true, noble),
+    ];
+
+    for (mut poly, chiral, options) in cases {
+        let results = match poly.faceting_crosscheck(GroupEnum::Chiral(chiral), &options).unwrap() {
+            Crosscheck::Verified(results) => results,
+            Crosscheck::Discrepancy(discrepancy) => panic!("unexpected discrepancy:\n{}", discrepancy),
+        };
+        let (facet_first, _) = facet_both_ways(&poly, chiral, &options);
+        let mut facets: Vec<_> = results.into_iter().map(|result| result.facets).collect();
+        facets.dedup();
+        assert_eq!(facets, facet_first);
+    }
+}
+
+/// Faceting the icosahedron under one of its pyritohedral subgroups finds its
+/// facetings with full symmetry, along with ones that only have pyritohedral
+/// symmetry.
+#[test]
+fn subgroup_faceting() {
+    let mut ike = Concrete::from_off(include_str!("fixtures/ike.off")).unwrap();
+    let vertices = ike.vertices.to_vec();
+    let (group, _) = ike.get_symmetry_group().unwrap();
+    let pyritohedral = group.subgroups_of_index(5);
+    assert_eq!(pyritohedral.len(), 5);
+
+    let (full, _) = ike.faceting_with_stats(vertices.clone(), GroupEnum::Chiral(false), &Default::default());
+    let (facetings, stats) = ike.faceting_with_stats(
+        vertices,
+        GroupEnum::ConcGroup(pyritohedral[0].clone()),
+        &Default::default(),
+    );
+    assert_eq!(stats.group_order, 24);
+    assert_eq!(full.len(), 4);
+    assert_eq!(facetings.len(), 29);
+
+    let counts = element_counts(&facetings);
+    for count in element_counts(&full) {
+        assert!(counts.contains(&count));
+    }
+}
+
+/// The rotations found among the subgroups of index 2 of the symmetry group of
+/// the icosahedron give the same facetings as its rotation group.
+#[test]
+fn index_two_faceting() {
+    let mut ike = Concrete::from_off(include_str!("fixtures/ike.off")).unwrap();
+    let vertices = ike.vertices.to_vec();
+    let (group, _) = ike.get_symmetry_group().unwrap();
+    let mut halves = group.index_two_subgroups();
+    assert_eq!(halves.len(), 1);
+    let (rotations, kind) = halves.swap_remove(0);
+    assert_eq!(kind, SubgroupKind::Rotation);
+
+    let (expected, expected_stats) =
+        ike.faceting_with_stats(vertices.clone(), GroupEnum::Chiral(true), &Default::default());
+    let (facetings, stats) = ike.faceting_with_stats(vertices, GroupEnum::ConcGroup(rotations), &Default::default());
+    assert_eq!(stats.group_order, 60);
+    assert_eq!(stats.hyperplane_orbit_count, expected_stats.hyperplane_orbit_count);
+
+    let mut counts = element_counts(&facetings);
+    counts.sort();
+    let mut expected_counts = element_counts(&expected);
+    expected_counts.sort();
+    assert_eq!(counts, expected_counts);
+}
+
+/// Returns the icosahedron with a vertex pushed out by a bit more than ten
+/// times the default tolerance, which only keeps the symmetries fixing it at
+/// that tolerance. No hyperplane is more than 0.8 times the circumradius away
+/// from the center, so the vertex still stays within it of the hyperplanes
+/// it was on.
+fn perturbed_ike() -> Concrete {
+    let mut ike = Concrete::from_off(include_str!("fixtures/ike.off")).unwrap();
+    let vertex = ike.vertices[0].clone();
+    ike.vertices[0] = &vertex * (1.0 + 12.0 * f64::EPS / vertex.norm());
+    ike
+}
+
+/// The hyperplane orbits of the perturbed icosahedron are split by its
+/// missing symmetries, which are found again at a looser tolerance. Merging
+/// the orbits gives back the facetings of the icosahedron.
+#[test]
+fn split_orbits() {
+    use miratope_core::conc::faceting::merge::MERGE_TOLERANCE_FACTOR;
+
+    let options = FacetingOptions {
+        geometry: GeomContext::with_tolerance(10.0 * f64::EPS),
+        detect_split_orbits: true,
+        ..Default::default()
+    };
+
+    let mut ike = Concrete::from_off(include_str!("fixtures/ike.off")).unwrap();
+    let vertices = ike.vertices.to_vec();
+    assert!(ike.split_hyperplane_orbits(vertices.clone(), GroupEnum::Chiral(false), &options).unwrap().is_empty());
+    let (expected, expected_stats) = ike.faceting_with_stats(vertices, GroupEnum::Chiral(false), &options);
+    assert_eq!(expected_stats.merge_candidate_count, 0);
+
+    let mut perturbed = perturbed_ike();
+    let vertices = perturbed.vertices.to_vec();
+    let candidates = perturbed.split_hyperplane_orbits(vertices.clone(), GroupEnum::Chiral(false), &options).unwrap();
+    let (split, stats) = perturbed.faceting_with_stats(vertices.clone(), GroupEnum::Chiral(false), &options);
+    assert_eq!(stats.group_order, 10);
+    assert_eq!(stats.hyperplane_orbit_count, 15);
+    assert_eq!(stats.merge_candidate_count, candidates.len());
+    assert_eq!(candidates.len(), stats.hyperplane_orbit_count - expected_stats.hyperplane_orbit_count);
+    assert!(split.len() > expected.len());
+    for candidate in &candidates {
+        assert!(candidate.first < candidate.second);
+        let tolerance = MERGE_TOLERANCE_FACTOR * options.tolerance();
+        assert!(candidate.miss > f64::EPS && candidate.miss < tolerance);
+        assert!(candidate.plane_distance < tolerance);
+        assert!(candidate.depth_difference < tolerance);
+    }
+
+    let options = FacetingOptions {
+        auto_merge: true,
+        ..options
+    };
+    let (merged, merged_stats) = perturbed.faceting_with_stats(vertices, GroupEnum::Chiral(false), &options);
+    assert_eq!(merged_stats.group_order, 120);
+    assert_eq!(merged_stats.merged_orbit_count, candidates.len());
+    assert_eq!(merged_stats.hyperplane_orbit_count, expected_stats.hyperplane_orbit_count);
+    assert_eq!(merged_stats.hyperplane_count, expected_stats.hyperplane_count);
+
+    let mut counts = element_counts(&merged);
+    counts.sort();
+    let mut expected_counts = element_counts(&expected);
+    expected_counts.sort();
+    assert_eq!(counts, expected_counts);
+}
+
+/// Rejecting the candidates with a facet close to the center prunes the
+/// search, and finds the facetings with no such facets.
+#[test]
+fn candidate_callback() {
+    let poly = Concrete::from_off(include_str!("fixtures/snic.off")).unwrap();
+    let dim = poly.dim().unwrap();
+    let min_depth = 0.5 * poly.vertices[0].norm();
+    let (all, all_stats) = facet_with_callback(&poly, None);
+
+    let callback = CandidateCallback::new(move |candidate| {
+        let origin = Point::zeros(dim);
+        if candidate.hyperplanes().iter().any(|hp| hp.distance(&origin) < min_depth) {
+            CandidateDecision::RejectSubtree
+        } else {
+            CandidateDecision::Accept
+        }
+    });
+    let (found, stats) = facet_with_callback(&poly, Some(callback));
+
+    assert!(stats.candidates_examined < all_stats.candidates_examined);
+    assert!(!found.is_empty() && found.len() < all.len());
+    for faceting in &found {
+        let rank = faceting.rank();
+        for idx in 0..faceting.el_count(rank - 1) {
+            let vertices = faceting.element_vertices_ref(rank - 1, idx).unwrap();
+            let hyperplane = Subspace::from_points(vertices.into_iter());
+            assert!(hyperplane.distance(&Point::zeros(dim)) > min_depth - 1e-6);
+        }
+    }
+}
+
+/// Returns the facetings of a polytope with at most two facet orbits and the
+/// stats, with a candidate callback. The faceting runs on another thread,
+/// which the options can be sent to.
+fn facet_with_callback(poly: &Concrete, candidate_callback: Option<CandidateCallback>) -> (Vec<Concrete>, FacetingStats) {
+    let mut poly = poly.clone();
+    let options = FacetingOptions {
+        facet_orbits: Some(FacetOrbitCount::AtMost(2)),
+        candidate_callback,
+        ..Default::default()
+    };
+    std::thread::spawn(move || {
+        let vertices = poly.vertices.to_vec();
+        let (facetings, stats) = poly.faceting_with_stats(vertices, GroupEnum::Chiral(false), &options);
+        (facetings.into_iter().map(|faceting| faceting.polytope).collect(), stats)
+    })
+    .join()
+    .unwrap()
+}
+
+/// Rejecting every faceting, but not the candidates leading to them, finds
+/// nothing after looking at every candidate.
+#[test]
+fn candidate_callback_reject() {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    let poly = Concrete::hypercube(4);
+    let (_, all_stats) = facet_with_callback(&poly, None);
+
+    let complete = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&complete);
+    let callback = CandidateCallback::new(move |candidate| {
+        if candidate.complete {
+            counter.fetch_add(1, Ordering::Relaxed);
+            CandidateDecision::Reject
+        } else {
+            CandidateDecision::Accept
+        }
+    });
+    let (found, stats) = facet_with_callback(&poly, Some(callback));
+
+    assert!(found.is_empty());
+    assert_eq!(stats.candidates_examined, all_stats.candidates_examined);
+    assert!(complete.load(Ordering::Relaxed) >= 2);
+}
+
+/// Returns the facet orbits of the facetings with the highest scores, best
+/// first, breaking ties by the facet orbits.
+fn best_facets(results: &[FacetingResult], k: usize, score: fn(&FacetingResult) -> f64) -> FacetLists {
+    let mut scored: Vec<_> = results.iter().map(|result| (score(result), result.facets.clone())).collect();
+    scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap().then_with(|| a.1.cmp(&b.1)));
+    scored.into_iter().take(k).map(|(_, facets)| facets).collect()
+}
+
+/// Keeping the best facetings by an objective finds the same ones as sorting
+/// all of them by it, with or without a bound, and going through either the
+/// hyperplane or the ridge orbits.
+#[test]
+fn objective() {
+    let mut poly = Concrete::from_off(include_str!("fixtures/ico.off")).unwrap();
+    let vertices = poly.vertices.to_vec();
+    let tables = poly
+        .faceting_tables(vertices, GroupEnum::Chiral(false), &Default::default())
+        .unwrap();
+    let all = tables.complete(&[], &Default::default()).unwrap();
+    assert!(all.len() > 3);
+
+    let facet_count = |result: &FacetingResult| {
+        let rank = result.polytope.rank();
+        result.polytope.el_count(rank - 1) as f64
+    };
+    let fewest_orbits = |result: &FacetingResult| -(result.facets.len() as f64);
+    let facets = |results: Vec<FacetingResult>| -> FacetLists { results.into_iter().map(|result| result.facets).collect() };
+
+    for &score in &[facet_count as fn(&FacetingResult) -> f64, fewest_orbits] {
+        let options = FacetingOptions {
+            objective: Some(FacetingObjective::new(3, score)),
+            ..Default::default()
+        };
+        let expected = best_facets(&all, 3, score);
+        assert_eq!(facets(tables.complete(&[], &options).unwrap()), expected);
+        assert_eq!(facets(tables.enumerate_ridge_first(&options)), expected);
+    }
+
+    // Facets are only ever added to a candidate, so the number of facet orbits can only go up.
+    let noble = FacetingOptions {
+        facet_orbits: Some(FacetOrbitCount::AtMost(2)),
+        ..Default::default()
+    };
+    let bounded = FacetingOptions {
+        objective: Some(
+            FacetingObjective::new(2, fewest_orbits).with_bound(|candidate| -(candidate.facets.len() as f64)),
+        ),
+        ..noble.clone()
+    };
+    let mut poly = Concrete::from_off(include_str!("fixtures/snic.off")).unwrap();
+    let vertices = poly.vertices.to_vec();
+    let (_, all_stats) = poly.faceting_with_stats(vertices.clone(), GroupEnum::Chiral(false), &noble);
+    let (facetings, stats) = poly.faceting_with_stats(vertices.clone(), GroupEnum::Chiral(false), &bounded);
+    assert!(stats.bound_pruned > 0);
+    assert!(stats.scored_count < all_stats.faceting_count);
+    assert_eq!(facetings.len(), 2);
+
+    let tables = poly.faceting_tables(vertices, GroupEnum::Chiral(false), &noble).unwrap();
+    let all = tables.complete(&[], &noble).unwrap();
+    assert_eq!(facets(tables.complete(&[], &bounded).unwrap()), best_facets(&all, 2, fewest_orbits));
+}
+
+/// A cube whose coordinates are slightly off has a trivial symmetry group,
+/// which stops the faceting unless allowed.
+#[test]
+fn trivial_group() {
+    let mut cube = Concrete::hypercube(4);
+    for (x, offset) in cube.vertices[0].iter_mut().zip(&[1e-4, 2e-4, 3e-4]) {
+        *x += offset;
+    }
+    let vertices = cube.vertices.to_vec();
+
+    match cube.try_faceting_with_stats(vertices.clone(), GroupEnum::Chiral(false), &Default::default()) {
+        Err(FacetingError::TrivialGroup { group_order, automorphism_bound, residual }) => {
+            assert_eq!(group_order, 1);
+            assert_eq!(automorphism_bound, 48);
+            assert!(residual > 0.0);
+        }
+        Err(err) => panic!("unexpected error: {}", err),
+        Ok(_) => panic!("faceting should have been aborted"),
+    }
+
+    let options = FacetingOptions {
+        allow_trivial_group: true,
+        noble: Some(1),
+        ..Default::default()
+    };
+    let (_, stats) = cube.try_faceting_with_stats(vertices, GroupEnum::Chiral(false), &options).unwrap();
+    assert_eq!(stats.group_order, 1);
+
+    // A group that's too small compared to the bound also stops it.
+    let mut cube = Concrete::hypercube(4);
+    let vertices = cube.vertices.to_vec();
+    let fraction = |fraction| FacetingOptions {
+        min_group_fraction: Some(fraction),
+        ..Default::default()
+    };
+    assert!(cube.try_faceting_with_stats(vertices.clone(), GroupEnum::Chiral(true), &fraction(1.0)).is_ok());
+    assert!(matches!(
+        cube.try_faceting_with_stats(vertices, GroupEnum::Chiral(false), &fraction(1.5)),
+        Err(FacetingError::TrivialGroup { group_order: 48, automorphism_bound: 48, .. })
+    ));
+}
+
+/// A geometry context lets the symmetry detection and the faceting share a
+/// looser tolerance and recenter the polytope.
+#[test]
+fn geometry_context() {
+    let mut cube = Concrete::hypercube(4);
+    for (x, offset) in cube.vertices[0].iter_mut().zip(&[1e-4, 2e-4, 3e-4]) {
+        *x += offset;
+    }
+    let vertices = cube.vertices.to_vec();
+    let options = FacetingOptions::with_geometry(GeomContext::with_tolerance(1e-3));
+    let (facetings, stats) = cube
+        .try_faceting_with_stats(vertices, GroupEnum::Chiral(false), &options)
+        .unwrap();
+    assert_eq!(stats.group_order, 48);
+    assert_eq!(facetings.len(), 2);
+
+    #[allow(deprecated)]
+    let shim = FacetingOptions::with_tolerance(1e-3);
+    assert_eq!(shim.geometry, options.geometry);
+    assert_eq!(shim.tolerance(), 1e-3);
+
+    let mut moved = Concrete::hypercube(4);
+    for vertex in moved.vertices.iter_mut() {
+        vertex[0] += 2.0;
+    }
+    let vertices = moved.vertices.to_vec();
+    let options = FacetingOptions::with_geometry(GeomContext {
+        center: CenterPolicy::Gravicenter,
+        ..Default::default()
+    });
+    let (facetings, stats) = moved
+        .try_faceting_with_stats(vertices, GroupEnum::Chiral(false), &options)
+        .unwrap();
+    assert_eq!(stats.group_order, 48);
+    assert_eq!(facetings.len(), 2);
+}
+
+/// The tables of the 24-cell give the same facetings after saving and loading
+/// them, and damaged files or files for other vertices are refused.
+#[test]
+fn binary_cache() {
+    let mut poly = Concrete::from_off(include_str!("fixtures/ico.off")).unwrap();
+    let vertices = poly.vertices.to_vec();
+    let options = FacetingOptions::default();
+    let tables = poly
+        .faceting_tables(vertices.clone(), GroupEnum::Chiral(false), &options)
+        .unwrap();
+
+    let mut bytes = Vec::new();
+    tables.save_binary(&mut bytes).unwrap();
+    let loaded = FacetingTables::load_binary(bytes.as_slice(), &vertices).unwrap();
+
+    assert_eq!(loaded.hyperplane_orbit_count(), tables.hyperplane_orbit_count());
+    for hp in 0..tables.hyperplane_orbit_count() {
+        assert_eq!(loaded.depth(hp), tables.depth(hp));
+        assert_eq!(loaded.facet_count(hp), tables.facet_count(hp));
+        assert_eq!(loaded.hyperplane_count(hp), tables.hyperplane_count(hp));
+    }
+    let results = |tables: &FacetingTables| -> Vec<_> {
+        tables
+            .complete(&[], &options)
+            .unwrap()
+            .into_iter()
+            .map(|result| (result.facets, result.polytope.meta.name))
+            .collect()
+    };
+    assert_eq!(results(&loaded), results(&tables));
+
+    // Cutting the file anywhere gives an error.
+    let step = bytes.len() / 200 + 1;
+    for len in (0..64).chain((64..bytes.len()).step_by(step)) {
+        assert!(
+            FacetingTables::load_binary(&bytes[..len], &vertices).is_err(),
+            "prefix of length {} was loaded",
+            len
+        );
+    }
+    assert!(matches!(
+        FacetingTables::load_binary(&bytes[..bytes.len() - 1], &vertices),
+        Err(CacheError::Truncated)
+    ));
+
+    let mut damaged = bytes.clone();
+    let middle = damaged.len() / 2;
+    damaged[middle] ^= 1;
+    assert!(matches!(
+        FacetingTables::load_binary(damaged.as_slice(), &vertices),
+        Err(CacheError::Corrupted(_))
+    ));
+
+    let mut newer = bytes.clone();
+    newer[8] += 1;
+    assert!(matches!(
+        FacetingTables::load_binary(newer.as_slice(), &vertices),
+        Err(CacheError::UnsupportedVersion { major: 2, minor: MINOR_VERSION })
+    ));
+
+    let mut other = vertices.clone();
+    other.swap(0, 1);
+    assert!(matches!(
+        FacetingTables::load_binary(bytes.as_slice(), &other),
+        Err(CacheError::ArrangementMismatch { .. })
+    ));
+    assert!(matches!(
+        FacetingTables::load_binary(&b"not a cache"[..], &vertices),
+        Err(CacheError::NotACache)
+    ));
+}
+
+/// Stopping after the facet types of the icosahedron gives the same tables the
+/// full run builds, without combining anything, and stopping after the
+/// hyperplanes gives their orbits.
+#[test]
+fn stop_after() {
+    let mut poly = Concrete::from_off(include_str!("fixtures/ike.off")).unwrap();
+    let vertices = poly.vertices.to_vec();
+    let staged = |poly: &mut Concrete, stop_after| {
+        let options = FacetingOptions {
+            stop_after,
+            ..Default::default()
+        };
+        poly.try_faceting_staged(vertices.clone(), GroupEnum::Chiral(false), &options)
+            .unwrap()
+    };
+
+    let (full, full_stats) = staged(&mut poly, Stage::Full);
+    let facetings = match full {
+        PartialFacetingOutput::Full(facetings) => facetings,
+        output => panic!("unexpected output: {:?}", output),
+    };
+    assert_eq!(facetings.len(), full_stats.faceting_count);
+    assert!(full_stats.faceting_count > 0);
+
+    let (output, stats) = staged(&mut poly, Stage::FacetTypes);
+    let tables = match output {
+        PartialFacetingOutput::FacetTypes(mut tables) if tables.len() == 1 => tables.remove(0),
+        output => panic!("unexpected output: {:?}", output),
+    };
+    assert_eq!(stats.faceting_count, 0);
+    assert_eq!(stats.combine_time + stats.build_time, Default::default());
+    assert_eq!(stats.possible_facet_count, full_stats.possible_facet_count);
+
+    let full_tables = poly
+        .faceting_tables(vertices.clone(), GroupEnum::Chiral(false), &Default::default())
+        .unwrap();
+    assert_eq!(tables.hyperplane_orbit_count(), full_tables.hyperplane_orbit_count());
+    for hp in 0..tables.hyperplane_orbit_count() {
+        assert_eq!(tables.facet_count(hp), full_tables.facet_count(hp));
+        assert_eq!(tables.hyperplane_count(hp), full_tables.hyperplane_count(hp));
+        assert_eq!(tables.depth(hp), full_tables.depth(hp));
+    }
+    let facets = |tables: &FacetingTables| -> Vec<_> {
+        let results = tables.complete(&[], &Default::default()).unwrap();
+        results.into_iter().map(|result| result.facets).collect()
+    };
+    assert_eq!(facets(&tables), facets(&full_tables));
+    assert_eq!(facets(&tables).len(), facetings.len());
+
+    let (output, stats) = staged(&mut poly, Stage::Hyperplanes);
+    let orbits = match output {
+        PartialFacetingOutput::Hyperplanes(mut orbits) if orbits.len() == 1 => orbits.remove(0),
+        output => panic!("unexpected output: {:?}", output),
+    };
+    assert_eq!(stats.possible_facet_count, 0);
+    assert_eq!(orbits.len(), tables.hyperplane_orbit_count());
+    for (hp, orbit) in orbits.iter().enumerate() {
+        assert_eq!(orbit.size, tables.hyperplane_count(hp));
+        assert_eq!(orbit.depth, tables.depth(hp));
+    }
+
+    // The plain faceting returns nothing when stopping early.
+    let options = FacetingOptions {
+        stop_after: Stage::FacetTypes,
+        ..Default::default()
+    };
+    assert!(poly.faceting(vertices, GroupEnum::Chiral(false), &options).is_empty());
+}
+
+/// The conditioning of the vertices is only checked when asked for.
+#[test]
+fn conditioning() {
+    let mut poly = Concrete::hypercube(4);
+    let vertices = poly.vertices.to_vec();
+    let (_, stats) = poly.faceting_with_stats(vertices.clone(), GroupEnum::Chiral(false), &Default::default());
+    assert_eq!(stats.conditioning, None);
+
+    let options = FacetingOptions {
+        check_conditioning: true,
+        ..Default::default()
+    };
+    let (facetings, stats) = poly.faceting_with_stats(vertices, GroupEnum::Chiral(false), &options);
+    assert_eq!(stats.conditioning, Some(Conditioning::Good));
+    assert_eq!(facetings.len(), 2);
+}
+
+/// Returns whether the poles of the facet hyperplanes of a polytope are
+/// exactly its vertices, with respect to the sphere about the origin with some
+/// squared radius.
+fn is_self_polar(poly: &Concrete, squared_radius: f64) -> bool {
+    let facet_rank = poly.rank() - 1;
+    let mut poles = Vec::new();
+    for idx in 0..poly.el_count(facet_rank) {
+        let foot = poly.affine_hull(facet_rank, idx).project(&Point::zeros(poly.dim_or()));
+        let depth_sq = foot.norm_squared();
+        if depth_sq < 1e-12 {
+            return false;
+        }
+        poles.push(foot * (squared_radius / depth_sq));
+    }
+
+    let matches = |a: &[Point<f64>], b: &[Point<f64>]| a.iter().all(|p| b.iter().any(|q| (p - q).norm() < 1e-6));
+    matches(&poles, &poly.vertices) && matches(&poly.vertices, &poles)
+}
+
+/// The 24-cell has two self-polar facetings, with respect to the sphere
+/// through the poles of its second hyperplane orbit. The search finds the same
+/// ones as filtering every faceting afterwards.
+#[test]
+fn self_polar() {
+    let mut poly = Concrete::from_off(include_str!("fixtures/ico.off")).unwrap();
+    let vertices = poly.vertices.to_vec();
+    let tables = poly
+        .faceting_tables(vertices.clone(), GroupEnum::Chiral(false), &Default::default())
+        .unwrap();
+    assert_eq!(tables.vertex_orbit_indices(), vec![0; 24]);
+    assert_eq!(tables.depth(1), 0.5);
+    let pairing = tables.polar_pairing(0.5);
+    assert_eq!(pairing[1], Some(0));
+    assert_eq!(pairing.iter().filter(|pair| pair.is_some()).count(), 1);
+
+    let all = poly.faceting(vertices.clone(), GroupEnum::Chiral(false), &Default::default());
+    let filtered: Vec<_> = all.into_iter().filter(|faceting| is_self_polar(&faceting.polytope, 0.5)).collect();
+    let mut expected = element_counts(&filtered);
+    expected.sort();
+
+    let options = FacetingOptions {
+        require_self_polar: Some(0.5),
+        ..Default::default()
+    };
+    let self_polar = poly.faceting(vertices, GroupEnum::Chiral(false), &options);
+    assert!(self_polar.iter().all(|faceting| is_self_polar(&faceting.polytope, 0.5)));
+    let mut counts = element_counts(&self_polar);
+    counts.sort();
+    assert_eq!(counts, vec![vec![1, 24, 72, 96, 24, 1], vec![1, 24, 96, 72, 24, 1]]);
+    assert_eq!(counts, expected);
+}
+
+/// The rotation group of the great rhombicuboctahedron splits its vertices
+/// into two orbits, which are mirror images. Every noble faceting found under
+/// it is still closed under the rotations.
+#[test]
+fn chiral_split_orbits() {
+    let mut cube = Concrete::hypercube(4);
+    cube.element_sort();
+    let mut girco = cube.truncate_with(vec![0, 1, 2], vec![1.0, std::f64::consts::SQRT_2, 1.0]);
+    let vertices = girco.vertices.to_vec();
+    let options = FacetingOptions {
+        noble: Some(1),
+        ..Default::default()
+    };
+    let (facetings, stats) = girco.faceting_with_stats(vertices.clone(), GroupEnum::Chiral(true), &options);
+    assert_eq!(stats.group_order, 24);
+    assert_eq!(stats.vertex_orbit_count, 2);
+    assert!(!facetings.is_empty());
+
+    let rotations = girco.get_rotation_group().unwrap().1;
+    for FacetingResult { polytope: faceting, .. } in &facetings {
+        // The vertices of each facet, as indices into the original vertices.
+        let original = |v: usize| {
+            vertices
+                .iter()
+                .position(|w| (w - &faceting.vertices[v]).norm() < 1e-9)
+                .unwrap()
+        };
+        let facets: HashSet<Vec<usize>> = (0..faceting.el_count(3))
+            .map(|idx| {
+                let mut facet: Vec<_> = faceting.abs.element_vertices(3, idx).unwrap().into_iter().map(original).collect();
+                facet.sort_unstable();
+                facet
+            })
+            .collect();
+
+        for row in &rotations {
+            for facet in &facets {
+                let mut image: Vec<_> = facet.iter().map(|&v| row[v]).collect();
+                image.sort_unstable();
+                assert!(facets.contains(&image), "{:?} isn't closed under the rotations", faceting.meta.name);
+            }
+        }
+    }
+}
+
+/// The facets of the cube are never degenerate, but they all are when the
+/// tolerance is larger than the cube: flagging keeps them, and excluding them
+/// leaves nothing to combine.
+#[test]
+fn degenerate_facets() {
+    let cube = Concrete::hypercube(4);
+    let facetings = |degenerate_facets, degeneracy_tolerance| {
+        let mut poly = cube.clone();
+        let options = FacetingOptions {
+            degenerate_facets,
+            degeneracy_tolerance,
+            ..Default::default()
+        };
+        poly.faceting_with_stats(cube.vertices.to_vec(), GroupEnum::Chiral(false), &options)
+    };
+
+    let (kept, kept_stats) = facetings(DegenerateFacets::Keep, 10.0);
+    let (flagged, flagged_stats) = facetings(DegenerateFacets::Flag, FacetingOptions::default().degeneracy_tolerance);
+    assert_eq!(kept_stats.degenerate_facet_count, 0);
+    assert_eq!(flagged_stats.degenerate_facet_count, 0);
+    assert_eq!(flagged.len(), kept.len());
+
+    let (flagged, flagged_stats) = facetings(DegenerateFacets::Flag, 10.0);
+    assert_eq!(flagged.len(), kept.len());
+    assert_eq!(flagged_stats.degenerate_facet_count, flagged_stats.possible_facet_count);
+
+    let (excluded, excluded_stats) = facetings(DegenerateFacets::Exclude, 10.0);
+    assert!(excluded.is_empty());
+    assert_eq!(excluded_stats.degenerate_facet_count, kept_stats.possible_facet_count);
+    assert_eq!(excluded_stats.possible_facet_count, 0);
+
+    let mut poly = cube.clone();
+    let options = FacetingOptions {
+        degenerate_facets: DegenerateFacets::Flag,
+        degeneracy_tolerance: 10.0,
+        ..Default::default()
+    };
+    let tables = poly.faceting_tables(cube.vertices.to_vec(), GroupEnum::Chiral(false), &options).unwrap();
+    assert!(tables.is_degenerate(0, 0));
+}
+
+/// A cube whose coordinates have drifted by more than the default tolerance
+/// only has its facetings found again with a looser one.
+#[test]
+fn tolerance() {
+    let mut cube = Concrete::hypercube(4);
+    let vertex_map = cube.get_symmetry_group().unwrap().1;
+    let vertices: Vec<_> = cube
+        .vertices
+        .iter()
+        .enumerate()
+        .map(|(idx, v)| v.map(|x| x + 1e-6 * (idx % 3) as f64))
+        .collect();
+
+    let facetings = |tolerance| {
+        let options = FacetingOptions::with_geometry(GeomContext::with_tolerance(tolerance));
+        let facetings = cube.clone().faceting(vertices.clone(), GroupEnum::VertexMap(vertex_map.clone().into()), &options);
+        let mut counts = element_counts(&facetings);
+        counts.sort();
+        counts
+    };
+
+    assert_ne!(facetings(FacetingOptions::default().tolerance()), facetings(1e-4));
+    assert_eq!(facetings(1e-4), vec![vec![1, 8, 12, 6, 1], vec![1, 8, 12, 8, 1]]);
+}
+
+/// Counting the facetings finds as many as building them, whether or not the
+/// compounds are included, and with noble facetings only.
+#[test]
+fn count_facetings() {
+    let cases = vec![
+        (Concrete::hypercube(4), FacetingOptions::default()),
+        (
+            triangular_prism(),
+            FacetingOptions {
+                include_compounds: true,
+                ..Default::default()
+            },
+        ),
+        (triangular_prism(), FacetingOptions::default()),
+        (
+            Concrete::from_off(include_str!("fixtures/ike.off")).unwrap(),
+            FacetingOptions {
+                noble: Some(1),
+                ..Default::default()
+            },
+        ),
+    ];
+
+    for (mut poly, options) in cases {
+        let vertices = poly.vertices.to_vec();
+        let facetings = poly.faceting(vertices.clone(), GroupEnum::Chiral(false), &options);
+        let (count, stats) = poly.count_facetings(vertices, GroupEnum::Chiral(false), &options).unwrap();
+
+        assert_eq!(count.total, facetings.len());
+        assert_eq!(stats.faceting_count, facetings.len());
+        assert_eq!(stats.build_time, Default::default());
+        assert_eq!(count.signatures, facetings.iter().map(|faceting| faceting.facets.clone()).collect::<Vec<_>>());
+
+        let mut by_facet_orbits = BTreeMap::new();
+        for faceting in &facetings {
+            *by_facet_orbits.entry(faceting.facets.len()).or_insert(0) += 1;
+        }
+        assert_eq!(count.by_facet_orbits, by_facet_orbits);
+    }
+}
+
+/// Under the symmetries that change the signs of the coordinates, the three
+/// pairs of faces of a cube are in different orbits, but they're congruent
+/// and faceted the same way, so only the first pair is faceted.
+#[test]
+fn memoize_subdim() {
+    let cube = Concrete::hypercube(4);
+    let vertex_map: Vec<Vec<usize>> = (0..8)
+        .map(|signs: usize| {
+            cube.vertices
+                .iter()
+                .map(|v| {
+                    let image = Point::from_iterator(3, v.iter().enumerate().map(|(i, x)| if signs >> i & 1 == 1 { -x } else { *x }));
+                    cube.vertices.iter().position(|w| (w - &image).norm() < 1e-9).unwrap()
+                })
+                .collect()
+        })
+        .collect();
+
+    let facetings = |memoize_subdim| {
+        let options = FacetingOptions {
+            memoize_subdim,
+            ..Default::default()
+        };
+        let (facetings, stats) =
+            cube.clone().faceting_with_stats(cube.vertices.to_vec(), GroupEnum::VertexMap(vertex_map.clone().into()), &options);
+        let mut counts = element_counts(&facetings);
+        counts.sort();
+        (counts, stats)
+    };
+
+    let (memoized, memoized_stats) = facetings(true);
+    let (plain, plain_stats) = facetings(false);
+    assert_eq!(memoized, plain);
+    assert_eq!(memoized_stats.possible_facet_count, plain_stats.possible_facet_count);
+    assert_eq!(plain_stats.memoized_hyperplane_orbit_count, 0);
+    assert!(memoized_stats.memoized_hyperplane_orbit_count >= 2);
+}
+
+/// The vertex stars of the icosahedron include its own, made of five of its
+/// faces, and that of every faceting that isn't fissary.
+#[test]
+fn vertex_stars() {
+    let mut ike = Concrete::from_off(include_str!("fixtures/ike.off")).unwrap();
+    let tables = ike.faceting_tables(ike.vertices.to_vec(), GroupEnum::Chiral(false), &FacetingOptions::default()).unwrap();
+    let stars = tables.vertex_stars(0);
+    let facet_orbits: HashSet<Vec<(usize, usize)>> = stars
+        .iter()
+        .map(|star| star.facet_counts().into_keys().collect())
+        .collect();
+
+    // The faces of the icosahedron are on the deepest hyperplanes.
+    assert!(stars.iter().any(|star| star.facets == vec![(0, 0); 5]));
+    for star in &stars {
+        assert_eq!(star.polytope.el_count(star.polytope.rank() - 1), star.facets.len());
+    }
+
+    let facetings = ike.clone().faceting(ike.vertices.to_vec(), GroupEnum::Chiral(false), &FacetingOptions::default());
+    assert!(facetings.iter().filter(|faceting| !faceting.polytope.is_fissary()).count() > 1);
+    for faceting in facetings.iter().filter(|faceting| !faceting.polytope.is_fissary()) {
+        assert!(facet_orbits.contains(&faceting.facets), "{:?}", faceting.facets);
+    }
+}
+
+/// The facetings stop at the maximum number of results, and are only marked
+/// as truncated if there were more.
+#[test]
+fn max_results() {
+    let ike = Concrete::from_off(include_str!("fixtures/ike.off")).unwrap();
+    let run = |max_results| {
+        let options = FacetingOptions {
+            noble: Some(1),
+            max_results,
+            ..Default::default()
+        };
+        let (facetings, stats) = ike.clone().faceting_with_stats(ike.vertices.to_vec(), GroupEnum::Chiral(false), &options);
+        (facetings.len(), stats.truncated)
+    };
+
+    let (all, truncated) = run(None);
+    assert!(all > 1);
+    assert!(!truncated);
+    assert_eq!(run(Some(all)), (all, false));
+    assert_eq!(run(Some(all - 1)), (all - 1, true));
+    assert_eq!(run(Some(0)), (0, true));
+
+    // The iterator stops in the same way.
+    let options = FacetingOptions {
+        noble: Some(1),
+        max_results: Some(1),
+        ..Default::default()
+    };
+    let mut iter = ike.clone().faceting_iter(ike.vertices.to_vec(), GroupEnum::Chiral(false), &options).unwrap();
+    assert_eq!(iter.by_ref().count(), 1);
+    assert!(iter.stats().truncated);
+    assert!(iter.next().is_none());
+}
+
+/// The stella octangula is left out of the facetings of the cube as a
+/// compound, though its facets are a single orbit.
+#[test]
+fn exclude_compounds() {
+    let cube = Concrete::hypercube(4);
+    let (facetings, _) = cube.clone().faceting_with_stats(cube.vertices.to_vec(), GroupEnum::Chiral(false), &Default::default());
+    assert_eq!(facetings.iter().filter(|result| result.polytope.is_compound()).count(), 1);
+
+    let options = FacetingOptions {
+        exclude_compounds: true,
+        ..Default::default()
+    };
+    let (facetings, stats) = cube.clone().faceting_with_stats(cube.vertices.to_vec(), GroupEnum::Chiral(false), &options);
+    assert_eq!(element_counts(&facetings), vec![vec![1, 8, 12, 6, 1]]);
+    assert_eq!(stats.faceting_count, 1);
+    assert_eq!(stats.excluded_compound_count, 1);
+
+    // The iterator leaves it out in the same way.
+    let mut iter = cube.clone().faceting_iter(cube.vertices.to_vec(), GroupEnum::Chiral(false), &options).unwrap();
+    assert_eq!(iter.by_ref().count(), 1);
+    assert_eq!(iter.stats().excluded_compound_count, 1);
+}
+
+/// The faces of the dodecahedron form the graph of an icosahedron, and those
+/// of the stella octangula split into the two tetrahedra.
+#[test]
+fn facet_adjacency() {
+    let doe = Concrete::from_off(include_str!("fixtures/ike.off"))
+        .unwrap()
+        .try_dual()
+        .unwrap();
+    let graph = doe.facet_adjacency();
+    assert_eq!(graph.len(), 12);
+    assert_eq!(graph.edge_count(), 30);
+    assert_eq!(graph.regular_degree(), Some(5));
+    assert_eq!(graph.diameter(), Some(3));
+
+    let mut cube = Concrete::hypercube(4);
+    let facetings = cube.faceting(cube.vertices.to_vec(), GroupEnum::Chiral(false), &Default::default());
+    let mut components: Vec<_> = facetings
+        .iter()
+        .map(|result| result.polytope.facet_adjacency().components().len())
+        .collect();
+    components.sort_unstable();
+    assert_eq!(components, vec![1, 2]);
+}
+
+/// The fissary facetings of the icositetrachoron are found by their sections,
+/// and can be left out.
+#[test]
+fn exclude_fissaries() {
+    let poly = Concrete::from_off(include_str!("fixtures/ico.off")).unwrap();
+    let (all, _) = poly.clone().faceting_with_stats(poly.vertices.to_vec(), GroupEnum::Chiral(false), &Default::default());
+    let fissary = all.iter().filter(|result| result.polytope.abs.is_fissary()).count();
+    assert_eq!(fissary, 5);
+    for result in &all {
+        assert_eq!(result.polytope.abs.is_fissary(), result.polytope.is_fissary(), "{:?}", result.facets);
+    }
+
+    let options = FacetingOptions {
+        exclude_fissaries: true,
+        ..Default::default()
+    };
+    let (kept, stats) = poly.clone().faceting_with_stats(poly.vertices.to_vec(), GroupEnum::Chiral(false), &options);
+    assert_eq!(kept.len(), all.len() - fissary);
+    assert_eq!(stats.excluded_fissary_count, fissary);
+    assert_eq!(stats.faceting_count, kept.len());
+    assert!(kept.iter().all(|result| !result.polytope.abs.is_fissary()));
+}
+
+/// Moving a vertex of the dodecahedron slightly splits the hyperplanes
+/// through it from the others in their orbits at a tight tolerance, which is
+/// reported, and retried at a looser one.
+#[test]
+fn tolerance_retry() {
+    let mut doe = Concrete::from_off(include_str!("fixtures/ike.off"))
+        .unwrap()
+        .try_dual()
+        .unwrap();
+    let vertex_map = doe.get_symmetry_group().unwrap().1;
+    let (_, exact) = doe
+        .faceting_tables_with_stats(
+            doe.vertices.to_vec(),
+            GroupEnum::VertexMap(vertex_map.clone().into()),
+            &Default::default(),
+        )
+        .unwrap();
+    assert_eq!(exact.hyperplane_inconsistency_count, 0);
+    assert_eq!(exact.retry_tolerance, None);
+
+    let mut vertices = doe.vertices.to_vec();
+    vertices[0] *= 1.0 + 1e-9;
+    let tight = FacetingOptions {
+        geometry: GeomContext::with_tolerance(1e-11),
+        ..Default::default()
+    };
+    let (_, stats) = doe
+        .faceting_tables_with_stats(
+            vertices.clone(),
+            GroupEnum::VertexMap(vertex_map.clone().into()),
+            &tight,
+        )
+        .unwrap();
+    assert!(stats.hyperplane_inconsistency_count > 0);
+    assert!(stats.first_hyperplane_inconsistency.is_some());
+    assert_eq!(stats.retry_tolerance, None);
+
+    let retry = FacetingOptions {
+        tolerance_retry: Some(vec![1e-10, 1e-7]),
+        ..tight
+    };
+    let (_, stats) = doe
+        .faceting_tables_with_stats(vertices, GroupEnum::VertexMap(vertex_map.into()), &retry)
+        .unwrap();
+    assert_eq!(stats.retry_tolerance, Some(1e-7));
+    assert_eq!(stats.hyperplane_inconsistency_count, 0);
+    assert_eq!(stats.hyperplane_orbit_count, exact.hyperplane_orbit_count);
+    assert_eq!(stats.hyperplane_count, exact.hyperplane_count);
+}
+
+/// Faceting into the hyperplanes found by the search gives the same
+/// facetings without searching, and only the given hyperplanes are used.
+#[test]
+fn given_hyperplanes() {
+    let mut ike = Concrete::from_off(include_str!("fixtures/ike.off")).unwrap();
+    let vertices = ike.vertices.to_vec();
+    let options = FacetingOptions {
+        stop_after: Stage::Hyperplanes,
+        ..Default::default()
+    };
+    let orbits = match ike.try_faceting_staged(vertices.clone(), GroupEnum::Chiral(false), &options) {
+        Ok((PartialFacetingOutput::Hyperplanes(mut orbits), _)) if orbits.len() == 1 => orbits.remove(0),
+        output => panic!("unexpected output: {:?}", output),
+    };
+    let (full, full_stats) = facet(ike.clone());
+
+    // Every orbit is given twice, once through a different hyperplane.
+    let mut hyperplanes: Vec<Vec<usize>> = orbits.iter().map(|orbit| orbit.vertices.iter().copied().collect()).collect();
+    let vertex_map = ike.get_symmetry_group().unwrap().1;
+    let images: Vec<_> = hyperplanes.iter().map(|list| list.iter().map(|&v| vertex_map[1][v]).collect()).collect();
+    hyperplanes.extend(images);
+
+    let options = FacetingOptions {
+        hyperplanes: Some(hyperplanes),
+        ..Default::default()
+    };
+    let (facetings, stats) = ike
+        .try_faceting_with_stats(vertices.clone(), GroupEnum::Chiral(false), &options)
+        .unwrap();
+    let mut counts = element_counts(&facetings);
+    counts.sort();
+    assert_eq!(counts, full);
+    assert_eq!(stats.hyperplane_orbit_count, full_stats.hyperplane_orbit_count);
+    assert_eq!(stats.hyperplane_count, full_stats.hyperplane_count);
+    assert_eq!(stats.estimated_tuples, 0);
+
+    // The planes of the faces only give back the icosahedron.
+    let options = FacetingOptions {
+        hyperplanes: Some(vec![ike.abs.element_vertices(3, 0).unwrap()]),
+        ..Default::default()
+    };
+    let facetings = ike.faceting(vertices, GroupEnum::Chiral(false), &options);
+    assert_eq!(element_counts(&facetings), vec![vec![1, 12, 30, 20, 1]]);
+}
+
+/// Vertices that don't exist or don't span a hyperplane are reported.
+#[test]
+fn invalid_hyperplanes() {
+    let mut cube = Concrete::hypercube(4);
+    let vertices = cube.vertices.to_vec();
+    let face = cube.abs.element_vertices(3, 0).unwrap();
+    let mut error = |hyperplanes| {
+        let options = FacetingOptions {
+            hyperplanes: Some(hyperplanes),
+            ..Default::default()
+        };
+        cube.faceting_tables_with_stats(vertices.clone(), GroupEnum::Chiral(false), &options)
+            .unwrap_err()
+    };
+
+    assert!(matches!(
+        error(vec![face.clone(), vec![0, 8]]),
+        FacetingError::HyperplaneVertexOutOfRange { hyperplane: 1, vertex: 8, vertex_count: 8 }
+    ));
+    assert!(matches!(
+        error(vec![vec![0, 1]]),
+        FacetingError::InvalidHyperplane { hyperplane: 0, rank: 1, expected: 2 }
+    ));
+    assert!(matches!(
+        error(vec![face.clone(), (0..8).collect()]),
+        FacetingError::InvalidHyperplane { hyperplane: 1, rank: 3, expected: 2 }
+    ));
+    assert!(matches!(
+        error(vec![Vec::new()]),
+        FacetingError::InvalidHyperplane { hyperplane: 0, rank: 0, expected: 2 }
+    ));
+}
+
+/// The facet filter only lets through the facetings made of the facets it
+/// allows, both when going through the hyperplane orbits and the ridge orbits.
+#[test]
+fn facet_filter() {
+    let prism = triangular_prism();
+    let filtered = |filter, noble| {
+        let options = FacetingOptions {
+            facet_filter: Some(filter),
+            noble,
+            ..Default::default()
+        };
+        let (facet_first, ridge_first) = facet_both_ways(&prism, false, &options);
+        assert_eq!(ridge_first, facet_first);
+        facet_first
+    };
+    let all = |noble| {
+        let options = FacetingOptions {
+            noble,
+            ..Default::default()
+        };
+        facet_both_ways(&prism, false, &options).0
+    };
+    let full = all(None);
+
+    // Allowing the facets of one faceting finds it again, and nothing with other facets.
+    let facets = full.iter().find(|facets| facets.len() > 1).unwrap();
+    let allowed: HashSet<_> = facets.iter().copied().collect();
+    let found = filtered(FacetFilter::Allow(allowed.clone()), None);
+    assert!(found.contains(facets));
+    assert!(found.iter().flatten().all(|facet| allowed.contains(facet)));
+
+    // Denying a facet leaves out exactly the facetings with it, also among the noble ones.
+    let denied = facets[0];
+    for &noble in &[None, Some(1)] {
+        let expected: Vec<_> = all(noble).into_iter().filter(|facets| !facets.contains(&denied)).collect();
+        assert_eq!(filtered(FacetFilter::Deny(vec![denied].into_iter().collect()), noble), expected);
+    }
+
+    // The predicate sees every facet, and can ask for the triangles only.
+    let triangles = filtered(FacetFilter::predicate(|_, _, facet| facet.vertex_count() == 3), None);
+    assert!(!triangles.is_empty());
+    assert!(triangles.len() < full.len());
+    assert!(filtered(FacetFilter::predicate(|_, _, _| false), None).is_empty());
+}
+
+/// The number of facets in each facet orbit divides the order of the group,
+/// for the facetings of some polytopes under their full and rotation groups,
+/// with and without compounds.
+#[test]
+fn orbit_stabilizer() {
+    let corpus = [
+        Concrete::hypercube(4),
+        Concrete::orthoplex(4),
+        Concrete::from_off(include_str!("fixtures/ike.off")).unwrap(),
+        Concrete::from_off(include_str!("fixtures/toe.off")).unwrap(),
+        Concrete::from_off(include_str!("fixtures/snic.off")).unwrap(),
+        Concrete::from_off(include_str!("fixtures/ico.off")).unwrap(),
+    ];
+
+    for poly in &corpus {
+        for (chiral, include_compounds) in [(false, false), (true, false), (false, true)] {
+            let mut poly = poly.clone();
+            let vertices = poly.vertices.to_vec();
+            let options = FacetingOptions {
+                facet_orbits: Some(FacetOrbitCount::AtMost(2)),
+                include_compounds,
+                ..Default::default()
+            };
+            let (facetings, stats) = poly.faceting_with_stats(vertices, GroupEnum::Chiral(chiral), &options);
+            assert!(!facetings.is_empty());
+            for faceting in &facetings {
+                assert_eq!(faceting.orbit_stabilizer_check(stats.group_order), Ok(()));
+            }
+        }
+    }
+
+    let prism = FacetingResult {
+        polytope: triangular_prism(),
+        facets: vec![(0, 0), (1, 0)],
+        facet_counts: vec![2, 3],
+        name: None,
+    };
+    assert_eq!(prism.orbit_stabilizer_check(12), Ok(()));
+    let violation = OrbitCountViolation {
+        facet: (1, 0),
+        facet_count: 3,
+        group_order: 8,
+    };
+    assert_eq!(prism.orbit_stabilizer_check(8), Err(violation));
+    assert_eq!(violation.to_string(), "facet orbit (1,0) has 3 facets, which doesn't divide the group order 8");
+}
+
+/// The noble facetings of the icosahedron are certified under their own
+/// symmetries, and a prism isn't noble, as its bases aren't related to its
+/// other faces.
+#[test]
+fn verify_noble() {
+    let mut ike = Concrete::from_off(include_str!("fixtures/ike.off")).unwrap();
+    let vertices = ike.vertices.to_vec();
+    let options = FacetingOptions {
+        noble: Some(1),
+        ..Default::default()
+    };
+    let facetings = ike.faceting(vertices, GroupEnum::Chiral(false), &options);
+    assert!(!facetings.is_empty());
+    for faceting in &facetings {
+        let certificate = faceting.verify_noble();
+        assert_eq!(
+            certificate,
+            NobleCertificate::Noble {
+                group_order: 120,
+                orbit_counts: vec![1, 1, 1]
+            }
+        );
+        assert_eq!(faceting.polytope.meta.properties["noble"], certificate.to_string());
+    }
+
+    let prism = FacetingResult {
+        polytope: triangular_prism(),
+        facets: vec![(0, 0), (1, 0)],
+        facet_counts: vec![2, 3],
+        name: None,
+    };
+    let certificate = prism.verify_noble();
+    assert!(!certificate.is_noble());
+    match certificate {
+        NobleCertificate::NotNoble { group_order, orbit_counts, rank, elements } => {
+            assert_eq!(group_order, 12);
+            assert_eq!(orbit_counts, vec![1, 2, 2]);
+            assert_eq!(rank, Rank::new(3));
+            let vertex_count = |idx| prism.polytope.abs.element_vertices(rank, idx).unwrap().len();
+            assert_ne!(vertex_count(elements.0), vertex_count(elements.1));
+        }
+        certificate => panic!("unexpected certificate: {:?}", certificate),
+    }
+}
+
+/// The 16-cell recovered from the distances between its vertices has the same
+/// facetings as the original.
+#[test]
+fn from_distances() {
+    let mut hex = Concrete::orthoplex(5);
+    let n = hex.vertices.len();
+    let distances = Matrix::from_fn(n, n, |i, j| (&hex.vertices[i] - &hex.vertices[j]).norm());
+    let mut embedded = Concrete::from_distance_matrix(&distances).unwrap();
+    assert_eq!(embedded.dim(), Some(4));
+    assert!(embedded.meta.properties["residual"].parse::<f64>().unwrap() < 1e-9);
+
+    let vertex_map = hex.get_symmetry_group().unwrap().1;
+    let options = FacetingOptions::default();
+    let mut expected = element_counts(&hex.faceting(hex.vertices.to_vec(), GroupEnum::Chiral(false), &options));
+    let vertices = embedded.vertices.to_vec();
+    let mut counts = element_counts(&embedded.faceting(vertices, GroupEnum::VertexMap(vertex_map.into()), &options));
+    expected.sort();
+    counts.sort();
+    assert!(!counts.is_empty());
+    assert_eq!(counts, expected);
+}
+
+/// Asking for exactly one facet orbit gives the noble facetings of the
+/// icosahedron, and asking for exactly two leaves out the facetings with one.
+#[test]
+fn exact_facet_orbits() {
+    let ike = Concrete::from_off(include_str!("fixtures/ike.off")).unwrap();
+    let facet_ike = |options: FacetingOptions| {
+        let mut ike = ike.clone();
+        let vertices = ike.vertices.to_vec();
+        ike.faceting(vertices, GroupEnum::Chiral(false), &options)
+    };
+
+    let exact = facet_ike(FacetingOptions {
+        facet_orbits: Some(FacetOrbitCount::Exact(1)),
+        ..Default::default()
+    });
+    assert!(exact.iter().all(|faceting| faceting.facets.len() == 1));
+    let mut counts = element_counts(&exact);
+    counts.sort();
+    assert_eq!(
+        counts,
+        vec![
+            vec![1, 12, 30, 12, 1],
+            vec![1, 12, 30, 12, 1],
+            vec![1, 12, 30, 20, 1],
+            vec![1, 12, 30, 20, 1]
+        ]
+    );
+
+    let mut noble = element_counts(&facet_ike(FacetingOptions {
+        noble: Some(1),
+        ..Default::default()
+    }));
+    noble.sort();
+    assert_eq!(counts, noble);
+
+    let prism = |count| {
+        facet_prism(FacetingOptions {
+            facet_orbits: Some(count),
+            ..Default::default()
+        })
+    };
+    let all = facet_prism(FacetingOptions::default());
+    let one = prism(FacetOrbitCount::Exact(1));
+    let two = prism(FacetOrbitCount::Exact(2));
+    assert!(!two.is_empty());
+    assert_eq!(one.len() + two.len(), prism(FacetOrbitCount::AtMost(2)).len());
+    assert_eq!(prism(FacetOrbitCount::Between(1, 2)).len(), prism(FacetOrbitCount::AtMost(2)).len());
+    assert!(two.iter().all(|counts| all.contains(counts)));
+
+    // The noble option asks for an exact number too, unless the numbers are given.
+    let noble = |facet_orbits| {
+        facet_prism(FacetingOptions {
+            noble: Some(2),
+            facet_orbits,
+            ..Default::default()
+        })
+    };
+    assert_eq!(noble(None), two);
+    assert_eq!(noble(Some(FacetOrbitCount::Exact(1))), one);
+}
+
+/// Facetings of the truncated octahedron under its rotation group that are
+/// mirror images of each other are only counted once when removing congruent
+/// copies.
+#[test]
+fn dedup_congruent() {
+    let poly = Concrete::from_off(include_str!("fixtures/toe.off")).unwrap();
+    let options = |dedup_congruent| FacetingOptions {
+        facet_orbits: Some(FacetOrbitCount::AtMost(2)),
+        dedup_congruent,
+        save: false,
+        ..Default::default()
+    };
+    assert_eq!(count_chiral(&poly, true, options(false)), 105);
+
+    let mut rotations = poly.clone();
+    let vertices = rotations.vertices.to_vec();
+    let (_, stats) = rotations.faceting_with_stats(vertices.clone(), GroupEnum::Chiral(true), &options(true));
+    assert_eq!(stats.faceting_count, 93);
+    assert_eq!(stats.congruent_count, 12);
+    assert!(stats.to_string().contains("93 facetings up to congruence (105 in all)"));
+
+    // Under the full group, there's nothing to remove.
+    let (_, stats) = rotations.faceting_with_stats(vertices, GroupEnum::Chiral(false), &options(true));
+    assert_eq!(stats.congruent_count, 0);
+}
+
+/// Taking the rotations of the full symmetry group computed beforehand gives
+/// the same facetings as computing the rotation group, including those kept
+/// by the chiral selection and those removed as congruent.
+#[test]
+fn chiral_of_full_group() {
+    let mut poly = Concrete::from_off(include_str!("fixtures/toe.off")).unwrap();
+    let vertices = poly.vertices.to_vec();
+    let group = poly.get_symmetry_group().unwrap().0;
+
+    for (chiral_selection, dedup_congruent) in [
+        (ChiralSelection::Any, false),
+        (ChiralSelection::PairedOnly, false),
+        (ChiralSelection::UnpairedOnly, false),
+        (ChiralSelection::Any, true),
+    ] {
+        let options = FacetingOptions {
+            facet_orbits: Some(FacetOrbitCount::AtMost(2)),
+            chiral_selection,
+            dedup_congruent,
+            ..Default::default()
+        };
+        let (expected, expected_stats) =
+            poly.faceting_with_stats(vertices.clone(), GroupEnum::Chiral(true), &options);
+        let (found, stats) =
+            poly.faceting_with_stats(vertices.clone(), GroupEnum::ChiralOf(group.clone()), &options);
+
+        assert_eq!(stats.group_order, 24);
+        assert_eq!(stats.group_order, expected_stats.group_order);
+        assert_eq!(stats.congruent_count, expected_stats.congruent_count);
+        assert_eq!(element_counts(&found), element_counts(&expected));
+    }
+}
+
+/// Facets the cube with a single allowed edge length.
+fn facet_cube_with_length(length: f64, allow_vacuous_edge_lengths: bool) -> Result<FacetingStats, FacetingError> {
+    let mut cube = Concrete::hypercube(4);
+    let vertices = cube.vertices.to_vec();
+    let options = FacetingOptions {
+        edge_length: EdgeLengthFilter::set(vec![length]),
+        allow_vacuous_edge_lengths,
+        save: false,
+        ..Default::default()
+    };
+    cube.try_faceting_with_stats(vertices, GroupEnum::Chiral(false), &options)
+        .map(|(_, stats)| stats)
+}
+
+/// An edge length just off from the edges of the cube matches no pair of
+/// vertices, and the edges are suggested instead.
+#[test]
+fn near_miss_edge_length() {
+    let length = 1.0 + 2.0 * f64::EPS;
+    let err = facet_cube_with_length(length, false).unwrap_err();
+    match err {
+        FacetingError::NoAllowedEdges { requested, tolerance, closest } => {
+            assert_eq!(requested, Some(length));
+            assert_eq!(tolerance, f64::EPS);
+            assert!((closest[0].unwrap() - 1.0).abs() < f64::EPS);
+        }
+        _ => panic!("unexpected error: {}", err),
+    }
+    assert!(err.to_string().contains("the closest distances between them are 1,"));
+}
+
+/// An edge length far longer than any distance between the vertices of the
+/// cube suggests the longest distances, and only gives a warning if allowed.
+#[test]
+fn wrong_edge_length() {
+    match facet_cube_with_length(100.0, false) {
+        Err(FacetingError::NoAllowedEdges { closest, .. }) => {
+            let expected = [3f64.sqrt(), 2f64.sqrt(), 1.0];
+            for (length, expected) in closest.iter().zip(&expected) {
+                assert!((length.unwrap() - expected).abs() < 1e-9);
+            }
+        }
+        result => panic!("unexpected result: {:?}", result.map(|stats| stats.faceting_count)),
+    }
+
+    let stats = facet_cube_with_length(100.0, true).unwrap();
+    assert_eq!(stats.faceting_count, 0);
+    assert_eq!(stats.warning_count, 1);
+
+    // Lengths that do occur aren't affected.
+    assert!(facet_cube_with_length(2f64.sqrt(), false).unwrap().faceting_count > 0);
+}
+
+/// Under the symmetries of the cube that preserve its two inscribed
+/// tetrahedra, those are facetings of it too, which only keep the vertices
+/// they use.
+#[test]
+fn unused_vertices() {
+    let mut cube = Concrete::hypercube(4);
+    let vertices = cube.vertices.to_vec();
+    let even: Vec<_> = vertices.iter().map(|v| v.iter().filter(|&&x| x > 0.0).count() % 2 == 0).collect();
+    let vertex_map: Vec<_> = cube
+        .get_symmetry_group()
+        .unwrap()
+        .1
+        .into_iter()
+        .filter(|row| (0..vertices.len()).all(|v| even[row[v]] == even[v]))
+        .collect();
+    assert_eq!(vertex_map.len(), 24);
+
+    let facetings = cube.faceting(vertices, GroupEnum::VertexMap(vertex_map.into()), &Default::default());
+    let mut counts = element_counts(&facetings);
+    counts.sort();
+    assert_eq!(counts, vec![vec![1, 4, 6, 4, 1], vec![1, 4, 6, 4, 1], vec![1, 8, 12, 6, 1]]);
+
+    for faceting in &facetings {
+        let poly = &faceting.polytope;
+        assert_eq!(poly.vertices.len(), poly.vertex_count());
+        assert!(poly.abs.ranks().is_dyadic().is_ok());
+        let used: HashSet<_> = (0..poly.edge_count())
+            .flat_map(|edge| poly.abs.element_vertices(2, edge).unwrap())
+            .collect();
+        assert_eq!(used.len(), poly.vertices.len());
+    }
+}
+
+/// A search resumed from a checkpoint finds the same facetings as one that
+/// wasn't interrupted, whether the checkpoint was written when the search was
+/// cancelled or along the way.
+#[test]
+fn checkpoint_resume() {
+    use std::sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    };
+
+    let mut cube = Concrete::hypercube(4);
+    let vertices = cube.vertices.to_vec();
+    let tables = cube.faceting_tables(vertices, GroupEnum::Chiral(false), &Default::default()).unwrap();
+    let facets = |results: &[FacetingResult]| results.iter().map(|result| result.facets.clone()).collect::<Vec<_>>();
+    let all = tables.complete(&[], &Default::default()).unwrap();
+    assert!(all.len() > 1);
+
+    let path = std::env::temp_dir().join(format!("miratope-checkpoint-{}", std::process::id()));
+    let resume_options = FacetingOptions {
+        checkpoint: Some(CheckpointOptions {
+            every_candidates: Some(1),
+            ..CheckpointOptions::new(&path)
+        }),
+        ..Default::default()
+    };
+
+    // Cancels the search once a faceting has been found.
+    let cancel = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&cancel);
+    let complete = Arc::new(AtomicUsize::new(0));
+    let options = FacetingOptions {
+        cancel: Some(cancel),
+        candidate_callback: Some(CandidateCallback::new(move |candidate| {
+            if candidate.complete && complete.fetch_add(1, Ordering::Relaxed) == 0 {
+                flag.store(true, Ordering::Relaxed);
+            }
+            CandidateDecision::Accept
+        })),
+        checkpoint: Some(CheckpointOptions::new(&path)),
+        ..Default::default()
+    };
+    let found = tables.complete(&[], &options).unwrap();
+    assert!(!found.is_empty() && found.len() < all.len());
+
+    let (resumed, stats) = Concrete::faceting_resume(&path, &resume_options).unwrap();
+    assert_eq!(facets(&resumed), facets(&all));
+    assert_eq!(element_counts(&resumed), element_counts(&all));
+    assert_eq!(stats.faceting_count, all.len());
+    assert!(!stats.cancelled);
+
+    // The last checkpoint written along the way is left once the search ends.
+    let (resumed, _) = Concrete::faceting_resume(&path, &resume_options).unwrap();
+    assert_eq!(facets(&resumed), facets(&all));
+
+    let mut cache = Vec::new();
+    tables.save_binary(&mut cache).unwrap();
+    std::fs::write(&path, cache).unwrap();
+    assert!(matches!(Concrete::faceting_resume(&path, &resume_options), Err(CacheError::NotACache)));
+    std::fs::remove_file(&path).unwrap();
+}
+
+/// The summaries of the outputs of each stage of the faceting of the cube.
+#[test]
+fn summaries() {
+    let mut cube = Concrete::hypercube(4);
+    let vertices = cube.vertices.to_vec();
+    let mut staged = |stop_after| {
+        let options = FacetingOptions {
+            stop_after,
+            ..Default::default()
+        };
+        cube.try_faceting_staged(vertices.clone(), GroupEnum::Chiral(false), &options).unwrap().0
+    };
+
+    let orbits = match staged(Stage::Hyperplanes) {
+        PartialFacetingOutput::Hyperplanes(orbits) => orbits,
+        output => panic!("unexpected output: {:?}", output),
+    };
+    assert_eq!(orbits[0][1].to_string(), "8 hyperplanes through 3 vertices, depth 0.288675");
+    assert_eq!(
+        format!("{:#}", orbits[0][0]),
+        "6 hyperplanes through 4 vertices, depth 0.500000\n  vertices [0, 1, 2, 3]"
+    );
+
+    let count = match staged(Stage::Count) {
+        PartialFacetingOutput::Count(mut count) => count.remove(0),
+        output => panic!("unexpected output: {:?}", output),
+    };
+    assert_eq!(count.to_string(), "2 facetings: 2 with 1 facet orbit");
+    assert_eq!(format!("{:#}", count), "2 facetings: 2 with 1 facet orbit\n  (0,0)\n  (1,0)");
+
+    let facetings = match staged(Stage::Full) {
+        PartialFacetingOutput::Full(facetings) => facetings,
+        output => panic!("unexpected output: {:?}", output),
+    };
+    assert_eq!(
+        facetings[1].to_string(),
+        "faceting #1 ((1,0)) (rank-4 polytope in 3D): 8 vertices, 12 edges, 8 faces; χ=4; 1 facet orbit"
+    );
+    assert_eq!(
+        format!("{:#}", facetings[0]),
+        "faceting #0 ((0,0)) (rank-4 polytope in 3D): 8 vertices, 12 edges, 6 faces; χ=2; 1 facet orbit\n  (0,0): 6 facets"
+    );
+}
+
+/// Faceting the vertices of a tetrahedron inscribed in the cube gives the
+/// tetrahedron, whether they're picked as an orbit of the symmetries of the
+/// tetrahedron or directly under the full symmetry group.
+#[test]
+fn vertex_subset() {
+    let mut cube = Concrete::hypercube(4);
+    let vertices = cube.vertices.to_vec();
+    let even: Vec<usize> = (0..8)
+        .filter(|&v| vertices[v].iter().product::<f64>() > 0.)
+        .collect();
+    let (_, full_map) = cube.get_symmetry_group().unwrap();
+    let tetrahedral_map: Vec<Vec<usize>> = full_map
+        .into_iter()
+        .filter(|row| even.iter().all(|v| even.contains(&row[*v])))
+        .collect();
+    assert_eq!(tetrahedral_map.len(), 24);
+    let orbit = if even.contains(&0) { 0 } else { 1 };
+
+    let mut facet_subset = |symmetry, subset| {
+        let options = FacetingOptions {
+            vertex_subset: Some(subset),
+            ..Default::default()
+        };
+        cube.try_faceting_with_stats(vertices.clone(), symmetry, &options)
+    };
+
+    for (symmetry, subset) in [
+        (GroupEnum::VertexMap(tetrahedral_map.clone().into()), VertexSubset::Orbits(vec![orbit])),
+        (GroupEnum::Chiral(false), VertexSubset::Vertices(even.clone())),
+    ] {
+        let (facetings, stats) = facet_subset(symmetry, subset).unwrap();
+        assert_eq!(element_counts(&facetings), vec![vec![1, 4, 6, 4, 1]]);
+        assert_eq!(stats.vertex_count, 4);
+        assert_eq!(stats.group_order, 24);
+        for vertex in facetings[0].polytope.vertices.iter() {
+            assert!(even.iter().any(|&v| (vertex - &vertices[v]).norm() < 1e-9));
+        }
+    }
+
+    assert!(matches!(
+        facet_subset(GroupEnum::Chiral(false), VertexSubset::Vertices(vec![0, 8])),
+        Err(FacetingError::SubsetVertexOutOfRange { vertex: 8, vertex_count: 8 })
+    ));
+    assert!(matches!(
+        facet_subset(GroupEnum::VertexMap(tetrahedral_map.into()), VertexSubset::Orbits(vec![2])),
+        Err(FacetingError::SubsetOrbitOutOfRange { orbit: 2, orbit_count: 2 })
+    ));
+    assert!(matches!(
+        facet_subset(GroupEnum::Chiral(false), VertexSubset::Orbits(Vec::new())),
+        Err(FacetingError::EmptyVertexSubset)
+    ));
+}
+
+/// The hyperplanes through the vertices of the cube are those of its 6 faces,
+/// of the 6 rectangles through opposite edges, and of the 8 triangles cutting
+/// off a vertex. The edge length of the cube rules out the rectangles and the
+/// triangles.
+#[test]
+fn hyperplane_orbits() {
+    let mut cube = Concrete::hypercube(4);
+    let summary = |orbits: &[HyperplaneOrbit]| -> Vec<(usize, usize)> {
+        orbits.iter().map(|orbit| (orbit.size, orbit.vertices.len())).collect()
+    };
+
+    let orbits = cube.hyperplane_orbits(GroupEnum::Chiral(false), None).unwrap();
+    assert_eq!(summary(&orbits), vec![(6, 4), (8, 3), (6, 4)]);
+    for orbit in &orbits {
+        assert_eq!(orbit.hyperplanes.len(), orbit.size);
+        assert!(orbit.hyperplanes.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(orbit.hyperplanes.contains(&orbit.vertices));
+        for hyperplane in &orbit.hyperplanes {
+            let subspace = Subspace::from_points(hyperplane.iter().map(|&v| &cube.vertices[v]));
+            assert_eq!(subspace.rank(), 2);
+        }
+    }
+
+    // The faceting enumerates the same orbits.
+    let vertices = cube.vertices.to_vec();
+    let tables = cube.faceting_tables(vertices, GroupEnum::Chiral(false), &Default::default()).unwrap();
+    assert_eq!(orbits.len(), tables.hyperplane_orbit_count());
+    for (hp, orbit) in orbits.iter().enumerate() {
+        assert_eq!(orbit.size, tables.hyperplane_count(hp));
+        assert_eq!(orbit.depth, tables.depth(hp));
+    }
+
+    let orbits = cube.hyperplane_orbits(GroupEnum::Chiral(false), Some(1.0)).unwrap();
+    assert_eq!(summary(&orbits), vec![(6, 4)]);
+    assert!(matches!(
+        cube.hyperplane_orbits(GroupEnum::Chiral(false), Some(0.5)),
+        Err(FacetingError::NoAllowedEdges { .. })
+    ));
+
+    // The enumeration is aborted when the symmetry of the cube is broken.
+    let mut skewed = Concrete::hypercube(4);
+    for (x, offset) in skewed.vertices[0].iter_mut().zip(&[1e-4, 2e-4, 3e-4]) {
+        *x += offset;
+    }
+    assert!(matches!(
+        skewed.hyperplane_orbits(GroupEnum::Chiral(false), None),
+        Err(FacetingError::TrivialGroup { .. })
+    ));
+    assert!(matches!(
+        skewed.possible_facets(GroupEnum::Chiral(false), None),
+        Err(FacetingError::TrivialGroup { .. })
+    ));
+}
+
+/// The possible facets of the cube are its faces, the triangles through three
+/// of its vertices, and the rectangles through opposite edges along with their
+/// crossed versions.
+#[test]
+fn possible_facets() {
+    let mut cube = Concrete::hypercube(4);
+    let facets = cube.possible_facets(GroupEnum::Chiral(false), None).unwrap();
+    let summary: Vec<Vec<(usize, usize)>> = facets
+        .iter()
+        .map(|orbit| orbit.iter().map(|facet| (facet.vertices.len(), facet.el_count(2))).collect())
+        .collect();
+    assert_eq!(summary, vec![vec![(4, 4)], vec![(3, 3)], vec![(4, 4); 3]]);
+
+    for orbit in &facets {
+        for facet in orbit {
+            assert!(facet.ranks().is_valid().is_ok());
+            let subspace = Subspace::from_points(facet.vertices.iter());
+            assert_eq!(subspace.rank(), 2);
+            assert!(facet.vertices.iter().all(|v| cube.vertices.iter().any(|w| (v - w).norm() < 1e-9)));
+        }
+    }
+
+    // Only the squares have edges of length 1.
+    let squares = cube.possible_facets(GroupEnum::Chiral(false), Some(1.0)).unwrap();
+    assert_eq!(squares.len(), 1);
+    assert_eq!(squares[0].len(), 1);
+    assert_eq!(squares[0][0].meta.name.as_deref(), Some("facet (0,0)"));
+}
+
+#[test]
+fn element_orbits() {
+    // The cube is transitive on its vertices, edges, and faces.
+    let mut cube = Concrete::hypercube(4);
+    for rank in 1..4 {
+        let orbits = cube.element_orbits(rank, GroupEnum::Chiral(false)).unwrap();
+        assert_eq!(orbits.len(), 1);
+        assert_eq!(orbits[0].len(), cube.el_count(rank));
+    }
+
+    // So is its rotation group.
+    let orbits = cube.element_orbits(3, GroupEnum::Chiral(true)).unwrap();
+    assert_eq!(orbits.len(), 1);
+
+    // The faces of a triangular prism are the bases and the squares.
+    let mut prism = Concrete::polygon(3).prism();
+    assert_eq!(prism.element_orbits(1, GroupEnum::Chiral(false)).unwrap().len(), 1);
+    assert_eq!(prism.element_orbits(2, GroupEnum::Chiral(false)).unwrap().len(), 2);
+    let mut faces = prism.element_orbits(3, GroupEnum::Chiral(false)).unwrap();
+    assert_eq!(faces.len(), 2);
+    faces.sort_by_key(Vec::len);
+    assert_eq!(faces.iter().map(Vec::len).collect::<Vec<_>>(), vec![2, 3]);
+    for orbit in &faces {
+        let mut sorted = orbit.clone();
+        sorted.sort_unstable();
+        assert_eq!(orbit[0], sorted[0]);
+    }
+
+    // Under the trivial group, every element is its own orbit.
+    let identity = VertexMap::identity(cube.vertex_count());
+    let orbits = cube.element_orbits(2, GroupEnum::VertexMap(identity)).unwrap();
+    assert_eq!(orbits, (0..12).map(|idx| vec![idx]).collect::<Vec<_>>());
+}
+
+/// The hyperplanes through a cloud of more than 20000 points with the symmetry
+/// of the cube are found without using memory quadratic in the number of
+/// points.
+#[test]
+fn large_point_cloud() {
+    const ORBITS: usize = 417;
+
+    // Points in general position, so that each orbit has 48 of them.
+    let mut seed = 1u64;
+    let mut random = || {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        0.05 + 0.9 * (seed >> 11) as f64 / (1u64 << 53) as f64
+    };
+    let generic: Vec<[f64; 3]> = (0..ORBITS).map(|_| [random(), random(), random()]).collect();
+
+    // The signed permutations of the coordinates.
+    let perms = [[0, 1, 2], [0, 2, 1], [1, 0, 2], [1, 2, 0], [2, 0, 1], [2, 1, 0]];
+    let symmetries: Vec<_> = perms
+        .iter()
+        .flat_map(|perm| (0..8).map(move |signs: usize| (*perm, signs)))
+        .collect();
+    let apply = |(perm, signs): ([usize; 3], usize), p: &[f64]| {
+        Point::from_iterator(3, (0..3).map(|i| if signs >> i & 1 == 1 { -p[perm[i]] } else { p[perm[i]] }))
+    };
+
+    let vertices: Vec<_> = generic
+        .iter()
+        .flat_map(|p| symmetries.iter().map(move |&s| apply(s, p)))
+        .collect();
+    let index: HashMap<Vec<u64>, usize> = vertices
+        .iter()
+        .enumerate()
+        .map(|(idx, v)| (v.iter().map(|x| x.to_bits()).collect(), idx))
+        .collect();
+    let vertex_map: Vec<Vec<usize>> = symmetries
+        .iter()
+        .map(|&s| {
+            vertices
+                .iter()
+                .map(|v| index[&apply(s, v.as_slice()).iter().map(|x| x.to_bits()).collect::<Vec<_>>()])
+                .collect()
+        })
+        .collect();
+    assert_eq!(vertices.len(), 20016);
+
+    // Only the closest points can share a facet.
+    let options = FacetingOptions {
+        edge_length: EdgeLengthFilter::Range { min: None, max: Some(0.025) },
+        ..Default::default()
+    };
+    let (orbits, stats) = Concrete::hypercube(4)
+        .try_hyperplane_orbits(vertices.clone(), GroupEnum::VertexMap(vertex_map.into()), &options)
+        .unwrap();
+
+    assert_eq!(stats.vertex_count, 20016);
+    assert_eq!(stats.vertex_orbit_count, ORBITS);
+    assert!(!orbits.is_empty());
+    for orbit in &orbits {
+        assert!(orbit.vertices.len() >= 3);
+        assert!(orbit.vertices.iter().all(|&v| orbit.hyperplane.distance(&vertices[v]) < 1e-9));
+    }
+}
+
+/// Vertex maps whose rows aren't permutations of the vertices are refused
+/// before anything is faceted.
+#[test]
+fn invalid_vertex_map() {
+    let mut prism = triangular_prism();
+    let vertices = prism.vertices.to_vec();
+    let identity: Vec<usize> = (0..6).collect();
+
+    let maps = vec![
+        (vec![identity.clone(), vec![1, 2, 0, 4, 5, 6]], VertexMapError::NotAPermutation { row: 1 }),
+        (vec![identity.clone(), vec![1, 2, 0, 4, 5]], VertexMapError::WrongLength { row: 1, len: 5, vertex_count: 6 }),
+        (vec![identity.clone(), vec![1, 2, 0, 3, 4, 5]], VertexMapError::NotClosed { first: 1, second: 1 }),
+    ];
+    for (rows, expected) in maps {
+        assert_eq!(VertexMap::new_checked(rows.clone(), 6, true), Err(expected));
+        match prism.try_faceting_with_stats(vertices.clone(), GroupEnum::VertexMap(rows.into()), &Default::default()) {
+            Err(FacetingError::InvalidVertexMap(err)) => assert_eq!(err, expected),
+            result => panic!("unexpected result: {:?}", result.map(|(_, stats)| stats)),
+        }
+    }
+}
+
+/// The prisms of the facetings of a cube and a triangular prism have twice as
+/// many vertices, and are equilateral when their height is the edge length of
+/// an equilateral faceting, like those of the cube. The facetings of the
+/// triangular prism aren't equilateral, so they're skipped with a warning.
+#[test]
+fn chain_prisms_of_facetings() {
+    let mut facetings = Vec::new();
+    for poly in [Concrete::hypercube(4), triangular_prism()] {
+        let mut poly = poly.with_meta(PolytopeMeta::named("polytope"));
+        let vertices = poly.vertices.to_vec();
+        facetings.extend(poly.faceting(vertices, GroupEnum::Chiral(false), &Default::default()));
+    }
+    let equilateral = facetings.iter().filter(|result| result.polytope.is_equilateral()).count();
+    assert!(equilateral > 0 && equilateral < facetings.len());
+
+    let (prisms, warnings) = chain_prisms_with_warnings(&facetings, HeightPolicy::EqualEdges);
+    assert_eq!(prisms.len(), equilateral);
+    assert_eq!(warnings.len(), facetings.len() - equilateral);
+    for prism in &prisms {
+        let idx: usize = prism.meta.properties["faceting"].parse().unwrap();
+        let result = &facetings[idx];
+        assert!(prism.is_equilateral());
+        assert_eq!(prism.dim(), Some(4));
+        assert_eq!(prism.vertex_count(), 2 * result.polytope.vertex_count());
+        assert_eq!(prism.meta.properties["prism of"], *result.polytope.meta.name.as_ref().unwrap());
+        assert_eq!(prism.meta.name, Some(format!("prism of {}", prism.meta.properties["prism of"])));
+        assert!(prism.meta.properties["facets"].starts_with('('));
+    }
+    for warning in &warnings {
+        match warning {
+            ChainWarning::MixedEdgeLengths { result, lengths } => {
+                assert!(!facetings[*result].polytope.is_equilateral());
+                assert!(lengths.len() > 1);
+            }
+            _ => panic!("unexpected warning: {}", warning),
+        }
+    }
+
+    let prisms = chain_prisms(&facetings, HeightPolicy::Fixed(0.5));
+    assert_eq!(prisms.len(), facetings.len());
+    assert!(prisms.iter().all(|prism| prism.meta.properties["height"] == "0.5"));
+
+    let (prisms, warnings) = chain_prisms_with_warnings(&facetings, HeightPolicy::Fixed(0.0));
+    assert!(prisms.is_empty());
+    assert_eq!(warnings[0], ChainWarning::DegenerateHeight { result: 0, height: 0.0 });
+    assert_eq!(warnings[0].to_string(), "skipping the prism of faceting 0, which would have height 0");
+}
@@ -0,0 +1,315 @@
+//! Property-based tests of the faceting algorithm, which facet small random
+//! vertex arrangements under random groups of symmetries and check the
+//! invariants every faceting found must satisfy.
+//!
+//! The arrangements are built from a group first: a few random signed
+//! permutation matrices are closed under multiplication, and the orbits of
+//! some random points on the unit sphere under the group are the vertices.
+//! Every element of the group is then an actual isometry of the vertices, and
+//! the vertex map is read off from how each of them permutes the vertices.
+
+use std::{
+    collections::{BTreeSet, HashSet, VecDeque},
+    fmt,
+    time::Duration,
+};
+
+use miratope_core::{
+    abs::Ranked,
+    conc::{
+        faceting::{FacetingOptions, FacetingResult, GroupEnum},
+        Concrete,
+    },
+    geometry::{Hyperplane, Point, Subspace},
+    Polytope,
+};
+
+use itertools::Itertools;
+use proptest::{prelude::*, test_runner::FileFailurePersistence};
+use vec_like::*;
+
+/// The most vertices an arrangement in 3D can have.
+const MAX_VERTICES_3D: usize = 10;
+
+/// The most vertices an arrangement in 4D can have.
+const MAX_VERTICES_4D: usize = 8;
+
+/// The tolerance used to compare the coordinates of the vertices.
+const TOLERANCE: f64 = 1e-9;
+
+/// An isometry with integer entries, given as the rows of its matrix.
+type IntMatrix = Vec<Vec<i32>>;
+
+/// A random vertex arrangement, along with the rows of a vertex map of a
+/// group of its symmetries.
+#[derive(Clone)]
+struct Arrangement {
+    /// The vertices, on the unit sphere.
+    vertices: Vec<Point<f64>>,
+
+    /// How each symmetry permutes the vertices, with no row repeated.
+    vertex_map: Vec<Vec<usize>>,
+}
+
+impl Arrangement {
+    /// Returns the number of dimensions of the space the vertices are in.
+    fn dim(&self) -> usize {
+        self.vertices[0].len()
+    }
+}
+
+/// Writes the vertices as lists of coordinates, so that failing cases are
+/// easy to read.
+impl fmt::Debug for Arrangement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let vertices: Vec<Vec<f64>> = self.vertices.iter().map(|v| v.iter().copied().collect()).collect();
+        f.debug_struct("Arrangement")
+            .field("vertices", &vertices)
+            .field("vertex_map", &self.vertex_map)
+            .finish()
+    }
+}
+
+/// Returns the signed permutation matrix sending each coordinate axis to the
+/// axis given by a permutation, with the signs given.
+fn signed_permutation(perm: &[usize], signs: &[bool]) -> IntMatrix {
+    let dim = perm.len();
+    let mut matrix = vec![vec![0; dim]; dim];
+    for (i, (&j, &sign)) in perm.iter().zip(signs).enumerate() {
+        matrix[j][i] = if sign { -1 } else { 1 };
+    }
+    matrix
+}
+
+/// Multiplies two integer matrices.
+fn mul(a: &IntMatrix, b: &IntMatrix) -> IntMatrix {
+    let dim = a.len();
+    (0..dim)
+        .map(|i| (0..dim).map(|j| (0..dim).map(|k| a[i][k] * b[k][j]).sum()).collect())
+        .collect()
+}
+
+/// Applies an integer matrix to a point.
+fn apply(matrix: &IntMatrix, p: &Point<f64>) -> Point<f64> {
+    Point::from_iterator(
+        p.len(),
+        matrix
+            .iter()
+            .map(|row| row.iter().zip(p.iter()).map(|(&a, &x)| a as f64 * x).sum()),
+    )
+}
+
+/// Returns every element of the group generated by some matrices.
+fn closure(dim: usize, generators: &[IntMatrix]) -> Vec<IntMatrix> {
+    let identity = signed_permutation(&(0..dim).collect::<Vec<_>>(), &vec![false; dim]);
+    let mut elements = vec![identity.clone()];
+    let mut found: HashSet<_> = elements.iter().cloned().collect();
+    let mut queue = VecDeque::from(vec![identity]);
+
+    while let Some(element) = queue.pop_front() {
+        for generator in generators {
+            let product = mul(generator, &element);
+            if found.insert(product.clone()) {
+                elements.push(product.clone());
+                queue.push_back(product);
+            }
+        }
+    }
+    elements
+}
+
+/// Returns the index of the vertex at a point, if any.
+fn position(vertices: &[Point<f64>], p: &Point<f64>) -> Option<usize> {
+    vertices.iter().position(|v| (v - p).norm() < TOLERANCE)
+}
+
+/// Builds the arrangement given by the orbits of some points under the group
+/// generated by some matrices, unless it has more vertices than some bound.
+/// Points at the origin are left out.
+fn arrangement(dim: usize, generators: &[IntMatrix], seeds: &[Vec<i32>], max_vertices: usize) -> Option<Arrangement> {
+    let group = closure(dim, generators);
+    let mut vertices: Vec<Point<f64>> = Vec::new();
+    for seed in seeds {
+        let seed = Point::from_iterator(dim, seed.iter().map(|&x| x as f64));
+        if seed.norm() < TOLERANCE {
+            continue;
+        }
+        let seed = seed.normalize();
+        for element in &group {
+            let p = apply(element, &seed);
+            if position(&vertices, &p).is_none() {
+                if vertices.len() == max_vertices {
+                    return None;
+                }
+                vertices.push(p);
+            }
+        }
+    }
+
+    let vertex_map: BTreeSet<Vec<usize>> = group
+        .iter()
+        .map(|element| {
+            vertices
+                .iter()
+                .map(|v| position(&vertices, &apply(element, v)).unwrap())
+                .collect()
+        })
+        .collect();
+    Some(Arrangement {
+        vertices,
+        vertex_map: vertex_map.into_iter().collect(),
+    })
+}
+
+/// Generates a random signed permutation matrix.
+fn generator(dim: usize) -> impl Strategy<Value = IntMatrix> {
+    (
+        Just((0..dim).collect::<Vec<_>>()).prop_shuffle(),
+        prop::collection::vec(any::<bool>(), dim),
+    )
+        .prop_map(|(perm, signs)| signed_permutation(&perm, &signs))
+}
+
+/// Generates a random arrangement in some dimensions, spanning the whole
+/// space and with few enough vertices to facet quickly. With no generators,
+/// the group is trivial.
+fn arrangements(dim: usize, max_vertices: usize) -> impl Strategy<Value = Arrangement> {
+    (
+        prop::collection::vec(generator(dim), 0..=2),
+        prop::collection::vec(prop::collection::vec(-2..=2, dim), 1..=dim + 2),
+    )
+        .prop_filter_map("too many vertices, or not spanning the space", move |(generators, seeds)| {
+            arrangement(dim, &generators, &seeds, max_vertices).filter(|arrangement| {
+                let vertices = &arrangement.vertices;
+                vertices.len() > dim && Subspace::from_points(vertices.iter()).is_full_rank()
+            })
+        })
+}
+
+/// Returns the vertices of each facet of a polytope, as sorted indices into
+/// some vertices, in sorted order.
+fn facet_vertices(poly: &Concrete, vertices: &[Point<f64>]) -> Vec<Vec<usize>> {
+    let rank = poly.rank();
+    let mut facets: Vec<Vec<usize>> = (0..poly.el_count(rank - 1))
+        .map(|idx| {
+            let mut facet: Vec<_> = poly
+                .abs
+                .element_vertices(rank - 1, idx)
+                .unwrap()
+                .into_iter()
+                .map(|v| position(vertices, &poly.vertices[v]).unwrap())
+                .collect();
+            facet.sort_unstable();
+            facet
+        })
+        .collect();
+    facets.sort();
+    facets
+}
+
+/// Returns the vertices on each facet of the convex hull of some vertices,
+/// found as the hyperplanes through them with every vertex on the same side.
+/// The vertices must span the space and be in convex position.
+fn hull_facets(vertices: &[Point<f64>]) -> Vec<Vec<usize>> {
+    let dim = vertices[0].len();
+    let mut facets = BTreeSet::new();
+    for subset in (0..vertices.len()).combinations(dim) {
+        let subspace = Subspace::from_points(subset.iter().map(|&v| &vertices[v]));
+        let hyperplane = match Hyperplane::from_subspace(subspace) {
+            Some(hyperplane) => hyperplane,
+            None => continue,
+        };
+
+        let distances: Vec<_> = vertices.iter().map(|v| hyperplane.distance(v)).collect();
+        if distances.iter().all(|&d| d < TOLERANCE) || distances.iter().all(|&d| d > -TOLERANCE) {
+            let facet: Vec<_> = (0..vertices.len()).filter(|&v| distances[v].abs() < TOLERANCE).collect();
+            facets.insert(facet);
+        }
+    }
+    facets.into_iter().collect()
+}
+
+/// Facets an arrangement under its group, checks the invariants of every
+/// faceting found, and returns them along with whether the search finished.
+fn check_facetings(arrangement: &Arrangement) -> Result<(Vec<FacetingResult>, bool), TestCaseError> {
+    let dim = arrangement.dim();
+    let vertices = &arrangement.vertices;
+    let options = FacetingOptions {
+        allow_trivial_group: true,
+        max_results: Some(500),
+        time_limit: Some(Duration::from_secs(20)),
+        ..Default::default()
+    };
+
+    let mut host = Concrete::hypercube(dim + 1);
+    let result = host.try_faceting_with_stats(
+        vertices.clone(),
+        GroupEnum::VertexMap(arrangement.vertex_map.clone().into()),
+        &options,
+    );
+    let (facetings, stats) = match result {
+        Ok(output) => output,
+        Err(err) => return Err(TestCaseError::fail(format!("faceting failed: {}", err))),
+    };
+    prop_assert_eq!(stats.group_order, arrangement.vertex_map.len());
+
+    for faceting in &facetings {
+        let poly = &faceting.polytope;
+        let rank = poly.rank();
+        prop_assert_eq!(rank, dim + 1);
+        prop_assert!(poly.abs.ranks().is_dyadic().is_ok(), "{:?} isn't dyadic", faceting.facets);
+
+        for ridge in poly.abs.ranks()[rank - 2].iter() {
+            prop_assert_eq!(ridge.sups.len(), 2, "a ridge of {:?} isn't in two facets", faceting.facets);
+        }
+
+        prop_assert_eq!(faceting.facet_counts.iter().sum::<usize>(), poly.el_count(rank - 1));
+        for &count in &faceting.facet_counts {
+            prop_assert_eq!(stats.group_order % count, 0, "{:?} has an orbit of {} facets", faceting.facets, count);
+        }
+
+        for facet in facet_vertices(poly, vertices) {
+            let subspace = Subspace::from_points(facet.iter().map(|&v| &vertices[v]));
+            prop_assert!(!subspace.is_full_rank(), "a facet of {:?} isn't flat", faceting.facets);
+        }
+    }
+
+    Ok((facetings, !stats.cancelled && !stats.truncated))
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        cases: 32,
+        max_shrink_iters: 256,
+        failure_persistence: Some(Box::new(FileFailurePersistence::WithSource("proptest-regressions"))),
+        ..ProptestConfig::default()
+    })]
+
+    /// The facetings of arrangements in 3D are polyhedra, and their convex
+    /// hull is one of them.
+    #[test]
+    fn facetings_3d(arrangement in arrangements(3, MAX_VERTICES_3D)) {
+        let (facetings, finished) = check_facetings(&arrangement)?;
+        if finished {
+            let hull = hull_facets(&arrangement.vertices);
+            prop_assert!(
+                facetings.iter().any(|faceting| facet_vertices(&faceting.polytope, &arrangement.vertices) == hull),
+                "the convex hull wasn't found"
+            );
+        }
+    }
+
+    /// The facetings of arrangements in 4D are polychora, and their convex
+    /// hull is one of them.
+    #[test]
+    fn facetings_4d(arrangement in arrangements(4, MAX_VERTICES_4D)) {
+        let (facetings, finished) = check_facetings(&arrangement)?;
+        if finished {
+            let hull = hull_facets(&arrangement.vertices);
+            prop_assert!(
+                facetings.iter().any(|faceting| facet_vertices(&faceting.polytope, &arrangement.vertices) == hull),
+                "the convex hull wasn't found"
+            );
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//! Tests recording the operations performed on polytopes, undoing them, and
+//! replaying them on other polytopes.
+
+use miratope_core::{
+    conc::{
+        history::{HistoryError, LoggedOp, Operation, OperationLog},
+        meta::PolytopeMeta,
+        Concrete, ConcretePolytope,
+    },
+    float::Float,
+    geometry::{Hypersphere, Matrix, Point},
+    Polytope,
+};
+
+/// Checks that two polytopes have the same vertices, up to a tolerance and
+/// their order.
+fn assert_same_vertices(p: &Concrete, q: &Concrete) {
+    assert_eq!(p.vertices.len(), q.vertices.len());
+    for v in p.vertices.iter() {
+        assert!(q.vertices.iter().any(|w| (v - w).norm() < f64::EPS), "{:?} not found", v);
+    }
+}
+
+/// Returns a cube moved away from the origin, which records its operations.
+fn moved_cube() -> Concrete {
+    let mut cube = Concrete::hypercube(4).with_meta(PolytopeMeta::named("cube")).with_history();
+    cube.recenter_with(&Point::from_vec(vec![-2.0, -2.0, -2.0]));
+    cube
+}
+
+/// Only the polytopes with a log attached record their operations, and every
+/// operation in place is recorded.
+#[test]
+fn recording() {
+    let mut cube = Concrete::hypercube(4);
+    cube.scale(2.0);
+    assert_eq!(cube.history, None);
+    assert_eq!(cube.undo(), Err(HistoryError::NoHistory));
+
+    let mut cube = moved_cube();
+    cube.scale(2.0);
+    cube.recenter();
+    let sphere = Hypersphere::with_squared_radius(Point::from_vec(vec![0.0, 0.0, 0.1]), 2.0);
+    cube.try_dual_mut_with(&sphere).unwrap();
+    assert!(cube.petrial_mut());
+    let cube = cube.apply(&Matrix::identity(3, 3));
+
+    let history = cube.history.as_ref().unwrap();
+    let ops: Vec<_> = history.operations().cloned().collect();
+    assert_eq!(ops.len(), 6);
+    assert_eq!(ops[0], Operation::Translate(Point::from_vec(vec![2.0, 2.0, 2.0])));
+    assert_eq!(ops[1], Operation::Scale(2.0));
+    assert_eq!(ops[2], Operation::Translate(Point::from_vec(vec![-4.0, -4.0, -4.0])));
+    assert_eq!(ops[3], Operation::dual(&sphere));
+    assert_eq!(ops[4], Operation::Petrial);
+    assert_eq!(ops[5], Operation::Transform(Matrix::identity(3, 3)));
+    assert!(history.is_invertible());
+    assert_eq!(cube.meta.name.as_deref(), Some("Petrial of dual of cube"));
+}
+
+/// Undoing every operation gives back the original polytope, with its name,
+/// and undoing a flattening is refused.
+#[test]
+fn undo() {
+    let original = moved_cube();
+    let mut cube = original.clone();
+    cube.scale(3.0);
+    cube.try_dual_mut().unwrap();
+    cube.petrial_mut();
+    cube.recenter();
+
+    for _ in 0..4 {
+        cube.undo().unwrap();
+    }
+    assert_same_vertices(&cube, &original);
+    assert_eq!(cube.meta, original.meta);
+    assert_eq!(cube.history, original.history);
+
+    cube.undo().unwrap();
+    assert_same_vertices(&cube, &Concrete::hypercube(4));
+    assert_eq!(cube.undo(), Err(HistoryError::NothingToUndo));
+
+    // The square is flattened into the plane it's in.
+    let mut square = Concrete::polygon(4).with_history();
+    square.vertices = square
+        .vertices
+        .iter()
+        .map(|v| Point::from_vec(vec![v[0], v[1], 0.0]))
+        .collect::<Vec<_>>()
+        .into();
+    square.flatten();
+    assert_eq!(square.dim(), Some(2));
+    let flattened = square.clone();
+    match square.undo() {
+        Err(err @ HistoryError::NotInvertible(Operation::Flatten { .. })) => {
+            assert_eq!(err.to_string(), "the operation \"flatten into 2 dimensions\" can't be undone");
+        }
+        result => panic!("unexpected result: {:?}", result),
+    }
+    assert_eq!(square.history, flattened.history);
+    assert_same_vertices(&square, &flattened);
+}
+
+/// Replaying the log of a polytope on the polytope it came from gives it back,
+/// and the log survives being serialized.
+#[test]
+fn replay() {
+    let base = Concrete::hypercube(4).with_meta(PolytopeMeta::named("cube"));
+    let mut cube = base.clone().with_history();
+    cube.recenter_with(&Point::from_vec(vec![0.0, 0.0, 2.0]));
+    cube.scale(0.5);
+    cube.try_dual_mut().unwrap();
+    let cube = cube.apply(&Matrix::from_vec(3, 3, vec![1.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0]));
+
+    let log = cube.history.clone().unwrap();
+    let replayed = Concrete::replay(&log, &base).unwrap();
+    assert_same_vertices(&replayed, &cube);
+    assert_eq!(replayed.meta, cube.meta);
+    assert_eq!(replayed.history, cube.history);
+
+    let read = OperationLog::from_ron(&log.to_ron()).unwrap();
+    assert_eq!(read, log);
+    assert_same_vertices(&Concrete::replay(&read, &base).unwrap(), &cube);
+
+    // The dual of a cube with a facet through the center fails.
+    let mut cube = Concrete::hypercube(4);
+    cube.recenter_with(&Point::from_vec(vec![0.5, 0.0, 0.0]));
+    let dual = Operation::dual(&Hypersphere::unit(3));
+    assert_eq!(Concrete::replay(&log, &cube).unwrap_err(), HistoryError::Failed(dual.clone()));
+    assert_eq!(dual.perform(&mut cube), Err(HistoryError::Failed(dual)));
+}
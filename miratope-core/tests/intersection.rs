@@ -0,0 +1,55 @@
+//! Finds the facets of some polyhedra that cross each other.
+
+use miratope_core::{
+    conc::{faceting::GroupEnum, Concrete},
+    file::FromFile,
+    Polytope,
+};
+
+/// The tolerance for the crossings and the identification of the sissid.
+const TOLERANCE: f64 = 1e-6;
+
+/// Returns the small stellated dodecahedron, as a faceting of the icosahedron.
+fn sissid() -> Concrete {
+    let mut ike = Concrete::from_off(include_str!("fixtures/ike.off")).unwrap();
+    let vertices = ike.vertices.to_vec();
+    ike.faceting(vertices, GroupEnum::Chiral(false), &Default::default())
+        .into_iter()
+        .map(|faceting| faceting.polytope)
+        .find(|p| matches!(p.identify_uniform(TOLERANCE), Some(id) if id.name == "sissid"))
+        .expect("the small stellated dodecahedron wasn't found")
+}
+
+/// The faces of a convex polyhedron never cross.
+#[test]
+fn icosahedron() {
+    let ike = Concrete::from_off(include_str!("fixtures/ike.off")).unwrap();
+    assert!(ike.facet_intersections(TOLERANCE).is_empty());
+    assert!(Concrete::hypercube(4).facet_intersections(TOLERANCE).is_empty());
+}
+
+/// Every pentagram of the small stellated dodecahedron crosses the five
+/// faces through a single one of its vertices, along a segment out of that
+/// vertex, but not the five it shares an edge with.
+#[test]
+fn small_stellated_dodecahedron() {
+    let sissid = sissid();
+    let crossings = sissid.facet_intersections(TOLERANCE);
+
+    let mut counts = vec![0; 12];
+    for crossing in &crossings {
+        let (i, j) = crossing.facets;
+        counts[i] += 1;
+        counts[j] += 1;
+
+        assert_eq!(crossing.subspace.rank(), 1);
+        assert_eq!(crossing.points.len(), 2);
+        for p in &crossing.points {
+            assert!(crossing.subspace.distance(p) < TOLERANCE);
+        }
+    }
+
+    assert_eq!(counts, vec![5; 12]);
+    assert_eq!(crossings.len(), 30);
+}
+
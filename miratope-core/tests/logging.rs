@@ -0,0 +1,140 @@
+//! Checks the messages logged while faceting.
+
+use std::{
+    cell::RefCell,
+    sync::{Arc, Mutex, Once},
+};
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use miratope_core::{
+    conc::{
+        faceting::{FacetingError, FacetingOptions, FacetingProgress, GroupEnum, ProgressSink},
+        Concrete,
+    },
+    Polytope,
+};
+
+thread_local! {
+    /// The messages logged from this thread, along with their levels, so that
+    /// tests running at the same time can tell theirs apart.
+    static RECORDS: RefCell<Vec<(Level, String)>> = RefCell::new(Vec::new());
+}
+
+/// Keeps every message logged in the thread it was logged from.
+struct CapturingLogger;
+
+impl Log for CapturingLogger {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        let entry = (record.level(), record.args().to_string());
+        RECORDS.with(|records| records.borrow_mut().push(entry));
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: CapturingLogger = CapturingLogger;
+
+static INIT: Once = Once::new();
+
+/// Runs a function, and returns the messages it logged from the current
+/// thread at some level.
+fn captured<T, F: FnOnce() -> T>(level: Level, f: F) -> (T, Vec<String>) {
+    INIT.call_once(|| {
+        log::set_logger(&LOGGER).unwrap();
+        log::set_max_level(LevelFilter::Debug);
+    });
+
+    RECORDS.with(|records| records.borrow_mut().clear());
+    let result = f();
+    let messages = RECORDS.with(|records| {
+        records
+            .borrow_mut()
+            .drain(..)
+            .filter(|(record_level, _)| *record_level == level)
+            .map(|(_, message)| message)
+            .collect()
+    });
+    (result, messages)
+}
+
+/// A cube whose coordinates are slightly off has a trivial symmetry group,
+/// which is warned about whether or not it's allowed, and the warnings are
+/// counted.
+#[test]
+fn trivial_group() {
+    let mut cube = Concrete::hypercube(4);
+    for (x, offset) in cube.vertices[0].iter_mut().zip(&[1e-4, 2e-4, 3e-4]) {
+        *x += offset;
+    }
+    let vertices = cube.vertices.to_vec();
+
+    let (result, warnings) = captured(Level::Warn, || {
+        cube.try_faceting_with_stats(vertices.clone(), GroupEnum::Chiral(false), &FacetingOptions::default())
+    });
+    let err = result.unwrap_err();
+    assert!(matches!(err, FacetingError::TrivialGroup { .. }));
+    assert_eq!(warnings, vec![err.to_string()]);
+
+    let options = FacetingOptions {
+        allow_trivial_group: true,
+        noble: Some(1),
+        ..Default::default()
+    };
+    let (result, warnings) = captured(Level::Warn, || {
+        cube.try_faceting_with_stats(vertices, GroupEnum::Chiral(false), &options)
+    });
+    let (_, stats) = result.unwrap();
+    assert_eq!(warnings, vec![err.to_string()]);
+    assert_eq!(stats.warning_count, 1);
+}
+
+/// Every hyperplane orbit is logged at the debug level.
+#[test]
+fn hyperplane_orbits() {
+    let mut cube = Concrete::hypercube(4);
+    let vertices = cube.vertices.to_vec();
+    let (result, messages) = captured(Level::Debug, || {
+        cube.try_faceting_with_stats(vertices, GroupEnum::Chiral(false), &FacetingOptions::default())
+    });
+    let (_, stats) = result.unwrap();
+    let orbits: Vec<_> = messages.iter().filter(|message| message.starts_with("hyperplane orbit")).collect();
+    assert_eq!(orbits.len(), stats.hyperplane_orbit_count);
+    assert_eq!(stats.warning_count, 0);
+}
+
+/// The stages are logged at the info level, and each hyperplane orbit faceted
+/// is reported to the progress sink.
+#[test]
+fn progress() {
+    let mut cube = Concrete::hypercube(4);
+    let vertices = cube.vertices.to_vec();
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let sink_reports = Arc::clone(&reports);
+    let options = FacetingOptions {
+        progress: Some(ProgressSink::new(move |progress| sink_reports.lock().unwrap().push(progress))),
+        ..Default::default()
+    };
+
+    let (result, messages) = captured(Level::Info, || {
+        cube.try_faceting_with_stats(vertices, GroupEnum::Chiral(false), &options)
+    });
+    let (_, stats) = result.unwrap();
+    assert!(messages.iter().any(|message| message == "Enumerating hyperplanes..."));
+    assert_eq!(messages.last().map(String::as_str), Some("Faceting complete"));
+
+    let total = stats.hyperplane_orbit_count;
+    let faceted: Vec<_> = reports
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|progress| match *progress {
+            FacetingProgress::HyperplaneFacets { done, total } => Some((done, total)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(faceted, (1..=total).map(|done| (done, total)).collect::<Vec<_>>());
+}
@@ -0,0 +1,174 @@
+//! Enumerates the polygons on some symmetric arrangements of points.
+
+use miratope_core::{
+    abs::{Abstract, Ranked},
+    conc::{faceting::GroupEnum, polygon_faceting::PolygonFacetingOptions, Concrete},
+    float::Float,
+    geometry::Point,
+    Polytope,
+};
+
+/// Euler's totient function.
+fn totient(n: usize) -> usize {
+    (1..=n).filter(|k| gcd(n, *k) == 1).count()
+}
+
+/// The greatest common divisor of two numbers.
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Returns the facetings of a polygon under its full symmetry group.
+fn facet(poly: &Concrete, options: PolygonFacetingOptions) -> Vec<Concrete> {
+    poly.polygon_facetings(GroupEnum::Chiral(false), &options)
+}
+
+/// Returns the number of components of a polygon.
+fn component_count(poly: &Concrete) -> usize {
+    let mut poly = poly.clone();
+    poly.element_sort();
+    poly.defiss().len()
+}
+
+/// The facetings of a regular polygon are the star polygons `{n/d}` with
+/// `d < n/2`, and those that aren't compounds are counted by the totient.
+#[test]
+fn regular() {
+    for n in 3..=12 {
+        let poly = Concrete::polygon(n);
+
+        let all = facet(&poly, Default::default());
+        assert_eq!(all.len(), (n - 1) / 2, "{}-gon", n);
+
+        let connected = facet(
+            &poly,
+            PolygonFacetingOptions {
+                connected_only: true,
+                ..Default::default()
+            },
+        );
+        assert_eq!(connected.len(), totient(n) / 2, "{}-gon", n);
+
+        for d in 1..=(n - 1) / 2 {
+            let wound = facet(
+                &poly,
+                PolygonFacetingOptions {
+                    winding: Some(d),
+                    ..Default::default()
+                },
+            );
+            assert_eq!(wound.len(), 1, "{{{}/{}}}", n, d);
+            assert_eq!(wound[0].el_count(2), n);
+            assert_eq!(component_count(&wound[0]), gcd(n, d));
+            assert_eq!(wound[0].meta.properties["winding"], d.to_string());
+        }
+    }
+}
+
+/// Fixing the edge length of the facetings of a hexagon.
+#[test]
+fn hexagon_edge_length() {
+    let poly = Concrete::polygon(6);
+    let side = (&poly.vertices[0] - &poly.vertices[1]).norm();
+    let facet_with = |edge_length| {
+        facet(
+            &poly,
+            PolygonFacetingOptions {
+                edge_length: Some(edge_length),
+                ..Default::default()
+            },
+        )
+    };
+
+    let hexagons = facet_with(side);
+    assert_eq!(hexagons.len(), 1);
+    assert_eq!(component_count(&hexagons[0]), 1);
+
+    let hexagrams = facet_with(side * 3f64.fsqrt());
+    assert_eq!(hexagrams.len(), 1);
+    assert_eq!(component_count(&hexagrams[0]), 2);
+
+    assert!(facet_with(2.0 * side).is_empty());
+}
+
+/// A ring of six points, at the vertices of a truncated triangle.
+fn truncated_triangle() -> Concrete {
+    let vertices = (0..6)
+        .map(|k| {
+            let angle = f64::TAU * (k / 2) as f64 / 3.0 + if k % 2 == 0 { -0.3 } else { 0.3 };
+            Point::from(vec![angle.fcos(), angle.fsin()])
+        })
+        .collect();
+    Concrete::new(vertices, Abstract::polygon(6))
+}
+
+/// The truncated triangle, the two triangles on the outer vertex pairs, and
+/// two hexagons joining the vertex pairs in other ways.
+#[test]
+fn truncated_triangle_ring() {
+    let poly = truncated_triangle();
+    let all = facet(&poly, Default::default());
+    assert_eq!(all.len(), 4);
+
+    let windings: Vec<_> = all.iter().map(|p| p.meta.properties["winding"].as_str()).collect();
+    assert_eq!(windings.iter().filter(|w| **w == "1").count(), 2);
+    assert_eq!(windings.iter().filter(|w| **w == "2").count(), 2);
+
+    let every_vertex = facet(
+        &poly,
+        PolygonFacetingOptions {
+            every_vertex: true,
+            ..Default::default()
+        },
+    );
+    assert_eq!(every_vertex.len(), 4);
+
+    let hamiltonian = facet(
+        &poly,
+        PolygonFacetingOptions {
+            every_vertex: true,
+            connected_only: true,
+            winding: Some(1),
+            ..Default::default()
+        },
+    );
+    assert_eq!(hamiltonian.len(), 2);
+    assert!(hamiltonian.iter().all(|p| p.el_count(2) == 6 && component_count(p) == 1));
+}
+
+/// Under the rotations of order 3, the vertices of a hexagon have two
+/// inscribed triangles, whose compound is only output if asked for.
+#[test]
+fn hexagon_compounds() {
+    let poly = Concrete::polygon(6);
+    let vertex_map: Vec<Vec<usize>> = (0..3).map(|k| (0..6).map(|v| (v + 2 * k) % 6).collect()).collect();
+    let facet_with = |options| poly.polygon_facetings(GroupEnum::VertexMap(vertex_map.clone().into()), &options);
+
+    // The triangles, the hexagon, and two hexagons through the center.
+    assert_eq!(facet_with(PolygonFacetingOptions::default()).len(), 5);
+    let with_compounds = facet_with(PolygonFacetingOptions {
+        include_compounds: true,
+        ..Default::default()
+    });
+    assert_eq!(with_compounds.len(), 6);
+
+    // The hexagons through the center don't wind around it.
+    let wound = facet_with(PolygonFacetingOptions {
+        winding: Some(1),
+        ..Default::default()
+    });
+    assert_eq!(wound.len(), 3);
+    assert_eq!(
+        facet_with(PolygonFacetingOptions {
+            every_vertex: true,
+            include_compounds: true,
+            ..Default::default()
+        })
+        .len(),
+        4
+    );
+}
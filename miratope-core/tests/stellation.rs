@@ -0,0 +1,49 @@
+//! Finds the stellations of some polytopes by faceting their duals.
+
+use miratope_core::{
+    abs::Ranked,
+    conc::{faceting::GroupEnum, meta::PolytopeMeta, Concrete},
+    file::FromFile,
+    Polytope,
+};
+
+/// The relative tolerance used to identify polytopes.
+const TOLERANCE: f64 = 1e-6;
+
+/// Returns the element counts of a polytope from the vertices to the facets.
+fn counts(poly: &Concrete) -> Vec<usize> {
+    (1..poly.rank()).map(|r| poly.el_count(r)).collect()
+}
+
+/// The stellations of the icosahedron include the great icosahedron and the
+/// compound of five octahedra. The faces of the compound lie in pairs in the
+/// face planes of the icosahedron, so its facets are pairs of triangles.
+#[test]
+fn icosahedron() {
+    let mut ike = Concrete::from_off(include_str!("fixtures/ike.off"))
+        .unwrap()
+        .with_meta(PolytopeMeta::named("ike"));
+    let stellations = ike.stellations_via_dual(GroupEnum::Chiral(false), &Default::default());
+
+    for (idx, stellation) in stellations.iter().enumerate() {
+        stellation.verify_facet_planarity(1e-5).unwrap();
+        assert_eq!(stellation.meta.name.as_deref(), Some(format!("stellation #{} of ike", idx).as_str()));
+        assert!(stellation.meta.properties["dual of"].starts_with("faceting #"));
+    }
+
+    let gike = stellations
+        .iter()
+        .find(|p| matches!(p.identify_uniform(TOLERANCE), Some(id) if id.name == "gike"))
+        .expect("the great icosahedron wasn't found");
+    assert_eq!(counts(gike), vec![12, 30, 20]);
+
+    let compound = stellations.iter().any(|p| {
+            let mut p = (*p).clone();
+            p.element_sort();
+            let components = p.defiss();
+            counts(&p) == vec![30, 60, 20]
+                && components.len() == 5
+                && components.iter().all(|c| counts(c) == vec![6, 12, 8])
+    });
+    assert!(compound, "the compound of five octahedra wasn't found");
+}
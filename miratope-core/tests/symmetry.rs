@@ -0,0 +1,227 @@
+//! Tests the canonical representatives of vertex orbits, the detection of
+//! mirror compounds, the bounds on the symmetries from the distances between
+//! vertices, the orbits of the elements under the symmetries, and the
+//! stabilizers of sets of vertices.
+
+use miratope_core::{
+    abs::Ranked,
+    conc::{
+        faceting::GroupEnum,
+        symmetry::{induced_stabilizer, restrict_vertex_map, stabilizer, VertexPermutation},
+        Concrete, ConcretePolytope,
+    },
+    file::FromFile,
+    float::Float,
+    geometry::{CenterPolicy, GeomContext, Matrix, Point},
+    Polytope,
+};
+
+/// The 600-cell has a single vertex orbit, whose representative is the only
+/// vertex in the dominant Weyl chamber.
+#[test]
+fn hexacosichoron() {
+    let mut poly = Concrete::from_off(include_str!("fixtures/ex.off")).unwrap();
+    let (group, vertex_map) = poly.get_symmetry_group().unwrap();
+    let group = group.cache();
+    let vertex_map: Vec<VertexPermutation> = vertex_map.into_iter().map(Into::into).collect();
+
+    let simple_roots = group.simple_roots();
+    assert_eq!(group.positive_roots().len(), 60);
+    assert_eq!(simple_roots.len(), 4);
+
+    let representatives = poly.orbit_representatives(&group, &vertex_map);
+    assert_eq!(representatives.len(), 1);
+    let (rep, size) = &representatives[0];
+    assert_eq!(*size, 120);
+
+    // The vertices are those of the usual 600-cell scaled by the golden ratio,
+    // and the one with the largest first coordinate is the representative.
+    let phi = (1.0 + 5f64.sqrt()) / 2.0;
+    let expected = Point::from_vec(vec![phi, 0.0, 0.0, 0.0]);
+    assert!((rep - &expected).norm() < f64::EPS);
+
+    for (idx, root) in simple_roots.iter().enumerate() {
+        assert!(rep.dot(root) > -f64::EPS);
+        for other in &simple_roots[idx + 1..] {
+            assert!(root.dot(other) < f64::EPS);
+        }
+    }
+}
+
+/// The stella octangula is the compound of a tetrahedron and its image under
+/// the central inversion.
+#[test]
+fn stella_octangula() {
+    let tet = Concrete::simplex(4);
+    let inversion = -Matrix::identity(3, 3);
+    let stella = tet.with_mirror_compound(&inversion);
+    assert_eq!(stella.vertices.len(), 8);
+    assert_eq!(stella.defiss().len(), 2);
+
+    let (component, isometry) = stella.is_enantiomorph_compound().unwrap();
+    assert_eq!(component.vertices.len(), 4);
+    assert!(isometry.determinant() < 0.0);
+
+    assert!(tet.is_enantiomorph_compound().is_none());
+    assert!(Concrete::hypercube(4).is_enantiomorph_compound().is_none());
+}
+
+/// A snub cube and its mirror image form a compound of enantiomorphs, while
+/// two rotated copies of the same snub cube don't.
+#[test]
+fn snub_cubes() {
+    let snic = Concrete::from_off(include_str!("fixtures/snic.off")).unwrap();
+    let reflection = Matrix::from_diagonal(&Point::from_vec(vec![-1.0, 1.0, 1.0]));
+    let compound = snic.with_mirror_compound(&reflection);
+    assert_eq!(compound.vertices.len(), 48);
+
+    let (_, isometry) = compound.is_enantiomorph_compound().unwrap();
+    assert!(isometry.determinant() < 0.0);
+
+    let (sin, cos) = 0.3f64.sin_cos();
+    let rotation = Matrix::from_row_slice(3, 3, &[cos, -sin, 0.0, sin, cos, 0.0, 0.0, 0.0, 1.0]);
+    let rotated = snic.with_mirror_compound(&rotation);
+    assert!(rotated.is_enantiomorph_compound().is_none());
+}
+
+/// Reflecting a symmetric polytope gives it back, so every vertex is merged.
+#[test]
+fn mirror_compound_merges() {
+    let cube = Concrete::hypercube(4);
+    let reflection = Matrix::from_diagonal(&Point::from_vec(vec![-1.0, 1.0, 1.0]));
+    let compound = cube.with_mirror_compound(&reflection);
+    assert_eq!(compound.vertices.len(), 8);
+    assert_eq!(compound.el_count(3), 12);
+}
+
+/// A cube whose first vertex is slightly off, in no particular direction.
+fn noisy_cube() -> Concrete {
+    let mut cube = Concrete::hypercube(4);
+    for (x, offset) in cube.vertices[0].iter_mut().zip(&[1e-4, 2e-4, 3e-4]) {
+        *x += offset;
+    }
+    cube
+}
+
+/// The distances between the vertices of a polytope bound its symmetries, even
+/// if its coordinates are slightly off.
+#[test]
+fn distance_spectrum() {
+    let spectrum = Concrete::hypercube(4).distance_spectrum(1e-3);
+    assert_eq!(spectrum.classes.len(), 1);
+    assert_eq!(spectrum.automorphism_bound, 48);
+    assert!(spectrum.residual < 1e-12);
+
+    let spectrum = Concrete::simplex(4).distance_spectrum(1e-3);
+    assert_eq!(spectrum.automorphism_bound, 24);
+
+    let mut cube = noisy_cube();
+    assert_eq!(cube.get_symmetry_group().unwrap().1.len(), 1);
+    let spectrum = cube.distance_spectrum(1e-3);
+    assert_eq!(spectrum.automorphism_bound, 48);
+    assert!(spectrum.residual > 1e-5 && spectrum.residual < 1e-3);
+
+    // With a smaller tolerance, the noise shows.
+    assert_eq!(cube.distance_spectrum(1e-6).automorphism_bound, 1);
+}
+
+/// Computing the symmetries of a polytope in a geometry context compares the
+/// vertices with its tolerance, and centers the polytope as it says.
+#[test]
+fn geometry_context() {
+    let mut cube = noisy_cube();
+    let context = GeomContext::with_tolerance(1e-3);
+    assert_eq!(cube.get_symmetry_group_in(Some(&context)).unwrap().1.len(), 48);
+    assert_eq!(cube.get_symmetry_group_in(None).unwrap().1.len(), 1);
+
+    let mut moved = Concrete::hypercube(4);
+    for vertex in moved.vertices.iter_mut() {
+        vertex[0] += 2.0;
+    }
+    let context = GeomContext {
+        center: CenterPolicy::Gravicenter,
+        ..Default::default()
+    };
+    let (group, vertex_map) = moved.get_symmetry_group_in(Some(&context)).unwrap();
+    assert_eq!(vertex_map.len(), 48);
+    assert_eq!(moved.get_vertex_map_in(group.cache(), Some(&context)), vertex_map);
+    assert!(!matches!(moved.get_symmetry_group(), Some((_, vertex_map)) if vertex_map.len() == 48));
+}
+
+/// The cuboctahedron is vertex- and edge-transitive, but its triangles and
+/// squares are in different orbits. The truncated octahedron is only
+/// vertex-transitive, and the noble facetings of the cuboctahedron are the ones
+/// certified as such.
+#[test]
+fn transitivity() {
+    let mut cube = Concrete::hypercube(4);
+    cube.element_sort();
+    let mut co = cube.truncate_with(vec![1], vec![0.0, 1.0]);
+    assert_eq!(co.vertices.len(), 12);
+    let report = co.transitivity().unwrap();
+    assert_eq!(report.group_order, 48);
+    assert_eq!(report.orbit_counts, vec![1, 1, 2]);
+    assert!(report.is_vertex_transitive());
+    assert!(report.is_edge_transitive());
+    assert!(!report.is_facet_transitive());
+    assert!(!report.is_noble());
+    assert_eq!(report.orbit_count(0), None);
+    assert_eq!(report.orbit_count(3), Some(2));
+    assert_eq!(report.orbit_count(4), None);
+    assert_eq!(report.to_string(), "symmetry group of order 48, with element orbits [1, 1, 2]");
+
+    let mut toe = Concrete::from_off(include_str!("fixtures/toe.off")).unwrap();
+    let report = toe.transitivity().unwrap();
+    assert_eq!(report.orbit_counts, vec![1, 2, 2]);
+    assert!(report.is_vertex_transitive());
+    assert!(!report.is_edge_transitive());
+
+    let report = cube.transitivity().unwrap();
+    assert!(report.is_noble() && report.is_edge_transitive());
+
+    let vertices = co.vertices.to_vec();
+    let facetings = co.faceting(vertices, GroupEnum::Chiral(false), &Default::default());
+    assert!(facetings.iter().any(|faceting| faceting.verify_noble().is_noble()));
+    assert!(facetings.iter().any(|faceting| !faceting.verify_noble().is_noble()));
+    for faceting in &facetings {
+        let report = faceting.polytope.clone().transitivity().unwrap();
+        assert_eq!(report.is_noble(), faceting.verify_noble().is_noble());
+    }
+}
+
+/// The stabilizers of a face and an edge of a cube have orders 8 and 4. The
+/// symmetries fixing an edge only act in two ways on its vertices, as the
+/// reflection through the edge fixes both of them.
+#[test]
+fn stabilizers() {
+    let mut cube = Concrete::hypercube(4);
+    let vertex_map: Vec<VertexPermutation> = cube.get_symmetry_group().unwrap().1.into_iter().map(Into::into).collect();
+    assert_eq!(vertex_map.len(), 48);
+
+    let sorted = |vertices: &[usize]| {
+        let mut vertices = vertices.to_vec();
+        vertices.sort_unstable();
+        vertices
+    };
+    let face = sorted(&cube.abs.element_vertices(3, 0).unwrap());
+    let edge = sorted(&cube.abs.element_vertices(2, 0).unwrap());
+
+    let face_stabilizer = stabilizer(&vertex_map, &face);
+    assert_eq!(face_stabilizer.len(), 8);
+    for row in &face_stabilizer {
+        assert_eq!(row.apply(&face).into_vec(), face);
+    }
+    assert_eq!(induced_stabilizer(&vertex_map, &face).len(), 8);
+    assert_eq!(restrict_vertex_map(&vertex_map, &face).len(), 8);
+
+    assert_eq!(stabilizer(&vertex_map, &edge).len(), 4);
+    let induced = induced_stabilizer(&vertex_map, &edge);
+    assert_eq!(induced.len(), 4);
+    assert!(induced.iter().all(|row| row.len() == 2));
+    assert_eq!(restrict_vertex_map(&vertex_map, &edge), vec![VertexPermutation(vec![0, 1]), VertexPermutation(vec![1, 0])]);
+
+    // Every symmetry fixes the whole cube, and those fixing a vertex permute
+    // its three edges.
+    assert_eq!(stabilizer(&vertex_map, &(0..8).collect::<Vec<_>>()).len(), 48);
+    assert_eq!(stabilizer(&vertex_map, &[0]).len(), 6);
+}
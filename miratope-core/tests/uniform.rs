@@ -0,0 +1,71 @@
+//! Identifies the uniform polytopes in the library.
+
+use std::{fs, path::PathBuf};
+
+use miratope_core::{
+    conc::{uniform::UNIFORM_TABLE, Concrete, ConcretePolytope},
+    file::FromFile,
+    Polytope,
+};
+
+/// The relative tolerance used to identify polytopes.
+const TOLERANCE: f64 = 1e-6;
+
+/// Returns the OFF files within a folder in the library.
+fn off_files(folder: &str) -> Vec<PathBuf> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../lib").join(folder);
+    fs::read_dir(path)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension() == Some("off".as_ref()))
+        .collect()
+}
+
+/// Every polytope in the table identifies as itself when loaded from the
+/// library.
+#[test]
+fn library() {
+    let mut files: Vec<_> = ["Omnitruncate", "Quasiregular", "Regular", "Snub", "Trapezivert", "Truncate"]
+        .iter()
+        .flat_map(|folder| off_files(&format!("3D/uniform/{}", folder)))
+        .chain(off_files("4D/convex uniform"))
+        .collect();
+    let regular = off_files("4D/regular");
+    for name in &["Pentachoron", "Tesseract", "Hexadecachoron", "Icositetrachoron", "Hecatonicosachoron", "Hexacosichoron"] {
+        files.extend(regular.iter().filter(|path| path.file_stem().unwrap() == *name).cloned());
+    }
+    assert_eq!(files.len(), UNIFORM_TABLE.len());
+
+    let mut found = vec![false; UNIFORM_TABLE.len()];
+    for path in files {
+        let poly = Concrete::from_off(&fs::read_to_string(&path).unwrap()).unwrap();
+        let name = path.file_stem().unwrap().to_str().unwrap();
+        let id = poly.identify_uniform(TOLERANCE).unwrap_or_else(|| panic!("{} wasn't identified", name));
+        assert_eq!(id.name, name);
+        found[id.index] = true;
+    }
+    assert!(found.into_iter().all(|found| found));
+}
+
+/// The pseudo-rhombicuboctahedron has the same invariants as the
+/// rhombicuboctahedron, but it isn't uniform.
+#[test]
+fn pseudo_rhombicuboctahedron() {
+    let sirco = Concrete::from_off(include_str!("../src/conc/uniform/sirco.off")).unwrap();
+    let pseudo = Concrete::from_off(include_str!("fixtures/esquigybcu.off")).unwrap();
+    assert_eq!(sirco.vertex_configuration(0), pseudo.vertex_configuration(0));
+    assert!((sirco.uniform_circumradius(TOLERANCE).unwrap() - pseudo.uniform_circumradius(TOLERANCE).unwrap()).abs() < TOLERANCE);
+
+    assert_eq!(sirco.identify_uniform(TOLERANCE).unwrap().name, "sirco");
+    assert!(pseudo.identify_uniform(TOLERANCE).is_none());
+}
+
+/// Polytopes that aren't uniform, or are but aren't in the table, aren't
+/// identified.
+#[test]
+fn not_uniform() {
+    let prism = Concrete::polygon(5).prism_with(2.0);
+    assert!(prism.identify_uniform(TOLERANCE).is_none());
+    assert!(Concrete::uniform_prism(5, 1).identify_uniform(TOLERANCE).is_none());
+    assert!(Concrete::polygon(5).identify_uniform(TOLERANCE).is_none());
+}
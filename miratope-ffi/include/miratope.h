@@ -0,0 +1,204 @@
+#ifndef MIRATOPE_H
+#define MIRATOPE_H
+
+#include <stdbool.h>
+#include <stddef.h>
+
+/**
+ * The status codes returned by the functions of the C interface.
+ */
+typedef enum MiratopeStatus {
+  /**
+   * The function succeeded.
+   */
+  MIRATOPE_STATUS_OK = 0,
+  /**
+   * A pointer that must not be null was null.
+   */
+  MIRATOPE_STATUS_NULL_POINTER = 1,
+  /**
+   * The input doesn't describe a valid polytope.
+   */
+  MIRATOPE_STATUS_INVALID_INPUT = 2,
+  /**
+   * The faceting was aborted.
+   */
+  MIRATOPE_STATUS_FACETING_ERROR = 3,
+  /**
+   * An index was out of range.
+   */
+  MIRATOPE_STATUS_OUT_OF_RANGE = 4,
+  /**
+   * A buffer was too small for the list to be copied into it.
+   */
+  MIRATOPE_STATUS_BUFFER_TOO_SMALL = 5,
+  /**
+   * The library panicked. This is a bug.
+   */
+  MIRATOPE_STATUS_PANIC = 6,
+} MiratopeStatus;
+
+/**
+ * An opaque handle to the facetings of a polytope.
+ */
+typedef struct MiratopeFacetings MiratopeFacetings;
+
+/**
+ * An opaque handle to a polytope.
+ */
+typedef struct MiratopePolytope MiratopePolytope;
+
+/**
+ * The options of the faceting that can be set through the C interface. Get
+ * the defaults from [`miratope_faceting_options_default`].
+ */
+typedef struct MiratopeFacetingOptions {
+  /**
+   * Whether to facet under the rotation group only.
+   */
+  bool chiral;
+  /**
+   * The exact number of facet orbits, or 0 for any number.
+   */
+  size_t noble;
+  /**
+   * The minimum edge length, or 0 for no minimum.
+   */
+  double min_edge_length;
+  /**
+   * The maximum edge length, or 0 for no maximum.
+   */
+  double max_edge_length;
+  /**
+   * Whether to also output the compounds.
+   */
+  bool include_compounds;
+  /**
+   * Whether to leave out the facetings with hemi facets.
+   */
+  bool exclude_hemis;
+} MiratopeFacetingOptions;
+
+/**
+ * Returns a short description of a status code. The string is static and
+ * must not be freed.
+ */
+const char *miratope_status_message(MiratopeStatus status);
+
+/**
+ * Reads a polytope from the contents of an OFF file.
+ *
+ * # Safety
+ * `off` must be a null-terminated string, and `out` must be valid for a
+ * write.
+ */
+MiratopeStatus miratope_polytope_from_off(const char *off, MiratopePolytope **out);
+
+/**
+ * Builds a polyhedron from its vertices and faces. The coordinates of the
+ * vertices are given one after another, `dim` of them each, where `dim` is
+ * at least 3. The faces are given by the indices of their vertices in cyclic
+ * order, one face after another, with the number of vertices of each face in
+ * `face_sizes`.
+ *
+ * # Safety
+ * `vertices` must be valid for `vertex_count * dim` reads, `face_sizes` for
+ * `face_count` reads, `faces` for as many reads as the sum of the face sizes,
+ * and `out` for a write.
+ */
+MiratopeStatus miratope_polyhedron_from_faces(const double *vertices,
+                                              size_t vertex_count,
+                                              size_t dim,
+                                              const size_t *faces,
+                                              const size_t *face_sizes,
+                                              size_t face_count,
+                                              MiratopePolytope **out);
+
+/**
+ * Frees a polytope. Freeing a null pointer does nothing.
+ *
+ * # Safety
+ * The handle must be null or valid, and must not be used afterwards.
+ */
+void miratope_polytope_free(MiratopePolytope *poly);
+
+/**
+ * Writes the default faceting options.
+ *
+ * # Safety
+ * `out` must be valid for a write.
+ */
+MiratopeStatus miratope_faceting_options_default(MiratopeFacetingOptions *out);
+
+/**
+ * Facets a polytope under its symmetry group, with some options, or the
+ * defaults if they're null.
+ *
+ * # Safety
+ * The polytope handle must be valid, `options` must be null or valid, and
+ * `out` must be valid for a write.
+ */
+MiratopeStatus miratope_facet(const MiratopePolytope *poly,
+                              const MiratopeFacetingOptions *options,
+                              MiratopeFacetings **out);
+
+/**
+ * Writes the number of facetings found.
+ *
+ * # Safety
+ * The handle must be valid, and `count` must be valid for a write.
+ */
+MiratopeStatus miratope_facetings_count(const MiratopeFacetings *facetings, size_t *count);
+
+/**
+ * Frees a list of facetings. Freeing a null pointer does nothing.
+ *
+ * # Safety
+ * The handle must be null or valid, and must not be used afterwards.
+ */
+void miratope_facetings_free(MiratopeFacetings *facetings);
+
+/**
+ * Copies the number of elements of each rank of a faceting, from the
+ * minimal element up to the maximal one.
+ *
+ * # Safety
+ * The handle must be valid, `counts` must be valid for `capacity` writes,
+ * and `len` for a write.
+ */
+MiratopeStatus miratope_faceting_element_counts(const MiratopeFacetings *facetings,
+                                                size_t idx,
+                                                size_t *counts,
+                                                size_t capacity,
+                                                size_t *len);
+
+/**
+ * Copies the coordinates of the vertices of a faceting, one vertex after
+ * another. The dimension is the number of coordinates divided by the number
+ * of vertices.
+ *
+ * # Safety
+ * The handle must be valid, `coordinates` must be valid for `capacity`
+ * writes, and `len` for a write.
+ */
+MiratopeStatus miratope_faceting_vertices(const MiratopeFacetings *facetings,
+                                          size_t idx,
+                                          double *coordinates,
+                                          size_t capacity,
+                                          size_t *len);
+
+/**
+ * Copies the indices of the vertices of a facet of a faceting.
+ *
+ * # Safety
+ * The handle must be valid, `vertices` must be valid for `capacity` writes,
+ * and `len` for a write.
+ */
+MiratopeStatus miratope_faceting_facet_vertices(const MiratopeFacetings *facetings,
+                                                size_t idx,
+                                                size_t facet,
+                                                size_t *vertices,
+                                                size_t capacity,
+                                                size_t *len);
+
+#endif /* MIRATOPE_H */
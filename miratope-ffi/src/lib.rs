@@ -0,0 +1,536 @@
+#![deny(
+    missing_docs,
+    nonstandard_style,
+    unused_parens,
+    unused_qualifications,
+    rust_2018_idioms,
+    rust_2018_compatibility,
+    future_incompatible
+)]
+
+//! A small C interface to the faceting algorithm of
+//! [`miratope_core`], so that it can be driven from other languages. It's
+//! built as a shared library on its own, so that the other users of
+//! `miratope-core` don't have to link one.
+//!
+//! Polytopes and facetings are passed around as opaque handles, which must be
+//! freed with [`miratope_polytope_free`] and [`miratope_facetings_free`]. Every
+//! function returns a [`MiratopeStatus`], and writes its actual output through
+//! pointers. Panics are caught before they reach the caller, and turned into
+//! [`MiratopeStatus::Panic`].
+//!
+//! Functions that copy out a list take a buffer and its capacity, and always
+//! write the length of the list to `len`. If the buffer is too small, nothing
+//! is copied and [`MiratopeStatus::BufferTooSmall`] is returned, so that the
+//! caller can call the function again with a big enough buffer.
+//!
+//! The header at `include/miratope.h` is generated with
+//!
+//! ```text
+//! cbindgen --config cbindgen.toml --output include/miratope.h
+//! ```
+//!
+//! from the `miratope-ffi` directory.
+
+use std::{
+    collections::HashMap,
+    ffi::CStr,
+    mem,
+    os::raw::c_char,
+    panic::{self, AssertUnwindSafe},
+    slice,
+};
+
+use vec_like::VecLike;
+
+use miratope_core::{
+    abs::{AbstractBuilder, Ranked, SubelementList, Subelements},
+    conc::{
+        faceting::{EdgeLengthFilter, FacetingOptions, FacetingResult, GroupEnum},
+        Concrete,
+    },
+    file::FromFile,
+    geometry::Point,
+};
+
+/// The status codes returned by the functions of the C interface.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MiratopeStatus {
+    /// The function succeeded.
+    Ok = 0,
+
+    /// A pointer that must not be null was null.
+    NullPointer = 1,
+
+    /// The input doesn't describe a valid polytope.
+    InvalidInput = 2,
+
+    /// The faceting was aborted.
+    FacetingError = 3,
+
+    /// An index was out of range.
+    OutOfRange = 4,
+
+    /// A buffer was too small for the list to be copied into it.
+    BufferTooSmall = 5,
+
+    /// The library panicked. This is a bug.
+    Panic = 6,
+}
+
+/// An opaque handle to a polytope.
+pub struct MiratopePolytope(Concrete);
+
+/// An opaque handle to the facetings of a polytope.
+pub struct MiratopeFacetings(Vec<FacetingResult>);
+
+/// The options of the faceting that can be set through the C interface. Get
+/// the defaults from [`miratope_faceting_options_default`].
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct MiratopeFacetingOptions {
+    /// Whether to facet under the rotation group only.
+    pub chiral: bool,
+
+    /// The exact number of facet orbits, or 0 for any number.
+    pub noble: usize,
+
+    /// The minimum edge length, or 0 for no minimum.
+    pub min_edge_length: f64,
+
+    /// The maximum edge length, or 0 for no maximum.
+    pub max_edge_length: f64,
+
+    /// Whether to also output the compounds.
+    pub include_compounds: bool,
+
+    /// Whether to leave out the facetings with hemi facets.
+    pub exclude_hemis: bool,
+}
+
+impl From<&MiratopeFacetingOptions> for FacetingOptions {
+    fn from(options: &MiratopeFacetingOptions) -> Self {
+        let positive = |x: f64| if x > 0.0 { Some(x) } else { None };
+
+        Self {
+            noble: if options.noble == 0 { None } else { Some(options.noble) },
+            edge_length: EdgeLengthFilter::new(
+                positive(options.min_edge_length),
+                positive(options.max_edge_length),
+                Vec::new(),
+            ),
+            include_compounds: options.include_compounds,
+            exclude_hemis: options.exclude_hemis,
+            ..Default::default()
+        }
+    }
+}
+
+/// Runs a function, turning its panics into [`MiratopeStatus::Panic`].
+fn guard<F: FnOnce() -> MiratopeStatus>(f: F) -> MiratopeStatus {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(MiratopeStatus::Panic)
+}
+
+/// Copies a list into a buffer if it fits, and writes its length.
+///
+/// # Safety
+/// The buffer must be valid for `capacity` writes, and `len` must be valid.
+unsafe fn copy_out<T: Copy>(list: &[T], buffer: *mut T, capacity: usize, len: *mut usize) -> MiratopeStatus {
+    if len.is_null() {
+        return MiratopeStatus::NullPointer;
+    }
+    *len = list.len();
+
+    if list.len() > capacity {
+        MiratopeStatus::BufferTooSmall
+    } else if list.is_empty() {
+        MiratopeStatus::Ok
+    } else if buffer.is_null() {
+        MiratopeStatus::NullPointer
+    } else {
+        slice::from_raw_parts_mut(buffer, list.len()).copy_from_slice(list);
+        MiratopeStatus::Ok
+    }
+}
+
+/// Returns the length of a buffer with some number of elements of a type, as
+/// long as the number didn't overflow and the buffer's size in bytes fits in
+/// an `isize`, as [`slice::from_raw_parts`] requires.
+fn checked_len<T>(count: Option<usize>) -> Option<usize> {
+    count.filter(|&count| {
+        count
+            .checked_mul(mem::size_of::<T>())
+            .map_or(false, |bytes| bytes <= isize::MAX as usize)
+    })
+}
+
+/// Returns a faceting by its index.
+///
+/// # Safety
+/// The handle must be null or valid.
+unsafe fn faceting<'a>(facetings: *const MiratopeFacetings, idx: usize) -> Result<&'a Concrete, MiratopeStatus> {
+    let facetings = facetings.as_ref().ok_or(MiratopeStatus::NullPointer)?;
+    facetings
+        .0
+        .get(idx)
+        .map(|faceting| &faceting.polytope)
+        .ok_or(MiratopeStatus::OutOfRange)
+}
+
+/// Returns a short description of a status code. The string is static and
+/// must not be freed.
+#[no_mangle]
+pub extern "C" fn miratope_status_message(status: MiratopeStatus) -> *const c_char {
+    let message: &'static [u8] = match status {
+        MiratopeStatus::Ok => b"ok\0",
+        MiratopeStatus::NullPointer => b"null pointer\0",
+        MiratopeStatus::InvalidInput => b"invalid polytope\0",
+        MiratopeStatus::FacetingError => b"faceting aborted\0",
+        MiratopeStatus::OutOfRange => b"index out of range\0",
+        MiratopeStatus::BufferTooSmall => b"buffer too small\0",
+        MiratopeStatus::Panic => b"internal panic\0",
+    };
+    message.as_ptr().cast()
+}
+
+/// Reads a polytope from the contents of an OFF file.
+///
+/// # Safety
+/// `off` must be a null-terminated string, and `out` must be valid for a
+/// write.
+#[no_mangle]
+pub unsafe extern "C" fn miratope_polytope_from_off(off: *const c_char, out: *mut *mut MiratopePolytope) -> MiratopeStatus {
+    guard(|| {
+        if off.is_null() || out.is_null() {
+            return MiratopeStatus::NullPointer;
+        }
+
+        let src = match CStr::from_ptr(off).to_str() {
+            Ok(src) => src,
+            Err(_) => return MiratopeStatus::InvalidInput,
+        };
+        match Concrete::from_off(src) {
+            Ok(poly) => {
+                *out = Box::into_raw(Box::new(MiratopePolytope(poly)));
+                MiratopeStatus::Ok
+            }
+            Err(_) => MiratopeStatus::InvalidInput,
+        }
+    })
+}
+
+/// Builds a polyhedron from its vertices and faces. The coordinates of the
+/// vertices are given one after another, `dim` of them each, where `dim` is
+/// at least 3. The faces are given by the indices of their vertices in cyclic
+/// order, one face after another, with the number of vertices of each face in
+/// `face_sizes`.
+///
+/// # Safety
+/// `vertices` must be valid for `vertex_count * dim` reads, `face_sizes` for
+/// `face_count` reads, `faces` for as many reads as the sum of the face sizes,
+/// and `out` for a write.
+///
+/// Sizes whose product or sum overflow are rejected as invalid input.
+#[no_mangle]
+pub unsafe extern "C" fn miratope_polyhedron_from_faces(
+    vertices: *const f64,
+    vertex_count: usize,
+    dim: usize,
+    faces: *const usize,
+    face_sizes: *const usize,
+    face_count: usize,
+    out: *mut *mut MiratopePolytope,
+) -> MiratopeStatus {
+    guard(|| {
+        if vertices.is_null() || faces.is_null() || face_sizes.is_null() || out.is_null() {
+            return MiratopeStatus::NullPointer;
+        }
+        if dim < 3 {
+            return MiratopeStatus::InvalidInput;
+        }
+
+        // Sizes that overflow can't come from valid buffers.
+        let coordinate_count = match checked_len::<f64>(vertex_count.checked_mul(dim)) {
+            Some(count) => count,
+            None => return MiratopeStatus::InvalidInput,
+        };
+        let face_count = match checked_len::<usize>(Some(face_count)) {
+            Some(count) => count,
+            None => return MiratopeStatus::InvalidInput,
+        };
+        let coordinates = slice::from_raw_parts(vertices, coordinate_count);
+        let face_sizes = slice::from_raw_parts(face_sizes, face_count);
+
+        let index_count = face_sizes
+            .iter()
+            .try_fold(0usize, |total, &size| total.checked_add(size));
+        let faces = match checked_len::<usize>(index_count) {
+            Some(count) => slice::from_raw_parts(faces, count),
+            None => return MiratopeStatus::InvalidInput,
+        };
+
+        // Every pair of consecutive vertices in a face is an edge.
+        let mut edge_idx = HashMap::new();
+        let mut edges = SubelementList::new();
+        let mut face_list = SubelementList::new();
+        let mut start = 0;
+
+        for &size in face_sizes {
+            if size < 3 {
+                return MiratopeStatus::InvalidInput;
+            }
+            let face = &faces[start..start + size];
+            start += size;
+
+            let mut subs = Subelements::new();
+            for i in 0..size {
+                let (a, b) = (face[i], face[(i + 1) % size]);
+                if a >= vertex_count || b >= vertex_count || a == b {
+                    return MiratopeStatus::InvalidInput;
+                }
+
+                let edge = (a.min(b), a.max(b));
+                let idx = *edge_idx.entry(edge).or_insert_with(|| {
+                    edges.push(vec![edge.0, edge.1].into());
+                    edges.len() - 1
+                });
+                subs.push(idx);
+            }
+            face_list.push(subs);
+        }
+
+        let mut builder = AbstractBuilder::new();
+        builder.push_min();
+        builder.push_vertices(vertex_count);
+        builder.push(edges);
+        builder.push(face_list);
+        builder.push_max();
+
+        if builder.ranks().is_valid().is_err() {
+            return MiratopeStatus::InvalidInput;
+        }
+
+        let vertices = coordinates
+            .chunks(dim)
+            .map(Point::from_column_slice)
+            .collect();
+
+        // Safety: we just checked that the polytope is valid.
+        let abs = builder.build();
+        *out = Box::into_raw(Box::new(MiratopePolytope(Concrete::new(vertices, abs))));
+        MiratopeStatus::Ok
+    })
+}
+
+/// Frees a polytope. Freeing a null pointer does nothing.
+///
+/// # Safety
+/// The handle must be null or valid, and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn miratope_polytope_free(poly: *mut MiratopePolytope) {
+    if !poly.is_null() {
+        drop(Box::from_raw(poly));
+    }
+}
+
+/// Writes the default faceting options.
+///
+/// # Safety
+/// `out` must be valid for a write.
+#[no_mangle]
+pub unsafe extern "C" fn miratope_faceting_options_default(out: *mut MiratopeFacetingOptions) -> MiratopeStatus {
+    if out.is_null() {
+        return MiratopeStatus::NullPointer;
+    }
+
+    *out = MiratopeFacetingOptions {
+        chiral: false,
+        noble: 0,
+        min_edge_length: 0.0,
+        max_edge_length: 0.0,
+        include_compounds: false,
+        exclude_hemis: false,
+    };
+    MiratopeStatus::Ok
+}
+
+/// Facets a polytope under its symmetry group, with some options, or the
+/// defaults if they're null.
+///
+/// # Safety
+/// The polytope handle must be valid, `options` must be null or valid, and
+/// `out` must be valid for a write.
+#[no_mangle]
+pub unsafe extern "C" fn miratope_facet(
+    poly: *const MiratopePolytope,
+    options: *const MiratopeFacetingOptions,
+    out: *mut *mut MiratopeFacetings,
+) -> MiratopeStatus {
+    guard(|| {
+        let poly = match poly.as_ref() {
+            Some(poly) => poly,
+            None => return MiratopeStatus::NullPointer,
+        };
+        if out.is_null() {
+            return MiratopeStatus::NullPointer;
+        }
+
+        let (faceting_options, chiral) = match options.as_ref() {
+            Some(options) => (options.into(), options.chiral),
+            None => (FacetingOptions::default(), false),
+        };
+
+        let mut poly = poly.0.clone();
+        let vertices = poly.vertices.to_vec();
+        match poly.try_faceting_with_stats(vertices, GroupEnum::Chiral(chiral), &faceting_options) {
+            Ok((facetings, _)) => {
+                *out = Box::into_raw(Box::new(MiratopeFacetings(facetings)));
+                MiratopeStatus::Ok
+            }
+            Err(_) => MiratopeStatus::FacetingError,
+        }
+    })
+}
+
+/// Writes the number of facetings found.
+///
+/// # Safety
+/// The handle must be valid, and `count` must be valid for a write.
+#[no_mangle]
+pub unsafe extern "C" fn miratope_facetings_count(facetings: *const MiratopeFacetings, count: *mut usize) -> MiratopeStatus {
+    match (facetings.as_ref(), count.is_null()) {
+        (Some(facetings), false) => {
+            *count = facetings.0.len();
+            MiratopeStatus::Ok
+        }
+        _ => MiratopeStatus::NullPointer,
+    }
+}
+
+/// Frees a list of facetings. Freeing a null pointer does nothing.
+///
+/// # Safety
+/// The handle must be null or valid, and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn miratope_facetings_free(facetings: *mut MiratopeFacetings) {
+    if !facetings.is_null() {
+        drop(Box::from_raw(facetings));
+    }
+}
+
+/// Copies the number of elements of each rank of a faceting, from the
+/// minimal element up to the maximal one.
+///
+/// # Safety
+/// The handle must be valid, `counts` must be valid for `capacity` writes,
+/// and `len` for a write.
+#[no_mangle]
+pub unsafe extern "C" fn miratope_faceting_element_counts(
+    facetings: *const MiratopeFacetings,
+    idx: usize,
+    counts: *mut usize,
+    capacity: usize,
+    len: *mut usize,
+) -> MiratopeStatus {
+    guard(|| match faceting(facetings, idx) {
+        Ok(poly) => copy_out(&poly.el_count_iter().collect::<Vec<_>>(), counts, capacity, len),
+        Err(status) => status,
+    })
+}
+
+/// Copies the coordinates of the vertices of a faceting, one vertex after
+/// another. The dimension is the number of coordinates divided by the number
+/// of vertices.
+///
+/// # Safety
+/// The handle must be valid, `coordinates` must be valid for `capacity`
+/// writes, and `len` for a write.
+#[no_mangle]
+pub unsafe extern "C" fn miratope_faceting_vertices(
+    facetings: *const MiratopeFacetings,
+    idx: usize,
+    coordinates: *mut f64,
+    capacity: usize,
+    len: *mut usize,
+) -> MiratopeStatus {
+    guard(|| match faceting(facetings, idx) {
+        Ok(poly) => {
+            let list: Vec<_> = poly.vertices.iter().flat_map(|v| v.iter().copied()).collect();
+            copy_out(&list, coordinates, capacity, len)
+        }
+        Err(status) => status,
+    })
+}
+
+/// Copies the indices of the vertices of a facet of a faceting.
+///
+/// # Safety
+/// The handle must be valid, `vertices` must be valid for `capacity` writes,
+/// and `len` for a write.
+#[no_mangle]
+pub unsafe extern "C" fn miratope_faceting_facet_vertices(
+    facetings: *const MiratopeFacetings,
+    idx: usize,
+    facet: usize,
+    vertices: *mut usize,
+    capacity: usize,
+    len: *mut usize,
+) -> MiratopeStatus {
+    guard(|| match faceting(facetings, idx) {
+        Ok(poly) => {
+            let rank = poly.rank();
+            if rank == 0 {
+                return MiratopeStatus::OutOfRange;
+            }
+
+            match poly.abs.element_vertices(rank - 1, facet) {
+                Some(list) => copy_out(&list, vertices, capacity, len),
+                None => MiratopeStatus::OutOfRange,
+            }
+        }
+        Err(status) => status,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::ptr;
+
+    /// Sizes that overflow are rejected before any buffer is read.
+    #[test]
+    fn overflowing_sizes() {
+        let coordinates = [0.0; 9];
+        let indices = [0, 1, 2];
+        let mut out = ptr::null_mut();
+
+        let status = unsafe {
+            miratope_polyhedron_from_faces(
+                coordinates.as_ptr(),
+                usize::MAX / 3 + 2,
+                3,
+                indices.as_ptr(),
+                [3].as_ptr(),
+                1,
+                &mut out,
+            )
+        };
+        assert_eq!(status, MiratopeStatus::InvalidInput);
+
+        let status = unsafe {
+            miratope_polyhedron_from_faces(
+                coordinates.as_ptr(),
+                3,
+                3,
+                indices.as_ptr(),
+                [usize::MAX, 4].as_ptr(),
+                2,
+                &mut out,
+            )
+        };
+        assert_eq!(status, MiratopeStatus::InvalidInput);
+        assert!(out.is_null());
+    }
+}
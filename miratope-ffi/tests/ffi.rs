@@ -0,0 +1,76 @@
+//! Tests the C interface, compiling a C program against it and running it on
+//! the octahedron.
+#![cfg(unix)]
+
+use std::{env, path::PathBuf, process::Command};
+
+/// Returns a path relative to the crate directory.
+fn manifest_path(path: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(path)
+}
+
+/// Returns the directory the shared library is in. Cargo builds it along with
+/// the tests, into the same directory as the test executable.
+fn library_dir() -> PathBuf {
+    let exe = env::current_exe().unwrap();
+    let lib_dir = exe.parent().unwrap().to_path_buf();
+    let lib = lib_dir.join(format!("{}miratope_ffi{}", env::consts::DLL_PREFIX, env::consts::DLL_SUFFIX));
+    assert!(lib.exists(), "{} wasn't built", lib.display());
+
+    lib_dir
+}
+
+/// The octahedron's only faceting is itself, whether it's read from an OFF
+/// file or built from its faces.
+#[test]
+fn octahedron() {
+    let lib_dir = library_dir();
+    let exe = env::temp_dir().join(format!("miratope-ffi-octahedron-{}", std::process::id()));
+
+    let status = Command::new(env::var("CC").unwrap_or_else(|_| "cc".to_string()))
+        .arg(manifest_path("tests/ffi/octahedron.c"))
+        .arg("-I")
+        .arg(manifest_path("include"))
+        .arg("-L")
+        .arg(&lib_dir)
+        .arg("-lmiratope_ffi")
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .arg("-o")
+        .arg(&exe)
+        .status()
+        .expect("couldn't run the C compiler");
+    assert!(status.success());
+
+    let output = Command::new(&exe)
+        .arg(manifest_path("../miratope-core/tests/fixtures/oct.off"))
+        .output()
+        .expect("couldn't run the C program");
+    let _ = std::fs::remove_file(&exe);
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "facetings: 1\n\
+         element counts: 1 6 12 8 1\n\
+         coordinates: 18\n\
+         first facet: 3 vertices\n"
+    );
+}
+
+/// A polyhedron can't be built from vertices with fewer than 3 coordinates,
+/// even when there are no coordinates to read.
+#[test]
+fn too_few_dimensions() {
+    use miratope_ffi::{miratope_polyhedron_from_faces, MiratopeStatus};
+
+    let coordinates = [0.0; 6];
+    let faces = [0, 1, 2];
+    for &dim in &[0, 2] {
+        let mut out = std::ptr::null_mut();
+        let status = unsafe {
+            miratope_polyhedron_from_faces(coordinates.as_ptr(), 3, dim, faces.as_ptr(), [3].as_ptr(), 1, &mut out)
+        };
+        assert_eq!(status, MiratopeStatus::InvalidInput, "dimension {}", dim);
+        assert!(out.is_null());
+    }
+}
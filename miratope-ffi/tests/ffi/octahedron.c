@@ -0,0 +1,129 @@
+/*
+ * Facets the octahedron through the C interface, once read from an OFF file
+ * and once built from its faces, and prints what it finds. Exits with a
+ * nonzero status as soon as something goes wrong.
+ *
+ * Usage: octahedron <path to oct.off>
+ */
+
+#include <stdio.h>
+#include <stdlib.h>
+
+#include "miratope.h"
+
+/* Exits if a call didn't succeed. */
+static void check(MiratopeStatus status, MiratopeStatus expected, const char *call) {
+  if (status != expected) {
+    fprintf(stderr, "%s: %s\n", call, miratope_status_message(status));
+    exit(1);
+  }
+}
+
+/* Reads a whole file into a null-terminated string. */
+static char *read_file(const char *path) {
+  FILE *file = fopen(path, "rb");
+  if (file == NULL) {
+    fprintf(stderr, "couldn't open %s\n", path);
+    exit(1);
+  }
+
+  fseek(file, 0, SEEK_END);
+  long size = ftell(file);
+  fseek(file, 0, SEEK_SET);
+
+  char *contents = malloc(size + 1);
+  if (fread(contents, 1, size, file) != (size_t)size) {
+    fprintf(stderr, "couldn't read %s\n", path);
+    exit(1);
+  }
+  contents[size] = '\0';
+  fclose(file);
+  return contents;
+}
+
+/* Facets a polytope with the default options, and returns how many facetings
+ * were found. */
+static size_t facet(const MiratopePolytope *poly, MiratopeFacetings **facetings) {
+  MiratopeFacetingOptions options;
+  check(miratope_faceting_options_default(&options), MIRATOPE_STATUS_OK, "miratope_faceting_options_default");
+  check(miratope_facet(poly, &options, facetings), MIRATOPE_STATUS_OK, "miratope_facet");
+
+  size_t count;
+  check(miratope_facetings_count(*facetings, &count), MIRATOPE_STATUS_OK, "miratope_facetings_count");
+  return count;
+}
+
+int main(int argc, char **argv) {
+  if (argc != 2) {
+    fprintf(stderr, "usage: %s <oct.off>\n", argv[0]);
+    return 2;
+  }
+
+  /* The octahedron from its OFF file. */
+  char *off = read_file(argv[1]);
+  MiratopePolytope *from_off = NULL;
+  check(miratope_polytope_from_off(off, &from_off), MIRATOPE_STATUS_OK, "miratope_polytope_from_off");
+  free(off);
+
+  /* The octahedron from its faces. */
+  const double vertices[] = {
+    1, 0, 0, -1, 0, 0, 0, 1, 0, 0, -1, 0, 0, 0, 1, 0, 0, -1,
+  };
+  const size_t faces[] = {
+    0, 2, 4, 2, 1, 4, 1, 3, 4, 3, 0, 4, 0, 2, 5, 2, 1, 5, 1, 3, 5, 3, 0, 5,
+  };
+  const size_t face_sizes[] = {3, 3, 3, 3, 3, 3, 3, 3};
+  MiratopePolytope *from_faces = NULL;
+  check(miratope_polyhedron_from_faces(vertices, 6, 3, faces, face_sizes, 8, &from_faces),
+        MIRATOPE_STATUS_OK, "miratope_polyhedron_from_faces");
+
+  /* A face with a repeated vertex isn't valid. */
+  const size_t bad_faces[] = {0, 0, 4};
+  MiratopePolytope *bad = NULL;
+  check(miratope_polyhedron_from_faces(vertices, 6, 3, bad_faces, face_sizes, 1, &bad),
+        MIRATOPE_STATUS_INVALID_INPUT, "miratope_polyhedron_from_faces");
+
+  MiratopeFacetings *facetings = NULL;
+  MiratopeFacetings *facetings_from_faces = NULL;
+  size_t count = facet(from_off, &facetings);
+  if (count != facet(from_faces, &facetings_from_faces)) {
+    fprintf(stderr, "the two octahedra have different facetings\n");
+    return 1;
+  }
+  printf("facetings: %zu\n", count);
+
+  for (size_t i = 0; i < count; i++) {
+    size_t counts[8];
+    size_t len;
+    check(miratope_faceting_element_counts(facetings, i, counts, 8, &len), MIRATOPE_STATUS_OK,
+          "miratope_faceting_element_counts");
+    printf("element counts:");
+    for (size_t r = 0; r < len; r++) {
+      printf(" %zu", counts[r]);
+    }
+    printf("\n");
+
+    /* Asks for the length of the list first. */
+    check(miratope_faceting_vertices(facetings, i, NULL, 0, &len), MIRATOPE_STATUS_BUFFER_TOO_SMALL,
+          "miratope_faceting_vertices");
+    double *coordinates = malloc(len * sizeof(double));
+    check(miratope_faceting_vertices(facetings, i, coordinates, len, &len), MIRATOPE_STATUS_OK,
+          "miratope_faceting_vertices");
+    printf("coordinates: %zu\n", len);
+    free(coordinates);
+
+    size_t facet_vertices[16];
+    check(miratope_faceting_facet_vertices(facetings, i, 0, facet_vertices, 16, &len), MIRATOPE_STATUS_OK,
+          "miratope_faceting_facet_vertices");
+    printf("first facet: %zu vertices\n", len);
+  }
+
+  check(miratope_faceting_element_counts(facetings, count, NULL, 0, NULL), MIRATOPE_STATUS_OUT_OF_RANGE,
+        "miratope_faceting_element_counts");
+
+  miratope_facetings_free(facetings);
+  miratope_facetings_free(facetings_from_faces);
+  miratope_polytope_free(from_off);
+  miratope_polytope_free(from_faces);
+  return 0;
+}
@@ -7,7 +7,7 @@ use crate::{Concrete, Float, Hyperplane, Point, Vector};
 
 use bevy::prelude::*;
 use bevy_egui::{egui::{self, menu, Ui}, EguiContext};
-use miratope_core::{conc::{ConcretePolytope, faceting::GroupEnum, symmetry::Vertices}, file::FromFile, float::Float as Float2, Polytope, abs::Ranked};
+use miratope_core::{conc::{ConcretePolytope, faceting::{EdgeLengthFilter, FacetingOptions, GroupEnum}, symmetry::Vertices}, file::FromFile, float::Float as Float2, Polytope, abs::Ranked};
 
 /// The plugin in charge of everything on the top panel.
 pub struct TopPanelPlugin;
@@ -795,37 +795,43 @@ pub fn show_top_panel(
                     if let Some(p) = query.iter_mut().next() {
                         let mut vertices_thing = (Vertices(vec![]), vec![]);
                         if let GroupEnum2::FromSlot(slot) = faceting_settings.group {
-                            vertices_thing = Vertices(p.vertices.clone()).copy_by_symmetry(slot.to_poly(&mut memory, &p).unwrap().clone().get_symmetry_group().unwrap().0);
+                            vertices_thing = Vertices(p.vertices.to_vec()).copy_by_symmetry(slot.to_poly(&mut memory, &p).unwrap().clone().get_symmetry_group().unwrap().0);
                         }
                         let facetings = p.clone().faceting(
                             match faceting_settings.group {
-                                GroupEnum2::Chiral(_) => p.vertices.clone(),
+                                GroupEnum2::Chiral(_) => p.vertices.to_vec(),
                                 GroupEnum2::FromSlot(_) => vertices_thing.0.0
                             },
                             match faceting_settings.group {
                                 GroupEnum2::Chiral(chiral) => GroupEnum::Chiral(chiral),
-                                GroupEnum2::FromSlot(_) => GroupEnum::VertexMap(vertices_thing.1)
+                                GroupEnum2::FromSlot(_) => GroupEnum::VertexMap(vertices_thing.1.into())
+                            },
+                            &FacetingOptions {
+                                any_single_edge_length: faceting_settings.any_single_edge_length,
+                                edge_length: EdgeLengthFilter::new(
+                                    if faceting_settings.do_min_edge_length {Some(faceting_settings.min_edge_length)} else {None},
+                                    if faceting_settings.do_max_edge_length {Some(faceting_settings.max_edge_length)} else {None},
+                                    Vec::new(),
+                                ),
+                                min_inradius: if faceting_settings.do_min_inradius {Some(faceting_settings.min_inradius)} else {None},
+                                max_inradius: if faceting_settings.do_max_inradius {Some(faceting_settings.max_inradius)} else {None},
+                                exclude_hemis: faceting_settings.exclude_hemis,
+                                only_below_vertex: faceting_settings.only_below_vertex,
+                                noble: if faceting_settings.max_facet_types == 0 {None} else {Some(faceting_settings.max_facet_types)},
+                                max_per_hyperplane: if faceting_settings.max_per_hyperplane == 0 {None} else {Some(faceting_settings.max_per_hyperplane)},
+                                uniform: faceting_settings.uniform,
+                                include_compounds: faceting_settings.compounds,
+                                mark_fissary: faceting_settings.mark_fissary,
+                                label_facets: faceting_settings.label_facets,
+                                save: faceting_settings.save,
+                                save_facets: faceting_settings.save_facets,
+                                save_to_file: faceting_settings.save_to_file,
+                                file_path: faceting_settings.file_path.clone(),
+                                ..Default::default()
                             },
-                            faceting_settings.any_single_edge_length,
-                            if faceting_settings.do_min_edge_length {Some(faceting_settings.min_edge_length)} else {None}, 
-                            if faceting_settings.do_max_edge_length {Some(faceting_settings.max_edge_length)} else {None}, 
-                            if faceting_settings.do_min_inradius {Some(faceting_settings.min_inradius)} else {None}, 
-                            if faceting_settings.do_max_inradius {Some(faceting_settings.max_inradius)} else {None}, 
-                            faceting_settings.exclude_hemis,
-                            faceting_settings.only_below_vertex,
-                            if faceting_settings.max_facet_types == 0 {None} else {Some(faceting_settings.max_facet_types)},
-                            if faceting_settings.max_per_hyperplane == 0 {None} else {Some(faceting_settings.max_per_hyperplane)},
-                            faceting_settings.uniform,
-                            faceting_settings.compounds,
-                            faceting_settings.mark_fissary,
-                            faceting_settings.label_facets,
-                            faceting_settings.save,
-                            faceting_settings.save_facets,
-                            faceting_settings.save_to_file,
-                            faceting_settings.file_path.clone(),
                         );
                         for faceting in facetings {
-                            memory.push(faceting);
+                            memory.push((faceting.polytope, faceting.name));
                         }
                     }
                 }